@@ -55,9 +55,16 @@
 #define	EDOM		 0xf76aa1d2u	/* Math argument out of domain of func */
 #define	ERANGE		 0xf8110a2du	/* Math result not representable */
 #define ENOTSUP      0xfbacfec0u    /* operation not supported */
+#define	ETIMEDOUT	 0x5c3a96e1u	/* Connection timed out */
+#define	EDEADLK		 0x6e1c4b97u	/* Resource deadlock would occur */
 
 int __shield_errno_location(void);
 
+#if CONFIG_WITH_RUST_ERRNO
+/* task errno storage, exported by the shield crate */
+int *__errno_location(void);
+#endif
+
 /* substituing errno only when not in UT*/
 #define errno shield_errno
 
@@ -9,13 +9,14 @@
 extern "C" {
 #endif
 
+#include <shield/sys/types.h>
+
 #ifdef CONFIG_WITH_SENTRY
 #include <types.h>
 #include <uapi.h>
 
 /** NOTE: this value should be kernel delivered */
 #define _SIGNUM SIGNAL_USR2
-typedef taskh_t pid_t;
 
 enum posix_sigs {
   SIGABORT = SIGNAL_ABORT,
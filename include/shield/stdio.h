@@ -17,6 +17,11 @@ extern "C" {
 __attribute__ ((format (printf, 1, 2))) int printf(const char *fmt, ...);
 __attribute__ ((format (printf, 3, 4))) int snprintf(char *dest, size_t len, const char *fmt, ...);
 
+#if CONFIG_WITH_RUST_PRINTF
+__attribute__ ((format (printf, 1, 0))) int vprintf(const char *fmt, va_list ap);
+__attribute__ ((format (printf, 3, 0))) int vsnprintf(char *dest, size_t len, const char *fmt, va_list ap);
+#endif
+
 #if defined(__cplusplus)
 }
 #endif
@@ -6,11 +6,18 @@
 #define POSIX_STDLIB_H
 
 #include <inttypes.h>
+#include <stddef.h>
 
 #if defined(__cplusplus)
 extern "C" {
 #endif
 
+/**
+ * 31bits settable max random value, allowing the
+ * POSIX int type (32bits length at least)
+ */
+#define RAND_MAX ((1 << 30) - 1)
+
 int abs(int j);
 long labs(long j);
 long long llabs(long long j);
@@ -19,6 +26,30 @@ intmax_t imaxabs(intmax_t j);
 unsigned long strtoul(const char *__restrict __n, char **__restrict __end_PTR, int __base);
 long strtol(const char *__restrict __n, char **__restrict __end_PTR, int __base);
 
+/**
+ * @brief ISO C pseudo random generator, seeded from the kernel TRNG
+ *
+ * WARNING: this generator is *NOT* cryptographically secure, use getrandom()
+ * instead when required.
+ */
+int rand(void);
+
+int rand_r(unsigned int *seedp);
+
+void srand(unsigned int seed);
+
+/**
+ * @brief heap allocation functions, provided by the shield crate `heap`
+ * and `ffi` features, sharing the task heap with Rust code
+ */
+void *malloc(size_t size);
+
+void *calloc(size_t nmemb, size_t size);
+
+void *realloc(void *ptr, size_t size);
+
+void free(void *ptr);
+
 #if defined(__cplusplus)
 }
 #endif
@@ -28,6 +28,13 @@ int strcmp(const char *str1, const char *str2);
 
 void *memcpy(void *dest, const void *src, size_t n);
 void *memset(void *s, int c, size_t n);
+
+#if CONFIG_WITH_RUST_STRING
+/* exported by the shield crate only */
+int strncmp(const char *s1, const char *s2, size_t n);
+void *memmove(void *dest, const void *src, size_t n);
+int memcmp(const void *s1, const void *s2, size_t n);
+#endif
 #else
 /* no aliasing */
 size_t shield_strlen(const char *s);
@@ -15,6 +15,7 @@
  */
 
 #include <uapi.h>
+#include <shield/sys/types.h>
 
 /* messaging mode */
 #define MSG_NOERROR    010000 /* truncate silently message if too long */
@@ -26,11 +27,6 @@
 #define IPC_NOWAIT	04000		/* Do not wait, return with EAGAIN flag in case of error */
 #define IPC_PRIVATE 0           /* key identifier to create new msgq */
 
-/* generic IPC key_t for EwoK IPC (remote task name) */
-typedef taskh_t key_t;
-
-typedef long ssize_t;
-
 /* Here, we hold a word-aligned structure in order to avoid
  * any unaligned access to mtex fields for u32 & u64 types.
  * The difference with the POSIX type is the mtext definition,
@@ -5,8 +5,13 @@
 #ifndef SHIELD_SYS_RANDOM_H_
 #define SHIELD_SYS_RANDOM_H_
 
-#include <stdlib.h>
-#include <stdio.h>
+#include <stddef.h>
+#include <shield/sys/types.h>
+
+/* getrandom() flags */
+#define GRND_NONBLOCK 0x1u /* do not wait for the TRNG */
+#define GRND_RANDOM   0x2u /* blocking pool, the TRNG having a single one */
+#define GRND_INSECURE 0x4u /* accept non cryptographic random bytes */
 
 /**
  * @brief Linux compatible getrandom API
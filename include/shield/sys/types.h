@@ -0,0 +1,64 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+#ifndef SHIELD_SYS_TYPES_H_
+#define SHIELD_SYS_TYPES_H_
+
+/* Generated by cbindgen from the shield crate, do not edit */
+
+#include <stdint.h>
+
+/**
+ * Signed size, or negative error indicator
+ */
+typedef long ssize_t;
+
+/**
+ * File offset
+ */
+typedef long off_t;
+
+/**
+ * Process identifier, the kernel task handle
+ */
+typedef int32_t pid_t;
+
+/**
+ * IPC key, the remote task handle
+ */
+typedef uint32_t key_t;
+
+/**
+ * Time in seconds
+ *
+ * Time64 is not supported, being considered out of embedded scope.
+ */
+typedef unsigned long time_t;
+
+/**
+ * Clock identifier
+ */
+typedef int clockid_t;
+
+/**
+ * Timer identifier, the timer creation cycle
+ */
+typedef uint64_t timer_t;
+
+/**
+ * Time in microseconds
+ */
+typedef unsigned int useconds_t;
+
+/**
+ * Signed time in microseconds
+ */
+typedef long suseconds_t;
+
+/**
+ * File permission bits
+ */
+typedef unsigned int mode_t;
+
+#endif  /* SHIELD_SYS_TYPES_H_ */
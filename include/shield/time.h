@@ -10,28 +10,16 @@ extern "C" {
 #endif
 
 #include <shield/signal.h>
+#include <shield/sys/types.h>
 
-/**
- * @def time_t definition, using target unsigned long
- *
- * NOTE: Libshield do not support time64 use case, considered out of embedded scope
- */
-typedef unsigned long time_t;
-
-typedef enum clockid {
+/* clockid_t values */
+enum clockid {
     CLOCK_MONOTONIC, /* monolithic clock, lonely supported by now */
     CLOCK_REALTIME,
     CLOCK_REALTIME_ALARM,
     CLOCK_BOOTTIME,
     CLOCK_BOOTTIME_ALARM,
-} clockid_t;
-
-/*!
- * @def standard timer_t type (timer identifier) for embedded.
- * Correspond to current cycle
- */
-typedef uint64_t timer_t;
-
+};
 
 /**
  * @def POSIX compliant timespec structure definition
@@ -89,6 +77,19 @@ int timer_gettime(timer_t timerid, struct itimerspec *curr_value);
  */
 int clock_gettime(clockid_t clockid, struct timespec *tp);
 
+#if CONFIG_WITH_RUST_TIME
+/*
+ * Set the CLOCK_REALTIME clock, the only settable one (POSIX API). Until set,
+ * CLOCK_REALTIME runs from the Unix epoch at startup.
+ */
+int clock_settime(clockid_t clockid, const struct timespec *tp);
+
+/*
+ * Get the CLOCK_REALTIME clock seconds, also stored in tloc if not NULL (ISO C API)
+ */
+time_t time(time_t *tloc);
+#endif
+
 #ifdef __cplusplus
 }
 #endif
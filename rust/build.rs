@@ -0,0 +1,87 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Task linker script generation
+//!
+//! With the `linker-script` feature, the `shield.ld` linker script is
+//! generated from `lnk/shield.ld.in` in the build directory, added to the
+//! linker search path. A task links with it by passing `-Tshield.ld` to the
+//! linker, its memory layout being set by the following environment
+//! variables, typically in the `[env]` table of its `.cargo/config.toml`:
+//!
+//! | Variable              | Default      | Layout                                |
+//! |-----------------------|--------------|---------------------------------------|
+//! | `SHIELD_CODE_ORIGIN`  | `0x08000000` | task code start address               |
+//! | `SHIELD_CODE_LENGTH`  | `0x40000`    | task code region size                 |
+//! | `SHIELD_RAM_ORIGIN`   | `0x20000000` | task RAM start address                |
+//! | `SHIELD_RAM_LENGTH`   | `0x2000`     | task RAM region size                  |
+//! | `SHIELD_HEAP_SIZE`    | `0`          | heap size, between `_sheap` and `_eheap` |
+//! | `SHIELD_STACK_SIZE`   | `0`          | stack size, checked to fit in the RAM |
+//! | `SHIELD_NOINIT_SIZE`  | `0`          | minimum no-init region size (crash log, core dump) |
+//!
+//! Sizes and addresses are given in decimal or `0x` prefixed hexadecimal,
+//! with an optional `K` or `M` suffix for sizes.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Template variables: name, environment variable and default value
+const VARIABLES: [(&str, &str, u64); 7] = [
+    ("CODE_ORIGIN", "SHIELD_CODE_ORIGIN", 0x0800_0000),
+    ("CODE_LENGTH", "SHIELD_CODE_LENGTH", 0x0004_0000),
+    ("RAM_ORIGIN", "SHIELD_RAM_ORIGIN", 0x2000_0000),
+    ("RAM_LENGTH", "SHIELD_RAM_LENGTH", 0x2000),
+    ("HEAP_SIZE", "SHIELD_HEAP_SIZE", 0),
+    ("STACK_SIZE", "SHIELD_STACK_SIZE", 0),
+    ("NOINIT_SIZE", "SHIELD_NOINIT_SIZE", 0),
+];
+
+const TEMPLATE: &str = "lnk/shield.ld.in";
+
+/// Parse a size or an address, in decimal or hexadecimal, optionally
+/// suffixed with `K` or `M`.
+fn parse(value: &str) -> Option<u64> {
+    let value = value.trim().replace('_', "");
+    let (value, unit) = match value.strip_suffix(['K', 'k']) {
+        Some(value) => (value.to_string(), 1024),
+        None => match value.strip_suffix(['M', 'm']) {
+            Some(value) => (value.to_string(), 1024 * 1024),
+            None => (value, 1),
+        },
+    };
+    let value = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+        None => value.parse().ok()?,
+    };
+    value
+        .checked_mul(unit)
+        .filter(|&value| value <= u64::from(u32::MAX))
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_LINKER_SCRIPT").is_none() {
+        return;
+    }
+    println!("cargo:rerun-if-changed={TEMPLATE}");
+
+    let mut script = fs::read_to_string(TEMPLATE).expect("missing linker script template");
+    for (name, variable, default) in VARIABLES {
+        println!("cargo:rerun-if-env-changed={variable}");
+        let value = match env::var(variable) {
+            Ok(value) => parse(&value)
+                .unwrap_or_else(|| panic!("{variable}: `{value}` is not a valid size or address")),
+            Err(_) => default,
+        };
+        script = script.replace(&format!("@{name}@"), &format!("{value:#x}"));
+    }
+
+    let out = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR not set"));
+    fs::write(out.join("shield.ld"), script).expect("can't write the linker script");
+    println!("cargo:rustc-link-search={}", out.display());
+}
@@ -0,0 +1,40 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Syscall latency benchmark task
+//!
+//! The task and shared memory labels must match the task project
+//! configuration, the IPC round trip requiring an echo task.
+
+#![cfg_attr(target_os = "none", no_std)]
+#![cfg_attr(target_os = "none", no_main)]
+
+extern crate shield;
+use shield::bench::{self, Stats};
+use shield::{Error, println};
+
+const RUNS: u32 = 100;
+const ECHO_TASK: u32 = 0xbabe;
+const BENCH_SHM: u32 = 0xf00;
+
+#[cfg(target_os = "none")]
+shield::shield_main!();
+
+fn report(name: &str, stats: Result<Stats, Error>) {
+    match stats {
+        Ok(stats) => println!("{name:<16} {stats}"),
+        Err(error) => println!("{name:<16} failed: {error}"),
+    }
+}
+
+fn main() {
+    report("process handle", bench::process_handle(ECHO_TASK, RUNS));
+    report("shm handle", bench::shm_handle(BENCH_SHM, RUNS));
+    report("shm map/unmap", bench::shm_map(BENCH_SHM, RUNS));
+    report("copy_from_kernel", bench::copy_from_kernel(RUNS));
+    match shield::process::get_process_handle(ECHO_TASK) {
+        Ok(echo) => report("ipc round trip", bench::ipc_round_trip(echo, 32, RUNS)),
+        Err(error) => println!("{:<16} failed: {error}", "ipc round trip"),
+    }
+}
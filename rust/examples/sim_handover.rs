@@ -0,0 +1,65 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Shared memory handover between two tasks, run on the host simulator:
+//!
+//! ```text
+//! cargo run --features sim --example sim_handover -- setup
+//! SHIELD_SIM_TASK=0x2 cargo run --features sim --example sim_handover -- consumer &
+//! SHIELD_SIM_TASK=0x1 cargo run --features sim --example sim_handover -- producer
+//! ```
+
+use shield::Error;
+use shield::executor;
+use shield::println;
+use shield::shm::{Consumer, Producer, Shm};
+
+const PRODUCER: u32 = 0x1;
+const CONSUMER: u32 = 0x2;
+const SHM_LABEL: u32 = 0xf00;
+
+async fn producer() -> Result<(), Error> {
+    let shm = Shm::new(SHM_LABEL)?.map(0)?;
+    let mut tx = Producer::new(shm, shield::process::get_process_handle(CONSUMER)?)?;
+    for round in 0..4_u8 {
+        let frame = b"frame #0";
+        let buffer = tx.buffer();
+        buffer[..frame.len()].copy_from_slice(frame);
+        buffer[frame.len() - 1] += round;
+        tx.send(frame.len()).await?;
+        println!("sent frame {round}");
+    }
+    Ok(())
+}
+
+async fn consumer() -> Result<(), Error> {
+    let shm = Shm::new(SHM_LABEL)?.map(0)?;
+    let mut rx = Consumer::new(shm, shield::process::get_process_handle(PRODUCER)?)?;
+    loop {
+        let frame = rx.receive().await?;
+        println!(
+            "received {}",
+            core::str::from_utf8(frame.data()).unwrap_or("?")
+        );
+        frame.ack()?;
+    }
+}
+
+fn main() {
+    let result = match std::env::args().nth(1).as_deref() {
+        Some("setup") => {
+            shield::sim::create_shm(SHM_LABEL, 256).expect("can't create the shared memory");
+            Ok(())
+        }
+        Some("producer") => executor::run(producer()),
+        Some("consumer") => executor::run(consumer()),
+        _ => {
+            println!("usage: sim_handover setup|producer|consumer");
+            Ok(())
+        }
+    };
+    if let Err(error) = result {
+        println!("{error}");
+    }
+}
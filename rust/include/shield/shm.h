@@ -0,0 +1,111 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+#ifndef SHIELD_SHM_H_
+#define SHIELD_SHM_H_
+
+/* Generated by cbindgen from the shield crate, do not edit */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * Shared memory descriptor, filled by [`shield_shm_get`]
+ */
+struct shield_shm {
+  /**
+   * Kernel shared memory handle
+   */
+  uint32_t handle;
+  /**
+   * Shared memory label
+   */
+  uint32_t label;
+  /**
+   * Whether the shared memory is mapped in the task address space
+   */
+  bool mapped;
+};
+
+/**
+ * Shared memory information, filled by [`shield_shm_get_info`]
+ */
+struct shield_shm_info {
+  /**
+   * Shared memory label
+   */
+  uint32_t label;
+  /**
+   * Kernel shared memory handle
+   */
+  uint32_t handle;
+  /**
+   * Base address of the shared memory
+   */
+  size_t base;
+  /**
+   * Length of the shared memory, in bytes
+   */
+  size_t len;
+  /**
+   * Permission mask of the current task
+   */
+  uint32_t perms;
+};
+
+/**
+ * Get the shared memory `label` handle into `shm`, in the unmapped state.
+ *
+ * # Safety
+ *
+ * `shm` must be null or valid for writes.
+ */
+uint32_t shield_shm_get(uint32_t label, struct shield_shm *shm);
+
+/**
+ * Map the shared memory in the task address space.
+ *
+ * Fails with `Status::AlreadyMapped` if the shared memory is already mapped.
+ *
+ * # Safety
+ *
+ * `shm` must be null or point to a descriptor filled by [`shield_shm_get`].
+ */
+uint32_t shield_shm_map(struct shield_shm *shm);
+
+/**
+ * Unmap the shared memory from the task address space.
+ *
+ * Fails with `Status::Invalid` if the shared memory is not mapped.
+ *
+ * # Safety
+ *
+ * `shm` must be null or point to a descriptor filled by [`shield_shm_get`].
+ */
+uint32_t shield_shm_unmap(struct shield_shm *shm);
+
+/**
+ * Set the permissions `perms` of the task `to_task` on the shared memory.
+ *
+ * As for [`Shm::set_credentials`], this is only valid while the shared memory
+ * is unmapped, failing with `Status::AlreadyMapped` otherwise.
+ *
+ * # Safety
+ *
+ * `shm` must be null or point to a descriptor filled by [`shield_shm_get`].
+ */
+uint32_t shield_shm_set_creds(struct shield_shm *shm, uint32_t to_task, uint32_t perms);
+
+/**
+ * Get the shared memory information into `info`.
+ *
+ * # Safety
+ *
+ * `shm` must be null or point to a descriptor filled by [`shield_shm_get`],
+ * `info` must be null or valid for writes.
+ */
+uint32_t shield_shm_get_info(struct shield_shm *shm, struct shield_shm_info *info);
+
+#endif  /* SHIELD_SHM_H_ */
@@ -13,12 +13,16 @@
 //!  - `main` (w/o mangling) is called by `_start` after shield initialization. This
 //!    symbol must be able to resolve crate::main, i.e. main that is defined in task
 //!    `main.rs` file.
+//!
+//! It also provides the `bind!` macro, emitting the typed resource labels of a
+//! task from its bindings file.
 
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse, parse_macro_input};
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{parse, parse_macro_input, Ident, LitStr};
 
 /// Generate shield entrypoint function
 ///
@@ -56,3 +60,163 @@ pub fn shield_main(tokens: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+/// Bindings file sections: section name, `shield::bind` type and maximum
+/// label value
+const SECTIONS: [(&str, &str, u64); 4] = [
+    ("devices", "Device", u8::MAX as u64),
+    ("shms", "SharedMemory", u32::MAX as u64),
+    ("dma_streams", "Stream", u32::MAX as u64),
+    ("peers", "Peer", u32::MAX as u64),
+];
+
+/// Label bound in a bindings file
+struct Binding {
+    section: usize,
+    name: Ident,
+    value: u64,
+}
+
+/// Parse an integer label, in decimal or `0x` prefixed hexadecimal.
+fn parse_label(value: &str) -> Option<u64> {
+    let value = value.replace('_', "");
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Parse a bindings file: `[section]` headers followed by `NAME = label`
+/// lines, `#` starting comments.
+fn parse_bindings(content: &str) -> Result<Vec<Binding>, String> {
+    let mut bindings: Vec<Binding> = Vec::new();
+    let mut section = None;
+    for (index, line) in content.lines().enumerate() {
+        let at = |message: String| format!("line {}: {message}", index + 1);
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            let name = name.trim();
+            section = Some(
+                SECTIONS
+                    .iter()
+                    .position(|&(section, _, _)| section == name)
+                    .ok_or_else(|| at(format!("unknown section `{name}`")))?,
+            );
+            continue;
+        }
+        let section = section.ok_or_else(|| at("label outside of a section".into()))?;
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| at("expected `NAME = label`".into()))?;
+        let (name, value) = (name.trim(), value.trim());
+        let name = syn::parse_str::<Ident>(name)
+            .map_err(|_| at(format!("`{name}` is not a valid identifier")))?;
+        let (_, _, max) = SECTIONS[section];
+        let value = parse_label(value)
+            .filter(|&value| value <= max)
+            .ok_or_else(|| at(format!("`{value}` is not a valid label")))?;
+        if bindings
+            .iter()
+            .any(|binding| binding.section == section && binding.name == name)
+        {
+            return Err(at(format!("`{name}` is bound twice")));
+        }
+        bindings.push(Binding {
+            section,
+            name,
+            value,
+        });
+    }
+    Ok(bindings)
+}
+
+/// Generate typed resource labels from a bindings file
+///
+/// # Usage
+///
+/// Procedural macro reading the bindings file at the given path, relative to
+/// the task crate root, and emitting a module per section present in the
+/// file: `devices`, `shms`, `dma_streams` and `peers`, holding a
+/// `shield::bind` typed constant per label.
+///
+/// > **NOTE**: The bindings file is hand-written, with the labels assigned by
+/// > the project configuration. It is not generated from it, as the project
+/// > configuration is handled by the integration build system.
+///
+/// Unknown sections, malformed lines, out of range labels and duplicated
+/// names are reported as compile errors.
+///
+/// # Example
+///
+/// ```toml
+/// [devices]
+/// UART0 = 0x01
+///
+/// [peers]
+/// CRYPTO_TASK = 0xbabe
+/// ```
+///
+/// ```ignore
+/// shield::bind!("bindings.toml");
+///
+/// let crypto = peers::CRYPTO_TASK.handle()?;
+/// ```
+#[proc_macro]
+pub fn bind(tokens: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(tokens as LitStr);
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into());
+    let full_path = std::path::Path::new(&root).join(path.value());
+    let error = |message: String| {
+        syn::Error::new(path.span(), message)
+            .to_compile_error()
+            .into()
+    };
+    let content = match std::fs::read_to_string(&full_path) {
+        Ok(content) => content,
+        Err(err) => return error(format!("can't read {}: {err}", full_path.display())),
+    };
+    let bindings = match parse_bindings(&content) {
+        Ok(bindings) => bindings,
+        Err(message) => return error(format!("{}: {message}", full_path.display())),
+    };
+
+    let modules = SECTIONS
+        .iter()
+        .enumerate()
+        .filter(|&(section, _)| bindings.iter().any(|binding| binding.section == section))
+        .map(|(section, &(module, ty, _))| {
+            let module = Ident::new(module, Span::call_site());
+            let ty = format_ident!("{ty}");
+            let constants = bindings
+                .iter()
+                .filter(|binding| binding.section == section)
+                .map(|Binding { name, value, .. }| {
+                    let value = proc_macro2::Literal::u64_unsuffixed(*value);
+                    quote! {
+                        pub const #name: ::shield::bind::#ty = ::shield::bind::#ty::new(#value);
+                    }
+                });
+            quote! {
+                #[allow(dead_code)]
+                pub mod #module {
+                    #(#constants)*
+                }
+            }
+        });
+    let full_path = full_path.display().to_string();
+    quote! {
+        // rebuilt on bindings file changes
+        const _: &[u8] = include_bytes!(#full_path);
+        #(#modules)*
+    }
+    .into()
+}
@@ -0,0 +1,278 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Attestation reports
+//!
+//! A [`Report`] accumulates the task measurements (Shield version, image
+//! digest, configuration...) into a SHA-256 hash chain, each measurement
+//! extending the chain as `chain = SHA-256(chain || kind || digest)`, like a
+//! TPM PCR. [`Report::collect`] starts a report with what the kernel and the
+//! runtime provide, the task label and the Shield version.
+//!
+//! [`Report::encode`] produces the report blob, bound to the verifier
+//! `nonce`, which a verifier task forwards off-device. The blob is signed
+//! with a [`ReportSigner`] when the platform holds an attestation key, and is
+//! only hash-chained otherwise. Its layout, little-endian, is:
+//!
+//! | Offset | Length | Field |
+//! |-|-|-|
+//! | 0 | 4 | magic, `ATST` |
+//! | 4 | 1 | format version, 1 |
+//! | 5 | 1 | measurement count, `n` |
+//! | 6 | 2 | signature length |
+//! | 8 | 4 | task label |
+//! | 12 | 32 | nonce |
+//! | 44 | 36 `n` | measurements, kind (4 bytes) and digest |
+//! | 44 + 36 `n` | 32 | chain |
+//! | 76 + 36 `n` | | signature of the SHA-256 digest of the previous fields |
+//!
+//! [`Report::decode`] parses a blob back, checking its chain.
+
+use uapi::systypes::{Status, TaskLabel};
+
+use crate::error::{Error, Subsystem};
+use crate::sha256::{DIGEST_LEN, Sha256};
+
+/// Maximum number of measurements of a [`Report`]
+pub const MAX_MEASUREMENTS: usize = 16;
+
+/// Largest blob length, signature excluded
+pub const MAX_REPORT_LEN: usize = HEADER_LEN + MAX_MEASUREMENTS * ENTRY_LEN + DIGEST_LEN;
+
+const MAGIC: u32 = 0x5453_5441;
+const FORMAT_VERSION: u8 = 1;
+const HEADER_LEN: usize = 12 + DIGEST_LEN;
+const ENTRY_LEN: usize = 4 + DIGEST_LEN;
+
+/// Measurement kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Shield crate version
+    ShieldVersion,
+    /// Running image digest
+    Image,
+    /// Task configuration
+    Config,
+    /// Application defined
+    Custom(u16),
+}
+
+impl Kind {
+    const fn code(self) -> u32 {
+        match self {
+            Self::ShieldVersion => 1,
+            Self::Image => 2,
+            Self::Config => 3,
+            Self::Custom(kind) => 0x1_0000 | kind as u32,
+        }
+    }
+
+    fn from_code(code: u32) -> Option<Self> {
+        match code {
+            1 => Some(Self::ShieldVersion),
+            2 => Some(Self::Image),
+            3 => Some(Self::Config),
+            0x1_0000..=0x1_ffff => Some(Self::Custom(code as u16)),
+            _ => None,
+        }
+    }
+}
+
+/// Report signature
+pub trait ReportSigner {
+    /// Signature length, in bytes
+    fn signature_len(&self) -> usize;
+
+    /// Sign the report `digest` into `signature`, of
+    /// [`ReportSigner::signature_len`] bytes.
+    ///
+    /// # Errors
+    /// Returns the signing key errors.
+    fn sign(&self, digest: &[u8; DIGEST_LEN], signature: &mut [u8]) -> Result<(), Error>;
+}
+
+fn error(status: Status) -> Error {
+    Error::new(Subsystem::Attest, status)
+}
+
+/// Measurements hash chain of a task, see the [module](self) documentation
+#[derive(Clone)]
+pub struct Report {
+    label: TaskLabel,
+    entries: [(Kind, [u8; DIGEST_LEN]); MAX_MEASUREMENTS],
+    count: usize,
+    chain: [u8; DIGEST_LEN],
+}
+
+impl Report {
+    /// Start the empty report of the task `label`.
+    pub const fn new(label: TaskLabel) -> Self {
+        Self {
+            label,
+            entries: [(Kind::Custom(0), [0; DIGEST_LEN]); MAX_MEASUREMENTS],
+            count: 0,
+            chain: [0; DIGEST_LEN],
+        }
+    }
+
+    /// Start the report of the current task, measuring the Shield version.
+    ///
+    /// # Errors
+    /// Returns an error if the current task is not registered (see
+    /// [`crate::process::register_current`]).
+    #[cfg(not(feature = "host-std"))]
+    pub fn collect() -> Result<Self, Error> {
+        let mut report = Self::new(crate::process::current_label()?);
+        report.measure(Kind::ShieldVersion, env!("CARGO_PKG_VERSION").as_bytes())?;
+        Ok(report)
+    }
+
+    /// Return the task label.
+    pub fn label(&self) -> TaskLabel {
+        self.label
+    }
+
+    /// Return the current chain value.
+    pub fn chain(&self) -> [u8; DIGEST_LEN] {
+        self.chain
+    }
+
+    /// Iterate over the measurements, in order.
+    pub fn measurements(&self) -> impl Iterator<Item = &(Kind, [u8; DIGEST_LEN])> {
+        self.entries[..self.count].iter()
+    }
+
+    /// Measure `data`, extending the chain with its digest.
+    ///
+    /// # Errors
+    /// Returns `Status::Busy` if the report holds [`MAX_MEASUREMENTS`]
+    /// measurements already.
+    pub fn measure(&mut self, kind: Kind, data: &[u8]) -> Result<(), Error> {
+        self.extend(kind, &Sha256::digest(data))
+    }
+
+    /// Extend the chain with the precomputed `digest`.
+    ///
+    /// # Errors
+    /// Returns `Status::Busy` if the report holds [`MAX_MEASUREMENTS`]
+    /// measurements already.
+    pub fn extend(&mut self, kind: Kind, digest: &[u8; DIGEST_LEN]) -> Result<(), Error> {
+        if self.count == MAX_MEASUREMENTS {
+            return Err(error(Status::Busy));
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(&self.chain);
+        hasher.update(&kind.code().to_le_bytes());
+        hasher.update(digest);
+        self.chain = hasher.finalize();
+        self.entries[self.count] = (kind, *digest);
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Return the blob length, signed with a `signature_len` bytes
+    /// signature.
+    pub fn encoded_len(&self, signature_len: usize) -> usize {
+        HEADER_LEN + self.count * ENTRY_LEN + DIGEST_LEN + signature_len
+    }
+
+    /// Encode the report, bound to `nonce`, into `out`, signed with `signer`
+    /// if any, returning the blob length.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if `out` is too small or the signature
+    /// longer than 64 KiB, or propagates the signer errors.
+    pub fn encode(
+        &self,
+        nonce: &[u8; DIGEST_LEN],
+        signer: Option<&dyn ReportSigner>,
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        let signature_len = signer.map_or(0, ReportSigner::signature_len);
+        let len = self.encoded_len(signature_len);
+        let Ok(encoded_signature_len) = u16::try_from(signature_len) else {
+            return Err(error(Status::Invalid));
+        };
+        if out.len() < len {
+            return Err(error(Status::Invalid));
+        }
+
+        out[..4].copy_from_slice(&MAGIC.to_le_bytes());
+        out[4] = FORMAT_VERSION;
+        out[5] = self.count as u8;
+        out[6..8].copy_from_slice(&encoded_signature_len.to_le_bytes());
+        out[8..12].copy_from_slice(&self.label.to_le_bytes());
+        out[12..HEADER_LEN].copy_from_slice(nonce);
+        let mut offset = HEADER_LEN;
+        for (kind, digest) in self.measurements() {
+            out[offset..offset + 4].copy_from_slice(&kind.code().to_le_bytes());
+            out[offset + 4..offset + ENTRY_LEN].copy_from_slice(digest);
+            offset += ENTRY_LEN;
+        }
+        out[offset..offset + DIGEST_LEN].copy_from_slice(&self.chain);
+        offset += DIGEST_LEN;
+
+        if let Some(signer) = signer {
+            let digest = Sha256::digest(&out[..offset]);
+            signer.sign(&digest, &mut out[offset..len])?;
+        }
+        Ok(len)
+    }
+
+    /// Parse the report `blob`, checking its chain.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if the blob is malformed or its chain
+    /// doesn't match its measurements.
+    pub fn decode(blob: &[u8]) -> Result<Decoded<'_>, Error> {
+        let invalid = error(Status::Invalid);
+        if blob.len() < HEADER_LEN
+            || blob[..4] != MAGIC.to_le_bytes()
+            || blob[4] != FORMAT_VERSION
+            || usize::from(blob[5]) > MAX_MEASUREMENTS
+        {
+            return Err(invalid);
+        }
+        let count = usize::from(blob[5]);
+        let signature_len = usize::from(u16::from_le_bytes([blob[6], blob[7]]));
+        let body_len = HEADER_LEN + count * ENTRY_LEN + DIGEST_LEN;
+        if blob.len() != body_len + signature_len {
+            return Err(invalid);
+        }
+
+        let label = u32::from_le_bytes([blob[8], blob[9], blob[10], blob[11]]);
+        let mut report = Self::new(label);
+        for entry in blob[HEADER_LEN..body_len - DIGEST_LEN].chunks_exact(ENTRY_LEN) {
+            let code = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]);
+            let kind = Kind::from_code(code).ok_or(invalid)?;
+            let mut digest = [0; DIGEST_LEN];
+            digest.copy_from_slice(&entry[4..]);
+            report.extend(kind, &digest)?;
+        }
+        if blob[body_len - DIGEST_LEN..body_len] != report.chain {
+            return Err(invalid);
+        }
+
+        let mut nonce = [0; DIGEST_LEN];
+        nonce.copy_from_slice(&blob[12..HEADER_LEN]);
+        Ok(Decoded {
+            report,
+            nonce,
+            signed_digest: Sha256::digest(&blob[..body_len]),
+            signature: &blob[body_len..],
+        })
+    }
+}
+
+/// Parsed report blob, see [`Report::decode`]
+pub struct Decoded<'a> {
+    /// Report, with its chain checked
+    pub report: Report,
+    /// Verifier nonce
+    pub nonce: [u8; DIGEST_LEN],
+    /// Digest covered by the signature
+    pub signed_digest: [u8; DIGEST_LEN],
+    /// Signature, empty for unsigned reports
+    pub signature: &'a [u8],
+}
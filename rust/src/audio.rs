@@ -0,0 +1,297 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! I2S audio streaming
+//!
+//! [`AudioStream`] runs a circular DMA stream between the I2S controller and
+//! a shared memory split in two halves: while the DMA fills (capture) or
+//! drains (playback) one half, the task processes the other one.
+//! [`AudioStream::next`] waits for the half and full transfer events, and
+//! returns the [`Half`] the task owns until the following event.
+//!
+//! ```ignore
+//! let mut mic = AudioStream::new(Spi2::mapped()?, config, Shm::new(MIC_SHM)?.map(0)?, DmaStream::new(MIC_STREAM)?)?;
+//! mic.start()?;
+//! loop {
+//!     let half = mic.next().await?;
+//!     for sample in config.format.decode(mic.half(half)) {
+//!         process(to_f32(sample));
+//!     }
+//! }
+//! ```
+//!
+//! The I2S controller is SoC specific: the board support code gives access
+//! to it through the [`I2sRegisters`] trait. The stream, statically
+//! configured in the device tree, must be circular over the whole shared
+//! memory, with the half transfer interrupt enabled.
+//!
+//! Samples are exchanged as little-endian words of their [`SampleFormat`],
+//! [`SampleFormat::decode`] and [`SampleFormat::encode`] converting them from
+//! and to `i32` samples scaled to the full `i32` range, and [`to_f32`] and
+//! [`from_f32`] from and to normalized floating-point samples.
+
+use uapi::systypes::dma::GpdmaChanInt;
+use uapi::systypes::{EventType, Status};
+
+use crate::dma::DmaStream;
+use crate::error::{Error, Subsystem};
+use crate::executor;
+use crate::shm::{Mapped, Shm};
+use crate::units::Hertz;
+
+/// Sample word format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleFormat {
+    /// 16-bit samples in 16-bit words
+    S16,
+    /// 24-bit samples, right aligned in 32-bit words
+    S24,
+    /// 32-bit samples
+    S32,
+}
+
+impl SampleFormat {
+    /// Return the length of a sample word, in bytes.
+    pub const fn word_len(self) -> usize {
+        match self {
+            Self::S16 => 2,
+            Self::S24 | Self::S32 => 4,
+        }
+    }
+
+    /// Decode the sample words of `bytes` into `i32` samples, scaled to the
+    /// whole `i32` range, a trailing partial word being ignored.
+    pub fn decode(self, bytes: &[u8]) -> impl Iterator<Item = i32> + '_ {
+        bytes
+            .chunks_exact(self.word_len())
+            .map(move |word| match self {
+                Self::S16 => i32::from(i16::from_le_bytes([word[0], word[1]])) << 16,
+                Self::S24 => i32::from_le_bytes([0, word[0], word[1], word[2]]),
+                Self::S32 => i32::from_le_bytes([word[0], word[1], word[2], word[3]]),
+            })
+    }
+
+    /// Encode `samples`, scaled to the whole `i32` range, into the sample
+    /// words of `bytes`, returning the number of samples written.
+    pub fn encode(self, samples: impl IntoIterator<Item = i32>, bytes: &mut [u8]) -> usize {
+        let mut count = 0;
+        for (word, sample) in bytes.chunks_exact_mut(self.word_len()).zip(samples) {
+            match self {
+                Self::S16 => word.copy_from_slice(&((sample >> 16) as i16).to_le_bytes()),
+                Self::S24 => word.copy_from_slice(&(sample >> 8).to_le_bytes()),
+                Self::S32 => word.copy_from_slice(&sample.to_le_bytes()),
+            }
+            count += 1;
+        }
+        count
+    }
+}
+
+/// Convert a full range `i32` sample to a sample in `[-1, 1)`.
+pub fn to_f32(sample: i32) -> f32 {
+    sample as f32 / 2_147_483_648.0
+}
+
+/// Convert a sample in `[-1, 1]` to a full range `i32` sample, saturating.
+pub fn from_f32(sample: f32) -> i32 {
+    // float to integer casts saturate
+    (sample * 2_147_483_648.0) as i32
+}
+
+/// Audio stream configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// Sample rate
+    pub sample_rate: Hertz,
+    /// Sample word format
+    pub format: SampleFormat,
+    /// Number of interleaved channels
+    pub channels: u8,
+}
+
+/// Transfer direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// From the I2S controller to the shared memory
+    Capture,
+    /// From the shared memory to the I2S controller
+    Playback,
+}
+
+/// Half of the shared memory buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Half {
+    /// First half, from the start of the shared memory
+    First,
+    /// Second half
+    Second,
+}
+
+/// Access to the registers of a mapped I2S controller
+pub trait I2sRegisters {
+    /// Configure the controller clocks and frame format, the controller
+    /// being stopped.
+    fn configure(&mut self, config: &Config, direction: Direction);
+
+    /// Enable the controller and its DMA requests.
+    fn start(&mut self);
+
+    /// Disable the controller and its DMA requests.
+    fn stop(&mut self);
+}
+
+/// I2S audio stream, see the [module](self) documentation
+pub struct AudioStream<R> {
+    regs: R,
+    config: Config,
+    direction: Direction,
+    buffer: Shm<Mapped>,
+    base: usize,
+    len: usize,
+    stream: DmaStream,
+    running: bool,
+}
+
+impl<R: I2sRegisters> AudioStream<R> {
+    /// Create the stream, its direction given by `stream` whose source or
+    /// destination is `buffer`.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if `config` has no channel, if
+    /// `stream` is not circular over the whole `buffer` with the half
+    /// transfer interrupt, if a half of `buffer` doesn't hold whole frames,
+    /// or if `buffer` is not writable for playback, or propagates kernel
+    /// errors if information retrieval fails.
+    pub fn new(
+        regs: R,
+        config: Config,
+        mut buffer: Shm<Mapped>,
+        stream: DmaStream,
+    ) -> Result<Self, Error> {
+        let invalid = Error::new(Subsystem::Audio, Status::Invalid);
+        let base = buffer.base_address()?;
+        let len = buffer.length()?;
+        let info = stream.info()?;
+        let direction = if info.dest == base && info.circular_dest {
+            Direction::Capture
+        } else if info.source == base && info.circular_source && buffer.is_writable() {
+            Direction::Playback
+        } else {
+            return Err(invalid);
+        };
+        let frame_len = config.format.word_len() * usize::from(config.channels);
+        if frame_len == 0
+            || info.transfer_len != len
+            || len % (2 * frame_len) != 0
+            || info.interrupts & GpdmaChanInt::HalfTransfer as u8 == 0
+        {
+            return Err(invalid);
+        }
+        Ok(Self {
+            regs,
+            config,
+            direction,
+            buffer,
+            base,
+            len,
+            stream,
+            running: false,
+        })
+    }
+
+    /// Stop the stream, then release the controller registers, the shared
+    /// memory and the DMA stream.
+    pub fn release(mut self) -> (R, Shm<Mapped>, DmaStream) {
+        let _ = self.stop();
+        (self.regs, self.buffer, self.stream)
+    }
+
+    /// Return the stream configuration.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Return the stream direction.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Configure the controller, then start the DMA stream and the
+    /// controller.
+    ///
+    /// For playback, both halves should be filled first.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the DMA stream can't be started.
+    pub fn start(&mut self) -> Result<(), Error> {
+        self.regs.stop();
+        self.regs.configure(&self.config, self.direction);
+        self.stream.start()?;
+        self.regs.start();
+        self.running = true;
+        Ok(())
+    }
+
+    /// Stop the controller, then suspend the DMA stream.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the DMA stream can't be suspended.
+    pub fn stop(&mut self) -> Result<(), Error> {
+        self.regs.stop();
+        if !core::mem::replace(&mut self.running, false) {
+            return Ok(());
+        }
+        self.stream.suspend()
+    }
+
+    /// Wait for the DMA to complete a half, returning it: its captured
+    /// samples can be read, or its played samples replaced, until the next
+    /// call.
+    ///
+    /// # Errors
+    /// Returns a `Status::Critical` error if the DMA reports a transfer
+    /// error, the stream being stopped.
+    pub async fn next(&mut self) -> Result<Half, Error> {
+        let event = executor::wait_event_from(EventType::Dma, self.stream.handle()).await;
+        // stream handle, followed by the channel interrupt flags
+        let flags = event.data().get(4).copied().unwrap_or_default();
+        if flags & GpdmaChanInt::DmaError as u8 != 0 {
+            let _ = self.stop();
+            return Err(
+                Error::new(Subsystem::Audio, Status::Critical).with_handle(self.stream.handle())
+            );
+        }
+        Ok(if flags & GpdmaChanInt::TransferComplete as u8 != 0 {
+            Half::Second
+        } else {
+            Half::First
+        })
+    }
+
+    /// Return the samples of `half`.
+    pub fn half(&self, half: Half) -> &[u8] {
+        // SAFETY: the half is in the shared memory, not written by the DMA
+        // until the next event for capture, and never for playback
+        unsafe { core::slice::from_raw_parts(self.half_address(half), self.len / 2) }
+    }
+
+    /// Return the samples of `half`, to be played.
+    ///
+    /// # Panics
+    /// Panics if the stream captures samples.
+    pub fn half_mut(&mut self, half: Half) -> &mut [u8] {
+        assert_eq!(self.direction, Direction::Playback);
+        // SAFETY: the half is in the shared memory, only read by the DMA
+        // until the next event
+        unsafe { core::slice::from_raw_parts_mut(self.half_address(half), self.len / 2) }
+    }
+
+    fn half_address(&self, half: Half) -> *mut u8 {
+        let offset = match half {
+            Half::First => 0,
+            Half::Second => self.len / 2,
+        };
+        core::ptr::with_exposed_provenance_mut(self.base + offset)
+    }
+}
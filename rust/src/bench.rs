@@ -0,0 +1,207 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Syscall latency benchmarking
+//!
+//! [`measure`] runs an operation a given number of times, timing each run with
+//! the kernel cycle counter, and reports the [`Stats`] of the runs. The cost of
+//! reading the cycle counter is measured beforehand and subtracted from each
+//! run.
+//!
+//! Benchmarks of the Shield wrapped syscalls are provided, so that regressions
+//! in the wrapper layer can be measured on target:
+//!
+//! ```ignore
+//! let stats = bench::shm_map(FRAME_SHM, 100)?;
+//! println!("map + unmap: {stats}");
+//! ```
+//!
+//! Reading the cycle counter requires the high precision chronometer
+//! capability.
+
+use core::fmt;
+use uapi::systypes::{EventType, Precision, Status, TaskHandle, TaskLabel};
+
+use crate::error::{Error, Subsystem};
+use crate::shm::{Shm, Unmapped};
+use crate::{process, time};
+
+/// Number of runs used to measure the cycle counter read cost
+const CALIBRATION_RUNS: u32 = 8;
+
+/// `wait_for_event()` timeout value for an infinite wait
+const WFE_WAIT_FOREVER: i32 = 0;
+
+/// Statistics of the runs of a benchmarked operation, in cycles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    samples: u32,
+    min: u64,
+    max: u64,
+    total: u64,
+}
+
+impl Stats {
+    const fn new() -> Self {
+        Self {
+            samples: 0,
+            min: u64::MAX,
+            max: 0,
+            total: 0,
+        }
+    }
+
+    fn add(&mut self, cycles: u64) {
+        self.samples += 1;
+        self.min = self.min.min(cycles);
+        self.max = self.max.max(cycles);
+        self.total = self.total.saturating_add(cycles);
+    }
+
+    /// Return the number of runs.
+    #[must_use]
+    pub const fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// Return the fastest run cost, or 0 without run.
+    #[must_use]
+    pub const fn min(&self) -> u64 {
+        if self.samples == 0 { 0 } else { self.min }
+    }
+
+    /// Return the slowest run cost.
+    #[must_use]
+    pub const fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Return the mean run cost, or 0 without run.
+    #[must_use]
+    pub const fn mean(&self) -> u64 {
+        if self.samples == 0 {
+            0
+        } else {
+            self.total / self.samples as u64
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} runs, min {} / mean {} / max {} cycles",
+            self.samples,
+            self.min(),
+            self.mean(),
+            self.max
+        )
+    }
+}
+
+fn cycles() -> Result<u64, Error> {
+    time::uptime(Precision::Cycle)
+}
+
+/// Measure the cost of reading the cycle counter.
+fn overhead() -> Result<u64, Error> {
+    let mut overhead = u64::MAX;
+    for _ in 0..CALIBRATION_RUNS {
+        let start = cycles()?;
+        let end = cycles()?;
+        overhead = overhead.min(end.saturating_sub(start));
+    }
+    Ok(overhead)
+}
+
+/// Run `op` `runs` times, measuring the cost of each run.
+///
+/// # Errors
+/// Propagates the cycle counter errors, and the errors of `op`, which stop
+/// the benchmark.
+pub fn measure<T>(runs: u32, mut op: impl FnMut() -> Result<T, Error>) -> Result<Stats, Error> {
+    let overhead = overhead()?;
+    let mut stats = Stats::new();
+    for _ in 0..runs {
+        let start = cycles()?;
+        op()?;
+        let end = cycles()?;
+        stats.add(end.saturating_sub(start).saturating_sub(overhead));
+    }
+    Ok(stats)
+}
+
+/// Benchmark the retrieval of the handle of the task `label`.
+///
+/// # Errors
+/// Propagates the benchmarked operation errors.
+pub fn process_handle(label: TaskLabel, runs: u32) -> Result<Stats, Error> {
+    measure(runs, || process::get_process_handle(label))
+}
+
+/// Benchmark the retrieval of the handle of the shared memory `label`.
+///
+/// # Errors
+/// Propagates the benchmarked operation errors.
+pub fn shm_handle(label: u32, runs: u32) -> Result<Stats, Error> {
+    measure(runs, || Shm::<Unmapped>::fetch_handle(label))
+}
+
+/// Benchmark the mapping, then unmapping, of the shared memory `label`.
+///
+/// # Errors
+/// Propagates the benchmarked operations errors.
+pub fn shm_map(label: u32, runs: u32) -> Result<Stats, Error> {
+    let mut shm = Some(Shm::new(label)?);
+    measure(runs, || {
+        let unmapped = shm
+            .take()
+            .ok_or(Error::new(Subsystem::Shm, Status::Invalid))?;
+        shm = Some(unmapped.map(0)?.unmap()?);
+        Ok(())
+    })
+}
+
+/// Benchmark a `u64` copy from the exchange area.
+///
+/// # Errors
+/// Propagates the benchmarked operation errors.
+pub fn copy_from_kernel(runs: u32) -> Result<Stats, Error> {
+    measure(runs, || {
+        let mut value = 0_u64;
+        match crate::sys::copy_from_kernel(&mut value) {
+            Ok(Status::Ok) => Ok(value),
+            Ok(status) | Err(status) => Err(Error::new(Subsystem::Process, status)),
+        }
+    })
+}
+
+/// Benchmark an IPC round trip of `len` bytes with the task `peer`, which
+/// must send back an IPC for each IPC received.
+///
+/// # Errors
+/// Returns a `Status::Invalid` error if `len` exceeds the exchange area size,
+/// or propagates the benchmarked operations errors.
+pub fn ipc_round_trip(peer: TaskHandle, len: u8, runs: u32) -> Result<Stats, Error> {
+    let error = |status| Error::new(Subsystem::Ipc, status).with_handle(peer);
+    let message = [0xa5_u8; uapi::length()];
+    let message = message
+        .get(..usize::from(len))
+        .ok_or(error(Status::Invalid))?;
+    measure(runs, || {
+        match crate::sys::copy_to_kernel(&message) {
+            Ok(Status::Ok) => {}
+            Ok(status) | Err(status) => return Err(error(status)),
+        }
+        match crate::sys::syscall::send_ipc(peer, len) {
+            Status::Ok => {}
+            status => return Err(error(status)),
+        }
+        match crate::sys::syscall::wait_for_event(EventType::Ipc.into(), WFE_WAIT_FOREVER) {
+            Status::Ok => Ok(()),
+            status => Err(error(status)),
+        }
+    })
+}
@@ -0,0 +1,145 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Typed resource labels
+//!
+//! The kernel identifies the devices, shared memories, DMA streams and peer
+//! tasks of a task by the integer labels of the project configuration. The
+//! [`bind!`](crate::bind!) macro reads these labels from a bindings file of
+//! the task crate and emits them as typed constants, in the `devices`,
+//! `shms`, `dma_streams` and `peers` modules:
+//!
+//! ```toml
+//! # bindings.toml
+//! [devices]
+//! UART0 = 0x01
+//!
+//! [shms]
+//! FRAME_BUFFER = 0xf00
+//!
+//! [peers]
+//! CRYPTO_TASK = 0xbabe
+//! ```
+//!
+//! ```ignore
+//! shield::bind!("bindings.toml");
+//!
+//! let frame = shms::FRAME_BUFFER.shm()?.map(0)?;
+//! let crypto = peers::CRYPTO_TASK.handle()?;
+//! ```
+//!
+//! A label of a given kind can't be passed where another is expected, and a
+//! missing or duplicated label fails the build instead of the task.
+//!
+//! The bindings file is written along with the task, with the labels the
+//! project configuration assigns to its resources. No generator extracting
+//! them from the project configuration is provided here: that configuration
+//! belongs to the integration build system, which knows the labels of every
+//! task. Keeping both in line is left to the task author.
+
+use uapi::systypes::{ShmLabel, StreamLabel, TaskHandle, TaskLabel};
+
+use crate::error::Error;
+
+/// Device label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Device(u8);
+
+impl Device {
+    /// Create a device label from its configuration value.
+    pub const fn new(label: u8) -> Self {
+        Self(label)
+    }
+
+    /// Return the configuration value of the label.
+    pub const fn label(self) -> u8 {
+        self.0
+    }
+
+    /// Retrieve the device, unmapped.
+    ///
+    /// # Errors
+    /// See [`crate::device::Device::new`].
+    #[cfg_attr(feature = "audit-syscalls", track_caller)]
+    pub fn device(self) -> Result<crate::device::Device<crate::device::Unmapped>, Error> {
+        crate::device::Device::new(self.0)
+    }
+}
+
+/// Shared memory label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SharedMemory(ShmLabel);
+
+impl SharedMemory {
+    /// Create a shared memory label from its configuration value.
+    pub const fn new(label: ShmLabel) -> Self {
+        Self(label)
+    }
+
+    /// Return the configuration value of the label.
+    pub const fn label(self) -> ShmLabel {
+        self.0
+    }
+
+    /// Retrieve the shared memory, unmapped.
+    ///
+    /// # Errors
+    /// See [`crate::shm::Shm::new`].
+    #[cfg(feature = "shm")]
+    #[cfg_attr(feature = "audit-syscalls", track_caller)]
+    pub fn shm(self) -> Result<crate::shm::Shm<crate::shm::Unmapped>, Error> {
+        crate::shm::Shm::new(self.0)
+    }
+}
+
+/// DMA stream label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Stream(StreamLabel);
+
+impl Stream {
+    /// Create a DMA stream label from its configuration value.
+    pub const fn new(label: StreamLabel) -> Self {
+        Self(label)
+    }
+
+    /// Return the configuration value of the label.
+    pub const fn label(self) -> StreamLabel {
+        self.0
+    }
+
+    /// Retrieve the stream and assign it to its hardware channel.
+    ///
+    /// # Errors
+    /// See [`crate::dma::DmaStream::new`].
+    #[cfg(feature = "dma")]
+    #[cfg_attr(feature = "audit-syscalls", track_caller)]
+    pub fn stream(self) -> Result<crate::dma::DmaStream, Error> {
+        crate::dma::DmaStream::new(self.0)
+    }
+}
+
+/// Peer task label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Peer(TaskLabel);
+
+impl Peer {
+    /// Create a peer task label from its configuration value.
+    pub const fn new(label: TaskLabel) -> Self {
+        Self(label)
+    }
+
+    /// Return the configuration value of the label.
+    pub const fn label(self) -> TaskLabel {
+        self.0
+    }
+
+    /// Retrieve the peer task handle.
+    ///
+    /// # Errors
+    /// See [`crate::process::get_process_handle`].
+    #[cfg_attr(feature = "audit-syscalls", track_caller)]
+    pub fn handle(self) -> Result<TaskHandle, Error> {
+        crate::process::get_process_handle(self.0)
+    }
+}
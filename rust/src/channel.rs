@@ -0,0 +1,241 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Bounded channels
+//!
+//! [`Bounded`] is a fixed-capacity FIFO shared by the activities of a task:
+//! drivers, event handlers and the event loop. [`Bounded::split`] returns its
+//! [`Receiver`], unique, and a [`Sender`], which may be cloned for several
+//! producers. Both sides have non-blocking operations, usable from an IRQ
+//! event callback, and async ones, waiting for room or for an item.
+//!
+//! A Sentry task being single-threaded, its IRQ events being handled in its
+//! own flow, the channel needs no atomic operation, and may be placed in a
+//! `static` item. It is not meant for inter-task exchanges
+//! through a shared memory.
+//!
+//! ```ignore
+//! static FRAMES: Bounded<Frame, 8> = Bounded::new();
+//!
+//! // IRQ event handler
+//! if FRAMES.try_send(frame).is_err() { overruns += 1; }
+//!
+//! // event loop
+//! let frame = FRAMES.recv().await;
+//! ```
+
+use core::cell::{Cell, UnsafeCell};
+use core::future::poll_fn;
+use core::mem::MaybeUninit;
+use core::task::{Poll, Waker};
+
+/// Fixed-capacity FIFO of `N` items, see the [module](self) documentation
+pub struct Bounded<T, const N: usize> {
+    slots: [UnsafeCell<MaybeUninit<T>>; N],
+    /// Index of the oldest item
+    head: Cell<usize>,
+    len: Cell<usize>,
+    /// Receiver waiting for an item
+    rx_waker: Cell<Option<Waker>>,
+    /// Sender waiting for room
+    tx_waker: Cell<Option<Waker>>,
+}
+
+// SAFETY: a Sentry task is single-threaded, the channel is never accessed
+// concurrently
+unsafe impl<T: Send, const N: usize> Sync for Bounded<T, N> {}
+
+impl<T, const N: usize> Default for Bounded<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Store `waker` in `slot`, waking the waker it replaces, if different, so
+/// that no waiter is lost.
+fn register(slot: &Cell<Option<Waker>>, waker: &Waker) {
+    match slot.take() {
+        Some(stored) if stored.will_wake(waker) => slot.set(Some(stored)),
+        previous => {
+            slot.set(Some(waker.clone()));
+            if let Some(previous) = previous {
+                previous.wake();
+            }
+        }
+    }
+}
+
+fn wake(slot: &Cell<Option<Waker>>) {
+    if let Some(waker) = slot.take() {
+        waker.wake();
+    }
+}
+
+impl<T, const N: usize> Bounded<T, N> {
+    /// Create an empty channel.
+    pub const fn new() -> Self {
+        Self {
+            slots: [const { UnsafeCell::new(MaybeUninit::uninit()) }; N],
+            head: Cell::new(0),
+            len: Cell::new(0),
+            rx_waker: Cell::new(None),
+            tx_waker: Cell::new(None),
+        }
+    }
+
+    /// Return the channel capacity.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Return the number of queued items.
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// Check whether the channel is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check whether the channel is full.
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    /// Split the channel into its sender and its receiver.
+    pub fn split(&mut self) -> (Sender<'_, T, N>, Receiver<'_, T, N>) {
+        let channel: &Self = self;
+        (Sender { channel }, Receiver { channel })
+    }
+
+    /// Queue `item`, or give it back if the channel is full.
+    ///
+    /// # Errors
+    /// Returns `item` back if the channel is full.
+    pub fn try_send(&self, item: T) -> Result<(), T> {
+        let len = self.len.get();
+        if len == N {
+            return Err(item);
+        }
+        let index = (self.head.get() + len) % N;
+        // SAFETY: the slot is free, and no reference to it exists
+        unsafe { (*self.slots[index].get()).write(item) };
+        self.len.set(len + 1);
+        wake(&self.rx_waker);
+        Ok(())
+    }
+
+    /// Dequeue the oldest item, if any.
+    pub fn try_recv(&self) -> Option<T> {
+        let len = self.len.get();
+        if len == 0 {
+            return None;
+        }
+        let head = self.head.get();
+        // SAFETY: the slot holds an item, moved out as the slot is freed
+        let item = unsafe { (*self.slots[head].get()).assume_init_read() };
+        self.head.set((head + 1) % N);
+        self.len.set(len - 1);
+        wake(&self.tx_waker);
+        Some(item)
+    }
+
+    /// Queue `item`, waiting for room if the channel is full.
+    pub async fn send(&self, item: T) {
+        let mut item = Some(item);
+        poll_fn(|cx| {
+            let Some(pending) = item.take() else {
+                return Poll::Ready(());
+            };
+            match self.try_send(pending) {
+                Ok(()) => Poll::Ready(()),
+                Err(pending) => {
+                    item = Some(pending);
+                    register(&self.tx_waker, cx.waker());
+                    Poll::Pending
+                }
+            }
+        })
+        .await;
+    }
+
+    /// Dequeue the oldest item, waiting for one if the channel is empty.
+    pub async fn recv(&self) -> T {
+        poll_fn(|cx| match self.try_recv() {
+            Some(item) => Poll::Ready(item),
+            None => {
+                register(&self.rx_waker, cx.waker());
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+impl<T, const N: usize> Drop for Bounded<T, N> {
+    fn drop(&mut self) {
+        while self.try_recv().is_some() {}
+    }
+}
+
+/// Producer side of a [`Bounded`] channel, cloned for several producers
+pub struct Sender<'a, T, const N: usize> {
+    channel: &'a Bounded<T, N>,
+}
+
+impl<T, const N: usize> Clone for Sender<'_, T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const N: usize> Copy for Sender<'_, T, N> {}
+
+impl<T, const N: usize> Sender<'_, T, N> {
+    /// See [`Bounded::try_send`].
+    ///
+    /// # Errors
+    /// Returns `item` back if the channel is full.
+    pub fn try_send(&self, item: T) -> Result<(), T> {
+        self.channel.try_send(item)
+    }
+
+    /// See [`Bounded::send`].
+    pub async fn send(&self, item: T) {
+        self.channel.send(item).await;
+    }
+
+    /// Check whether the channel is full.
+    pub fn is_full(&self) -> bool {
+        self.channel.is_full()
+    }
+}
+
+/// Consumer side of a [`Bounded`] channel
+pub struct Receiver<'a, T, const N: usize> {
+    channel: &'a Bounded<T, N>,
+}
+
+impl<T, const N: usize> Receiver<'_, T, N> {
+    /// See [`Bounded::try_recv`].
+    pub fn try_recv(&mut self) -> Option<T> {
+        self.channel.try_recv()
+    }
+
+    /// See [`Bounded::recv`].
+    pub async fn recv(&mut self) -> T {
+        self.channel.recv().await
+    }
+
+    /// Return the number of queued items.
+    pub fn len(&self) -> usize {
+        self.channel.len()
+    }
+
+    /// Check whether the channel is empty.
+    pub fn is_empty(&self) -> bool {
+        self.channel.is_empty()
+    }
+}
@@ -0,0 +1,321 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! CRC checksums
+//!
+//! A streaming [`Crc`] computes the CRC-8, CRC-16 and CRC-32 variants of the
+//! frame formats, storage layouts and images handled by the tasks, given by
+//! their [`Algorithm`] parameters. The usual ones are provided as constants,
+//! named after the CRC catalogue.
+//!
+//! ```ignore
+//! let mut crc = Crc::new(CRC_32_ISO_HDLC);
+//! crc.update(header);
+//! crc.update(payload);
+//! let checksum = crc.finalize();
+//!
+//! assert_eq!(Crc::checksum(CRC_16_IBM_3740, b"123456789"), 0x29b1);
+//! ```
+//!
+//! The software implementation is bitwise, trading speed for size. On SoCs
+//! with a programmable CRC unit, [`HardwareCrc`] offloads the computation
+//! to the mapped unit, with the same results.
+
+/// CRC algorithm parameters
+///
+/// The polynomial and the initial value are given in the non-reflected
+/// form, as in the CRC catalogue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Algorithm<W> {
+    /// Generator polynomial, without its leading term
+    pub poly: W,
+    /// Initial register value
+    pub init: W,
+    /// Whether the input bytes and the result are bit reflected
+    pub reflected: bool,
+    /// Value XORed with the final register
+    pub xorout: W,
+}
+
+/// CRC-8/SMBUS, also known as CRC-8
+pub const CRC_8_SMBUS: Algorithm<u8> = Algorithm {
+    poly: 0x07,
+    init: 0,
+    reflected: false,
+    xorout: 0,
+};
+
+/// CRC-8/MAXIM-DOW, used by 1-Wire devices
+pub const CRC_8_MAXIM_DOW: Algorithm<u8> = Algorithm {
+    poly: 0x31,
+    init: 0,
+    reflected: true,
+    xorout: 0,
+};
+
+/// CRC-16/IBM-3740, also known as CRC-16/CCITT-FALSE
+pub const CRC_16_IBM_3740: Algorithm<u16> = Algorithm {
+    poly: 0x1021,
+    init: 0xffff,
+    reflected: false,
+    xorout: 0,
+};
+
+/// CRC-16/XMODEM
+pub const CRC_16_XMODEM: Algorithm<u16> = Algorithm {
+    poly: 0x1021,
+    init: 0,
+    reflected: false,
+    xorout: 0,
+};
+
+/// CRC-16/KERMIT, the CRC of HDLC based links
+pub const CRC_16_KERMIT: Algorithm<u16> = Algorithm {
+    poly: 0x1021,
+    init: 0,
+    reflected: true,
+    xorout: 0,
+};
+
+/// CRC-16/MODBUS
+pub const CRC_16_MODBUS: Algorithm<u16> = Algorithm {
+    poly: 0x8005,
+    init: 0xffff,
+    reflected: true,
+    xorout: 0,
+};
+
+/// CRC-32/ISO-HDLC, the CRC-32 of Ethernet, zlib and PNG
+pub const CRC_32_ISO_HDLC: Algorithm<u32> = Algorithm {
+    poly: 0x04c1_1db7,
+    init: 0xffff_ffff,
+    reflected: true,
+    xorout: 0xffff_ffff,
+};
+
+/// CRC-32/ISCSI, also known as CRC-32C (Castagnoli)
+pub const CRC_32_ISCSI: Algorithm<u32> = Algorithm {
+    poly: 0x1edc_6f41,
+    init: 0xffff_ffff,
+    reflected: true,
+    xorout: 0xffff_ffff,
+};
+
+/// CRC-32/MPEG-2, the default configuration of STM32 CRC units
+pub const CRC_32_MPEG_2: Algorithm<u32> = Algorithm {
+    poly: 0x04c1_1db7,
+    init: 0xffff_ffff,
+    reflected: false,
+    xorout: 0,
+};
+
+/// CRC register type, implemented for `u8`, `u16` and `u32`
+pub trait Width: Copy + Eq + core::fmt::Debug {
+    /// CRC width, in bits
+    const BITS: u32;
+
+    /// Return the value with its bits reversed.
+    fn reflect(self) -> Self;
+
+    /// Return the register `crc` updated with `byte`, the polynomial being
+    /// reflected for reflected algorithms.
+    fn update(crc: Self, poly: Self, reflected: bool, byte: u8) -> Self;
+
+    /// Return the value, zero extended.
+    fn to_u32(self) -> u32;
+
+    /// Return the low bits of `value`.
+    fn from_u32(value: u32) -> Self;
+}
+
+macro_rules! width {
+    ($($width:ty),+) => {
+        $(
+            impl Width for $width {
+                const BITS: u32 = <$width>::BITS;
+
+                fn reflect(self) -> Self {
+                    self.reverse_bits()
+                }
+
+                fn update(mut crc: Self, poly: Self, reflected: bool, byte: u8) -> Self {
+                    if reflected {
+                        crc ^= Self::from(byte);
+                        for _ in 0..8 {
+                            crc = (crc >> 1) ^ (poly & (crc & 1).wrapping_neg());
+                        }
+                    } else {
+                        crc ^= Self::from(byte) << (Self::BITS - 8);
+                        for _ in 0..8 {
+                            crc = (crc << 1) ^ (poly & (crc >> (Self::BITS - 1)).wrapping_neg());
+                        }
+                    }
+                    crc
+                }
+
+                fn to_u32(self) -> u32 {
+                    u32::from(self)
+                }
+
+                fn from_u32(value: u32) -> Self {
+                    value as Self
+                }
+            }
+        )+
+    };
+}
+
+width!(u8, u16, u32);
+
+/// Streaming CRC computation
+#[derive(Debug, Clone)]
+pub struct Crc<W> {
+    algorithm: Algorithm<W>,
+    /// Polynomial in the register bit order
+    poly: W,
+    /// Register, reflected for reflected algorithms
+    crc: W,
+}
+
+impl<W: Width> Crc<W> {
+    /// Start a CRC computation with `algorithm`.
+    pub fn new(algorithm: Algorithm<W>) -> Self {
+        let (poly, crc) = match algorithm.reflected {
+            true => (algorithm.poly.reflect(), algorithm.init.reflect()),
+            false => (algorithm.poly, algorithm.init),
+        };
+        Self {
+            algorithm,
+            poly,
+            crc,
+        }
+    }
+
+    /// Return the CRC of `data` with `algorithm`.
+    pub fn checksum(algorithm: Algorithm<W>, data: &[u8]) -> W {
+        let mut crc = Self::new(algorithm);
+        crc.update(data);
+        crc.finalize()
+    }
+
+    /// Return the algorithm of the computation.
+    pub fn algorithm(&self) -> Algorithm<W> {
+        self.algorithm
+    }
+
+    /// Feed `data` to the computation.
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.crc = W::update(self.crc, self.poly, self.algorithm.reflected, byte);
+        }
+    }
+
+    /// Return the CRC of the data fed so far, the computation going on.
+    pub fn value(&self) -> W {
+        W::from_u32(self.crc.to_u32() ^ self.algorithm.xorout.to_u32())
+    }
+
+    /// Return the CRC of the data fed.
+    pub fn finalize(self) -> W {
+        self.value()
+    }
+}
+
+#[cfg(not(feature = "host-std"))]
+pub use hardware::HardwareCrc;
+
+#[cfg(not(feature = "host-std"))]
+mod hardware {
+    use core::marker::PhantomData;
+
+    use super::{Algorithm, Width};
+    use crate::device::{Device, Mapped};
+
+    // register offsets, in words
+
+    /// Data register
+    const DR: usize = 0;
+    /// Control register
+    const CR: usize = 2;
+    /// Initial value register
+    const INIT: usize = 4;
+    /// Polynomial register
+    const POL: usize = 5;
+
+    const CR_RESET: u32 = 1 << 0;
+    const CR_POLYSIZE_SHIFT: u32 = 3;
+    /// Input bit reversal, by byte
+    const CR_REV_IN_BYTE: u32 = 1 << 5;
+    const CR_REV_OUT: u32 = 1 << 7;
+
+    /// CRC computation offloaded to a programmable STM32 CRC unit
+    ///
+    /// The unit is borrowed by a single computation at a time, bytes being
+    /// fed to its data register. STM32F1, F2 and F4 units only compute
+    /// [`super::CRC_32_MPEG_2`] on words, and are not supported.
+    pub struct HardwareCrc<'a, W> {
+        registers: *mut u32,
+        xorout: W,
+        _device: PhantomData<&'a mut Device<Mapped>>,
+    }
+
+    impl<'a, W: Width> HardwareCrc<'a, W> {
+        /// Start a CRC computation with `algorithm` on the unit `device`,
+        /// whose registers are at `registers`.
+        ///
+        /// # Safety
+        /// `registers` must be the address of the registers of the device,
+        /// a programmable STM32 CRC unit.
+        pub unsafe fn new(
+            _device: &'a mut Device<Mapped>,
+            registers: *mut (),
+            algorithm: Algorithm<W>,
+        ) -> Self {
+            let registers = registers.cast::<u32>();
+            let polysize = match W::BITS {
+                8 => 0b10,
+                16 => 0b01,
+                _ => 0b00,
+            };
+            let reverse = match algorithm.reflected {
+                true => CR_REV_IN_BYTE | CR_REV_OUT,
+                false => 0,
+            };
+            // SAFETY: the registers of the unit are mapped while borrowed
+            unsafe {
+                registers.add(INIT).write_volatile(algorithm.init.to_u32());
+                registers.add(POL).write_volatile(algorithm.poly.to_u32());
+                registers
+                    .add(CR)
+                    .write_volatile((polysize << CR_POLYSIZE_SHIFT) | reverse | CR_RESET);
+            }
+            Self {
+                registers,
+                xorout: algorithm.xorout,
+                _device: PhantomData,
+            }
+        }
+
+        /// Feed `data` to the computation.
+        pub fn update(&mut self, data: &[u8]) {
+            let dr = self.registers.wrapping_add(DR).cast::<u8>();
+            for &byte in data {
+                // SAFETY: byte accesses to the data register feed 8 bits
+                unsafe { dr.write_volatile(byte) };
+            }
+        }
+
+        /// Return the CRC of the data fed so far, the computation going on.
+        pub fn value(&self) -> W {
+            // SAFETY: the registers of the unit are mapped while borrowed
+            let crc = unsafe { self.registers.add(DR).read_volatile() };
+            W::from_u32(crc ^ self.xorout.to_u32())
+        }
+
+        /// Return the CRC of the data fed.
+        pub fn finalize(self) -> W {
+            self.value()
+        }
+    }
+}
@@ -0,0 +1,576 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Bounded collections
+//!
+//! [`FixedVec`], [`FixedString`] and [`FixedMap`] hold at most `N` items
+//! inline, with no allocation. Adding an item never panics: the `try_*`
+//! operations hand the rejected item back, or fail with a `Status::Busy`
+//! error, once the collection is full.
+//!
+//! ```ignore
+//! let mut peers = FixedMap::<TaskLabel, TaskHandle, 4>::new();
+//! if peers.try_insert(label, handle).is_err() {
+//!     return Err(Error::new(Subsystem::Ipc, Status::Busy));
+//! }
+//! ```
+//!
+//! With the `heap` feature, [`SpillVec`] starts inline, as a [`FixedVec`],
+//! and moves to the task heap once full.
+
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+
+fn full() -> Error {
+    Error::new(Subsystem::Collections, Status::Busy)
+}
+
+/// Vector of at most `N` items, see the [module](self) documentation
+pub struct FixedVec<T, const N: usize> {
+    items: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> FixedVec<T, N> {
+    /// Create an empty vector.
+    pub const fn new() -> Self {
+        Self {
+            items: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    /// Return the maximum number of items.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Return the number of items.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return whether the vector is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return whether the vector is full.
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Append `value`, handing it back if the vector is full.
+    ///
+    /// # Errors
+    /// Returns `value` if the vector is full.
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        match self.items.get_mut(self.len) {
+            Some(slot) => {
+                slot.write(value);
+                self.len += 1;
+                Ok(())
+            }
+            None => Err(value),
+        }
+    }
+
+    /// Remove the last item.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: the item was initialized, and is now out of the vector
+        Some(unsafe { self.items[self.len].assume_init_read() })
+    }
+
+    /// Insert `value` at `index`, shifting the following items.
+    ///
+    /// # Errors
+    /// Returns `value` if the vector is full or `index` is past its length.
+    pub fn try_insert(&mut self, index: usize, value: T) -> Result<(), T> {
+        if index > self.len || self.is_full() {
+            return Err(value);
+        }
+        let base = self.items.as_mut_ptr().cast::<T>();
+        // SAFETY: the items from `index` are moved one slot up, within the
+        // capacity as the vector is not full
+        unsafe {
+            ptr::copy(base.add(index), base.add(index + 1), self.len - index);
+            base.add(index).write(value);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Remove and return the item at `index`, shifting the following items.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+        let base = self.items.as_mut_ptr().cast::<T>();
+        // SAFETY: the item is initialized, and the following ones are moved
+        // one slot down over it
+        let value = unsafe {
+            let value = base.add(index).read();
+            ptr::copy(base.add(index + 1), base.add(index), self.len - index - 1);
+            value
+        };
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Remove and return the item at `index`, replacing it with the last
+    /// one.
+    pub fn swap_remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+        let last = self.len - 1;
+        self.as_mut_slice().swap(index, last);
+        self.pop()
+    }
+
+    /// Keep the first `len` items, dropping the others.
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            drop(self.pop());
+        }
+    }
+
+    /// Remove all the items.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Keep the items for which `keep` returns `true`, in order.
+    pub fn retain(&mut self, mut keep: impl FnMut(&T) -> bool) {
+        let mut index = 0;
+        while index < self.len {
+            if keep(&self[index]) {
+                index += 1;
+            } else {
+                drop(self.remove(index));
+            }
+        }
+    }
+
+    /// Return the items.
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the first `len` items are initialized
+        unsafe { core::slice::from_raw_parts(self.items.as_ptr().cast(), self.len) }
+    }
+
+    /// Return the items, mutably.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: the first `len` items are initialized
+        unsafe { core::slice::from_raw_parts_mut(self.items.as_mut_ptr().cast(), self.len) }
+    }
+}
+
+impl<T: Clone, const N: usize> FixedVec<T, N> {
+    /// Append clones of all the `values`, or none of them.
+    ///
+    /// # Errors
+    /// Returns a `Status::Busy` error if they don't all fit.
+    pub fn try_extend_from_slice(&mut self, values: &[T]) -> Result<(), Error> {
+        if values.len() > N - self.len {
+            return Err(full());
+        }
+        for value in values {
+            let _ = self.try_push(value.clone());
+        }
+        Ok(())
+    }
+}
+
+impl<T, const N: usize> Drop for FixedVec<T, N> {
+    fn drop(&mut self) {
+        // SAFETY: the items are initialized, and dropped once
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) };
+    }
+}
+
+impl<T, const N: usize> Default for FixedVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for FixedVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut clone = Self::new();
+        for value in self.iter() {
+            let _ = clone.try_push(value.clone());
+        }
+        clone
+    }
+}
+
+impl<T, const N: usize> Deref for FixedVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> DerefMut for FixedVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for FixedVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for FixedVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for FixedVec<T, N> {}
+
+impl<T: Clone, const N: usize> TryFrom<&[T]> for FixedVec<T, N> {
+    type Error = Error;
+
+    fn try_from(values: &[T]) -> Result<Self, Error> {
+        let mut vec = Self::new();
+        vec.try_extend_from_slice(values)?;
+        Ok(vec)
+    }
+}
+
+/// UTF-8 string of at most `N` bytes, see the [module](self) documentation
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct FixedString<const N: usize> {
+    bytes: FixedVec<u8, N>,
+}
+
+impl<const N: usize> FixedString<N> {
+    /// Create an empty string.
+    pub const fn new() -> Self {
+        Self {
+            bytes: FixedVec::new(),
+        }
+    }
+
+    /// Return the maximum length, in bytes.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Return the length, in bytes.
+    pub const fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Return whether the string is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Return the string.
+    pub fn as_str(&self) -> &str {
+        // SAFETY: only whole UTF-8 strings are appended, and truncation
+        // happens on character boundaries
+        unsafe { core::str::from_utf8_unchecked(self.bytes.as_slice()) }
+    }
+
+    /// Append the character `c`, handing it back if it doesn't fit.
+    ///
+    /// # Errors
+    /// Returns `c` if the string is full.
+    pub fn try_push(&mut self, c: char) -> Result<(), char> {
+        self.try_push_str(c.encode_utf8(&mut [0; 4])).map_err(|_| c)
+    }
+
+    /// Append the whole string `s`.
+    ///
+    /// # Errors
+    /// Returns a `Status::Busy` error, the string being left unchanged, if
+    /// `s` doesn't fit.
+    pub fn try_push_str(&mut self, s: &str) -> Result<(), Error> {
+        self.bytes.try_extend_from_slice(s.as_bytes())
+    }
+
+    /// Remove the last character.
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self.as_str().chars().next_back()?;
+        self.bytes.truncate(self.len() - c.len_utf8());
+        Some(c)
+    }
+
+    /// Keep the first `len` bytes, rounded down to a character boundary.
+    pub fn truncate(&mut self, len: usize) {
+        let mut len = len.min(self.len());
+        while !self.as_str().is_char_boundary(len) {
+            len -= 1;
+        }
+        self.bytes.truncate(len);
+    }
+
+    /// Empty the string.
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+}
+
+impl<const N: usize> Deref for FixedString<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> TryFrom<&str> for FixedString<N> {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Error> {
+        let mut string = Self::new();
+        string.try_push_str(s)?;
+        Ok(string)
+    }
+}
+
+/// Formatting fails once the string is full, the fitting fragments being
+/// kept.
+impl<const N: usize> fmt::Write for FixedString<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+impl<const N: usize> fmt::Display for FixedString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// Map of at most `N` entries, see the [module](self) documentation
+///
+/// Entries are kept in insertion order, and looked up linearly: the map is
+/// meant for the handful of entries of a task configuration.
+#[derive(Clone, PartialEq, Eq)]
+pub struct FixedMap<K, V, const N: usize> {
+    entries: FixedVec<(K, V), N>,
+}
+
+impl<K: Eq, V, const N: usize> FixedMap<K, V, N> {
+    /// Create an empty map.
+    pub const fn new() -> Self {
+        Self {
+            entries: FixedVec::new(),
+        }
+    }
+
+    /// Return the maximum number of entries.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Return the number of entries.
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return whether the map is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn position(&self, key: &K) -> Option<usize> {
+        self.entries.iter().position(|(k, _)| k == key)
+    }
+
+    /// Insert `value` for `key`, returning the value it replaces.
+    ///
+    /// # Errors
+    /// Returns the entry if `key` is not in the map and the map is full.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        match self.position(&key) {
+            Some(index) => Ok(Some(core::mem::replace(&mut self.entries[index].1, value))),
+            None => self.entries.try_push((key, value)).map(|()| None),
+        }
+    }
+
+    /// Return the value of `key`.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.position(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Return the value of `key`, mutably.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.position(key).map(|index| &mut self.entries[index].1)
+    }
+
+    /// Return whether `key` is in the map.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.position(key).is_some()
+    }
+
+    /// Remove `key` from the map, returning its value.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let index = self.position(key)?;
+        self.entries.remove(index).map(|(_, value)| value)
+    }
+
+    /// Remove all the entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Return an iterator over the entries, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+
+    /// Return an iterator over the keys, in insertion order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    /// Return an iterator over the values, in insertion order.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+}
+
+impl<K: Eq, V, const N: usize> Default for FixedMap<K, V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, const N: usize> fmt::Debug for FixedMap<K, V, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.entries.iter().map(|(key, value)| (key, value)))
+            .finish()
+    }
+}
+
+/// Vector holding up to `N` items inline, then moving to the task heap, see
+/// the [module](self) documentation
+#[cfg(feature = "heap")]
+pub enum SpillVec<T, const N: usize> {
+    /// Items held inline
+    Inline(FixedVec<T, N>),
+    /// Items moved to the heap
+    Heap(alloc::vec::Vec<T>),
+}
+
+#[cfg(feature = "heap")]
+impl<T, const N: usize> SpillVec<T, N> {
+    /// Create an empty vector, holding its items inline.
+    pub const fn new() -> Self {
+        Self::Inline(FixedVec::new())
+    }
+
+    /// Return whether the items have been moved to the heap.
+    pub fn spilled(&self) -> bool {
+        matches!(self, Self::Heap(_))
+    }
+
+    /// Append `value`, moving the items to the heap if they don't fit inline
+    /// anymore.
+    ///
+    /// # Errors
+    /// Returns `value` if the heap is exhausted.
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        let value = match self {
+            Self::Inline(items) => match items.try_push(value) {
+                Ok(()) => return Ok(()),
+                Err(value) => value,
+            },
+            Self::Heap(items) => {
+                if items.try_reserve(1).is_err() {
+                    return Err(value);
+                }
+                items.push(value);
+                return Ok(());
+            }
+        };
+        let mut heap = alloc::vec::Vec::new();
+        if heap.try_reserve(N + 1).is_err() {
+            return Err(value);
+        }
+        if let Self::Inline(items) = self {
+            heap.extend(core::iter::from_fn(|| items.pop()));
+            heap.reverse();
+        }
+        heap.push(value);
+        *self = Self::Heap(heap);
+        Ok(())
+    }
+
+    /// Remove the last item.
+    pub fn pop(&mut self) -> Option<T> {
+        match self {
+            Self::Inline(items) => items.pop(),
+            Self::Heap(items) => items.pop(),
+        }
+    }
+
+    /// Remove all the items, keeping the heap storage if any.
+    pub fn clear(&mut self) {
+        match self {
+            Self::Inline(items) => items.clear(),
+            Self::Heap(items) => items.clear(),
+        }
+    }
+}
+
+#[cfg(feature = "heap")]
+impl<T, const N: usize> Default for SpillVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "heap")]
+impl<T, const N: usize> Deref for SpillVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self {
+            Self::Inline(items) => items,
+            Self::Heap(items) => items,
+        }
+    }
+}
+
+#[cfg(feature = "heap")]
+impl<T, const N: usize> DerefMut for SpillVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        match self {
+            Self::Inline(items) => items,
+            Self::Heap(items) => items,
+        }
+    }
+}
+
+#[cfg(feature = "heap")]
+impl<T: fmt::Debug, const N: usize> fmt::Debug for SpillVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
@@ -0,0 +1,414 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Core dumps
+//!
+//! Along with each [`crate::crashlog`] report the task writes, with
+//! [`crashlog::record`](crate::crashlog::record) or
+//! [`crashlog::abort`](crate::crashlog::abort), a core dump is written in a
+//! no-init RAM region (see the `.noinit` section of the Shield linker
+//! script): the capture registers, the memory layout declared with
+//! [`set_layout`], and the top of the stack, from the stack pointer to the
+//! end of its region. As the crash report, it is copied with [`last`]
+//! once the task is restarted, or read by a supervisor from a shared memory
+//! bound with [`bind_shm`], then forwarded as is ([`CoreDump::as_bytes`]).
+//!
+//! No dump is written on panics or faults, as no crash report is (see the
+//! note of [`crate::crashlog`]).
+//!
+//! Without a declared layout, no stack is dumped, the stack bounds being
+//! unknown.
+//!
+//! ```ignore
+//! static LAYOUT: &[Region] = &[
+//!     Region { name: "stack", start: 0x2000_0000, len: 0x1000 },
+//!     Region { name: "data", start: 0x2000_1000, len: 0x800 },
+//! ];
+//! coredump::set_layout(LAYOUT);
+//! ```
+//!
+//! The dump is made of little-endian integers:
+//!
+//! | Offset | Content |
+//! |--------|---------|
+//! | 0      | magic (`u32`, `CDMP`) |
+//! | 4      | layout version (`u32`, [`VERSION`]) |
+//! | 8      | dump length (`u32`) |
+//! | 12     | FNV-1a checksum of the following bytes (`u32`) |
+//! | 16     | crash kind (`u32`, [`CrashKind`]) and task label (`u32`) |
+//! | 24     | uptime (`u64`, microseconds) |
+//! | 32     | `pc`, `lr` and `sp` at capture (`u64` each) |
+//! | 56     | number of regions and stack length (`u32` each) |
+//! | 64     | regions, name (8 bytes, zero-padded), start and length (`u64` each) |
+//! | ..     | stack content, from `sp` |
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use uapi::systypes::Status;
+
+use crate::crashlog::CrashKind;
+use crate::error::{Error, Subsystem};
+use crate::shm::{Mapped, Shm};
+
+/// Layout version
+pub const VERSION: u32 = 1;
+
+/// No-init region length, in bytes, bounding the dumped stack
+pub const COREDUMP_LEN: usize = 1024;
+
+/// Maximum number of declared regions
+pub const MAX_REGIONS: usize = 8;
+
+/// Maximum region name length, in bytes
+pub const MAX_REGION_NAME: usize = 8;
+
+/// Valid core dump marker ("CDMP")
+const MAGIC: u32 = 0x4344_4d50;
+
+const HEADER_LEN: usize = 64;
+const REGION_LEN: usize = MAX_REGION_NAME + 16;
+
+/// Task memory region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    /// Region name, truncated to [`MAX_REGION_NAME`] bytes
+    pub name: &'static str,
+    /// Start address
+    pub start: usize,
+    /// Length, in bytes
+    pub len: usize,
+}
+
+/// Registers at capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Registers {
+    /// Program counter
+    pub pc: u64,
+    /// Link register
+    pub lr: u64,
+    /// Stack pointer
+    pub sp: u64,
+}
+
+/// Dumped region, as decoded from a core dump
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DumpedRegion<'a> {
+    name: &'a [u8],
+    /// Start address
+    pub start: u64,
+    /// Length, in bytes
+    pub len: u64,
+}
+
+impl DumpedRegion<'_> {
+    /// Return the region name.
+    pub fn name(&self) -> &str {
+        let len = self
+            .name
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(self.name.len());
+        core::str::from_utf8(&self.name[..len]).unwrap_or_default()
+    }
+}
+
+/// Core dump, see the [module](self) documentation
+#[derive(Clone, Copy)]
+pub struct CoreDump<'a> {
+    bytes: &'a [u8],
+}
+
+fn word(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+fn dword(bytes: &[u8], offset: usize) -> u64 {
+    u64::from(word(bytes, offset)) | (u64::from(word(bytes, offset + 4)) << 32)
+}
+
+/// FNV-1a digest, as the crash report checksum
+fn checksum(bytes: impl Iterator<Item = u8>) -> u32 {
+    bytes.fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+impl<'a> CoreDump<'a> {
+    /// Decode a core dump, typically forwarded by the crashed task.
+    ///
+    /// Returns `None` if `bytes` does not start with a valid core dump.
+    pub fn decode(bytes: &'a [u8]) -> Option<Self> {
+        if bytes.len() < HEADER_LEN || word(bytes, 0) != MAGIC || word(bytes, 4) != VERSION {
+            return None;
+        }
+        let len = word(bytes, 8) as usize;
+        let regions = word(bytes, 56) as usize;
+        let stack = word(bytes, 60) as usize;
+        if regions > MAX_REGIONS
+            || len > bytes.len()
+            || len != HEADER_LEN + regions * REGION_LEN + stack
+        {
+            return None;
+        }
+        let bytes = &bytes[..len];
+        (checksum(bytes[16..].iter().copied()) == word(bytes, 12)).then_some(Self { bytes })
+    }
+
+    /// Read the core dump written by a peer task in a shared memory bound
+    /// with [`bind_shm`].
+    ///
+    /// Returns `Ok(None)` if the shared memory does not hold a valid dump.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if information retrieval fails.
+    pub fn read_from(shm: &'a mut Shm<Mapped>) -> Result<Option<Self>, Error> {
+        let len = shm.length()?;
+        let base = core::ptr::with_exposed_provenance::<u8>(shm.base_address()?);
+        // SAFETY: the shared memory is mapped for as long as it is borrowed,
+        // the crashed peer not writing it anymore
+        let bytes = unsafe { core::slice::from_raw_parts(base, len) };
+        Ok(Self::decode(bytes))
+    }
+
+    /// Return the raw dump, typically to forward it to another task.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Return the origin of the crash.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the dump holds an unknown kind.
+    pub fn kind(&self) -> Result<CrashKind, Error> {
+        CrashKind::try_from(word(self.bytes, 16))
+    }
+
+    /// Return the label of the crashed task, 0 if it was not registered.
+    pub fn label(&self) -> u32 {
+        word(self.bytes, 20)
+    }
+
+    /// Return the uptime at which the crash occurred, in microseconds.
+    pub fn uptime_us(&self) -> u64 {
+        dword(self.bytes, 24)
+    }
+
+    /// Return the registers at capture.
+    pub fn registers(&self) -> Registers {
+        Registers {
+            pc: dword(self.bytes, 32),
+            lr: dword(self.bytes, 40),
+            sp: dword(self.bytes, 48),
+        }
+    }
+
+    /// Iterate over the declared memory regions.
+    pub fn regions(&self) -> impl Iterator<Item = DumpedRegion<'a>> + 'a {
+        let bytes = self.bytes;
+        (0..word(bytes, 56) as usize).map(move |index| {
+            let offset = HEADER_LEN + index * REGION_LEN;
+            DumpedRegion {
+                name: &bytes[offset..offset + MAX_REGION_NAME],
+                start: dword(bytes, offset + MAX_REGION_NAME),
+                len: dword(bytes, offset + MAX_REGION_NAME + 8),
+            }
+        })
+    }
+
+    /// Return the dumped stack, starting at the stack pointer.
+    pub fn stack(&self) -> &'a [u8] {
+        &self.bytes[self.bytes.len() - word(self.bytes, 60) as usize..]
+    }
+}
+
+/// Core dump configuration
+struct Config {
+    regions: &'static [Region],
+    /// Base address of the bound shared memory, 0 if none
+    shm: usize,
+}
+
+struct ConfigCell(UnsafeCell<Config>);
+
+// SAFETY: a Sentry task is single-threaded, and the configuration is never
+// borrowed across calls of `with_config`
+unsafe impl Sync for ConfigCell {}
+
+static CONFIG: ConfigCell = ConfigCell(UnsafeCell::new(Config {
+    regions: &[],
+    shm: 0,
+}));
+
+/// Execute `f` with an exclusive access to the configuration
+fn with_config<R>(f: impl FnOnce(&mut Config) -> R) -> R {
+    // SAFETY: see ConfigCell, `f` never reaches `with_config`
+    f(unsafe { &mut *CONFIG.0.get() })
+}
+
+/// Persistent core dump region
+#[unsafe(link_section = ".noinit.coredump")]
+static mut COREDUMP: MaybeUninit<[u8; COREDUMP_LEN]> = MaybeUninit::uninit();
+
+/// Declare the task memory layout, the region holding the stack pointer at
+/// capture bounding the dumped stack.
+///
+/// Only the first [`MAX_REGIONS`] regions are dumped.
+pub fn set_layout(regions: &'static [Region]) {
+    with_config(|config| config.regions = &regions[..regions.len().min(MAX_REGIONS)]);
+}
+
+/// Also write core dumps into the given shared memory.
+///
+/// The shared memory is consumed, and can't be unmapped anymore, so that it
+/// can be written at any time.
+///
+/// # Errors
+/// Returns a `Status::Invalid` error if the shared memory is too small or
+/// not writable, or propagates kernel errors if information retrieval fails.
+pub fn bind_shm(mut shm: Shm<Mapped>) -> Result<(), Error> {
+    let base = shm.base_address()?;
+    let len = shm.length()?;
+    if len < COREDUMP_LEN || !shm.is_writable() {
+        return Err(Error::new(Subsystem::CrashLog, Status::Invalid));
+    }
+    with_config(|config| config.shm = base);
+    Ok(())
+}
+
+/// Return the stack pointer, along with the program counter and link
+/// register of the caller.
+#[inline(always)]
+fn registers() -> Registers {
+    #[cfg(target_arch = "arm")]
+    {
+        let (pc, lr, sp): (u32, u32, u32);
+        // SAFETY: register reads only
+        unsafe {
+            core::arch::asm!(
+                "mov {0}, pc",
+                "mov {1}, lr",
+                "mov {2}, sp",
+                out(reg) pc,
+                out(reg) lr,
+                out(reg) sp,
+                options(nomem, nostack, preserves_flags),
+            );
+        }
+        Registers {
+            pc: pc.into(),
+            lr: lr.into(),
+            sp: sp.into(),
+        }
+    }
+    #[cfg(not(target_arch = "arm"))]
+    {
+        // the host backends only approximate the stack pointer
+        let marker = 0_u8;
+        Registers {
+            pc: 0,
+            lr: 0,
+            sp: (&raw const marker).expose_provenance() as u64,
+        }
+    }
+}
+
+/// Write a core dump, replacing the previous one. Called by
+/// [`crashlog::record`](crate::crashlog::record).
+#[inline(never)]
+pub(crate) fn capture(kind: CrashKind) {
+    let registers = registers();
+    let regions = with_config(|config| config.regions);
+    let base = (&raw mut COREDUMP).cast::<u8>();
+    let put = |offset: usize, bytes: &[u8]| {
+        for (index, &byte) in bytes.iter().enumerate() {
+            // SAFETY: a Sentry task is single-threaded, the region is only
+            // accessed through raw pointers, and offsets are below its length
+            unsafe { base.add(offset + index).write_volatile(byte) };
+        }
+    };
+
+    put(16, &(kind as u32).to_le_bytes());
+    let label = crate::process::current_label().unwrap_or_default();
+    put(20, &label.to_le_bytes());
+    put(
+        24,
+        &crate::time::uptime_us().unwrap_or_default().to_le_bytes(),
+    );
+    put(32, &registers.pc.to_le_bytes());
+    put(40, &registers.lr.to_le_bytes());
+    put(48, &registers.sp.to_le_bytes());
+
+    let mut offset = HEADER_LEN;
+    for region in regions {
+        let mut name = [0; MAX_REGION_NAME];
+        let len = region.name.len().min(MAX_REGION_NAME);
+        name[..len].copy_from_slice(&region.name.as_bytes()[..len]);
+        put(offset, &name);
+        put(
+            offset + MAX_REGION_NAME,
+            &(region.start as u64).to_le_bytes(),
+        );
+        put(
+            offset + MAX_REGION_NAME + 8,
+            &(region.len as u64).to_le_bytes(),
+        );
+        offset += REGION_LEN;
+    }
+
+    let sp = usize::try_from(registers.sp).unwrap_or_default();
+    let stack_end = regions
+        .iter()
+        .find(|region| (region.start..region.start + region.len).contains(&sp))
+        .map_or(sp, |region| region.start + region.len);
+    let stack_len = (stack_end - sp).min(COREDUMP_LEN - offset);
+    for index in 0..stack_len {
+        // SAFETY: the stack is in the region declared by the application
+        let byte = unsafe { core::ptr::with_exposed_provenance::<u8>(sp + index).read_volatile() };
+        put(offset + index, &[byte]);
+    }
+    let len = offset + stack_len;
+
+    put(56, &(regions.len() as u32).to_le_bytes());
+    put(60, &(stack_len as u32).to_le_bytes());
+    put(8, &(len as u32).to_le_bytes());
+    // SAFETY: see `put`
+    let digest = checksum((16..len).map(|index| unsafe { base.add(index).read_volatile() }));
+    put(12, &digest.to_le_bytes());
+    put(4, &VERSION.to_le_bytes());
+    put(0, &MAGIC.to_le_bytes());
+
+    let shm = with_config(|config| config.shm);
+    if shm != 0 {
+        let dest = core::ptr::with_exposed_provenance_mut::<u8>(shm);
+        for index in 0..len {
+            // SAFETY: the bound shared memory is mapped forever, and larger
+            // than the dump
+            unsafe {
+                dest.add(index)
+                    .write_volatile(base.add(index).read_volatile())
+            };
+        }
+    }
+}
+
+/// Copy the last recorded core dump to `out`, returning it, if any.
+pub fn last(out: &mut [u8; COREDUMP_LEN]) -> Option<CoreDump<'_>> {
+    let base = (&raw const COREDUMP).cast::<u8>();
+    for (index, byte) in out.iter_mut().enumerate() {
+        // SAFETY: see `capture`, any content being checked by `decode`
+        *byte = unsafe { base.add(index).read_volatile() };
+    }
+    CoreDump::decode(out)
+}
+
+/// Clear the last recorded core dump, typically once forwarded.
+pub fn clear() {
+    // SAFETY: see `capture`
+    unsafe { (&raw mut COREDUMP).cast::<u32>().write_volatile(0) };
+}
@@ -0,0 +1,270 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Persistent crash report
+//!
+//! When a task dies, the panic (or fault) handler writes a short report in a
+//! dedicated, no-init, RAM region. This region is neither loaded nor zeroified
+//! by the kernel at task startup (see the `.noinit` section of the Shield
+//! linker script), so that the report can be retrieved with [`last`] when the
+//! task is restarted, typically to forward it to a supervising task.
+//!
+//! The report can also be written into a shared memory bound with
+//! [`bind_shm`], so that the supervisor reads it directly with
+//! [`CrashReport::read_from`].
+//!
+//! With the `coredump` feature, a core dump is written along with each
+//! report written by [`record`] or [`abort`], see [`crate::coredump`].
+//!
+//! A task reports its own unrecoverable errors with [`abort`], which writes
+//! the report, then exits.
+//!
+//! > **NOTE**: the panic handler of a Shield task is delivered by the
+//! > `sentry-uapi` crate, which loops forever without writing any report, and
+//! > can't be replaced as long as `sentry-uapi` defines it unconditionally:
+//! > [`record_panic`] is meant for a task panic handler, once one can be
+//! > installed. Likewise, the Sentry kernel terminates a faulting task without
+//! > notifying it, so that a [`CrashKind::Fault`] report is only written by a
+//! > platform specific fault handler.
+
+use core::fmt::{self, Write};
+use core::mem::{MaybeUninit, size_of};
+use core::panic::PanicInfo;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+use crate::shm::{Mapped, Shm};
+
+/// Maximum length of a crash report message
+pub const CRASHLOG_MSG_LEN: usize = 128;
+
+/// Exit status of a task calling [`abort`]
+pub const ABORT_STATUS: i32 = -1;
+
+/// Valid crash report marker ("CRSH")
+const CRASHLOG_MAGIC: u32 = 0x4352_5348;
+
+/// Origin of a crash report
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrashKind {
+    /// Rust panic
+    Panic = 1,
+    /// Hardware fault
+    Fault = 2,
+    /// Explicit abort requested by the application
+    Abort = 3,
+}
+
+impl TryFrom<u32> for CrashKind {
+    type Error = Error;
+
+    fn try_from(kind: u32) -> Result<Self, Self::Error> {
+        match kind {
+            1 => Ok(CrashKind::Panic),
+            2 => Ok(CrashKind::Fault),
+            3 => Ok(CrashKind::Abort),
+            _ => Err(Error::new(Subsystem::CrashLog, Status::Invalid)),
+        }
+    }
+}
+
+/// Crash report, as stored in the persistent region
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CrashReport {
+    magic: u32,
+    kind: u32,
+    len: u32,
+    checksum: u32,
+    msg: [u8; CRASHLOG_MSG_LEN],
+}
+
+impl CrashReport {
+    const fn empty() -> Self {
+        Self {
+            magic: 0,
+            kind: 0,
+            len: 0,
+            checksum: 0,
+            msg: [0; CRASHLOG_MSG_LEN],
+        }
+    }
+
+    /// Return the origin of the crash.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the report holds an unknown kind.
+    pub fn kind(&self) -> Result<CrashKind, Error> {
+        CrashKind::try_from(self.kind)
+    }
+
+    /// Return the crash message, possibly truncated.
+    pub fn message(&self) -> &str {
+        let msg = &self.msg[..self.msg_len()];
+        match core::str::from_utf8(msg) {
+            Ok(msg) => msg,
+            // keep the longest valid part of the message
+            Err(err) => core::str::from_utf8(&msg[..err.valid_up_to()]).unwrap_or_default(),
+        }
+    }
+
+    /// Return the raw report, typically to forward it to another task.
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: repr(C) structure made of integers only, without padding
+        unsafe {
+            core::slice::from_raw_parts(core::ptr::from_ref(self).cast::<u8>(), size_of::<Self>())
+        }
+    }
+
+    /// Rebuild a report from its raw form, as delivered by [`CrashReport::as_bytes`].
+    ///
+    /// Returns `None` if the content is not a valid report.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < size_of::<Self>() {
+            return None;
+        }
+        // SAFETY: length checked, any bit pattern is a valid CrashReport
+        let report = unsafe { bytes.as_ptr().cast::<Self>().read_unaligned() };
+        report.is_valid().then_some(report)
+    }
+
+    /// Read the report written by a peer task in a shared memory bound with
+    /// [`bind_shm`].
+    ///
+    /// Returns `Ok(None)` if the shared memory does not hold a valid report.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the shared memory is too small, or
+    /// propagates kernel errors if information retrieval fails.
+    pub fn read_from(shm: &mut Shm<Mapped>) -> Result<Option<Self>, Error> {
+        if shm.length()? < size_of::<Self>() {
+            return Err(Error::new(Subsystem::CrashLog, Status::Invalid));
+        }
+        let base: *const Self = core::ptr::with_exposed_provenance(shm.base_address()?);
+        // SAFETY: the shared memory is mapped and large enough
+        let report = unsafe { base.read_volatile() };
+        Ok(report.is_valid().then_some(report))
+    }
+
+    fn msg_len(&self) -> usize {
+        usize::try_from(self.len).map_or(0, |len| len.min(CRASHLOG_MSG_LEN))
+    }
+
+    fn compute_checksum(&self) -> u32 {
+        // FNV-1a over the report content
+        self.kind
+            .to_le_bytes()
+            .iter()
+            .chain(self.len.to_le_bytes().iter())
+            .chain(self.msg[..self.msg_len()].iter())
+            .fold(0x811c_9dc5_u32, |hash, &byte| {
+                (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+            })
+    }
+
+    fn is_valid(&self) -> bool {
+        self.magic == CRASHLOG_MAGIC && self.checksum == self.compute_checksum()
+    }
+}
+
+impl Write for CrashReport {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let len = self.msg_len();
+        let mut count = s.len().min(CRASHLOG_MSG_LEN - len);
+        while !s.is_char_boundary(count) {
+            count -= 1;
+        }
+        self.msg[len..len + count].copy_from_slice(&s.as_bytes()[..count]);
+        self.len += u32::try_from(count).map_err(|_| fmt::Error)?;
+        Ok(())
+    }
+}
+
+/// Persistent crash report region
+#[unsafe(link_section = ".noinit.crashlog")]
+static mut CRASHLOG: MaybeUninit<CrashReport> = MaybeUninit::uninit();
+
+/// Base address of the bound shared memory, 0 if none
+static CRASHLOG_SHM: AtomicUsize = AtomicUsize::new(0);
+
+/// Write a crash report, replacing the previous one.
+pub fn record(kind: CrashKind, args: fmt::Arguments) {
+    let mut report = CrashReport::empty();
+    report.kind = kind as u32;
+    let _ = report.write_fmt(args);
+    report.checksum = report.compute_checksum();
+    report.magic = CRASHLOG_MAGIC;
+
+    // SAFETY: a Sentry task is single-threaded, the region is only accessed
+    // through raw pointers
+    unsafe {
+        (&raw mut CRASHLOG)
+            .cast::<CrashReport>()
+            .write_volatile(report)
+    };
+
+    let shm = CRASHLOG_SHM.load(Ordering::Acquire);
+    if shm != 0 {
+        // SAFETY: the bound shared memory is mapped forever, and large enough
+        unsafe {
+            core::ptr::with_exposed_provenance_mut::<CrashReport>(shm).write_volatile(report)
+        };
+    }
+    #[cfg(feature = "coredump")]
+    crate::coredump::capture(kind);
+}
+
+/// Write a crash report for the given panic.
+pub fn record_panic(info: &PanicInfo) {
+    record(CrashKind::Panic, format_args!("{info}"));
+}
+
+/// Write an [`CrashKind::Abort`] crash report, then exit the task with
+/// [`ABORT_STATUS`].
+pub fn abort(args: fmt::Arguments) -> ! {
+    record(CrashKind::Abort, args);
+    loop {
+        crate::sys::syscall::exit(ABORT_STATUS);
+    }
+}
+
+/// Return the last recorded crash report, if any.
+pub fn last() -> Option<CrashReport> {
+    // SAFETY: any bit pattern is a valid CrashReport, validity is checked
+    // afterward
+    let report = unsafe { (&raw const CRASHLOG).cast::<CrashReport>().read_volatile() };
+    report.is_valid().then_some(report)
+}
+
+/// Clear the last recorded crash report, typically once forwarded.
+pub fn clear() {
+    // SAFETY: see `record`
+    unsafe {
+        (&raw mut CRASHLOG)
+            .cast::<CrashReport>()
+            .write_volatile(CrashReport::empty())
+    };
+}
+
+/// Also write crash reports into the given shared memory.
+///
+/// The shared memory is consumed, and can't be unmapped anymore, so that it
+/// can be written at any time.
+///
+/// # Errors
+/// Returns a `Status::Invalid` error if the shared memory is too small, misaligned or
+/// not writable, or propagates kernel errors if information retrieval fails.
+pub fn bind_shm(mut shm: Shm<Mapped>) -> Result<(), Error> {
+    let base = shm.base_address()?;
+    if shm.length()? < size_of::<CrashReport>()
+        || base % align_of::<CrashReport>() != 0
+        || !shm.is_writable()
+    {
+        return Err(Error::new(Subsystem::CrashLog, Status::Invalid));
+    }
+    CRASHLOG_SHM.store(base, Ordering::Release);
+    Ok(())
+}
@@ -0,0 +1,109 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! `defmt` global logger over the kernel log channel
+//!
+//! defmt frames are encoded (rzcobs) and emitted through the kernel `log`
+//! syscall, in chunks of the kernel exchange area size. Frames are decoded on
+//! the host side using the task ELF file, as for any defmt transport.
+//!
+//! The task linker script must include the `defmt.x` linker script delivered
+//! by the defmt crate.
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::sys::{copy_to_kernel, syscall};
+
+/// Emission buffer length, matching the kernel exchange area size
+const CHUNK_LEN: usize = uapi::length();
+
+struct Chunk {
+    buf: [u8; CHUNK_LEN],
+    len: usize,
+}
+
+impl Chunk {
+    fn push(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            if self.len == CHUNK_LEN {
+                self.flush();
+            }
+            self.buf[self.len] = byte;
+            self.len += 1;
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+        let chunk = &self.buf[..self.len];
+        if copy_to_kernel(&chunk).is_ok() {
+            syscall::log(chunk.len());
+        }
+        self.len = 0;
+    }
+}
+
+struct LoggerState {
+    encoder: defmt::Encoder,
+    chunk: Chunk,
+}
+
+struct SharedState(UnsafeCell<LoggerState>);
+
+// SAFETY: a Sentry task is single-threaded, and the state is only accessed
+// between acquire and release, protected by the TAKEN flag
+unsafe impl Sync for SharedState {}
+
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+static STATE: SharedState = SharedState(UnsafeCell::new(LoggerState {
+    encoder: defmt::Encoder::new(),
+    chunk: Chunk {
+        buf: [0; CHUNK_LEN],
+        len: 0,
+    },
+}));
+
+#[defmt::global_logger]
+struct ShieldDefmtLogger;
+
+// SAFETY: acquire/release pairing is checked through the TAKEN flag
+unsafe impl defmt::Logger for ShieldDefmtLogger {
+    fn acquire() {
+        // a Sentry task being single-threaded, a taken logger means that
+        // defmt is being called from the logger itself
+        assert!(
+            !TAKEN.load(Ordering::Acquire),
+            "defmt logger taken reentrantly"
+        );
+        TAKEN.store(true, Ordering::Release);
+
+        // SAFETY: exclusive access guaranteed by the TAKEN flag
+        let state = unsafe { &mut *STATE.0.get() };
+        state.encoder.start_frame(|bytes| state.chunk.push(bytes));
+    }
+
+    unsafe fn flush() {
+        // SAFETY: called between acquire and release
+        let state = unsafe { &mut *STATE.0.get() };
+        state.chunk.flush();
+    }
+
+    unsafe fn release() {
+        // SAFETY: called between acquire and release
+        let state = unsafe { &mut *STATE.0.get() };
+        state.encoder.end_frame(|bytes| state.chunk.push(bytes));
+        state.chunk.flush();
+        TAKEN.store(false, Ordering::Release);
+    }
+
+    unsafe fn write(bytes: &[u8]) {
+        // SAFETY: called between acquire and release
+        let state = unsafe { &mut *STATE.0.get() };
+        state.encoder.write(bytes, |bytes| state.chunk.push(bytes));
+    }
+}
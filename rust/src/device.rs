@@ -0,0 +1,190 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Memory mapped devices
+//!
+//! A device owned by the task is mapped in its address space with
+//! [`Device::map`], at the address of its registers. The kernel doesn't
+//! report this address, which is known to the peripheral access crate of the
+//! SoC instead: [`Device::peripheral`] plugs the svd2rust generated peripheral
+//! types into the mapping, the peripheral being usable as long as the device
+//! stays mapped.
+//!
+//! ```ignore
+//! shield::stealable!(pac::USART1);
+//!
+//! let usart = devices::USART1.device()?.map()?;
+//! let regs = usart.peripheral::<pac::USART1>();
+//! regs.cr1().modify(|_, w| w.ue().set_bit());
+//! ```
+
+use core::marker::PhantomData;
+use core::ops::Deref;
+use uapi::systypes::{DeviceHandle, Status};
+
+use crate::error::{Error, Subsystem};
+
+/// Marker type representing an **unmapped** device.
+pub struct Unmapped;
+
+/// Marker type representing a **mapped** device.
+pub struct Mapped;
+
+/// Device owned by the task, using the *typestate* pattern.
+///
+/// # Typestate
+/// - [`Device<Unmapped>`]: the device is owned but not mapped
+/// - [`Device<Mapped>`]: the device registers are mapped
+pub struct Device<State> {
+    handle: DeviceHandle,
+    label: u8,
+    _state: PhantomData<State>,
+}
+
+impl<State> Device<State> {
+    /// Return the device handle.
+    pub fn handle(&self) -> DeviceHandle {
+        self.handle
+    }
+
+    /// Return the device label.
+    pub fn label(&self) -> u8 {
+        self.label
+    }
+
+    fn error(&self, status: Status) -> Error {
+        Error::new(Subsystem::Device, status).with_handle(self.handle)
+    }
+
+    fn into_state<Next>(self) -> Device<Next> {
+        Device {
+            handle: self.handle,
+            label: self.label,
+            _state: PhantomData,
+        }
+    }
+}
+
+impl Device<Unmapped> {
+    /// Retrieve the device `label`, unmapped.
+    ///
+    /// # Errors
+    /// Propagates kernel errors, typically `Status::Denied` if the device is
+    /// not owned by the task, along with the label.
+    #[cfg_attr(feature = "audit-syscalls", track_caller)]
+    pub fn new(label: u8) -> Result<Self, Error> {
+        let error = |status| Error::new(Subsystem::Device, status).with_handle(u32::from(label));
+        match crate::sys::syscall::get_device_handle(label) {
+            Status::Ok => {}
+            status => return Err(error(status)),
+        }
+        let mut handle = 0;
+        match crate::sys::copy_from_kernel(&mut handle) {
+            Ok(Status::Ok) => Ok(Self {
+                handle,
+                label,
+                _state: PhantomData,
+            }),
+            Ok(status) | Err(status) => Err(error(status)),
+        }
+    }
+
+    /// Map the device registers.
+    ///
+    /// # Errors
+    /// Returns kernel errors such as `Status::Busy` if too many regions are
+    /// mapped, along with the device handle.
+    #[cfg_attr(feature = "audit-syscalls", track_caller)]
+    pub fn map(self) -> Result<Device<Mapped>, Error> {
+        match crate::sys::syscall::map_dev(self.handle) {
+            Status::Ok => Ok(self.into_state()),
+            status => Err(self.error(status)),
+        }
+    }
+}
+
+impl Device<Mapped> {
+    /// Unmap the device registers.
+    ///
+    /// # Errors
+    /// Returns kernel errors if unmapping fails.
+    #[cfg_attr(feature = "audit-syscalls", track_caller)]
+    pub fn unmap(self) -> Result<Device<Unmapped>, Error> {
+        match crate::sys::syscall::unmap_dev(self.handle) {
+            Status::Ok => Ok(self.into_state()),
+            status => Err(self.error(status)),
+        }
+    }
+
+    /// Return the peripheral `P` of the mapped device registers.
+    ///
+    /// The peripheral borrows the device, so that it can't be used once
+    /// unmapped.
+    pub fn peripheral<P: StealablePeripheral>(&self) -> Peripheral<'_, P> {
+        Peripheral {
+            // SAFETY: the device being mapped, its registers are accessible
+            // to the task, during the borrow
+            peripheral: unsafe { P::steal() },
+            _device: PhantomData,
+        }
+    }
+}
+
+/// Peripheral type of a peripheral access crate, svd2rust generated ones
+/// being adapted with [`stealable!`](crate::stealable!)
+///
+/// # Safety
+/// [`StealablePeripheral::REGISTERS`] must be the address of the peripheral
+/// registers, accessed by the stolen peripheral.
+pub unsafe trait StealablePeripheral: Sized {
+    /// Registers base address
+    const REGISTERS: *const ();
+
+    /// Return an instance of the peripheral, with no ownership tracking.
+    ///
+    /// # Safety
+    /// The registers must be mapped in the task address space for the
+    /// lifetime of the instance.
+    unsafe fn steal() -> Self;
+}
+
+/// Implement [`StealablePeripheral`] for svd2rust generated peripheral types,
+/// from their `PTR` constant and `steal` function.
+#[macro_export]
+macro_rules! stealable {
+    ($($peripheral:ty),+ $(,)?) => {
+        $(
+            // SAFETY: svd2rust peripherals access the registers at `PTR`
+            unsafe impl $crate::device::StealablePeripheral for $peripheral {
+                const REGISTERS: *const () = <$peripheral>::PTR.cast();
+
+                unsafe fn steal() -> Self {
+                    // SAFETY: see the trait function contract
+                    unsafe { <$peripheral>::steal() }
+                }
+            }
+        )+
+    };
+}
+
+/// Peripheral of a mapped device, see [`Device::peripheral`]
+pub struct Peripheral<'a, P> {
+    peripheral: P,
+    _device: PhantomData<&'a Device<Mapped>>,
+}
+
+impl<P: StealablePeripheral> Peripheral<'_, P> {
+    /// Return the registers base address.
+    pub fn base_address(&self) -> usize {
+        P::REGISTERS.expose_provenance()
+    }
+}
+
+impl<P> Deref for Peripheral<'_, P> {
+    type Target = P;
+
+    fn deref(&self) -> &P {
+        &self.peripheral
+    }
+}
@@ -0,0 +1,205 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Double buffered framebuffer in shared memory
+//!
+//! [`Display`] lays out two RGB565 framebuffers in a shared memory, the panel
+//! controller scanning out the front one while the task draws into the back
+//! one. [`Display::swap`] hands the back buffer to the controller and waits
+//! for the vertical synchronization interrupt, after which the controller
+//! scans it out, so that no frame is ever shown half drawn.
+//!
+//! The display is an `embedded-graphics` [`DrawTarget`] over its back buffer
+//! (pixels out of the screen being clipped), so that UI code renders without
+//! knowing the panel controller details. Those are SoC or panel specific
+//! (LTDC layer, DSI or SPI bridge): the board support code gives access to
+//! them through the [`Panel`] trait.
+//!
+//! ```ignore
+//! let mut display = Display::new(Ltdc::mapped()?, Shm::new(FB_SHM)?.map(0)?, 480, 272, LTDC_IRQ)?;
+//! loop {
+//!     display.clear(Rgb565::BLACK)?;
+//!     Text::new("Hello", Point::new(10, 20), style).draw(&mut display)?;
+//!     display.swap().await?;
+//! }
+//! ```
+
+use core::convert::Infallible;
+use embedded_graphics_core::Pixel;
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{Dimensions, OriginDimensions, Size};
+use embedded_graphics_core::pixelcolor::{IntoStorage, Rgb565};
+use embedded_graphics_core::primitives::Rectangle;
+use uapi::systypes::{EventType, Status};
+
+use crate::error::{Error, Subsystem};
+use crate::executor;
+use crate::shm::{Mapped, Shm};
+
+/// Access to the registers of a mapped panel controller
+pub trait Panel {
+    /// Set the address of the framebuffer to scan out, taking effect on the
+    /// next vertical blanking.
+    fn set_front(&mut self, address: usize);
+
+    /// Clear the vertical synchronization interrupt flag.
+    fn clear_vsync(&mut self);
+}
+
+/// Double buffered framebuffer, see the [module](self) documentation
+pub struct Display<P> {
+    panel: P,
+    shm: Shm<Mapped>,
+    base: usize,
+    width: u16,
+    height: u16,
+    /// Index of the buffer scanned out
+    front: usize,
+    irq: u16,
+}
+
+impl<P: Panel> Display<P> {
+    /// Lay out two `width` × `height` framebuffers in `shm`, scan the first
+    /// one out and unmask the vertical synchronization interrupt `irq`.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the shared memory is too small
+    /// for two framebuffers, misaligned or not writable, or propagates kernel
+    /// errors if information retrieval fails or the interrupt can't be
+    /// enabled.
+    pub fn new(
+        mut panel: P,
+        mut shm: Shm<Mapped>,
+        width: u16,
+        height: u16,
+        irq: u16,
+    ) -> Result<Self, Error> {
+        let invalid = shm.error(Status::Invalid);
+        let base = shm.base_address()?;
+        let frame_len = usize::from(width) * usize::from(height) * size_of::<u16>();
+        if frame_len == 0
+            || shm.length()? / 2 < frame_len
+            || base % align_of::<u32>() != 0
+            || !shm.is_writable()
+        {
+            return Err(invalid);
+        }
+        panel.set_front(base);
+        let display = Self {
+            panel,
+            shm,
+            base,
+            width,
+            height,
+            front: 0,
+            irq,
+        };
+        display.check(crate::sys::syscall::irq_enable(irq))?;
+        Ok(display)
+    }
+
+    /// Mask the vertical synchronization interrupt, then release the panel
+    /// controller and the shared memory.
+    pub fn release(self) -> (P, Shm<Mapped>) {
+        let _ = crate::sys::syscall::irq_disable(self.irq);
+        (self.panel, self.shm)
+    }
+
+    /// Return the screen width, in pixels.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Return the screen height, in pixels.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Return the back buffer pixels, row after row, as their raw RGB565
+    /// values.
+    pub fn back_buffer(&mut self) -> &mut [u16] {
+        let len = usize::from(self.width) * usize::from(self.height);
+        let address = self.base + (1 - self.front) * len * size_of::<u16>();
+        // SAFETY: the back buffer is in the shared memory, checked at
+        // creation, and not read by the controller until swapped
+        unsafe {
+            core::slice::from_raw_parts_mut(core::ptr::with_exposed_provenance_mut(address), len)
+        }
+    }
+
+    /// Scan out the back buffer, waiting for the vertical synchronization.
+    ///
+    /// The front buffer then becomes the back one, holding the frame drawn
+    /// before the swapped one.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the interrupt can't be acknowledged.
+    pub async fn swap(&mut self) -> Result<(), Error> {
+        let back = 1 - self.front;
+        let frame_len = usize::from(self.width) * usize::from(self.height) * size_of::<u16>();
+        // the drawn pixels must reach the memory before the controller reads
+        // them
+        core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+        self.panel.set_front(self.base + back * frame_len);
+        executor::wait_event_from(EventType::Irq, u32::from(self.irq)).await;
+        self.panel.clear_vsync();
+        self.front = back;
+        self.check(crate::sys::syscall::irq_acknowledge(self.irq))
+    }
+
+    fn check(&self, status: Status) -> Result<(), Error> {
+        match status {
+            Status::Ok => Ok(()),
+            status => Err(Error::new(Subsystem::Display, status).with_handle(u32::from(self.irq))),
+        }
+    }
+}
+
+impl<P> OriginDimensions for Display<P> {
+    fn size(&self) -> Size {
+        Size::new(u32::from(self.width), u32::from(self.height))
+    }
+}
+
+impl<P: Panel> DrawTarget for Display<P> {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    /// Draw the given pixels into the back buffer, those out of the screen
+    /// being ignored.
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Infallible>
+    where
+        I: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        let (width, height) = (i32::from(self.width), i32::from(self.height));
+        let buffer = self.back_buffer();
+        for Pixel(point, color) in pixels {
+            if (0..width).contains(&point.x) && (0..height).contains(&point.y) {
+                buffer[(point.y * width + point.x) as usize] = color.into_storage();
+            }
+        }
+        Ok(())
+    }
+
+    /// Fill a rectangle of the back buffer, clipped to the screen.
+    fn fill_solid(&mut self, area: &Rectangle, color: Rgb565) -> Result<(), Infallible> {
+        let area = area.intersection(&self.bounding_box());
+        let (rows, columns) = (area.rows(), area.columns());
+        let stride = usize::from(self.width);
+        let buffer = self.back_buffer();
+        // the clipped rectangle is within the screen
+        let (start, end) = (columns.start as usize, columns.end as usize);
+        for row in rows {
+            let row = row as usize * stride;
+            buffer[row + start..row + end].fill(color.into_storage());
+        }
+        Ok(())
+    }
+
+    /// Fill the whole back buffer.
+    fn clear(&mut self, color: Rgb565) -> Result<(), Infallible> {
+        self.back_buffer().fill(color.into_storage());
+        Ok(())
+    }
+}
@@ -0,0 +1,309 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! DMA streams
+//!
+//! Sentry DMA streams are statically configured (source, destination, length)
+//! in the device tree. A task owning a stream only assigns it to its hardware
+//! channel, and starts or suspends it. Completion and errors are reported as
+//! DMA events.
+//!
+//! With the `async` feature, [`DmaStream::transfer`] packages a complete
+//! transfer as a future, driven by the [`crate::executor`]:
+//!
+//! ```ignore
+//! let mut stream = DmaStream::new(ADC_STREAM)?;
+//! let frame = stream.transfer(frame).await.map_err(|err| err.error)?;
+//! ```
+//!
+//! Out of the executor, [`DmaStream::transfer_blocking`] waits for the
+//! completion event instead.
+
+#[cfg(feature = "async")]
+use core::future::Future;
+#[cfg(feature = "async")]
+use core::pin::Pin;
+#[cfg(feature = "async")]
+use core::task::{Context, Poll};
+use uapi::systypes::dma::{GpdmaChanInt, GpdmaStreamConfig};
+use uapi::systypes::{EventType, ExchangeHeader, Status, StreamHandle, StreamLabel};
+
+use crate::error::{Error, Subsystem};
+#[cfg(feature = "async")]
+use crate::executor::{self, EventFuture};
+
+/// `wait_for_event()` timeout value for an infinite wait
+const WFE_WAIT_FOREVER: i32 = 0;
+
+/// Maximum length of the data of an event
+const EVENT_DATA_LEN: usize = uapi::length() - size_of::<ExchangeHeader>();
+
+/// DMA stream owned by the current task
+pub struct DmaStream {
+    handle: StreamHandle,
+}
+
+impl DmaStream {
+    /// Retrieve the stream from its label and assign it to its hardware
+    /// channel.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the stream can't be retrieved or assigned.
+    #[cfg_attr(feature = "audit-syscalls", track_caller)]
+    pub fn new(label: StreamLabel) -> Result<Self, Error> {
+        let error = |status| Error::new(Subsystem::Dma, status);
+        match crate::sys::syscall::get_dma_stream_handle(label) {
+            Status::Ok => {}
+            status => return Err(error(status)),
+        }
+        let mut handle = 0;
+        match crate::sys::copy_from_kernel(&mut handle) {
+            Ok(Status::Ok) => {}
+            Ok(status) | Err(status) => return Err(error(status)),
+        }
+
+        let stream = Self { handle };
+        stream.check(crate::sys::syscall::dma_assign_stream(handle))?;
+        Ok(stream)
+    }
+
+    /// Stream handle
+    pub fn handle(&self) -> StreamHandle {
+        self.handle
+    }
+
+    /// Return the static configuration of the stream.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the configuration can't be retrieved.
+    pub fn info(&self) -> Result<GpdmaStreamConfig, Error> {
+        self.check(crate::sys::syscall::dma_get_stream_info(self.handle))?;
+        let mut info = GpdmaStreamConfig {
+            channel: 0,
+            stream: 0,
+            controller: 0,
+            transfer_type: 0,
+            source: 0,
+            dest: 0,
+            transfer_len: 0,
+            circular_source: false,
+            circular_dest: false,
+            interrupts: 0,
+            is_triggered: false,
+            trigger: 0,
+            priority: 0,
+            transfer_mode: 0,
+            src_beat_len: 0,
+            dest_beat_len: 0,
+        };
+        match crate::sys::copy_from_kernel(&mut info) {
+            Ok(Status::Ok) => Ok(info),
+            Ok(status) | Err(status) => Err(self.error(status)),
+        }
+    }
+
+    /// Start the stream.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the stream can't be started.
+    #[inline]
+    pub fn start(&self) -> Result<(), Error> {
+        self.check(crate::sys::syscall::dma_start_stream(self.handle))
+    }
+
+    /// Suspend the stream.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the stream can't be suspended.
+    #[inline]
+    pub fn suspend(&self) -> Result<(), Error> {
+        self.check(crate::sys::syscall::dma_suspend_stream(self.handle))
+    }
+
+    /// Resume a suspended stream.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the stream can't be resumed.
+    #[inline]
+    pub fn resume(&self) -> Result<(), Error> {
+        self.check(crate::sys::syscall::dma_resume_stream(self.handle))
+    }
+
+    /// Unassign the stream from its hardware channel.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the stream can't be unassigned, returning
+    /// the stream.
+    pub fn release(self) -> Result<(), (Error, Self)> {
+        match crate::sys::syscall::dma_unassign_stream(self.handle) {
+            Status::Ok => Ok(()),
+            status => Err((self.error(status), self)),
+        }
+    }
+
+    /// Run a complete transfer of the stream.
+    ///
+    /// `buffer` is the memory targeted by the stream configuration (e.g. the
+    /// mapped shared memory). It is owned by the transfer while the hardware
+    /// accesses it, and given back on completion.
+    ///
+    /// Dropping the transfer before its completion suspends the stream.
+    #[cfg(feature = "async")]
+    pub fn transfer<B>(&mut self, buffer: B) -> Transfer<'_, B> {
+        let event = executor::wait_event_from(EventType::Dma, self.handle);
+        Transfer {
+            stream: self,
+            buffer: Some(buffer),
+            event,
+            started: false,
+        }
+    }
+
+    /// Run a complete transfer of the stream, blocking until its completion.
+    ///
+    /// This is the counterpart of [`DmaStream::transfer`] for the code
+    /// running out of the executor: the events received meanwhile, other
+    /// than the stream ones, are dropped.
+    ///
+    /// # Errors
+    /// Returns a `Status::Critical` error if the hardware reports a transfer
+    /// error, or propagates kernel errors. The stream is suspended on
+    /// failure.
+    pub fn transfer_blocking(&mut self) -> Result<(), Error> {
+        self.start()?;
+        let result = self.wait_completion();
+        if result.is_err() {
+            let _ = self.suspend();
+        }
+        result
+    }
+
+    /// Wait for the completion event of the started stream.
+    fn wait_completion(&self) -> Result<(), Error> {
+        loop {
+            match crate::sys::syscall::wait_for_event(EventType::Dma.into(), WFE_WAIT_FOREVER) {
+                Status::Ok => {}
+                status => return Err(self.error(status)),
+            }
+            let mut data = [0; EVENT_DATA_LEN];
+            let mut event = uapi::systypes::Event {
+                header: ExchangeHeader {
+                    event: 0,
+                    length: 0,
+                    magic: 0,
+                    peer: 0,
+                },
+                data: &mut data,
+            };
+            match crate::sys::copy_from_kernel(&mut event) {
+                Ok(Status::Ok) => {}
+                Ok(status) | Err(status) => return Err(self.error(status)),
+            }
+            // stream handle, followed by the channel interrupt flags
+            let len = usize::from(event.header.length);
+            if len < 5 || data[..4] != self.handle.to_ne_bytes() {
+                continue;
+            }
+            if data[4] & GpdmaChanInt::DmaError as u8 != 0 {
+                return Err(self.error(Status::Critical));
+            }
+            if data[4] & GpdmaChanInt::TransferComplete as u8 != 0 {
+                return Ok(());
+            }
+            // half transfer, wait for the next event
+        }
+    }
+
+    #[inline]
+    fn error(&self, status: Status) -> Error {
+        Error::new(Subsystem::Dma, status).with_handle(self.handle)
+    }
+
+    #[inline]
+    fn check(&self, status: Status) -> Result<(), Error> {
+        match status {
+            Status::Ok => Ok(()),
+            status => Err(self.error(status)),
+        }
+    }
+}
+
+/// Failed DMA transfer
+#[cfg(feature = "async")]
+pub struct TransferError<B> {
+    pub error: Error,
+    /// Transfer buffer, whose content is unspecified
+    pub buffer: B,
+}
+
+/// DMA transfer future, returned by [`DmaStream::transfer`]
+#[cfg(feature = "async")]
+pub struct Transfer<'a, B> {
+    stream: &'a mut DmaStream,
+    buffer: Option<B>,
+    event: EventFuture,
+    started: bool,
+}
+
+// the buffer is never pinned
+#[cfg(feature = "async")]
+impl<B> Unpin for Transfer<'_, B> {}
+
+#[cfg(feature = "async")]
+impl<B> Transfer<'_, B> {
+    fn complete(&mut self, result: Result<(), Error>) -> Poll<Result<B, TransferError<B>>> {
+        self.started = false;
+        let Some(buffer) = self.buffer.take() else {
+            // polled again after completion
+            return Poll::Pending;
+        };
+        Poll::Ready(match result {
+            Ok(()) => Ok(buffer),
+            Err(error) => Err(TransferError { error, buffer }),
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<B> Future for Transfer<'_, B> {
+    type Output = Result<B, TransferError<B>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        if !this.started && this.buffer.is_some() {
+            if let Err(err) = this.stream.start() {
+                return this.complete(Err(err));
+            }
+            this.started = true;
+        }
+
+        loop {
+            let event = match Pin::new(&mut this.event).poll(cx) {
+                Poll::Ready(event) => event,
+                Poll::Pending => return Poll::Pending,
+            };
+            // stream handle, followed by the channel interrupt flags
+            let flags = event.data().get(4).copied().unwrap_or_default();
+            if flags & GpdmaChanInt::DmaError as u8 != 0 {
+                let error = this.stream.error(Status::Critical);
+                return this.complete(Err(error));
+            }
+            if flags & GpdmaChanInt::TransferComplete as u8 != 0 {
+                return this.complete(Ok(()));
+            }
+
+            // half transfer, wait for the next event
+            this.event = executor::wait_event_from(EventType::Dma, this.stream.handle);
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<B> Drop for Transfer<'_, B> {
+    fn drop(&mut self) {
+        if self.started {
+            let _ = self.stream.suspend();
+        }
+    }
+}
@@ -0,0 +1,34 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! `embassy-time` driver
+//!
+//! The time base is the kernel uptime clock. Timers are driven by the
+//! [`crate::executor`], which must be used to run the embassy based futures.
+
+use core::task::Waker;
+use embassy_time_driver::{Driver, TICK_HZ};
+
+use crate::executor::timer;
+use crate::time;
+
+struct ShieldTimeDriver;
+
+const US_PER_S: u64 = 1_000_000;
+
+impl Driver for ShieldTimeDriver {
+    fn now(&self) -> u64 {
+        let now_us = time::uptime_us().unwrap_or_default();
+        u64::try_from(u128::from(now_us) * u128::from(TICK_HZ) / u128::from(US_PER_S))
+            .unwrap_or(u64::MAX)
+    }
+
+    fn schedule_wake(&self, at: u64, waker: &Waker) {
+        let at_us = u128::from(at) * u128::from(US_PER_S);
+        let at_us = u64::try_from(at_us.div_ceil(u128::from(TICK_HZ))).unwrap_or(u64::MAX);
+        timer::schedule(at_us, waker);
+    }
+}
+
+embassy_time_driver::time_driver_impl!(static DRIVER: ShieldTimeDriver = ShieldTimeDriver);
@@ -0,0 +1,99 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Base64 encoding (RFC 4648)
+//!
+//! Bytes are encoded with the standard alphabet, padded. Padding is optional
+//! when decoding.
+//!
+//! ```ignore
+//! let mut text = [0; encoded_len(REPORT_LEN)];
+//! info!("report {}", base64::encode_to(&report, &mut text)?);
+//! ```
+
+use uapi::systypes::Status;
+
+use super::error;
+use crate::error::Error;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const PADDING: u8 = b'=';
+
+/// Return the length of the encoding of `len` bytes.
+pub const fn encoded_len(len: usize) -> usize {
+    len.div_ceil(3) * 4
+}
+
+/// Return the maximum decoded length of a `len` characters encoding.
+pub const fn max_decoded_len(len: usize) -> usize {
+    len.div_ceil(4) * 3
+}
+
+/// Encode `data` into `out`, returning the encoded string.
+///
+/// # Errors
+/// Returns a `Status::Busy` error if `out` is too small, see
+/// [`encoded_len`].
+pub fn encode_to<'a>(data: &[u8], out: &'a mut [u8]) -> Result<&'a str, Error> {
+    let out = out
+        .get_mut(..encoded_len(data.len()))
+        .ok_or(error(Status::Busy))?;
+    for (bytes, chars) in data.chunks(3).zip(out.chunks_exact_mut(4)) {
+        let mut group = [0; 3];
+        group[..bytes.len()].copy_from_slice(bytes);
+        let group = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+        for (index, char) in chars.iter_mut().enumerate() {
+            *char = match index <= bytes.len() {
+                true => ALPHABET[(group >> (18 - 6 * index) & 0x3f) as usize],
+                false => PADDING,
+            };
+        }
+    }
+    // SAFETY: the encoding is ASCII
+    Ok(unsafe { core::str::from_utf8_unchecked(out) })
+}
+
+fn sextet(char: u8) -> Result<u32, Error> {
+    let value = match char {
+        b'A'..=b'Z' => char - b'A',
+        b'a'..=b'z' => char - b'a' + 26,
+        b'0'..=b'9' => char - b'0' + 52,
+        b'+' => 62,
+        b'/' => 63,
+        _ => return Err(error(Status::Invalid)),
+    };
+    Ok(u32::from(value))
+}
+
+/// Decode `text` into `out`, returning the decoded length.
+///
+/// # Errors
+/// Returns a `Status::Invalid` error if `text` is not a base64 encoding, or
+/// a `Status::Busy` one if `out` is too small.
+pub fn decode(text: impl AsRef<[u8]>, out: &mut [u8]) -> Result<usize, Error> {
+    let mut text = text.as_ref();
+    if text.len() % 4 == 0 {
+        text = text
+            .strip_suffix(b"==")
+            .or_else(|| text.strip_suffix(b"="))
+            .unwrap_or(text);
+    }
+    if text.len() % 4 == 1 {
+        return Err(error(Status::Invalid));
+    }
+    let mut len = 0;
+    for chars in text.chunks(4) {
+        let mut group = 0;
+        for (index, &char) in chars.iter().enumerate() {
+            group |= sextet(char)? << (18 - 6 * index);
+        }
+        let bytes = &group.to_be_bytes()[1..chars.len()];
+        out.get_mut(len..len + bytes.len())
+            .ok_or(error(Status::Busy))?
+            .copy_from_slice(bytes);
+        len += bytes.len();
+    }
+    Ok(len)
+}
@@ -0,0 +1,820 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! CBOR encoding (RFC 8949)
+//!
+//! [`Encoder`] writes CBOR items into a buffer, and [`Decoder`] reads them
+//! back, borrowing strings from the decoded data. Types implementing
+//! [`Encode`] and [`Decode`] are written and read as a whole, e.g. as IPC
+//! messages with [`crate::ipc::IpcStream::send_cbor`] or as key-value store
+//! values with [`crate::kvstore::KvStore::set_cbor`].
+//!
+//! ```ignore
+//! struct Reading { sensor: u8, value: i32 }
+//!
+//! impl Encode for Reading {
+//!     fn encode(&self, e: &mut Encoder<'_>) -> Result<(), Error> {
+//!         e.array(2)?.u8(self.sensor)?.i32(self.value)?;
+//!         Ok(())
+//!     }
+//! }
+//!
+//! impl Decode<'_> for Reading {
+//!     fn decode(d: &mut Decoder<'_>) -> Result<Self, Error> {
+//!         if d.array()? != Some(2) {
+//!             return Err(Error::new(Subsystem::Encoding, Status::Invalid));
+//!         }
+//!         Ok(Self { sensor: d.u8()?, value: d.i32()? })
+//!     }
+//! }
+//! ```
+//!
+//! Byte and text strings are encoded with definite lengths, and decoded
+//! from such encodings only. Arrays and maps of either form are decoded.
+
+use uapi::systypes::Status;
+
+use super::error;
+use crate::collections::{FixedString, FixedVec};
+use crate::error::Error;
+
+/// Maximum nesting of the items skipped by [`Decoder::skip`]
+pub const MAX_NESTING: usize = 16;
+
+const UNSIGNED: u8 = 0;
+const NEGATIVE: u8 = 1;
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const TAG: u8 = 6;
+const SIMPLE: u8 = 7;
+
+const FALSE: u8 = 0xf4;
+const TRUE: u8 = 0xf5;
+const NULL: u8 = 0xf6;
+const UNDEFINED: u8 = 0xf7;
+const FLOAT32: u8 = 0xfa;
+const FLOAT64: u8 = 0xfb;
+const BREAK: u8 = 0xff;
+
+/// Additional information of indefinite length items
+const INDEFINITE: u8 = 31;
+
+/// CBOR item type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    /// Unsigned integer
+    Unsigned,
+    /// Negative integer
+    Negative,
+    /// Byte string
+    Bytes,
+    /// Text string
+    Text,
+    /// Array
+    Array,
+    /// Map
+    Map,
+    /// Tag
+    Tag,
+    /// `true` or `false`
+    Bool,
+    /// `null`
+    Null,
+    /// `undefined`
+    Undefined,
+    /// Floating-point number
+    Float,
+    /// End of an indefinite length array or map
+    Break,
+    /// Other simple value
+    Simple,
+}
+
+/// Type written as CBOR
+pub trait Encode {
+    /// Write the value to `e`.
+    ///
+    /// # Errors
+    /// Propagates the encoder errors.
+    fn encode(&self, e: &mut Encoder<'_>) -> Result<(), Error>;
+}
+
+/// Type read from CBOR, possibly borrowing from the decoded data
+pub trait Decode<'b>: Sized {
+    /// Read a value from `d`.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the next item is not a value of
+    /// the type, or propagates the decoder errors.
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error>;
+}
+
+/// CBOR encoder, writing into a buffer
+pub struct Encoder<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Encoder<'a> {
+    /// Create an encoder writing at the start of `buf`.
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    /// Return the length of the items written.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return whether no item was written.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the items written.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<&mut Self, Error> {
+        self.buf
+            .get_mut(self.len..self.len + data.len())
+            .ok_or(error(Status::Busy))?
+            .copy_from_slice(data);
+        self.len += data.len();
+        Ok(self)
+    }
+
+    /// Write an item header, with its argument in its shortest form.
+    fn header(&mut self, major: u8, argument: u64) -> Result<&mut Self, Error> {
+        let major = major << 5;
+        // the matches bound the casts
+        match argument {
+            0..=23 => self.write(&[major | argument as u8]),
+            24..=0xff => self.write(&[major | 24, argument as u8]),
+            0x100..=0xffff => {
+                self.write(&[major | 25])?;
+                self.write(&(argument as u16).to_be_bytes())
+            }
+            0x1_0000..=0xffff_ffff => {
+                self.write(&[major | 26])?;
+                self.write(&(argument as u32).to_be_bytes())
+            }
+            _ => {
+                self.write(&[major | 27])?;
+                self.write(&argument.to_be_bytes())
+            }
+        }
+    }
+
+    /// Write an unsigned integer.
+    ///
+    /// # Errors
+    /// Returns a `Status::Busy` error if the buffer is full, as all the
+    /// encoder methods.
+    pub fn u64(&mut self, value: u64) -> Result<&mut Self, Error> {
+        self.header(UNSIGNED, value)
+    }
+
+    /// Write an unsigned integer, see [`Encoder::u64`].
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn u32(&mut self, value: u32) -> Result<&mut Self, Error> {
+        self.u64(value.into())
+    }
+
+    /// Write an unsigned integer, see [`Encoder::u64`].
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn u16(&mut self, value: u16) -> Result<&mut Self, Error> {
+        self.u64(value.into())
+    }
+
+    /// Write an unsigned integer, see [`Encoder::u64`].
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn u8(&mut self, value: u8) -> Result<&mut Self, Error> {
+        self.u64(value.into())
+    }
+
+    /// Write a signed integer.
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn i64(&mut self, value: i64) -> Result<&mut Self, Error> {
+        match u64::try_from(value) {
+            Ok(value) => self.header(UNSIGNED, value),
+            // -1 - value, as an unsigned integer
+            Err(_) => self.header(NEGATIVE, !value as u64),
+        }
+    }
+
+    /// Write a signed integer, see [`Encoder::i64`].
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn i32(&mut self, value: i32) -> Result<&mut Self, Error> {
+        self.i64(value.into())
+    }
+
+    /// Write a signed integer, see [`Encoder::i64`].
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn i16(&mut self, value: i16) -> Result<&mut Self, Error> {
+        self.i64(value.into())
+    }
+
+    /// Write a signed integer, see [`Encoder::i64`].
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn i8(&mut self, value: i8) -> Result<&mut Self, Error> {
+        self.i64(value.into())
+    }
+
+    /// Write a boolean.
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn bool(&mut self, value: bool) -> Result<&mut Self, Error> {
+        self.write(&[if value { TRUE } else { FALSE }])
+    }
+
+    /// Write `null`.
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn null(&mut self) -> Result<&mut Self, Error> {
+        self.write(&[NULL])
+    }
+
+    /// Write a single precision floating-point number.
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn f32(&mut self, value: f32) -> Result<&mut Self, Error> {
+        self.write(&[FLOAT32])?;
+        self.write(&value.to_be_bytes())
+    }
+
+    /// Write a double precision floating-point number.
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn f64(&mut self, value: f64) -> Result<&mut Self, Error> {
+        self.write(&[FLOAT64])?;
+        self.write(&value.to_be_bytes())
+    }
+
+    /// Write a byte string.
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn bytes(&mut self, value: &[u8]) -> Result<&mut Self, Error> {
+        self.header(BYTES, value.len() as u64)?;
+        self.write(value)
+    }
+
+    /// Write a text string.
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn str(&mut self, value: &str) -> Result<&mut Self, Error> {
+        self.header(TEXT, value.len() as u64)?;
+        self.write(value.as_bytes())
+    }
+
+    /// Start an array of `len` items, written next.
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn array(&mut self, len: u64) -> Result<&mut Self, Error> {
+        self.header(ARRAY, len)
+    }
+
+    /// Start a map of `len` pairs, their keys and values written next.
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn map(&mut self, len: u64) -> Result<&mut Self, Error> {
+        self.header(MAP, len)
+    }
+
+    /// Start an array of indefinite length, terminated with
+    /// [`Encoder::end`].
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn begin_array(&mut self) -> Result<&mut Self, Error> {
+        self.write(&[(ARRAY << 5) | INDEFINITE])
+    }
+
+    /// Start a map of indefinite length, terminated with [`Encoder::end`].
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn begin_map(&mut self) -> Result<&mut Self, Error> {
+        self.write(&[(MAP << 5) | INDEFINITE])
+    }
+
+    /// Terminate the current indefinite length array or map.
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn end(&mut self) -> Result<&mut Self, Error> {
+        self.write(&[BREAK])
+    }
+
+    /// Write the tag of the next item.
+    ///
+    /// # Errors
+    /// See [`Encoder::u64`].
+    pub fn tag(&mut self, tag: u64) -> Result<&mut Self, Error> {
+        self.header(TAG, tag)
+    }
+
+    /// Write `value`.
+    ///
+    /// # Errors
+    /// Propagates the value encoding errors.
+    pub fn encode<T: Encode + ?Sized>(&mut self, value: &T) -> Result<&mut Self, Error> {
+        value.encode(self)?;
+        Ok(self)
+    }
+}
+
+/// Write `value` into `buf`, returning the encoded length.
+///
+/// # Errors
+/// Propagates the value encoding errors, typically `Status::Busy` if `buf`
+/// is too small.
+pub fn to_slice<T: Encode + ?Sized>(value: &T, buf: &mut [u8]) -> Result<usize, Error> {
+    let mut encoder = Encoder::new(buf);
+    value.encode(&mut encoder)?;
+    Ok(encoder.len())
+}
+
+/// Read a value from `data`, which must hold a single item.
+///
+/// # Errors
+/// Returns a `Status::Invalid` error if `data` doesn't hold a single value
+/// of the type.
+pub fn from_slice<'b, T: Decode<'b>>(data: &'b [u8]) -> Result<T, Error> {
+    let mut decoder = Decoder::new(data);
+    let value = T::decode(&mut decoder)?;
+    match decoder.position() == data.len() {
+        true => Ok(value),
+        false => Err(error(Status::Invalid)),
+    }
+}
+
+/// CBOR decoder, reading from a buffer
+///
+/// Decoding errors are `Status::Invalid` ones, the position being then left
+/// unspecified.
+pub struct Decoder<'b> {
+    data: &'b [u8],
+    position: usize,
+}
+
+impl<'b> Decoder<'b> {
+    /// Create a decoder reading from the start of `data`.
+    pub fn new(data: &'b [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    /// Return the offset of the next item.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    fn read(&mut self, len: usize) -> Result<&'b [u8], Error> {
+        let data = self
+            .data
+            .get(self.position..self.position.saturating_add(len))
+            .ok_or(error(Status::Invalid))?;
+        self.position += len;
+        Ok(data)
+    }
+
+    fn peek(&self) -> Result<u8, Error> {
+        self.data
+            .get(self.position)
+            .copied()
+            .ok_or(error(Status::Invalid))
+    }
+
+    /// Read an item header, returning its major type and its argument, or
+    /// `None` for indefinite lengths.
+    fn header(&mut self) -> Result<(u8, Option<u64>), Error> {
+        let initial = self.read(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        let len = match info {
+            0..=23 => return Ok((major, Some(u64::from(info)))),
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            INDEFINITE if matches!(major, BYTES | TEXT | ARRAY | MAP | SIMPLE) => {
+                return Ok((major, None));
+            }
+            _ => return Err(error(Status::Invalid)),
+        };
+        let mut argument = [0; 8];
+        argument[8 - len..].copy_from_slice(self.read(len)?);
+        Ok((major, Some(u64::from_be_bytes(argument))))
+    }
+
+    /// Read the header of an item of type `major`, of definite argument.
+    fn expect(&mut self, major: u8) -> Result<u64, Error> {
+        match self.header()? {
+            (actual, Some(argument)) if actual == major => Ok(argument),
+            _ => Err(error(Status::Invalid)),
+        }
+    }
+
+    /// Return the type of the next item, without reading it.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error at the end of the data.
+    pub fn datatype(&self) -> Result<Type, Error> {
+        let initial = self.peek()?;
+        Ok(match (initial >> 5, initial) {
+            (UNSIGNED, _) => Type::Unsigned,
+            (NEGATIVE, _) => Type::Negative,
+            (BYTES, _) => Type::Bytes,
+            (TEXT, _) => Type::Text,
+            (ARRAY, _) => Type::Array,
+            (MAP, _) => Type::Map,
+            (TAG, _) => Type::Tag,
+            (_, FALSE | TRUE) => Type::Bool,
+            (_, NULL) => Type::Null,
+            (_, UNDEFINED) => Type::Undefined,
+            (_, 0xf9 | FLOAT32 | FLOAT64) => Type::Float,
+            (_, BREAK) => Type::Break,
+            _ => Type::Simple,
+        })
+    }
+
+    /// Read an unsigned integer.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the next item is not an integer
+    /// in the range of the type, as all the decoder methods for their type.
+    pub fn u64(&mut self) -> Result<u64, Error> {
+        self.expect(UNSIGNED)
+    }
+
+    /// Read an unsigned integer, see [`Decoder::u64`].
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        self.u64()?.try_into().map_err(|_| error(Status::Invalid))
+    }
+
+    /// Read an unsigned integer, see [`Decoder::u64`].
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn u16(&mut self) -> Result<u16, Error> {
+        self.u64()?.try_into().map_err(|_| error(Status::Invalid))
+    }
+
+    /// Read an unsigned integer, see [`Decoder::u64`].
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn u8(&mut self) -> Result<u8, Error> {
+        self.u64()?.try_into().map_err(|_| error(Status::Invalid))
+    }
+
+    /// Read a signed integer.
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn i64(&mut self) -> Result<i64, Error> {
+        let invalid = || error(Status::Invalid);
+        match self.header()? {
+            (UNSIGNED, Some(value)) => value.try_into().map_err(|_| invalid()),
+            (NEGATIVE, Some(value)) => i64::try_from(value)
+                .map(|value| !value)
+                .map_err(|_| invalid()),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Read a signed integer, see [`Decoder::i64`].
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn i32(&mut self) -> Result<i32, Error> {
+        self.i64()?.try_into().map_err(|_| error(Status::Invalid))
+    }
+
+    /// Read a signed integer, see [`Decoder::i64`].
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn i16(&mut self) -> Result<i16, Error> {
+        self.i64()?.try_into().map_err(|_| error(Status::Invalid))
+    }
+
+    /// Read a signed integer, see [`Decoder::i64`].
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn i8(&mut self) -> Result<i8, Error> {
+        self.i64()?.try_into().map_err(|_| error(Status::Invalid))
+    }
+
+    /// Read a boolean.
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn bool(&mut self) -> Result<bool, Error> {
+        match self.read(1)?[0] {
+            FALSE => Ok(false),
+            TRUE => Ok(true),
+            _ => Err(error(Status::Invalid)),
+        }
+    }
+
+    /// Read `null`.
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn null(&mut self) -> Result<(), Error> {
+        match self.read(1)?[0] {
+            NULL => Ok(()),
+            _ => Err(error(Status::Invalid)),
+        }
+    }
+
+    /// Read a single precision floating-point number.
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn f32(&mut self) -> Result<f32, Error> {
+        match self.read(1)?[0] {
+            FLOAT32 => Ok(f32::from_be_bytes(
+                self.read(4)?.try_into().unwrap_or_default(),
+            )),
+            _ => Err(error(Status::Invalid)),
+        }
+    }
+
+    /// Read a floating-point number, in single or double precision.
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn f64(&mut self) -> Result<f64, Error> {
+        match self.peek()? {
+            FLOAT32 => self.f32().map(f64::from),
+            FLOAT64 => {
+                self.position += 1;
+                Ok(f64::from_be_bytes(
+                    self.read(8)?.try_into().unwrap_or_default(),
+                ))
+            }
+            _ => Err(error(Status::Invalid)),
+        }
+    }
+
+    /// Read a byte string.
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn bytes(&mut self) -> Result<&'b [u8], Error> {
+        let len = self.expect(BYTES)?;
+        self.read(usize::try_from(len).map_err(|_| error(Status::Invalid))?)
+    }
+
+    /// Read a text string.
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`], the string being also checked to be UTF-8.
+    pub fn str(&mut self) -> Result<&'b str, Error> {
+        let len = self.expect(TEXT)?;
+        let data = self.read(usize::try_from(len).map_err(|_| error(Status::Invalid))?)?;
+        core::str::from_utf8(data).map_err(|_| error(Status::Invalid))
+    }
+
+    /// Read the start of an array, returning its length, or `None` for an
+    /// indefinite length array, whose end is read with [`Decoder::end`].
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn array(&mut self) -> Result<Option<u64>, Error> {
+        match self.header()? {
+            (ARRAY, len) => Ok(len),
+            _ => Err(error(Status::Invalid)),
+        }
+    }
+
+    /// Read the start of a map, returning its number of pairs, or `None` for
+    /// an indefinite length map, whose end is read with [`Decoder::end`].
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn map(&mut self) -> Result<Option<u64>, Error> {
+        match self.header()? {
+            (MAP, len) => Ok(len),
+            _ => Err(error(Status::Invalid)),
+        }
+    }
+
+    /// Read the end of an indefinite length array or map.
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn end(&mut self) -> Result<(), Error> {
+        match self.read(1)?[0] {
+            BREAK => Ok(()),
+            _ => Err(error(Status::Invalid)),
+        }
+    }
+
+    /// Read the tag of the next item.
+    ///
+    /// # Errors
+    /// See [`Decoder::u64`].
+    pub fn tag(&mut self) -> Result<u64, Error> {
+        self.expect(TAG)
+    }
+
+    /// Read a value.
+    ///
+    /// # Errors
+    /// Propagates the value decoding errors.
+    pub fn decode<T: Decode<'b>>(&mut self) -> Result<T, Error> {
+        T::decode(self)
+    }
+
+    /// Skip the next item, along with the items it contains.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the item is malformed, or nests
+    /// more than [`MAX_NESTING`] levels.
+    pub fn skip(&mut self) -> Result<(), Error> {
+        self.skip_nested(0)
+    }
+
+    fn skip_nested(&mut self, depth: usize) -> Result<(), Error> {
+        let invalid = || error(Status::Invalid);
+        if depth == MAX_NESTING {
+            return Err(invalid());
+        }
+        let (major, argument) = self.header()?;
+        let items = match (major, argument) {
+            (UNSIGNED | NEGATIVE, _) => 0,
+            (BYTES | TEXT, Some(len)) => {
+                self.read(usize::try_from(len).map_err(|_| invalid())?)?;
+                0
+            }
+            (ARRAY, Some(len)) => len,
+            (MAP, Some(len)) => len.checked_mul(2).ok_or_else(invalid)?,
+            (ARRAY | MAP, None) => {
+                while self.peek()? != BREAK {
+                    self.skip_nested(depth + 1)?;
+                }
+                return self.end();
+            }
+            (TAG, _) => 1,
+            // the argument of floats and simple values is their value
+            (SIMPLE, Some(_)) => 0,
+            _ => return Err(invalid()),
+        };
+        for _ in 0..items {
+            self.skip_nested(depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! primitive {
+    ($($type:ty => $method:ident),+) => {
+        $(
+            impl Encode for $type {
+                fn encode(&self, e: &mut Encoder<'_>) -> Result<(), Error> {
+                    e.$method(*self)?;
+                    Ok(())
+                }
+            }
+
+            impl Decode<'_> for $type {
+                fn decode(d: &mut Decoder<'_>) -> Result<Self, Error> {
+                    d.$method()
+                }
+            }
+        )+
+    };
+}
+
+primitive!(
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64,
+    i8 => i8, i16 => i16, i32 => i32, i64 => i64,
+    bool => bool, f32 => f32, f64 => f64
+);
+
+impl<T: Encode + ?Sized> Encode for &T {
+    fn encode(&self, e: &mut Encoder<'_>) -> Result<(), Error> {
+        (**self).encode(e)
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, e: &mut Encoder<'_>) -> Result<(), Error> {
+        e.str(self)?;
+        Ok(())
+    }
+}
+
+impl<'b> Decode<'b> for &'b str {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
+        d.str()
+    }
+}
+
+/// Byte slices are encoded as byte strings
+impl Encode for [u8] {
+    fn encode(&self, e: &mut Encoder<'_>) -> Result<(), Error> {
+        e.bytes(self)?;
+        Ok(())
+    }
+}
+
+impl<'b> Decode<'b> for &'b [u8] {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
+        d.bytes()
+    }
+}
+
+/// `None` is encoded as `null`
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, e: &mut Encoder<'_>) -> Result<(), Error> {
+        match self {
+            Some(value) => value.encode(e),
+            None => e.null().map(|_| ()),
+        }
+    }
+}
+
+impl<'b, T: Decode<'b>> Decode<'b> for Option<T> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
+        match d.datatype()? {
+            Type::Null => d.null().map(|()| None),
+            _ => T::decode(d).map(Some),
+        }
+    }
+}
+
+impl<const N: usize> Encode for FixedString<N> {
+    fn encode(&self, e: &mut Encoder<'_>) -> Result<(), Error> {
+        e.str(self)?;
+        Ok(())
+    }
+}
+
+impl<const N: usize> Decode<'_> for FixedString<N> {
+    fn decode(d: &mut Decoder<'_>) -> Result<Self, Error> {
+        Self::try_from(d.str()?).map_err(|_| error(Status::Invalid))
+    }
+}
+
+/// Vectors are encoded as arrays
+impl<T: Encode, const N: usize> Encode for FixedVec<T, N> {
+    fn encode(&self, e: &mut Encoder<'_>) -> Result<(), Error> {
+        e.array(self.len() as u64)?;
+        self.iter().try_for_each(|item| item.encode(e))
+    }
+}
+
+impl<'b, T: Decode<'b>, const N: usize> Decode<'b> for FixedVec<T, N> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
+        let len = d.array()?;
+        let mut vec = Self::new();
+        let mut index = 0;
+        loop {
+            match len {
+                Some(len) if index == len => break,
+                None if d.datatype()? == Type::Break => {
+                    d.end()?;
+                    break;
+                }
+                _ => {}
+            }
+            vec.try_push(T::decode(d)?)
+                .map_err(|_| error(Status::Invalid))?;
+            index += 1;
+        }
+        Ok(vec)
+    }
+}
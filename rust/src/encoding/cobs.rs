@@ -0,0 +1,317 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Consistent Overhead Byte Stuffing (COBS) framing
+//!
+//! COBS removes the zero bytes of a packet, at the cost of a byte every 254,
+//! so that a zero byte delimits the packets of a byte stream: a receiver
+//! joining the stream, or losing bytes, resynchronizes on the next
+//! delimiter. Frames handled by this module are the encoded packet followed
+//! by the [`DELIMITER`].
+//!
+//! ```ignore
+//! let mut frame = [0; max_encoded_len(MAX_PACKET_LEN)];
+//! let len = cobs::encode(packet, &mut frame)?;
+//! uart.write_all(&frame[..len]).await?;
+//!
+//! let mut decoder = Decoder::<MAX_PACKET_LEN>::new();
+//! let len = uart.read(&mut chunk).await?;
+//! decoder.feed(&chunk[..len], |packet| match packet {
+//!     Ok(packet) => handle(packet),
+//!     Err(_) => dropped += 1,
+//! });
+//! ```
+//!
+//! [`Encoder`] builds a frame from several pieces of a packet, and
+//! [`Decoder`] extracts the packets of a stream received in chunks.
+
+use uapi::systypes::Status;
+
+use super::error;
+use crate::error::Error;
+
+/// Frame delimiter
+pub const DELIMITER: u8 = 0;
+
+/// Largest number of data bytes of a COBS block
+const MAX_BLOCK_LEN: usize = 254;
+
+/// Return the maximum length of the frame of a `len` bytes packet,
+/// delimiter included.
+pub const fn max_encoded_len(len: usize) -> usize {
+    len + len / MAX_BLOCK_LEN + 2
+}
+
+/// Encode `packet` as a frame into `frame`, returning the frame length.
+///
+/// # Errors
+/// Returns a `Status::Busy` error if `frame` is too small, see
+/// [`max_encoded_len`].
+pub fn encode(packet: &[u8], frame: &mut [u8]) -> Result<usize, Error> {
+    let mut encoder = Encoder::new(frame);
+    encoder.push(packet)?;
+    encoder.finish()
+}
+
+/// Decode the frame `frame` into `packet`, returning the packet length.
+///
+/// The frame ends at its first delimiter, or at its end if it has none.
+///
+/// # Errors
+/// Returns a `Status::Invalid` error if the frame is malformed, or a
+/// `Status::Busy` one if `packet` is too small.
+pub fn decode(frame: &[u8], packet: &mut [u8]) -> Result<usize, Error> {
+    let mut block = Block::new();
+    let mut len = 0;
+    for &byte in frame.iter().take_while(|&&byte| byte != DELIMITER) {
+        if let Some(byte) = block.decode(byte) {
+            *packet.get_mut(len).ok_or(error(Status::Busy))? = byte;
+            len += 1;
+        }
+    }
+    block.check()?;
+    Ok(len)
+}
+
+/// Decode the frame in `buffer` in place, returning the packet length, the
+/// packet being written at the start of the buffer.
+///
+/// # Errors
+/// Returns a `Status::Invalid` error if the frame is malformed.
+pub fn decode_in_place(buffer: &mut [u8]) -> Result<usize, Error> {
+    let mut block = Block::new();
+    let mut len = 0;
+    for index in 0..buffer.len() {
+        let byte = buffer[index];
+        if byte == DELIMITER {
+            break;
+        }
+        // a packet is never longer than its encoding
+        if let Some(byte) = block.decode(byte) {
+            buffer[len] = byte;
+            len += 1;
+        }
+    }
+    block.check()?;
+    Ok(len)
+}
+
+/// Decoding state of the current COBS block
+#[derive(Clone, Copy)]
+struct Block {
+    /// Whether a code byte was decoded
+    started: bool,
+    /// Data bytes left in the block
+    remaining: u8,
+    /// Whether the block is followed by a zero byte, if not the last one
+    zero: bool,
+}
+
+impl Block {
+    const fn new() -> Self {
+        Self {
+            started: false,
+            remaining: 0,
+            zero: false,
+        }
+    }
+
+    /// Decode the non-delimiter `byte`, returning the packet byte it yields.
+    fn decode(&mut self, byte: u8) -> Option<u8> {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            return Some(byte);
+        }
+        let zero = self.zero;
+        self.started = true;
+        self.remaining = byte - 1;
+        self.zero = usize::from(byte) <= MAX_BLOCK_LEN;
+        zero.then_some(0)
+    }
+
+    /// Check that the frame ends at the end of a block.
+    fn check(&self) -> Result<(), Error> {
+        match self.started && self.remaining == 0 {
+            true => Ok(()),
+            false => Err(error(Status::Invalid)),
+        }
+    }
+}
+
+/// Encoding state of the current COBS block
+#[derive(Clone, Copy)]
+enum Code {
+    /// A block follows, possibly empty
+    Pending,
+    /// A block is being encoded, its code byte at the offset
+    Open(usize),
+    /// A full block has been encoded, a block only follows if there is data
+    Closed,
+}
+
+/// Frame encoder, for packets given in several pieces
+///
+/// ```ignore
+/// let mut encoder = Encoder::new(&mut frame);
+/// encoder.push(&header)?;
+/// encoder.push(payload)?;
+/// let len = encoder.finish()?;
+/// ```
+pub struct Encoder<'a> {
+    frame: &'a mut [u8],
+    len: usize,
+    code: Code,
+}
+
+impl<'a> Encoder<'a> {
+    /// Start a frame in `frame`.
+    pub fn new(frame: &'a mut [u8]) -> Self {
+        Self {
+            frame,
+            len: 0,
+            code: Code::Pending,
+        }
+    }
+
+    fn write(&mut self, byte: u8) -> Result<(), Error> {
+        *self.frame.get_mut(self.len).ok_or(error(Status::Busy))? = byte;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Write the code byte of the block open at `offset`.
+    fn close(&mut self, offset: usize) {
+        // blocks are at most 254 bytes long
+        self.frame[offset] = (self.len - offset) as u8;
+    }
+
+    /// Append `data` to the packet.
+    ///
+    /// # Errors
+    /// Returns a `Status::Busy` error if the frame buffer is full, the
+    /// encoder being left in an unspecified state.
+    pub fn push(&mut self, data: &[u8]) -> Result<(), Error> {
+        for &byte in data {
+            let offset = match self.code {
+                Code::Open(offset) => offset,
+                Code::Pending | Code::Closed => {
+                    let offset = self.len;
+                    self.write(0)?;
+                    offset
+                }
+            };
+            if byte == 0 {
+                self.close(offset);
+                self.code = Code::Pending;
+                continue;
+            }
+            self.write(byte)?;
+            self.code = match self.len - offset > MAX_BLOCK_LEN {
+                true => {
+                    self.close(offset);
+                    Code::Closed
+                }
+                false => Code::Open(offset),
+            };
+        }
+        Ok(())
+    }
+
+    /// Terminate the frame, returning its length.
+    ///
+    /// # Errors
+    /// Returns a `Status::Busy` error if the frame buffer is full.
+    pub fn finish(mut self) -> Result<usize, Error> {
+        match self.code {
+            Code::Open(offset) => self.close(offset),
+            Code::Pending => self.write(1)?,
+            Code::Closed => {}
+        }
+        self.write(DELIMITER)?;
+        Ok(self.len)
+    }
+}
+
+/// Stream decoder, extracting the packets of up to `N` bytes of a stream
+///
+/// Empty frames, i.e. consecutive delimiters, are skipped so that a sender
+/// can flush a receiver with a delimiter before its first frame.
+pub struct Decoder<const N: usize> {
+    packet: [u8; N],
+    len: usize,
+    block: Block,
+    /// Error of the current frame, whose bytes are skipped
+    error: Option<Status>,
+}
+
+impl<const N: usize> Default for Decoder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Decoder<N> {
+    /// Create a decoder waiting for the start of a frame.
+    pub const fn new() -> Self {
+        Self {
+            packet: [0; N],
+            len: 0,
+            block: Block::new(),
+            error: None,
+        }
+    }
+
+    /// Drop the frame being received.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.block = Block::new();
+        self.error = None;
+    }
+
+    /// Decode the next `byte` of the stream, returning the packet it ends,
+    /// if any.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error at the end of a malformed frame, or
+    /// a `Status::Busy` one at the end of a frame whose packet is longer
+    /// than `N` bytes.
+    pub fn push(&mut self, byte: u8) -> Result<Option<&[u8]>, Error> {
+        if byte != DELIMITER {
+            if self.error.is_none()
+                && let Some(byte) = self.block.decode(byte)
+            {
+                match self.packet.get_mut(self.len) {
+                    Some(slot) => {
+                        *slot = byte;
+                        self.len += 1;
+                    }
+                    None => self.error = Some(Status::Busy),
+                }
+            }
+            return Ok(None);
+        }
+        let (block, len, status) = (self.block, self.len, self.error);
+        self.reset();
+        match status {
+            Some(status) => Err(error(status)),
+            None if !block.started => Ok(None),
+            None => {
+                block.check()?;
+                Ok(Some(&self.packet[..len]))
+            }
+        }
+    }
+
+    /// Decode the next `data` bytes of the stream, calling `f` with each
+    /// packet, or error, they end.
+    pub fn feed(&mut self, data: &[u8], mut f: impl FnMut(Result<&[u8], Error>)) {
+        for &byte in data {
+            match self.push(byte) {
+                Ok(Some(packet)) => f(Ok(packet)),
+                Ok(None) => {}
+                Err(err) => f(Err(err)),
+            }
+        }
+    }
+}
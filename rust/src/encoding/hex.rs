@@ -0,0 +1,69 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Hexadecimal encoding
+//!
+//! Bytes are encoded as lowercase digits, two per byte, and decoded from
+//! either case.
+//!
+//! ```ignore
+//! let mut text = [0; encoded_len(DIGEST_LEN)];
+//! info!("digest {}", hex::encode_to(&digest, &mut text)?);
+//! ```
+
+use uapi::systypes::Status;
+
+use super::error;
+use crate::error::Error;
+
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Return the length of the encoding of `len` bytes.
+pub const fn encoded_len(len: usize) -> usize {
+    2 * len
+}
+
+/// Encode `data` into `out`, returning the encoded string.
+///
+/// # Errors
+/// Returns a `Status::Busy` error if `out` is too small, see
+/// [`encoded_len`].
+pub fn encode_to<'a>(data: &[u8], out: &'a mut [u8]) -> Result<&'a str, Error> {
+    let out = out
+        .get_mut(..encoded_len(data.len()))
+        .ok_or(error(Status::Busy))?;
+    for (&byte, digits) in data.iter().zip(out.chunks_exact_mut(2)) {
+        digits[0] = DIGITS[usize::from(byte >> 4)];
+        digits[1] = DIGITS[usize::from(byte & 0xf)];
+    }
+    // SAFETY: the encoding is ASCII
+    Ok(unsafe { core::str::from_utf8_unchecked(out) })
+}
+
+fn digit(digit: u8) -> Result<u8, Error> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(error(Status::Invalid)),
+    }
+}
+
+/// Decode `hex` into `out`, returning the decoded length.
+///
+/// # Errors
+/// Returns a `Status::Invalid` error if `hex` has an odd length or a
+/// non-hexadecimal digit, or a `Status::Busy` one if `out` is too small.
+pub fn decode(hex: impl AsRef<[u8]>, out: &mut [u8]) -> Result<usize, Error> {
+    let hex = hex.as_ref();
+    if hex.len() % 2 != 0 {
+        return Err(error(Status::Invalid));
+    }
+    let len = hex.len() / 2;
+    let out = out.get_mut(..len).ok_or(error(Status::Busy))?;
+    for (byte, digits) in out.iter_mut().zip(hex.chunks_exact(2)) {
+        *byte = (digit(digits[0])? << 4) | digit(digits[1])?;
+    }
+    Ok(len)
+}
@@ -0,0 +1,30 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Data encodings
+//!
+//! Encoders and decoders of this module write into caller provided buffers,
+//! without allocating. Malformed input is reported as a `Status::Invalid`
+//! error, and a full output buffer as a `Status::Busy` one.
+//!
+//! The hexadecimal and base64 helpers are also available as
+//! [`encode_hex_to`], [`decode_hex`], [`encode_base64_to`] and
+//! [`decode_base64`]. CBOR is supported with the `cbor` feature.
+
+pub mod base64;
+#[cfg(feature = "cbor")]
+pub mod cbor;
+pub mod cobs;
+pub mod hex;
+
+pub use base64::{decode as decode_base64, encode_to as encode_base64_to};
+pub use hex::{decode as decode_hex, encode_to as encode_hex_to};
+
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+
+fn error(status: Status) -> Error {
+    Error::new(Subsystem::Encoding, status)
+}
@@ -0,0 +1,228 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Kernel status to POSIX errno mapping
+//!
+//! Shield C library reports failures through `errno`, using the hardened
+//! values of `<shield/errno.h>`, while Rust APIs report a kernel [`Status`].
+//! This module holds the canonical mapping between the two, so that both sides
+//! report consistent errors to applications. It is also exported to C through
+//! [`SHIELD_ERRNO_TABLE`], [`shield_status_to_errno`] and
+//! [`shield_errno_to_status`].
+//!
+//! The task `errno` itself is stored here, read and written with [`get`] and
+//! [`set`]. It is exported to the C library through [`__errno_location`], so
+//! that C and Rust code of a task share a single `errno`.
+
+use core::ffi::c_int;
+use core::fmt;
+use core::sync::atomic::{AtomicU32, Ordering};
+use uapi::systypes::Status;
+
+use crate::error::Error;
+
+/// POSIX error code, as defined by `<shield/errno.h>`
+///
+/// Error codes are hardened random 32 bits values, not the usual small
+/// integers. `0` means no error.
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Errno(pub u32);
+
+impl Errno {
+    /// No error
+    pub const NONE: Self = Self(0);
+    /// Operation not permitted
+    pub const EPERM: Self = Self(0x2af5_e248);
+    /// No such file or directory
+    pub const ENOENT: Self = Self(0x3999_3cc3);
+    /// No such process
+    pub const ESRCH: Self = Self(0x3f34_f248);
+    /// Interrupted system call
+    pub const EINTR: Self = Self(0x41de_4352);
+    /// I/O error
+    pub const EIO: Self = Self(0x455a_5555);
+    /// No such device or address
+    pub const ENXIO: Self = Self(0x55a5_55aa);
+    /// Argument list too long
+    pub const E2BIG: Self = Self(0x6a55_5a5a);
+    /// Exec format error
+    pub const ENOEXEC: Self = Self(0x73a5_753c);
+    /// Bad file number
+    pub const EBADF: Self = Self(0x753c_95a5);
+    /// No child processes
+    pub const ECHILD: Self = Self(0x7a59_a833);
+    /// Try again
+    pub const EAGAIN: Self = Self(0x7aaa_5aa5);
+    /// Out of memory
+    pub const ENOMEM: Self = Self(0x7f38_a4df);
+    /// Permission denied
+    pub const EACCES: Self = Self(0xc9a9_de4d);
+    /// Bad address
+    pub const EFAULT: Self = Self(0xc9b3_682b);
+    /// Block device required
+    pub const ENOTBLK: Self = Self(0xca9d_8516);
+    /// Device or resource busy
+    pub const EBUSY: Self = Self(0xcb0b_87b8);
+    /// File exists
+    pub const EEXIST: Self = Self(0xcc1a_0dcf);
+    /// Cross-device link
+    pub const EXDEV: Self = Self(0xcc1c_c8fc);
+    /// No such device
+    pub const ENODEV: Self = Self(0xcdb7_e2d7);
+    /// Not a directory
+    pub const ENOTDIR: Self = Self(0xce87_fe5b);
+    /// Is a directory
+    pub const EISDIR: Self = Self(0xcf30_29ee);
+    /// Invalid argument
+    pub const EINVAL: Self = Self(0xcfdc_42ff);
+    /// File table overflow
+    pub const ENFILE: Self = Self(0xd2d4_772a);
+    /// Too many open files
+    pub const EMFILE: Self = Self(0xd34c_eab1);
+    /// Not a typewriter
+    pub const ENOTTY: Self = Self(0xd3d8_d228);
+    /// Text file busy
+    pub const ETXTBSY: Self = Self(0xd557_703e);
+    /// File too large
+    pub const EFBIG: Self = Self(0xd7ae_5135);
+    /// No space left on device
+    pub const ENOSPC: Self = Self(0xea81_e11e);
+    /// Illegal seek
+    pub const ESPIPE: Self = Self(0xe145_8a11);
+    /// Read-only file system
+    pub const EROFS: Self = Self(0xe855_a984);
+    /// Too many links
+    pub const EMLINK: Self = Self(0xf1e5_a143);
+    /// Broken pipe
+    pub const EPIPE: Self = Self(0xf375_1957);
+    /// Math argument out of domain of func
+    pub const EDOM: Self = Self(0xf76a_a1d2);
+    /// Math result not representable
+    pub const ERANGE: Self = Self(0xf811_0a2d);
+    /// Operation not supported
+    pub const ENOTSUP: Self = Self(0xfbac_fec0);
+    /// Connection timed out
+    pub const ETIMEDOUT: Self = Self(0x5c3a_96e1);
+    /// Resource deadlock would occur
+    pub const EDEADLK: Self = Self(0x6e1c_4b97);
+}
+
+impl fmt::Debug for Errno {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Errno({:#010x})", self.0)
+    }
+}
+
+/// Status to errno mapping entry, as exported to C
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ErrnoEntry {
+    /// Kernel status, as register encoded
+    pub status: u32,
+    /// Matching errno value
+    pub errno: u32,
+}
+
+const fn entry(status: Status, errno: Errno) -> ErrnoEntry {
+    ErrnoEntry {
+        status: status as u32,
+        errno: errno.0,
+    }
+}
+
+/// Canonical status to errno mapping, one entry per kernel status.
+#[unsafe(no_mangle)]
+pub static SHIELD_ERRNO_TABLE: [ErrnoEntry; 11] = [
+    entry(Status::Ok, Errno::NONE),
+    entry(Status::Invalid, Errno::EINVAL),
+    entry(Status::Denied, Errno::EPERM),
+    entry(Status::NoEntity, Errno::ENOENT),
+    entry(Status::Busy, Errno::EBUSY),
+    entry(Status::AlreadyMapped, Errno::EEXIST),
+    entry(Status::Critical, Errno::EIO),
+    entry(Status::Timeout, Errno::ETIMEDOUT),
+    entry(Status::Again, Errno::EAGAIN),
+    entry(Status::Intr, Errno::EINTR),
+    entry(Status::Deadlk, Errno::EDEADLK),
+];
+
+impl From<Status> for Errno {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::Ok => Self::NONE,
+            Status::Invalid => Self::EINVAL,
+            Status::Denied => Self::EPERM,
+            Status::NoEntity => Self::ENOENT,
+            Status::Busy => Self::EBUSY,
+            Status::AlreadyMapped => Self::EEXIST,
+            Status::Critical => Self::EIO,
+            Status::Timeout => Self::ETIMEDOUT,
+            Status::Again => Self::EAGAIN,
+            Status::Intr => Self::EINTR,
+            Status::Deadlk => Self::EDEADLK,
+        }
+    }
+}
+
+impl From<Error> for Errno {
+    fn from(err: Error) -> Self {
+        err.status().into()
+    }
+}
+
+/// Errno to status conversion.
+///
+/// Besides the canonical values of [`SHIELD_ERRNO_TABLE`], close errno values
+/// are accepted (e.g. `EACCES` for `Status::Denied`). Any other errno value is
+/// reported as `Status::Invalid`.
+impl From<Errno> for Status {
+    fn from(errno: Errno) -> Self {
+        match errno {
+            Errno::NONE => Status::Ok,
+            Errno::EPERM | Errno::EACCES => Status::Denied,
+            Errno::ENOENT | Errno::ESRCH | Errno::ENODEV | Errno::ENXIO => Status::NoEntity,
+            Errno::EBUSY | Errno::ETXTBSY => Status::Busy,
+            Errno::EEXIST => Status::AlreadyMapped,
+            Errno::EIO | Errno::EFAULT => Status::Critical,
+            Errno::ETIMEDOUT => Status::Timeout,
+            Errno::EAGAIN => Status::Again,
+            Errno::EINTR => Status::Intr,
+            Errno::EDEADLK => Status::Deadlk,
+            _ => Status::Invalid,
+        }
+    }
+}
+
+/// Return the errno value matching the given register encoded kernel status.
+#[unsafe(no_mangle)]
+pub extern "C" fn shield_status_to_errno(status: u32) -> u32 {
+    Errno::from(Status::from(status)).0
+}
+
+/// Return the register encoded kernel status matching the given errno value.
+#[unsafe(no_mangle)]
+pub extern "C" fn shield_errno_to_status(errno: u32) -> u32 {
+    Status::from(Errno(errno)) as u32
+}
+
+/// Task `errno`, a Sentry task being single-threaded
+static ERRNO: AtomicU32 = AtomicU32::new(0);
+
+/// Return the task `errno`, [`Errno::NONE`] if never set.
+pub fn get() -> Errno {
+    Errno(ERRNO.load(Ordering::Relaxed))
+}
+
+/// Set the task `errno`.
+pub fn set(errno: Errno) {
+    ERRNO.store(errno.0, Ordering::Relaxed);
+}
+
+/// Return the location of the task `errno`, as used by the C library `errno`
+/// macro.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub extern "C" fn __errno_location() -> *mut c_int {
+    ERRNO.as_ptr().cast()
+}
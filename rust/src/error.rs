@@ -0,0 +1,339 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Shield error type
+//!
+//! Kernel calls report a raw [`Status`], which does not tell which step of a
+//! multi-step operation failed. Shield APIs report an [`Error`] instead, which
+//! wraps the kernel status along with the subsystem that failed and, when
+//! relevant, the kernel handle involved.
+//!
+//! Callers can attach what they were doing when the failure occurred with
+//! [`Context::context`]. Contexts are static strings carried in the error
+//! itself, and are printed, outermost first, when the error is displayed:
+//!
+//! ```ignore
+//! use shield::error::Context;
+//!
+//! let shm = Shm::new(FRAME_SHM).and_then(|shm| shm.map(0)).context("mapping frame SHM")?;
+//! // on failure, displays "mapping frame SHM: shm: denied (handle 0x...)"
+//! ```
+
+use core::fmt;
+use uapi::systypes::Status;
+
+/// Shield subsystem in which an error occurred
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    /// Task handles and identity ([`crate::process`])
+    Process,
+    /// Shared memories ([`crate::shm`])
+    Shm,
+    /// Synchronization primitives ([`crate::sync`])
+    Sync,
+    /// Time and clocks ([`crate::time`])
+    Time,
+    /// Log backend
+    Log,
+    /// Crash log ([`crate::crashlog`])
+    CrashLog,
+    /// DMA streams ([`crate::dma`])
+    Dma,
+    /// UART driver
+    Uart,
+    /// Inter-task messages
+    Ipc,
+    /// Kernel entropy source ([`crate::random`])
+    Random,
+    /// Persistent storage
+    Storage,
+    /// Firmware updates ([`crate::update`])
+    Update,
+    /// Attestation reports ([`crate::attest`])
+    Attest,
+    /// Secure element ([`crate::secure_element`])
+    SecureElement,
+    /// Metrics export ([`crate::metrics`])
+    Metrics,
+    /// Event tracing ([`crate::trace`])
+    Trace,
+    /// Subsystem heartbeats ([`crate::health`])
+    Health,
+    /// Network stack ([`crate::net`])
+    Net,
+    /// Ethernet MAC driver ([`crate::eth`])
+    Eth,
+    /// Framebuffer display ([`crate::display`])
+    Display,
+    /// Input events ([`crate::input`])
+    Input,
+    /// I2S audio streaming ([`crate::audio`])
+    Audio,
+    /// Anti-rollback counters ([`crate::rollback`])
+    Rollback,
+    /// Sealed key-value store ([`crate::securestore`])
+    SecureStore,
+    /// SPI bus driver ([`crate::hal::spi`])
+    Spi,
+    /// I2C bus driver ([`crate::hal::i2c`])
+    I2c,
+    /// GPIO inputs ([`crate::hal::gpio`])
+    Gpio,
+    /// Host communication channels ([`crate::hostlink`])
+    HostLink,
+    /// Child task supervision ([`crate::supervisor`])
+    Supervisor,
+    /// Memory mapped devices ([`crate::device`])
+    Device,
+    /// Bounded collections ([`crate::collections`])
+    Collections,
+    /// Data encodings ([`crate::encoding`])
+    Encoding,
+    /// Power-on self-tests ([`crate::selftest`])
+    Selftest,
+}
+
+impl Subsystem {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Process => "process",
+            Self::Shm => "shm",
+            Self::Sync => "sync",
+            Self::Time => "time",
+            Self::Log => "log",
+            Self::CrashLog => "crashlog",
+            Self::Dma => "dma",
+            Self::Uart => "uart",
+            Self::Ipc => "ipc",
+            Self::Random => "random",
+            Self::Storage => "storage",
+            Self::Update => "update",
+            Self::Attest => "attest",
+            Self::SecureElement => "secure-element",
+            Self::Metrics => "metrics",
+            Self::Trace => "trace",
+            Self::Health => "health",
+            Self::Net => "net",
+            Self::Eth => "eth",
+            Self::Display => "display",
+            Self::Input => "input",
+            Self::Audio => "audio",
+            Self::Rollback => "rollback",
+            Self::SecureStore => "securestore",
+            Self::Spi => "spi",
+            Self::I2c => "i2c",
+            Self::Gpio => "gpio",
+            Self::HostLink => "hostlink",
+            Self::Supervisor => "supervisor",
+            Self::Device => "device",
+            Self::Collections => "collections",
+            Self::Encoding => "encoding",
+            Self::Selftest => "selftest",
+        }
+    }
+}
+
+impl fmt::Display for Subsystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Maximum number of contexts carried by an [`Error`]
+pub const MAX_CONTEXTS: usize = 4;
+
+/// Shield error
+#[derive(Clone, Copy, PartialEq)]
+pub struct Error {
+    status: Status,
+    subsystem: Subsystem,
+    handle: Option<u32>,
+    contexts: [&'static str; MAX_CONTEXTS],
+    depth: usize,
+}
+
+impl Error {
+    /// Create a new error reported by the given subsystem.
+    pub const fn new(subsystem: Subsystem, status: Status) -> Self {
+        Self {
+            status,
+            subsystem,
+            handle: None,
+            contexts: [""; MAX_CONTEXTS],
+            depth: 0,
+        }
+    }
+
+    /// Attach the kernel handle involved in the failure.
+    #[must_use]
+    pub const fn with_handle(mut self, handle: u32) -> Self {
+        self.handle = Some(handle);
+        self
+    }
+
+    /// Attach a context to the error.
+    ///
+    /// Once [`MAX_CONTEXTS`] contexts are attached, the outer ones are dropped.
+    #[must_use]
+    pub const fn with_context(mut self, context: &'static str) -> Self {
+        if self.depth < MAX_CONTEXTS {
+            self.contexts[self.depth] = context;
+            self.depth += 1;
+        }
+        self
+    }
+
+    /// Attached contexts, innermost first.
+    pub fn contexts(&self) -> &[&'static str] {
+        &self.contexts[..self.depth]
+    }
+
+    /// Kernel status of the failure.
+    pub const fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Subsystem that reported the failure.
+    pub const fn subsystem(&self) -> Subsystem {
+        self.subsystem
+    }
+
+    /// Kernel handle involved in the failure, if any.
+    pub const fn handle(&self) -> Option<u32> {
+        self.handle
+    }
+}
+
+impl From<Error> for Status {
+    fn from(err: Error) -> Self {
+        err.status
+    }
+}
+
+/// Return a human readable description of a kernel status.
+///
+/// `Status` only implements `Debug` in debug builds, this is usable in any
+/// profile.
+pub const fn status_name(status: Status) -> &'static str {
+    match status {
+        Status::Ok => "ok",
+        Status::Invalid => "invalid parameter",
+        Status::Denied => "denied",
+        Status::NoEntity => "no such entity",
+        Status::Busy => "busy",
+        Status::AlreadyMapped => "already mapped",
+        Status::Critical => "critical failure",
+        Status::Timeout => "timed out",
+        Status::Again => "try again",
+        Status::Intr => "interrupted",
+        Status::Deadlk => "deadlock",
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for context in self.contexts().iter().rev() {
+            write!(f, "{context}: ")?;
+        }
+        write!(f, "{}: {}", self.subsystem, status_name(self.status))?;
+        if let Some(handle) = self.handle {
+            write!(f, " (handle {handle:#x})")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Error")
+            .field("status", &status_name(self.status))
+            .field("subsystem", &self.subsystem)
+            .field("handle", &self.handle)
+            .field("contexts", &self.contexts())
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Error {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        for context in self.contexts().iter().rev() {
+            defmt::write!(f, "{=str}: ", context);
+        }
+        defmt::write!(
+            f,
+            "{=str}: {=str}",
+            self.subsystem.name(),
+            status_name(self.status)
+        );
+        if let Some(handle) = self.handle {
+            defmt::write!(f, " (handle {=u32:#x})", handle);
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+#[cfg(any(feature = "net", feature = "embedded-io-async"))]
+impl embedded_io::Error for Error {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        use embedded_io::ErrorKind;
+
+        match self.status() {
+            Status::Invalid => ErrorKind::InvalidInput,
+            Status::Denied => ErrorKind::PermissionDenied,
+            Status::NoEntity => ErrorKind::NotFound,
+            Status::AlreadyMapped => ErrorKind::AlreadyExists,
+            Status::Timeout => ErrorKind::TimedOut,
+            Status::Intr => ErrorKind::Interrupted,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+#[cfg(feature = "hal")]
+impl embedded_hal::spi::Error for Error {
+    fn kind(&self) -> embedded_hal::spi::ErrorKind {
+        embedded_hal::spi::ErrorKind::Other
+    }
+}
+
+#[cfg(feature = "hal")]
+impl embedded_hal::i2c::Error for Error {
+    fn kind(&self) -> embedded_hal::i2c::ErrorKind {
+        use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+
+        // see the crate::hal::i2c documentation
+        match (self.subsystem(), self.status()) {
+            (Subsystem::I2c, Status::NoEntity) => {
+                ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown)
+            }
+            (Subsystem::I2c, Status::Busy) => ErrorKind::ArbitrationLoss,
+            (Subsystem::I2c, Status::Critical) => ErrorKind::Bus,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+#[cfg(feature = "hal")]
+impl embedded_hal::digital::Error for Error {
+    fn kind(&self) -> embedded_hal::digital::ErrorKind {
+        embedded_hal::digital::ErrorKind::Other
+    }
+}
+
+/// Context attachment for results
+pub trait Context<T> {
+    /// Attach `context` to the error, if any.
+    ///
+    /// # Errors
+    /// Returns the original error, with the context attached.
+    fn context(self, context: &'static str) -> Result<T, Error>;
+}
+
+impl<T> Context<T> for Result<T, Error> {
+    fn context(self, context: &'static str) -> Result<T, Error> {
+        self.map_err(|err| err.with_context(context))
+    }
+}
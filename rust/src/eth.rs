@@ -0,0 +1,359 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Ethernet MAC driver
+//!
+//! [`Mac`] drives a memory-mapped Synopsys Ethernet QoS MAC (STM32H5, H7, U5,
+//! MP1 families) owned by the task: the DMA descriptor rings and the frame
+//! buffers are laid out in a shared memory, which the MAC DMA accesses, and
+//! the MAC interrupt reports the completions. It implements
+//! [`smoltcp::phy::Device`] and [`crate::net::Notify`], as the
+//! [`crate::net::ShmDevice`], for boards where the networking task owns the
+//! MAC instead of exchanging frames with a driver task: a [`crate::net::Stack`]
+//! runs over either.
+//!
+//! The MAC configuration (PHY, speed, duplex, address filters, FCS stripping)
+//! and its DMA channel registers are SoC specific: the board support code
+//! gives access to them through the [`MacRegisters`] trait.
+//!
+//! The shared memory holds the receive, then transmit, descriptor rings, of
+//! the same length, followed by a [`BUFFER_LEN`] bytes buffer per descriptor.
+//!
+//! ```ignore
+//! let mac = Mac::new(Eth1::mapped()?, Shm::new(ETH_SHM)?.map(0)?, ETH_IRQ)?;
+//! let stack = Stack::new(mac, Config::new(MAC_ADDRESS.into()), &mut sockets);
+//! let socket = UdpSocket::bind(&stack, 5683, rx, tx)?;
+//! ```
+
+use core::cell::{Cell, RefCell};
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{Ordering, fence};
+use smoltcp::phy::{self, DeviceCapabilities, Medium};
+use smoltcp::time::Instant;
+use uapi::systypes::{EventType, Status};
+
+use crate::error::{Error, Subsystem};
+use crate::net::{MTU, Notify};
+use crate::shm::{Mapped, Shm};
+
+/// Frame buffer length, in bytes
+pub const BUFFER_LEN: usize = 1536;
+
+/// Descriptor length, in bytes
+pub const DESCRIPTOR_LEN: usize = 16;
+
+/// Maximum number of descriptors per ring
+pub const MAX_DESCRIPTORS: usize = 32;
+
+/// Descriptor owned by the DMA
+const OWN: u32 = 1 << 31;
+/// Receive descriptor: interrupt on completion
+const RX_IOC: u32 = 1 << 30;
+/// Receive descriptor: buffer 1 address valid
+const RX_BUF1V: u32 = 1 << 24;
+/// First and last descriptors of the frame
+const FD: u32 = 1 << 29;
+const LD: u32 = 1 << 28;
+/// Receive write-back: error summary
+const RX_ES: u32 = 1 << 15;
+/// Receive write-back: context descriptor
+const RX_CTXT: u32 = 1 << 30;
+/// Frame length field
+const LENGTH: u32 = 0x7fff;
+/// Transmit descriptor: interrupt on completion
+const TX_IOC: u32 = 1 << 31;
+
+/// Access to the registers of a mapped Ethernet MAC
+pub trait MacRegisters {
+    /// Program the descriptor rings, of `len` descriptors each, and start the
+    /// MAC and its DMA channel.
+    fn start(&mut self, rx_ring: usize, tx_ring: usize, len: usize);
+
+    /// Stop the MAC and its DMA channel.
+    fn stop(&mut self);
+
+    /// Set the receive tail pointer, the DMA handling the descriptors up to
+    /// `descriptor`, excluded.
+    fn set_rx_tail(&mut self, descriptor: usize);
+
+    /// Set the transmit tail pointer, see [`MacRegisters::set_rx_tail`].
+    fn set_tx_tail(&mut self, descriptor: usize);
+
+    /// Clear the DMA channel interrupt flags.
+    fn clear_interrupts(&mut self);
+}
+
+/// Ethernet MAC driver, see the [module](self) documentation
+pub struct Mac<R> {
+    // shared by the two tokens of a frame, which are consumed in turn
+    regs: RefCell<R>,
+    shm: Shm<Mapped>,
+    base: NonNull<u8>,
+    len: usize,
+    rx_next: Cell<usize>,
+    tx_next: Cell<usize>,
+    irq: u16,
+}
+
+/// Access to a descriptor word
+#[derive(Clone, Copy)]
+struct Descriptor(*mut u32);
+
+impl Descriptor {
+    fn read(self, index: usize) -> u32 {
+        // SAFETY: aligned word of a descriptor in the shared memory
+        u32::from_le(unsafe { self.0.add(index).read_volatile() })
+    }
+
+    fn write(self, index: usize, value: u32) {
+        // SAFETY: see read
+        unsafe { self.0.add(index).write_volatile(value.to_le()) };
+    }
+
+    fn addr(self) -> usize {
+        self.0.addr()
+    }
+}
+
+impl<R: MacRegisters> Mac<R> {
+    /// Lay out the descriptor rings in `shm`, start the MAC and unmask its
+    /// interrupt `irq`.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the shared memory is too small
+    /// for a descriptor per direction, misaligned or not writable, or
+    /// propagates kernel errors if information retrieval fails or the
+    /// interrupt can't be enabled.
+    pub fn new(mut regs: R, mut shm: Shm<Mapped>, irq: u16) -> Result<Self, Error> {
+        let invalid = shm.error(Status::Invalid);
+        let base = shm.base_address()?;
+        let len = (shm.length()? / (2 * (DESCRIPTOR_LEN + BUFFER_LEN))).min(MAX_DESCRIPTORS);
+        if len == 0 || base % align_of::<u32>() != 0 || !shm.is_writable() {
+            return Err(invalid);
+        }
+        let base = NonNull::new(ptr::with_exposed_provenance_mut(base)).ok_or(invalid)?;
+        regs.stop();
+        let mac = Self {
+            regs: RefCell::new(regs),
+            shm,
+            base,
+            len,
+            rx_next: Cell::new(0),
+            tx_next: Cell::new(0),
+            irq,
+        };
+        for index in 0..len {
+            mac.arm_rx(index);
+            for word in 0..DESCRIPTOR_LEN / 4 {
+                mac.tx(index).write(word, 0);
+            }
+        }
+        fence(Ordering::SeqCst);
+        {
+            let mut regs = mac.regs.borrow_mut();
+            regs.start(mac.rx(0).addr(), mac.tx(0).addr(), len);
+            regs.set_rx_tail(mac.rx(len - 1).addr() + DESCRIPTOR_LEN);
+        }
+        mac.check(crate::sys::syscall::irq_enable(irq))?;
+        Ok(mac)
+    }
+
+    /// Stop the MAC, mask its interrupt, then release its registers and the
+    /// shared memory.
+    pub fn release(self) -> (R, Shm<Mapped>) {
+        let _ = crate::sys::syscall::irq_disable(self.irq);
+        let mut regs = self.regs.into_inner();
+        regs.stop();
+        (regs, self.shm)
+    }
+
+    /// Wait for the MAC interrupt, unless a frame is received already.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the interrupt can't be acknowledged.
+    #[cfg(feature = "async")]
+    pub async fn wait_irq(&mut self) -> Result<(), Error> {
+        if self.readable().is_some() {
+            return Ok(());
+        }
+        crate::executor::wait_event_from(EventType::Irq, u32::from(self.irq)).await;
+        self.acknowledge()
+    }
+
+    fn check(&self, status: Status) -> Result<(), Error> {
+        match status {
+            Status::Ok => Ok(()),
+            status => Err(Error::new(Subsystem::Eth, status).with_handle(u32::from(self.irq))),
+        }
+    }
+
+    fn address(&self, offset: usize) -> *mut u8 {
+        // SAFETY: offsets are in the shared memory, checked at creation
+        unsafe { self.base.as_ptr().add(offset) }
+    }
+
+    fn rx(&self, index: usize) -> Descriptor {
+        Descriptor(self.address(index * DESCRIPTOR_LEN).cast())
+    }
+
+    fn tx(&self, index: usize) -> Descriptor {
+        Descriptor(self.address((self.len + index) * DESCRIPTOR_LEN).cast())
+    }
+
+    fn rx_buffer(&self, index: usize) -> *mut u8 {
+        self.address(2 * self.len * DESCRIPTOR_LEN + index * BUFFER_LEN)
+    }
+
+    fn tx_buffer(&self, index: usize) -> *mut u8 {
+        self.rx_buffer(self.len + index)
+    }
+
+    /// Hand the receive descriptor `index` to the DMA.
+    fn arm_rx(&self, index: usize) {
+        let descriptor = self.rx(index);
+        descriptor.write(0, self.rx_buffer(index).addr() as u32);
+        descriptor.write(1, 0);
+        descriptor.write(2, 0);
+        fence(Ordering::Release);
+        descriptor.write(3, OWN | RX_IOC | RX_BUF1V);
+    }
+
+    /// Give the oldest receive descriptor back to the DMA.
+    fn recycle_rx(&self) {
+        let index = self.rx_next.get();
+        self.arm_rx(index);
+        self.rx_next.set((index + 1) % self.len);
+        self.regs
+            .borrow_mut()
+            .set_rx_tail(self.rx(index).addr() + DESCRIPTOR_LEN);
+    }
+
+    /// Return the oldest received frame descriptor, recycling the errored
+    /// ones.
+    fn readable(&self) -> Option<usize> {
+        for _ in 0..self.len {
+            let index = self.rx_next.get();
+            let status = self.rx(index).read(3);
+            if status & OWN != 0 {
+                return None;
+            }
+            fence(Ordering::Acquire);
+            if status & (FD | LD | RX_ES | RX_CTXT) == FD | LD {
+                return Some(index);
+            }
+            // frames spanning several buffers are larger than the MTU
+            self.recycle_rx();
+        }
+        None
+    }
+
+    fn writable(&self) -> Option<usize> {
+        let index = self.tx_next.get();
+        (self.tx(index).read(3) & OWN == 0).then_some(index)
+    }
+}
+
+impl<R: MacRegisters> phy::Device for Mac<R> {
+    type RxToken<'a>
+        = RxToken<'a, R>
+    where
+        Self: 'a;
+    type TxToken<'a>
+        = TxToken<'a, R>
+    where
+        Self: 'a;
+
+    /// Return the oldest received frame, along with a transmit token for the
+    /// reply, if a frame is received and a transmit descriptor is free.
+    fn receive(&mut self, _timestamp: Instant) -> Option<(RxToken<'_, R>, TxToken<'_, R>)> {
+        self.writable()?;
+        let index = self.readable()?;
+        Some((RxToken { mac: self, index }, TxToken { mac: self }))
+    }
+
+    /// Return a transmit token, if a transmit descriptor is free.
+    fn transmit(&mut self, _timestamp: Instant) -> Option<TxToken<'_, R>> {
+        self.writable()?;
+        Some(TxToken { mac: self })
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        let mut caps = DeviceCapabilities::default();
+        caps.medium = Medium::Ethernet;
+        caps.max_transmission_unit = MTU;
+        caps.max_burst_size = Some(self.len);
+        caps
+    }
+}
+
+impl<R: MacRegisters> Notify for Mac<R> {
+    /// The MAC interrupt
+    fn event(&self) -> (EventType, u32) {
+        (EventType::Irq, u32::from(self.irq))
+    }
+
+    /// Clear the interrupt flags, then acknowledge the interrupt, whose line
+    /// stays masked until then.
+    fn acknowledge(&mut self) -> Result<(), Error> {
+        self.regs.get_mut().clear_interrupts();
+        self.check(crate::sys::syscall::irq_acknowledge(self.irq))
+    }
+}
+
+/// Received frame of a [`Mac`]
+pub struct RxToken<'a, R: MacRegisters> {
+    mac: &'a Mac<R>,
+    index: usize,
+}
+
+impl<R: MacRegisters> phy::RxToken for RxToken<'_, R> {
+    /// Process the frame with `f`, then give its descriptor back to the DMA.
+    fn consume<T, F>(self, f: F) -> T
+    where
+        F: FnOnce(&[u8]) -> T,
+    {
+        let len = (self.mac.rx(self.index).read(3) & LENGTH) as usize;
+        // SAFETY: the DMA doesn't write the buffer until its descriptor is
+        // armed again, and the length is clamped to the buffer
+        let frame =
+            unsafe { core::slice::from_raw_parts(self.mac.rx_buffer(self.index), len.min(MTU)) };
+        let output = f(frame);
+        self.mac.recycle_rx();
+        output
+    }
+}
+
+/// Frame to transmit over a [`Mac`]
+pub struct TxToken<'a, R: MacRegisters> {
+    mac: &'a Mac<R>,
+}
+
+impl<R: MacRegisters> phy::TxToken for TxToken<'_, R> {
+    /// Build a `len` bytes frame with `f`, clamped to the [`MTU`], then hand
+    /// it to the DMA.
+    fn consume<T, F>(self, len: usize, f: F) -> T
+    where
+        F: FnOnce(&mut [u8]) -> T,
+    {
+        let mac = self.mac;
+        let len = len.min(MTU);
+        let index = mac.tx_next.get();
+        let buffer = mac.tx_buffer(index);
+        // SAFETY: the descriptor is owned by the CPU, the DMA not reading
+        // the buffer until it is handed over
+        let output = f(unsafe { core::slice::from_raw_parts_mut(buffer, len) });
+
+        let descriptor = mac.tx(index);
+        descriptor.write(0, buffer.addr() as u32);
+        descriptor.write(1, 0);
+        descriptor.write(2, TX_IOC | len as u32);
+        fence(Ordering::Release);
+        descriptor.write(3, OWN | FD | LD | len as u32);
+        mac.tx_next.set((index + 1) % mac.len);
+        mac.regs
+            .borrow_mut()
+            .set_tx_tail(descriptor.addr() + DESCRIPTOR_LEN);
+        output
+    }
+}
@@ -0,0 +1,168 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Future combinators
+//!
+//! The executor waker polls the whole top-level future again: combinators
+//! below simply poll all their pending futures, in order, each time they are
+//! polled. They require no allocation.
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+/// Future being polled, or its output
+enum MaybeDone<F: Future> {
+    Pending(F),
+    Done(F::Output),
+    Taken,
+}
+
+impl<F: Future> MaybeDone<F> {
+    /// Poll the future if still pending, returning whether it is done.
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> bool {
+        // SAFETY: the future is never moved out of the pinned enum, it is
+        // dropped in place when replaced by its output
+        let this = unsafe { self.get_unchecked_mut() };
+        match this {
+            Self::Pending(future) => {
+                // SAFETY: see above
+                match unsafe { Pin::new_unchecked(future) }.poll(cx) {
+                    Poll::Ready(output) => {
+                        *this = Self::Done(output);
+                        true
+                    }
+                    Poll::Pending => false,
+                }
+            }
+            Self::Done(_) | Self::Taken => true,
+        }
+    }
+
+    fn take(self: Pin<&mut Self>) -> Option<F::Output> {
+        // SAFETY: only a completed (or taken) future is replaced
+        let this = unsafe { self.get_unchecked_mut() };
+        match this {
+            Self::Done(_) => match core::mem::replace(this, Self::Taken) {
+                Self::Done(output) => Some(output),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+macro_rules! join {
+    ($(#[$meta:meta])* $join:ident, $name:ident, $($fut:ident: $F:ident),+) => {
+        $(#[$meta])*
+        pub fn $join<$($F: Future),+>($($fut: $F),+) -> $name<$($F),+> {
+            $name {
+                $($fut: MaybeDone::Pending($fut)),+
+            }
+        }
+
+        #[doc = concat!("Future returned by [`", stringify!($join), "`]")]
+        pub struct $name<$($F: Future),+> {
+            $($fut: MaybeDone<$F>),+
+        }
+
+        impl<$($F: Future),+> Future for $name<$($F),+> {
+            type Output = ($($F::Output),+);
+
+            fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                // SAFETY: fields are structurally pinned, and never moved
+                let this = unsafe { self.get_unchecked_mut() };
+                let mut done = true;
+                $(
+                    // SAFETY: see above
+                    done &= unsafe { Pin::new_unchecked(&mut this.$fut) }.poll(cx);
+                )+
+                if !done {
+                    return Poll::Pending;
+                }
+                // SAFETY: see above
+                let outputs = ($(unsafe { Pin::new_unchecked(&mut this.$fut) }.take()),+);
+                match outputs {
+                    ($(Some($fut)),+) => Poll::Ready(($($fut),+)),
+                    // polled again after completion
+                    _ => Poll::Pending,
+                }
+            }
+        }
+    };
+}
+
+join!(
+    /// Wait for the completion of two futures, returning both outputs.
+    join2, Join2, a: A, b: B
+);
+join!(
+    /// Wait for the completion of three futures, returning all outputs.
+    join3, Join3, a: A, b: B, c: C
+);
+join!(
+    /// Wait for the completion of four futures, returning all outputs.
+    join4, Join4, a: A, b: B, c: C, d: D
+);
+
+/// Output of [`select2`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Either<A, B> {
+    First(A),
+    Second(B),
+}
+
+/// Output of [`select3`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Either3<A, B, C> {
+    First(A),
+    Second(B),
+    Third(C),
+}
+
+macro_rules! select {
+    (
+        $(#[$meta:meta])* $select:ident, $name:ident, $either:ident,
+        $($fut:ident: $F:ident => $variant:ident),+
+    ) => {
+        $(#[$meta])*
+        pub fn $select<$($F: Future),+>($($fut: $F),+) -> $name<$($F),+> {
+            $name { $($fut),+ }
+        }
+
+        #[doc = concat!("Future returned by [`", stringify!($select), "`]")]
+        pub struct $name<$($F),+> {
+            $($fut: $F),+
+        }
+
+        impl<$($F: Future),+> Future for $name<$($F),+> {
+            type Output = $either<$($F::Output),+>;
+
+            fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                // SAFETY: fields are structurally pinned, and never moved
+                let this = unsafe { self.get_unchecked_mut() };
+                $(
+                    // SAFETY: see above
+                    if let Poll::Ready(output) = unsafe { Pin::new_unchecked(&mut this.$fut) }.poll(cx) {
+                        return Poll::Ready($either::$variant(output));
+                    }
+                )+
+                Poll::Pending
+            }
+        }
+    };
+}
+
+select!(
+    /// Wait for the first completed of two futures, polled in order.
+    ///
+    /// The other future is cancelled when the returned future is dropped.
+    select2, Select2, Either, a: A => First, b: B => Second
+);
+select!(
+    /// Wait for the first completed of three futures, polled in order.
+    ///
+    /// The other futures are cancelled when the returned future is dropped.
+    select3, Select3, Either3, a: A => First, b: B => Second, c: C => Third
+);
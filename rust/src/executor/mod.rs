@@ -0,0 +1,215 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Minimal async executor
+//!
+//! [`run`] drives a single top-level future, which typically joins or selects
+//! the task activities. When the future is pending, the executor parks the
+//! task in the kernel through `wait_for_event()`, waiting only for the event
+//! types that futures are interested in. Each delivered event is then
+//! dispatched to the future waiting for it (see [`wait_event`]), which is
+//! woken up.
+//!
+//! ```ignore
+//! use shield::executor;
+//! use uapi::systypes::EventType;
+//!
+//! executor::run(async {
+//!     loop {
+//!         let irq = executor::wait_event_from(EventType::Irq, USART1_IRQ).await;
+//!         // handle the interrupt...
+//!     }
+//! });
+//! ```
+//!
+//! Events received while no future waits for them are kept in a short
+//! backlog (see [`BACKLOG_LEN`]), so that a future registering soon after
+//! does not miss them.
+//!
+//! A long-running future gives the other futures, and the event dispatch, a
+//! chance to run with [`yield_now`].
+//!
+//! Futures are composed with the [`join2`] and [`select2`] families of
+//! combinators.
+//!
+//! With the `embassy`, `rtic`, `net` or `hal` features, the executor also
+//! drives the `embassy-time` timers, the RTIC monotonic delays, the network
+//! stack deadlines or the `embedded-hal-async` delays, through the kernel
+//! alarm.
+
+mod combinators;
+mod reactor;
+#[cfg(any(
+    feature = "embassy",
+    feature = "rtic",
+    feature = "net",
+    feature = "hal"
+))]
+pub(crate) mod timer;
+
+use core::future::Future;
+use core::pin::{Pin, pin};
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use uapi::systypes::{EventType, Signal, Status, TaskHandle};
+
+use reactor::Filter;
+
+pub use combinators::{
+    Either, Either3, Join2, Join3, Join4, Select2, Select3, join2, join3, join4, select2, select3,
+};
+pub use reactor::{BACKLOG_LEN, EVENT_DATA_LEN, Event, EventFuture, MAX_EVENT_WAITERS};
+
+/// `wait_for_event()` timeout value for a non-blocking wait
+const WFE_WAIT_NO: i32 = -1;
+
+/// `wait_for_event()` timeout value for an infinite wait
+const WFE_WAIT_FOREVER: i32 = 0;
+
+/// Set when the top-level future must be polled again
+static WOKEN: AtomicBool = AtomicBool::new(false);
+
+/// Set while [`run`] executes
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake, drop);
+
+fn raw_waker() -> RawWaker {
+    RawWaker::new(core::ptr::null(), &VTABLE)
+}
+
+fn clone(_: *const ()) -> RawWaker {
+    raw_waker()
+}
+
+fn wake(_: *const ()) {
+    WOKEN.store(true, Ordering::Relaxed);
+}
+
+fn drop(_: *const ()) {}
+
+/// Run `future` to completion, driving it from the kernel events.
+///
+/// # Panics
+/// Panics if called from a future driven by the executor.
+pub fn run<F: Future>(future: F) -> F::Output {
+    // a Sentry task being single-threaded, no atomic swap is required
+    assert!(!RUNNING.load(Ordering::Relaxed), "nested executor::run");
+    RUNNING.store(true, Ordering::Relaxed);
+
+    // SAFETY: the waker vtable functions do not use the (null) data pointer
+    let waker = unsafe { Waker::from_raw(raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    WOKEN.store(true, Ordering::Relaxed);
+    let output = loop {
+        if WOKEN.load(Ordering::Relaxed) {
+            WOKEN.store(false, Ordering::Relaxed);
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                break output;
+            }
+        }
+
+        #[cfg(any(
+            feature = "embassy",
+            feature = "rtic",
+            feature = "net",
+            feature = "hal"
+        ))]
+        let timers = timer::process();
+        #[cfg(not(any(
+            feature = "embassy",
+            feature = "rtic",
+            feature = "net",
+            feature = "hal"
+        )))]
+        let timers = 0;
+
+        // only check for already pending events if woken up during the poll
+        let timeout = if WOKEN.load(Ordering::Relaxed) {
+            WFE_WAIT_NO
+        } else {
+            WFE_WAIT_FOREVER
+        };
+        let mask = match reactor::interest() | timers {
+            0 => EventType::All.into(),
+            mask => mask,
+        };
+        if crate::sys::syscall::wait_for_event(mask, timeout) == Status::Ok
+            && let Some(event) = Event::receive()
+        {
+            // expired timers are woken up on the next iteration
+            #[cfg(any(
+                feature = "embassy",
+                feature = "rtic",
+                feature = "net",
+                feature = "hal"
+            ))]
+            if timer::is_alarm(&event) {
+                continue;
+            }
+            if let Some(waker) = reactor::dispatch(event) {
+                waker.wake();
+            }
+        }
+    };
+
+    RUNNING.store(false, Ordering::Relaxed);
+    output
+}
+
+/// Wait for the next event of the given type.
+pub fn wait_event(kind: EventType) -> EventFuture {
+    EventFuture::new(Filter {
+        kind: kind.into(),
+        source: None,
+        peer: None,
+    })
+}
+
+/// Wait for the next event of the given type emitted by `source`.
+///
+/// The source is the event peer for IPC, and the first data word for other
+/// events: the signal, the IRQ number, or the DMA stream handle.
+pub fn wait_event_from(kind: EventType, source: u32) -> EventFuture {
+    EventFuture::new(Filter {
+        kind: kind.into(),
+        source: Some(source),
+        peer: None,
+    })
+}
+
+/// Wait for the next `signal` sent by the task `peer`.
+pub fn wait_signal_from(signal: Signal, peer: TaskHandle) -> EventFuture {
+    EventFuture::new(Filter {
+        kind: EventType::Signal.into(),
+        source: Some(signal as u32),
+        peer: Some(peer),
+    })
+}
+
+/// Yield to the executor, which dispatches the pending events, without
+/// waiting for new ones, before polling again.
+pub fn yield_now() -> YieldNow {
+    YieldNow { yielded: false }
+}
+
+/// Future returned by [`yield_now`]
+pub struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
@@ -0,0 +1,236 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+use core::cell::UnsafeCell;
+use core::future::Future;
+use core::mem::size_of;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use uapi::systypes::{EventType, ExchangeHeader};
+
+/// Maximum number of futures waiting for an event at a given time
+pub const MAX_EVENT_WAITERS: usize = 8;
+
+/// Maximum number of received events kept while no future waits for them
+pub const BACKLOG_LEN: usize = 4;
+
+/// Maximum length of the data of an event
+pub const EVENT_DATA_LEN: usize = uapi::length() - size_of::<ExchangeHeader>();
+
+/// Event delivered by the kernel
+#[derive(Clone, Copy)]
+pub struct Event {
+    header: ExchangeHeader,
+    data: [u8; EVENT_DATA_LEN],
+}
+
+impl Event {
+    /// Read the event delivered by the last successful `wait_for_event()`.
+    pub(crate) fn receive() -> Option<Self> {
+        let mut data = [0; EVENT_DATA_LEN];
+        let mut event = uapi::systypes::Event {
+            header: ExchangeHeader {
+                event: 0,
+                length: 0,
+                magic: 0,
+                peer: 0,
+            },
+            data: &mut data,
+        };
+        crate::sys::copy_from_kernel(&mut event).ok()?;
+        let header = event.header;
+        Some(Self { header, data })
+    }
+
+    /// Event type
+    pub fn kind(&self) -> EventType {
+        self.header.event.into()
+    }
+
+    /// Task handle of the event emitter
+    pub fn peer(&self) -> u32 {
+        self.header.peer
+    }
+
+    /// Event data
+    pub fn data(&self) -> &[u8] {
+        &self.data[..usize::from(self.header.length).min(EVENT_DATA_LEN)]
+    }
+
+    /// Event source: the peer for IPC, the first data word otherwise.
+    pub fn source(&self) -> u32 {
+        if self.kind() == EventType::Ipc {
+            return self.peer();
+        }
+        let mut word = [0; 4];
+        let len = self.data().len().min(word.len());
+        word[..len].copy_from_slice(&self.data[..len]);
+        u32::from_ne_bytes(word)
+    }
+
+    fn matches(&self, filter: &Filter) -> bool {
+        self.header.event & filter.kind != 0
+            && filter.source.is_none_or(|source| source == self.source())
+            && filter.peer.is_none_or(|peer| peer == self.peer())
+    }
+}
+
+/// Events awaited by an [`EventFuture`]
+#[derive(Clone, Copy)]
+pub(super) struct Filter {
+    /// Event types mask
+    pub(super) kind: u8,
+    pub(super) source: Option<u32>,
+    pub(super) peer: Option<u32>,
+}
+
+struct Slot {
+    filter: Filter,
+    waker: Option<Waker>,
+    event: Option<Event>,
+}
+
+struct Reactor {
+    slots: [Option<Slot>; MAX_EVENT_WAITERS],
+    backlog: [Option<Event>; BACKLOG_LEN],
+}
+
+struct ReactorCell(UnsafeCell<Reactor>);
+
+// SAFETY: a Sentry task is single-threaded, and the reactor is never borrowed
+// across calls of `with_reactor`
+unsafe impl Sync for ReactorCell {}
+
+static REACTOR: ReactorCell = ReactorCell(UnsafeCell::new(Reactor {
+    slots: [const { None }; MAX_EVENT_WAITERS],
+    backlog: [None; BACKLOG_LEN],
+}));
+
+/// Execute `f` with an exclusive access to the reactor
+fn with_reactor<R>(f: impl FnOnce(&mut Reactor) -> R) -> R {
+    // SAFETY: see ReactorCell, `f` never wakes a future nor reaches
+    // `with_reactor` again
+    f(unsafe { &mut *REACTOR.0.get() })
+}
+
+/// Return the mask of the event types futures are waiting for.
+pub(super) fn interest() -> u8 {
+    with_reactor(|reactor| {
+        reactor
+            .slots
+            .iter()
+            .flatten()
+            .filter(|slot| slot.event.is_none())
+            .fold(0, |mask, slot| mask | slot.filter.kind)
+    })
+}
+
+/// Hand `event` to the future waiting for it, returning its waker.
+///
+/// The event is stored in the backlog if no future waits for it, dropping the
+/// oldest one if the backlog is full.
+pub(super) fn dispatch(event: Event) -> Option<Waker> {
+    with_reactor(|reactor| {
+        if let Some(slot) = reactor
+            .slots
+            .iter_mut()
+            .flatten()
+            .find(|slot| slot.event.is_none() && event.matches(&slot.filter))
+        {
+            slot.event = Some(event);
+            return slot.waker.take();
+        }
+
+        let backlog = &mut reactor.backlog;
+        if backlog.iter().all(Option::is_some) {
+            backlog.rotate_left(1);
+            backlog[BACKLOG_LEN - 1] = None;
+        }
+        if let Some(entry) = backlog.iter_mut().find(|entry| entry.is_none()) {
+            *entry = Some(event);
+        }
+        None
+    })
+}
+
+/// Future returned by [`super::wait_event`] and [`super::wait_event_from`]
+pub struct EventFuture {
+    filter: Filter,
+    slot: Option<usize>,
+}
+
+impl EventFuture {
+    pub(super) fn new(filter: Filter) -> Self {
+        Self { filter, slot: None }
+    }
+}
+
+impl Future for EventFuture {
+    type Output = Event;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Event> {
+        let filter = self.filter;
+        let polled = with_reactor(|reactor| {
+            if let Some(index) = self.slot {
+                let slot = &mut reactor.slots[index];
+                if let Some(event) = slot.as_mut().and_then(|slot| slot.event.take()) {
+                    *slot = None;
+                    return Some(Poll::Ready(event));
+                }
+                if let Some(slot) = slot {
+                    slot.waker = Some(cx.waker().clone());
+                }
+                return Some(Poll::Pending);
+            }
+
+            // oldest matching event first
+            if let Some(index) = reactor
+                .backlog
+                .iter()
+                .position(|entry| entry.is_some_and(|event| event.matches(&filter)))
+            {
+                let event = reactor.backlog[index].take();
+                reactor.backlog[index..].rotate_left(1);
+                return event.map(Poll::Ready);
+            }
+
+            let index = reactor.slots.iter().position(Option::is_none)?;
+            reactor.slots[index] = Some(Slot {
+                filter,
+                waker: Some(cx.waker().clone()),
+                event: None,
+            });
+            self.slot = Some(index);
+            Some(Poll::Pending)
+        });
+
+        match polled {
+            Some(Poll::Ready(event)) => {
+                self.slot = None;
+                Poll::Ready(event)
+            }
+            Some(Poll::Pending) => Poll::Pending,
+            None => {
+                // all slots are busy, try again once some futures progressed
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for EventFuture {
+    fn drop(&mut self) {
+        if let Some(index) = self.slot.take() {
+            with_reactor(|reactor| {
+                // an event handed to a cancelled future goes back to the backlog
+                if let Some(event) = reactor.slots[index].take().and_then(|slot| slot.event)
+                    && let Some(entry) = reactor.backlog.iter_mut().find(|entry| entry.is_none())
+                {
+                    *entry = Some(event);
+                }
+            });
+        }
+    }
+}
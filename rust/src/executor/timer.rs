@@ -0,0 +1,143 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Executor timer queue
+//!
+//! Futures waiting for a deadline register their waker here. The executor
+//! arms the kernel alarm for the earliest deadline, and wakes the expired
+//! futures when the alarm signal is received.
+
+use core::cell::UnsafeCell;
+use core::task::Waker;
+use uapi::systypes::{AlarmFlag, EventType, Signal};
+
+use super::Event;
+use crate::time;
+
+/// Maximum number of pending deadlines
+const MAX_TIMERS: usize = 8;
+
+struct Timer {
+    at_us: u64,
+    waker: Waker,
+}
+
+struct TimerQueue {
+    timers: [Option<Timer>; MAX_TIMERS],
+    armed_us: Option<u64>,
+}
+
+struct QueueCell(UnsafeCell<TimerQueue>);
+
+// SAFETY: a Sentry task is single-threaded, and the queue is never borrowed
+// across calls of `with_queue`
+unsafe impl Sync for QueueCell {}
+
+static QUEUE: QueueCell = QueueCell(UnsafeCell::new(TimerQueue {
+    timers: [const { None }; MAX_TIMERS],
+    armed_us: None,
+}));
+
+/// Execute `f` with an exclusive access to the timer queue
+fn with_queue<R>(f: impl FnOnce(&mut TimerQueue) -> R) -> R {
+    // SAFETY: see QueueCell, `f` never wakes a future nor reaches
+    // `with_queue` again
+    f(unsafe { &mut *QUEUE.0.get() })
+}
+
+/// Wake up `waker` once the uptime reaches `at_us` microseconds.
+///
+/// If the queue is full, the waker is woken up immediately, the future polling
+/// again later.
+pub(crate) fn schedule(at_us: u64, waker: &Waker) {
+    let registered = with_queue(|queue| {
+        if let Some(timer) = queue
+            .timers
+            .iter_mut()
+            .flatten()
+            .find(|timer| timer.waker.will_wake(waker))
+        {
+            timer.at_us = timer.at_us.min(at_us);
+            return true;
+        }
+        let Some(slot) = queue.timers.iter_mut().find(|slot| slot.is_none()) else {
+            return false;
+        };
+        *slot = Some(Timer {
+            at_us,
+            waker: waker.clone(),
+        });
+        true
+    });
+    if !registered {
+        waker.wake_by_ref();
+    }
+}
+
+/// Wake up the expired timers and arm the kernel alarm for the next one.
+///
+/// Returns the event types to wait for, i.e. signals while timers are pending.
+pub(super) fn process() -> u8 {
+    let now_us = time::uptime_us().unwrap_or_default();
+    let mut expired = [const { None }; MAX_TIMERS];
+    let pending = with_queue(|queue| {
+        for (slot, expired) in queue.timers.iter_mut().zip(expired.iter_mut()) {
+            if slot.as_ref().is_some_and(|timer| timer.at_us <= now_us) {
+                *expired = slot.take().map(|timer| timer.waker);
+            }
+        }
+
+        let next_us = queue.timers.iter().flatten().map(|timer| timer.at_us).min();
+        if next_us != queue.armed_us {
+            if let Some(next_us) = next_us {
+                let delay_ms = (next_us - now_us).div_ceil(1000).max(1);
+                // the alarm being restarted, a previous one never fires
+                crate::sys::syscall::alarm(
+                    u32::try_from(delay_ms).unwrap_or(u32::MAX),
+                    AlarmFlag::AlarmStart,
+                );
+            } else {
+                crate::sys::syscall::alarm(0, AlarmFlag::AlarmStop);
+            }
+            queue.armed_us = next_us;
+        }
+        next_us.is_some()
+    });
+
+    expired.into_iter().flatten().for_each(Waker::wake);
+    if pending { EventType::Signal.into() } else { 0 }
+}
+
+/// Check whether `event` is the kernel alarm signal.
+pub(super) fn is_alarm(event: &Event) -> bool {
+    event.kind() == EventType::Signal && event.source() == Signal::Alarm as u32
+}
+
+/// Wait for the uptime to reach `at_us` microseconds.
+#[cfg(any(feature = "net", feature = "hal"))]
+pub(crate) fn until(at_us: u64) -> Until {
+    Until { at_us }
+}
+
+/// Future returned by [`until`]
+#[cfg(any(feature = "net", feature = "hal"))]
+pub(crate) struct Until {
+    at_us: u64,
+}
+
+#[cfg(any(feature = "net", feature = "hal"))]
+impl core::future::Future for Until {
+    type Output = ();
+
+    fn poll(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<()> {
+        if time::uptime_us().unwrap_or_default() >= self.at_us {
+            return core::task::Poll::Ready(());
+        }
+        schedule(self.at_us, cx.waker());
+        core::task::Poll::Pending
+    }
+}
@@ -0,0 +1,779 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! `printf` formatting core
+//!
+//! C format strings are interpreted here, numbers being rendered by
+//! `core::fmt` so that C and Rust code of a task print values identically:
+//! - flags `-`, `+`, space, `#` and `0`, field width and precision, given
+//!   inline or as `*` arguments
+//! - length modifiers `hh`, `h`, `l`, `ll`, `j`, `z`, `t` and `L`
+//! - conversions `d`, `i`, `u`, `o`, `x`, `X`, `c`, `s`, `p`, `f`, `F`, `e`,
+//!   `E`, `g`, `G` and `%`
+//!
+//! `%n` is deliberately not supported, the format being rejected.
+//!
+//! Arguments are fetched through the [`Args`] trait. On Arm targets, the C
+//! `va_list` is walked by [`VaList`], backing the exported [`vsnprintf`] and
+//! [`vprintf`] functions, on which the C library `printf` family is built.
+
+use core::ffi::c_long;
+use core::fmt::{self, Write};
+
+/// Source of `printf` arguments, as laid out by the C calling convention
+pub trait Args {
+    /// Fetch an argument of 32 bits or less (promoted `char`, `short`, `int`).
+    fn next_u32(&mut self) -> u32;
+    /// Fetch a 64 bits integer argument.
+    fn next_u64(&mut self) -> u64;
+    /// Fetch a `double` argument.
+    fn next_f64(&mut self) -> f64;
+}
+
+/// `printf` argument, for formatting from Rust code
+#[derive(Debug, Clone, Copy)]
+pub enum Arg {
+    /// Any integer, pointer or character
+    Int(u64),
+    /// Floating point value
+    Float(f64),
+}
+
+/// Arguments given as a slice, missing arguments being read as `0`.
+impl Args for core::slice::Iter<'_, Arg> {
+    fn next_u32(&mut self) -> u32 {
+        #[allow(clippy::cast_possible_truncation)]
+        let value = self.next_u64() as u32;
+        value
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self.next() {
+            Some(Arg::Int(value)) => *value,
+            Some(Arg::Float(value)) => value.to_bits(),
+            None => 0,
+        }
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        match self.next() {
+            Some(Arg::Float(value)) => *value,
+            Some(Arg::Int(value)) => f64::from_bits(*value),
+            None => 0.0,
+        }
+    }
+}
+
+/// Output of the formatting core
+trait Sink {
+    fn put(&mut self, bytes: &[u8]);
+
+    fn repeat(&mut self, byte: u8, count: usize) {
+        for _ in 0..count {
+            self.put(&[byte]);
+        }
+    }
+}
+
+/// Sink counting the formatted bytes only
+struct Counter(usize);
+
+impl Sink for Counter {
+    fn put(&mut self, bytes: &[u8]) {
+        self.0 += bytes.len();
+    }
+}
+
+/// Sink counting the bytes written to another one
+struct Tally<'a, S> {
+    sink: &'a mut S,
+    total: usize,
+}
+
+impl<S: Sink> Sink for Tally<'_, S> {
+    fn put(&mut self, bytes: &[u8]) {
+        self.total += bytes.len();
+        self.sink.put(bytes);
+    }
+}
+
+/// Sink filling a slice, silently truncating the output
+struct SliceSink<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl Sink for SliceSink<'_> {
+    fn put(&mut self, bytes: &[u8]) {
+        let count = bytes.len().min(self.buf.len() - self.len);
+        self.buf[self.len..self.len + count].copy_from_slice(&bytes[..count]);
+        self.len += count;
+    }
+}
+
+/// `core::fmt` adapter over a sink
+struct Adapter<'a, S>(&'a mut S);
+
+impl<S: Sink> Write for Adapter<'_, S> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.put(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Length {
+    Char,
+    Short,
+    Int,
+    Long,
+    LongLong,
+    Max,
+    Size,
+    Ptrdiff,
+    LongDouble,
+}
+
+impl Length {
+    fn bytes(self) -> usize {
+        match self {
+            Self::Char | Self::Short | Self::Int | Self::LongDouble => size_of::<u32>(),
+            Self::Long => size_of::<c_long>(),
+            Self::LongLong | Self::Max => size_of::<u64>(),
+            Self::Size | Self::Ptrdiff => size_of::<usize>(),
+        }
+    }
+}
+
+/// Conversion specification
+#[derive(Clone, Copy)]
+#[allow(clippy::struct_excessive_bools)]
+struct Spec {
+    left: bool,
+    plus: bool,
+    space: bool,
+    alt: bool,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    length: Length,
+}
+
+impl Spec {
+    /// Write `body`, of `body_len` bytes, after `prefix` and padded to the
+    /// field width. Zero padding is inserted between the prefix and the body.
+    fn pad<S: Sink>(
+        &self,
+        sink: &mut S,
+        zero_pad: bool,
+        prefix: &[u8],
+        body_len: usize,
+        body: impl FnOnce(&mut S),
+    ) {
+        let fill = self.width.saturating_sub(prefix.len() + body_len);
+        if !self.left && !zero_pad {
+            sink.repeat(b' ', fill);
+        }
+        sink.put(prefix);
+        if !self.left && zero_pad {
+            sink.repeat(b'0', fill);
+        }
+        body(sink);
+        if self.left {
+            sink.repeat(b' ', fill);
+        }
+    }
+}
+
+fn fetch_unsigned(args: &mut impl Args, length: Length) -> u64 {
+    let value = if length.bytes() == size_of::<u64>() {
+        args.next_u64()
+    } else {
+        u64::from(args.next_u32())
+    };
+    match length {
+        Length::Char => value & 0xff,
+        Length::Short => value & 0xffff,
+        _ => value,
+    }
+}
+
+#[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+fn fetch_signed(args: &mut impl Args, length: Length) -> i64 {
+    let value = fetch_unsigned(args, length);
+    match length {
+        Length::Char => i64::from(value as i8),
+        Length::Short => i64::from(value as i16),
+        _ if length.bytes() == size_of::<u64>() => value as i64,
+        _ => i64::from(value as i32),
+    }
+}
+
+fn sign(spec: &Spec, negative: bool) -> &'static [u8] {
+    if negative {
+        b"-"
+    } else if spec.plus {
+        b"+"
+    } else if spec.space {
+        b" "
+    } else {
+        b""
+    }
+}
+
+fn digits<S: Sink>(sink: &mut S, conv: u8, value: u64) {
+    let mut out = Adapter(sink);
+    let _ = match conv {
+        b'o' => write!(out, "{value:o}"),
+        b'x' => write!(out, "{value:x}"),
+        b'X' => write!(out, "{value:X}"),
+        _ => write!(out, "{value}"),
+    };
+}
+
+fn integer<S: Sink>(sink: &mut S, spec: &Spec, conv: u8, negative: bool, value: u64) {
+    let radix = if conv == b'p' { b'x' } else { conv };
+    let mut count = Counter(0);
+    digits(&mut count, radix, value);
+    // an explicit zero precision prints no digit for zero
+    let digits_len = if value == 0 && spec.precision == Some(0) {
+        0
+    } else {
+        count.0
+    };
+
+    let mut zeros = spec.precision.unwrap_or(0).saturating_sub(digits_len);
+    let prefix: &[u8] = match conv {
+        b'd' | b'i' => sign(spec, negative),
+        b'x' if spec.alt && value != 0 => b"0x",
+        b'X' if spec.alt && value != 0 => b"0X",
+        b'p' => b"0x",
+        b'o' if spec.alt && zeros == 0 && (value != 0 || digits_len == 0) => {
+            zeros = 1;
+            b""
+        }
+        _ => b"",
+    };
+
+    let zero_pad = spec.zero && spec.precision.is_none();
+    spec.pad(sink, zero_pad, prefix, zeros + digits_len, |sink| {
+        sink.repeat(b'0', zeros);
+        if digits_len != 0 {
+            digits(sink, radix, value);
+        }
+    });
+}
+
+#[derive(Clone, Copy)]
+enum Style {
+    Fixed,
+    Exponent,
+}
+
+/// `core::fmt` adapter rewriting Rust float output to the C layout: exponent
+/// sign and at least two exponent digits, optional trailing zeros removal
+/// (`%g`) and forced decimal point (`#` flag).
+struct FloatWriter<'a, S> {
+    sink: &'a mut S,
+    upper: bool,
+    strip: bool,
+    force_dot: bool,
+    dot_seen: bool,
+    pending_dot: bool,
+    pending_zeros: usize,
+    exponent: Option<(bool, u32)>,
+}
+
+impl<'a, S: Sink> FloatWriter<'a, S> {
+    fn new(sink: &'a mut S, upper: bool, strip: bool, force_dot: bool) -> Self {
+        Self {
+            sink,
+            upper,
+            strip,
+            force_dot,
+            dot_seen: false,
+            pending_dot: false,
+            pending_zeros: 0,
+            exponent: None,
+        }
+    }
+
+    fn end_mantissa(&mut self) {
+        if self.force_dot && !self.dot_seen {
+            self.sink.put(b".");
+            self.dot_seen = true;
+        }
+    }
+
+    fn finish(mut self) {
+        self.end_mantissa();
+        if let Some((negative, exponent)) = self.exponent {
+            self.sink.put(if self.upper { b"E" } else { b"e" });
+            self.sink.put(if negative { b"-" } else { b"+" });
+            if exponent < 10 {
+                self.sink.put(b"0");
+            }
+            let _ = write!(Adapter(self.sink), "{exponent}");
+        }
+    }
+}
+
+impl<S: Sink> Write for FloatWriter<'_, S> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &byte in s.as_bytes() {
+            match (byte, self.exponent.as_mut()) {
+                (b'-', Some((negative, _))) => *negative = true,
+                (b'0'..=b'9', Some((_, exponent))) => {
+                    *exponent = *exponent * 10 + u32::from(byte - b'0');
+                }
+                (b'e' | b'E', None) => {
+                    self.end_mantissa();
+                    self.exponent = Some((false, 0));
+                }
+                (b'.', None) => {
+                    self.dot_seen = true;
+                    if self.strip {
+                        self.pending_dot = true;
+                    } else {
+                        self.sink.put(b".");
+                    }
+                }
+                (b'0', None) if self.strip && self.dot_seen => self.pending_zeros += 1,
+                (_, None) => {
+                    if self.pending_dot {
+                        self.sink.put(b".");
+                        self.pending_dot = false;
+                    }
+                    self.sink.repeat(b'0', self.pending_zeros);
+                    self.pending_zeros = 0;
+                    self.sink.put(&[byte]);
+                }
+                (_, Some(_)) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Return the decimal exponent of `value` once rounded to `precision`
+/// fractional digits in exponent style.
+fn decimal_exponent(value: f64, precision: usize) -> i32 {
+    struct Probe(Option<i32>, bool);
+
+    impl Write for Probe {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            for &byte in s.as_bytes() {
+                match (byte, self.0.as_mut()) {
+                    (b'e', None) => self.0 = Some(0),
+                    (b'-', Some(_)) => self.1 = true,
+                    (b'0'..=b'9', Some(exponent)) => {
+                        *exponent = *exponent * 10 + i32::from(byte - b'0');
+                    }
+                    _ => {}
+                }
+            }
+            Ok(())
+        }
+    }
+
+    let mut probe = Probe(None, false);
+    let _ = write!(probe, "{value:.precision$e}");
+    let exponent = probe.0.unwrap_or(0);
+    if probe.1 { -exponent } else { exponent }
+}
+
+fn float_digits<S: Sink>(
+    sink: &mut S,
+    value: f64,
+    spec: &Spec,
+    upper: bool,
+    style: Style,
+    precision: usize,
+    strip: bool,
+) {
+    let mut out = FloatWriter::new(sink, upper, strip, spec.alt);
+    let _ = match style {
+        Style::Fixed => write!(out, "{value:.precision$}"),
+        Style::Exponent => write!(out, "{value:.precision$e}"),
+    };
+    out.finish();
+}
+
+fn float<S: Sink>(sink: &mut S, spec: &Spec, conv: u8, value: f64) {
+    let prefix = sign(spec, value.is_sign_negative() && !value.is_nan());
+    let upper = conv.is_ascii_uppercase();
+    let value = value.abs();
+
+    if !value.is_finite() {
+        let text: &[u8] = match (value.is_nan(), upper) {
+            (true, false) => b"nan",
+            (true, true) => b"NAN",
+            (false, false) => b"inf",
+            (false, true) => b"INF",
+        };
+        spec.pad(sink, false, prefix, text.len(), |sink| sink.put(text));
+        return;
+    }
+
+    let precision = spec.precision.unwrap_or(6);
+    let (style, precision, strip) = match conv.to_ascii_lowercase() {
+        b'f' => (Style::Fixed, precision, false),
+        b'e' => (Style::Exponent, precision, false),
+        _ => {
+            // %g: exponent style for exponents below -4 or above the precision
+            let significant = precision.max(1);
+            let exponent = decimal_exponent(value, significant - 1);
+            match usize::try_from(exponent) {
+                Ok(exponent) if exponent < significant => {
+                    (Style::Fixed, significant - 1 - exponent, !spec.alt)
+                }
+                Err(_) if exponent >= -4 => (
+                    Style::Fixed,
+                    significant - 1 + exponent.unsigned_abs() as usize,
+                    !spec.alt,
+                ),
+                _ => (Style::Exponent, significant - 1, !spec.alt),
+            }
+        }
+    };
+
+    let mut count = Counter(0);
+    float_digits(&mut count, value, spec, upper, style, precision, strip);
+    spec.pad(sink, spec.zero, prefix, count.0, |sink| {
+        float_digits(sink, value, spec, upper, style, precision, strip);
+    });
+}
+
+/// Read a NUL terminated string, up to `max` bytes.
+///
+/// # Safety
+///
+/// `ptr` must point to a NUL terminated string, or to at least `max` bytes.
+unsafe fn c_str<'a>(ptr: *const u8, max: usize) -> &'a [u8] {
+    let mut len = 0;
+    // SAFETY: bytes up to the NUL or `max` are readable per the contract
+    while len < max && unsafe { *ptr.add(len) } != 0 {
+        len += 1;
+    }
+    // SAFETY: the `len` first bytes have just been read
+    unsafe { core::slice::from_raw_parts(ptr, len) }
+}
+
+/// Parse a decimal number, returning it and the remaining format.
+fn number(fmt: &[u8]) -> (usize, &[u8]) {
+    let len = fmt.iter().take_while(|byte| byte.is_ascii_digit()).count();
+    let value = fmt[..len].iter().fold(0_usize, |value, &digit| {
+        value
+            .saturating_mul(10)
+            .saturating_add(usize::from(digit - b'0'))
+    });
+    (value, &fmt[len..])
+}
+
+#[allow(clippy::cast_possible_wrap)]
+fn star(args: &mut impl Args) -> i32 {
+    args.next_u32() as i32
+}
+
+/// Format one conversion, `fmt` starting after the `%`, returning the
+/// remaining format or `None` if the conversion is invalid.
+///
+/// # Safety
+///
+/// See [`format`].
+unsafe fn conversion<'f, S: Sink>(
+    sink: &mut S,
+    mut fmt: &'f [u8],
+    args: &mut impl Args,
+) -> Option<&'f [u8]> {
+    let mut spec = Spec {
+        left: false,
+        plus: false,
+        space: false,
+        alt: false,
+        zero: false,
+        width: 0,
+        precision: None,
+        length: Length::Int,
+    };
+
+    while let Some((&flag, rest)) = fmt.split_first() {
+        match flag {
+            b'-' => spec.left = true,
+            b'+' => spec.plus = true,
+            b' ' => spec.space = true,
+            b'#' => spec.alt = true,
+            b'0' => spec.zero = true,
+            _ => break,
+        }
+        fmt = rest;
+    }
+
+    if let Some(rest) = fmt.strip_prefix(b"*") {
+        let width = star(args);
+        spec.left |= width < 0;
+        spec.width = width.unsigned_abs() as usize;
+        fmt = rest;
+    } else {
+        (spec.width, fmt) = number(fmt);
+    }
+
+    if let Some(rest) = fmt.strip_prefix(b".") {
+        if let Some(rest) = rest.strip_prefix(b"*") {
+            // a negative precision is taken as if omitted
+            spec.precision = usize::try_from(star(args)).ok();
+            fmt = rest;
+        } else {
+            let precision;
+            (precision, fmt) = number(rest);
+            spec.precision = Some(precision);
+        }
+    }
+
+    for (modifier, length) in [
+        (&b"hh"[..], Length::Char),
+        (b"h", Length::Short),
+        (b"ll", Length::LongLong),
+        (b"l", Length::Long),
+        (b"j", Length::Max),
+        (b"z", Length::Size),
+        (b"t", Length::Ptrdiff),
+        (b"L", Length::LongDouble),
+    ] {
+        if let Some(rest) = fmt.strip_prefix(modifier) {
+            spec.length = length;
+            fmt = rest;
+            break;
+        }
+    }
+
+    let (&conv, rest) = fmt.split_first()?;
+    match conv {
+        b'%' => sink.put(b"%"),
+        b'd' | b'i' => {
+            let value = fetch_signed(args, spec.length);
+            integer(sink, &spec, conv, value < 0, value.unsigned_abs());
+        }
+        b'u' | b'o' | b'x' | b'X' => {
+            let value = fetch_unsigned(args, spec.length);
+            integer(sink, &spec, conv, false, value);
+        }
+        b'p' => {
+            spec.length = Length::Size;
+            let value = fetch_unsigned(args, spec.length);
+            integer(sink, &spec, conv, false, value);
+        }
+        b'c' => {
+            #[allow(clippy::cast_possible_truncation)]
+            let byte = args.next_u32() as u8;
+            spec.pad(sink, false, b"", 1, |sink| sink.put(&[byte]));
+        }
+        b's' => {
+            spec.length = Length::Size;
+            #[allow(clippy::cast_possible_truncation)]
+            let ptr = fetch_unsigned(args, spec.length) as usize as *const u8;
+            let text = if ptr.is_null() {
+                &b"(null)"[..]
+            } else {
+                // SAFETY: valid string argument per the function contract
+                unsafe { c_str(ptr, spec.precision.unwrap_or(usize::MAX)) }
+            };
+            spec.pad(sink, false, b"", text.len(), |sink| sink.put(text));
+        }
+        b'f' | b'F' | b'e' | b'E' | b'g' | b'G' => {
+            float(sink, &spec, conv, args.next_f64());
+        }
+        _ => return None,
+    }
+    Some(rest)
+}
+
+/// Format `fmt` with `args`, returning the formatted length or `None` if the
+/// format is invalid.
+///
+/// # Safety
+///
+/// `args` must match the conversions of `fmt`, `%s` arguments being valid NUL
+/// terminated strings.
+unsafe fn format<S: Sink>(sink: &mut S, mut fmt: &[u8], args: &mut impl Args) -> Option<usize> {
+    let mut sink = Tally { sink, total: 0 };
+    while !fmt.is_empty() {
+        let literal = fmt.iter().take_while(|&&byte| byte != b'%').count();
+        sink.put(&fmt[..literal]);
+        fmt = &fmt[literal..];
+        if let Some(spec) = fmt.strip_prefix(b"%") {
+            // SAFETY: forwarded function contract
+            fmt = unsafe { conversion(&mut sink, spec, args) }?;
+        }
+    }
+    Some(sink.total)
+}
+
+/// Format `fmt` with `args` in `buf` as `snprintf`, returning the length of
+/// the whole formatted string or `None` if the format is invalid.
+///
+/// The output is truncated to fit in `buf` with its NUL terminator.
+///
+/// # Safety
+///
+/// `args` must match the conversions of `fmt`, `%s` arguments being pointers
+/// to valid NUL terminated strings.
+pub unsafe fn format_into(buf: &mut [u8], fmt: &[u8], args: &mut impl Args) -> Option<usize> {
+    let Some(cap) = buf.len().checked_sub(1) else {
+        // SAFETY: forwarded function contract
+        return unsafe { format(&mut Counter(0), fmt, args) };
+    };
+    let mut sink = SliceSink {
+        buf: &mut buf[..cap],
+        len: 0,
+    };
+    // SAFETY: forwarded function contract
+    let total = unsafe { format(&mut sink, fmt, args) };
+    let len = sink.len;
+    buf[len] = 0;
+    total
+}
+
+/// Sink writing to the kernel log channel, one syscall per exchange area
+/// sized chunk.
+struct LogSink {
+    buf: [u8; uapi::length()],
+    len: usize,
+}
+
+impl LogSink {
+    fn flush(&mut self) {
+        let chunk = &self.buf[..self.len];
+        if !chunk.is_empty() && crate::sys::copy_to_kernel(&chunk).is_ok() {
+            crate::sys::syscall::log(chunk.len());
+        }
+        self.len = 0;
+    }
+}
+
+impl Sink for LogSink {
+    fn put(&mut self, mut bytes: &[u8]) {
+        while !bytes.is_empty() {
+            if self.len == self.buf.len() {
+                self.flush();
+            }
+            let count = bytes.len().min(self.buf.len() - self.len);
+            self.buf[self.len..self.len + count].copy_from_slice(&bytes[..count]);
+            self.len += count;
+            bytes = &bytes[count..];
+        }
+    }
+}
+
+/// Format `fmt` with `args` to the kernel log channel, returning the
+/// formatted length or `None` if the format is invalid.
+///
+/// The output is emitted as it is formatted: if the format is invalid, the
+/// output before the invalid conversion has been written.
+///
+/// # Safety
+///
+/// See [`format_into`].
+pub unsafe fn print(fmt: &[u8], args: &mut impl Args) -> Option<usize> {
+    let mut sink = LogSink {
+        buf: [0; uapi::length()],
+        len: 0,
+    };
+    // SAFETY: forwarded function contract
+    let total = unsafe { format(&mut sink, fmt, args) };
+    sink.flush();
+    total
+}
+
+/// C `va_list`, as defined by the Arm procedure call standard: a pointer to
+/// the next argument, arguments of 64 bits being 8 bytes aligned.
+#[cfg(target_arch = "arm")]
+#[repr(transparent)]
+pub struct VaList(*const u8);
+
+#[cfg(target_arch = "arm")]
+impl Args for VaList {
+    fn next_u32(&mut self) -> u32 {
+        // SAFETY: the C caller passes as many arguments as conversions
+        let value = unsafe { self.0.cast::<u32>().read() };
+        // SAFETY: still in the argument area
+        self.0 = unsafe { self.0.add(size_of::<u32>()) };
+        value
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let offset = self.0.align_offset(size_of::<u64>());
+        // SAFETY: the C caller passes as many arguments as conversions
+        let value = unsafe { self.0.add(offset).cast::<u64>().read() };
+        // SAFETY: still in the argument area
+        self.0 = unsafe { self.0.add(offset + size_of::<u64>()) };
+        value
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        f64::from_bits(self.next_u64())
+    }
+}
+
+/// Convert a formatted length to the C return value, `-1` on error, `errno`
+/// being set to `EINVAL`.
+#[cfg(target_arch = "arm")]
+fn c_len(total: Option<usize>) -> core::ffi::c_int {
+    total
+        .and_then(|total| core::ffi::c_int::try_from(total).ok())
+        .unwrap_or_else(|| {
+            crate::errno::set(crate::errno::Errno::EINVAL);
+            -1
+        })
+}
+
+/// Format `fmt` with `ap` in `dest`, as ISO C `vsnprintf`.
+///
+/// # Safety
+///
+/// `dest` must be valid for `len` bytes writes, `fmt` must be a NUL
+/// terminated string and `ap` must match its conversions.
+#[cfg(target_arch = "arm")]
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn vsnprintf(
+    dest: *mut core::ffi::c_char,
+    len: usize,
+    fmt: *const core::ffi::c_char,
+    mut ap: VaList,
+) -> core::ffi::c_int {
+    if fmt.is_null() || (dest.is_null() && len != 0) {
+        return c_len(None);
+    }
+    // SAFETY: valid per the function contract
+    let fmt = unsafe { c_str(fmt.cast(), usize::MAX) };
+    let buf = if len == 0 {
+        &mut [][..]
+    } else {
+        // SAFETY: valid per the function contract
+        unsafe { core::slice::from_raw_parts_mut(dest.cast(), len) }
+    };
+    // SAFETY: forwarded function contract
+    c_len(unsafe { format_into(buf, fmt, &mut ap) })
+}
+
+/// Format `fmt` with `ap` to the kernel log channel, as ISO C `vprintf`.
+///
+/// # Safety
+///
+/// `fmt` must be a NUL terminated string and `ap` must match its
+/// conversions.
+#[cfg(target_arch = "arm")]
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn vprintf(
+    fmt: *const core::ffi::c_char,
+    mut ap: VaList,
+) -> core::ffi::c_int {
+    if fmt.is_null() {
+        return c_len(None);
+    }
+    // SAFETY: valid per the function contract
+    let fmt = unsafe { c_str(fmt.cast(), usize::MAX) };
+    // SAFETY: forwarded function contract
+    c_len(unsafe { print(fmt, &mut ap) })
+}
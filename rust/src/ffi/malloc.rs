@@ -0,0 +1,132 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! C heap functions
+//!
+//! `malloc` and friends are thin wrappers over the Rust global allocator, the
+//! task [`crate::heap::Heap`] on the target, so that C and Rust code of a task
+//! share the same heap. As `free` is not given the block size, each block is
+//! prefixed by a header holding it.
+//!
+//! As mandated by ISO C, failed allocations set `errno` to `ENOMEM`.
+
+use alloc::alloc::{Layout, alloc, alloc_zeroed, dealloc, realloc as rust_realloc};
+use core::ffi::c_void;
+use core::ptr;
+
+use crate::errno::{self, Errno};
+
+/// Alignment of the returned blocks, as C `max_align_t`
+const ALIGN: usize = 2 * size_of::<usize>();
+
+/// Block header size, keeping the returned blocks aligned
+const HEADER: usize = ALIGN;
+
+/// Set `errno` to `ENOMEM`, returning the null C block pointer.
+fn out_of_memory() -> *mut c_void {
+    errno::set(Errno::ENOMEM);
+    ptr::null_mut()
+}
+
+fn layout(size: usize) -> Option<Layout> {
+    Layout::from_size_align(size.checked_add(HEADER)?, ALIGN).ok()
+}
+
+/// Store the block size in its header, returning the C block pointer, null
+/// if the allocation failed.
+///
+/// # Safety
+///
+/// `block` must be null or a block allocated with `layout(size)`.
+unsafe fn init_block(block: *mut u8, size: usize) -> *mut c_void {
+    if block.is_null() {
+        return out_of_memory();
+    }
+    // SAFETY: the block starts with its header, aligned for usize
+    unsafe {
+        block.cast::<usize>().write(size);
+        block.add(HEADER).cast()
+    }
+}
+
+/// Return the allocated block and its C size from the C block pointer.
+///
+/// # Safety
+///
+/// `ptr` must have been returned by this module allocation functions.
+unsafe fn block(ptr: *mut c_void) -> (*mut u8, usize) {
+    // SAFETY: the header precedes the C block
+    unsafe {
+        let block = ptr.cast::<u8>().sub(HEADER);
+        (block, block.cast::<usize>().read())
+    }
+}
+
+/// Allocate `size` bytes, returning null on failure.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub extern "C" fn malloc(size: usize) -> *mut c_void {
+    match layout(size) {
+        // SAFETY: non zero layout size, thanks to the header
+        Some(layout) => unsafe { init_block(alloc(layout), size) },
+        None => out_of_memory(),
+    }
+}
+
+/// Allocate a zeroed array of `count` elements of `size` bytes, returning
+/// null on failure.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub extern "C" fn calloc(count: usize, size: usize) -> *mut c_void {
+    match count
+        .checked_mul(size)
+        .and_then(|total| Some((total, layout(total)?)))
+    {
+        // SAFETY: non zero layout size, thanks to the header
+        Some((total, layout)) => unsafe { init_block(alloc_zeroed(layout), total) },
+        None => out_of_memory(),
+    }
+}
+
+/// Resize the block `ptr` to `size` bytes, keeping its content, returning
+/// null on failure, `ptr` being left untouched.
+///
+/// # Safety
+///
+/// `ptr` must be null or a block returned by the `malloc` family and not
+/// freed yet.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn realloc(ptr: *mut c_void, size: usize) -> *mut c_void {
+    if ptr.is_null() {
+        return malloc(size);
+    }
+    let Some(new_layout) = layout(size) else {
+        return out_of_memory();
+    };
+    // SAFETY: valid per the function contract
+    unsafe {
+        let (block, old_size) = block(ptr);
+        let old_layout = Layout::from_size_align_unchecked(old_size + HEADER, ALIGN);
+        init_block(rust_realloc(block, old_layout, new_layout.size()), size)
+    }
+}
+
+/// Release the block `ptr`, null being ignored.
+///
+/// # Safety
+///
+/// `ptr` must be null or a block returned by the `malloc` family and not
+/// freed yet.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn free(ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+    // SAFETY: valid per the function contract
+    unsafe {
+        let (block, size) = block(ptr);
+        dealloc(
+            block,
+            Layout::from_size_align_unchecked(size + HEADER, ALIGN),
+        );
+    }
+}
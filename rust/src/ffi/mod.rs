@@ -0,0 +1,56 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! C ABI entry points
+//!
+//! C code sharing a task with Rust code calls the Shield API through these
+//! functions, getting the same checked behavior as Rust callers instead of
+//! issuing raw syscalls. Their declarations, as the POSIX types of the C
+//! library headers, are generated with `cbindgen` (see the `cbindgen`
+//! directory).
+//!
+//! Fallible functions return the register encoded kernel [`Status`] of the
+//! operation, `0` (`STATUS_OK`) meaning success. On failure, all functions
+//! also set the task `errno` (see [`crate::errno`]) from the status, or as
+//! mandated by ISO C for the C library ones.
+//!
+//! C library functions (`rand`, `memcpy`...) are exported under their standard name on
+//! the target only, so that host tests against the fake kernel don't override
+//! the host C library. Their declarations are the Shield C library ones.
+//!
+//! [`Status`]: uapi::systypes::Status
+
+use uapi::systypes::Status;
+
+use crate::errno::{self, Errno};
+use crate::error::Error;
+
+pub mod fmt;
+#[cfg(feature = "heap")]
+pub mod malloc;
+#[cfg(feature = "newlib")]
+pub mod newlib;
+pub mod rand;
+pub mod random;
+#[cfg(feature = "shm")]
+pub mod shm;
+pub mod string;
+pub mod time;
+pub mod types;
+
+/// Return the register encoded status of `result`, setting `errno` on failure.
+#[cfg_attr(not(feature = "shm"), allow(dead_code))]
+pub(crate) fn status(result: Result<(), Error>) -> u32 {
+    match result {
+        Ok(()) => Status::Ok as u32,
+        Err(err) => fail(err.status()),
+    }
+}
+
+/// Set `errno` from the failure `status`, returning it register encoded.
+#[cfg_attr(not(feature = "shm"), allow(dead_code))]
+pub(crate) fn fail(status: Status) -> u32 {
+    errno::set(Errno::from(status));
+    status as u32
+}
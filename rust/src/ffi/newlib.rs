@@ -0,0 +1,303 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! newlib system call stubs
+//!
+//! C code linked against newlib instead of the Shield C library needs these
+//! low level functions. Only the standard streams exist: the standard output
+//! and error are written to the kernel log channel, and the standard input is
+//! always at its end. Besides the usual `_write`, `_read`, `_sbrk`, `_close`,
+//! `_fstat` and `_exit`, `_isatty` and `_lseek` are provided as newlib stdio
+//! requires them.
+//!
+//! The stubs report their errors through the newlib `errno`, with the newlib
+//! values, not through the Shield one (see [`crate::errno`]). On the host, the
+//! newlib `errno` is stood in for, and read with [`errno`].
+//!
+//! `_sbrk` grows the program break over the task heap region.
+//!
+//! > **WARNING**: with the `heap` feature, the task heap region is owned by
+//! > the [`crate::heap`] allocator, which also serves the C `malloc` family:
+//! > `_sbrk` then **always fails** with `ENOMEM`, and so does the newlib
+//! > `malloc` if the task links it instead of the Shield one.
+
+use core::ffi::{c_int, c_void};
+use uapi::systypes::Status;
+
+use super::types::{mode_t, off_t};
+
+/// Character device file type, as defined by newlib `<sys/stat.h>`
+pub const S_IFCHR: mode_t = 0o020_000;
+
+/// Operation not permitted, as defined by newlib `<sys/errno.h>`
+pub const EPERM: c_int = 1;
+/// Interrupted system call
+pub const EINTR: c_int = 4;
+/// I/O error
+pub const EIO: c_int = 5;
+/// Bad file number
+pub const EBADF: c_int = 9;
+/// No more processes
+pub const EAGAIN: c_int = 11;
+/// Not enough space
+pub const ENOMEM: c_int = 12;
+/// Bad address
+pub const EFAULT: c_int = 14;
+/// Device or resource busy
+pub const EBUSY: c_int = 16;
+/// Invalid argument
+pub const EINVAL: c_int = 22;
+/// Illegal seek
+pub const ESPIPE: c_int = 29;
+
+#[cfg(target_os = "none")]
+fn set_errno(errno: c_int) {
+    unsafe extern "C" {
+        fn __errno() -> *mut c_int;
+    }
+    // SAFETY: newlib returns the errno location of the current reentrancy
+    // structure
+    unsafe { __errno().write(errno) };
+}
+
+/// Host stand-in of the newlib `errno`
+#[cfg(not(target_os = "none"))]
+static ERRNO: core::sync::atomic::AtomicI32 = core::sync::atomic::AtomicI32::new(0);
+
+#[cfg(not(target_os = "none"))]
+fn set_errno(errno: c_int) {
+    ERRNO.store(errno, core::sync::atomic::Ordering::Relaxed);
+}
+
+/// Return the `errno` last set by the stubs, newlib being out of reach on the
+/// host.
+#[cfg(not(target_os = "none"))]
+pub fn errno() -> c_int {
+    ERRNO.load(core::sync::atomic::Ordering::Relaxed)
+}
+
+/// Return the newlib `errno` value matching `status`.
+fn status_errno(status: Status) -> c_int {
+    match status {
+        Status::Invalid => EINVAL,
+        Status::Denied => EPERM,
+        Status::Busy => EBUSY,
+        Status::Again => EAGAIN,
+        Status::Intr => EINTR,
+        _ => EIO,
+    }
+}
+
+/// Leading fields of the newlib `struct stat`, the only ones set by [`_fstat`]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StatHead {
+    /// Device
+    pub st_dev: i16,
+    /// Inode
+    pub st_ino: u16,
+    /// File type and mode
+    pub st_mode: mode_t,
+}
+
+fn is_stdio(fd: c_int) -> bool {
+    (0..=2).contains(&fd)
+}
+
+/// Set `errno`, returning the C error indicator.
+fn fail(errno: c_int) -> c_int {
+    set_errno(errno);
+    -1
+}
+
+/// Write `len` bytes of `buf` to the kernel log channel, for the standard
+/// output and error.
+///
+/// # Safety
+///
+/// `buf` must be valid for `len` bytes reads.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn _write(fd: c_int, buf: *const c_void, len: usize) -> c_int {
+    if fd != 1 && fd != 2 {
+        return fail(EBADF);
+    }
+    let Ok(written) = c_int::try_from(len) else {
+        return fail(EINVAL);
+    };
+    if len == 0 {
+        return 0;
+    }
+    if buf.is_null() {
+        return fail(EFAULT);
+    }
+    // SAFETY: checked non null, valid per the function contract
+    let buf = unsafe { core::slice::from_raw_parts(buf.cast::<u8>(), len) };
+    for chunk in buf.chunks(uapi::length()) {
+        match crate::sys::copy_to_kernel(&chunk) {
+            Ok(Status::Ok) => {}
+            Ok(status) | Err(status) => return fail(status_errno(status)),
+        }
+        crate::sys::syscall::log(chunk.len());
+    }
+    written
+}
+
+/// Read from the standard input, which is always at its end.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub extern "C" fn _read(fd: c_int, _buf: *mut c_void, _len: usize) -> c_int {
+    if fd == 0 { 0 } else { fail(EBADF) }
+}
+
+/// Close `fd`, the standard streams being never closed.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub extern "C" fn _close(fd: c_int) -> c_int {
+    if is_stdio(fd) { 0 } else { fail(EBADF) }
+}
+
+/// Get the `fd` file status, the standard streams being character devices.
+///
+/// Only the file type and mode are set.
+///
+/// # Safety
+///
+/// `st` must be null or point to a newlib `struct stat`.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn _fstat(fd: c_int, st: *mut StatHead) -> c_int {
+    if !is_stdio(fd) {
+        return fail(EBADF);
+    }
+    // SAFETY: valid per the function contract
+    match unsafe { st.as_mut() } {
+        Some(st) => {
+            st.st_mode = S_IFCHR;
+            0
+        }
+        None => fail(EFAULT),
+    }
+}
+
+/// Return whether `fd` is a terminal, as the standard streams are.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub extern "C" fn _isatty(fd: c_int) -> c_int {
+    if is_stdio(fd) {
+        1
+    } else {
+        set_errno(EBADF);
+        0
+    }
+}
+
+/// Reposition `fd`, which the standard streams don't support.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub extern "C" fn _lseek(fd: c_int, _offset: off_t, _whence: c_int) -> off_t {
+    set_errno(if is_stdio(fd) { ESPIPE } else { EBADF });
+    -1
+}
+
+/// Terminate the task with `status`.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub extern "C" fn _exit(status: c_int) -> ! {
+    loop {
+        crate::sys::syscall::exit(status);
+    }
+}
+
+#[cfg(not(feature = "heap"))]
+mod brk {
+    use core::cell::UnsafeCell;
+    use core::ffi::c_void;
+    use core::ptr;
+
+    struct Break {
+        start: *mut u8,
+        len: usize,
+        /// Current break offset from `start`
+        current: usize,
+        initialized: bool,
+    }
+
+    struct BreakCell(UnsafeCell<Break>);
+
+    // SAFETY: a Sentry task is single-threaded, and the break is never
+    // borrowed across calls of `with_break`
+    unsafe impl Sync for BreakCell {}
+
+    static BREAK: BreakCell = BreakCell(UnsafeCell::new(Break {
+        start: ptr::null_mut(),
+        len: 0,
+        current: 0,
+        initialized: false,
+    }));
+
+    /// Execute `f` with an exclusive access to the break, initializing it
+    /// over the task heap region if needed.
+    fn with_break<R>(f: impl FnOnce(&mut Break) -> R) -> R {
+        // SAFETY: see BreakCell, `f` doesn't reach `with_break`
+        let brk = unsafe { &mut *BREAK.0.get() };
+        #[cfg(target_os = "none")]
+        if !brk.initialized {
+            unsafe extern "C" {
+                static mut _sheap: u8;
+                static mut _eheap: u8;
+            }
+            let start = &raw mut _sheap;
+            let end = &raw mut _eheap;
+            *brk = Break {
+                start,
+                len: end.addr() - start.addr(),
+                current: 0,
+                initialized: true,
+            };
+        }
+        f(brk)
+    }
+
+    /// Set the region grown by [`_sbrk`] to the `len` bytes at `start`,
+    /// instead of the task heap region, the break being reset to `start`.
+    ///
+    /// # Safety
+    ///
+    /// The region must be valid for reads and writes and unused for the
+    /// lifetime of the task, and no memory returned by [`_sbrk`] may be in
+    /// use.
+    pub unsafe fn set_sbrk_region(start: *mut u8, len: usize) {
+        with_break(|brk| {
+            *brk = Break {
+                start,
+                len,
+                current: 0,
+                initialized: true,
+            };
+        });
+    }
+
+    /// Move the program break by `incr` bytes, returning the previous break,
+    /// or `(void *)-1` if the region is exhausted.
+    #[cfg_attr(target_os = "none", unsafe(no_mangle))]
+    pub extern "C" fn _sbrk(incr: isize) -> *mut c_void {
+        with_break(|brk| match brk.current.checked_add_signed(incr) {
+            Some(current) if brk.initialized && current <= brk.len => {
+                let previous = brk.start.wrapping_add(brk.current);
+                brk.current = current;
+                previous.cast()
+            }
+            _ => {
+                super::set_errno(super::ENOMEM);
+                ptr::without_provenance_mut(usize::MAX)
+            }
+        })
+    }
+}
+
+#[cfg(not(feature = "heap"))]
+pub use brk::{_sbrk, set_sbrk_region};
+
+/// Always fail with `ENOMEM`, the task heap region being owned by
+/// [`crate::heap`], see the [module](self) documentation.
+#[cfg(feature = "heap")]
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub extern "C" fn _sbrk(_incr: isize) -> *mut c_void {
+    set_errno(ENOMEM);
+    core::ptr::without_provenance_mut(usize::MAX)
+}
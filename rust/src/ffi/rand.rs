@@ -0,0 +1,76 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! ISO C pseudo random generator
+//!
+//! As the Shield C library one, this is a linear congruential generator using
+//! the GNU libc parameters, and is **not** cryptographically secure. It is
+//! seeded at startup from the kernel TRNG, or from the SSP seed given by the
+//! kernel if the task is not allowed to use the TRNG.
+//!
+//! The libShield ones are left out with `CONFIG_WITH_RUST_RAND`, the symbols
+//! clashing otherwise.
+
+use core::ffi::{c_int, c_uint};
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// Maximum value returned by [`rand`], as defined by `<shield/stdlib.h>`
+pub const RAND_MAX: c_int = (1 << 30) - 1;
+
+/// Generator state, `1` until seeded as mandated by ISO C
+static SEED: AtomicU32 = AtomicU32::new(1);
+
+const fn next(seed: c_uint) -> c_uint {
+    seed.wrapping_mul(1_103_515_245).wrapping_add(12345)
+}
+
+#[allow(clippy::cast_possible_wrap)]
+const fn value(seed: c_uint) -> c_int {
+    ((seed / 65536) % (RAND_MAX as c_uint + 1)) as c_int
+}
+
+/// Seed the generator from the kernel TRNG, or from `fallback` if it can't be
+/// used.
+#[cfg(not(any(feature = "mock", feature = "sim")))]
+pub(crate) fn init(fallback: u32) {
+    SEED.store(
+        crate::random::random_u32().unwrap_or(fallback),
+        Ordering::Relaxed,
+    );
+}
+
+/// Return a pseudo random value between 0 and [`RAND_MAX`].
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub extern "C" fn rand() -> c_int {
+    // a Sentry task being single-threaded, no atomic update is required
+    let seed = next(SEED.load(Ordering::Relaxed));
+    SEED.store(seed, Ordering::Relaxed);
+    value(seed)
+}
+
+/// Seed the generator used by [`rand`].
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub extern "C" fn srand(seed: c_uint) {
+    SEED.store(seed, Ordering::Relaxed);
+}
+
+/// Return a pseudo random value between 0 and [`RAND_MAX`], the generator
+/// state being held by the caller.
+///
+/// Returns `0` if `seedp` is null.
+///
+/// # Safety
+///
+/// `seedp` must be null or valid for reads and writes.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn rand_r(seedp: *mut c_uint) -> c_int {
+    // SAFETY: valid per the function contract
+    match unsafe { seedp.as_mut() } {
+        Some(seed) => {
+            *seed = next(*seed);
+            value(*seed)
+        }
+        None => 0,
+    }
+}
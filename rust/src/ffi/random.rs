@@ -0,0 +1,100 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Linux `getrandom`
+//!
+//! Random bytes come from the kernel TRNG through [`crate::random`], 32 bits
+//! per syscall. A TRNG not ready yet (`Status::Again`) is retried after
+//! yielding, up to [`RETRIES`] times per word, unless `GRND_NONBLOCK` is
+//! given.
+//!
+//! The TRNG is never silently replaced by a weaker source: a task not allowed
+//! to use it gets `EPERM`, unless `GRND_INSECURE` is given, the buffer being
+//! then filled by the ISO C [`rand`] generator.
+
+use core::ffi::{c_uint, c_void};
+use uapi::systypes::Status;
+
+use super::rand::rand;
+use super::types::ssize_t;
+use crate::errno::{self, Errno};
+use crate::random::random_u32;
+
+/// Don't wait for the TRNG, as defined by `<shield/sys/random.h>`
+pub const GRND_NONBLOCK: c_uint = 0x1;
+/// Use the blocking pool, as defined by `<shield/sys/random.h>`, the TRNG
+/// having a single pool
+pub const GRND_RANDOM: c_uint = 0x2;
+/// Accept non cryptographic random bytes, as defined by
+/// `<shield/sys/random.h>`
+pub const GRND_INSECURE: c_uint = 0x4;
+
+/// TRNG retries per word while it is not ready
+pub const RETRIES: u32 = 8;
+
+/// Return a random word, as requested by the `getrandom` `flags`.
+fn random_word(flags: c_uint) -> Result<u32, Errno> {
+    let mut retries = if flags & GRND_NONBLOCK == 0 {
+        RETRIES
+    } else {
+        0
+    };
+    loop {
+        match random_u32() {
+            Ok(word) => return Ok(word),
+            Err(err) if err.status() == Status::Again && retries != 0 => {
+                retries -= 1;
+                crate::sys::syscall::sched_yield();
+            }
+            Err(err) if err.status() == Status::Denied && flags & GRND_INSECURE != 0 => {
+                return Ok((rand().cast_unsigned() << 16) ^ rand().cast_unsigned());
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+/// Fill `buf` with `buflen` random bytes, as Linux `getrandom`.
+///
+/// Returns the number of filled bytes, which is less than `buflen` if the
+/// TRNG failed after the first word, or `-1` if it failed on the first one.
+///
+/// # Safety
+///
+/// `buf` must be null or valid for `buflen` bytes writes.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn getrandom(buf: *mut c_void, buflen: usize, flags: c_uint) -> ssize_t {
+    if flags & !(GRND_NONBLOCK | GRND_RANDOM | GRND_INSECURE) != 0
+        || flags & (GRND_RANDOM | GRND_INSECURE) == GRND_RANDOM | GRND_INSECURE
+    {
+        errno::set(Errno::EINVAL);
+        return -1;
+    }
+    if buflen == 0 {
+        return 0;
+    }
+    if buf.is_null() {
+        errno::set(Errno::EFAULT);
+        return -1;
+    }
+
+    let len = buflen.min(ssize_t::MAX as usize);
+    // SAFETY: checked non null, valid per the function contract
+    let buf = unsafe { core::slice::from_raw_parts_mut(buf.cast::<u8>(), len) };
+    let mut filled = 0;
+    for chunk in buf.chunks_mut(size_of::<u32>()) {
+        match random_word(flags) {
+            Ok(word) => {
+                chunk.copy_from_slice(&word.to_ne_bytes()[..chunk.len()]);
+                filled += chunk.len();
+            }
+            Err(errno) if filled == 0 => {
+                errno::set(errno);
+                return -1;
+            }
+            Err(_) => break,
+        }
+    }
+    filled as ssize_t
+}
@@ -0,0 +1,183 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Shared memory C API
+//!
+//! The [`Shm`] typestate can't cross the C ABI, so the mapping state is kept
+//! in the caller owned [`ShieldShm`] descriptor and checked on each call:
+//! invalid transitions fail with no syscall issued.
+
+use uapi::systypes::Status;
+
+use super::{fail, status};
+use crate::error::{Error, Subsystem};
+use crate::shm::{Mapped, Shm, Unmapped};
+
+/// Shared memory descriptor, filled by [`shield_shm_get`]
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ShieldShm {
+    /// Kernel shared memory handle
+    pub handle: u32,
+    /// Shared memory label
+    pub label: u32,
+    /// Whether the shared memory is mapped in the task address space
+    pub mapped: bool,
+}
+
+/// Shared memory information, filled by [`shield_shm_get_info`]
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ShieldShmInfo {
+    /// Shared memory label
+    pub label: u32,
+    /// Kernel shared memory handle
+    pub handle: u32,
+    /// Base address of the shared memory
+    pub base: usize,
+    /// Length of the shared memory, in bytes
+    pub len: usize,
+    /// Permission mask of the current task
+    pub perms: u32,
+}
+
+/// Run `f` on the descriptor pointed to by `shm`, failing with
+/// `Status::Invalid` if it is null.
+///
+/// # Safety
+///
+/// `shm` must be null or valid for reads and writes.
+unsafe fn with_shm(
+    shm: *mut ShieldShm,
+    f: impl FnOnce(&mut ShieldShm) -> Result<(), Error>,
+) -> u32 {
+    // SAFETY: valid per the function contract
+    match unsafe { shm.as_mut() } {
+        Some(shm) => status(f(shm)),
+        None => fail(Status::Invalid),
+    }
+}
+
+/// Get the shared memory `label` handle into `shm`, in the unmapped state.
+///
+/// # Safety
+///
+/// `shm` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn shield_shm_get(label: u32, shm: *mut ShieldShm) -> u32 {
+    if shm.is_null() {
+        return fail(Status::Invalid);
+    }
+    status(Shm::<Unmapped>::fetch_handle(label).map(|handle| {
+        // SAFETY: checked non null, valid per the function contract
+        unsafe {
+            shm.write(ShieldShm {
+                handle,
+                label,
+                mapped: false,
+            });
+        }
+    }))
+}
+
+/// Map the shared memory in the task address space.
+///
+/// Fails with `Status::AlreadyMapped` if the shared memory is already mapped.
+///
+/// # Safety
+///
+/// `shm` must be null or point to a descriptor filled by [`shield_shm_get`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn shield_shm_map(shm: *mut ShieldShm) -> u32 {
+    // SAFETY: forwarded function contract
+    unsafe {
+        with_shm(shm, |shm| {
+            if shm.mapped {
+                return Err(
+                    Error::new(Subsystem::Shm, Status::AlreadyMapped).with_handle(shm.handle)
+                );
+            }
+            Shm::<Unmapped>::from_raw(shm.handle, shm.label).map(0)?;
+            shm.mapped = true;
+            Ok(())
+        })
+    }
+}
+
+/// Unmap the shared memory from the task address space.
+///
+/// Fails with `Status::Invalid` if the shared memory is not mapped.
+///
+/// # Safety
+///
+/// `shm` must be null or point to a descriptor filled by [`shield_shm_get`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn shield_shm_unmap(shm: *mut ShieldShm) -> u32 {
+    // SAFETY: forwarded function contract
+    unsafe {
+        with_shm(shm, |shm| {
+            if !shm.mapped {
+                return Err(Error::new(Subsystem::Shm, Status::Invalid).with_handle(shm.handle));
+            }
+            Shm::<Mapped>::from_raw(shm.handle, shm.label).unmap()?;
+            shm.mapped = false;
+            Ok(())
+        })
+    }
+}
+
+/// Set the permissions `perms` of the task `to_task` on the shared memory.
+///
+/// As for [`Shm::set_credentials`], this is only valid while the shared memory
+/// is unmapped, failing with `Status::AlreadyMapped` otherwise.
+///
+/// # Safety
+///
+/// `shm` must be null or point to a descriptor filled by [`shield_shm_get`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn shield_shm_set_creds(
+    shm: *mut ShieldShm,
+    to_task: u32,
+    perms: u32,
+) -> u32 {
+    // SAFETY: forwarded function contract
+    unsafe {
+        with_shm(shm, |shm| {
+            if shm.mapped {
+                return Err(
+                    Error::new(Subsystem::Shm, Status::AlreadyMapped).with_handle(shm.handle)
+                );
+            }
+            Shm::<Unmapped>::from_raw(shm.handle, shm.label).set_credentials(to_task, perms)
+        })
+    }
+}
+
+/// Get the shared memory information into `info`.
+///
+/// # Safety
+///
+/// `shm` must be null or point to a descriptor filled by [`shield_shm_get`],
+/// `info` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn shield_shm_get_info(shm: *mut ShieldShm, info: *mut ShieldShmInfo) -> u32 {
+    if info.is_null() {
+        return fail(Status::Invalid);
+    }
+    // SAFETY: forwarded function contract
+    unsafe {
+        with_shm(shm, |shm| {
+            let raw = *Shm::<Unmapped>::from_raw(shm.handle, shm.label).refresh_info()?;
+            // SAFETY: checked non null, valid per the function contract
+            info.write(ShieldShmInfo {
+                label: raw.label,
+                handle: raw.handle,
+                base: raw.base,
+                len: raw.len,
+                perms: raw.perms,
+            });
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,419 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! C memory and string functions
+//!
+//! The `mem*` functions move whole words once the destination is aligned,
+//! four words per iteration, the source being read with unaligned loads if
+//! needed: shared memory copies run at the bus speed instead of one byte per
+//! iteration. `strlen` and `strnlen` scan aligned words for the terminator.
+//!
+//! As these functions are also the targets of the compiler generated calls,
+//! they follow the ISO C semantic strictly: no argument check is done, and
+//! `errno` is never set.
+
+use core::ffi::{c_char, c_int, c_void};
+
+const WORD: usize = size_of::<usize>();
+
+/// `0x0101...01` word, broadcasting a byte value when multiplied by it
+const LOW: usize = usize::from_ne_bytes([0x01; WORD]);
+
+/// `0x8080...80` word, the high bit of each byte
+const HIGH: usize = LOW << 7;
+
+/// Copy `n` bytes one at a time, from the lowest address.
+///
+/// # Safety
+///
+/// `src` and `dest` must be valid for `n` bytes, `dest` not overlapping the
+/// end of `src`.
+#[inline(always)]
+unsafe fn copy_bytes(dest: *mut u8, src: *const u8, n: usize) {
+    for offset in 0..n {
+        // SAFETY: in bounds per the function contract
+        unsafe { dest.add(offset).write(src.add(offset).read()) };
+    }
+}
+
+/// Copy `words` words from the lowest address, four words per iteration,
+/// each group being read before being written.
+///
+/// The groups are scalar locals: an aggregate could be moved with a
+/// `memcpy` call in debug builds, recursing into [`memcpy`].
+///
+/// # Safety
+///
+/// `dest` must be aligned, `src` and `dest` must be valid for `words` words
+/// and `dest` must be lower than `src` if they overlap.
+#[inline(always)]
+unsafe fn copy_words_forward(
+    mut dest: *mut usize,
+    mut src: *const usize,
+    mut words: usize,
+    load: impl Fn(*const usize) -> usize,
+) {
+    // SAFETY: in bounds per the function contract
+    unsafe {
+        while words >= 4 {
+            let a = load(src);
+            let b = load(src.add(1));
+            let c = load(src.add(2));
+            let d = load(src.add(3));
+            dest.write(a);
+            dest.add(1).write(b);
+            dest.add(2).write(c);
+            dest.add(3).write(d);
+            dest = dest.add(4);
+            src = src.add(4);
+            words -= 4;
+        }
+        for index in 0..words {
+            dest.add(index).write(load(src.add(index)));
+        }
+    }
+}
+
+/// Copy `words` words from the highest address, four words per iteration,
+/// `dest` and `src` pointing past the end of the areas.
+///
+/// # Safety
+///
+/// `dest` must be aligned, the `words` words before `src` and `dest` must be
+/// valid and `dest` must be higher than `src` if they overlap.
+#[inline(always)]
+unsafe fn copy_words_backward(
+    mut dest: *mut usize,
+    mut src: *const usize,
+    mut words: usize,
+    load: impl Fn(*const usize) -> usize,
+) {
+    // SAFETY: in bounds per the function contract
+    unsafe {
+        while words >= 4 {
+            src = src.sub(4);
+            dest = dest.sub(4);
+            let a = load(src.add(3));
+            let b = load(src.add(2));
+            let c = load(src.add(1));
+            let d = load(src);
+            dest.add(3).write(a);
+            dest.add(2).write(b);
+            dest.add(1).write(c);
+            dest.write(d);
+            words -= 4;
+        }
+        for _ in 0..words {
+            src = src.sub(1);
+            dest = dest.sub(1);
+            dest.write(load(src));
+        }
+    }
+}
+
+/// Copy `n` bytes from the lowest address.
+///
+/// # Safety
+///
+/// `src` and `dest` must be valid for `n` bytes, `dest` being lower than
+/// `src` if they overlap.
+#[inline(always)]
+unsafe fn copy_forward(mut dest: *mut u8, mut src: *const u8, mut n: usize) {
+    // SAFETY: in bounds per the function contract
+    unsafe {
+        if n >= 4 * WORD {
+            let head = dest.addr().wrapping_neg() % WORD;
+            copy_bytes(dest, src, head);
+            dest = dest.add(head);
+            src = src.add(head);
+            n -= head;
+
+            let words = n / WORD;
+            if src.cast::<usize>().is_aligned() {
+                copy_words_forward(dest.cast(), src.cast(), words, |word| word.read());
+            } else {
+                copy_words_forward(dest.cast(), src.cast(), words, |word| word.read_unaligned());
+            }
+            dest = dest.add(words * WORD);
+            src = src.add(words * WORD);
+            n %= WORD;
+        }
+        copy_bytes(dest, src, n);
+    }
+}
+
+/// Copy `n` bytes from the highest address.
+///
+/// # Safety
+///
+/// `src` and `dest` must be valid for `n` bytes, `dest` being higher than
+/// `src` if they overlap.
+#[inline(always)]
+unsafe fn copy_backward(dest: *mut u8, src: *const u8, mut n: usize) {
+    // SAFETY: in bounds per the function contract
+    unsafe {
+        if n >= 4 * WORD {
+            let tail = dest.add(n).addr() % WORD;
+            for offset in (n - tail..n).rev() {
+                dest.add(offset).write(src.add(offset).read());
+            }
+            n -= tail;
+
+            let words = n / WORD;
+            let (dest_end, src_end) = (dest.add(n).cast::<usize>(), src.add(n).cast::<usize>());
+            if src_end.is_aligned() {
+                copy_words_backward(dest_end, src_end, words, |word| word.read());
+            } else {
+                copy_words_backward(dest_end, src_end, words, |word| word.read_unaligned());
+            }
+            n %= WORD;
+        }
+        for offset in (0..n).rev() {
+            dest.add(offset).write(src.add(offset).read());
+        }
+    }
+}
+
+/// Copy `n` bytes from `src` to `dest`, which must not overlap.
+///
+/// # Safety
+///
+/// `src` and `dest` must be valid for `n` bytes and must not overlap.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn memcpy(dest: *mut c_void, src: *const c_void, n: usize) -> *mut c_void {
+    // SAFETY: forwarded function contract
+    unsafe { copy_forward(dest.cast(), src.cast(), n) };
+    dest
+}
+
+/// Copy `n` bytes from `src` to `dest`, which may overlap.
+///
+/// # Safety
+///
+/// `src` and `dest` must be valid for `n` bytes.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn memmove(dest: *mut c_void, src: *const c_void, n: usize) -> *mut c_void {
+    // SAFETY: forwarded function contract, the copy direction never
+    // overwrites source bytes not copied yet
+    unsafe {
+        if dest.addr().wrapping_sub(src.addr()) >= n {
+            copy_forward(dest.cast(), src.cast(), n);
+        } else {
+            copy_backward(dest.cast(), src.cast(), n);
+        }
+    }
+    dest
+}
+
+/// Fill `n` bytes at `s` with the byte value `c`.
+///
+/// # Safety
+///
+/// `s` must be valid for `n` bytes writes.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn memset(s: *mut c_void, c: c_int, n: usize) -> *mut c_void {
+    // ISO C converts the value to unsigned char
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    let byte = c as u8;
+    let mut dest = s.cast::<u8>();
+    let mut n = n;
+    // SAFETY: in bounds per the function contract
+    unsafe {
+        if n >= 4 * WORD {
+            let head = dest.addr().wrapping_neg() % WORD;
+            for offset in 0..head {
+                dest.add(offset).write(byte);
+            }
+            dest = dest.add(head);
+            n -= head;
+
+            let word = usize::from(byte) * LOW;
+            let mut words = dest.cast::<usize>();
+            for _ in 0..n / (4 * WORD) {
+                for index in 0..4 {
+                    words.add(index).write(word);
+                }
+                words = words.add(4);
+            }
+            for _ in 0..(n / WORD) % 4 {
+                words.write(word);
+                words = words.add(1);
+            }
+            dest = words.cast();
+            n %= WORD;
+        }
+        for offset in 0..n {
+            dest.add(offset).write(byte);
+        }
+    }
+    s
+}
+
+/// Compare `n` bytes of `s1` and `s2`, returning the difference of their
+/// first differing bytes, or `0` if they are equal.
+///
+/// # Safety
+///
+/// `s1` and `s2` must be valid for `n` bytes reads.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn memcmp(s1: *const c_void, s2: *const c_void, n: usize) -> c_int {
+    let (s1, s2) = (s1.cast::<u8>(), s2.cast::<u8>());
+    let mut offset = 0;
+    // SAFETY: in bounds per the function contract
+    unsafe {
+        // skip the equal words, the first differing byte being then found in
+        // the byte loop
+        while n - offset >= WORD
+            && s1.add(offset).cast::<usize>().read_unaligned()
+                == s2.add(offset).cast::<usize>().read_unaligned()
+        {
+            offset += WORD;
+        }
+        while offset < n {
+            let (a, b) = (s1.add(offset).read(), s2.add(offset).read());
+            if a != b {
+                return c_int::from(a) - c_int::from(b);
+            }
+            offset += 1;
+        }
+    }
+    0
+}
+
+/// Return whether one of the bytes of `word` is zero.
+const fn has_zero(word: usize) -> bool {
+    word.wrapping_sub(LOW) & !word & HIGH != 0
+}
+
+/// Read the aligned word at `ptr`.
+///
+/// An aligned word never crosses a memory protection region boundary, so it
+/// can be read as a whole as soon as its first byte is readable, even when
+/// the string terminator precedes its end. The read is done in assembly, the
+/// Rust memory model not allowing to read past the end of an allocation.
+///
+/// # Safety
+///
+/// `ptr` must be aligned, its first byte being valid for reads.
+#[cfg(any(target_arch = "arm", target_arch = "x86_64"))]
+#[inline(always)]
+unsafe fn read_word(ptr: *const usize) -> usize {
+    let word: usize;
+    // SAFETY: readable per the function contract
+    unsafe {
+        #[cfg(target_arch = "arm")]
+        core::arch::asm!(
+            "ldr {word}, [{ptr}]",
+            word = out(reg) word,
+            ptr = in(reg) ptr,
+            options(nostack, preserves_flags, readonly, pure),
+        );
+        #[cfg(target_arch = "x86_64")]
+        core::arch::asm!(
+            "mov {word}, qword ptr [{ptr}]",
+            word = out(reg) word,
+            ptr = in(reg) ptr,
+            options(nostack, preserves_flags, readonly, pure),
+        );
+    }
+    word
+}
+
+/// Return the length of the string `s`, scanning at most `max` bytes.
+///
+/// # Safety
+///
+/// `s` must be valid for reads up to its terminator or `max` bytes.
+#[inline(always)]
+unsafe fn scan(s: *const u8, max: usize) -> usize {
+    let mut len = 0;
+    // SAFETY: in bounds per the function contract
+    unsafe {
+        while len < max && !s.add(len).cast::<usize>().is_aligned() {
+            if s.add(len).read() == 0 {
+                return len;
+            }
+            len += 1;
+        }
+        #[cfg(any(target_arch = "arm", target_arch = "x86_64"))]
+        while max - len >= WORD && !has_zero(read_word(s.add(len).cast())) {
+            len += WORD;
+        }
+        while len < max && s.add(len).read() != 0 {
+            len += 1;
+        }
+    }
+    len
+}
+
+/// Return the length of the string `s`.
+///
+/// # Safety
+///
+/// `s` must be a valid NUL terminated string.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn strlen(s: *const c_char) -> usize {
+    // SAFETY: forwarded function contract
+    unsafe { scan(s.cast(), usize::MAX) }
+}
+
+/// Return the length of the string `s`, at most `maxlen`.
+///
+/// # Safety
+///
+/// `s` must be valid for reads up to its terminator or `maxlen` bytes.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn strnlen(s: *const c_char, maxlen: usize) -> usize {
+    // SAFETY: forwarded function contract
+    unsafe { scan(s.cast(), maxlen) }
+}
+
+/// Compare at most `n` characters of the strings `s1` and `s2`, returning
+/// the difference of their first differing characters, as unsigned char, or
+/// `0` if they are equal.
+///
+/// # Safety
+///
+/// `s1` and `s2` must be valid for reads up to their terminator or `n`
+/// bytes.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn strncmp(s1: *const c_char, s2: *const c_char, n: usize) -> c_int {
+    let (s1, s2) = (s1.cast::<u8>(), s2.cast::<u8>());
+    for offset in 0..n {
+        // SAFETY: in bounds per the function contract, up to the first
+        // terminator
+        let (a, b) = unsafe { (s1.add(offset).read(), s2.add(offset).read()) };
+        if a != b {
+            return c_int::from(a) - c_int::from(b);
+        }
+        if a == 0 {
+            break;
+        }
+    }
+    0
+}
+
+/// Compare the strings `s1` and `s2`, as [`strncmp`] with no length limit.
+///
+/// # Safety
+///
+/// `s1` and `s2` must be valid NUL terminated strings.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn strcmp(s1: *const c_char, s2: *const c_char) -> c_int {
+    // SAFETY: forwarded function contract
+    unsafe { strncmp(s1, s2, usize::MAX) }
+}
+
+/// Copy the string `src`, including its terminator, to `dest`.
+///
+/// # Safety
+///
+/// `src` must be a valid NUL terminated string, `dest` must be valid for its
+/// length plus one bytes writes, and they must not overlap.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn strcpy(dest: *mut c_char, src: *const c_char) -> *mut c_char {
+    // SAFETY: forwarded function contract
+    unsafe { copy_forward(dest.cast(), src.cast(), strlen(src) + 1) };
+    dest
+}
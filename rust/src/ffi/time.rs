@@ -0,0 +1,144 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! C clock functions
+//!
+//! The clocks are the [`crate::time`] ones: `CLOCK_MONOTONIC` and
+//! `CLOCK_BOOTTIME` are the uptime, a Sentry task never being suspended, and
+//! `CLOCK_REALTIME` is the realtime clock. The uptime is read with a
+//! nanosecond resolution if the task is allowed to, a microsecond one
+//! otherwise.
+
+use core::ffi::{c_int, c_long};
+use uapi::systypes::{Precision, Status};
+
+use super::types::{clockid_t, time_t};
+use crate::errno::{self, Errno};
+use crate::error::Error;
+use crate::time;
+
+/// Monotonic clock, as defined by `<shield/time.h>`
+pub const CLOCK_MONOTONIC: clockid_t = 0;
+/// Realtime clock, as defined by `<shield/time.h>`
+pub const CLOCK_REALTIME: clockid_t = 1;
+/// Boot time clock, as defined by `<shield/time.h>`
+pub const CLOCK_BOOTTIME: clockid_t = 3;
+
+const NSEC_PER_SEC: u64 = 1_000_000_000;
+
+/// C `struct timespec`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timespec {
+    /// Seconds
+    pub tv_sec: time_t,
+    /// Nanoseconds, from 0 to 999 999 999
+    pub tv_nsec: c_long,
+}
+
+/// Return the uptime in nanoseconds, with a microsecond resolution if the
+/// task is not allowed to use the nanosecond one.
+fn uptime_ns() -> Result<u64, Error> {
+    match time::uptime(Precision::Nanoseconds) {
+        Err(err) if err.status() == Status::Denied => Ok(time::uptime_us()? * 1000),
+        result => result,
+    }
+}
+
+/// Return the `clockid` clock, in nanoseconds.
+fn clock_ns(clockid: clockid_t) -> Result<u64, Errno> {
+    match clockid {
+        CLOCK_MONOTONIC | CLOCK_BOOTTIME => Ok(uptime_ns()?),
+        CLOCK_REALTIME => {
+            Ok(uptime_ns()?.saturating_add_signed(time::realtime_offset_us().saturating_mul(1000)))
+        }
+        _ => Err(Errno::EINVAL),
+    }
+}
+
+/// Set `errno`, returning the C error indicator.
+fn fail(errno: Errno) -> c_int {
+    errno::set(errno);
+    -1
+}
+
+/// Get the `clockid` clock into `tp`, as POSIX `clock_gettime`.
+///
+/// # Safety
+///
+/// `tp` must be null or valid for writes.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn clock_gettime(clockid: clockid_t, tp: *mut Timespec) -> c_int {
+    if tp.is_null() {
+        return fail(Errno::EINVAL);
+    }
+    match clock_ns(clockid) {
+        Ok(now) => {
+            // SAFETY: checked non null, valid per the function contract
+            unsafe {
+                tp.write(Timespec {
+                    tv_sec: (now / NSEC_PER_SEC) as time_t,
+                    tv_nsec: (now % NSEC_PER_SEC) as c_long,
+                });
+            }
+            0
+        }
+        Err(errno) => fail(errno),
+    }
+}
+
+/// Set the `clockid` clock from `tp`, as POSIX `clock_settime`.
+///
+/// Only `CLOCK_REALTIME` can be set.
+///
+/// # Safety
+///
+/// `tp` must be null or valid for reads.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn clock_settime(clockid: clockid_t, tp: *const Timespec) -> c_int {
+    // SAFETY: valid per the function contract
+    let Some(tp) = (unsafe { tp.as_ref() }) else {
+        return fail(Errno::EINVAL);
+    };
+    let Ok(nsec) = u64::try_from(tp.tv_nsec) else {
+        return fail(Errno::EINVAL);
+    };
+    if clockid != CLOCK_REALTIME || nsec >= NSEC_PER_SEC {
+        return fail(Errno::EINVAL);
+    }
+    #[allow(clippy::useless_conversion)]
+    let now_us = u64::from(tp.tv_sec)
+        .saturating_mul(1_000_000)
+        .saturating_add(nsec / 1000);
+    match time::set_realtime_us(now_us) {
+        Ok(()) => 0,
+        Err(err) => fail(err.into()),
+    }
+}
+
+/// Return the realtime clock in seconds since the Unix epoch, also stored in
+/// `tloc` if not null, as ISO C `time`.
+///
+/// Returns `(time_t)-1` on error.
+///
+/// # Safety
+///
+/// `tloc` must be null or valid for writes.
+#[cfg_attr(target_os = "none", unsafe(no_mangle))]
+pub unsafe extern "C" fn time(tloc: *mut time_t) -> time_t {
+    match clock_ns(CLOCK_REALTIME) {
+        Ok(now) => {
+            let now = (now / NSEC_PER_SEC) as time_t;
+            // SAFETY: valid per the function contract
+            if let Some(tloc) = unsafe { tloc.as_mut() } {
+                *tloc = now;
+            }
+            now
+        }
+        Err(errno) => {
+            errno::set(errno);
+            time_t::MAX
+        }
+    }
+}
@@ -0,0 +1,44 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! POSIX types
+//!
+//! This is the single definition of the POSIX types shared by the C headers
+//! and the C ABI entry points, `<shield/sys/types.h>` being generated from it.
+
+#![allow(non_camel_case_types)]
+
+use core::ffi::{c_int, c_long, c_uint, c_ulong};
+
+/// Signed size, or negative error indicator
+pub type ssize_t = c_long;
+
+/// File offset
+pub type off_t = c_long;
+
+/// Process identifier, the kernel task handle
+pub type pid_t = i32;
+
+/// IPC key, the remote task handle
+pub type key_t = u32;
+
+/// Time in seconds
+///
+/// Time64 is not supported, being considered out of embedded scope.
+pub type time_t = c_ulong;
+
+/// Clock identifier
+pub type clockid_t = c_int;
+
+/// Timer identifier, the timer creation cycle
+pub type timer_t = u64;
+
+/// Time in microseconds
+pub type useconds_t = c_uint;
+
+/// Signed time in microseconds
+pub type suseconds_t = c_long;
+
+/// File permission bits
+pub type mode_t = c_uint;
@@ -0,0 +1,233 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Async GPIO input
+//!
+//! An [`InputPin`] is a GPIO of a device owned by the task, read with the
+//! kernel GPIO syscalls, and waited for through its external interrupt line,
+//! delivered as an IRQ event. The edges triggering the interrupt are set by
+//! the device tree: both edges for the edge waits to see every transition,
+//! in which case a wait for an edge resolves on the first interrupt leaving
+//! the pin at the expected level.
+//!
+//! An [`OutputPin`] is driven with the same syscalls, e.g. as the chip select
+//! of an [`super::spi::ExclusiveDevice`].
+
+use embedded_hal::digital::{self, ErrorType};
+use embedded_hal_async::digital::Wait;
+use uapi::systypes::{DeviceHandle, Status};
+
+use crate::error::{Error, Subsystem};
+
+/// GPIO input of a device, see the [module](self) documentation
+pub struct InputPin {
+    device: DeviceHandle,
+    io: u8,
+    irq: u16,
+}
+
+impl InputPin {
+    /// Configure the GPIO `io` of `device` as declared by its pinmux, and
+    /// unmask its interrupt `irq`.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the GPIO can't be configured or the
+    /// interrupt enabled.
+    pub fn new(device: DeviceHandle, io: u8, irq: u16) -> Result<Self, Error> {
+        let pin = Self { device, io, irq };
+        pin.check(crate::sys::syscall::gpio_configure(device, io))?;
+        super::enable_irq(Subsystem::Gpio, irq)?;
+        Ok(pin)
+    }
+
+    /// Release the pin, masking its interrupt.
+    pub fn release(self) {
+        let _ = crate::sys::syscall::irq_disable(self.irq);
+    }
+
+    /// Check whether the pin is high.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the GPIO can't be read.
+    pub fn is_high(&self) -> Result<bool, Error> {
+        self.check(crate::sys::syscall::gpio_get(self.device, self.io))?;
+        let mut level = 0_u8;
+        match crate::sys::copy_from_kernel(&mut level) {
+            Ok(Status::Ok) => Ok(level != 0),
+            Ok(status) | Err(status) => Err(self.error(status)),
+        }
+    }
+
+    /// Check whether the pin is low.
+    ///
+    /// # Errors
+    /// See [`InputPin::is_high`].
+    pub fn is_low(&self) -> Result<bool, Error> {
+        self.is_high().map(|high| !high)
+    }
+
+    /// Wait for the pin to be high, returning at once if already.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the GPIO can't be read or the interrupt
+    /// wait fails.
+    pub async fn wait_for_high(&mut self) -> Result<(), Error> {
+        while !self.is_high()? {
+            super::wait_irq(Subsystem::Gpio, self.irq).await?;
+        }
+        Ok(())
+    }
+
+    /// Wait for the pin to be low, returning at once if already.
+    ///
+    /// # Errors
+    /// See [`InputPin::wait_for_high`].
+    pub async fn wait_for_low(&mut self) -> Result<(), Error> {
+        while self.is_high()? {
+            super::wait_irq(Subsystem::Gpio, self.irq).await?;
+        }
+        Ok(())
+    }
+
+    /// Wait for a low to high transition.
+    ///
+    /// # Errors
+    /// See [`InputPin::wait_for_high`].
+    pub async fn wait_for_rising_edge(&mut self) -> Result<(), Error> {
+        self.wait_for_edge(true).await
+    }
+
+    /// Wait for a high to low transition.
+    ///
+    /// # Errors
+    /// See [`InputPin::wait_for_high`].
+    pub async fn wait_for_falling_edge(&mut self) -> Result<(), Error> {
+        self.wait_for_edge(false).await
+    }
+
+    /// Wait for any transition.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the interrupt wait fails.
+    pub async fn wait_for_any_edge(&mut self) -> Result<(), Error> {
+        super::wait_irq(Subsystem::Gpio, self.irq).await
+    }
+
+    async fn wait_for_edge(&mut self, high: bool) -> Result<(), Error> {
+        loop {
+            super::wait_irq(Subsystem::Gpio, self.irq).await?;
+            if self.is_high()? == high {
+                return Ok(());
+            }
+        }
+    }
+
+    fn check(&self, status: Status) -> Result<(), Error> {
+        check(self.device, status)
+    }
+
+    fn error(&self, status: Status) -> Error {
+        error(self.device, status)
+    }
+}
+
+impl ErrorType for InputPin {
+    type Error = Error;
+}
+
+impl digital::InputPin for InputPin {
+    fn is_high(&mut self) -> Result<bool, Error> {
+        InputPin::is_high(self)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Error> {
+        InputPin::is_low(self)
+    }
+}
+
+impl Wait for InputPin {
+    async fn wait_for_high(&mut self) -> Result<(), Error> {
+        InputPin::wait_for_high(self).await
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Error> {
+        InputPin::wait_for_low(self).await
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Error> {
+        InputPin::wait_for_rising_edge(self).await
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Error> {
+        InputPin::wait_for_falling_edge(self).await
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Error> {
+        InputPin::wait_for_any_edge(self).await
+    }
+}
+
+/// GPIO output of a device, see the [module](self) documentation
+pub struct OutputPin {
+    device: DeviceHandle,
+    io: u8,
+}
+
+impl OutputPin {
+    /// Configure the GPIO `io` of `device` as declared by its pinmux.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the GPIO can't be configured.
+    pub fn new(device: DeviceHandle, io: u8) -> Result<Self, Error> {
+        check(device, crate::sys::syscall::gpio_configure(device, io))?;
+        Ok(Self { device, io })
+    }
+
+    /// Drive the pin high if `high`, low otherwise.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the GPIO can't be set.
+    pub fn set(&mut self, high: bool) -> Result<(), Error> {
+        check(
+            self.device,
+            crate::sys::syscall::gpio_set(self.device, self.io, high),
+        )
+    }
+
+    /// Invert the pin level.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the GPIO can't be toggled.
+    pub fn toggle(&mut self) -> Result<(), Error> {
+        check(
+            self.device,
+            crate::sys::syscall::gpio_toggle(self.device, self.io),
+        )
+    }
+}
+
+impl ErrorType for OutputPin {
+    type Error = Error;
+}
+
+impl digital::OutputPin for OutputPin {
+    fn set_low(&mut self) -> Result<(), Error> {
+        self.set(false)
+    }
+
+    fn set_high(&mut self) -> Result<(), Error> {
+        self.set(true)
+    }
+}
+
+fn check(device: DeviceHandle, status: Status) -> Result<(), Error> {
+    match status {
+        Status::Ok => Ok(()),
+        status => Err(error(device, status)),
+    }
+}
+
+fn error(device: DeviceHandle, status: Status) -> Error {
+    Error::new(Subsystem::Gpio, status).with_handle(device)
+}
@@ -0,0 +1,242 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Async I2C master driver
+//!
+//! [`I2c::transaction`] follows the `embedded-hal` transaction contract:
+//! adjacent operations of the same kind are merged in a single transfer, a
+//! repeated start separating the transfers of different kinds, and a stop
+//! condition ending the transaction. The controller is expected to count the
+//! bytes of a transfer and to generate the stop condition itself, as the
+//! STM32 I2C v2 `NBYTES` and `AUTOEND` do.
+//!
+//! A NACK is reported as a `Status::NoEntity` error, a lost arbitration as
+//! `Status::Busy` and a bus error as `Status::Critical`.
+
+pub use embedded_hal::i2c::Operation;
+use embedded_hal::i2c::{ErrorType, SevenBitAddress};
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+
+fn is_read(operation: &Operation<'_>) -> bool {
+    matches!(operation, Operation::Read(_))
+}
+
+fn len(operation: &Operation<'_>) -> usize {
+    match operation {
+        Operation::Read(buf) => buf.len(),
+        Operation::Write(buf) => buf.len(),
+    }
+}
+
+/// Pending condition of the I2C controller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum I2cEvent {
+    /// Nothing to handle
+    None,
+    /// A received byte can be read
+    RxReady,
+    /// A byte to transmit can be written
+    TxReady,
+    /// The bytes of the transfer have been exchanged, the stop condition
+    /// being generated if requested
+    Complete,
+    /// The address or a written byte was not acknowledged
+    Nack,
+    /// The arbitration was lost to another master
+    ArbitrationLost,
+    /// Misplaced start or stop condition
+    BusError,
+}
+
+/// Access to the registers of a mapped I2C controller
+pub trait I2cRegisters {
+    /// Start, or restart, a transfer of `len` bytes from or to the 7-bit
+    /// `address`, a stop condition ending it if `stop`.
+    fn start(&mut self, address: u8, read: bool, len: usize, stop: bool);
+
+    /// Return the pending condition, the error ones being cleared.
+    fn poll(&mut self) -> I2cEvent;
+
+    /// Read a received byte.
+    fn read_byte(&mut self) -> u8;
+
+    /// Write a byte to transmit.
+    fn write_byte(&mut self, byte: u8);
+
+    /// Generate a stop condition, ending a failed transfer.
+    fn stop(&mut self);
+
+    /// Enable the interrupts (RX not empty, TX empty, transfer complete and
+    /// errors).
+    fn enable_interrupts(&mut self, enabled: bool);
+}
+
+/// Async I2C master driver, see the [module](self) documentation
+pub struct I2c<R> {
+    regs: R,
+    irq: u16,
+}
+
+impl<R: I2cRegisters> I2c<R> {
+    /// Create the driver, unmasking the I2C interrupt.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the interrupt can't be enabled.
+    pub fn new(mut regs: R, irq: u16) -> Result<Self, Error> {
+        regs.enable_interrupts(false);
+        super::enable_irq(Subsystem::I2c, irq)?;
+        Ok(Self { regs, irq })
+    }
+
+    /// Release the I2C registers, masking the I2C interrupt.
+    pub fn release(self) -> R {
+        let _ = crate::sys::syscall::irq_disable(self.irq);
+        self.regs
+    }
+
+    /// Read `read.len()` bytes from `address`.
+    ///
+    /// # Errors
+    /// See [`I2c::transaction`].
+    pub async fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Error> {
+        self.transaction(address, &mut [Operation::Read(read)])
+            .await
+    }
+
+    /// Write `write` to `address`.
+    ///
+    /// # Errors
+    /// See [`I2c::transaction`].
+    pub async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Error> {
+        self.transaction(address, &mut [Operation::Write(write)])
+            .await
+    }
+
+    /// Write `write` to `address`, then read `read.len()` bytes from it after
+    /// a repeated start.
+    ///
+    /// # Errors
+    /// See [`I2c::transaction`].
+    pub async fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Error> {
+        self.transaction(
+            address,
+            &mut [Operation::Write(write), Operation::Read(read)],
+        )
+        .await
+    }
+
+    /// Run `operations` on `address` as a single transaction.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if `address` is not a 7-bit address,
+    /// `Status::NoEntity` if a byte was not acknowledged, `Status::Busy` if
+    /// the arbitration was lost and `Status::Critical` on bus errors, or
+    /// propagates kernel errors if the interrupt wait fails.
+    pub async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Error> {
+        if address > 0x7f {
+            return Err(self.error(Status::Invalid));
+        }
+        let mut first = 0;
+        while first < operations.len() {
+            let read = is_read(&operations[first]);
+            let end = operations[first..]
+                .iter()
+                .position(|operation| is_read(operation) != read)
+                .map_or(operations.len(), |len| first + len);
+            let len = operations[first..end].iter().map(len).sum();
+            self.regs.start(address, read, len, end == operations.len());
+            if let Err(err) = self.run(&mut operations[first..end]).await {
+                self.regs.stop();
+                return Err(err);
+            }
+            first = end;
+        }
+        Ok(())
+    }
+
+    /// Exchange the bytes of the started transfer of `operations`, then wait
+    /// for its completion.
+    async fn run(&mut self, operations: &mut [Operation<'_>]) -> Result<(), Error> {
+        for operation in operations {
+            match operation {
+                Operation::Read(buf) => {
+                    for byte in buf.iter_mut() {
+                        self.wait_for(I2cEvent::RxReady).await?;
+                        *byte = self.regs.read_byte();
+                    }
+                }
+                Operation::Write(buf) => {
+                    for &byte in buf.iter() {
+                        self.wait_for(I2cEvent::TxReady).await?;
+                        self.regs.write_byte(byte);
+                    }
+                }
+            }
+        }
+        self.wait_for(I2cEvent::Complete).await
+    }
+
+    /// Wait for the `expected` condition, failing on error conditions.
+    async fn wait_for(&mut self, expected: I2cEvent) -> Result<(), Error> {
+        loop {
+            match self.regs.poll() {
+                event if event == expected => return Ok(()),
+                I2cEvent::Nack => return Err(self.error(Status::NoEntity)),
+                I2cEvent::ArbitrationLost => return Err(self.error(Status::Busy)),
+                I2cEvent::BusError => return Err(self.error(Status::Critical)),
+                _ => {}
+            }
+            self.regs.enable_interrupts(true);
+            let waited = super::wait_irq(Subsystem::I2c, self.irq).await;
+            self.regs.enable_interrupts(false);
+            waited?;
+        }
+    }
+
+    fn error(&self, status: Status) -> Error {
+        Error::new(Subsystem::I2c, status).with_handle(u32::from(self.irq))
+    }
+}
+
+impl<R: I2cRegisters> ErrorType for I2c<R> {
+    type Error = Error;
+}
+
+impl<R: I2cRegisters> embedded_hal_async::i2c::I2c<SevenBitAddress> for I2c<R> {
+    async fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Error> {
+        I2c::read(self, address, read).await
+    }
+
+    async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Error> {
+        I2c::write(self, address, write).await
+    }
+
+    async fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Error> {
+        I2c::write_read(self, address, write, read).await
+    }
+
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Error> {
+        I2c::transaction(self, address, operations).await
+    }
+}
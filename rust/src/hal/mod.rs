@@ -0,0 +1,75 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Async SPI, I2C and GPIO drivers
+//!
+//! The [`spi::Spi`], [`i2c::I2c`] and [`gpio::InputPin`] drivers await their
+//! device interrupt, delivered by the kernel as an IRQ event, through the
+//! [`crate::executor`], so that the async sensor drivers run on the Shield
+//! executor. As for the [`crate::uart`] driver, the SoC specific registers are
+//! reached through the [`spi::SpiRegisters`] and [`i2c::I2cRegisters`]
+//! traits, implemented by the board support code.
+//!
+//! They implement the `embedded-hal-async` 1.0 traits, their inherent async
+//! methods having the same names, arguments and semantics:
+//!
+//! | driver | trait |
+//! |--------|-------|
+//! | [`spi::Spi`] | [`embedded_hal_async::spi::SpiBus<u8>`] |
+//! | [`spi::ExclusiveDevice`] | [`embedded_hal_async::spi::SpiDevice<u8>`] |
+//! | [`i2c::I2c`] | [`embedded_hal_async::i2c::I2c<SevenBitAddress>`](embedded_hal_async::i2c::I2c) |
+//! | [`gpio::InputPin`] | [`embedded_hal_async::digital::Wait`] |
+//! | [`Delay`] | [`embedded_hal_async::delay::DelayNs`] |
+//!
+//! The GPIOs also implement the blocking `embedded_hal::digital` pin traits,
+//! an [`gpio::OutputPin`] being the chip select of an
+//! [`spi::ExclusiveDevice`].
+//!
+//! ```ignore
+//! let mut spi = Spi::new(Spi1::mapped()?, SPI1_IRQ)?;
+//! let mut id = [0x9f, 0, 0, 0];
+//! spi.transfer_in_place(&mut id).await?;
+//! ```
+
+use uapi::systypes::{EventType, Status};
+
+use crate::error::{Error, Subsystem};
+use crate::executor::{self, timer};
+
+pub mod gpio;
+pub mod i2c;
+pub mod spi;
+
+/// Delay of the executor timers, see [`embedded_hal_async::delay::DelayNs`]
+///
+/// The delays are rounded up to the microsecond, the uptime resolution.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Delay;
+
+impl embedded_hal_async::delay::DelayNs for Delay {
+    async fn delay_ns(&mut self, ns: u32) {
+        let now_us = crate::time::uptime_us().unwrap_or_default();
+        timer::until(now_us + u64::from(ns.div_ceil(1000))).await;
+    }
+}
+
+/// Enable `irq`, reporting failures as `subsystem` errors.
+fn enable_irq(subsystem: Subsystem, irq: u16) -> Result<(), Error> {
+    check(subsystem, irq, crate::sys::syscall::irq_enable(irq))
+}
+
+/// Wait for `irq` and acknowledge it, reporting failures as `subsystem`
+/// errors.
+async fn wait_irq(subsystem: Subsystem, irq: u16) -> Result<(), Error> {
+    executor::wait_event_from(EventType::Irq, u32::from(irq)).await;
+    // the interrupt is masked by the kernel until acknowledged
+    check(subsystem, irq, crate::sys::syscall::irq_acknowledge(irq))
+}
+
+fn check(subsystem: Subsystem, irq: u16, status: Status) -> Result<(), Error> {
+    match status {
+        Status::Ok => Ok(()),
+        status => Err(Error::new(subsystem, status).with_handle(u32::from(irq))),
+    }
+}
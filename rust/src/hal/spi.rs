@@ -0,0 +1,267 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Async SPI bus driver
+//!
+//! [`Spi`] drives the bus in full duplex, a byte being received for each
+//! byte sent: reads send `0x00` bytes, and the bytes received while writing
+//! are discarded. Up to [`SpiRegisters::fifo_depth`] bytes are in flight, so
+//! that the receive FIFO never overruns.
+//!
+//! [`ExclusiveDevice`] adds a chip select to the bus, as the only device on
+//! it, implementing [`SpiDevice`] for the device drivers.
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal::spi::{ErrorType, Operation};
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::spi::{SpiBus, SpiDevice};
+
+use super::Delay;
+use crate::error::{Error, Subsystem};
+
+/// Byte sent by reads
+const FILL: u8 = 0x00;
+
+/// Access to the registers of a mapped SPI controller, configured as master
+pub trait SpiRegisters {
+    /// Read a received byte, if any.
+    fn try_read(&mut self) -> Option<u8>;
+
+    /// Write a byte if the transmit FIFO has room, returning whether it has
+    /// been written.
+    fn try_write(&mut self, byte: u8) -> bool;
+
+    /// Check whether all the written bytes have been shifted out.
+    fn is_idle(&self) -> bool;
+
+    /// Enable the interrupts (RX not empty, TX empty and end of transfer).
+    fn enable_interrupts(&mut self, enabled: bool);
+
+    /// Depth of the receive FIFO, in bytes.
+    fn fifo_depth(&self) -> usize {
+        1
+    }
+}
+
+/// Bytes exchanged by a transfer
+enum Buffers<'a> {
+    Split { read: &'a mut [u8], write: &'a [u8] },
+    InPlace(&'a mut [u8]),
+}
+
+impl Buffers<'_> {
+    fn len(&self) -> usize {
+        match self {
+            Self::Split { read, write } => read.len().max(write.len()),
+            Self::InPlace(words) => words.len(),
+        }
+    }
+
+    fn sent(&self, index: usize) -> u8 {
+        match self {
+            Self::Split { write, .. } => write.get(index).copied().unwrap_or(FILL),
+            Self::InPlace(words) => words[index],
+        }
+    }
+
+    fn received(&mut self, index: usize, byte: u8) {
+        match self {
+            Self::Split { read, .. } => {
+                if let Some(slot) = read.get_mut(index) {
+                    *slot = byte;
+                }
+            }
+            Self::InPlace(words) => words[index] = byte,
+        }
+    }
+}
+
+/// Async SPI bus driver, see the [module](self) documentation
+pub struct Spi<R> {
+    regs: R,
+    irq: u16,
+}
+
+impl<R: SpiRegisters> Spi<R> {
+    /// Create the driver, unmasking the SPI interrupt.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the interrupt can't be enabled.
+    pub fn new(mut regs: R, irq: u16) -> Result<Self, Error> {
+        regs.enable_interrupts(false);
+        super::enable_irq(Subsystem::Spi, irq)?;
+        Ok(Self { regs, irq })
+    }
+
+    /// Release the SPI registers, masking the SPI interrupt.
+    pub fn release(self) -> R {
+        let _ = crate::sys::syscall::irq_disable(self.irq);
+        self.regs
+    }
+
+    /// Read `words`, sending `0x00` bytes.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the interrupt wait fails.
+    pub async fn read(&mut self, words: &mut [u8]) -> Result<(), Error> {
+        self.exchange(Buffers::Split {
+            read: words,
+            write: &[],
+        })
+        .await
+    }
+
+    /// Write `words`, discarding the received bytes.
+    ///
+    /// # Errors
+    /// See [`Spi::read`].
+    pub async fn write(&mut self, words: &[u8]) -> Result<(), Error> {
+        self.exchange(Buffers::Split {
+            read: &mut [],
+            write: words,
+        })
+        .await
+    }
+
+    /// Write `write` while reading `read`, the longest one setting the
+    /// transfer length: `0x00` bytes are sent past the end of `write`, and
+    /// the bytes received past the end of `read` discarded.
+    ///
+    /// # Errors
+    /// See [`Spi::read`].
+    pub async fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Error> {
+        self.exchange(Buffers::Split { read, write }).await
+    }
+
+    /// Write `words`, replacing them by the received bytes.
+    ///
+    /// # Errors
+    /// See [`Spi::read`].
+    pub async fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Error> {
+        self.exchange(Buffers::InPlace(words)).await
+    }
+
+    /// Wait for the bus to be idle.
+    ///
+    /// # Errors
+    /// See [`Spi::read`].
+    pub async fn flush(&mut self) -> Result<(), Error> {
+        while !self.regs.is_idle() {
+            self.wait_irq().await?;
+        }
+        Ok(())
+    }
+
+    async fn exchange(&mut self, mut buffers: Buffers<'_>) -> Result<(), Error> {
+        let len = buffers.len();
+        let depth = self.regs.fifo_depth().max(1);
+        let (mut sent, mut received) = (0, 0);
+        while received < len {
+            let mut progress = false;
+            while sent < len && sent - received < depth && self.regs.try_write(buffers.sent(sent)) {
+                sent += 1;
+                progress = true;
+            }
+            while received < sent {
+                let Some(byte) = self.regs.try_read() else {
+                    break;
+                };
+                buffers.received(received, byte);
+                received += 1;
+                progress = true;
+            }
+            if !progress {
+                self.wait_irq().await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn wait_irq(&mut self) -> Result<(), Error> {
+        self.regs.enable_interrupts(true);
+        let waited = super::wait_irq(Subsystem::Spi, self.irq).await;
+        self.regs.enable_interrupts(false);
+        waited
+    }
+}
+
+impl<R: SpiRegisters> ErrorType for Spi<R> {
+    type Error = Error;
+}
+
+impl<R: SpiRegisters> SpiBus for Spi<R> {
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), Error> {
+        Spi::read(self, words).await
+    }
+
+    async fn write(&mut self, words: &[u8]) -> Result<(), Error> {
+        Spi::write(self, words).await
+    }
+
+    async fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Error> {
+        Spi::transfer(self, read, write).await
+    }
+
+    async fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Error> {
+        Spi::transfer_in_place(self, words).await
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        Spi::flush(self).await
+    }
+}
+
+/// Sole device of an SPI bus, selected by the `CS` pin, see the
+/// [module](self) documentation
+pub struct ExclusiveDevice<R, CS> {
+    spi: Spi<R>,
+    cs: CS,
+}
+
+impl<R: SpiRegisters, CS: OutputPin<Error = Error>> ExclusiveDevice<R, CS> {
+    /// Create the device, deselecting it.
+    ///
+    /// # Errors
+    /// Returns the chip select error.
+    pub fn new(spi: Spi<R>, mut cs: CS) -> Result<Self, Error> {
+        cs.set_high()?;
+        Ok(Self { spi, cs })
+    }
+
+    /// Release the bus and the chip select.
+    pub fn release(self) -> (Spi<R>, CS) {
+        (self.spi, self.cs)
+    }
+
+    async fn run(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Error> {
+        for operation in operations {
+            match operation {
+                Operation::Read(words) => self.spi.read(words).await?,
+                Operation::Write(words) => self.spi.write(words).await?,
+                Operation::Transfer(read, write) => self.spi.transfer(read, write).await?,
+                Operation::TransferInPlace(words) => self.spi.transfer_in_place(words).await?,
+                Operation::DelayNs(ns) => {
+                    self.spi.flush().await?;
+                    Delay.delay_ns(*ns).await;
+                }
+            }
+        }
+        self.spi.flush().await
+    }
+}
+
+impl<R: SpiRegisters, CS: OutputPin<Error = Error>> ErrorType for ExclusiveDevice<R, CS> {
+    type Error = Error;
+}
+
+impl<R: SpiRegisters, CS: OutputPin<Error = Error>> SpiDevice for ExclusiveDevice<R, CS> {
+    /// Select the device, run `operations` and wait for the bus to be idle,
+    /// then deselect the device, even if an operation failed.
+    async fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Error> {
+        self.cs.set_low()?;
+        let result = self.run(operations).await;
+        let deselected = self.cs.set_high();
+        result.and(deselected)
+    }
+}
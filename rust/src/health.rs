@@ -0,0 +1,178 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Subsystem heartbeats gating the hardware watchdog
+//!
+//! Each subsystem of the task is [`register`]ed with the longest delay
+//! allowed between two of its heartbeats, then calls [`beat`] from its own
+//! loop. The main loop calls [`feed`] periodically, which only feeds the
+//! hardware [`Watchdog`] while all the heartbeats are fresh: a stuck
+//! subsystem lets the watchdog reset the device, even though the main loop
+//! still runs. The name of the stale subsystem is logged first, with the
+//! `print` feature, so that the reset cause appears in the kernel log.
+//!
+//! ```ignore
+//! health::register("uart", 100)?;
+//! health::register("net", 500)?;
+//! loop {
+//!     // in the UART activity
+//!     health::beat("uart");
+//!     // ...
+//!     health::feed(&mut iwdg)?;
+//! }
+//! ```
+
+use core::cell::UnsafeCell;
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+use crate::time;
+
+/// Maximum number of registered subsystems
+pub const MAX_BEATS: usize = 8;
+
+/// Hardware watchdog, typically the independent watchdog of the MCU mapped
+/// by the task
+pub trait Watchdog {
+    /// Reload the watchdog counter.
+    fn feed(&mut self);
+}
+
+#[derive(Clone, Copy)]
+struct Beat {
+    name: &'static str,
+    timeout_ms: u32,
+    /// Uptime of the last heartbeat, in milliseconds
+    last_ms: u64,
+}
+
+struct Beats {
+    beats: [Option<Beat>; MAX_BEATS],
+    /// Stale subsystem already logged
+    reported: bool,
+}
+
+struct BeatsCell(UnsafeCell<Beats>);
+
+// SAFETY: a Sentry task is single-threaded, and the table is never borrowed
+// across calls of `with_beats`
+unsafe impl Sync for BeatsCell {}
+
+static BEATS: BeatsCell = BeatsCell(UnsafeCell::new(Beats {
+    beats: [None; MAX_BEATS],
+    reported: false,
+}));
+
+/// Execute `f` with an exclusive access to the heartbeats table
+fn with_beats<R>(f: impl FnOnce(&mut Beats) -> R) -> R {
+    // SAFETY: see BeatsCell, `f` never reaches `with_beats`
+    f(unsafe { &mut *BEATS.0.get() })
+}
+
+/// Register the subsystem `name`, whose heartbeats must be at most
+/// `timeout_ms` apart, registration counting as its first heartbeat.
+///
+/// # Errors
+/// Returns a `Status::Invalid` error if `name` is already registered, a
+/// `Status::Busy` error if [`MAX_BEATS`] subsystems are registered already,
+/// or propagates kernel errors if the uptime can't be read.
+pub fn register(name: &'static str, timeout_ms: u32) -> Result<(), Error> {
+    let now = time::uptime_ms()?;
+    with_beats(|table| {
+        if table.beats.iter().flatten().any(|beat| beat.name == name) {
+            return Err(Error::new(Subsystem::Health, Status::Invalid));
+        }
+        let slot = table
+            .beats
+            .iter_mut()
+            .find(|slot| slot.is_none())
+            .ok_or(Error::new(Subsystem::Health, Status::Busy))?;
+        *slot = Some(Beat {
+            name,
+            timeout_ms,
+            last_ms: now,
+        });
+        Ok(())
+    })
+}
+
+/// Unregister the subsystem `name`, typically when it is stopped on purpose.
+pub fn unregister(name: &str) {
+    with_beats(|table| {
+        for slot in &mut table.beats {
+            if slot.is_some_and(|beat| beat.name == name) {
+                *slot = None;
+            }
+        }
+    });
+}
+
+/// Record a heartbeat of the subsystem `name`.
+///
+/// Unregistered names are ignored, as well as heartbeats whose uptime can't
+/// be read, the subsystem then becoming stale.
+pub fn beat(name: &str) {
+    let Ok(now) = time::uptime_ms() else {
+        return;
+    };
+    with_beats(|table| {
+        if let Some(beat) = table
+            .beats
+            .iter_mut()
+            .flatten()
+            .find(|beat| beat.name == name)
+        {
+            beat.last_ms = now;
+        }
+    });
+}
+
+/// Return the first subsystem whose last heartbeat is older than its
+/// timeout, if any.
+///
+/// # Errors
+/// Propagates kernel errors if the uptime can't be read.
+pub fn stale() -> Result<Option<&'static str>, Error> {
+    let now = time::uptime_ms()?;
+    Ok(with_beats(|table| {
+        table
+            .beats
+            .iter()
+            .flatten()
+            .find(|beat| now.saturating_sub(beat.last_ms) > u64::from(beat.timeout_ms))
+            .map(|beat| beat.name)
+    }))
+}
+
+/// Feed `watchdog` if all the heartbeats are fresh, returning whether it was
+/// fed.
+///
+/// The first time a subsystem is found stale, its name is logged.
+///
+/// # Errors
+/// Propagates kernel errors if the uptime can't be read, the watchdog not
+/// being fed.
+pub fn feed(watchdog: &mut impl Watchdog) -> Result<bool, Error> {
+    let Some(name) = stale()? else {
+        with_beats(|table| table.reported = false);
+        watchdog.feed();
+        return Ok(true);
+    };
+    if !with_beats(|table| core::mem::replace(&mut table.reported, true)) {
+        #[cfg(feature = "print")]
+        crate::println!("health: {name} is stale, the watchdog is no longer fed");
+        #[cfg(not(feature = "print"))]
+        let _ = name;
+    }
+    Ok(false)
+}
+
+/// Drop all the registered subsystems, for a fresh fake kernel session.
+#[cfg(feature = "mock")]
+pub(crate) fn clear() {
+    with_beats(|table| {
+        table.beats = [None; MAX_BEATS];
+        table.reported = false;
+    });
+}
@@ -0,0 +1,261 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Task heap allocator
+//!
+//! [`Heap`] is a first-fit allocator over the task heap region, delimited by
+//! the `_sheap` and `_eheap` linker script symbols. Free blocks are kept in an
+//! address ordered list, being merged with their neighbours when released.
+//!
+//! On the target, a [`Heap`] is installed as the Rust global allocator, its
+//! usage being reported by [`stats`]. With the `ffi` feature, the C `malloc`
+//! family is built over the global allocator, so that C and Rust code of a
+//! task share the same heap and the same statistics.
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::UnsafeCell;
+use core::ptr;
+
+/// Free block header, stored at the start of the block
+struct Node {
+    size: usize,
+    next: *mut Node,
+}
+
+/// Allocation granularity: block addresses and sizes are multiples of it, so
+/// that any free block can hold its header.
+const UNIT: usize = size_of::<Node>().next_power_of_two();
+
+const fn align_up(value: usize, align: usize) -> usize {
+    (value + align - 1) & !(align - 1)
+}
+
+/// Heap usage statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeapStats {
+    /// Heap region size, in bytes
+    pub size: usize,
+    /// Allocated bytes, including the allocation granularity padding
+    pub used: usize,
+    /// Highest allocated bytes since initialization
+    pub peak: usize,
+    /// Live allocations
+    pub allocations: usize,
+    /// Failed allocation requests
+    pub failures: usize,
+}
+
+struct State {
+    /// Heap region start, giving the provenance of the blocks
+    base: *mut u8,
+    free: *mut Node,
+    initialized: bool,
+    stats: HeapStats,
+}
+
+impl State {
+    /// Block size and alignment serving `layout`
+    fn block(layout: Layout) -> (usize, usize) {
+        (
+            align_up(layout.size().max(1), UNIT),
+            layout.align().max(UNIT),
+        )
+    }
+
+    /// # Safety
+    ///
+    /// The heap must be initialized.
+    unsafe fn alloc(&mut self, layout: Layout) -> *mut u8 {
+        let (size, align) = Self::block(layout);
+        let mut prev: *mut *mut Node = &raw mut self.free;
+        // SAFETY: free list nodes are valid free blocks headers
+        unsafe {
+            while let Some(node) = (*prev).as_mut() {
+                let start = ptr::from_mut(node).addr();
+                let end = start + node.size;
+                let addr = align_up(start, align);
+                if addr + size <= end {
+                    let next = node.next;
+                    *prev = next;
+                    if addr + size != end {
+                        self.insert(addr + size, end - (addr + size));
+                    }
+                    if addr != start {
+                        self.insert(start, addr - start);
+                    }
+                    self.stats.used += size;
+                    self.stats.peak = self.stats.peak.max(self.stats.used);
+                    self.stats.allocations += 1;
+                    return self.base.with_addr(addr);
+                }
+                prev = &raw mut node.next;
+            }
+        }
+        self.stats.failures += 1;
+        ptr::null_mut()
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must have been allocated with `layout`.
+    unsafe fn dealloc(&mut self, ptr: *mut u8, layout: Layout) {
+        let (size, _) = Self::block(layout);
+        // SAFETY: the block is released to the free list
+        unsafe { self.insert(ptr.addr(), size) };
+        self.stats.used -= size;
+        self.stats.allocations -= 1;
+    }
+
+    /// Insert the free block `[addr, addr + size)` in the free list, merging
+    /// it with its neighbours.
+    ///
+    /// # Safety
+    ///
+    /// The block must be an unused part of the heap region.
+    unsafe fn insert(&mut self, addr: usize, size: usize) {
+        let mut prev: *mut Node = ptr::null_mut();
+        let mut next = self.free;
+        // SAFETY: free list nodes are valid free blocks headers
+        unsafe {
+            while !next.is_null() && next.addr() < addr {
+                prev = next;
+                next = (*next).next;
+            }
+
+            let node = self.base.with_addr(addr).cast::<Node>();
+            node.write(Node { size, next });
+            if !next.is_null() && addr + size == next.addr() {
+                (*node).size += (*next).size;
+                (*node).next = (*next).next;
+            }
+            if prev.is_null() {
+                self.free = node;
+            } else if prev.addr() + (*prev).size == addr {
+                (*prev).size += (*node).size;
+                (*prev).next = (*node).next;
+            } else {
+                (*prev).next = node;
+            }
+        }
+    }
+
+    /// # Safety
+    ///
+    /// See [`Heap::init`].
+    unsafe fn init(&mut self, start: *mut u8, len: usize) {
+        let base = align_up(start.addr(), UNIT);
+        let size = (start.addr() + len).saturating_sub(base) & !(UNIT - 1);
+        self.base = start;
+        self.free = ptr::null_mut();
+        self.stats = HeapStats {
+            size,
+            ..HeapStats::default()
+        };
+        self.initialized = true;
+        if size != 0 {
+            // SAFETY: the region is owned by the heap per the function contract
+            unsafe { self.insert(base, size) };
+        }
+    }
+}
+
+/// Task heap allocator
+pub struct Heap(UnsafeCell<State>);
+
+// SAFETY: a Sentry task is single-threaded, and the state is never borrowed
+// across calls of `with_state`
+unsafe impl Sync for Heap {}
+
+impl Heap {
+    /// Create a heap, initialized over the task heap region on first use.
+    pub const fn new() -> Self {
+        Self(UnsafeCell::new(State {
+            base: ptr::null_mut(),
+            free: ptr::null_mut(),
+            initialized: false,
+            stats: HeapStats {
+                size: 0,
+                used: 0,
+                peak: 0,
+                allocations: 0,
+                failures: 0,
+            },
+        }))
+    }
+
+    /// Initialize the heap over the `len` bytes region at `start`, instead
+    /// of the task heap region.
+    ///
+    /// # Safety
+    ///
+    /// The region must be valid for reads and writes and unused for the
+    /// lifetime of the heap, and no allocation may be live.
+    pub unsafe fn init(&self, start: *mut u8, len: usize) {
+        // SAFETY: forwarded function contract
+        self.with_state(|state| unsafe { state.init(start, len) });
+    }
+
+    /// Return the heap usage statistics.
+    pub fn stats(&self) -> HeapStats {
+        self.with_state(|state| state.stats)
+    }
+
+    /// Execute `f` with an exclusive access to the heap state, initializing
+    /// it over the task heap region if needed.
+    fn with_state<R>(&self, f: impl FnOnce(&mut State) -> R) -> R {
+        // SAFETY: see Heap, `f` doesn't reach `with_state` as the heap doesn't
+        // allocate
+        let state = unsafe { &mut *self.0.get() };
+        #[cfg(target_os = "none")]
+        if !state.initialized {
+            unsafe extern "C" {
+                static mut _sheap: u8;
+                static mut _eheap: u8;
+            }
+            let start = &raw mut _sheap;
+            let end = &raw mut _eheap;
+            // SAFETY: the linker script reserves the region for the heap
+            unsafe { state.init(start, end.addr() - start.addr()) };
+        }
+        f(state)
+    }
+}
+
+impl Default for Heap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// SAFETY: blocks are carved from the free list, never overlapping, and kept
+// allocated until released
+unsafe impl GlobalAlloc for Heap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.with_state(|state| {
+            if state.initialized {
+                // SAFETY: initialized heap
+                unsafe { state.alloc(layout) }
+            } else {
+                state.stats.failures += 1;
+                ptr::null_mut()
+            }
+        })
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: forwarded function contract
+        self.with_state(|state| unsafe { state.dealloc(ptr, layout) });
+    }
+}
+
+/// Global allocator of the task
+#[cfg(target_os = "none")]
+#[global_allocator]
+static HEAP: Heap = Heap::new();
+
+/// Return the task heap usage statistics.
+#[cfg(target_os = "none")]
+pub fn stats() -> HeapStats {
+    HEAP.stats()
+}
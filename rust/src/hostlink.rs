@@ -0,0 +1,323 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Host communication channels over a shared memory, RTT compatible
+//!
+//! A [`HostLink`] lays out a SEGGER RTT control block in a shared memory,
+//! along with the ring buffers of its channels: a debugger or host tool
+//! (probe-rs, OpenOCD, J-Link) finds it by scanning the RAM for its
+//! identifier, and polls the channels while the task runs, without a UART.
+//! Up channels carry bytes to the host, down channels bytes from the host,
+//! and neither direction ever blocks: a write stores the bytes which fit
+//! (the RTT `NO_BLOCK_TRIM` mode), and a read returns the bytes available.
+//!
+//! The shared memory holds the control block, as declared by `SEGGER_RTT.h`:
+//!
+//! | Offset | Content |
+//! |--------|---------|
+//! | 0      | identifier, `SEGGER RTT`, zero-padded to 16 bytes |
+//! | 16     | number of up channels (`u32`) |
+//! | 20     | number of down channels (`u32`) |
+//! | 24     | up, then down, channel descriptors |
+//!
+//! Each descriptor holds the name and buffer addresses, then the buffer size,
+//! write offset, read offset and flags `u32` words, i.e. 24 bytes on 32-bit
+//! targets. The first up and down channels are named [`TERMINAL`], the others
+//! being unnamed. The name string, then the buffers, of equal sizes, follow
+//! the descriptors. The writer of a channel only updates its write offset, and
+//! its reader only the read offset.
+//!
+//! ```ignore
+//! let mut link = HostLink::new(Shm::new(RTT_SHM)?.map(0)?, 1)?;
+//! link.write(0, b"booted\n")?;
+//! let len = link.read(0, &mut command)?;
+//! ```
+
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{Ordering, fence};
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+use crate::shm::{Mapped, Shm};
+
+/// Control block identifier
+pub const ID: &[u8; 10] = b"SEGGER RTT";
+
+/// Name of the first up and down channels
+pub const TERMINAL: &str = "Terminal";
+
+/// Maximum number of channels in each direction
+pub const MAX_CHANNELS: usize = 4;
+
+/// Smallest channel buffer, in bytes
+pub const MIN_BUFFER_LEN: usize = 16;
+
+/// Identifier field length
+const ID_LEN: usize = 16;
+
+/// Channel descriptor length
+const DESCRIPTOR_LEN: usize = 2 * size_of::<usize>() + 16;
+
+/// Name string area length
+const NAME_LEN: usize = 16;
+
+/// `SEGGER_RTT_MODE_NO_BLOCK_TRIM` flags of the up channels
+const MODE_NO_BLOCK_TRIM: u32 = 1;
+
+/// Descriptor fields, as offsets
+const NAME: usize = 0;
+const BUFFER: usize = size_of::<usize>();
+const SIZE: usize = 2 * size_of::<usize>();
+const WRITE: usize = SIZE + 4;
+const READ: usize = SIZE + 8;
+const FLAGS: usize = SIZE + 12;
+
+/// RTT control block and channels in a shared memory, see the
+/// [module](self) documentation
+pub struct HostLink {
+    shm: Shm<Mapped>,
+    base: NonNull<u8>,
+    channels: usize,
+    buffer_len: u32,
+}
+
+impl HostLink {
+    /// Lay out the control block of `channels` up and down channels in
+    /// `shm`, sharing it equally between their buffers.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if `channels` is 0 or above
+    /// [`MAX_CHANNELS`], or if the shared memory is too small for buffers of
+    /// [`MIN_BUFFER_LEN`] bytes, misaligned or not writable, or propagates
+    /// kernel errors if information retrieval fails.
+    pub fn new(mut shm: Shm<Mapped>, channels: usize) -> Result<Self, Error> {
+        let invalid = Error::new(Subsystem::HostLink, Status::Invalid);
+        let len = shm.length()?;
+        let base = shm.base_address()?;
+        if !(1..=MAX_CHANNELS).contains(&channels)
+            || base % align_of::<usize>() != 0
+            || !shm.is_writable()
+        {
+            return Err(invalid);
+        }
+        let buffers = Self::buffers_offset(channels);
+        let buffer_len = (len.saturating_sub(buffers) / (2 * channels)) & !3;
+        if buffer_len < MIN_BUFFER_LEN {
+            return Err(invalid);
+        }
+        let base = NonNull::new(ptr::with_exposed_provenance_mut(base)).ok_or(invalid)?;
+        let link = Self {
+            shm,
+            base,
+            channels,
+            buffer_len: u32::try_from(buffer_len).map_err(|_| invalid)?,
+        };
+        link.lay_out();
+        Ok(link)
+    }
+
+    /// Release the shared memory, the host seeing the channels until it is
+    /// unmapped.
+    #[must_use]
+    pub fn release(self) -> Shm<Mapped> {
+        self.shm
+    }
+
+    /// Return the number of channels in each direction.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    /// Return the capacity of a channel buffer, one byte being always free.
+    pub fn capacity(&self) -> usize {
+        self.buffer_len as usize - 1
+    }
+
+    /// Write the bytes of `data` which fit in the up channel `channel`,
+    /// returning their number.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the channel does not exist or
+    /// the host corrupted its descriptor.
+    pub fn write(&mut self, channel: usize, data: &[u8]) -> Result<usize, Error> {
+        let descriptor = self.descriptor(true, channel)?;
+        let size = self.buffer_len;
+        // SAFETY: the descriptor is in the control block
+        let (write, read) =
+            unsafe { (self.word(descriptor + WRITE), self.word(descriptor + READ)) };
+        if read >= size || write >= size {
+            return Err(self.error(channel));
+        }
+        fence(Ordering::Acquire);
+        let free = (read + size - write - 1) % size;
+        let len = data.len().min(free as usize);
+        let buffer = self.buffer_offset(descriptor);
+        for (index, &byte) in data[..len].iter().enumerate() {
+            let offset = (write as usize + index) % size as usize;
+            // SAFETY: the offset is below the buffer size, in the shared memory
+            unsafe {
+                self.base.add(buffer + offset).as_ptr().write_volatile(byte);
+            }
+        }
+        fence(Ordering::Release);
+        // the length is below the buffer size
+        let write = (write + len as u32) % size;
+        // SAFETY: see above
+        unsafe { self.set_word(descriptor + WRITE, write) };
+        Ok(len)
+    }
+
+    /// Read the bytes written by the host in the down channel `channel` into
+    /// `buf`, returning their number.
+    ///
+    /// # Errors
+    /// See [`HostLink::write`].
+    pub fn read(&mut self, channel: usize, buf: &mut [u8]) -> Result<usize, Error> {
+        let descriptor = self.descriptor(false, channel)?;
+        let size = self.buffer_len;
+        // SAFETY: the descriptor is in the control block
+        let (write, read) =
+            unsafe { (self.word(descriptor + WRITE), self.word(descriptor + READ)) };
+        if read >= size || write >= size {
+            return Err(self.error(channel));
+        }
+        fence(Ordering::Acquire);
+        let len = buf.len().min(((write + size - read) % size) as usize);
+        let buffer = self.buffer_offset(descriptor);
+        for (index, byte) in buf[..len].iter_mut().enumerate() {
+            let offset = (read as usize + index) % size as usize;
+            // SAFETY: the offset is below the buffer size, in the shared memory
+            *byte = unsafe { self.base.add(buffer + offset).as_ptr().read_volatile() };
+        }
+        fence(Ordering::Release);
+        // the length is below the buffer size
+        let read = (read + len as u32) % size;
+        // SAFETY: see above
+        unsafe { self.set_word(descriptor + READ, read) };
+        Ok(len)
+    }
+
+    /// Return the number of bytes of the down channel `channel` not read yet,
+    /// 0 if it does not exist.
+    pub fn available(&self, channel: usize) -> usize {
+        let Ok(descriptor) = self.descriptor(false, channel) else {
+            return 0;
+        };
+        let size = self.buffer_len;
+        // SAFETY: the descriptor is in the control block
+        let (write, read) =
+            unsafe { (self.word(descriptor + WRITE), self.word(descriptor + READ)) };
+        ((write % size + size - read % size) % size) as usize
+    }
+
+    /// Write the control block, its identifier last so that the host never
+    /// finds a partial one.
+    fn lay_out(&self) {
+        let name = Self::buffers_offset(self.channels) - NAME_LEN;
+        // SAFETY: the control block, name and buffers are in the shared
+        // memory, as checked by `new`
+        unsafe {
+            for index in 0..ID_LEN {
+                self.base.add(index).as_ptr().write_volatile(0);
+            }
+            self.set_word(ID_LEN, self.channels as u32);
+            self.set_word(ID_LEN + 4, self.channels as u32);
+            for (index, byte) in TERMINAL.bytes().chain([0]).enumerate() {
+                self.base.add(name + index).as_ptr().write_volatile(byte);
+            }
+            for channel in 0..2 * self.channels {
+                let descriptor = ID_LEN + 8 + channel * DESCRIPTOR_LEN;
+                // the first up and down channels
+                let name = if channel % self.channels == 0 {
+                    self.base.add(name).as_ptr().expose_provenance()
+                } else {
+                    0
+                };
+                let buffer = self.buffer_offset(descriptor);
+                self.set_address(descriptor + NAME, name);
+                self.set_address(
+                    descriptor + BUFFER,
+                    self.base.add(buffer).as_ptr().expose_provenance(),
+                );
+                self.set_word(descriptor + SIZE, self.buffer_len);
+                self.set_word(descriptor + WRITE, 0);
+                self.set_word(descriptor + READ, 0);
+                let flags = if channel < self.channels {
+                    MODE_NO_BLOCK_TRIM
+                } else {
+                    0
+                };
+                self.set_word(descriptor + FLAGS, flags);
+            }
+            fence(Ordering::Release);
+            for (index, &byte) in ID.iter().enumerate() {
+                self.base.add(index).as_ptr().write_volatile(byte);
+            }
+        }
+    }
+
+    /// Offset of the buffers, after the descriptors and the name string
+    const fn buffers_offset(channels: usize) -> usize {
+        ID_LEN + 8 + 2 * channels * DESCRIPTOR_LEN + NAME_LEN
+    }
+
+    /// Offset of the buffer of the channel at `descriptor`
+    fn buffer_offset(&self, descriptor: usize) -> usize {
+        let index = (descriptor - ID_LEN - 8) / DESCRIPTOR_LEN;
+        Self::buffers_offset(self.channels) + index * self.buffer_len as usize
+    }
+
+    /// Offset of the descriptor of the up or down channel `channel`
+    fn descriptor(&self, up: bool, channel: usize) -> Result<usize, Error> {
+        if channel >= self.channels {
+            return Err(self.error(channel));
+        }
+        let index = if up { channel } else { self.channels + channel };
+        Ok(ID_LEN + 8 + index * DESCRIPTOR_LEN)
+    }
+
+    fn error(&self, channel: usize) -> Error {
+        Error::new(Subsystem::HostLink, Status::Invalid)
+            .with_handle(u32::try_from(channel).unwrap_or(u32::MAX))
+    }
+
+    /// Read the `u32` at `offset`.
+    ///
+    /// # Safety
+    /// `offset` must be an aligned offset in the control block.
+    unsafe fn word(&self, offset: usize) -> u32 {
+        // SAFETY: see the function contract
+        unsafe { self.base.add(offset).cast::<u32>().as_ptr().read_volatile() }
+    }
+
+    /// Write the `u32` at `offset`.
+    ///
+    /// # Safety
+    /// See [`HostLink::word`].
+    unsafe fn set_word(&self, offset: usize, value: u32) {
+        // SAFETY: see the function contract
+        unsafe {
+            self.base
+                .add(offset)
+                .cast::<u32>()
+                .as_ptr()
+                .write_volatile(value);
+        }
+    }
+
+    /// Write the address at `offset`.
+    ///
+    /// # Safety
+    /// `offset` must be an address aligned offset in the control block.
+    unsafe fn set_address(&self, offset: usize, address: usize) {
+        // SAFETY: see the function contract
+        unsafe {
+            self.base
+                .add(offset)
+                .cast::<usize>()
+                .as_ptr()
+                .write_volatile(address);
+        }
+    }
+}
@@ -0,0 +1,261 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Input events from buttons, touch controllers and rotary encoders
+//!
+//! Each input device implements [`InputSource`], reporting its state when its
+//! interrupt is raised. [`Input`] waits for the interrupts of its sources
+//! through the [`crate::executor`], along with the other futures of the task,
+//! and normalizes the changes of state into timestamped [`InputEvent`]s:
+//!
+//! - a button is pressed or released, its bounces being ignored for the
+//!   debounce delay following each accepted change,
+//! - a touch controller reports a contact (a move to the contact position,
+//!   then a press), its moves and the release,
+//! - a rotary encoder reports the steps turned, as a relative move.
+//!
+//! ```ignore
+//! let mut input: Input<'_, 4> = Input::new(20);
+//! let ok = input.add(&mut ok_button)?;
+//! input.add(&mut touch)?;
+//! loop {
+//!     let event = input.next().await?;
+//!     if event.source == ok && event.kind == InputKind::Press {
+//!         // ...
+//!     }
+//! }
+//! ```
+
+use core::future::poll_fn;
+use core::pin::Pin;
+use core::task::Poll;
+use uapi::systypes::{EventType, Status};
+
+use crate::channel::Bounded;
+use crate::error::{Error, Subsystem};
+use crate::executor::{self, EventFuture};
+use crate::time;
+
+/// Maximum number of events queued before [`Input::next`] returns them,
+/// further events being dropped
+pub const PENDING_LEN: usize = 8;
+
+/// Change of state of an input source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    /// Button pressed, or contact on a touch screen
+    Press,
+    /// Button or touch screen released
+    Release,
+    /// Move to the touch position `(x, y)`, or `x` encoder steps turned
+    /// (negative counterclockwise), `y` being 0
+    Move {
+        /// Horizontal position or steps
+        x: i32,
+        /// Vertical position
+        y: i32,
+    },
+}
+
+/// Timestamped input event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputEvent {
+    /// Uptime at which the interrupt was handled, in microseconds
+    pub timestamp_us: u64,
+    /// Source identifier, as returned by [`Input::add`]
+    pub source: u8,
+    /// Change of state
+    pub kind: InputKind,
+}
+
+/// Input source state, read after its interrupt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sample {
+    /// Button level, `true` when pressed
+    Button(bool),
+    /// Touch position, if in contact
+    Touch(Option<(u16, u16)>),
+    /// Encoder steps turned since the previous sample
+    Encoder(i32),
+}
+
+/// Input device, typically a GPIO line or a touch controller mapped by the
+/// task
+pub trait InputSource {
+    /// Return the interrupt line of the source, several sources possibly
+    /// sharing it.
+    fn irq(&self) -> u16;
+
+    /// Read the source state, and clear its interrupt flag.
+    fn sample(&mut self) -> Sample;
+}
+
+struct Slot<'a> {
+    source: &'a mut dyn InputSource,
+    /// Last accepted state
+    state: Sample,
+    /// Uptime of the last accepted change, in microseconds
+    changed_us: Option<u64>,
+}
+
+/// Input event dispatcher, see the [module](self) documentation
+pub struct Input<'a, const N: usize> {
+    slots: [Option<Slot<'a>>; N],
+    debounce_us: u64,
+    pending: Bounded<InputEvent, PENDING_LEN>,
+}
+
+impl<'a, const N: usize> Input<'a, N> {
+    /// Create a dispatcher for up to `N` sources, the buttons being debounced
+    /// for `debounce_ms` milliseconds.
+    pub fn new(debounce_ms: u32) -> Self {
+        Self {
+            slots: [const { None }; N],
+            debounce_us: u64::from(debounce_ms) * 1000,
+            pending: Bounded::new(),
+        }
+    }
+
+    /// Add `source`, unmasking its interrupt, and return its identifier.
+    ///
+    /// # Errors
+    /// Returns a `Status::Busy` error if `N` sources are added already, or
+    /// propagates kernel errors if the interrupt can't be enabled.
+    pub fn add(&mut self, source: &'a mut dyn InputSource) -> Result<u8, Error> {
+        let index = self
+            .slots
+            .iter()
+            .position(Option::is_none)
+            .filter(|&index| index <= usize::from(u8::MAX))
+            .ok_or(Error::new(Subsystem::Input, Status::Busy))?;
+        let irq = source.irq();
+        check(irq, crate::sys::syscall::irq_enable(irq))?;
+        let state = match source.sample() {
+            Sample::Encoder(_) => Sample::Encoder(0),
+            state => state,
+        };
+        self.slots[index] = Some(Slot {
+            source,
+            state,
+            changed_us: None,
+        });
+        Ok(index as u8)
+    }
+
+    /// Remove the source `id`, masking its interrupt unless shared with
+    /// another source.
+    pub fn remove(&mut self, id: u8) {
+        let Some(slot) = self.slots.get_mut(usize::from(id)).and_then(Option::take) else {
+            return;
+        };
+        let irq = slot.source.irq();
+        if !self
+            .slots
+            .iter()
+            .flatten()
+            .any(|slot| slot.source.irq() == irq)
+        {
+            let _ = crate::sys::syscall::irq_disable(irq);
+        }
+    }
+
+    /// Wait for the next input event.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if no source is added, or propagates
+    /// kernel errors if the uptime can't be read or an interrupt can't be
+    /// acknowledged.
+    pub async fn next(&mut self) -> Result<InputEvent, Error> {
+        if self.slots.iter().all(Option::is_none) {
+            return Err(Error::new(Subsystem::Input, Status::Invalid));
+        }
+        loop {
+            if let Some(event) = self.pending.try_recv() {
+                return Ok(event);
+            }
+            let irq = self.wait_irq().await;
+            self.handle(irq)?;
+        }
+    }
+
+    /// Wait for the interrupt of any source.
+    async fn wait_irq(&self) -> u16 {
+        let mut waiters: [Option<EventFuture>; N] = core::array::from_fn(|index| {
+            self.slots[index]
+                .as_ref()
+                .map(|slot| executor::wait_event_from(EventType::Irq, u32::from(slot.source.irq())))
+        });
+        let event = poll_fn(|cx| {
+            for waiter in waiters.iter_mut().flatten() {
+                if let Poll::Ready(event) = Pin::new(waiter).poll(cx) {
+                    return Poll::Ready(event);
+                }
+            }
+            Poll::Pending
+        })
+        .await;
+        // the other waiters give their events, if any, back to the executor
+        event.source() as u16
+    }
+
+    /// Sample the sources of `irq`, queueing their events.
+    fn handle(&mut self, irq: u16) -> Result<(), Error> {
+        let now = time::uptime_us()?;
+        for (id, slot) in self.slots.iter_mut().enumerate() {
+            let Some(slot) = slot.as_mut().filter(|slot| slot.source.irq() == irq) else {
+                continue;
+            };
+            let sample = slot.source.sample();
+            let emit = |kind| {
+                let _ = self.pending.try_send(InputEvent {
+                    timestamp_us: now,
+                    source: id as u8,
+                    kind,
+                });
+            };
+            match (slot.state, sample) {
+                (Sample::Button(before), Sample::Button(pressed)) => {
+                    let bouncing = slot
+                        .changed_us
+                        .is_some_and(|changed| now.saturating_sub(changed) < self.debounce_us);
+                    if pressed == before || bouncing {
+                        continue;
+                    }
+                    emit(if pressed {
+                        InputKind::Press
+                    } else {
+                        InputKind::Release
+                    });
+                }
+                (Sample::Touch(before), Sample::Touch(contact)) => match (before, contact) {
+                    (_, Some((x, y))) if before != contact => {
+                        emit(InputKind::Move {
+                            x: i32::from(x),
+                            y: i32::from(y),
+                        });
+                        if before.is_none() {
+                            emit(InputKind::Press);
+                        }
+                    }
+                    (Some(_), None) => emit(InputKind::Release),
+                    _ => continue,
+                },
+                (_, Sample::Encoder(0)) => continue,
+                (_, Sample::Encoder(steps)) => emit(InputKind::Move { x: steps, y: 0 }),
+                // a source whose kind of sample changes is resynchronized
+                _ => {}
+            }
+            slot.state = sample;
+            slot.changed_us = Some(now);
+        }
+        check(irq, crate::sys::syscall::irq_acknowledge(irq))
+    }
+}
+
+fn check(irq: u16, status: Status) -> Result<(), Error> {
+    match status {
+        Status::Ok => Ok(()),
+        status => Err(Error::new(Subsystem::Input, status).with_handle(u32::from(irq))),
+    }
+}
@@ -0,0 +1,191 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Byte streams over IPC messages
+//!
+//! An [`IpcStream`] carries a byte stream in each direction between two
+//! tasks, each running an [`IpcStream`] towards the other, as IPC messages
+//! of up to [`MAX_MESSAGE_LEN`] bytes. With the `embedded-io-async` feature,
+//! it implements [`embedded_io_async::Read`] and [`embedded_io_async::Write`],
+//! so that protocol code runs over an inter-task link as over a serial port.
+//!
+//! `send_ipc()` blocking until the peer reads the message, the writer only
+//! sends once the reader is ready: a reader out of data sends
+//! [`MAILBOX_READY_SIGNAL`] to its peer before waiting for an IPC, each
+//! signal allowing the peer a single message. A writer thus never blocks
+//! the executor, waiting for the signal instead.
+//!
+//! ```ignore
+//! let mut link = IpcStream::new(modem_task);
+//! link.write_all(b"AT+CGMI\r\n").await?;
+//! let len = link.read(&mut response).await?;
+//! ```
+
+use uapi::systypes::{EventType, Signal, Status, TaskHandle};
+
+#[cfg(feature = "cbor")]
+use crate::encoding::cbor;
+use crate::error::{Error, Subsystem};
+use crate::executor::{self, EVENT_DATA_LEN};
+
+/// Largest IPC message, in bytes
+pub const MAX_MESSAGE_LEN: usize = EVENT_DATA_LEN;
+
+/// Signal sent by a reader ready to receive a message
+pub const MAILBOX_READY_SIGNAL: Signal = Signal::Io;
+
+/// Byte stream with a peer task, see the [module](self) documentation
+pub struct IpcStream {
+    peer: TaskHandle,
+    rx: [u8; MAX_MESSAGE_LEN],
+    rx_start: usize,
+    rx_end: usize,
+    /// Whether the peer was allowed a message not received yet
+    granted: bool,
+}
+
+impl IpcStream {
+    /// Create the stream with the task `peer`.
+    pub const fn new(peer: TaskHandle) -> Self {
+        Self {
+            peer,
+            rx: [0; MAX_MESSAGE_LEN],
+            rx_start: 0,
+            rx_end: 0,
+            granted: false,
+        }
+    }
+
+    /// Return the peer task handle.
+    pub fn peer(&self) -> TaskHandle {
+        self.peer
+    }
+
+    /// Return the number of received bytes not read yet.
+    pub fn available(&self) -> usize {
+        self.rx_end - self.rx_start
+    }
+
+    /// Read the received bytes into `buf`, waiting for a message if none,
+    /// and return the number of bytes read.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the peer can't be signaled.
+    pub async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while self.available() == 0 {
+            if !self.granted {
+                self.check(crate::sys::syscall::send_signal(
+                    self.peer,
+                    MAILBOX_READY_SIGNAL,
+                ))?;
+                self.granted = true;
+            }
+            let event = executor::wait_event_from(EventType::Ipc, self.peer).await;
+            let data = event.data();
+            self.rx[..data.len()].copy_from_slice(data);
+            (self.rx_start, self.rx_end) = (0, data.len());
+            self.granted = false;
+        }
+        let len = buf.len().min(self.available());
+        buf[..len].copy_from_slice(&self.rx[self.rx_start..self.rx_start + len]);
+        self.rx_start += len;
+        Ok(len)
+    }
+
+    /// Send up to [`MAX_MESSAGE_LEN`] bytes of `buf` in a message, once the
+    /// peer is ready, and return the number of bytes sent.
+    ///
+    /// # Errors
+    /// Returns `Status::Intr` if the peer terminated before reading the
+    /// message, or propagates kernel errors.
+    pub async fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        executor::wait_signal_from(MAILBOX_READY_SIGNAL, self.peer).await;
+        let message = &buf[..buf.len().min(MAX_MESSAGE_LEN)];
+        match crate::sys::copy_to_kernel(&message) {
+            Ok(Status::Ok) => {}
+            Ok(status) | Err(status) => return Err(self.error(status)),
+        }
+        // at most MAX_MESSAGE_LEN, below the exchange area length
+        self.check(crate::sys::syscall::send_ipc(
+            self.peer,
+            message.len() as u8,
+        ))?;
+        Ok(message.len())
+    }
+
+    /// Send the CBOR encoding of `value` in a message, see
+    /// [`IpcStream::write`].
+    ///
+    /// # Errors
+    /// Returns a `Status::Busy` [`Subsystem::Encoding`] error if the
+    /// encoding exceeds [`MAX_MESSAGE_LEN`], or the [`IpcStream::write`]
+    /// errors.
+    #[cfg(feature = "cbor")]
+    pub async fn send_cbor<T: cbor::Encode + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let mut message = [0; MAX_MESSAGE_LEN];
+        let len = cbor::to_slice(value, &mut message)?;
+        self.write(&message[..len]).await.map(|_| ())
+    }
+
+    /// Receive a message holding the CBOR encoding of a `T` into `buf`, and
+    /// return the decoded value.
+    ///
+    /// The stream must be at a message boundary, i.e. the messages of the
+    /// peer must be read whole, as here.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` [`Subsystem::Encoding`] error if the
+    /// message is not a CBOR encoding of `T`, or the [`IpcStream::read`]
+    /// errors.
+    #[cfg(feature = "cbor")]
+    pub async fn receive_cbor<'b, T: cbor::Decode<'b>>(
+        &mut self,
+        buf: &'b mut [u8; MAX_MESSAGE_LEN],
+    ) -> Result<T, Error> {
+        let len = self.read(buf).await?;
+        cbor::from_slice(&buf[..len])
+    }
+
+    fn check(&self, status: Status) -> Result<(), Error> {
+        match status {
+            Status::Ok => Ok(()),
+            status => Err(self.error(status)),
+        }
+    }
+
+    fn error(&self, status: Status) -> Error {
+        Error::new(Subsystem::Ipc, status).with_handle(self.peer)
+    }
+}
+
+#[cfg(feature = "embedded-io-async")]
+impl embedded_io_async::ErrorType for IpcStream {
+    type Error = Error;
+}
+
+#[cfg(feature = "embedded-io-async")]
+impl embedded_io_async::Read for IpcStream {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        IpcStream::read(self, buf).await
+    }
+}
+
+#[cfg(feature = "embedded-io-async")]
+impl embedded_io_async::Write for IpcStream {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        IpcStream::write(self, buf).await
+    }
+
+    /// Messages are sent as they are written, and read by the peer once
+    /// `write` returns.
+    async fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,658 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Key-value store
+//!
+//! [`KvStore`] persists small values, such as calibration data and counters,
+//! in the flash region granted to the task, accessed through a [`Backing`]
+//! implementation. The region is split into two banks. Records are appended
+//! to the active bank, a new value of a key superseding the previous one, and
+//! once the active bank is full, the live records are compacted into the
+//! other bank, which becomes the active one.
+//!
+//! Records and bank headers are CRC protected. The header of a compacted bank
+//! is programmed last, so that an interrupted compaction leaves the previous
+//! bank active. A record torn by a power loss ends the log, the next update
+//! compacting the store.
+//!
+//! ```ignore
+//! let mut store = KvStore::open(flash)?;
+//! store.set(b"offset", &offset.to_le_bytes())?;
+//! let mut raw = [0; 4];
+//! if store.get(b"offset", &mut raw)?.is_some() { ... }
+//! ```
+
+use uapi::systypes::Status;
+
+use crate::checksum::{CRC_32_ISO_HDLC, Crc};
+#[cfg(feature = "cbor")]
+use crate::encoding::cbor;
+use crate::error::{Error, Subsystem};
+
+/// Maximum key length, in bytes
+pub const MAX_KEY_LEN: usize = 32;
+
+/// Maximum value length, in bytes
+pub const MAX_VALUE_LEN: usize = u16::MAX as usize;
+
+/// Maximum program unit of a [`Backing`], in bytes
+pub const MAX_WRITE_SIZE: usize = 64;
+
+/// Value of the erased bytes
+const ERASED: u8 = 0xff;
+
+const BANK_MAGIC: u32 = 0x3153_564b;
+const BANK_HEADER_LEN: usize = 12;
+
+const RECORD_HEADER_LEN: usize = 8;
+const KIND_SET: u8 = 0xa5;
+const KIND_DELETE: u8 = 0x5a;
+
+/// Flash region holding a [`KvStore`]
+///
+/// Offsets are relative to the region start. Erased bytes read as `0xff`,
+/// and programming only clears bits of erased bytes.
+pub trait Backing {
+    /// Region size, in bytes, a multiple of [`Backing::erase_size`]
+    fn size(&self) -> usize;
+
+    /// Erase unit, in bytes
+    fn erase_size(&self) -> usize;
+
+    /// Program unit, in bytes, a power of two up to [`MAX_WRITE_SIZE`]
+    fn write_size(&self) -> usize;
+
+    /// Read `buf.len()` bytes at `offset`.
+    ///
+    /// # Errors
+    /// Returns the flash driver errors.
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), Error>;
+
+    /// Program `data` to the erased bytes at `offset`, the offset and length
+    /// being multiples of [`Backing::write_size`].
+    ///
+    /// # Errors
+    /// Returns the flash driver errors.
+    fn program(&mut self, offset: usize, data: &[u8]) -> Result<(), Error>;
+
+    /// Erase the `len` bytes at `offset`, the offset and length being
+    /// multiples of [`Backing::erase_size`].
+    ///
+    /// # Errors
+    /// Returns the flash driver errors.
+    fn erase(&mut self, offset: usize, len: usize) -> Result<(), Error>;
+}
+
+fn error(status: Status) -> Error {
+    Error::new(Subsystem::Storage, status)
+}
+
+const fn align_up(value: usize, align: usize) -> usize {
+    (value + align - 1) & !(align - 1)
+}
+
+/// Record header, followed by the key and the value
+#[derive(Clone, Copy)]
+struct Record {
+    offset: usize,
+    kind: u8,
+    key_len: usize,
+    value_len: usize,
+    crc: u32,
+}
+
+impl Record {
+    fn parse(offset: usize, raw: [u8; RECORD_HEADER_LEN]) -> Option<Self> {
+        let key_len = usize::from(raw[0]);
+        if key_len == 0 || key_len > MAX_KEY_LEN || (raw[1] != KIND_SET && raw[1] != KIND_DELETE) {
+            return None;
+        }
+        Some(Self {
+            offset,
+            kind: raw[1],
+            key_len,
+            value_len: usize::from(u16::from_le_bytes([raw[2], raw[3]])),
+            crc: u32::from_le_bytes([raw[4], raw[5], raw[6], raw[7]]),
+        })
+    }
+
+    fn header(kind: u8, key: &[u8], value: &[u8]) -> [u8; RECORD_HEADER_LEN] {
+        let mut raw = [0; RECORD_HEADER_LEN];
+        // lengths checked by the caller
+        raw[0] = key.len() as u8;
+        raw[1] = kind;
+        raw[2..4].copy_from_slice(&(value.len() as u16).to_le_bytes());
+        let mut crc = Crc::new(CRC_32_ISO_HDLC);
+        crc.update(&raw[..4]);
+        crc.update(key);
+        crc.update(value);
+        raw[4..].copy_from_slice(&crc.finalize().to_le_bytes());
+        raw
+    }
+
+    fn key_offset(&self) -> usize {
+        self.offset + RECORD_HEADER_LEN
+    }
+
+    fn value_offset(&self) -> usize {
+        self.key_offset() + self.key_len
+    }
+
+    fn len(&self, align: usize) -> usize {
+        align_up(RECORD_HEADER_LEN + self.key_len + self.value_len, align)
+    }
+}
+
+/// Streams bytes to the backing, by program units
+struct Writer<'a, B: Backing> {
+    backing: &'a mut B,
+    offset: usize,
+    align: usize,
+    buf: [u8; MAX_WRITE_SIZE],
+    fill: usize,
+}
+
+impl<'a, B: Backing> Writer<'a, B> {
+    fn new(backing: &'a mut B, offset: usize, align: usize) -> Self {
+        Self {
+            backing,
+            offset,
+            align,
+            buf: [ERASED; MAX_WRITE_SIZE],
+            fill: 0,
+        }
+    }
+
+    fn push(&mut self, mut bytes: &[u8]) -> Result<(), Error> {
+        while !bytes.is_empty() {
+            let len = bytes.len().min(MAX_WRITE_SIZE - self.fill);
+            self.buf[self.fill..self.fill + len].copy_from_slice(&bytes[..len]);
+            self.fill += len;
+            bytes = &bytes[len..];
+            if self.fill == MAX_WRITE_SIZE {
+                self.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Program the pending bytes, padded to the record alignment.
+    fn flush(&mut self) -> Result<(), Error> {
+        let len = align_up(self.fill, self.align);
+        self.buf[self.fill..len].fill(ERASED);
+        if len != 0 {
+            self.backing.program(self.offset, &self.buf[..len])?;
+        }
+        self.offset += len;
+        self.fill = 0;
+        Ok(())
+    }
+}
+
+/// Live key-value pair, returned by [`KvStore::iter`]
+#[derive(Debug, Clone, Copy)]
+pub struct Entry {
+    key: [u8; MAX_KEY_LEN],
+    key_len: usize,
+    value_offset: usize,
+    value_len: usize,
+}
+
+impl Entry {
+    /// Key of the pair
+    pub fn key(&self) -> &[u8] {
+        &self.key[..self.key_len]
+    }
+
+    /// Length of the value, read with [`KvStore::read_value`]
+    pub fn value_len(&self) -> usize {
+        self.value_len
+    }
+}
+
+/// Key-value store over a flash region (see the [module](self) documentation)
+pub struct KvStore<B: Backing> {
+    backing: B,
+    /// Program alignment of the headers and records
+    align: usize,
+    bank_len: usize,
+    /// Active bank index
+    active: usize,
+    generation: u32,
+    /// Offset of the end of the log
+    end: usize,
+    /// Whether the bytes after the end of the log are not erased
+    dirty: bool,
+}
+
+impl<B: Backing> KvStore<B> {
+    /// Open the store held by `backing`, formatting it if it holds none.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if the region can't hold two banks or its
+    /// program unit is not supported, or propagates the backing errors.
+    pub fn open(backing: B) -> Result<Self, Error> {
+        let write_size = backing.write_size();
+        let erase_size = backing.erase_size();
+        if !write_size.is_power_of_two() || write_size > MAX_WRITE_SIZE || erase_size == 0 {
+            return Err(error(Status::Invalid));
+        }
+        let bank_len = backing.size() / 2 / erase_size * erase_size;
+        let align = write_size.max(4);
+        if bank_len < align_up(BANK_HEADER_LEN, align) + align_up(RECORD_HEADER_LEN, align) {
+            return Err(error(Status::Invalid));
+        }
+
+        let mut store = Self {
+            backing,
+            align,
+            bank_len,
+            active: 0,
+            generation: 0,
+            end: 0,
+            dirty: false,
+        };
+        let banks = [store.bank_generation(0)?, store.bank_generation(1)?];
+        match banks {
+            [None, None] => {
+                store.format(0, 1)?;
+                return Ok(store);
+            }
+            [Some(generation), None] => store.generation = generation,
+            [None, Some(generation)] => (store.active, store.generation) = (1, generation),
+            [Some(first), Some(second)] => {
+                // the generations wrap around
+                if second.wrapping_sub(first).cast_signed() > 0 {
+                    (store.active, store.generation) = (1, second);
+                } else {
+                    store.generation = first;
+                }
+            }
+        }
+        store.recover()?;
+        Ok(store)
+    }
+
+    /// Give the backing back.
+    pub fn release(self) -> B {
+        self.backing
+    }
+
+    /// Return the value of `key` into `buf`, along with its length, or
+    /// `None` if the key is not set.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if `buf` can't hold the value, or propagates
+    /// the backing errors.
+    pub fn get(&self, key: &[u8], buf: &mut [u8]) -> Result<Option<usize>, Error> {
+        match self.lookup(key)? {
+            Some(record) if record.kind == KIND_SET => {
+                let value = buf
+                    .get_mut(..record.value_len)
+                    .ok_or(error(Status::Invalid))?;
+                self.backing.read(record.value_offset(), value)?;
+                Ok(Some(record.value_len))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Set the value of `key` to `value`.
+    ///
+    /// Setting the current value again doesn't program the flash.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if the key is empty or longer than
+    /// [`MAX_KEY_LEN`], or the value longer than [`MAX_VALUE_LEN`],
+    /// `Status::Busy` if the store is full, or propagates the backing errors.
+    pub fn set(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        if value.len() > MAX_VALUE_LEN {
+            return Err(error(Status::Invalid));
+        }
+        if let Some(record) = self.lookup(key)?
+            && record.kind == KIND_SET
+            && record.value_len == value.len()
+            && record.crc == Self::crc(&Record::header(KIND_SET, key, value))
+            && self.value_equals(&record, value)?
+        {
+            return Ok(());
+        }
+        self.append(KIND_SET, key, value)
+    }
+
+    /// Return the value of `key` decoded from CBOR, read into `buf`, or
+    /// `None` if the key is not set.
+    ///
+    /// # Errors
+    /// Returns the [`KvStore::get`] errors, or a `Status::Invalid`
+    /// [`Subsystem::Encoding`] error if the value is not a CBOR encoding of
+    /// `T`.
+    #[cfg(feature = "cbor")]
+    pub fn get_cbor<'b, T: cbor::Decode<'b>>(
+        &self,
+        key: &[u8],
+        buf: &'b mut [u8],
+    ) -> Result<Option<T>, Error> {
+        match self.get(key, buf)? {
+            Some(len) => cbor::from_slice(&buf[..len]).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Set the value of `key` to the CBOR encoding of `value`, encoded in
+    /// `buf`.
+    ///
+    /// # Errors
+    /// Returns the [`KvStore::set`] errors, or a `Status::Busy`
+    /// [`Subsystem::Encoding`] error if `buf` can't hold the encoding.
+    #[cfg(feature = "cbor")]
+    pub fn set_cbor<T: cbor::Encode + ?Sized>(
+        &mut self,
+        key: &[u8],
+        value: &T,
+        buf: &mut [u8],
+    ) -> Result<(), Error> {
+        let len = cbor::to_slice(value, buf)?;
+        self.set(key, &buf[..len])
+    }
+
+    /// Delete `key`, returning whether it was set.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if the key is empty or longer than
+    /// [`MAX_KEY_LEN`], `Status::Busy` if the store is full, or propagates
+    /// the backing errors.
+    pub fn delete(&mut self, key: &[u8]) -> Result<bool, Error> {
+        match self.lookup(key)? {
+            Some(record) if record.kind == KIND_SET => {
+                self.append(KIND_DELETE, key, &[])?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Iterate over the live key-value pairs, in their update order.
+    pub fn iter(&self) -> Iter<'_, B> {
+        Iter {
+            store: self,
+            offset: self.first_record(self.active),
+        }
+    }
+
+    /// Read the value of `entry` into `buf`, returning its length.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if `buf` can't hold the value, or propagates
+    /// the backing errors.
+    pub fn read_value(&self, entry: &Entry, buf: &mut [u8]) -> Result<usize, Error> {
+        let value = buf
+            .get_mut(..entry.value_len)
+            .ok_or(error(Status::Invalid))?;
+        self.backing.read(entry.value_offset, value)?;
+        Ok(entry.value_len)
+    }
+
+    /// Return the free space of the active bank, in bytes.
+    pub fn free(&self) -> usize {
+        self.bank_start(self.active) + self.bank_len - self.end
+    }
+
+    /// Compact the live records into the other bank, which becomes active.
+    ///
+    /// # Errors
+    /// Propagates the backing errors.
+    pub fn compact(&mut self) -> Result<(), Error> {
+        let target = 1 - self.active;
+        let start = self.bank_start(target);
+        self.backing.erase(start, self.bank_len)?;
+
+        let mut offset = self.first_record(self.active);
+        let first = self.first_record(target);
+        let mut writer = Writer::new(&mut self.backing, first, self.align);
+        while let Some(record) = Self::record_at(writer.backing, offset, self.end)? {
+            offset += record.len(self.align);
+            if record.kind != KIND_SET
+                || !Self::is_latest_in(writer.backing, self.align, &record, offset, self.end)?
+            {
+                continue;
+            }
+            let mut chunk = [0; MAX_WRITE_SIZE];
+            let mut copied = 0;
+            let len = RECORD_HEADER_LEN + record.key_len + record.value_len;
+            while copied < len {
+                let part = (len - copied).min(MAX_WRITE_SIZE);
+                writer
+                    .backing
+                    .read(record.offset + copied, &mut chunk[..part])?;
+                writer.push(&chunk[..part])?;
+                copied += part;
+            }
+            writer.flush()?;
+        }
+        let end = writer.offset;
+
+        let generation = self.generation.wrapping_add(1);
+        self.program_bank_header(target, generation)?;
+        (self.active, self.generation, self.end, self.dirty) = (target, generation, end, false);
+        Ok(())
+    }
+
+    fn bank_start(&self, bank: usize) -> usize {
+        bank * self.bank_len
+    }
+
+    fn first_record(&self, bank: usize) -> usize {
+        self.bank_start(bank) + align_up(BANK_HEADER_LEN, self.align)
+    }
+
+    fn crc(header: &[u8; RECORD_HEADER_LEN]) -> u32 {
+        u32::from_le_bytes([header[4], header[5], header[6], header[7]])
+    }
+
+    /// Return the generation of `bank`, if it holds a valid header.
+    fn bank_generation(&self, bank: usize) -> Result<Option<u32>, Error> {
+        let mut raw = [0; BANK_HEADER_LEN];
+        self.backing.read(self.bank_start(bank), &mut raw)?;
+        let word = |index: usize| {
+            u32::from_le_bytes([raw[index], raw[index + 1], raw[index + 2], raw[index + 3]])
+        };
+        let valid = word(0) == BANK_MAGIC && word(8) == Crc::checksum(CRC_32_ISO_HDLC, &raw[..8]);
+        Ok(valid.then(|| word(4)))
+    }
+
+    fn program_bank_header(&mut self, bank: usize, generation: u32) -> Result<(), Error> {
+        let mut raw = [0; BANK_HEADER_LEN];
+        raw[..4].copy_from_slice(&BANK_MAGIC.to_le_bytes());
+        raw[4..8].copy_from_slice(&generation.to_le_bytes());
+        let crc = Crc::checksum(CRC_32_ISO_HDLC, &raw[..8]);
+        raw[8..].copy_from_slice(&crc.to_le_bytes());
+        let start = self.bank_start(bank);
+        let mut writer = Writer::new(&mut self.backing, start, self.align);
+        writer.push(&raw)?;
+        writer.flush()
+    }
+
+    /// Format `bank` as an empty active bank.
+    fn format(&mut self, bank: usize, generation: u32) -> Result<(), Error> {
+        self.backing.erase(self.bank_start(bank), self.bank_len)?;
+        self.program_bank_header(bank, generation)?;
+        (self.active, self.generation) = (bank, generation);
+        (self.end, self.dirty) = (self.first_record(bank), false);
+        Ok(())
+    }
+
+    /// Find the end of the log of the active bank, checking the records.
+    fn recover(&mut self) -> Result<(), Error> {
+        let bank_end = self.bank_start(self.active) + self.bank_len;
+        let mut offset = self.first_record(self.active);
+        while let Some(record) = Self::record_at(&self.backing, offset, bank_end)? {
+            if !self.check(&record)? {
+                break;
+            }
+            offset += record.len(self.align);
+        }
+        self.end = offset;
+
+        let mut chunk = [0; MAX_WRITE_SIZE];
+        while offset < bank_end {
+            let len = (bank_end - offset).min(MAX_WRITE_SIZE);
+            self.backing.read(offset, &mut chunk[..len])?;
+            if chunk[..len].iter().any(|&byte| byte != ERASED) {
+                self.dirty = true;
+                break;
+            }
+            offset += len;
+        }
+        Ok(())
+    }
+
+    /// Check the CRC of `record`.
+    fn check(&self, record: &Record) -> Result<bool, Error> {
+        let mut raw = [0; RECORD_HEADER_LEN];
+        self.backing.read(record.offset, &mut raw)?;
+        let mut crc = Crc::new(CRC_32_ISO_HDLC);
+        crc.update(&raw[..4]);
+        let mut chunk = [0; MAX_WRITE_SIZE];
+        let mut offset = record.key_offset();
+        let end = record.value_offset() + record.value_len;
+        while offset < end {
+            let len = (end - offset).min(MAX_WRITE_SIZE);
+            self.backing.read(offset, &mut chunk[..len])?;
+            crc.update(&chunk[..len]);
+            offset += len;
+        }
+        Ok(crc.finalize() == record.crc)
+    }
+
+    /// Return the record at `offset`, if any before `end`.
+    fn record_at(backing: &B, offset: usize, end: usize) -> Result<Option<Record>, Error> {
+        if offset + RECORD_HEADER_LEN > end {
+            return Ok(None);
+        }
+        let mut raw = [0; RECORD_HEADER_LEN];
+        backing.read(offset, &mut raw)?;
+        Ok(Record::parse(offset, raw)
+            .filter(|record| record.value_offset() + record.value_len <= end))
+    }
+
+    fn key_equals(backing: &B, record: &Record, key: &[u8]) -> Result<bool, Error> {
+        if record.key_len != key.len() {
+            return Ok(false);
+        }
+        let mut raw = [0; MAX_KEY_LEN];
+        backing.read(record.key_offset(), &mut raw[..key.len()])?;
+        Ok(raw[..key.len()] == *key)
+    }
+
+    fn value_equals(&self, record: &Record, value: &[u8]) -> Result<bool, Error> {
+        let mut chunk = [0; MAX_WRITE_SIZE];
+        for (index, part) in value.chunks(MAX_WRITE_SIZE).enumerate() {
+            let raw = &mut chunk[..part.len()];
+            self.backing
+                .read(record.value_offset() + index * MAX_WRITE_SIZE, raw)?;
+            if raw != part {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Check whether no record of the `record` key follows, from `next`.
+    fn is_latest(&self, record: &Record, next: usize) -> Result<bool, Error> {
+        Self::is_latest_in(&self.backing, self.align, record, next, self.end)
+    }
+
+    fn is_latest_in(
+        backing: &B,
+        align: usize,
+        record: &Record,
+        mut next: usize,
+        end: usize,
+    ) -> Result<bool, Error> {
+        let mut raw = [0; MAX_KEY_LEN];
+        let key = &mut raw[..record.key_len];
+        backing.read(record.key_offset(), key)?;
+        while let Some(later) = Self::record_at(backing, next, end)? {
+            if Self::key_equals(backing, &later, key)? {
+                return Ok(false);
+            }
+            next += later.len(align);
+        }
+        Ok(true)
+    }
+
+    /// Return the latest record of `key`.
+    fn lookup(&self, key: &[u8]) -> Result<Option<Record>, Error> {
+        if key.is_empty() || key.len() > MAX_KEY_LEN {
+            return Err(error(Status::Invalid));
+        }
+        let mut latest = None;
+        let mut offset = self.first_record(self.active);
+        while let Some(record) = Self::record_at(&self.backing, offset, self.end)? {
+            if Self::key_equals(&self.backing, &record, key)? {
+                latest = Some(record);
+            }
+            offset += record.len(self.align);
+        }
+        Ok(latest)
+    }
+
+    fn append(&mut self, kind: u8, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        let header = Record::header(kind, key, value);
+        let len = align_up(RECORD_HEADER_LEN + key.len() + value.len(), self.align);
+        if self.dirty || len > self.free() {
+            self.compact()?;
+        }
+        if len > self.free() {
+            return Err(error(Status::Busy));
+        }
+        let mut writer = Writer::new(&mut self.backing, self.end, self.align);
+        writer.push(&header)?;
+        writer.push(key)?;
+        writer.push(value)?;
+        writer.flush()?;
+        self.end += len;
+        Ok(())
+    }
+}
+
+/// Live key-value pairs iterator, returned by [`KvStore::iter`]
+pub struct Iter<'a, B: Backing> {
+    store: &'a KvStore<B>,
+    offset: usize,
+}
+
+impl<B: Backing> Iterator for Iter<'_, B> {
+    type Item = Result<Entry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let store = self.store;
+        loop {
+            let record = match KvStore::<B>::record_at(&store.backing, self.offset, store.end) {
+                Ok(Some(record)) => record,
+                Ok(None) => return None,
+                Err(err) => return Some(Err(err)),
+            };
+            self.offset += record.len(store.align);
+            if record.kind != KIND_SET {
+                continue;
+            }
+            match store.is_latest(&record, self.offset) {
+                Ok(false) => {}
+                Ok(true) => {
+                    let mut entry = Entry {
+                        key: [0; MAX_KEY_LEN],
+                        key_len: record.key_len,
+                        value_offset: record.value_offset(),
+                        value_len: record.value_len,
+                    };
+                    let key = &mut entry.key[..record.key_len];
+                    return Some(store.backing.read(record.key_offset(), key).map(|()| entry));
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
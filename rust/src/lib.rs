@@ -5,12 +5,150 @@
 
 #![no_std]
 
+#[cfg(all(feature = "host-std", any(feature = "mock", feature = "sim")))]
+compile_error!("the `host-std` feature excludes the kernel backends");
+
+#[cfg(feature = "heap")]
+extern crate alloc;
 extern crate sentry_uapi as uapi;
 extern crate shield_macros as macros;
 
-pub use macros::shield_main;
+// With the `host-std` feature, only the modules which never reach the kernel
+// are built (error types, log record codec, executor combinators), so that
+// they can be tested on the host under Miri and sanitizers.
+//
+// The `shm`, `sync`, `async`, `dma` and `print` features compile out the
+// matching subsystems, so that a minimal task only links what it uses. Items
+// involving two subsystems are only built when both are enabled.
+
+pub use error::{Context, Error, Subsystem};
+pub use macros::{bind, shield_main};
 pub use uapi::systypes::Status;
+#[cfg(feature = "attest")]
+pub mod attest;
+#[cfg(all(feature = "audio", not(feature = "host-std")))]
+pub mod audio;
+#[cfg(all(feature = "shm", not(feature = "host-std")))]
+pub mod bench;
+#[cfg(not(feature = "host-std"))]
+pub mod bind;
+pub mod channel;
+pub mod checksum;
+pub mod collections;
+#[cfg(all(feature = "coredump", not(feature = "host-std")))]
+pub mod coredump;
+#[cfg(all(feature = "shm", not(feature = "host-std")))]
+pub mod crashlog;
+#[cfg(all(feature = "defmt", not(feature = "host-std")))]
+mod defmt_logger;
+#[cfg(not(feature = "host-std"))]
+pub mod device;
+#[cfg(all(feature = "display", not(feature = "host-std")))]
+pub mod display;
+#[cfg(all(feature = "dma", not(feature = "host-std")))]
+pub mod dma;
+#[cfg(all(feature = "embassy", not(feature = "host-std")))]
+mod embassy_time;
+pub mod encoding;
+pub mod errno;
+pub mod error;
+#[cfg(all(feature = "eth", not(feature = "host-std")))]
+pub mod eth;
+#[cfg(all(feature = "async", not(feature = "host-std")))]
+pub mod executor;
+#[cfg(all(feature = "ffi", not(feature = "host-std")))]
+pub mod ffi;
+#[cfg(all(feature = "hal", not(feature = "host-std")))]
+pub mod hal;
+#[cfg(all(feature = "health", not(feature = "host-std")))]
+pub mod health;
+#[cfg(feature = "heap")]
+pub mod heap;
+#[cfg(all(feature = "hostlink", not(feature = "host-std")))]
+pub mod hostlink;
+#[cfg(all(feature = "input", not(feature = "host-std")))]
+pub mod input;
+#[cfg(all(feature = "ipc", not(feature = "host-std")))]
+pub mod ipc;
+#[cfg(feature = "kvstore")]
+pub mod kvstore;
+#[cfg(all(feature = "log", not(feature = "host-std")))]
+pub mod log;
+#[cfg(all(feature = "metrics", not(feature = "host-std")))]
+pub mod metrics;
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(all(feature = "net", not(feature = "host-std")))]
+pub mod net;
+#[cfg(all(feature = "print", not(feature = "host-std")))]
 pub mod print;
+#[cfg(not(feature = "host-std"))]
 pub mod process;
+#[cfg(not(feature = "host-std"))]
+pub mod random;
+#[cfg(not(feature = "host-std"))]
+pub mod retry;
+#[cfg(feature = "rollback")]
+pub mod rollback;
+#[cfg(all(feature = "rtic", not(feature = "host-std")))]
+pub mod rtic;
+#[cfg(all(feature = "sdmmc", not(feature = "host-std")))]
+pub mod sdmmc;
+#[cfg(feature = "secure-element")]
+pub mod secure_element;
+#[cfg(all(feature = "securestore", not(feature = "host-std")))]
+pub mod securestore;
+#[cfg(all(feature = "selftest", not(feature = "host-std")))]
+pub mod selftest;
+#[cfg(any(feature = "update", feature = "attest"))]
+pub mod sha256;
+#[cfg(feature = "shell")]
+pub mod shell;
+#[cfg(all(feature = "shm", not(feature = "host-std")))]
 pub mod shm;
+#[cfg(feature = "sim")]
+pub mod sim;
+#[cfg(feature = "storage")]
+pub mod storage;
+#[cfg(all(feature = "supervisor", not(feature = "host-std")))]
+pub mod supervisor;
+#[cfg(all(feature = "sync", not(feature = "host-std")))]
+pub mod sync;
+#[cfg(not(feature = "host-std"))]
+mod sys;
+#[cfg(all(feature = "audit-syscalls", not(feature = "host-std")))]
+pub mod syscall_audit;
+#[cfg(all(feature = "trace-syscalls", not(feature = "host-std")))]
+pub mod syscall_trace;
+#[cfg(not(feature = "host-std"))]
 pub mod system;
+#[cfg(not(feature = "host-std"))]
+pub mod task;
+#[cfg(not(feature = "host-std"))]
+pub mod time;
+#[cfg(feature = "trace")]
+pub mod trace;
+#[cfg(all(feature = "embedded-io-async", not(feature = "host-std")))]
+pub mod uart;
+pub mod units;
+#[cfg(feature = "update")]
+pub mod update;
+
+/// Executor combinators, the executor itself requiring the kernel
+#[cfg(feature = "host-std")]
+pub mod executor {
+    mod combinators;
+
+    pub use combinators::{
+        Either, Either3, Join2, Join3, Join4, Select2, Select3, join2, join3, join4, select2,
+        select3,
+    };
+}
+
+/// Binary log record codec, the log backend requiring the kernel
+#[cfg(feature = "host-std")]
+pub mod log {
+    pub mod record;
+
+    pub use record::RecordFormat;
+}
@@ -0,0 +1,129 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+use ::log::LevelFilter;
+use core::cell::UnsafeCell;
+use uapi::systypes::Status;
+
+/// Maximum number of per-module filters
+pub const MAX_FILTERS: usize = 8;
+
+/// Maximum length of a filtered module prefix
+pub const MAX_PREFIX_LEN: usize = 32;
+
+#[derive(Clone, Copy)]
+struct Filter {
+    prefix: [u8; MAX_PREFIX_LEN],
+    len: usize,
+    level: LevelFilter,
+}
+
+impl Filter {
+    fn prefix(&self) -> &[u8] {
+        &self.prefix[..self.len]
+    }
+
+    /// Return whether `target` is the filtered module or one of its children
+    fn matches(&self, target: &str) -> bool {
+        let target = target.as_bytes();
+        target.starts_with(self.prefix())
+            && (target.len() == self.len || target[self.len..].starts_with(b"::"))
+    }
+}
+
+/// Runtime filter table: a default level and module prefix specific levels
+pub(super) struct FilterTable {
+    default: LevelFilter,
+    filters: [Option<Filter>; MAX_FILTERS],
+}
+
+impl FilterTable {
+    const fn new() -> Self {
+        Self {
+            default: LevelFilter::Off,
+            filters: [None; MAX_FILTERS],
+        }
+    }
+
+    /// Return the level applicable to `target`, the longest matching prefix
+    /// taking precedence.
+    pub(super) fn level_for(&self, target: &str) -> LevelFilter {
+        self.filters
+            .iter()
+            .flatten()
+            .filter(|filter| filter.matches(target))
+            .max_by_key(|filter| filter.len)
+            .map_or(self.default, |filter| filter.level)
+    }
+
+    /// Return the most verbose level of the table
+    pub(super) fn max_level(&self) -> LevelFilter {
+        self.filters
+            .iter()
+            .flatten()
+            .map(|filter| filter.level)
+            .fold(self.default, Ord::max)
+    }
+
+    pub(super) fn set_default(&mut self, level: LevelFilter) {
+        self.default = level;
+    }
+
+    pub(super) fn set(&mut self, prefix: &str, level: LevelFilter) -> Result<(), Status> {
+        if prefix.is_empty() || prefix.len() > MAX_PREFIX_LEN {
+            return Err(Status::Invalid);
+        }
+
+        if let Some(filter) = self
+            .filters
+            .iter_mut()
+            .flatten()
+            .find(|filter| filter.prefix() == prefix.as_bytes())
+        {
+            filter.level = level;
+            return Ok(());
+        }
+
+        let slot = self
+            .filters
+            .iter_mut()
+            .find(|slot| slot.is_none())
+            .ok_or(Status::Busy)?;
+        let mut filter = Filter {
+            prefix: [0; MAX_PREFIX_LEN],
+            len: prefix.len(),
+            level,
+        };
+        filter.prefix[..prefix.len()].copy_from_slice(prefix.as_bytes());
+        *slot = Some(filter);
+        Ok(())
+    }
+
+    pub(super) fn reset(&mut self, prefix: &str) {
+        for slot in &mut self.filters {
+            if slot.is_some_and(|filter| filter.prefix() == prefix.as_bytes()) {
+                *slot = None;
+            }
+        }
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.filters = [None; MAX_FILTERS];
+    }
+}
+
+struct TableCell(UnsafeCell<FilterTable>);
+
+// SAFETY: a Sentry task is single-threaded, and the table is never borrowed
+// across calls of `with_table`
+unsafe impl Sync for TableCell {}
+
+static TABLE: TableCell = TableCell(UnsafeCell::new(FilterTable::new()));
+
+/// Execute `f` with an exclusive access to the filter table
+pub(super) fn with_table<R>(f: impl FnOnce(&mut FilterTable) -> R) -> R {
+    // SAFETY: see TableCell, `f` can't reach `with_table` as logging is
+    // not done from the filter table
+    f(unsafe { &mut *TABLE.0.get() })
+}
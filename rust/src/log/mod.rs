@@ -0,0 +1,352 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! `log` crate backend over the kernel log channel
+//!
+//! Once installed with [`init`], records emitted through the `log` facade
+//! (`info!`, `warn!`... from the application or its dependencies) are written
+//! through the kernel `log` syscall.
+//!
+//! Each record is formatted in a buffer of the kernel exchange area size and
+//! emitted with a single syscall, so that records are never interleaved.
+//! Records exceeding this size are truncated, the truncation being marked with
+//! a trailing `...`.
+//!
+//! Records are filtered at runtime against a default level and a table of
+//! module prefix specific levels, so that a given driver logging can be made
+//! verbose without reflashing the task. The table is updated either through
+//! [`set_module_level`] and friends, or by forwarding control messages received
+//! from a peer task (typically over IPC) to [`apply_control_message`].
+//!
+//! Records are emitted as text by default. The [`RecordFormat::Binary`]
+//! format, selected with [`set_format`], adds a binary header to each record
+//! (see [`record`]).
+//!
+//! Byte buffers are dumped with [`hexdump!`], as records of 16 bytes each.
+
+mod filter;
+pub mod record;
+
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicU8, AtomicU32, Ordering};
+use uapi::systypes::Status;
+
+use crate::sys::{copy_to_kernel, syscall};
+
+pub use ::log::{Level, LevelFilter};
+pub use filter::{MAX_FILTERS, MAX_PREFIX_LEN};
+pub use record::RecordFormat;
+
+use crate::encoding::hex;
+use crate::error::{Error, Subsystem};
+use crate::{process, time};
+use filter::with_table;
+use record::{HEADER_LEN, RecordHeader};
+
+/// Maximum length of an emitted record, including the trailing newline
+const RECORD_LEN: usize = uapi::length();
+
+/// Truncation marker, replacing the end of truncated records
+const TRUNCATION_MARK: &[u8] = b"...\n";
+
+/// Fixed size record formatting buffer
+struct RecordBuffer {
+    buf: [u8; RECORD_LEN],
+    start: usize,
+    len: usize,
+    truncated: bool,
+}
+
+impl RecordBuffer {
+    /// Create a new buffer, reserving `start` bytes for the record header.
+    const fn new(start: usize) -> Self {
+        Self {
+            buf: [0; RECORD_LEN],
+            start,
+            len: start,
+            truncated: false,
+        }
+    }
+
+    /// Terminate the text record and write it through the kernel log channel.
+    fn emit_text(&mut self) {
+        if self.truncated {
+            let start = RECORD_LEN - TRUNCATION_MARK.len();
+            self.buf[start..].copy_from_slice(TRUNCATION_MARK);
+            self.len = RECORD_LEN;
+        } else if self.len == 0 || self.buf[self.len - 1] != b'\n' {
+            if self.len == RECORD_LEN {
+                self.len -= 1;
+            }
+            self.buf[self.len] = b'\n';
+            self.len += 1;
+        }
+        self.emit();
+    }
+
+    /// Prepend the given header to the binary record and write it through the
+    /// kernel log channel.
+    fn emit_binary(&mut self, mut header: RecordHeader) {
+        if self.truncated {
+            // no trailing newline in binary records
+            let start = RECORD_LEN - (TRUNCATION_MARK.len() - 1);
+            self.buf[start..].copy_from_slice(&TRUNCATION_MARK[..TRUNCATION_MARK.len() - 1]);
+            self.len = RECORD_LEN;
+        }
+        header.len = u16::try_from(self.len - self.start).unwrap_or(u16::MAX);
+        let mut raw = [0; HEADER_LEN];
+        header.encode(&mut raw);
+        self.buf[..HEADER_LEN].copy_from_slice(&raw);
+        self.emit();
+    }
+
+    fn emit(&self) {
+        let record = &self.buf[..self.len];
+        if copy_to_kernel(&record).is_ok() {
+            syscall::log(record.len());
+        }
+    }
+}
+
+impl Write for RecordBuffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.truncated {
+            return Ok(());
+        }
+
+        let room = RECORD_LEN - self.len;
+        let mut count = s.len().min(room);
+        if count < s.len() {
+            // keep the record valid UTF-8 when cutting it
+            while !s.is_char_boundary(count) {
+                count -= 1;
+            }
+            self.truncated = true;
+        }
+        self.buf[self.len..self.len + count].copy_from_slice(&s.as_bytes()[..count]);
+        self.len += count;
+        Ok(())
+    }
+}
+
+struct ShieldLogger;
+
+static LOGGER: ShieldLogger = ShieldLogger;
+
+/// Current record format
+static FORMAT: AtomicU8 = AtomicU8::new(RecordFormat::Text as u8);
+
+/// Sequence number of the next binary record
+static SEQUENCE: AtomicU32 = AtomicU32::new(0);
+
+impl ::log::Log for ShieldLogger {
+    fn enabled(&self, metadata: &::log::Metadata) -> bool {
+        metadata.level() <= with_table(|table| table.level_for(metadata.target()))
+    }
+
+    fn log(&self, record: &::log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        if FORMAT.load(Ordering::Relaxed) == RecordFormat::Binary as u8 {
+            // a Sentry task being single-threaded, no atomic increment is required
+            let seq = SEQUENCE.load(Ordering::Relaxed);
+            SEQUENCE.store(seq.wrapping_add(1), Ordering::Relaxed);
+            let header = RecordHeader {
+                level: record.level(),
+                seq: (seq & 0xffff) as u16,
+                task: process::current_label().unwrap_or_default(),
+                timestamp_us: time::uptime_us().unwrap_or_default(),
+                len: 0,
+            };
+
+            let mut buffer = RecordBuffer::new(HEADER_LEN);
+            let _ = write!(buffer, "{}: {}", record.target(), record.args());
+            buffer.emit_binary(header);
+        } else {
+            let mut buffer = RecordBuffer::new(0);
+            let _ = write!(
+                buffer,
+                "[{:<5}] {}: {}",
+                record.level(),
+                record.target(),
+                record.args()
+            );
+            buffer.emit_text();
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install the Shield logger as the `log` facade backend.
+///
+/// # Arguments
+/// * `level` - Maximum level of the emitted records
+///
+/// # Errors
+///
+/// Will return a `Status::Busy` error if a logger is already installed.
+pub fn init(level: LevelFilter) -> Result<(), Error> {
+    #[cfg(target_has_atomic = "ptr")]
+    let installed = ::log::set_logger(&LOGGER);
+    // SAFETY: a Sentry task is single-threaded, the logger can't be set concurrently
+    #[cfg(not(target_has_atomic = "ptr"))]
+    let installed = unsafe { ::log::set_logger_racy(&LOGGER) };
+
+    installed.map_err(|_| Error::new(Subsystem::Log, Status::Busy))?;
+    set_level(level);
+    Ok(())
+}
+
+/// Select the format of the emitted records.
+pub fn set_format(format: RecordFormat) {
+    FORMAT.store(format as u8, Ordering::Relaxed);
+}
+
+/// Update the default maximum level of the emitted records.
+///
+/// This level applies to modules without specific filter.
+pub fn set_level(level: LevelFilter) {
+    with_table(|table| table.set_default(level));
+    update_max_level();
+}
+
+/// Set the maximum level of the records emitted by the module `prefix` and
+/// its children.
+///
+/// When several filters match a given module, the longest prefix applies.
+/// # Errors
+///
+/// Will return a `Status::Invalid` error if the prefix is empty or longer than
+/// [`MAX_PREFIX_LEN`], or a `Status::Busy` error if the filter table is full.
+pub fn set_module_level(prefix: &str, level: LevelFilter) -> Result<(), Error> {
+    with_table(|table| table.set(prefix, level))
+        .map_err(|status| Error::new(Subsystem::Log, status))?;
+    update_max_level();
+    Ok(())
+}
+
+/// Remove the filter of the module `prefix`, the default level applying again.
+pub fn reset_module_level(prefix: &str) {
+    with_table(|table| table.reset(prefix));
+    update_max_level();
+}
+
+/// Remove all module filters.
+pub fn clear_module_levels() {
+    with_table(filter::FilterTable::clear);
+    update_max_level();
+}
+
+/// Control message command: set the default level
+pub const CONTROL_SET_DEFAULT: u8 = 0;
+/// Control message command: set a module level
+pub const CONTROL_SET_MODULE: u8 = 1;
+/// Control message command: reset a module level
+pub const CONTROL_RESET_MODULE: u8 = 2;
+/// Control message command: remove all module filters
+pub const CONTROL_CLEAR_MODULES: u8 = 3;
+
+/// Apply a filter control message, typically received over IPC.
+///
+/// The message layout is the following:
+/// - byte 0: command, one of the `CONTROL_*` values
+/// - byte 1: level, from 0 (`Off`) to 5 (`Trace`), ignored by reset and clear
+/// - next bytes: module prefix, UTF-8 encoded, for module commands only
+///
+/// # Errors
+///
+/// Will return a `Status::Invalid` error if the message is malformed, or
+/// propagates the filter update errors.
+pub fn apply_control_message(msg: &[u8]) -> Result<(), Error> {
+    let invalid = Error::new(Subsystem::Log, Status::Invalid);
+    let (&command, rest) = msg.split_first().ok_or(invalid)?;
+    let level = rest
+        .first()
+        .and_then(|&level| LevelFilter::iter().nth(usize::from(level)));
+    let prefix = rest
+        .get(1..)
+        .map(core::str::from_utf8)
+        .transpose()
+        .map_err(|_| invalid)?
+        .unwrap_or_default();
+
+    match command {
+        CONTROL_SET_DEFAULT => set_level(level.ok_or(invalid)?),
+        CONTROL_SET_MODULE => set_module_level(prefix, level.ok_or(invalid)?)?,
+        CONTROL_RESET_MODULE => reset_module_level(prefix),
+        CONTROL_CLEAR_MODULES => clear_module_levels(),
+        _ => return Err(invalid),
+    }
+    Ok(())
+}
+
+/// Align the facade maximum level with the most verbose filter so that
+/// records reach the logger, which applies the filter table.
+fn update_max_level() {
+    let level = with_table(|table| table.max_level());
+    #[cfg(target_has_atomic = "ptr")]
+    ::log::set_max_level(level);
+    // SAFETY: a Sentry task is single-threaded, the level can't be set concurrently
+    #[cfg(not(target_has_atomic = "ptr"))]
+    unsafe {
+        ::log::set_max_level_racy(level);
+    }
+}
+
+/// Bytes per record of [`hexdump!`]
+const HEXDUMP_LINE_LEN: usize = 16;
+
+/// Log the bytes of `data`, named `name`, at `level`.
+#[doc(hidden)]
+pub fn _hexdump(level: Level, target: &str, name: &str, data: &[u8]) {
+    if !::log::log_enabled!(target: target, level) {
+        return;
+    }
+    ::log::log!(target: target, level, "{name}: {} bytes", data.len());
+    for (index, line) in data.chunks(HEXDUMP_LINE_LEN).enumerate() {
+        let mut digits = [0; hex::encoded_len(HEXDUMP_LINE_LEN)];
+        let digits = hex::encode_to(line, &mut digits).unwrap_or_default();
+        let mut ascii = [b'.'; HEXDUMP_LINE_LEN];
+        for (char, &byte) in ascii.iter_mut().zip(line) {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                *char = byte;
+            }
+        }
+        let ascii = core::str::from_utf8(&ascii[..line.len()]).unwrap_or_default();
+        ::log::log!(
+            target: target,
+            level,
+            "{:04x}: {digits:<width$} {ascii}",
+            index * HEXDUMP_LINE_LEN,
+            width = hex::encoded_len(HEXDUMP_LINE_LEN)
+        );
+    }
+}
+
+/// Log the bytes of a buffer, 16 per record, in hexadecimal and as text,
+/// at the `Debug` level or the given one.
+///
+/// ```ignore
+/// log::hexdump!(frame);
+/// log::hexdump!(Level::Warn, &raw[..len]);
+/// // [DEBUG] app: frame: 19 bytes
+/// // [DEBUG] app: 0000: 48656c6c6f2c2073656375726520776f Hello, secure wo
+/// // [DEBUG] app: 0010: 726c64                           rld
+/// ```
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_hexdump {
+    ($data:expr) => {
+        $crate::log::hexdump!($crate::log::Level::Debug, $data)
+    };
+    ($level:expr, $data:expr) => {
+        $crate::log::_hexdump($level, module_path!(), stringify!($data), &$data)
+    };
+}
+
+#[doc(inline)]
+pub use crate::__log_hexdump as hexdump;
@@ -0,0 +1,152 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Structured log records
+//!
+//! In [`RecordFormat::Binary`] format, each record emitted by the logger is
+//! prefixed with a compact header holding the record level, the emitting task
+//! label, a per-task sequence number and a monotonic timestamp, so that logs of
+//! multiple tasks can be merged and ordered on the host with [`RecordDecoder`].
+//!
+//! Header layout (little endian):
+//!
+//! | offset | size | content                               |
+//! |--------|------|---------------------------------------|
+//! | 0      | 1    | [`RECORD_MARKER`]                     |
+//! | 1      | 1    | level, from 1 (`Error`) to 5 (`Trace`) |
+//! | 2      | 2    | sequence number                       |
+//! | 4      | 4    | task label                            |
+//! | 8      | 8    | timestamp since startup, in µs        |
+//! | 16     | 2    | payload length                        |
+//!
+//! The header is followed by the UTF-8 encoded payload (`target: message`).
+
+use ::log::Level;
+use uapi::systypes::{Status, TaskLabel};
+
+use crate::error::{Error, Subsystem};
+
+/// Binary record header length
+pub const HEADER_LEN: usize = 18;
+
+/// Binary record start marker
+pub const RECORD_MARKER: u8 = 0xa5;
+
+/// Emitted records format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// Human readable text, one record per line
+    Text,
+    /// Binary header followed by the text payload
+    Binary,
+}
+
+/// Binary record header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordHeader {
+    pub level: Level,
+    pub seq: u16,
+    pub task: TaskLabel,
+    pub timestamp_us: u64,
+    pub len: u16,
+}
+
+impl RecordHeader {
+    /// Encode the header in its binary form.
+    pub fn encode(&self, out: &mut [u8; HEADER_LEN]) {
+        out[0] = RECORD_MARKER;
+        out[1] = self.level as u8;
+        out[2..4].copy_from_slice(&self.seq.to_le_bytes());
+        out[4..8].copy_from_slice(&self.task.to_le_bytes());
+        out[8..16].copy_from_slice(&self.timestamp_us.to_le_bytes());
+        out[16..18].copy_from_slice(&self.len.to_le_bytes());
+    }
+
+    /// Decode a header from its binary form.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if `bytes` does not start with a valid header.
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let invalid = Error::new(Subsystem::Log, Status::Invalid);
+        let header: &[u8; HEADER_LEN] = bytes
+            .get(..HEADER_LEN)
+            .and_then(|header| header.try_into().ok())
+            .ok_or(invalid)?;
+        if header[0] != RECORD_MARKER {
+            return Err(invalid);
+        }
+        let level = usize::from(header[1])
+            .checked_sub(1)
+            .and_then(|level| Level::iter().nth(level))
+            .ok_or(invalid)?;
+
+        Ok(Self {
+            level,
+            seq: u16::from_le_bytes([header[2], header[3]]),
+            task: u32::from_le_bytes([header[4], header[5], header[6], header[7]]),
+            timestamp_us: u64::from_le_bytes([
+                header[8], header[9], header[10], header[11], header[12], header[13], header[14],
+                header[15],
+            ]),
+            len: u16::from_le_bytes([header[16], header[17]]),
+        })
+    }
+}
+
+/// Record decoded by [`RecordDecoder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodedRecord<'a> {
+    pub header: RecordHeader,
+    pub payload: &'a str,
+}
+
+impl DecodedRecord<'_> {
+    /// Ordering key used to merge records of multiple tasks: timestamp first,
+    /// then task label and sequence number for records of the same instant.
+    pub fn sort_key(&self) -> (u64, TaskLabel, u16) {
+        (self.header.timestamp_us, self.header.task, self.header.seq)
+    }
+}
+
+/// Iterator over the binary records found in a captured log stream.
+///
+/// Bytes that are not part of a binary record (such as text emitted through
+/// `println!`) are skipped.
+pub struct RecordDecoder<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> RecordDecoder<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Self { input }
+    }
+}
+
+impl<'a> Iterator for RecordDecoder<'a> {
+    type Item = DecodedRecord<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.input.iter().position(|&b| b == RECORD_MARKER)?;
+            self.input = &self.input[start..];
+            let Ok(header) = RecordHeader::decode(self.input) else {
+                // not a record, or truncated stream
+                self.input = self.input.get(1..)?;
+                continue;
+            };
+            let end = HEADER_LEN + usize::from(header.len);
+            let payload = self
+                .input
+                .get(HEADER_LEN..end)
+                .and_then(|payload| core::str::from_utf8(payload).ok());
+            match payload {
+                Some(payload) => {
+                    self.input = &self.input[end..];
+                    return Some(DecodedRecord { header, payload });
+                }
+                None => self.input = &self.input[1..],
+            }
+        }
+    }
+}
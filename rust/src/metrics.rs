@@ -0,0 +1,482 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Metrics exported over a shared memory
+//!
+//! A task registers its [`Counter`]s, [`Gauge`]s and [`Histogram`]s (queue
+//! depths, error counts, loop latencies) in a [`Registry`], which lays them
+//! out in a shared memory. A supervisor task maps this shared memory and reads
+//! them with a [`Scraper`].
+//!
+//! Values are `u32` words, each one updated with a single store, so that the
+//! supervisor never reads a torn value. The buckets of a histogram may however
+//! be read while a sample is recorded. Counters wrap.
+//!
+//! The shared memory holds little-endian `u32` words:
+//!
+//! | Words | Content |
+//! |-------|---------|
+//! | 0     | magic, `MTRC` |
+//! | 1     | layout version, [`VERSION`] |
+//! | 2     | number of registered metrics |
+//! | 3     | capacity, in metrics |
+//! | 4..   | metrics, [`ENTRY_LEN`] bytes each |
+//!
+//! Each metric holds its name (up to [`MAX_NAME_LEN`] bytes, zero-padded),
+//! its kind (1 counter, 2 gauge, 3 histogram), the histogram bucket upper
+//! bounds, then [`BUCKETS`] value words: the counter value, the gauge value
+//! (`i32`) and its high-water mark, or the histogram bucket counts, the last
+//! bucket counting the samples above the last bound.
+//!
+//! ```ignore
+//! let registry = Registry::new(Shm::new(METRICS_SHM)?.map(0)?)?;
+//! let rx_frames = registry.counter("rx_frames")?;
+//! let latency = registry.histogram("loop_us", &[10, 20, 50, 100, 200, 500, 1000])?;
+//! loop {
+//!     let start = time::uptime_us()?;
+//!     rx_frames.increment();
+//!     latency.record((time::uptime_us()? - start) as u32);
+//! }
+//! ```
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{Ordering, fence};
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+use crate::shm::{Mapped, Shm};
+
+/// Layout version
+pub const VERSION: u32 = 1;
+
+/// Maximum metric name length, in bytes
+pub const MAX_NAME_LEN: usize = 16;
+
+/// Number of histogram buckets, the last one being unbounded
+pub const BUCKETS: usize = 8;
+
+/// Metric length in the shared memory, in bytes
+pub const ENTRY_LEN: usize = MAX_NAME_LEN + 4 + 4 * (BUCKETS - 1) + 4 * BUCKETS;
+
+/// Layout marker ("MTRC")
+const MAGIC: u32 = 0x4d54_5243;
+
+const HEADER_WORDS: usize = 4;
+const ENTRY_WORDS: usize = ENTRY_LEN / 4;
+const KIND_WORD: usize = MAX_NAME_LEN / 4;
+const BOUNDS_WORD: usize = KIND_WORD + 1;
+const VALUES_WORD: usize = BOUNDS_WORD + BUCKETS - 1;
+
+const COUNTER: u32 = 1;
+const GAUGE: u32 = 2;
+const HISTOGRAM: u32 = 3;
+
+/// Word `index` of the layout at `base`
+///
+/// # Safety
+/// The word must be in the shared memory.
+unsafe fn word(base: NonNull<u32>, index: usize) -> *mut u32 {
+    // SAFETY: see the function contract
+    unsafe { base.as_ptr().add(index) }
+}
+
+/// # Safety
+/// See [`word`].
+unsafe fn read(base: NonNull<u32>, index: usize) -> u32 {
+    // SAFETY: see the function contract
+    u32::from_le(unsafe { word(base, index).read_volatile() })
+}
+
+/// # Safety
+/// See [`word`].
+unsafe fn write(base: NonNull<u32>, index: usize, value: u32) {
+    // SAFETY: see the function contract
+    unsafe { word(base, index).write_volatile(value.to_le()) };
+}
+
+/// Check the shared memory holding the metrics, returning its base address
+/// and capacity.
+fn layout(shm: &mut Shm<Mapped>, writable: bool) -> Result<(NonNull<u32>, u32), Error> {
+    let invalid = Error::new(Subsystem::Metrics, Status::Invalid);
+    let len = shm.length()?;
+    let base = shm.base_address()?;
+    if base % align_of::<u32>() != 0 || (writable && !shm.is_writable()) {
+        return Err(invalid);
+    }
+    let capacity = len.saturating_sub(HEADER_WORDS * 4) / ENTRY_LEN;
+    if capacity == 0 {
+        return Err(invalid);
+    }
+    let base = NonNull::new(ptr::with_exposed_provenance_mut(base)).ok_or(invalid)?;
+    Ok((base, u32::try_from(capacity).map_err(|_| invalid)?))
+}
+
+/// Task side of the metrics shared memory, see the [module](self)
+/// documentation
+pub struct Registry {
+    shm: Shm<Mapped>,
+    base: NonNull<u32>,
+    capacity: u32,
+    count: Cell<u32>,
+}
+
+impl Registry {
+    /// Lay out an empty metrics table in `shm`.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the shared memory is too small
+    /// for a metric, misaligned or not writable, or propagates kernel errors
+    /// if information retrieval fails.
+    pub fn new(mut shm: Shm<Mapped>) -> Result<Self, Error> {
+        let (base, capacity) = layout(&mut shm, true)?;
+        // SAFETY: the shared memory is larger than the header
+        unsafe {
+            write(base, 2, 0);
+            write(base, 3, capacity);
+            write(base, 1, VERSION);
+            write(base, 0, MAGIC);
+        }
+        Ok(Self {
+            shm,
+            base,
+            capacity,
+            count: Cell::new(0),
+        })
+    }
+
+    /// Release the shared memory, the metrics staying readable by the
+    /// supervisor.
+    #[must_use]
+    pub fn release(self) -> Shm<Mapped> {
+        self.shm
+    }
+
+    /// Return the number of registered metrics.
+    pub fn len(&self) -> usize {
+        self.count.get() as usize
+    }
+
+    /// Check whether no metric is registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the maximum number of metrics.
+    pub fn capacity(&self) -> usize {
+        self.capacity as usize
+    }
+
+    /// Register a counter, starting at 0.
+    ///
+    /// # Errors
+    /// See [`Registry::histogram`].
+    pub fn counter(&self, name: &str) -> Result<Counter<'_>, Error> {
+        let values = self.register(name, COUNTER, &[0; BUCKETS - 1])?;
+        Ok(Counter {
+            values,
+            _registry: PhantomData,
+        })
+    }
+
+    /// Register a gauge, starting at 0.
+    ///
+    /// # Errors
+    /// See [`Registry::histogram`].
+    pub fn gauge(&self, name: &str) -> Result<Gauge<'_>, Error> {
+        let values = self.register(name, GAUGE, &[0; BUCKETS - 1])?;
+        Ok(Gauge {
+            values,
+            _registry: PhantomData,
+        })
+    }
+
+    /// Register a histogram, whose buckets count the samples up to each of
+    /// `bounds`, the last bucket counting the larger samples.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if `name` is empty or longer than
+    /// [`MAX_NAME_LEN`], or if `bounds` are not increasing, and a
+    /// `Status::Busy` error if the shared memory is full.
+    pub fn histogram(
+        &self,
+        name: &str,
+        bounds: &[u32; BUCKETS - 1],
+    ) -> Result<Histogram<'_>, Error> {
+        if bounds.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(Error::new(Subsystem::Metrics, Status::Invalid));
+        }
+        let values = self.register(name, HISTOGRAM, bounds)?;
+        Ok(Histogram {
+            values,
+            bounds: *bounds,
+            _registry: PhantomData,
+        })
+    }
+
+    /// Write a metric entry, publishing it once complete, and return its
+    /// first value word.
+    fn register(
+        &self,
+        name: &str,
+        kind: u32,
+        bounds: &[u32; BUCKETS - 1],
+    ) -> Result<NonNull<u32>, Error> {
+        if name.is_empty() || name.len() > MAX_NAME_LEN {
+            return Err(Error::new(Subsystem::Metrics, Status::Invalid));
+        }
+        let index = self.count.get();
+        if index == self.capacity {
+            return Err(Error::new(Subsystem::Metrics, Status::Busy));
+        }
+
+        let mut padded = [0; MAX_NAME_LEN];
+        padded[..name.len()].copy_from_slice(name.as_bytes());
+        // SAFETY: the entry is below the capacity, in the shared memory
+        let entry = unsafe {
+            NonNull::new_unchecked(word(self.base, HEADER_WORDS + index as usize * ENTRY_WORDS))
+        };
+        // SAFETY: all the words are in the entry
+        unsafe {
+            for (i, chunk) in padded.chunks_exact(4).enumerate() {
+                let chunk = [chunk[0], chunk[1], chunk[2], chunk[3]];
+                word(entry, i).write_volatile(u32::from_ne_bytes(chunk));
+            }
+            write(entry, KIND_WORD, kind);
+            for (i, &bound) in bounds.iter().enumerate() {
+                write(entry, BOUNDS_WORD + i, bound);
+            }
+            for i in 0..BUCKETS {
+                write(entry, VALUES_WORD + i, 0);
+            }
+        }
+        fence(Ordering::Release);
+        self.count.set(index + 1);
+        // SAFETY: see above
+        unsafe {
+            write(self.base, 2, index + 1);
+            Ok(NonNull::new_unchecked(word(entry, VALUES_WORD)))
+        }
+    }
+}
+
+/// Monotonic counter, wrapping
+#[derive(Clone, Copy)]
+pub struct Counter<'a> {
+    values: NonNull<u32>,
+    _registry: PhantomData<&'a Registry>,
+}
+
+impl Counter<'_> {
+    /// Add 1 to the counter.
+    pub fn increment(&self) {
+        self.add(1);
+    }
+
+    /// Add `n` to the counter.
+    pub fn add(&self, n: u32) {
+        // SAFETY: the value word is in the registry shared memory
+        unsafe { write(self.values, 0, read(self.values, 0).wrapping_add(n)) };
+    }
+
+    /// Return the counter value.
+    pub fn get(&self) -> u32 {
+        // SAFETY: see add
+        unsafe { read(self.values, 0) }
+    }
+}
+
+/// Instantaneous value, along with its high-water mark
+#[derive(Clone, Copy)]
+pub struct Gauge<'a> {
+    values: NonNull<u32>,
+    _registry: PhantomData<&'a Registry>,
+}
+
+impl Gauge<'_> {
+    /// Set the gauge value.
+    pub fn set(&self, value: i32) {
+        // SAFETY: the value words are in the registry shared memory
+        unsafe {
+            write(self.values, 0, value.cast_unsigned());
+            if value > read(self.values, 1).cast_signed() {
+                write(self.values, 1, value.cast_unsigned());
+            }
+        }
+    }
+
+    /// Return the gauge value.
+    pub fn get(&self) -> i32 {
+        // SAFETY: see set
+        unsafe { read(self.values, 0) }.cast_signed()
+    }
+
+    /// Return the largest value the gauge was set to.
+    pub fn max(&self) -> i32 {
+        // SAFETY: see set
+        unsafe { read(self.values, 1) }.cast_signed()
+    }
+}
+
+/// Sample distribution over [`BUCKETS`] buckets
+#[derive(Clone, Copy)]
+pub struct Histogram<'a> {
+    values: NonNull<u32>,
+    bounds: [u32; BUCKETS - 1],
+    _registry: PhantomData<&'a Registry>,
+}
+
+impl Histogram<'_> {
+    /// Count `sample` in its bucket.
+    pub fn record(&self, sample: u32) {
+        let bucket = self
+            .bounds
+            .iter()
+            .position(|&bound| sample <= bound)
+            .unwrap_or(BUCKETS - 1);
+        // SAFETY: the value words are in the registry shared memory
+        unsafe {
+            write(
+                self.values,
+                bucket,
+                read(self.values, bucket).wrapping_add(1),
+            )
+        };
+    }
+}
+
+/// Metric value, as read by a [`Scraper`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    /// Counter value
+    Counter(u32),
+    /// Gauge value and high-water mark
+    Gauge {
+        /// Current value
+        value: i32,
+        /// Largest value
+        max: i32,
+    },
+    /// Histogram bucket bounds and counts
+    Histogram {
+        /// Bucket upper bounds, the last bucket being unbounded
+        bounds: [u32; BUCKETS - 1],
+        /// Samples per bucket
+        buckets: [u32; BUCKETS],
+    },
+}
+
+/// Metric, as read by a [`Scraper`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metric {
+    name: [u8; MAX_NAME_LEN],
+    /// Metric value
+    pub value: Value,
+}
+
+impl Metric {
+    /// Return the metric name.
+    pub fn name(&self) -> &str {
+        let len = self
+            .name
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(MAX_NAME_LEN);
+        core::str::from_utf8(&self.name[..len]).unwrap_or_default()
+    }
+}
+
+/// Supervisor side of a metrics shared memory, mapped from a peer task
+pub struct Scraper {
+    shm: Shm<Mapped>,
+    base: NonNull<u32>,
+    capacity: u32,
+}
+
+impl Scraper {
+    /// Read the metrics laid out in `shm` by a peer [`Registry`].
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the shared memory does not hold
+    /// a metrics table of this layout version, or is too small for its
+    /// capacity, or propagates kernel errors if information retrieval fails.
+    pub fn new(mut shm: Shm<Mapped>) -> Result<Self, Error> {
+        let invalid = Error::new(Subsystem::Metrics, Status::Invalid);
+        let (base, capacity) = layout(&mut shm, false)?;
+        // SAFETY: the shared memory is larger than the header
+        let (magic, version, declared) = unsafe { (read(base, 0), read(base, 1), read(base, 3)) };
+        if magic != MAGIC || version != VERSION || declared > capacity {
+            return Err(invalid);
+        }
+        Ok(Self {
+            shm,
+            base,
+            capacity: declared,
+        })
+    }
+
+    /// Release the shared memory.
+    #[must_use]
+    pub fn release(self) -> Shm<Mapped> {
+        self.shm
+    }
+
+    /// Return the number of metrics registered so far.
+    pub fn len(&self) -> usize {
+        // SAFETY: the header is in the shared memory
+        unsafe { read(self.base, 2) }.min(self.capacity) as usize
+    }
+
+    /// Check whether no metric is registered yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read the metric `index`, returning `None` past the registered ones,
+    /// or if the metric kind is unknown.
+    pub fn get(&self, index: usize) -> Option<Metric> {
+        if index >= self.len() {
+            return None;
+        }
+        fence(Ordering::Acquire);
+        // SAFETY: the entry is below the checked capacity
+        let entry =
+            unsafe { NonNull::new_unchecked(word(self.base, HEADER_WORDS + index * ENTRY_WORDS)) };
+        let mut name = [0; MAX_NAME_LEN];
+        let mut bounds = [0; BUCKETS - 1];
+        let mut values = [0; BUCKETS];
+        // SAFETY: all the words are in the entry
+        let kind = unsafe {
+            for (i, chunk) in name.chunks_exact_mut(4).enumerate() {
+                chunk.copy_from_slice(&word(entry, i).read_volatile().to_ne_bytes());
+            }
+            for (i, bound) in bounds.iter_mut().enumerate() {
+                *bound = read(entry, BOUNDS_WORD + i);
+            }
+            for (i, value) in values.iter_mut().enumerate() {
+                *value = read(entry, VALUES_WORD + i);
+            }
+            read(entry, KIND_WORD)
+        };
+        let value = match kind {
+            COUNTER => Value::Counter(values[0]),
+            GAUGE => Value::Gauge {
+                value: values[0].cast_signed(),
+                max: values[1].cast_signed(),
+            },
+            HISTOGRAM => Value::Histogram {
+                bounds,
+                buckets: values,
+            },
+            _ => return None,
+        };
+        Some(Metric { name, value })
+    }
+
+    /// Iterate over the registered metrics.
+    pub fn iter(&self) -> impl Iterator<Item = Metric> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+}
@@ -0,0 +1,586 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! In-process fake kernel
+//!
+//! With the `mock` feature, Shield syscalls are served by a fake kernel
+//! running in the calling process instead of the Sentry kernel, so that Shield
+//! based logic can be unit-tested on the host with `cargo test`.
+//!
+//! Tests configure the fake kernel through a [`Session`], which serializes
+//! the tests using it (the kernel exchange area being process-wide) and
+//! resets the kernel state:
+//!
+//! ```ignore
+//! let kernel = shield::mock::session();
+//! kernel.add_shm(SHM_LABEL, 0x42, 256, SHMPermission::Map as u32);
+//! let shm = Shm::new(SHM_LABEL)?.map(0)?;
+//! assert!(kernel.is_mapped(0x42));
+//! ```
+//!
+//! Error paths are exercised by scripting failures, such as the third
+//! `map_shm()` call returning `Status::Busy` with [`Session::fail_nth`], or the
+//! next `copy_from_kernel()` failing with [`Session::fail_copy_from_kernel`].
+//!
+//! With the `trace-syscalls` feature, a syscall trace captured on target can be
+//! replayed with [`Session::replay`], turning a failure reproduced once on
+//! hardware into a deterministic test.
+//!
+//! The fake kernel is synchronous: a blocking wait for events that are not
+//! queued with [`Session::push_event`] returns `Status::Deadlk`, and a
+//! successful `exit()` panics, so that the exit paths are caught with
+//! `std::panic::catch_unwind`.
+
+extern crate std;
+
+pub mod syscall;
+
+#[cfg(feature = "trace-syscalls")]
+use crate::syscall_trace::SyscallRecord;
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::vec::Vec;
+use uapi::SentryExchangeable;
+use uapi::systypes::dma::GpdmaStreamConfig;
+use uapi::systypes::shm::ShmInfo;
+use uapi::systypes::{
+    DeviceHandle, EventType, ShmHandle, ShmLabel, Signal, Status, StreamHandle, StreamLabel,
+    Syscall, TaskHandle, TaskLabel,
+};
+
+/// Shared memory known by the fake kernel
+struct MockShm {
+    info: ShmInfo,
+    /// Backing memory, as `u64` for the base address to be aligned
+    _memory: Vec<u64>,
+    mapped: bool,
+    credentials: Vec<(TaskHandle, u32)>,
+}
+
+/// Event waiting to be delivered by `wait_for_event()`
+struct MockEvent {
+    kind: u8,
+    peer: u32,
+    data: Vec<u8>,
+}
+
+/// Operation a fault can be injected in
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FaultPoint {
+    Syscall(u8),
+    CopyFromKernel,
+    CopyToKernel,
+}
+
+/// Scripted failure, returning `status` from the `countdown`-th next call of
+/// the operation
+struct Fault {
+    point: FaultPoint,
+    countdown: usize,
+    status: Status,
+}
+
+/// Replayed syscall trace
+#[cfg(feature = "trace-syscalls")]
+struct Replay {
+    records: Vec<SyscallRecord>,
+    next: usize,
+    /// Status of the record being replayed
+    expected: Option<Status>,
+    divergence: Option<ReplayError>,
+}
+
+/// Divergence between a replayed trace and the syscalls issued
+#[cfg(feature = "trace-syscalls")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayError {
+    /// Index of the diverging record in the trace
+    pub index: usize,
+    /// Record expected, `None` if the trace was already fully replayed
+    pub expected: Option<SyscallRecord>,
+    /// Syscall issued, `None` if the trace has not been fully replayed
+    pub actual: Option<SyscallRecord>,
+}
+
+#[cfg(feature = "trace-syscalls")]
+impl core::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "trace record #{}: ", self.index)?;
+        match (self.expected, self.actual) {
+            (Some(expected), Some(actual)) => write!(f, "expected {expected}, got {actual}"),
+            (Some(expected), None) => write!(f, "expected {expected}, not issued"),
+            (None, Some(actual)) => write!(f, "unexpected {actual}"),
+            (None, None) => write!(f, "diverged"),
+        }
+    }
+}
+
+/// Fake kernel state
+pub(crate) struct Kernel {
+    tasks: Vec<(TaskLabel, TaskHandle)>,
+    devices: Vec<(u8, DeviceHandle)>,
+    shms: Vec<MockShm>,
+    streams: Vec<(StreamLabel, StreamHandle)>,
+    stream_configs: Vec<(StreamHandle, GpdmaStreamConfig)>,
+    overrides: Vec<(u8, Status)>,
+    faults: Vec<Fault>,
+    #[cfg(feature = "trace-syscalls")]
+    replay: Option<Replay>,
+    events: VecDeque<MockEvent>,
+    signals: Vec<(TaskHandle, Signal)>,
+    ipc: Vec<(TaskHandle, Vec<u8>)>,
+    log: Vec<u8>,
+    calls: Vec<u8>,
+    gpios: Vec<((u32, u8), bool)>,
+    uptime_us: u64,
+    alarm_us: Option<u64>,
+    random: u32,
+}
+
+impl Kernel {
+    const fn new() -> Self {
+        Self {
+            tasks: Vec::new(),
+            devices: Vec::new(),
+            shms: Vec::new(),
+            streams: Vec::new(),
+            stream_configs: Vec::new(),
+            overrides: Vec::new(),
+            faults: Vec::new(),
+            #[cfg(feature = "trace-syscalls")]
+            replay: None,
+            events: VecDeque::new(),
+            signals: Vec::new(),
+            ipc: Vec::new(),
+            log: Vec::new(),
+            calls: Vec::new(),
+            gpios: Vec::new(),
+            uptime_us: 0,
+            alarm_us: None,
+            random: 0x2545_f491,
+        }
+    }
+
+    /// Record a syscall, returning the status it is forced to return, if any.
+    fn enter(&mut self, syscall: Syscall) -> Option<Status> {
+        let id = syscall as u8;
+        self.calls.push(id);
+        self.inject(FaultPoint::Syscall(id)).or_else(|| {
+            self.overrides
+                .iter()
+                .find(|(overridden, _)| *overridden == id)
+                .map(|&(_, status)| status)
+        })
+    }
+
+    /// Count down the faults scripted on `point`, returning the status of the
+    /// fault to inject, if any.
+    fn inject(&mut self, point: FaultPoint) -> Option<Status> {
+        let mut injected = None;
+        self.faults.retain_mut(|fault| {
+            if fault.point != point {
+                return true;
+            }
+            fault.countdown -= 1;
+            if fault.countdown > 0 {
+                return true;
+            }
+            injected.get_or_insert(fault.status);
+            false
+        });
+        injected
+    }
+
+    fn script(&mut self, point: FaultPoint, nth: usize, status: Status) {
+        self.faults.push(Fault {
+            point,
+            countdown: nth.max(1),
+            status,
+        });
+    }
+
+    fn shm(&mut self, handle: ShmHandle) -> Option<&mut MockShm> {
+        self.shms.iter_mut().find(|shm| shm.info.handle == handle)
+    }
+
+    fn gpio(&self, device: u32, io: u8) -> bool {
+        self.gpios
+            .iter()
+            .any(|&(pin, high)| pin == (device, io) && high)
+    }
+
+    fn set_gpio(&mut self, device: u32, io: u8, high: bool) {
+        self.gpios.retain(|&(pin, _)| pin != (device, io));
+        self.gpios.push(((device, io), high));
+    }
+
+    fn stream(&self, handle: StreamHandle) -> bool {
+        self.streams.iter().any(|&(_, stream)| stream == handle)
+    }
+
+    fn stream_config(&self, handle: StreamHandle) -> Option<GpdmaStreamConfig> {
+        self.stream_configs
+            .iter()
+            .find(|&&(stream, _)| stream == handle)
+            .map(|&(_, config)| config)
+    }
+
+    /// Pop the first queued event matching `mask`.
+    fn pop_event(&mut self, mask: u8) -> Option<MockEvent> {
+        let index = self
+            .events
+            .iter()
+            .position(|event| event.kind & mask != 0)?;
+        self.events.remove(index)
+    }
+
+    /// Fire the armed alarm if due before the end of a wait for `mask`
+    /// events lasting `timeout` milliseconds, letting the time elapse.
+    fn fire_alarm(&mut self, mask: u8, timeout: i32) -> Option<MockEvent> {
+        let signal = u8::from(EventType::Signal);
+        let at_us = self.alarm_us.filter(|_| mask & signal != 0)?;
+        let end_us = match timeout {
+            ..0 => self.uptime_us,
+            0 => u64::MAX,
+            timeout => self.uptime_us + u64::from(timeout.unsigned_abs()) * 1000,
+        };
+        if at_us > end_us {
+            return None;
+        }
+        self.alarm_us = None;
+        self.uptime_us = self.uptime_us.max(at_us);
+        Some(MockEvent {
+            kind: signal,
+            peer: 0,
+            data: (Signal::Alarm as u32).to_ne_bytes().to_vec(),
+        })
+    }
+}
+
+static KERNEL: Mutex<Kernel> = Mutex::new(Kernel::new());
+
+static SESSION: Mutex<()> = Mutex::new(());
+
+/// Execute `f` with an exclusive access to the fake kernel state
+pub(crate) fn with_kernel<R>(f: impl FnOnce(&mut Kernel) -> R) -> R {
+    f(&mut KERNEL.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// `copy_from_kernel()`, unless a fault is injected
+pub(crate) fn copy_from_kernel<T>(to: &mut T) -> Result<Status, Status>
+where
+    T: SentryExchangeable + ?Sized,
+{
+    match with_kernel(|kernel| kernel.inject(FaultPoint::CopyFromKernel)) {
+        Some(status) => Err(status),
+        None => uapi::copy_from_kernel(to),
+    }
+}
+
+/// `copy_to_kernel()`, unless a fault is injected
+#[allow(dead_code)] // only needed by some of the optional subsystems
+pub(crate) fn copy_to_kernel<T>(from: &T) -> Result<Status, Status>
+where
+    T: SentryExchangeable + ?Sized,
+{
+    match with_kernel(|kernel| kernel.inject(FaultPoint::CopyToKernel)) {
+        Some(status) => Err(status),
+        None => uapi::copy_to_kernel(from),
+    }
+}
+
+/// Match the syscall `id`, whose arguments digest is `args`, against the
+/// replayed trace, returning the status it is forced to return, if any.
+///
+/// Syscalls recorded as successful are run by the fake kernel, for it to
+/// deliver their results.
+#[cfg(feature = "trace-syscalls")]
+pub(crate) fn replay_enter(id: u8, args: u32) -> Option<Status> {
+    with_kernel(|kernel| {
+        let replay = kernel
+            .replay
+            .as_mut()
+            .filter(|replay| replay.divergence.is_none())?;
+        replay.expected = None;
+        let expected = replay.records.get(replay.next).copied();
+        match expected {
+            Some(record) if record.syscall == id && record.args == args => {
+                replay.next += 1;
+                replay.expected = Some(record.status);
+                (record.status != Status::Ok).then_some(record.status)
+            }
+            _ => {
+                replay.divergence = Some(ReplayError {
+                    index: replay.next,
+                    expected,
+                    actual: Some(SyscallRecord {
+                        syscall: id,
+                        args,
+                        status: Status::Ok,
+                        timestamp_us: 0,
+                    }),
+                });
+                None
+            }
+        }
+    })
+}
+
+/// Check the status of the syscall being replayed against the trace.
+#[cfg(feature = "trace-syscalls")]
+pub(crate) fn replay_exit(status: Status) {
+    with_kernel(|kernel| {
+        let Some(replay) = kernel.replay.as_mut() else {
+            return;
+        };
+        if replay
+            .expected
+            .take()
+            .is_some_and(|expected| expected != status)
+        {
+            let index = replay.next - 1;
+            let expected = replay.records[index];
+            replay.divergence = Some(ReplayError {
+                index,
+                expected: Some(expected),
+                actual: Some(SyscallRecord { status, ..expected }),
+            });
+        }
+    });
+}
+
+/// Start a fake kernel session, with a fresh kernel state, no cached shared
+/// memory information and an empty syscall audit log.
+///
+/// Sessions are exclusive: this blocks until the previous session is dropped.
+pub fn session() -> Session {
+    let lock = SESSION.lock().unwrap_or_else(PoisonError::into_inner);
+    with_kernel(|kernel| *kernel = Kernel::new());
+    #[cfg(feature = "shm")]
+    crate::shm::clear_info_cache();
+    #[cfg(feature = "health")]
+    crate::health::clear();
+    #[cfg(feature = "selftest")]
+    crate::selftest::clear();
+    #[cfg(feature = "audit-syscalls")]
+    crate::syscall_audit::clear();
+    Session { _lock: lock }
+}
+
+/// Fake kernel session, see [`session`]
+pub struct Session {
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl Session {
+    /// Declare the task `label`, whose handle is `handle`.
+    pub fn add_task(&self, label: TaskLabel, handle: TaskHandle) {
+        with_kernel(|kernel| kernel.tasks.push((label, handle)));
+    }
+
+    /// Declare a shared memory of `len` bytes, with the given permissions
+    /// (`SHMPermission` mask). Returns its base address.
+    pub fn add_shm(&self, label: ShmLabel, handle: ShmHandle, len: usize, perms: u32) -> usize {
+        with_kernel(|kernel| {
+            let memory = std::vec![0_u64; len.div_ceil(size_of::<u64>())];
+            let base = memory.as_ptr().expose_provenance();
+            kernel.shms.push(MockShm {
+                info: ShmInfo {
+                    handle,
+                    label,
+                    base,
+                    len,
+                    perms,
+                },
+                _memory: memory,
+                mapped: false,
+                credentials: Vec::new(),
+            });
+            base
+        })
+    }
+
+    /// Declare the device `label`, owned by the task, whose handle is
+    /// `handle`.
+    pub fn add_device(&self, label: u8, handle: DeviceHandle) {
+        with_kernel(|kernel| kernel.devices.push((label, handle)));
+    }
+
+    /// Declare the DMA stream `label`, whose handle is `handle`.
+    pub fn add_dma_stream(&self, label: StreamLabel, handle: StreamHandle) {
+        with_kernel(|kernel| kernel.streams.push((label, handle)));
+    }
+
+    /// Set the static configuration of the DMA stream `handle`, delivered by
+    /// `dma_get_stream_info()`.
+    ///
+    /// Starting a memory-to-memory stream then copies `config.transfer_len`
+    /// bytes from `config.source` to `config.dest`, which must be valid host
+    /// addresses, and queues its completion event.
+    pub fn set_dma_stream_config(&self, handle: StreamHandle, config: GpdmaStreamConfig) {
+        with_kernel(|kernel| {
+            kernel
+                .stream_configs
+                .retain(|&(stream, _)| stream != handle);
+            kernel.stream_configs.push((handle, config));
+        });
+    }
+
+    /// Force `syscall` to return `status`, until [`Session::clear_status`].
+    pub fn set_status(&self, syscall: Syscall, status: Status) {
+        with_kernel(|kernel| {
+            let id = syscall as u8;
+            kernel.overrides.retain(|(overridden, _)| *overridden != id);
+            kernel.overrides.push((id, status));
+        });
+    }
+
+    /// Let `syscall` behave normally again.
+    pub fn clear_status(&self, syscall: Syscall) {
+        let id = syscall as u8;
+        with_kernel(|kernel| kernel.overrides.retain(|(overridden, _)| *overridden != id));
+    }
+
+    /// Make the `nth` next call of `syscall` (1 being the next one) return
+    /// `status`, once.
+    ///
+    /// Scripted failures take precedence over [`Session::set_status`].
+    pub fn fail_nth(&self, syscall: Syscall, nth: usize, status: Status) {
+        with_kernel(|kernel| kernel.script(FaultPoint::Syscall(syscall as u8), nth, status));
+    }
+
+    /// Make the `nth` next `copy_from_kernel()` (1 being the next one) fail
+    /// with `status`, once.
+    pub fn fail_copy_from_kernel(&self, nth: usize, status: Status) {
+        with_kernel(|kernel| kernel.script(FaultPoint::CopyFromKernel, nth, status));
+    }
+
+    /// Make the `nth` next `copy_to_kernel()` (1 being the next one) fail with
+    /// `status`, once.
+    pub fn fail_copy_to_kernel(&self, nth: usize, status: Status) {
+        with_kernel(|kernel| kernel.script(FaultPoint::CopyToKernel, nth, status));
+    }
+
+    /// Replay the syscall trace `records`, typically captured on target.
+    ///
+    /// The next syscalls must match the trace records, in order, and return
+    /// the recorded statuses: failures are injected, while successful
+    /// syscalls are run by the fake kernel, which must be set up for them to
+    /// succeed. Divergences are reported by [`Session::replay_result`].
+    #[cfg(feature = "trace-syscalls")]
+    pub fn replay(&self, records: &[SyscallRecord]) {
+        with_kernel(|kernel| {
+            kernel.replay = Some(Replay {
+                records: records.to_vec(),
+                next: 0,
+                expected: None,
+                divergence: None,
+            });
+        });
+    }
+
+    /// Replay a trace written by `syscall_trace::dump()`, lines which are not
+    /// trace records being ignored. Returns the number of records to replay.
+    #[cfg(feature = "trace-syscalls")]
+    pub fn replay_dump(&self, dump: &str) -> usize {
+        let records: Vec<SyscallRecord> =
+            dump.lines().filter_map(|line| line.parse().ok()).collect();
+        self.replay(&records);
+        records.len()
+    }
+
+    /// Check that the replayed trace has been fully and exactly reproduced.
+    ///
+    /// # Errors
+    /// Returns the first divergence from the trace, or the first record not
+    /// reproduced.
+    #[cfg(feature = "trace-syscalls")]
+    pub fn replay_result(&self) -> Result<(), ReplayError> {
+        with_kernel(|kernel| {
+            let Some(replay) = kernel.replay.as_ref() else {
+                return Ok(());
+            };
+            if let Some(divergence) = replay.divergence {
+                return Err(divergence);
+            }
+            match replay.records.get(replay.next) {
+                Some(&expected) => Err(ReplayError {
+                    index: replay.next,
+                    expected: Some(expected),
+                    actual: None,
+                }),
+                None => Ok(()),
+            }
+        })
+    }
+
+    /// Set the uptime clock, in microseconds.
+    pub fn set_uptime_us(&self, uptime_us: u64) {
+        with_kernel(|kernel| kernel.uptime_us = uptime_us);
+    }
+
+    /// Set the level of the GPIO `io` of the device `device`, the unset ones
+    /// reading low.
+    pub fn set_gpio(&self, device: u32, io: u8, high: bool) {
+        with_kernel(|kernel| kernel.set_gpio(device, io, high));
+    }
+
+    /// Return the level of the GPIO `io` of the device `device`, as set by
+    /// the task or the test.
+    pub fn gpio(&self, device: u32, io: u8) -> bool {
+        with_kernel(|kernel| kernel.gpio(device, io))
+    }
+
+    /// Queue an event, to be delivered by `wait_for_event()`.
+    pub fn push_event(&self, kind: EventType, peer: u32, data: &[u8]) {
+        with_kernel(|kernel| {
+            kernel.events.push_back(MockEvent {
+                kind: kind.into(),
+                peer,
+                data: data.to_vec(),
+            });
+        });
+    }
+
+    /// Queue a signal event, sent by `peer`.
+    pub fn push_signal(&self, signal: Signal, peer: TaskHandle) {
+        self.push_event(EventType::Signal, peer, &(signal as u32).to_ne_bytes());
+    }
+
+    /// Check whether the shared memory `handle` is mapped.
+    pub fn is_mapped(&self, handle: ShmHandle) -> bool {
+        with_kernel(|kernel| kernel.shm(handle).is_some_and(|shm| shm.mapped))
+    }
+
+    /// Return the credentials granted on the shared memory `handle`.
+    pub fn credentials(&self, handle: ShmHandle) -> Vec<(TaskHandle, u32)> {
+        with_kernel(|kernel| {
+            kernel
+                .shm(handle)
+                .map(|shm| shm.credentials.clone())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Return the signals sent so far, as (target, signal) pairs.
+    pub fn signals(&self) -> Vec<(TaskHandle, Signal)> {
+        with_kernel(|kernel| kernel.signals.clone())
+    }
+
+    /// Return the IPC messages sent so far, as (target, message) pairs.
+    pub fn sent_ipc(&self) -> Vec<(TaskHandle, Vec<u8>)> {
+        with_kernel(|kernel| kernel.ipc.clone())
+    }
+
+    /// Return the content emitted so far through the kernel log channel.
+    pub fn log_output(&self) -> Vec<u8> {
+        with_kernel(|kernel| kernel.log.clone())
+    }
+
+    /// Return the number of calls of `syscall` so far.
+    pub fn call_count(&self, syscall: Syscall) -> usize {
+        let id = syscall as u8;
+        with_kernel(|kernel| kernel.calls.iter().filter(|&&call| call == id).count())
+    }
+}
@@ -0,0 +1,372 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Fake kernel syscalls, with the `sentry_uapi::syscall` signatures
+
+#![allow(clippy::needless_pass_by_value)]
+
+use uapi::systypes::dma::{GpdmaChanInt, GpdmaTransferType};
+use uapi::systypes::{
+    AlarmFlag, CPUSleep, DeviceHandle, EventType, Precision, ShmHandle, ShmLabel, Signal,
+    SleepDuration, SleepMode, Status, StreamHandle, StreamLabel, Syscall, TaskHandle, TaskLabel,
+};
+
+use super::{Kernel, MockEvent, with_kernel};
+use crate::sys::exchange::{
+    EXCHANGE_LEN, deliver_event, deliver_shm_info, deliver_stream_config, deliver_u32, deliver_u64,
+    read_exchange, write_exchange,
+};
+
+/// CPU frequency used to convert the uptime to cycles
+const CYCLES_PER_US: u64 = 64;
+
+/// Run the syscall `syscall` with `f`, unless its status is forced.
+fn call(syscall: Syscall, f: impl FnOnce(&mut Kernel) -> Status) -> Status {
+    with_kernel(|kernel| match kernel.enter(syscall) {
+        Some(status) => status,
+        None => f(kernel),
+    })
+}
+
+/// Unless a failure is scripted, panics with the exit status, as the task
+/// never returns from a successful exit.
+pub fn exit(status: i32) -> Status {
+    match call(Syscall::Exit, |_| Status::Ok) {
+        Status::Ok => panic!("task exited with status {status}"),
+        status => status,
+    }
+}
+
+pub fn get_process_handle(process: TaskLabel) -> Status {
+    call(Syscall::GetProcessHandle, |kernel| {
+        match kernel.tasks.iter().find(|&&(label, _)| label == process) {
+            Some(&(_, handle)) => {
+                deliver_u32(handle);
+                Status::Ok
+            }
+            None => Status::Invalid,
+        }
+    })
+}
+
+pub fn get_shm_handle(shm: ShmLabel) -> Status {
+    call(Syscall::GetShmHandle, |kernel| {
+        match kernel.shms.iter().find(|mock| mock.info.label == shm) {
+            Some(mock) => {
+                deliver_u32(mock.info.handle);
+                Status::Ok
+            }
+            None => Status::Invalid,
+        }
+    })
+}
+
+pub fn get_dma_stream_handle(stream: StreamLabel) -> Status {
+    call(Syscall::GetDmaStreamHandle, |kernel| {
+        match kernel.streams.iter().find(|&&(label, _)| label == stream) {
+            Some(&(_, handle)) => {
+                deliver_u32(handle);
+                Status::Ok
+            }
+            None => Status::Invalid,
+        }
+    })
+}
+
+pub fn sched_yield() -> Status {
+    call(Syscall::Yield, |_| Status::Ok)
+}
+
+pub fn sleep(duration_ms: SleepDuration, _mode: SleepMode) -> Status {
+    call(Syscall::Sleep, |kernel| {
+        kernel.uptime_us += u64::from(u32::from(duration_ms)) * 1000;
+        Status::Ok
+    })
+}
+
+pub fn start(_process: TaskLabel) -> Status {
+    call(Syscall::Start, |_| Status::Ok)
+}
+
+pub fn map_dev(_dev: DeviceHandle) -> Status {
+    call(Syscall::MapDev, |_| Status::Ok)
+}
+
+pub fn map_shm(shm: ShmHandle) -> Status {
+    call(Syscall::MapShm, |kernel| match kernel.shm(shm) {
+        None => Status::Invalid,
+        Some(mock) if mock.mapped => Status::AlreadyMapped,
+        Some(mock) if mock.info.perms & uapi::systypes::SHMPermission::Map as u32 == 0 => {
+            Status::Denied
+        }
+        Some(mock) => {
+            mock.mapped = true;
+            Status::Ok
+        }
+    })
+}
+
+pub fn unmap_dev(_dev: DeviceHandle) -> Status {
+    call(Syscall::UnmapDev, |_| Status::Ok)
+}
+
+pub fn unmap_shm(shm: ShmHandle) -> Status {
+    call(Syscall::UnmapShm, |kernel| match kernel.shm(shm) {
+        Some(mock) if mock.mapped => {
+            mock.mapped = false;
+            Status::Ok
+        }
+        _ => Status::Invalid,
+    })
+}
+
+pub fn shm_set_credential(shm: ShmHandle, id: TaskHandle, shm_perm: u32) -> Status {
+    call(Syscall::SHMSetCredential, |kernel| match kernel.shm(shm) {
+        None => Status::Invalid,
+        Some(mock) if mock.mapped => Status::Busy,
+        Some(mock) => {
+            mock.credentials.retain(|&(task, _)| task != id);
+            mock.credentials.push((id, shm_perm));
+            Status::Ok
+        }
+    })
+}
+
+pub fn send_ipc(target: TaskHandle, length: u8) -> Status {
+    call(Syscall::SendIPC, |kernel| {
+        kernel
+            .ipc
+            .push((target, read_exchange(usize::from(length))));
+        Status::Ok
+    })
+}
+
+pub fn send_signal(resource: u32, signal_type: Signal) -> Status {
+    call(Syscall::SendSignal, |kernel| {
+        kernel.signals.push((resource, signal_type));
+        Status::Ok
+    })
+}
+
+pub fn gpio_get(resource: u32, io: u8) -> Status {
+    call(Syscall::GpioGet, |kernel| {
+        write_exchange(&[u8::from(kernel.gpio(resource, io))]);
+        Status::Ok
+    })
+}
+
+pub fn gpio_set(resource: u32, io: u8, val: bool) -> Status {
+    call(Syscall::GpioSet, |kernel| {
+        kernel.set_gpio(resource, io, val);
+        Status::Ok
+    })
+}
+
+pub fn gpio_reset(resource: u32, io: u8) -> Status {
+    call(Syscall::GpioReset, |kernel| {
+        kernel.set_gpio(resource, io, false);
+        Status::Ok
+    })
+}
+
+pub fn gpio_toggle(resource: u32, io: u8) -> Status {
+    call(Syscall::GpioToggle, |kernel| {
+        let high = kernel.gpio(resource, io);
+        kernel.set_gpio(resource, io, !high);
+        Status::Ok
+    })
+}
+
+pub fn gpio_configure(_resource: u32, _io: u8) -> Status {
+    call(Syscall::GpioConfigure, |_| Status::Ok)
+}
+
+pub fn get_device_handle(devlabel: u8) -> Status {
+    call(Syscall::GetDeviceHandle, |kernel| {
+        match kernel.devices.iter().find(|&&(label, _)| label == devlabel) {
+            Some(&(_, handle)) => {
+                deliver_u32(handle);
+                Status::Ok
+            }
+            None => Status::Invalid,
+        }
+    })
+}
+
+pub fn irq_acknowledge(_irq: u16) -> Status {
+    call(Syscall::IrqAcknowledge, |_| Status::Ok)
+}
+
+pub fn irq_enable(_irq: u16) -> Status {
+    call(Syscall::IrqEnable, |_| Status::Ok)
+}
+
+pub fn irq_disable(_irq: u16) -> Status {
+    call(Syscall::IrqDisable, |_| Status::Ok)
+}
+
+/// Deliver the first queued event matching `mask`.
+///
+/// Without queued event, the armed alarm fires if due before the end of the
+/// wait. Otherwise, a non-blocking wait returns `Status::Again`, a wait with
+/// timeout lets the time elapse and returns `Status::Timeout`, and an
+/// infinite wait returns `Status::Deadlk`.
+pub fn wait_for_event(mask: u8, timeout: i32) -> Status {
+    call(Syscall::WaitForEvent, |kernel| {
+        match kernel
+            .pop_event(mask)
+            .or_else(|| kernel.fire_alarm(mask, timeout))
+        {
+            Some(event) => deliver_event(event.kind, event.peer, &event.data),
+            None if timeout < 0 => Status::Again,
+            None if timeout == 0 => Status::Deadlk,
+            None => {
+                kernel.uptime_us += u64::from(timeout.unsigned_abs()) * 1000;
+                Status::Timeout
+            }
+        }
+    })
+}
+
+pub fn pm_manage(_mode: CPUSleep) -> Status {
+    call(Syscall::PmManage, |_| Status::Ok)
+}
+
+pub fn alarm(timeout_ms: u32, flag: AlarmFlag) -> Status {
+    call(Syscall::Alarm, |kernel| {
+        kernel.alarm_us = match flag {
+            // a periodic alarm only fires once
+            AlarmFlag::AlarmStart | AlarmFlag::AlarmStartPeriodic => {
+                Some(kernel.uptime_us + u64::from(timeout_ms) * 1000)
+            }
+            AlarmFlag::AlarmStop => None,
+        };
+        Status::Ok
+    })
+}
+
+pub fn log(length: usize) -> Status {
+    if length > uapi::length() {
+        return Status::Invalid;
+    }
+    call(Syscall::Log, |kernel| {
+        kernel.log.extend(read_exchange(length));
+        Status::Ok
+    })
+}
+
+pub fn get_random() -> Status {
+    call(Syscall::GetRandom, |kernel| {
+        // xorshift32, deterministic across sessions
+        let mut random = kernel.random;
+        random ^= random << 13;
+        random ^= random >> 17;
+        random ^= random << 5;
+        kernel.random = random;
+        deliver_u32(random);
+        Status::Ok
+    })
+}
+
+pub fn get_cycle(precision: Precision) -> Status {
+    call(Syscall::GetCycle, |kernel| {
+        let uptime_us = kernel.uptime_us;
+        deliver_u64(match precision {
+            Precision::Cycle => uptime_us * CYCLES_PER_US,
+            Precision::Nanoseconds => uptime_us * 1000,
+            Precision::Microseconds => uptime_us,
+            Precision::Milliseconds => uptime_us / 1000,
+        });
+        Status::Ok
+    })
+}
+
+pub fn pm_set_clock(_clk_reg: u32, _clkmsk: u32, _val: u32) -> Status {
+    call(Syscall::PmSetClock, |_| Status::Ok)
+}
+
+fn dma_call(syscall: Syscall, dmah: StreamHandle) -> Status {
+    call(syscall, |kernel| {
+        if kernel.stream(dmah) {
+            Status::Ok
+        } else {
+            Status::Invalid
+        }
+    })
+}
+
+/// A configured memory-to-memory stream copies its buffer right away, and
+/// queues its completion event.
+pub fn dma_start_stream(dmah: StreamHandle) -> Status {
+    call(Syscall::DmaStartStream, |kernel| {
+        if !kernel.stream(dmah) {
+            return Status::Invalid;
+        }
+        let Some(config) = kernel.stream_config(dmah) else {
+            return Status::Ok;
+        };
+        if config.transfer_type == GpdmaTransferType::MemoryToMemory as u16 {
+            // SAFETY: valid host addresses, per set_dma_stream_config()
+            unsafe {
+                core::ptr::copy(
+                    core::ptr::with_exposed_provenance::<u8>(config.source),
+                    core::ptr::with_exposed_provenance_mut(config.dest),
+                    config.transfer_len,
+                );
+            }
+            let mut data = dmah.to_ne_bytes().to_vec();
+            data.push(GpdmaChanInt::TransferComplete as u8);
+            kernel.events.push_back(MockEvent {
+                kind: EventType::Dma.into(),
+                peer: 0,
+                data,
+            });
+        }
+        Status::Ok
+    })
+}
+
+pub fn dma_suspend_stream(dmah: StreamHandle) -> Status {
+    dma_call(Syscall::DmaSuspendStream, dmah)
+}
+
+pub fn dma_get_stream_status(dmah: StreamHandle) -> Status {
+    dma_call(Syscall::DmaGetStreamStatus, dmah)
+}
+
+pub fn shm_get_infos(shm: ShmHandle) -> Status {
+    call(Syscall::ShmGetInfos, |kernel| match kernel.shm(shm) {
+        Some(mock) => {
+            deliver_shm_info(&mock.info);
+            Status::Ok
+        }
+        None => Status::Invalid,
+    })
+}
+
+pub fn dma_assign_stream(dmah: StreamHandle) -> Status {
+    dma_call(Syscall::DmaAssignStream, dmah)
+}
+
+pub fn dma_unassign_stream(dmah: StreamHandle) -> Status {
+    dma_call(Syscall::DmaUnassignStream, dmah)
+}
+
+/// Streams with no configuration set deliver an all-zero one.
+pub fn dma_get_stream_info(dmah: StreamHandle) -> Status {
+    call(Syscall::DmaGetStreamInfo, |kernel| {
+        if !kernel.stream(dmah) {
+            return Status::Invalid;
+        }
+        match kernel.stream_config(dmah) {
+            Some(config) => deliver_stream_config(&config),
+            None => write_exchange(&[0; EXCHANGE_LEN]),
+        }
+        Status::Ok
+    })
+}
+
+pub fn dma_resume_stream(dmah: StreamHandle) -> Status {
+    dma_call(Syscall::DmaResumeStream, dmah)
+}
@@ -0,0 +1,286 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Network frames exchange with a driver task
+//!
+//! [`ShmDevice`] exchanges Ethernet frames with the task owning the MAC over
+//! two shared memory rings, one per direction, mapped by both tasks. A
+//! one-byte IPC, the doorbell, tells the peer that a ring was updated. It
+//! implements [`smoltcp::phy::Device`], so that a TCP/IP stack can run over
+//! it without owning the MAC, such as the [`Stack`] serving the [`TcpStream`]
+//! and [`UdpSocket`] sockets.
+//!
+//! Each ring starts with two little-endian `u32` counters, `head`, advanced
+//! by the producer once a frame is written, and `tail`, advanced by the
+//! consumer once a frame is read, followed by [`SLOT_LEN`] bytes slots, each
+//! holding the frame length (`u16`, LE), two reserved bytes and the frame.
+//! The counters wrap, the ring holding `head - tail` frames.
+//!
+//! ```ignore
+//! let device = ShmDevice::new(rx_shm, tx_shm, driver)?;
+//! let stack = Stack::new(device, Config::new(MAC.into()), &mut storage);
+//! let socket = UdpSocket::bind(&stack, 5683, rx_buffer, tx_buffer)?;
+//! let (len, peer) = socket.recv_from(&mut request)?;
+//! socket.send_to(&reply(&request[..len]), peer)?;
+//! ```
+
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{Ordering, fence};
+use smoltcp::phy::{self, DeviceCapabilities, Medium};
+use smoltcp::time::Instant;
+use uapi::systypes::{EventType, Status, TaskHandle};
+
+use crate::error::Error;
+use crate::shm::{Mapped, Shm};
+
+mod stack;
+
+pub use stack::{Notify, Stack, TcpStream, UdpSocket};
+
+/// Largest Ethernet frame, headers included and FCS excluded
+pub const MTU: usize = 1514;
+
+/// Ring slot length, in bytes
+pub const SLOT_LEN: usize = (4 + MTU).next_multiple_of(4);
+
+/// Doorbell IPC payload
+const DOORBELL: u8 = 0xdb;
+
+const RING_HEADER_LEN: usize = 8;
+
+/// Shared memory frame ring
+struct Ring {
+    shm: Shm<Mapped>,
+    base: NonNull<u8>,
+    slots: u32,
+}
+
+impl Ring {
+    fn new(mut shm: Shm<Mapped>) -> Result<Self, Error> {
+        let invalid = shm.error(Status::Invalid);
+        let len = shm.length()?;
+        let base = shm.base_address()?;
+        let slots = len.saturating_sub(RING_HEADER_LEN) / SLOT_LEN;
+        if slots == 0 || base % align_of::<u32>() != 0 || !shm.is_writable() {
+            return Err(invalid);
+        }
+        let base = NonNull::new(ptr::with_exposed_provenance_mut(base)).ok_or(invalid)?;
+        Ok(Self {
+            shm,
+            base,
+            slots: u32::try_from(slots).map_err(|_| invalid)?,
+        })
+    }
+
+    fn counter(&self, index: usize) -> *mut u32 {
+        // SAFETY: the ring is larger than its header, which is aligned
+        unsafe { self.base.cast::<u32>().as_ptr().add(index) }
+    }
+
+    fn head(&self) -> u32 {
+        // SAFETY: aligned counter of the mapped ring
+        u32::from_le(unsafe { self.counter(0).read_volatile() })
+    }
+
+    fn tail(&self) -> u32 {
+        // SAFETY: see head
+        u32::from_le(unsafe { self.counter(1).read_volatile() })
+    }
+
+    fn slot(&self, counter: u32) -> *mut u8 {
+        let index = (counter % self.slots) as usize;
+        // SAFETY: the slot is in the ring
+        unsafe { self.base.as_ptr().add(RING_HEADER_LEN + index * SLOT_LEN) }
+    }
+
+    /// Return the slot of the oldest queued frame, if any.
+    fn readable(&self) -> Option<u32> {
+        let tail = self.tail();
+        (self.head() != tail).then_some(tail)
+    }
+
+    /// Return the next free slot, if any.
+    fn writable(&self) -> Option<u32> {
+        let head = self.head();
+        (head.wrapping_sub(self.tail()) < self.slots).then_some(head)
+    }
+
+    /// Publish `counter` as the new head or tail, once the slot accesses
+    /// are done.
+    fn publish(&self, index: usize, counter: u32) {
+        fence(Ordering::Release);
+        // SAFETY: see head
+        unsafe { self.counter(index).write_volatile(counter.to_le()) };
+    }
+}
+
+/// Frame device over shared memory rings, see the [module](self)
+/// documentation
+pub struct ShmDevice {
+    rx: Ring,
+    tx: Tx,
+}
+
+/// Transmit side, borrowed apart from the receive side by the tokens
+struct Tx {
+    ring: Ring,
+    driver: TaskHandle,
+    doorbell_errors: u32,
+}
+
+impl Tx {
+    fn ring_doorbell(&mut self) {
+        let message: &[u8] = &[DOORBELL];
+        let status = match crate::sys::copy_to_kernel(&message) {
+            Ok(Status::Ok) => crate::sys::syscall::send_ipc(self.driver, 1),
+            Ok(status) | Err(status) => status,
+        };
+        // the frame stays queued, the driver finding it on its next doorbell
+        if status != Status::Ok {
+            self.doorbell_errors = self.doorbell_errors.wrapping_add(1);
+        }
+    }
+}
+
+impl ShmDevice {
+    /// Create the device exchanging frames with the task `driver`, over the
+    /// `rx` ring, filled by the driver, and the `tx` ring, filled by the
+    /// device.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if a ring is too small for a slot,
+    /// misaligned or not writable (the consumer advances the tail), or
+    /// propagates kernel errors if information retrieval fails.
+    pub fn new(rx: Shm<Mapped>, tx: Shm<Mapped>, driver: TaskHandle) -> Result<Self, Error> {
+        Ok(Self {
+            rx: Ring::new(rx)?,
+            tx: Tx {
+                ring: Ring::new(tx)?,
+                driver,
+                doorbell_errors: 0,
+            },
+        })
+    }
+
+    /// Release the rx and tx shared memories.
+    #[must_use]
+    pub fn release(self) -> (Shm<Mapped>, Shm<Mapped>) {
+        (self.rx.shm, self.tx.ring.shm)
+    }
+
+    /// Return the number of doorbells which could not be sent, the frames
+    /// staying queued.
+    pub fn doorbell_errors(&self) -> u32 {
+        self.tx.doorbell_errors
+    }
+
+    /// Wait for the driver doorbell, unless a frame is queued already.
+    #[cfg(feature = "async")]
+    pub async fn wait_rx(&self) {
+        if self.rx.readable().is_none() {
+            crate::executor::wait_event_from(uapi::systypes::EventType::Ipc, self.tx.driver).await;
+        }
+    }
+}
+
+impl phy::Device for ShmDevice {
+    type RxToken<'a> = RxToken<'a>;
+    type TxToken<'a> = TxToken<'a>;
+
+    /// Return the oldest received frame, along with a transmit token for the
+    /// reply, if a frame is queued and a tx slot is free.
+    fn receive(&mut self, _timestamp: Instant) -> Option<(RxToken<'_>, TxToken<'_>)> {
+        let rx = self.rx.readable()?;
+        let tx = self.tx.ring.writable()?;
+        Some((
+            RxToken {
+                ring: &self.rx,
+                counter: rx,
+            },
+            TxToken {
+                tx: &mut self.tx,
+                counter: tx,
+            },
+        ))
+    }
+
+    /// Return a transmit token, if a tx slot is free.
+    fn transmit(&mut self, _timestamp: Instant) -> Option<TxToken<'_>> {
+        let counter = self.tx.ring.writable()?;
+        Some(TxToken {
+            tx: &mut self.tx,
+            counter,
+        })
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        let mut caps = DeviceCapabilities::default();
+        caps.medium = Medium::Ethernet;
+        caps.max_transmission_unit = MTU;
+        caps.max_burst_size = Some(self.rx.slots.min(self.tx.ring.slots) as usize);
+        caps
+    }
+}
+
+impl Notify for ShmDevice {
+    /// The driver doorbell
+    fn event(&self) -> (EventType, u32) {
+        (EventType::Ipc, self.tx.driver)
+    }
+}
+
+/// Received frame of a [`ShmDevice`]
+pub struct RxToken<'a> {
+    ring: &'a Ring,
+    counter: u32,
+}
+
+impl phy::RxToken for RxToken<'_> {
+    /// Process the frame with `f`, then free its slot.
+    fn consume<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        fence(Ordering::Acquire);
+        let slot = self.ring.slot(self.counter);
+        // SAFETY: the driver doesn't touch the slot until the tail moves past
+        // it, and the length is clamped to the slot
+        let output = unsafe {
+            let len = u16::from_le(slot.cast::<u16>().read_volatile());
+            f(core::slice::from_raw_parts(
+                slot.add(4),
+                usize::from(len).min(MTU),
+            ))
+        };
+        self.ring.publish(1, self.counter.wrapping_add(1));
+        output
+    }
+}
+
+/// Frame to transmit over a [`ShmDevice`]
+pub struct TxToken<'a> {
+    tx: &'a mut Tx,
+    counter: u32,
+}
+
+impl phy::TxToken for TxToken<'_> {
+    /// Build a `len` bytes frame with `f`, clamped to the [`MTU`], then queue
+    /// it and ring the doorbell.
+    fn consume<R, F>(self, len: usize, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        let len = len.min(MTU);
+        let slot = self.tx.ring.slot(self.counter);
+        // SAFETY: the slot is free, the driver not reading it until the head
+        // moves past it
+        let output = unsafe {
+            slot.cast::<u16>().write_volatile((len as u16).to_le());
+            f(core::slice::from_raw_parts_mut(slot.add(4), len))
+        };
+        self.tx.ring.publish(0, self.counter.wrapping_add(1));
+        self.tx.ring_doorbell();
+        output
+    }
+}
@@ -0,0 +1,656 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! TCP/IP stack and sockets
+//!
+//! A [`Stack`] runs a `smoltcp` interface over a frame device, such as a
+//! [`ShmDevice`](super::ShmDevice), along with the sockets created on it.
+//! [`TcpStream`] and [`UdpSocket`] follow their `std::net` counterparts:
+//! their blocking methods poll the stack until done, waiting in the kernel
+//! for the device [`Notify`] event or the next interface deadline in
+//! between, and their `_async` methods do the same from the
+//! [`crate::executor`]. A [`TcpStream`] implements the `embedded-io` traits,
+//! and the `embedded-io-async` ones with that feature.
+//!
+//! ```ignore
+//! let mut storage = [SocketStorage::EMPTY; 4];
+//! let stack = Stack::new(device, Config::new(MAC.into()), &mut storage);
+//! stack.with_interface(|iface| iface.update_ip_addrs(|addrs| addrs.push(ADDRESS).unwrap()));
+//!
+//! let (mut rx, mut tx) = ([0; 1024], [0; 1024]);
+//! let mut stream = TcpStream::connect(&stack, (SERVER, 80), 49152, &mut rx, &mut tx)?;
+//! stream.write_all(b"GET / HTTP/1.0\r\n\r\n")?;
+//! let len = stream.read(&mut response)?;
+//! ```
+
+use core::cell::RefCell;
+use smoltcp::iface::{Config, Interface, SocketHandle, SocketSet, SocketStorage};
+use smoltcp::phy::Device;
+use smoltcp::socket::{AnySocket, tcp, udp};
+use smoltcp::time::{Duration, Instant};
+use smoltcp::wire::{IpEndpoint, IpListenEndpoint};
+use uapi::systypes::{EventType, Status};
+
+use crate::error::{Error, Subsystem};
+
+/// `wait_for_event()` timeout value for an infinite wait
+const WFE_WAIT_FOREVER: i32 = 0;
+
+/// Frame device notifying the received frames with a kernel event
+pub trait Notify {
+    /// Return the type and source of the event notifying the received
+    /// frames, as given to [`crate::executor::wait_event_from`].
+    fn event(&self) -> (EventType, u32);
+
+    /// Handle the notification event, once received.
+    ///
+    /// # Errors
+    /// Returns the device errors.
+    fn acknowledge(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+fn error(status: Status) -> Error {
+    Error::new(Subsystem::Net, status)
+}
+
+fn now() -> Instant {
+    let now_us = crate::time::uptime_us().unwrap_or_default();
+    Instant::from_micros(i64::try_from(now_us).unwrap_or(i64::MAX))
+}
+
+/// Return the milliseconds to wait for, rounded up and at least one, so that
+/// a device unable to transmit yet is not polled in a loop.
+fn delay_ms(delay: Duration) -> u64 {
+    delay.total_micros().div_ceil(1_000).max(1)
+}
+
+struct Inner<'a, D> {
+    device: D,
+    iface: Interface,
+    sockets: SocketSet<'a>,
+    capacity: usize,
+}
+
+impl<'a, D: Device> Inner<'a, D> {
+    fn poll(&mut self) {
+        self.iface.poll(now(), &mut self.device, &mut self.sockets);
+    }
+
+    fn socket<T: AnySocket<'a>>(&mut self, handle: SocketHandle) -> &mut T {
+        self.sockets.get_mut(handle)
+    }
+}
+
+/// TCP/IP stack, see the [module](self) documentation
+pub struct Stack<'a, D> {
+    // borrowed by the socket calls, which never nest
+    inner: RefCell<Inner<'a, D>>,
+}
+
+impl<'a, D: Device + Notify> Stack<'a, D> {
+    /// Create the stack running the interface configured by `config` over
+    /// `device`, along with up to `storage.len()` sockets.
+    ///
+    /// The interface addresses and routes are set with
+    /// [`Stack::with_interface`].
+    pub fn new(mut device: D, config: Config, storage: &'a mut [SocketStorage<'a>]) -> Self {
+        let iface = Interface::new(config, &mut device, now());
+        let capacity = storage.len();
+        Self {
+            inner: RefCell::new(Inner {
+                device,
+                iface,
+                sockets: SocketSet::new(storage),
+                capacity,
+            }),
+        }
+    }
+
+    /// Release the device.
+    pub fn release(self) -> D {
+        self.inner.into_inner().device
+    }
+
+    /// Execute `f` with the interface, to set its addresses and routes.
+    pub fn with_interface<R>(&self, f: impl FnOnce(&mut Interface) -> R) -> R {
+        f(&mut self.inner.borrow_mut().iface)
+    }
+
+    /// Process the received frames and the socket timers, then transmit the
+    /// queued data, without waiting.
+    ///
+    /// The socket methods poll the stack as they wait, this is only required
+    /// to serve the sockets while none is used.
+    pub fn poll(&self) {
+        self.inner.borrow_mut().poll();
+    }
+
+    fn add<T: AnySocket<'a>>(&self, socket: T) -> Result<SocketHandle, Error> {
+        let inner = &mut *self.inner.borrow_mut();
+        if inner.sockets.iter().count() == inner.capacity {
+            return Err(error(Status::Busy));
+        }
+        Ok(inner.sockets.add(socket))
+    }
+
+    fn remove(&self, handle: SocketHandle) {
+        self.inner.borrow_mut().sockets.remove(handle);
+    }
+
+    fn with_socket<T: AnySocket<'a>, R>(
+        &self,
+        handle: SocketHandle,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> R {
+        f(self.inner.borrow_mut().socket(handle))
+    }
+
+    /// Poll the stack until `f` returns an outcome, waiting for the device
+    /// event or the next interface deadline in between.
+    ///
+    /// The other events of the device event type received meanwhile are
+    /// dropped.
+    fn block_on<T: AnySocket<'a>, R>(
+        &self,
+        handle: SocketHandle,
+        mut f: impl FnMut(&mut T) -> Option<Result<R, Error>>,
+    ) -> Result<R, Error> {
+        loop {
+            let inner = &mut *self.inner.borrow_mut();
+            inner.poll();
+            if let Some(outcome) = f(inner.socket(handle)) {
+                return outcome;
+            }
+            let timeout = match inner.iface.poll_delay(now(), &inner.sockets) {
+                Some(delay) => i32::try_from(delay_ms(delay)).unwrap_or(i32::MAX),
+                None => WFE_WAIT_FOREVER,
+            };
+            let (kind, _) = inner.device.event();
+            match crate::sys::syscall::wait_for_event(kind.into(), timeout) {
+                Status::Ok => inner.device.acknowledge()?,
+                Status::Timeout => {}
+                status => return Err(error(status)),
+            }
+        }
+    }
+
+    /// Poll the stack until `f` returns an outcome, waiting for the device
+    /// event or the next interface deadline in between, from the executor.
+    #[cfg(feature = "async")]
+    async fn run<T: AnySocket<'a>, R>(
+        &self,
+        handle: SocketHandle,
+        mut f: impl FnMut(&mut T) -> Option<Result<R, Error>>,
+    ) -> Result<R, Error> {
+        use crate::executor::{self, Either, timer};
+
+        loop {
+            let (delay, (kind, source)) = {
+                let inner = &mut *self.inner.borrow_mut();
+                inner.poll();
+                if let Some(outcome) = f(inner.socket(handle)) {
+                    return outcome;
+                }
+                let delay = inner.iface.poll_delay(now(), &inner.sockets);
+                (delay.map(delay_ms), inner.device.event())
+            };
+            let event = executor::wait_event_from(kind, source);
+            let woken = match delay {
+                Some(delay_ms) => {
+                    let at_us = crate::time::uptime_us()? + delay_ms * 1_000;
+                    executor::select2(event, timer::until(at_us)).await
+                }
+                None => Either::First(event.await),
+            };
+            if let Either::First(_) = woken {
+                self.inner.borrow_mut().device.acknowledge()?;
+            }
+        }
+    }
+}
+
+/// TCP connection, see the [module](self) documentation
+///
+/// Dropping the stream resets the connection, [`TcpStream::close`] closing it
+/// gracefully.
+pub struct TcpStream<'s, 'a, D: Device + Notify> {
+    stack: &'s Stack<'a, D>,
+    handle: SocketHandle,
+}
+
+impl<'s, 'a, D: Device + Notify> TcpStream<'s, 'a, D> {
+    fn open(
+        stack: &'s Stack<'a, D>,
+        rx_buffer: &'a mut [u8],
+        tx_buffer: &'a mut [u8],
+    ) -> Result<Self, Error> {
+        let socket = tcp::Socket::new(
+            tcp::SocketBuffer::new(rx_buffer),
+            tcp::SocketBuffer::new(tx_buffer),
+        );
+        let handle = stack.add(socket)?;
+        Ok(Self { stack, handle })
+    }
+
+    /// Start connecting to `remote` from `local_port`.
+    fn start_connect(
+        stack: &'s Stack<'a, D>,
+        remote: IpEndpoint,
+        local_port: u16,
+        rx_buffer: &'a mut [u8],
+        tx_buffer: &'a mut [u8],
+    ) -> Result<Self, Error> {
+        let stream = Self::open(stack, rx_buffer, tx_buffer)?;
+        let inner = &mut *stack.inner.borrow_mut();
+        let socket: &mut tcp::Socket = inner.sockets.get_mut(stream.handle);
+        socket
+            .connect(inner.iface.context(), remote, local_port)
+            .map_err(|_| error(Status::Invalid))?;
+        Ok(stream)
+    }
+
+    /// Start listening on `local`.
+    fn start_accept(
+        stack: &'s Stack<'a, D>,
+        local: IpListenEndpoint,
+        rx_buffer: &'a mut [u8],
+        tx_buffer: &'a mut [u8],
+    ) -> Result<Self, Error> {
+        let stream = Self::open(stack, rx_buffer, tx_buffer)?;
+        stack
+            .with_socket(stream.handle, |socket: &mut tcp::Socket| {
+                socket.listen(local)
+            })
+            .map_err(|_| error(Status::Invalid))?;
+        Ok(stream)
+    }
+
+    /// Connect to `remote` from `local_port`, the socket buffering the data
+    /// in `rx_buffer` and `tx_buffer`.
+    ///
+    /// # Errors
+    /// Returns `Status::Busy` if the stack has no free socket,
+    /// `Status::Invalid` if an endpoint is unspecified, `Status::Intr` if the
+    /// connection is refused or times out, or propagates the wait errors.
+    pub fn connect(
+        stack: &'s Stack<'a, D>,
+        remote: impl Into<IpEndpoint>,
+        local_port: u16,
+        rx_buffer: &'a mut [u8],
+        tx_buffer: &'a mut [u8],
+    ) -> Result<Self, Error> {
+        let stream = Self::start_connect(stack, remote.into(), local_port, rx_buffer, tx_buffer)?;
+        stack.block_on(stream.handle, established)?;
+        Ok(stream)
+    }
+
+    /// Connect to `remote`, see [`TcpStream::connect`].
+    ///
+    /// # Errors
+    /// See [`TcpStream::connect`].
+    #[cfg(feature = "async")]
+    pub async fn connect_async(
+        stack: &'s Stack<'a, D>,
+        remote: impl Into<IpEndpoint>,
+        local_port: u16,
+        rx_buffer: &'a mut [u8],
+        tx_buffer: &'a mut [u8],
+    ) -> Result<Self, Error> {
+        let stream = Self::start_connect(stack, remote.into(), local_port, rx_buffer, tx_buffer)?;
+        stack.run(stream.handle, established).await?;
+        Ok(stream)
+    }
+
+    /// Wait for a connection on `local`, the socket buffering the data in
+    /// `rx_buffer` and `tx_buffer`.
+    ///
+    /// # Errors
+    /// Returns `Status::Busy` if the stack has no free socket,
+    /// `Status::Invalid` if the port is zero, `Status::Intr` if the
+    /// connection is reset while established, or propagates the wait errors.
+    pub fn accept(
+        stack: &'s Stack<'a, D>,
+        local: impl Into<IpListenEndpoint>,
+        rx_buffer: &'a mut [u8],
+        tx_buffer: &'a mut [u8],
+    ) -> Result<Self, Error> {
+        let stream = Self::start_accept(stack, local.into(), rx_buffer, tx_buffer)?;
+        stack.block_on(stream.handle, established)?;
+        Ok(stream)
+    }
+
+    /// Wait for a connection on `local`, see [`TcpStream::accept`].
+    ///
+    /// # Errors
+    /// See [`TcpStream::accept`].
+    #[cfg(feature = "async")]
+    pub async fn accept_async(
+        stack: &'s Stack<'a, D>,
+        local: impl Into<IpListenEndpoint>,
+        rx_buffer: &'a mut [u8],
+        tx_buffer: &'a mut [u8],
+    ) -> Result<Self, Error> {
+        let stream = Self::start_accept(stack, local.into(), rx_buffer, tx_buffer)?;
+        stack.run(stream.handle, established).await?;
+        Ok(stream)
+    }
+
+    /// Return the local endpoint, unless the connection is closed.
+    pub fn local_endpoint(&self) -> Option<IpEndpoint> {
+        self.stack
+            .with_socket(self.handle, |socket: &mut tcp::Socket| {
+                socket.local_endpoint()
+            })
+    }
+
+    /// Return the remote endpoint, unless the connection is closed.
+    pub fn remote_endpoint(&self) -> Option<IpEndpoint> {
+        self.stack
+            .with_socket(self.handle, |socket: &mut tcp::Socket| {
+                socket.remote_endpoint()
+            })
+    }
+
+    /// Set the delay after which an unacknowledged connection is reset,
+    /// none by default.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.stack
+            .with_socket(self.handle, |socket: &mut tcp::Socket| {
+                socket.set_timeout(timeout);
+            });
+    }
+
+    /// Read the received bytes into `buf`, waiting for data if none, and
+    /// return the number of bytes read, zero once the peer closed the
+    /// connection.
+    ///
+    /// # Errors
+    /// Returns `Status::Intr` if the connection is reset, or propagates the
+    /// wait errors.
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.stack.block_on(self.handle, |socket| recv(socket, buf))
+    }
+
+    /// Read the received bytes into `buf`, see [`TcpStream::read`].
+    ///
+    /// # Errors
+    /// See [`TcpStream::read`].
+    #[cfg(feature = "async")]
+    pub async fn read_async(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.stack
+            .run(self.handle, |socket| recv(socket, buf))
+            .await
+    }
+
+    /// Queue the bytes of `buf` for transmission, waiting for room if the
+    /// transmit buffer is full, and return the number of bytes queued.
+    ///
+    /// # Errors
+    /// Returns `Status::Intr` if the connection is closed for writing, or
+    /// propagates the wait errors.
+    pub fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.stack.block_on(self.handle, |socket| send(socket, buf))
+    }
+
+    /// Queue the bytes of `buf` for transmission, see [`TcpStream::write`].
+    ///
+    /// # Errors
+    /// See [`TcpStream::write`].
+    #[cfg(feature = "async")]
+    pub async fn write_async(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.stack
+            .run(self.handle, |socket| send(socket, buf))
+            .await
+    }
+
+    /// Wait for the peer to acknowledge the queued bytes.
+    ///
+    /// # Errors
+    /// Returns `Status::Intr` if the connection is reset meanwhile, or
+    /// propagates the wait errors.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.stack.block_on(self.handle, flushed)
+    }
+
+    /// Wait for the peer to acknowledge the queued bytes, see
+    /// [`TcpStream::flush`].
+    ///
+    /// # Errors
+    /// See [`TcpStream::flush`].
+    #[cfg(feature = "async")]
+    pub async fn flush_async(&mut self) -> Result<(), Error> {
+        self.stack.run(self.handle, flushed).await
+    }
+
+    /// Close the connection for writing once the queued bytes are sent, and
+    /// wait for the peer to acknowledge it. Reading goes on until the peer
+    /// closes the connection too.
+    ///
+    /// # Errors
+    /// Returns `Status::Intr` if the connection is reset meanwhile, or
+    /// propagates the wait errors.
+    pub fn close(&mut self) -> Result<(), Error> {
+        self.stack.with_socket(self.handle, tcp::Socket::close);
+        self.stack.block_on(self.handle, closed)
+    }
+
+    /// Close the connection for writing, see [`TcpStream::close`].
+    ///
+    /// # Errors
+    /// See [`TcpStream::close`].
+    #[cfg(feature = "async")]
+    pub async fn close_async(&mut self) -> Result<(), Error> {
+        self.stack.with_socket(self.handle, tcp::Socket::close);
+        self.stack.run(self.handle, closed).await
+    }
+}
+
+impl<D: Device + Notify> Drop for TcpStream<'_, '_, D> {
+    fn drop(&mut self) {
+        self.stack.with_socket(self.handle, tcp::Socket::abort);
+        // send the reset before the socket goes away
+        self.stack.poll();
+        self.stack.remove(self.handle);
+    }
+}
+
+fn established(socket: &mut tcp::Socket) -> Option<Result<(), Error>> {
+    match socket.state() {
+        tcp::State::Listen | tcp::State::SynSent | tcp::State::SynReceived => None,
+        tcp::State::Closed => Some(Err(error(Status::Intr))),
+        _ => Some(Ok(())),
+    }
+}
+
+fn recv(socket: &mut tcp::Socket, buf: &mut [u8]) -> Option<Result<usize, Error>> {
+    match socket.recv_slice(buf) {
+        Ok(0) if !buf.is_empty() => None,
+        Ok(len) => Some(Ok(len)),
+        Err(tcp::RecvError::Finished) => Some(Ok(0)),
+        Err(tcp::RecvError::InvalidState) => Some(Err(error(Status::Intr))),
+    }
+}
+
+fn send(socket: &mut tcp::Socket, buf: &[u8]) -> Option<Result<usize, Error>> {
+    match socket.send_slice(buf) {
+        Ok(0) if !buf.is_empty() => None,
+        Ok(len) => Some(Ok(len)),
+        Err(tcp::SendError::InvalidState) => Some(Err(error(Status::Intr))),
+    }
+}
+
+fn flushed(socket: &mut tcp::Socket) -> Option<Result<(), Error>> {
+    match (socket.send_queue(), socket.state()) {
+        (0, _) => Some(Ok(())),
+        (_, tcp::State::Closed) => Some(Err(error(Status::Intr))),
+        _ => None,
+    }
+}
+
+fn closed(socket: &mut tcp::Socket) -> Option<Result<(), Error>> {
+    match socket.state() {
+        tcp::State::FinWait2 | tcp::State::TimeWait => Some(Ok(())),
+        // the peer may have closed its side first
+        tcp::State::Closed if socket.send_queue() == 0 => Some(Ok(())),
+        tcp::State::Closed => Some(Err(error(Status::Intr))),
+        _ => None,
+    }
+}
+
+impl<D: Device + Notify> embedded_io::ErrorType for TcpStream<'_, '_, D> {
+    type Error = Error;
+}
+
+impl<D: Device + Notify> embedded_io::Read for TcpStream<'_, '_, D> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        TcpStream::read(self, buf)
+    }
+}
+
+impl<D: Device + Notify> embedded_io::Write for TcpStream<'_, '_, D> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        TcpStream::write(self, buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        TcpStream::flush(self)
+    }
+}
+
+#[cfg(feature = "embedded-io-async")]
+impl<D: Device + Notify> embedded_io_async::Read for TcpStream<'_, '_, D> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.read_async(buf).await
+    }
+}
+
+#[cfg(feature = "embedded-io-async")]
+impl<D: Device + Notify> embedded_io_async::Write for TcpStream<'_, '_, D> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.write_async(buf).await
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        self.flush_async().await
+    }
+}
+
+/// UDP socket, see the [module](self) documentation
+///
+/// Dropping the socket unbinds it.
+pub struct UdpSocket<'s, 'a, D: Device + Notify> {
+    stack: &'s Stack<'a, D>,
+    handle: SocketHandle,
+}
+
+impl<'s, 'a, D: Device + Notify> UdpSocket<'s, 'a, D> {
+    /// Bind a socket to `local`, buffering the datagrams in `rx_buffer` and
+    /// `tx_buffer`.
+    ///
+    /// # Errors
+    /// Returns `Status::Busy` if the stack has no free socket, or
+    /// `Status::Invalid` if the port is zero.
+    pub fn bind(
+        stack: &'s Stack<'a, D>,
+        local: impl Into<IpListenEndpoint>,
+        rx_buffer: udp::PacketBuffer<'a>,
+        tx_buffer: udp::PacketBuffer<'a>,
+    ) -> Result<Self, Error> {
+        let handle = stack.add(udp::Socket::new(rx_buffer, tx_buffer))?;
+        let socket = Self { stack, handle };
+        stack
+            .with_socket(handle, |socket: &mut udp::Socket| socket.bind(local))
+            .map_err(|_| error(Status::Invalid))?;
+        Ok(socket)
+    }
+
+    /// Return the bound endpoint.
+    pub fn local_endpoint(&self) -> IpListenEndpoint {
+        self.stack
+            .with_socket(self.handle, |socket: &mut udp::Socket| socket.endpoint())
+    }
+
+    /// Queue the datagram `buf` for `remote`, waiting for room if the
+    /// transmit buffer is full.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if the datagram exceeds the transmit buffer
+    /// or `remote` is unspecified, or propagates the wait errors.
+    pub fn send_to(&self, buf: &[u8], remote: impl Into<IpEndpoint>) -> Result<(), Error> {
+        let remote = remote.into();
+        self.stack
+            .block_on(self.handle, |socket| send_to(socket, buf, remote))
+    }
+
+    /// Queue the datagram `buf` for `remote`, see [`UdpSocket::send_to`].
+    ///
+    /// # Errors
+    /// See [`UdpSocket::send_to`].
+    #[cfg(feature = "async")]
+    pub async fn send_to_async(
+        &self,
+        buf: &[u8],
+        remote: impl Into<IpEndpoint>,
+    ) -> Result<(), Error> {
+        let remote = remote.into();
+        self.stack
+            .run(self.handle, |socket| send_to(socket, buf, remote))
+            .await
+    }
+
+    /// Receive a datagram into `buf`, waiting for one if none, and return its
+    /// length along with its sender.
+    ///
+    /// A datagram larger than `buf` is truncated, its excess bytes being
+    /// dropped.
+    ///
+    /// # Errors
+    /// Propagates the wait errors.
+    pub fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, IpEndpoint), Error> {
+        self.stack
+            .block_on(self.handle, |socket| recv_from(socket, buf))
+    }
+
+    /// Receive a datagram into `buf`, see [`UdpSocket::recv_from`].
+    ///
+    /// # Errors
+    /// See [`UdpSocket::recv_from`].
+    #[cfg(feature = "async")]
+    pub async fn recv_from_async(&self, buf: &mut [u8]) -> Result<(usize, IpEndpoint), Error> {
+        self.stack
+            .run(self.handle, |socket| recv_from(socket, buf))
+            .await
+    }
+}
+
+impl<D: Device + Notify> Drop for UdpSocket<'_, '_, D> {
+    fn drop(&mut self) {
+        self.stack.remove(self.handle);
+    }
+}
+
+fn send_to(socket: &mut udp::Socket, buf: &[u8], remote: IpEndpoint) -> Option<Result<(), Error>> {
+    if buf.len() > socket.payload_send_capacity() {
+        return Some(Err(error(Status::Invalid)));
+    }
+    match socket.send_slice(buf, remote) {
+        Ok(()) => Some(Ok(())),
+        Err(udp::SendError::BufferFull) => None,
+        Err(udp::SendError::Unaddressable) => Some(Err(error(Status::Invalid))),
+    }
+}
+
+fn recv_from(
+    socket: &mut udp::Socket,
+    buf: &mut [u8],
+) -> Option<Result<(usize, IpEndpoint), Error>> {
+    let (datagram, meta) = socket.peek().ok()?;
+    let len = datagram.len().min(buf.len());
+    buf[..len].copy_from_slice(&datagram[..len]);
+    let remote = meta.endpoint;
+    let _ = socket.recv();
+    Some(Ok((len, remote)))
+}
@@ -4,29 +4,84 @@
 // SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
 
 use core::fmt;
-use uapi::{copy_to_kernel, syscall};
+use uapi::systypes::Status;
+
+use crate::sys::{copy_to_kernel, syscall};
+
+/// Print buffer length, matching the kernel exchange area size
+pub const PRINT_BUFFER_LEN: usize = uapi::length();
 
 // XXX for a given logger, we should support multiple sink
 // e.g. __sys_log syscall, other term, file, etc.
-struct LogSink;
+/// Kernel log channel sink
+///
+/// Formatted content is accumulated in a stack buffer, emitted through the
+/// kernel `log` syscall each time the buffer is full, and when the sink is
+/// flushed: output longer than the exchange area is emitted in several
+/// chunks, instead of being rejected by the kernel.
+struct LogSink {
+    buf: [u8; PRINT_BUFFER_LEN],
+    len: usize,
+}
+
+impl LogSink {
+    const fn new() -> Self {
+        Self {
+            buf: [0; PRINT_BUFFER_LEN],
+            len: 0,
+        }
+    }
+
+    /// Emit the buffered content through the kernel log channel.
+    fn flush(&mut self) -> fmt::Result {
+        if self.len == 0 {
+            return Ok(());
+        }
+        let chunk = &self.buf[..self.len];
+        self.len = 0;
+        match copy_to_kernel(&chunk) {
+            Ok(Status::Ok) => {}
+            _ => return Err(fmt::Error),
+        }
+        match syscall::log(chunk.len()) {
+            Status::Ok => Ok(()),
+            _ => Err(fmt::Error),
+        }
+    }
+}
 
 /// Write trait impl for `LogSink` type
 impl fmt::Write for LogSink {
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        let raw = s.as_bytes();
-        let _ = copy_to_kernel(&raw);
-        syscall::log(raw.len());
+        let mut raw = s.as_bytes();
+        while !raw.is_empty() {
+            if self.len == PRINT_BUFFER_LEN {
+                self.flush()?;
+            }
+            let count = raw.len().min(PRINT_BUFFER_LEN - self.len);
+            self.buf[self.len..self.len + count].copy_from_slice(&raw[..count]);
+            self.len += count;
+            raw = &raw[count..];
+        }
         Ok(())
     }
 }
 
 /// public print entrypoint called by macro rules
-/// # Panics
 ///
-/// Will panic if `write_fmt` panics.
+/// Output the kernel refuses is dropped, as printing is best effort.
 pub fn _print(args: fmt::Arguments) {
     use core::fmt::Write;
-    LogSink.write_fmt(args).expect("Print failed");
+    let mut sink = LogSink::new();
+    let _ = sink.write_fmt(args).and_then(|()| sink.flush());
+}
+
+/// public error print entrypoint called by macro rules
+///
+/// Sentry tasks have a single output channel: error output is emitted through
+/// the same kernel log channel as standard output.
+pub fn _eprint(args: fmt::Arguments) {
+    _print(args);
 }
 
 #[macro_export]
@@ -42,3 +97,18 @@ macro_rules! println {
     () => ($crate::print!("\n"));
     ($($arg:tt)*) => ($crate::print!("{}\n", format_args!($($arg)*)))
 }
+
+/// eprint macro implementation for shield/sentry based application
+#[macro_export]
+macro_rules! eprint {
+    ($($arg:tt)*) => {
+        ($crate::print::_eprint(format_args!($($arg)*)))
+    }
+}
+
+/// eprintln macro implementation for shield/sentry based application
+#[macro_export]
+macro_rules! eprintln {
+    () => ($crate::eprint!("\n"));
+    ($($arg:tt)*) => ($crate::eprint!("{}\n", format_args!($($arg)*)))
+}
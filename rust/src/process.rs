@@ -2,9 +2,17 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
 
-use sentry_uapi::copy_from_kernel;
+use crate::error::{Error, Subsystem};
+use crate::sys::copy_from_kernel;
+use core::sync::atomic::{AtomicU32, Ordering};
 use uapi::systypes::Status;
-use uapi::systypes::TaskLabel;
+use uapi::systypes::{Signal, TaskHandle, TaskLabel};
+
+/// Current task handle, set by [`register_current`]. 0 means unregistered.
+static CURRENT_HANDLE: AtomicU32 = AtomicU32::new(0);
+
+/// Current task label, set by [`register_current`].
+static CURRENT_LABEL: AtomicU32 = AtomicU32::new(0);
 
 /// This function retrieves the process handle associated with a given
 /// task label.
@@ -13,13 +21,13 @@ use uapi::systypes::TaskLabel;
 /// If the syscall is successful, it copies the handle from the kernel
 /// memory to the user space using `copy_from_kernel`.
 /// If the syscall fails or if the handle cannot be copied, it returns
-/// an error status.
+/// an error.
 /// # Arguments
 /// * `task` - A `TaskLabel` representing the task for which we want
 ///   to retrieve the process handle.
 /// # Returns
 /// * `Ok(u32)` - The process handle associated with the task.
-/// * `Err(Status)` - An error status indicating the failure reason.
+/// * `Err(Error)` - An error indicating the failure reason.
 /// # Example
 /// ```
 /// let task_label = 0x12 as u32;
@@ -31,14 +39,69 @@ use uapi::systypes::TaskLabel;
 /// # Errors
 ///
 /// Will return `Err` if denied by the sentry kernel.
-pub fn get_process_handle(task: TaskLabel) -> Result<u32, Status> {
-    if sentry_uapi::syscall::get_process_handle(task) != Status::Ok {
-        return Err(Status::Denied);
+#[cfg_attr(feature = "audit-syscalls", track_caller)]
+pub fn get_process_handle(task: TaskLabel) -> Result<u32, Error> {
+    let denied = Error::new(Subsystem::Process, Status::Denied);
+    if crate::sys::syscall::get_process_handle(task) != Status::Ok {
+        return Err(denied);
     }
 
     let mut handle = 0_u32;
     match copy_from_kernel(&mut handle) {
         Ok(Status::Ok) => Ok(handle),
-        _ => Err(Status::Denied),
+        _ => Err(denied),
+    }
+}
+
+/// Register the label of the current task.
+///
+/// The kernel does not deliver the current task handle at startup. This
+/// function resolves it from the task label and keeps it so that primitives
+/// that need to be woken up by peers (see [`crate::sync`]) can identify the
+/// current task.
+/// # Errors
+///
+/// Will return `Err` if the handle can't be retrieved from the kernel.
+pub fn register_current(label: TaskLabel) -> Result<TaskHandle, Error> {
+    let handle = get_process_handle(label)?;
+    CURRENT_LABEL.store(label, Ordering::Relaxed);
+    CURRENT_HANDLE.store(handle, Ordering::Relaxed);
+    Ok(handle)
+}
+
+/// Return the current task label.
+/// # Errors
+///
+/// Will return a `Status::NoEntity` error if [`register_current`] has not been
+/// called yet.
+#[inline]
+pub fn current_label() -> Result<TaskLabel, Error> {
+    current_handle().map(|_| CURRENT_LABEL.load(Ordering::Relaxed))
+}
+
+/// Return the current task handle.
+/// # Errors
+///
+/// Will return a `Status::NoEntity` error if [`register_current`] has not been
+/// called yet.
+#[inline]
+pub fn current_handle() -> Result<TaskHandle, Error> {
+    match CURRENT_HANDLE.load(Ordering::Relaxed) {
+        0 => Err(Error::new(Subsystem::Process, Status::NoEntity)),
+        handle => Ok(handle),
+    }
+}
+
+/// Send `signal` to the task `task`.
+/// # Errors
+///
+/// Will return a `Status::Busy` error if a signal is already pending for the
+/// target, or propagate kernel errors.
+#[inline]
+#[cfg_attr(feature = "audit-syscalls", track_caller)]
+pub fn send_signal(task: TaskHandle, signal: Signal) -> Result<(), Error> {
+    match crate::sys::syscall::send_signal(task, signal) {
+        Status::Ok => Ok(()),
+        status => Err(Error::new(Subsystem::Process, status).with_handle(task)),
     }
 }
@@ -0,0 +1,45 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Kernel entropy source
+//!
+//! The kernel TRNG delivers 32 bits of entropy per syscall, and requires the
+//! task to hold the random capability.
+
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+
+/// Return 32 random bits from the kernel TRNG.
+///
+/// # Errors
+/// Returns `Status::Denied` if the task is not allowed to use the kernel
+/// TRNG, or propagates kernel errors.
+#[inline]
+#[cfg_attr(feature = "audit-syscalls", track_caller)]
+pub fn random_u32() -> Result<u32, Error> {
+    match crate::sys::syscall::get_random() {
+        Status::Ok => {}
+        status => return Err(Error::new(Subsystem::Random, status)),
+    }
+
+    let mut random = 0_u32;
+    match crate::sys::copy_from_kernel(&mut random) {
+        Ok(Status::Ok) => Ok(random),
+        Ok(status) | Err(status) => Err(Error::new(Subsystem::Random, status)),
+    }
+}
+
+/// Fill `buf` with random bytes from the kernel TRNG.
+///
+/// # Errors
+/// Propagates [`random_u32`] errors, `buf` content being unspecified then.
+#[cfg_attr(feature = "audit-syscalls", track_caller)]
+pub fn fill(buf: &mut [u8]) -> Result<(), Error> {
+    for chunk in buf.chunks_mut(size_of::<u32>()) {
+        let random = random_u32()?.to_ne_bytes();
+        chunk.copy_from_slice(&random[..chunk.len()]);
+    }
+    Ok(())
+}
@@ -0,0 +1,114 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Retry of operations rejected with `Status::Busy`
+//!
+//! Kernel resources such as shared memories may be transiently busy (e.g.
+//! while the owner updates their credentials). [`Retry`] retries the
+//! operation instead of having each caller implement its own loop:
+//!
+//! ```ignore
+//! use shield::retry::Retry;
+//!
+//! (|| shm.set_credentials(peer, perms)).retry_busy(3)?;
+//! ```
+
+use uapi::systypes::Status;
+
+use crate::time;
+
+/// Backoff policy of [`Retry::retry_with_backoff`]
+///
+/// The delay between two attempts starts at `initial_delay_ms` and is doubled
+/// after each attempt, up to `max_delay_ms`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackoffPolicy {
+    /// Maximum number of retries after the first attempt
+    pub retries: u32,
+    /// Delay before the first retry, in milliseconds
+    pub initial_delay_ms: u32,
+    /// Maximum delay between two attempts, in milliseconds
+    pub max_delay_ms: u32,
+}
+
+impl BackoffPolicy {
+    /// Create a new policy, with a maximum delay of 1 second.
+    pub const fn new(retries: u32, initial_delay_ms: u32) -> Self {
+        Self {
+            retries,
+            initial_delay_ms,
+            max_delay_ms: 1000,
+        }
+    }
+
+    /// Set the maximum delay between two attempts.
+    #[must_use]
+    pub const fn max_delay_ms(mut self, max_delay_ms: u32) -> Self {
+        self.max_delay_ms = max_delay_ms;
+        self
+    }
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self::new(5, 1)
+    }
+}
+
+/// Retry of fallible operations
+///
+/// Implemented for closures returning a result whose error converts to a
+/// kernel [`Status`], i.e. `Status` itself or [`crate::Error`].
+pub trait Retry<T, E> {
+    /// Run the operation, retrying immediately (after yielding to other tasks)
+    /// up to `retries` times while it fails with `Status::Busy`.
+    ///
+    /// # Errors
+    /// Returns the last error of the operation.
+    fn retry_busy(self, retries: u32) -> Result<T, E>;
+
+    /// Run the operation, retrying it according to `policy` while it fails
+    /// with `Status::Busy`.
+    ///
+    /// # Errors
+    /// Returns the last error of the operation.
+    fn retry_with_backoff(self, policy: BackoffPolicy) -> Result<T, E>;
+}
+
+impl<T, E, F> Retry<T, E> for F
+where
+    F: FnMut() -> Result<T, E>,
+    E: Copy + Into<Status>,
+{
+    fn retry_busy(mut self, retries: u32) -> Result<T, E> {
+        let mut result = self();
+        for _ in 0..retries {
+            match result {
+                Err(err) if err.into() == Status::Busy => {
+                    crate::sys::syscall::sched_yield();
+                    result = self();
+                }
+                _ => break,
+            }
+        }
+        result
+    }
+
+    fn retry_with_backoff(mut self, policy: BackoffPolicy) -> Result<T, E> {
+        let mut delay_ms = policy.initial_delay_ms;
+        let mut result = self();
+        for _ in 0..policy.retries {
+            match result {
+                Err(err) if err.into() == Status::Busy => {
+                    // an early wake-up only shortens the delay
+                    let _ = time::sleep_ms(delay_ms.min(policy.max_delay_ms));
+                    delay_ms = delay_ms.saturating_mul(2);
+                    result = self();
+                }
+                _ => break,
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,179 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Anti-rollback counters
+//!
+//! A [`MonotonicCounter`] only ever grows: it holds the lowest version still
+//! accepted, firmware images or application data (credential sets,
+//! configuration blobs) of a lower version being rejected by
+//! [`MonotonicCounter::check`]. Once a new version is known good (an update
+//! confirmed with [`crate::update::Slots::confirm`], say),
+//! [`MonotonicCounter::advance_to`] raises the counter, revoking the older
+//! versions.
+//!
+//! Sentry having no counter syscall, the counters are backed by:
+//!
+//! - a [`crate::kvstore::KvStore`] key, with [`KvCounter`]. The flash region
+//!   being writable by the task, this only protects against a rollback
+//!   through the update path, not against an attacker rewriting the flash.
+//! - a secure element counter, with [`SecureElementCounter`], the element
+//!   enforcing the monotonicity.
+//!
+//! ```ignore
+//! let store = RefCell::new(KvStore::open(flash)?);
+//! let mut counter = KvCounter::new(&store, b"fw-version");
+//! counter.check(image_version)?;
+//! // boot the image, then once confirmed
+//! counter.advance_to(image_version)?;
+//! ```
+
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+
+fn error(status: Status) -> Error {
+    Error::new(Subsystem::Rollback, status)
+}
+
+/// Counter only ever incremented
+pub trait MonotonicCounter {
+    /// Return the counter value, 0 if never incremented.
+    ///
+    /// # Errors
+    /// Returns the backing errors.
+    fn read(&mut self) -> Result<u32, Error>;
+
+    /// Increment the counter, returning its new value.
+    ///
+    /// # Errors
+    /// Returns `Status::Denied` if the counter reached its limit, or the
+    /// backing errors.
+    fn increment(&mut self) -> Result<u32, Error>;
+
+    /// Raise the counter to `value`, leaving it untouched if already equal or
+    /// above, and returning its new value.
+    ///
+    /// The default implementation increments the counter step by step, the
+    /// backings able to set it at once overriding it.
+    ///
+    /// # Errors
+    /// See [`MonotonicCounter::increment`].
+    fn advance_to(&mut self, value: u32) -> Result<u32, Error> {
+        let mut current = self.read()?;
+        while current < value {
+            current = self.increment()?;
+        }
+        Ok(current)
+    }
+
+    /// Check that `version` is not below the counter.
+    ///
+    /// # Errors
+    /// Returns a `Status::Denied` error if `version` was revoked, or the
+    /// backing errors.
+    fn check(&mut self, version: u32) -> Result<(), Error> {
+        if version < self.read()? {
+            return Err(error(Status::Denied).with_handle(version));
+        }
+        Ok(())
+    }
+}
+
+/// Counter held by a key-value store key, as a little-endian `u32`
+///
+/// The store is borrowed through a `RefCell`, so that several counters and
+/// the other users of the store share it.
+#[cfg(feature = "kvstore")]
+pub struct KvCounter<'a, B: crate::kvstore::Backing> {
+    store: &'a core::cell::RefCell<crate::kvstore::KvStore<B>>,
+    key: &'a [u8],
+}
+
+#[cfg(feature = "kvstore")]
+impl<'a, B: crate::kvstore::Backing> KvCounter<'a, B> {
+    /// Hold the counter in the `key` of `store`.
+    pub const fn new(
+        store: &'a core::cell::RefCell<crate::kvstore::KvStore<B>>,
+        key: &'a [u8],
+    ) -> Self {
+        Self { store, key }
+    }
+
+    fn store(&self) -> Result<core::cell::RefMut<'a, crate::kvstore::KvStore<B>>, Error> {
+        self.store.try_borrow_mut().map_err(|_| error(Status::Busy))
+    }
+
+    fn write(&mut self, value: u32) -> Result<u32, Error> {
+        self.store()?.set(self.key, &value.to_le_bytes())?;
+        Ok(value)
+    }
+}
+
+#[cfg(feature = "kvstore")]
+impl<B: crate::kvstore::Backing> MonotonicCounter for KvCounter<'_, B> {
+    /// A key holding another value than a `u32` is reported as a
+    /// `Status::Critical` error.
+    fn read(&mut self) -> Result<u32, Error> {
+        let mut raw = [0; 8];
+        match self.store()?.get(self.key, &mut raw)? {
+            None => Ok(0),
+            Some(4) => Ok(u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]])),
+            Some(_) => Err(error(Status::Critical)),
+        }
+    }
+
+    fn increment(&mut self) -> Result<u32, Error> {
+        let value = self.read()?.checked_add(1).ok_or(error(Status::Denied))?;
+        self.write(value)
+    }
+
+    /// Set the counter at once, with a single store update.
+    fn advance_to(&mut self, value: u32) -> Result<u32, Error> {
+        let current = self.read()?;
+        if current >= value {
+            return Ok(current);
+        }
+        self.write(value)
+    }
+}
+
+/// Monotonic counter of a secure element
+///
+/// The secure element is borrowed through a `RefCell`, as by
+/// [`crate::secure_element::SigningKey`].
+#[cfg(feature = "secure-element")]
+pub struct SecureElementCounter<'a, T: crate::secure_element::Transport> {
+    element: &'a core::cell::RefCell<crate::secure_element::SecureElement<T>>,
+    slot: crate::secure_element::KeySlot,
+}
+
+#[cfg(feature = "secure-element")]
+impl<'a, T: crate::secure_element::Transport> SecureElementCounter<'a, T> {
+    /// Use the counter `slot` of `element`.
+    pub const fn new(
+        element: &'a core::cell::RefCell<crate::secure_element::SecureElement<T>>,
+        slot: crate::secure_element::KeySlot,
+    ) -> Self {
+        Self { element, slot }
+    }
+
+    fn element(
+        &self,
+    ) -> Result<core::cell::RefMut<'a, crate::secure_element::SecureElement<T>>, Error> {
+        self.element
+            .try_borrow_mut()
+            .map_err(|_| error(Status::Busy))
+    }
+}
+
+#[cfg(feature = "secure-element")]
+impl<T: crate::secure_element::Transport> MonotonicCounter for SecureElementCounter<'_, T> {
+    fn read(&mut self) -> Result<u32, Error> {
+        self.element()?.read_counter(self.slot)
+    }
+
+    fn increment(&mut self) -> Result<u32, Error> {
+        self.element()?.increment_counter(self.slot)
+    }
+}
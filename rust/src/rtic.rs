@@ -0,0 +1,96 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! RTIC 2 monotonic timer
+//!
+//! [`ShieldMono`] implements [`rtic_time::Monotonic`] over the kernel uptime
+//! clock: code written against an RTIC monotonic, such as
+//! `Mono::delay(100.millis())` software tasks, runs unchanged. The time base
+//! is the microsecond, as a `fugit::TimerInstantU64<1_000_000>`, and delays
+//! are driven by the [`crate::executor`] through the kernel alarm, which must
+//! be used to run the futures.
+//!
+//! ```ignore
+//! use shield::rtic::{ExtU64, Monotonic, ShieldMono as Mono};
+//!
+//! executor::run(async {
+//!     loop {
+//!         led.toggle();
+//!         Mono::delay(500.millis()).await;
+//!     }
+//! });
+//! ```
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+pub use fugit::ExtU64;
+pub use rtic_time::{Monotonic, TimeoutError};
+
+use crate::executor::{self, Either, timer};
+use crate::time;
+
+/// Instant of the uptime clock, in microseconds since the kernel startup
+pub type Instant = fugit::TimerInstantU64<1_000_000>;
+
+/// Duration, in microseconds
+pub type Duration = fugit::TimerDurationU64<1_000_000>;
+
+/// Monotonic timer over the kernel uptime clock, see the [module](self)
+/// documentation
+pub struct ShieldMono;
+
+impl ShieldMono {
+    /// Shortest duration told apart by the timer
+    pub const TICK_PERIOD: Duration = Duration::from_ticks(1);
+}
+
+impl Monotonic for ShieldMono {
+    type Instant = Instant;
+    type Duration = Duration;
+
+    fn now() -> Instant {
+        Instant::from_ticks(time::uptime_us().unwrap_or_default())
+    }
+
+    async fn delay(duration: Duration) {
+        Self::delay_until(Self::now() + duration).await;
+    }
+
+    async fn delay_until(instant: Instant) {
+        Delay { at: instant }.await;
+    }
+
+    async fn timeout_at<F: Future>(instant: Instant, future: F) -> Result<F::Output, TimeoutError> {
+        match executor::select2(future, Self::delay_until(instant)).await {
+            Either::First(output) => Ok(output),
+            Either::Second(()) => Err(TimeoutError),
+        }
+    }
+
+    async fn timeout_after<F: Future>(
+        duration: Duration,
+        future: F,
+    ) -> Result<F::Output, TimeoutError> {
+        Self::timeout_at(Self::now() + duration, future).await
+    }
+}
+
+/// Future waiting for an instant
+struct Delay {
+    at: Instant,
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if ShieldMono::now() >= self.at {
+            return Poll::Ready(());
+        }
+        timer::schedule(self.at.ticks(), cx.waker());
+        Poll::Pending
+    }
+}
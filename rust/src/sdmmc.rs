@@ -0,0 +1,534 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! SD card block device
+//!
+//! [`Sdmmc`] initializes an SD card (SDSC, SDHC or SDXC) in 4-bit mode, and
+//! reads and writes its blocks. Commands are issued through the mapped SDMMC controller, given by the
+//! board support code through the [`SdmmcRegisters`] trait, as the
+//! [`crate::uart`] registers. Data blocks move between the controller FIFO
+//! and a shared memory buffer through two DMA streams, the device tree
+//! configuring one [`BLOCK_LEN`] bytes transfer per stream: from the FIFO to
+//! the buffer (`rx`), and from the buffer to the FIFO (`tx`).
+//!
+//! ```ignore
+//! let mut card = Sdmmc::new(Sdmmc1::mapped()?, buffer, rx, tx)?;
+//! card.init()?;
+//! let mut blocks = [[0; BLOCK_LEN]; 2];
+//! card.read(0, &mut blocks).await?;
+//! ```
+//!
+//! [`BlockingSdmmc`] implements [`embedded_sdmmc::BlockDevice`] over the
+//! blocking transfers, so that the `embedded-sdmmc` FAT filesystem runs on a
+//! card slot:
+//!
+//! ```ignore
+//! let volumes = VolumeManager::new(BlockingSdmmc::new(card), clock);
+//! let volume = volumes.open_volume(VolumeIdx(0))?;
+//! ```
+
+use core::cell::RefCell;
+use embedded_sdmmc::{BlockCount, BlockIdx};
+use uapi::systypes::Status;
+
+use crate::dma::DmaStream;
+use crate::error::{Error, Subsystem};
+use crate::shm::{Mapped, Shm};
+use crate::time;
+use crate::units::{Hertz, KiloHertz, MegaHertz};
+
+/// Block length, in bytes
+pub const BLOCK_LEN: usize = 512;
+
+/// Data block
+pub type Block = [u8; BLOCK_LEN];
+
+/// Clock frequency during the card identification
+const IDENTIFICATION_CLOCK: KiloHertz = KiloHertz(400);
+
+/// Clock frequency in the default speed mode
+const TRANSFER_CLOCK: MegaHertz = MegaHertz(25);
+
+/// `ACMD41` attempts, 1 ms apart, before the card is deemed absent
+const POWER_UP_ATTEMPTS: u32 = 1000;
+
+/// Card status error bits of a R1 response
+const R1_ERRORS: u32 = 0xfdf9_8008;
+
+/// Card status `READY_FOR_DATA` bit
+const R1_READY_FOR_DATA: u32 = 1 << 8;
+
+/// `SEND_IF_COND` voltage range (2.7-3.6 V) and check pattern
+const IF_COND: u32 = 0x1aa;
+
+/// `SD_SEND_OP_COND` voltage window (3.2-3.4 V) and high capacity support
+const OP_COND: u32 = 0x4030_0000;
+
+/// Operation conditions register `busy` bit, set once powered up
+const OCR_POWERED_UP: u32 = 1 << 31;
+
+/// Operation conditions register card capacity status bit
+const OCR_HIGH_CAPACITY: u32 = 1 << 30;
+
+const GO_IDLE_STATE: u8 = 0;
+const ALL_SEND_CID: u8 = 2;
+const SEND_RELATIVE_ADDR: u8 = 3;
+const SET_BUS_WIDTH: u8 = 6;
+const SELECT_CARD: u8 = 7;
+const SEND_IF_COND: u8 = 8;
+const SEND_CSD: u8 = 9;
+const SEND_STATUS: u8 = 13;
+const SET_BLOCKLEN: u8 = 16;
+const READ_SINGLE_BLOCK: u8 = 17;
+const WRITE_BLOCK: u8 = 24;
+const SD_SEND_OP_COND: u8 = 41;
+const APP_CMD: u8 = 55;
+
+/// Command response format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    /// No response
+    None,
+    /// 48 bits response, whose CRC is checked (R1, R6, R7)
+    Short,
+    /// 48 bits response, whose CRC is not checked (R3)
+    ShortNoCrc,
+    /// 48 bits response, the card signaling busy on `DAT0` until done (R1b)
+    ShortBusy,
+    /// 136 bits response (R2)
+    Long,
+}
+
+/// Data bus width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusWidth {
+    /// `DAT0` only, during the identification
+    One,
+    /// `DAT0` to `DAT3`
+    Four,
+}
+
+/// Data transfer direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Read from the card into the FIFO
+    CardToHost,
+    /// Write from the FIFO to the card
+    HostToCard,
+}
+
+/// Command or data transfer failure reported by the controller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferError {
+    /// No response, or no data, in time
+    Timeout,
+    /// Corrupted response or data
+    Crc,
+}
+
+impl TransferError {
+    fn error(self) -> Error {
+        let status = match self {
+            Self::Timeout => Status::Timeout,
+            Self::Crc => Status::Critical,
+        };
+        Error::new(Subsystem::Storage, status)
+    }
+}
+
+/// Access to the registers of a mapped SDMMC controller
+pub trait SdmmcRegisters {
+    /// Set the card clock frequency.
+    fn set_clock(&mut self, frequency: Hertz);
+
+    /// Set the data bus width.
+    fn set_bus_width(&mut self, width: BusWidth);
+
+    /// Send the command `index` with its argument, and wait for its
+    /// response, returned with its most significant word first (only the
+    /// first word is meaningful for short responses).
+    ///
+    /// # Errors
+    /// Returns the controller error if the response timed out or is
+    /// corrupted.
+    fn command(
+        &mut self,
+        index: u8,
+        arg: u32,
+        response: Response,
+    ) -> Result<[u32; 4], TransferError>;
+
+    /// Arm the data path for a `len` bytes transfer, with its DMA requests.
+    fn start_data(&mut self, direction: Direction, len: usize);
+
+    /// Wait for the end of the data transfer, once the DMA stream completed.
+    ///
+    /// # Errors
+    /// Returns the controller error if the data timed out or is corrupted.
+    fn finish_data(&mut self) -> Result<(), TransferError>;
+}
+
+/// Initialized card
+#[derive(Clone, Copy)]
+struct Card {
+    rca: u32,
+    high_capacity: bool,
+    blocks: u32,
+}
+
+impl Card {
+    /// Address increment between two blocks
+    fn step(&self) -> u32 {
+        // standard capacity cards are byte addressed
+        if self.high_capacity {
+            1
+        } else {
+            BLOCK_LEN as u32
+        }
+    }
+}
+
+/// SD card over its SDMMC controller, see the [module](self) documentation
+pub struct Sdmmc<R> {
+    regs: R,
+    buffer: Shm<Mapped>,
+    rx: DmaStream,
+    tx: DmaStream,
+    card: Option<Card>,
+}
+
+impl<R: SdmmcRegisters> Sdmmc<R> {
+    /// Create the driver, `rx` and `tx` transferring a block between the
+    /// controller FIFO and `buffer`.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if `buffer` is smaller than a block
+    /// or not writable, or if the streams don't transfer a block from or to
+    /// its start, or propagates kernel errors if information retrieval fails.
+    pub fn new(
+        regs: R,
+        mut buffer: Shm<Mapped>,
+        rx: DmaStream,
+        tx: DmaStream,
+    ) -> Result<Self, Error> {
+        let invalid = Error::new(Subsystem::Storage, Status::Invalid);
+        let base = buffer.base_address()?;
+        if buffer.length()? < BLOCK_LEN || !buffer.is_writable() {
+            return Err(invalid);
+        }
+        let (rx_info, tx_info) = (rx.info()?, tx.info()?);
+        if rx_info.dest != base
+            || tx_info.source != base
+            || rx_info.transfer_len != BLOCK_LEN
+            || tx_info.transfer_len != BLOCK_LEN
+        {
+            return Err(invalid);
+        }
+        Ok(Self {
+            regs,
+            buffer,
+            rx,
+            tx,
+            card: None,
+        })
+    }
+
+    /// Release the controller registers, the buffer and the streams.
+    pub fn release(self) -> (R, Shm<Mapped>, DmaStream, DmaStream) {
+        (self.regs, self.buffer, self.rx, self.tx)
+    }
+
+    /// Identify and select the card, then switch it to 4-bit mode.
+    ///
+    /// # Errors
+    /// Returns a `Status::NoEntity` error if no card answers, a
+    /// `Status::Invalid` error if the card is not supported (e.g. MMC), or
+    /// the command failures.
+    pub fn init(&mut self) -> Result<(), Error> {
+        self.card = None;
+        self.regs.set_bus_width(BusWidth::One);
+        self.regs.set_clock(IDENTIFICATION_CLOCK.into());
+        self.command(GO_IDLE_STATE, 0, Response::None)?;
+
+        // version 1 cards don't know about SEND_IF_COND
+        let version2 = match self.regs.command(SEND_IF_COND, IF_COND, Response::Short) {
+            Ok(response) if response[0] & 0xfff == IF_COND => true,
+            Ok(_) => return Err(Error::new(Subsystem::Storage, Status::Invalid)),
+            Err(TransferError::Timeout) => false,
+            Err(err) => return Err(err.error()),
+        };
+        let op_cond = if version2 {
+            OP_COND
+        } else {
+            OP_COND & !OCR_HIGH_CAPACITY
+        };
+        let mut ocr = 0;
+        for _ in 0..POWER_UP_ATTEMPTS {
+            ocr = match self.app_command(0, SD_SEND_OP_COND, op_cond, Response::ShortNoCrc) {
+                Ok(response) => response,
+                // MMC cards don't know about application commands
+                Err(err) if err.status() == Status::Timeout => {
+                    return Err(Error::new(Subsystem::Storage, Status::NoEntity));
+                }
+                Err(err) => return Err(err),
+            };
+            if ocr & OCR_POWERED_UP != 0 {
+                break;
+            }
+            time::sleep_ms(1)?;
+        }
+        if ocr & OCR_POWERED_UP == 0 {
+            return Err(Error::new(Subsystem::Storage, Status::NoEntity));
+        }
+
+        self.command(ALL_SEND_CID, 0, Response::Long)?;
+        let rca = self.command(SEND_RELATIVE_ADDR, 0, Response::Short)?[0] & 0xffff_0000;
+        let blocks = csd_blocks(self.command(SEND_CSD, rca, Response::Long)?)
+            .ok_or(Error::new(Subsystem::Storage, Status::Invalid))?;
+        self.card_command(SELECT_CARD, rca, Response::ShortBusy)?;
+        // 4-bit mode is mandatory for SD memory cards
+        self.app_command(rca, SET_BUS_WIDTH, 2, Response::Short)?;
+        self.regs.set_bus_width(BusWidth::Four);
+        let high_capacity = ocr & OCR_HIGH_CAPACITY != 0;
+        if !high_capacity {
+            self.card_command(SET_BLOCKLEN, BLOCK_LEN as u32, Response::Short)?;
+        }
+        self.regs.set_clock(TRANSFER_CLOCK.into());
+
+        self.card = Some(Card {
+            rca,
+            high_capacity,
+            blocks,
+        });
+        Ok(())
+    }
+
+    fn command(&mut self, index: u8, arg: u32, response: Response) -> Result<[u32; 4], Error> {
+        self.regs
+            .command(index, arg, response)
+            .map_err(TransferError::error)
+    }
+
+    /// Issue a command whose response is the card status, checking it.
+    fn card_command(&mut self, index: u8, arg: u32, response: Response) -> Result<u32, Error> {
+        let status = self.command(index, arg, response)?[0];
+        if status & R1_ERRORS != 0 {
+            return Err(Error::new(Subsystem::Storage, Status::Critical));
+        }
+        Ok(status)
+    }
+
+    /// Issue the application specific command `index` to the card `rca`.
+    fn app_command(
+        &mut self,
+        rca: u32,
+        index: u8,
+        arg: u32,
+        response: Response,
+    ) -> Result<u32, Error> {
+        self.card_command(APP_CMD, rca, Response::Short)?;
+        Ok(self.command(index, arg, response)?[0])
+    }
+
+    fn card(&self) -> Result<Card, Error> {
+        self.card
+            .ok_or(Error::new(Subsystem::Storage, Status::NoEntity))
+    }
+
+    /// Return the address of `count` blocks starting at `start`.
+    fn address(&self, start: u32, count: usize) -> Result<(Card, u32), Error> {
+        let card = self.card()?;
+        let end = u32::try_from(count)
+            .ok()
+            .and_then(|count| start.checked_add(count));
+        if end.is_none_or(|end| end > card.blocks) {
+            return Err(Error::new(Subsystem::Storage, Status::Invalid));
+        }
+        Ok((card, start * card.step()))
+    }
+
+    /// Return the number of blocks of the card.
+    ///
+    /// # Errors
+    /// Returns a `Status::NoEntity` error if the card is not initialized.
+    pub fn num_blocks(&self) -> Result<u32, Error> {
+        self.card().map(|card| card.blocks)
+    }
+
+    /// Read `blocks.len()` blocks, starting at the block `start`.
+    ///
+    /// # Errors
+    /// Returns a `Status::NoEntity` error if the card is not initialized, a
+    /// `Status::Invalid` error if the blocks are out of the card, or the
+    /// command and transfer failures.
+    pub async fn read(&mut self, start: u32, blocks: &mut [Block]) -> Result<(), Error> {
+        let (card, mut address) = self.address(start, blocks.len())?;
+        for block in blocks {
+            self.start_read(address)?;
+            self.rx.transfer(()).await.map_err(|err| err.error)?;
+            self.finish_read(block)?;
+            address += card.step();
+        }
+        Ok(())
+    }
+
+    /// Write `blocks`, starting at the block `start`.
+    ///
+    /// # Errors
+    /// See [`Sdmmc::read`].
+    pub async fn write(&mut self, start: u32, blocks: &[Block]) -> Result<(), Error> {
+        let (card, mut address) = self.address(start, blocks.len())?;
+        for block in blocks {
+            self.start_write(address, block)?;
+            self.tx.transfer(()).await.map_err(|err| err.error)?;
+            self.finish_write(card)?;
+            address += card.step();
+        }
+        Ok(())
+    }
+
+    /// Read `blocks.len()` blocks, starting at the block `start`, blocking
+    /// on the DMA transfers, see [`DmaStream::transfer_blocking`].
+    ///
+    /// # Errors
+    /// See [`Sdmmc::read`].
+    pub fn read_blocking(&mut self, start: u32, blocks: &mut [Block]) -> Result<(), Error> {
+        let (card, mut address) = self.address(start, blocks.len())?;
+        for block in blocks {
+            self.start_read(address)?;
+            self.rx.transfer_blocking()?;
+            self.finish_read(block)?;
+            address += card.step();
+        }
+        Ok(())
+    }
+
+    /// Write `blocks`, starting at the block `start`, blocking on the DMA
+    /// transfers, see [`DmaStream::transfer_blocking`].
+    ///
+    /// # Errors
+    /// See [`Sdmmc::read`].
+    pub fn write_blocking(&mut self, start: u32, blocks: &[Block]) -> Result<(), Error> {
+        let (card, mut address) = self.address(start, blocks.len())?;
+        for block in blocks {
+            self.start_write(address, block)?;
+            self.tx.transfer_blocking()?;
+            self.finish_write(card)?;
+            address += card.step();
+        }
+        Ok(())
+    }
+
+    /// Arm the data path and request the block at `address`, before the
+    /// `rx` transfer.
+    fn start_read(&mut self, address: u32) -> Result<(), Error> {
+        self.regs.start_data(Direction::CardToHost, BLOCK_LEN);
+        self.card_command(READ_SINGLE_BLOCK, address, Response::Short)?;
+        Ok(())
+    }
+
+    /// Copy out the block read by the `rx` transfer.
+    fn finish_read(&mut self, block: &mut Block) -> Result<(), Error> {
+        self.regs.finish_data().map_err(TransferError::error)?;
+        self.buffer.copy_from_shm(0, block)?;
+        Ok(())
+    }
+
+    /// Copy in `block`, then send it to `address`, before the `tx`
+    /// transfer.
+    fn start_write(&mut self, address: u32, block: &Block) -> Result<(), Error> {
+        self.buffer.copy_into_shm(0, block)?;
+        self.card_command(WRITE_BLOCK, address, Response::Short)?;
+        self.regs.start_data(Direction::HostToCard, BLOCK_LEN);
+        Ok(())
+    }
+
+    /// Wait for the card to program the block sent by the `tx` transfer.
+    fn finish_write(&mut self, card: Card) -> Result<(), Error> {
+        self.regs.finish_data().map_err(TransferError::error)?;
+        self.wait_ready(card)
+    }
+
+    /// Wait until the card is ready for data again, after a write.
+    fn wait_ready(&mut self, card: Card) -> Result<(), Error> {
+        for _ in 0..POWER_UP_ATTEMPTS {
+            if self.card_command(SEND_STATUS, card.rca, Response::Short)? & R1_READY_FOR_DATA != 0 {
+                return Ok(());
+            }
+            time::sleep_ms(1)?;
+        }
+        Err(Error::new(Subsystem::Storage, Status::Timeout))
+    }
+}
+
+/// [`Sdmmc`] with blocking transfers, as an [`embedded_sdmmc::BlockDevice`]
+///
+/// The block device is shared by the volume manager and its open files, the
+/// driver is borrowed for each access.
+pub struct BlockingSdmmc<R>(RefCell<Sdmmc<R>>);
+
+impl<R: SdmmcRegisters> BlockingSdmmc<R> {
+    /// Wrap the driver, whose card is initialized.
+    pub const fn new(sdmmc: Sdmmc<R>) -> Self {
+        Self(RefCell::new(sdmmc))
+    }
+
+    /// Release the driver.
+    pub fn into_inner(self) -> Sdmmc<R> {
+        self.0.into_inner()
+    }
+}
+
+impl<R: SdmmcRegisters> embedded_sdmmc::BlockDevice for BlockingSdmmc<R> {
+    type Error = Error;
+
+    fn read(
+        &self,
+        blocks: &mut [embedded_sdmmc::Block],
+        start: BlockIdx,
+    ) -> Result<(), Self::Error> {
+        let mut sdmmc = self.0.borrow_mut();
+        sdmmc.address(start.0, blocks.len())?;
+        for (index, block) in (start.0..).zip(blocks) {
+            sdmmc.read_blocking(index, core::slice::from_mut(&mut block.contents))?;
+        }
+        Ok(())
+    }
+
+    fn write(&self, blocks: &[embedded_sdmmc::Block], start: BlockIdx) -> Result<(), Self::Error> {
+        let mut sdmmc = self.0.borrow_mut();
+        sdmmc.address(start.0, blocks.len())?;
+        for (index, block) in (start.0..).zip(blocks) {
+            sdmmc.write_blocking(index, core::slice::from_ref(&block.contents))?;
+        }
+        Ok(())
+    }
+
+    fn num_blocks(&self) -> Result<BlockCount, Self::Error> {
+        self.0.borrow().num_blocks().map(BlockCount)
+    }
+}
+
+/// Return the bits `high..=low` of a 128 bits register
+fn bits(register: u128, high: u32, low: u32) -> u32 {
+    ((register >> low) & ((1 << (high - low + 1)) - 1)) as u32
+}
+
+/// Return the card capacity, in blocks, from its CSD register.
+fn csd_blocks(csd: [u32; 4]) -> Option<u32> {
+    let csd = csd.iter().fold(0_u128, |register, &word| {
+        (register << 32) | u128::from(word)
+    });
+    match bits(csd, 127, 126) {
+        // SDSC: (C_SIZE + 1) * 2^(C_SIZE_MULT + 2) blocks of 2^READ_BL_LEN bytes
+        0 => {
+            let shift = bits(csd, 49, 47) + 2 + bits(csd, 83, 80);
+            let bytes = u64::from(bits(csd, 73, 62) + 1) << shift;
+            u32::try_from(bytes / BLOCK_LEN as u64).ok()
+        }
+        // SDHC and SDXC: (C_SIZE + 1) * 512 KiB
+        1 => (bits(csd, 69, 48) + 1).checked_mul(1024),
+        _ => None,
+    }
+}
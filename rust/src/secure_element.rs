@@ -0,0 +1,395 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Secure element access
+//!
+//! [`SecureElement`] drives the platform secure element through a
+//! [`Transport`], provided by the driver of the mapped device (an I2C or SPI
+//! bus driver) or by the task owning it. Keys never leave the secure element:
+//! they are designated by their [`KeySlot`], only public keys and signatures
+//! being returned.
+//!
+//! Commands and responses are framed as:
+//!
+//! - command: code (1 byte), slot (1 byte), payload length (2 bytes, LE),
+//!   payload,
+//! - response: status (1 byte, 0 for success), payload length (2 bytes,
+//!   LE), payload.
+//!
+//! With the `attest` feature, a [`SigningKey`] signs attestation reports, and
+//! with the `securestore` feature, the secure element seals the
+//! [`crate::securestore`] values with its AES-GCM keys.
+
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+
+/// Maximum command or response payload, in bytes
+pub const MAX_PAYLOAD: usize = 256;
+
+/// P-256 public key length (uncompressed point, without the format byte)
+pub const PUBLIC_KEY_LEN: usize = 64;
+
+/// P-256 raw signature length (`r || s`)
+pub const SIGNATURE_LEN: usize = 64;
+
+/// AES-GCM nonce length
+pub const NONCE_LEN: usize = 12;
+
+/// AES-GCM tag length
+pub const TAG_LEN: usize = 16;
+
+const FRAME_HEADER_LEN: usize = 4;
+const RESPONSE_HEADER_LEN: usize = 3;
+
+const CMD_GENERATE: u8 = 0x01;
+const CMD_PUBLIC_KEY: u8 = 0x02;
+const CMD_SIGN: u8 = 0x03;
+const CMD_VERIFY: u8 = 0x04;
+const CMD_WRITE_DATA: u8 = 0x05;
+const CMD_READ_DATA: u8 = 0x06;
+const CMD_ERASE: u8 = 0x07;
+const CMD_COUNTER_READ: u8 = 0x08;
+const CMD_COUNTER_INCREMENT: u8 = 0x09;
+const CMD_SEAL: u8 = 0x0a;
+const CMD_OPEN: u8 = 0x0b;
+
+/// Response status of a rejected signature or tag
+const VERIFY_FAILED: u8 = 0x10;
+
+/// Command exchange with the secure element
+pub trait Transport {
+    /// Send the `command` frame and receive the response frame into
+    /// `response`, returning its length.
+    ///
+    /// # Errors
+    /// Returns the bus errors.
+    fn exchange(&mut self, command: &[u8], response: &mut [u8]) -> Result<usize, Error>;
+}
+
+/// Key or data slot of the secure element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeySlot(pub u8);
+
+fn error(status: Status) -> Error {
+    Error::new(Subsystem::SecureElement, status)
+}
+
+/// Map a secure element response status to an error.
+fn response_error(status: u8) -> Error {
+    error(match status {
+        0x01 => Status::Invalid,
+        0x02 => Status::Denied,
+        0x03 => Status::NoEntity,
+        0x04 => Status::Busy,
+        _ => Status::Critical,
+    })
+}
+
+/// Secure element, see the [module](self) documentation
+pub struct SecureElement<T: Transport> {
+    transport: T,
+    frame: [u8; FRAME_HEADER_LEN + MAX_PAYLOAD],
+}
+
+impl<T: Transport> SecureElement<T> {
+    /// Drive the secure element over `transport`.
+    pub const fn new(transport: T) -> Self {
+        Self {
+            transport,
+            frame: [0; FRAME_HEADER_LEN + MAX_PAYLOAD],
+        }
+    }
+
+    /// Give the transport back.
+    pub fn release(self) -> T {
+        self.transport
+    }
+
+    /// Issue the `code` command on `slot`, returning the response status
+    /// and the payload length, copied to `out`.
+    fn command(
+        &mut self,
+        code: u8,
+        slot: KeySlot,
+        payload: &[&[u8]],
+        out: &mut [u8],
+    ) -> Result<(u8, usize), Error> {
+        let len: usize = payload.iter().map(|part| part.len()).sum();
+        if len > MAX_PAYLOAD {
+            return Err(error(Status::Invalid));
+        }
+        self.frame[0] = code;
+        self.frame[1] = slot.0;
+        self.frame[2..4].copy_from_slice(&(len as u16).to_le_bytes());
+        let mut offset = FRAME_HEADER_LEN;
+        for part in payload {
+            self.frame[offset..offset + part.len()].copy_from_slice(part);
+            offset += part.len();
+        }
+
+        let mut response = [0; RESPONSE_HEADER_LEN + MAX_PAYLOAD];
+        let received = self
+            .transport
+            .exchange(&self.frame[..offset], &mut response)?;
+        if received < RESPONSE_HEADER_LEN {
+            return Err(error(Status::Critical));
+        }
+        let payload_len = usize::from(u16::from_le_bytes([response[1], response[2]]));
+        if RESPONSE_HEADER_LEN + payload_len > received {
+            return Err(error(Status::Critical));
+        }
+        if payload_len > out.len() {
+            return Err(error(Status::Invalid));
+        }
+        out[..payload_len]
+            .copy_from_slice(&response[RESPONSE_HEADER_LEN..RESPONSE_HEADER_LEN + payload_len]);
+        Ok((response[0], payload_len))
+    }
+
+    /// Issue a command expected to succeed.
+    fn checked(
+        &mut self,
+        code: u8,
+        slot: KeySlot,
+        payload: &[&[u8]],
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        match self.command(code, slot, payload, out)? {
+            (0, len) => Ok(len),
+            (status, _) => Err(response_error(status)),
+        }
+    }
+
+    /// Issue a command expected to return exactly `out.len()` bytes.
+    fn fixed(
+        &mut self,
+        code: u8,
+        slot: KeySlot,
+        payload: &[&[u8]],
+        out: &mut [u8],
+    ) -> Result<(), Error> {
+        if self.checked(code, slot, payload, out)? != out.len() {
+            return Err(error(Status::Critical));
+        }
+        Ok(())
+    }
+
+    /// Generate a P-256 key pair in `slot`, returning its public key.
+    ///
+    /// # Errors
+    /// Returns the secure element errors, `Status::Denied` if the slot is
+    /// locked.
+    pub fn generate_key(&mut self, slot: KeySlot) -> Result<[u8; PUBLIC_KEY_LEN], Error> {
+        let mut key = [0; PUBLIC_KEY_LEN];
+        self.fixed(CMD_GENERATE, slot, &[], &mut key)?;
+        Ok(key)
+    }
+
+    /// Return the public key of the key pair in `slot`.
+    ///
+    /// # Errors
+    /// Returns the secure element errors, `Status::NoEntity` if the slot is
+    /// empty.
+    pub fn public_key(&mut self, slot: KeySlot) -> Result<[u8; PUBLIC_KEY_LEN], Error> {
+        let mut key = [0; PUBLIC_KEY_LEN];
+        self.fixed(CMD_PUBLIC_KEY, slot, &[], &mut key)?;
+        Ok(key)
+    }
+
+    /// Sign `digest` with the key in `slot`.
+    ///
+    /// # Errors
+    /// Returns the secure element errors.
+    pub fn sign(&mut self, slot: KeySlot, digest: &[u8; 32]) -> Result<[u8; SIGNATURE_LEN], Error> {
+        let mut signature = [0; SIGNATURE_LEN];
+        self.fixed(CMD_SIGN, slot, &[digest], &mut signature)?;
+        Ok(signature)
+    }
+
+    /// Check `signature` of `digest` with the key in `slot`.
+    ///
+    /// # Errors
+    /// Returns the secure element errors, a rejected signature not being
+    /// an error.
+    pub fn verify(
+        &mut self,
+        slot: KeySlot,
+        digest: &[u8; 32],
+        signature: &[u8; SIGNATURE_LEN],
+    ) -> Result<bool, Error> {
+        match self.command(CMD_VERIFY, slot, &[digest, signature], &mut [])? {
+            (0, _) => Ok(true),
+            (VERIFY_FAILED, _) => Ok(false),
+            (status, _) => Err(response_error(status)),
+        }
+    }
+
+    /// Store `data` in the data slot `slot`.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if `data` is longer than [`MAX_PAYLOAD`],
+    /// or the secure element errors.
+    pub fn write_data(&mut self, slot: KeySlot, data: &[u8]) -> Result<(), Error> {
+        self.checked(CMD_WRITE_DATA, slot, &[data], &mut [])
+            .map(|_| ())
+    }
+
+    /// Read the data slot `slot` into `buf`, returning the data length.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if `buf` is too small, or the secure
+    /// element errors.
+    pub fn read_data(&mut self, slot: KeySlot, buf: &mut [u8]) -> Result<usize, Error> {
+        self.checked(CMD_READ_DATA, slot, &[], buf)
+    }
+
+    /// Erase the key or data in `slot`.
+    ///
+    /// # Errors
+    /// Returns the secure element errors.
+    pub fn erase(&mut self, slot: KeySlot) -> Result<(), Error> {
+        self.checked(CMD_ERASE, slot, &[], &mut []).map(|_| ())
+    }
+
+    /// Return the value of the monotonic counter `slot`.
+    ///
+    /// # Errors
+    /// Returns the secure element errors.
+    pub fn read_counter(&mut self, slot: KeySlot) -> Result<u32, Error> {
+        let mut value = [0; 4];
+        self.fixed(CMD_COUNTER_READ, slot, &[], &mut value)?;
+        Ok(u32::from_le_bytes(value))
+    }
+
+    /// Increment the monotonic counter `slot`, returning its new value.
+    ///
+    /// # Errors
+    /// Returns the secure element errors, `Status::Denied` once the counter
+    /// reached its limit.
+    pub fn increment_counter(&mut self, slot: KeySlot) -> Result<u32, Error> {
+        let mut value = [0; 4];
+        self.fixed(CMD_COUNTER_INCREMENT, slot, &[], &mut value)?;
+        Ok(u32::from_le_bytes(value))
+    }
+
+    /// Encrypt `data` in place with the AES-GCM key in `slot`, `aad` being
+    /// authenticated along with it, returning the tag.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if `aad` is longer than 255 bytes or the
+    /// command longer than [`MAX_PAYLOAD`], or the secure element errors.
+    pub fn seal(
+        &mut self,
+        slot: KeySlot,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        data: &mut [u8],
+    ) -> Result<[u8; TAG_LEN], Error> {
+        let aad_len = u8::try_from(aad.len()).map_err(|_| error(Status::Invalid))?;
+        let mut out = [0; MAX_PAYLOAD];
+        let len = self.checked(CMD_SEAL, slot, &[nonce, &[aad_len], aad, data], &mut out)?;
+        if len != data.len() + TAG_LEN {
+            return Err(error(Status::Critical));
+        }
+        data.copy_from_slice(&out[..data.len()]);
+        let mut tag = [0; TAG_LEN];
+        tag.copy_from_slice(&out[data.len()..len]);
+        Ok(tag)
+    }
+
+    /// Decrypt `data` in place with the AES-GCM key in `slot`, checking
+    /// `tag` over `data` and `aad`.
+    ///
+    /// # Errors
+    /// Returns `Status::Denied` if the tag doesn't match, `data` being left
+    /// untouched, see [`SecureElement::seal`] otherwise.
+    pub fn open(
+        &mut self,
+        slot: KeySlot,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &[u8; TAG_LEN],
+    ) -> Result<(), Error> {
+        let aad_len = u8::try_from(aad.len()).map_err(|_| error(Status::Invalid))?;
+        let mut out = [0; MAX_PAYLOAD];
+        match self.command(
+            CMD_OPEN,
+            slot,
+            &[nonce, &[aad_len], aad, data, tag],
+            &mut out,
+        )? {
+            (0, len) if len == data.len() => {
+                data.copy_from_slice(&out[..len]);
+                Ok(())
+            }
+            (0, _) => Err(error(Status::Critical)),
+            (VERIFY_FAILED, _) => Err(error(Status::Denied)),
+            (status, _) => Err(response_error(status)),
+        }
+    }
+}
+
+/// Secure element key signing attestation reports
+///
+/// The secure element is borrowed through a `RefCell`, reports being signed
+/// from a shared reference.
+#[cfg(feature = "attest")]
+pub struct SigningKey<'a, T: Transport> {
+    element: &'a core::cell::RefCell<SecureElement<T>>,
+    slot: KeySlot,
+}
+
+#[cfg(feature = "attest")]
+impl<'a, T: Transport> SigningKey<'a, T> {
+    /// Sign with the key in `slot` of `element`.
+    pub const fn new(element: &'a core::cell::RefCell<SecureElement<T>>, slot: KeySlot) -> Self {
+        Self { element, slot }
+    }
+}
+
+#[cfg(feature = "attest")]
+impl<T: Transport> crate::attest::ReportSigner for SigningKey<'_, T> {
+    fn signature_len(&self) -> usize {
+        SIGNATURE_LEN
+    }
+
+    fn sign(&self, digest: &[u8; 32], signature: &mut [u8]) -> Result<(), Error> {
+        let mut element = self
+            .element
+            .try_borrow_mut()
+            .map_err(|_| error(Status::Busy))?;
+        signature.copy_from_slice(&element.sign(self.slot, digest)?);
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "securestore", not(feature = "host-std")))]
+impl<T: Transport> crate::securestore::Sealer for SecureElement<T> {
+    fn seal(
+        &mut self,
+        key: u8,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        data: &mut [u8],
+    ) -> Result<[u8; TAG_LEN], Error> {
+        SecureElement::seal(self, KeySlot(key), nonce, aad, data)
+    }
+
+    fn open(
+        &mut self,
+        key: u8,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &[u8; TAG_LEN],
+    ) -> Result<(), Error> {
+        SecureElement::open(self, KeySlot(key), nonce, aad, data, tag)
+    }
+
+    fn erase(&mut self, key: u8) -> Result<(), Error> {
+        SecureElement::erase(self, KeySlot(key))
+    }
+}
@@ -0,0 +1,274 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Sealed key-value store
+//!
+//! [`SecureStore`] keeps credentials and tokens in a [`KvStore`], each value
+//! being sealed with an authenticated encryption (AES-GCM) by a [`Sealer`]
+//! holding the device-unique keys, the secure element with the
+//! `secure-element` feature. The keys never leave the sealer: they are
+//! designated by their slot, and the plaintext only lives in the task
+//! buffers, the intermediate copies being wiped.
+//!
+//! Each value is stored as:
+//!
+//! | bytes | content |
+//! |-------|---------|
+//! | 1 | slot of the sealing key |
+//! | [`NONCE_LEN`] | random nonce, drawn from the kernel TRNG |
+//! | n | ciphertext |
+//! | [`TAG_LEN`] | tag, over the ciphertext and the value key |
+//!
+//! The value key being authenticated, a sealed value moved to another key
+//! fails to open. New values are sealed with the current key, while each
+//! value is opened with the key it names, so that [`SecureStore::rotate`]
+//! reseals the values one at a time: a rotation interrupted by a reset
+//! leaves a readable store, resumed by rotating again, the previous key
+//! being erased from the sealer ([`Sealer::erase`]) once the rotation
+//! completes.
+//!
+//! ```ignore
+//! let mut store = SecureStore::new(KvStore::open(flash)?, element, DEVICE_KEY);
+//! store.set(b"wifi-psk", psk)?;
+//! let mut token = [0; 64];
+//! let len = store.get(b"token", &mut token)?.ok_or(NotProvisioned)?;
+//! store.rotate(NEXT_DEVICE_KEY)?;
+//! ```
+
+use core::sync::atomic::{Ordering, compiler_fence};
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+use crate::kvstore::{Backing, Entry, KvStore};
+
+/// Nonce length, in bytes
+pub const NONCE_LEN: usize = 12;
+
+/// Tag length, in bytes
+pub const TAG_LEN: usize = 16;
+
+/// Maximum value length, in bytes
+pub const MAX_SECRET_LEN: usize = 128;
+
+const HEADER_LEN: usize = 1 + NONCE_LEN;
+const MAX_SEALED_LEN: usize = HEADER_LEN + MAX_SECRET_LEN + TAG_LEN;
+
+fn error(status: Status) -> Error {
+    Error::new(Subsystem::SecureStore, status)
+}
+
+/// Zero `buf`, the writes not being elided.
+fn wipe(buf: &mut [u8]) {
+    for byte in buf {
+        // SAFETY: valid, exclusively borrowed byte
+        unsafe { core::ptr::write_volatile(byte, 0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Authenticated encryption with device keys
+pub trait Sealer {
+    /// Encrypt `data` in place with the key in `slot`, `aad` being
+    /// authenticated along with it, returning the tag.
+    ///
+    /// # Errors
+    /// Returns the sealer errors.
+    fn seal(
+        &mut self,
+        slot: u8,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        data: &mut [u8],
+    ) -> Result<[u8; TAG_LEN], Error>;
+
+    /// Decrypt `data` in place with the key in `slot`, checking `tag` over
+    /// `data` and `aad`.
+    ///
+    /// # Errors
+    /// Returns `Status::Denied` if the tag doesn't match, or the sealer
+    /// errors.
+    fn open(
+        &mut self,
+        slot: u8,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &[u8; TAG_LEN],
+    ) -> Result<(), Error>;
+
+    /// Erase the key in `slot`.
+    ///
+    /// # Errors
+    /// Returns the sealer errors.
+    fn erase(&mut self, slot: u8) -> Result<(), Error>;
+}
+
+/// Key-value store of sealed values, see the [module](self) documentation
+pub struct SecureStore<B: Backing, S: Sealer> {
+    store: KvStore<B>,
+    sealer: S,
+    slot: u8,
+}
+
+impl<B: Backing, S: Sealer> SecureStore<B, S> {
+    /// Seal the values of `store` with the key in `slot` of `sealer`.
+    pub const fn new(store: KvStore<B>, sealer: S, slot: u8) -> Self {
+        Self {
+            store,
+            sealer,
+            slot,
+        }
+    }
+
+    /// Give the store and the sealer back.
+    pub fn release(self) -> (KvStore<B>, S) {
+        (self.store, self.sealer)
+    }
+
+    /// Return the slot of the key sealing the new values.
+    pub fn slot(&self) -> u8 {
+        self.slot
+    }
+
+    /// Open the value of `key` into `buf`, returning its length, or `None`
+    /// if the key is not set.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if `buf` can't hold the value,
+    /// `Status::Critical` if the stored value is not a sealed one, or
+    /// propagates the sealer errors, `Status::Denied` if the value was
+    /// tampered with, and the store errors.
+    pub fn get(&mut self, key: &[u8], buf: &mut [u8]) -> Result<Option<usize>, Error> {
+        let mut sealed = [0; MAX_SEALED_LEN];
+        let Some(len) = self.store.get(key, &mut sealed)? else {
+            return Ok(None);
+        };
+        let opened = self.open(key, &mut sealed[..len]).and_then(|value| {
+            let out = buf.get_mut(..value.len()).ok_or(error(Status::Invalid))?;
+            out.copy_from_slice(value);
+            Ok(value.len())
+        });
+        wipe(&mut sealed);
+        opened.map(Some)
+    }
+
+    /// Seal `value` with the current key, and set it as the value of `key`.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if `value` is longer than
+    /// [`MAX_SECRET_LEN`], or propagates the TRNG, sealer and store errors.
+    pub fn set(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        if value.len() > MAX_SECRET_LEN {
+            return Err(error(Status::Invalid));
+        }
+        let mut sealed = [0; MAX_SEALED_LEN];
+        let len = HEADER_LEN + value.len() + TAG_LEN;
+        sealed[HEADER_LEN..HEADER_LEN + value.len()].copy_from_slice(value);
+        let stored = self
+            .seal(self.slot, key, &mut sealed[..len])
+            .and_then(|()| self.store.set(key, &sealed[..len]));
+        wipe(&mut sealed);
+        stored
+    }
+
+    /// Delete `key`, returning whether it was set.
+    ///
+    /// # Errors
+    /// Propagates the store errors.
+    pub fn delete(&mut self, key: &[u8]) -> Result<bool, Error> {
+        self.store.delete(key)
+    }
+
+    /// Reseal all the values with the key in `slot`, which seals the new
+    /// values from then on, returning the number of values resealed. The
+    /// previous key is then erased from the sealer.
+    ///
+    /// The values already sealed with `slot` are skipped, so that an
+    /// interrupted rotation is resumed by rotating again.
+    ///
+    /// # Errors
+    /// See [`SecureStore::get`] and [`SecureStore::set`], the current key
+    /// being kept on failure, or propagates the sealer errors if the
+    /// previous key can't be erased, the rotation being complete then.
+    pub fn rotate(&mut self, slot: u8) -> Result<usize, Error> {
+        let mut resealed = 0;
+        let mut sealed = [0; MAX_SEALED_LEN];
+        let rotated = loop {
+            match self.reseal_next(slot, &mut sealed) {
+                Ok(true) => resealed += 1,
+                Ok(false) => break Ok(resealed),
+                Err(err) => break Err(err),
+            }
+        };
+        wipe(&mut sealed);
+        let resealed = rotated?;
+        let previous = core::mem::replace(&mut self.slot, slot);
+        if previous != slot {
+            self.sealer.erase(previous)?;
+        }
+        Ok(resealed)
+    }
+
+    /// Reseal with the key in `slot` the first value sealed with another
+    /// key, returning whether there was one.
+    fn reseal_next(&mut self, slot: u8, sealed: &mut [u8; MAX_SEALED_LEN]) -> Result<bool, Error> {
+        let Some((entry, len)) = self.next_stale(slot, sealed)? else {
+            return Ok(false);
+        };
+        let value_len = self.open(entry.key(), &mut sealed[..len])?.len();
+        // opened in place, past the header
+        self.seal(
+            slot,
+            entry.key(),
+            &mut sealed[..HEADER_LEN + value_len + TAG_LEN],
+        )?;
+        self.store.set(entry.key(), &sealed[..len])?;
+        Ok(true)
+    }
+
+    /// Read into `sealed` the first value sealed with another key than the
+    /// one in `slot`, returning its entry and length.
+    fn next_stale(
+        &self,
+        slot: u8,
+        sealed: &mut [u8; MAX_SEALED_LEN],
+    ) -> Result<Option<(Entry, usize)>, Error> {
+        for entry in self.store.iter() {
+            let entry = entry?;
+            let len = self.store.read_value(&entry, sealed)?;
+            if len > 0 && sealed[0] != slot {
+                return Ok(Some((entry, len)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Seal the value in `sealed`, past the header, in place with the key in
+    /// `slot`, filling the header and the tag.
+    fn seal(&mut self, slot: u8, key: &[u8], sealed: &mut [u8]) -> Result<(), Error> {
+        let (header, rest) = sealed.split_at_mut(HEADER_LEN);
+        let (data, tag) = rest.split_at_mut(rest.len() - TAG_LEN);
+        header[0] = slot;
+        let mut nonce = [0; NONCE_LEN];
+        crate::random::fill(&mut nonce)?;
+        header[1..].copy_from_slice(&nonce);
+        tag.copy_from_slice(&self.sealer.seal(slot, &nonce, key, data)?);
+        Ok(())
+    }
+
+    /// Open the sealed value in `sealed` in place, returning the plaintext.
+    fn open<'s>(&mut self, key: &[u8], sealed: &'s mut [u8]) -> Result<&'s [u8], Error> {
+        if sealed.len() < HEADER_LEN + TAG_LEN {
+            return Err(error(Status::Critical));
+        }
+        let (header, rest) = sealed.split_at_mut(HEADER_LEN);
+        let (data, tag) = rest.split_at_mut(rest.len() - TAG_LEN);
+        let mut nonce = [0; NONCE_LEN];
+        nonce.copy_from_slice(&header[1..]);
+        let mut raw_tag = [0; TAG_LEN];
+        raw_tag.copy_from_slice(tag);
+        self.sealer.open(header[0], &nonce, key, data, &raw_tag)?;
+        Ok(data)
+    }
+}
@@ -0,0 +1,281 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Power-on self-tests
+//!
+//! Drivers [`register`] their power-on checks, typically from their
+//! initialization, then the task calls [`run`] once before starting its main
+//! logic. The checks run in registration order, each outcome being kept for
+//! [`result`] and, with the `print` feature, logged along with the overall
+//! [`Summary`]. The task then exports the summary as metrics with
+//! `Summary::export`, and reports it to its supervisor with
+//! `supervisor::notify_selftest`, with the `metrics` and `supervisor`
+//! features.
+//!
+//! ```ignore
+//! fn sram2() -> Result<(), Error> {
+//!     selftest::ram_pattern(&mut Shm::new(SRAM2_SHM)?.map(0)?)
+//! }
+//!
+//! selftest::register("sram2", sram2)?;
+//! selftest::register("clock", || selftest::check_clock(10.ms()))?;
+//! selftest::register("imu", imu_present)?;
+//! let summary = selftest::run();
+//! supervisor::notify_selftest(SUPERVISOR, summary)?;
+//! if !summary.is_pass() {
+//!     supervisor::notify_exit(SUPERVISOR, -1)?;
+//!     process::exit(Status::Critical);
+//! }
+//! ```
+//!
+//! A few generic checks are provided: [`ram_pattern`] tests a shared memory,
+//! [`check_clock`] the kernel clock against a sleep, and [`expect_id`] the
+//! identification register of a peripheral.
+
+use core::cell::UnsafeCell;
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+use crate::units::Milliseconds;
+
+/// Maximum number of registered checks
+pub const MAX_CHECKS: usize = 16;
+
+/// Power-on check, failing with an error describing the fault
+pub type Check = fn() -> Result<(), Error>;
+
+#[derive(Clone, Copy)]
+struct Entry {
+    name: &'static str,
+    check: Check,
+    /// Outcome of the last run, if any
+    result: Option<Result<(), Error>>,
+}
+
+struct Checks {
+    checks: [Option<Entry>; MAX_CHECKS],
+    summary: Option<Summary>,
+}
+
+struct ChecksCell(UnsafeCell<Checks>);
+
+// SAFETY: a Sentry task is single-threaded, and the table is never borrowed
+// across calls of `with_checks`
+unsafe impl Sync for ChecksCell {}
+
+static CHECKS: ChecksCell = ChecksCell(UnsafeCell::new(Checks {
+    checks: [None; MAX_CHECKS],
+    summary: None,
+}));
+
+/// Execute `f` with an exclusive access to the checks table
+fn with_checks<R>(f: impl FnOnce(&mut Checks) -> R) -> R {
+    // SAFETY: see ChecksCell, `f` never reaches `with_checks`
+    f(unsafe { &mut *CHECKS.0.get() })
+}
+
+fn error(status: Status) -> Error {
+    Error::new(Subsystem::Selftest, status)
+}
+
+/// Outcome of a self-test run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// Number of checks passed
+    pub passed: u8,
+    /// Number of checks failed
+    pub failed: u8,
+}
+
+impl Summary {
+    /// Return whether all the checks passed.
+    pub const fn is_pass(&self) -> bool {
+        self.failed == 0
+    }
+
+    /// Export the summary as the `selftest_passed` and `selftest_failed`
+    /// gauges of `registry`.
+    ///
+    /// # Errors
+    /// See [`Registry::gauge`](crate::metrics::Registry::gauge).
+    #[cfg(feature = "metrics")]
+    pub fn export(&self, registry: &crate::metrics::Registry) -> Result<(), Error> {
+        registry
+            .gauge("selftest_passed")?
+            .set(i32::from(self.passed));
+        registry
+            .gauge("selftest_failed")?
+            .set(i32::from(self.failed));
+        Ok(())
+    }
+}
+
+/// Register the check `name`.
+///
+/// # Errors
+/// Returns a `Status::Invalid` error if `name` is already registered, or a
+/// `Status::Busy` error if [`MAX_CHECKS`] checks are registered already.
+pub fn register(name: &'static str, check: Check) -> Result<(), Error> {
+    with_checks(|table| {
+        if table
+            .checks
+            .iter()
+            .flatten()
+            .any(|entry| entry.name == name)
+        {
+            return Err(error(Status::Invalid));
+        }
+        let slot = table
+            .checks
+            .iter_mut()
+            .find(|slot| slot.is_none())
+            .ok_or(error(Status::Busy))?;
+        *slot = Some(Entry {
+            name,
+            check,
+            result: None,
+        });
+        Ok(())
+    })
+}
+
+/// Run the registered checks, in registration order, and return their
+/// summary.
+///
+/// With the `print` feature, the failed checks are logged, followed by the
+/// summary.
+pub fn run() -> Summary {
+    let mut summary = Summary::default();
+    for index in 0..MAX_CHECKS {
+        // the check runs out of the table, which it may reach
+        let Some(entry) = with_checks(|table| table.checks[index]) else {
+            continue;
+        };
+        let result = (entry.check)();
+        match result {
+            Ok(()) => summary.passed = summary.passed.saturating_add(1),
+            Err(_err) => {
+                summary.failed = summary.failed.saturating_add(1);
+                #[cfg(feature = "print")]
+                crate::println!("selftest: {} failed: {_err}", entry.name);
+            }
+        }
+        with_checks(|table| {
+            if let Some(entry) = &mut table.checks[index] {
+                entry.result = Some(result);
+            }
+        });
+    }
+    #[cfg(feature = "print")]
+    crate::println!(
+        "selftest: {} passed, {} failed",
+        summary.passed,
+        summary.failed
+    );
+    with_checks(|table| table.summary = Some(summary));
+    summary
+}
+
+/// Return the summary of the last [`run`], if any.
+pub fn summary() -> Option<Summary> {
+    with_checks(|table| table.summary)
+}
+
+/// Return the outcome of the check `name` in the last [`run`], if it was
+/// registered by then.
+pub fn result(name: &str) -> Option<Result<(), Error>> {
+    with_checks(|table| {
+        table
+            .checks
+            .iter()
+            .flatten()
+            .find(|entry| entry.name == name)
+            .and_then(|entry| entry.result)
+    })
+}
+
+/// Test the words of `shm` with walking patterns, then clear them.
+///
+/// Each word is written and read back with alternating bit patterns, then
+/// with its own offset, to find stuck bits and address decoding faults. The
+/// shared memory content is lost.
+///
+/// # Errors
+/// Returns a `Status::Denied` error if the shared memory is not writable, a
+/// `Status::Critical` error along with the offset of the first faulty word,
+/// or propagates kernel errors.
+#[cfg(feature = "shm")]
+pub fn ram_pattern(shm: &mut crate::shm::Shm<crate::shm::Mapped>) -> Result<(), Error> {
+    if !shm.is_writable() {
+        return Err(error(Status::Denied));
+    }
+    let base = shm.base_address()?;
+    let words = shm.length()? / 4;
+    let word = |index: usize| core::ptr::with_exposed_provenance_mut::<u32>(base + index * 4);
+    let patterns: [fn(usize) -> u32; 3] = [
+        |_| 0x5555_5555,
+        |_| 0xaaaa_aaaa,
+        // distinct for each word of a shared memory below 4 GiB
+        |index| (index * 4) as u32,
+    ];
+    for pattern in patterns {
+        for index in 0..words {
+            // SAFETY: the shared memory is mapped and writable, the words
+            // being within its length
+            unsafe { word(index).write_volatile(pattern(index)) };
+        }
+        for index in 0..words {
+            // SAFETY: see above
+            if unsafe { word(index).read_volatile() } != pattern(index) {
+                return Err(error(Status::Critical).with_handle((index * 4) as u32));
+            }
+        }
+    }
+    for index in 0..words {
+        // SAFETY: see above
+        unsafe { word(index).write_volatile(0) };
+    }
+    Ok(())
+}
+
+/// Check that the kernel clock measures a sleep of `duration` as lasting
+/// between `duration` and twice it, the task being possibly scheduled late.
+///
+/// # Errors
+/// Returns a `Status::Invalid` error along with the measured duration, in
+/// milliseconds, or propagates the sleep and clock errors, such as a
+/// `Status::Intr` one if the sleep is interrupted by an event.
+pub fn check_clock(duration: impl Into<Milliseconds>) -> Result<(), Error> {
+    let duration = duration.into().get();
+    let before = crate::time::uptime_us()?;
+    crate::time::sleep_ms(duration)?;
+    let elapsed_us = crate::time::uptime_us()?.saturating_sub(before);
+    let expected_us = u64::from(duration) * 1_000;
+    if elapsed_us < expected_us || elapsed_us > 2 * expected_us {
+        let elapsed_ms = u32::try_from(elapsed_us / 1_000).unwrap_or(u32::MAX);
+        return Err(error(Status::Invalid).with_handle(elapsed_ms));
+    }
+    Ok(())
+}
+
+/// Check the identification register value `id` of a peripheral, read by
+/// the driver, against the `expected` one.
+///
+/// # Errors
+/// Returns a `Status::NoEntity` error along with `id` if they differ.
+pub fn expect_id(id: u32, expected: u32) -> Result<(), Error> {
+    match id == expected {
+        true => Ok(()),
+        false => Err(error(Status::NoEntity).with_handle(id)),
+    }
+}
+
+/// Drop all the registered checks, for a fresh fake kernel session.
+#[cfg(feature = "mock")]
+pub(crate) fn clear() {
+    with_checks(|table| {
+        table.checks = [None; MAX_CHECKS];
+        table.summary = None;
+    });
+}
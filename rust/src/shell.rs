@@ -0,0 +1,259 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Debug shell
+//!
+//! [`Shell`] runs a command line over any [`embedded_io_async`] stream, the
+//! [`crate::uart::Uart`] driver or a USB-CDC class. The application registers
+//! its [`Command`]s, each handler receiving the application context, the
+//! command arguments, and an [`Output`] buffer flushed to the stream once the
+//! handler returns. The `help` command lists the registered commands.
+//!
+//! The line editor echoes the input, and handles backspace, `Ctrl-U` (line
+//! erase) and `Ctrl-C` (line discard). Escape sequences (arrow keys) are
+//! ignored. Arguments are separated by spaces, double quotes grouping an
+//! argument holding spaces.
+//!
+//! ```ignore
+//! const COMMANDS: &[Command<Board>] = &[Command {
+//!     name: "led",
+//!     help: "led <on|off>: drive the status LED",
+//!     handler: |board, args, _| board.set_led(args.first() == Some(&"on")),
+//! }];
+//! Shell::new(COMMANDS, "> ").run(&mut uart, &mut board).await?;
+//! ```
+
+use core::fmt;
+use embedded_io_async::{Read, Write};
+
+use crate::error::Error;
+
+/// Maximum command line length, in bytes
+pub const MAX_LINE: usize = 128;
+
+/// Maximum number of arguments, the command name excluded
+pub const MAX_ARGS: usize = 8;
+
+/// [`Output`] capacity, in bytes
+pub const MAX_OUTPUT: usize = 256;
+
+const BACKSPACE: u8 = 0x08;
+const DELETE: u8 = 0x7f;
+const CTRL_C: u8 = 0x03;
+const CTRL_U: u8 = 0x15;
+const ESCAPE: u8 = 0x1b;
+
+/// Command handler, called with the application context, the arguments and
+/// the output buffer
+pub type Handler<C> = fn(&mut C, &[&str], &mut Output) -> Result<(), Error>;
+
+/// Shell command
+pub struct Command<C> {
+    /// Command name
+    pub name: &'static str,
+    /// One-line help, listed by `help`
+    pub help: &'static str,
+    /// Command handler, whose errors are printed
+    pub handler: Handler<C>,
+}
+
+/// Command output buffer, truncated past [`MAX_OUTPUT`] bytes
+pub struct Output {
+    buf: [u8; MAX_OUTPUT],
+    len: usize,
+    truncated: bool,
+}
+
+impl Output {
+    const fn new() -> Self {
+        Self {
+            buf: [0; MAX_OUTPUT],
+            len: 0,
+            truncated: false,
+        }
+    }
+
+    /// Append `bytes`, truncating them if the buffer is full.
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        let len = bytes.len().min(MAX_OUTPUT - self.len);
+        self.buf[self.len..self.len + len].copy_from_slice(&bytes[..len]);
+        self.len += len;
+        self.truncated |= len < bytes.len();
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+impl fmt::Write for Output {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_bytes(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Line editor state
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Input {
+    Normal,
+    /// After `ESC`
+    Escape,
+    /// In a control sequence, after `ESC [`
+    Sequence,
+}
+
+/// Command line shell, see the [module](self) documentation
+pub struct Shell<'a, C> {
+    commands: &'a [Command<C>],
+    prompt: &'static str,
+    line: [u8; MAX_LINE],
+    len: usize,
+    input: Input,
+}
+
+impl<'a, C> Shell<'a, C> {
+    /// Create the shell of `commands`, printing `prompt` before each line.
+    pub const fn new(commands: &'a [Command<C>], prompt: &'static str) -> Self {
+        Self {
+            commands,
+            prompt,
+            line: [0; MAX_LINE],
+            len: 0,
+            input: Input::Normal,
+        }
+    }
+
+    /// Run the shell over `io`, until the stream ends.
+    ///
+    /// # Errors
+    /// Propagates the stream errors.
+    pub async fn run<S: Read + Write>(
+        &mut self,
+        io: &mut S,
+        context: &mut C,
+    ) -> Result<(), S::Error> {
+        io.write_all(self.prompt.as_bytes()).await?;
+        io.flush().await?;
+        let mut chunk = [0; 16];
+        loop {
+            let received = io.read(&mut chunk).await?;
+            if received == 0 {
+                return Ok(());
+            }
+            for &byte in &chunk[..received] {
+                self.feed(io, context, byte).await?;
+            }
+            io.flush().await?;
+        }
+    }
+
+    /// Handle the input `byte`.
+    async fn feed<S: Write>(
+        &mut self,
+        io: &mut S,
+        context: &mut C,
+        byte: u8,
+    ) -> Result<(), S::Error> {
+        match (self.input, byte) {
+            (Input::Normal, ESCAPE) => self.input = Input::Escape,
+            (Input::Escape, b'[') => self.input = Input::Sequence,
+            (Input::Escape, _) => self.input = Input::Normal,
+            // the final byte of a control sequence is in @..~
+            (Input::Sequence, 0x40..=0x7e) => self.input = Input::Normal,
+            (Input::Sequence, _) => {}
+            (Input::Normal, b'\r' | b'\n') => {
+                io.write_all(b"\r\n").await?;
+                if self.len > 0 {
+                    let mut output = Output::new();
+                    self.execute(context, &mut output);
+                    io.write_all(output.as_bytes()).await?;
+                    if output.truncated {
+                        io.write_all(b"...\r\n").await?;
+                    }
+                    self.len = 0;
+                }
+                io.write_all(self.prompt.as_bytes()).await?;
+            }
+            (Input::Normal, BACKSPACE | DELETE) => {
+                if self.len > 0 {
+                    self.len -= 1;
+                    io.write_all(b"\x08 \x08").await?;
+                }
+            }
+            (Input::Normal, CTRL_U) => {
+                for _ in 0..self.len {
+                    io.write_all(b"\x08 \x08").await?;
+                }
+                self.len = 0;
+            }
+            (Input::Normal, CTRL_C) => {
+                self.len = 0;
+                io.write_all(b"^C\r\n").await?;
+                io.write_all(self.prompt.as_bytes()).await?;
+            }
+            (Input::Normal, b' '..=b'~') => {
+                if self.len < MAX_LINE {
+                    self.line[self.len] = byte;
+                    self.len += 1;
+                    io.write_all(&[byte]).await?;
+                }
+            }
+            (Input::Normal, _) => {}
+        }
+        Ok(())
+    }
+
+    /// Parse and dispatch the current line.
+    fn execute(&self, context: &mut C, output: &mut Output) {
+        use fmt::Write as _;
+
+        // the line only holds printable ASCII
+        let Ok(line) = core::str::from_utf8(&self.line[..self.len]) else {
+            return;
+        };
+        let mut words = [""; MAX_ARGS + 1];
+        let Some(count) = split(line, &mut words) else {
+            let _ = write!(output, "too many arguments\r\n");
+            return;
+        };
+        let Some((&name, args)) = words[..count].split_first() else {
+            return;
+        };
+
+        if name == "help" {
+            for command in self.commands {
+                let _ = write!(output, "{}\r\n", command.help);
+            }
+            return;
+        }
+        match self.commands.iter().find(|command| command.name == name) {
+            Some(command) => {
+                if let Err(err) = (command.handler)(context, args, output) {
+                    let _ = write!(output, "error: {err}\r\n");
+                }
+            }
+            None => {
+                let _ = write!(output, "{name}: unknown command, try help\r\n");
+            }
+        }
+    }
+}
+
+/// Split `line` into `words`, returning the word count, or `None` if the
+/// line holds too many words.
+fn split<'l>(line: &'l str, words: &mut [&'l str]) -> Option<usize> {
+    let mut count = 0;
+    let mut rest = line.trim_start();
+    while !rest.is_empty() {
+        let (word, next) = match rest.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => rest.split_once(' ').unwrap_or((rest, "")),
+        };
+        *words.get_mut(count)? = word;
+        count += 1;
+        rest = next.trim_start();
+    }
+    Some(count)
+}
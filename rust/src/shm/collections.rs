@@ -0,0 +1,392 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Collections laid out in a mapped shared memory
+//!
+//! [`ShmVec`] and [`ShmRing`] follow the `heapless::Vec` and
+//! `heapless::spsc::Queue` interfaces, their storage being a mapped shared
+//! memory instead of an array: the capacity is derived from the shared memory
+//! length, and the elements are written in place, so that a peer task mapping
+//! the same shared memory reads them without a copy into its own RAM.
+//!
+//! The shared memory starts with a header of `u32` words, the length of a
+//! vector, or the read and write counts of a ring, the elements following at
+//! the first offset aligned for them. Elements are `Copy`, and should not
+//! hold any pointer, as the shared memory is not mapped at the same address
+//! in each task.
+//!
+//! A vector is modified by a single task at a time, its owner handing it over
+//! to the peer, see [`Producer`](super::Producer). A ring has one producer
+//! task, enqueueing, and one consumer task, dequeueing, at a time.
+//!
+//! ```ignore
+//! let mut samples = ShmVec::<u16>::new(Shm::new(SAMPLES_SHM)?.map(0)?)?;
+//! while samples.push(adc.read()?).is_ok() {}
+//! ```
+
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{Ordering, fence};
+use uapi::systypes::Status;
+
+use super::{Mapped, Shm};
+use crate::error::Error;
+
+/// Header word of a vector
+const VEC_LEN: usize = 0;
+
+/// Header words of a ring
+const RING_READ: usize = 0;
+const RING_WRITE: usize = 1;
+
+/// Storage of a collection in a shared memory
+struct Storage<T> {
+    shm: Shm<Mapped>,
+    header: NonNull<u32>,
+    data: NonNull<T>,
+    capacity: u32,
+}
+
+impl<T: Copy> Storage<T> {
+    /// Locate a header of `words` words and the elements in `shm`.
+    fn new(mut shm: Shm<Mapped>, words: usize) -> Result<Self, Error> {
+        let invalid = shm.error(Status::Invalid);
+        let base = shm.base_address()?;
+        let offset = (words * size_of::<u32>()).next_multiple_of(align_of::<T>());
+        let capacity = shm
+            .length()?
+            .saturating_sub(offset)
+            .checked_div(size_of::<T>())
+            .unwrap_or_default();
+        if capacity == 0 || base % align_of::<u32>().max(align_of::<T>()) != 0 || !shm.is_writable()
+        {
+            return Err(invalid);
+        }
+        let header = NonNull::new(ptr::with_exposed_provenance_mut(base)).ok_or(invalid)?;
+        let data = NonNull::new(ptr::with_exposed_provenance_mut(base + offset)).ok_or(invalid)?;
+        Ok(Self {
+            shm,
+            header,
+            data,
+            // ring counts running up to twice the capacity
+            capacity: u32::try_from(capacity)
+                .unwrap_or(u32::MAX)
+                .min(u32::MAX / 2),
+        })
+    }
+
+    fn word(&self, index: usize) -> u32 {
+        // SAFETY: the header is at the base of the shared memory
+        unsafe { self.header.add(index).read_volatile() }
+    }
+
+    fn set_word(&self, index: usize, value: u32) {
+        // SAFETY: see `word`
+        unsafe { self.header.add(index).write_volatile(value) }
+    }
+
+    /// Return a pointer to the element `index`, below the capacity.
+    fn slot(&self, index: u32) -> NonNull<T> {
+        debug_assert!(index < self.capacity);
+        // SAFETY: the index is below the capacity, in the shared memory
+        unsafe { self.data.add(index as usize) }
+    }
+}
+
+/// Vector in a shared memory, see the [module](self) documentation
+pub struct ShmVec<T> {
+    storage: Storage<T>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Copy> ShmVec<T> {
+    /// Lay out an empty vector in `shm`.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the shared memory is too small
+    /// for an element, misaligned or not writable, or propagates kernel
+    /// errors if information retrieval fails.
+    pub fn new(shm: Shm<Mapped>) -> Result<Self, Error> {
+        let storage = Storage::new(shm, 1)?;
+        storage.set_word(VEC_LEN, 0);
+        Ok(Self {
+            storage,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Return the vector laid out in `shm` by a peer with [`ShmVec::new`].
+    ///
+    /// # Safety
+    /// The elements written by the peer must be valid `T` values.
+    ///
+    /// # Errors
+    /// See [`ShmVec::new`].
+    pub unsafe fn open(shm: Shm<Mapped>) -> Result<Self, Error> {
+        Ok(Self {
+            storage: Storage::new(shm, 1)?,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Release the shared memory, the elements staying in it.
+    #[must_use]
+    pub fn release(self) -> Shm<Mapped> {
+        self.storage.shm
+    }
+
+    /// Return the maximum number of elements.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.storage.capacity as usize
+    }
+
+    /// Return the number of elements, bounded by the capacity should the
+    /// peer have corrupted it.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.storage.word(VEC_LEN).min(self.storage.capacity) as usize
+    }
+
+    /// Check whether the vector is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check whether the vector is full.
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// Append `item`, returning it back if the vector is full.
+    ///
+    /// # Errors
+    /// Returns `item` if the vector is full.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        let len = self.storage.word(VEC_LEN).min(self.storage.capacity);
+        if len == self.storage.capacity {
+            return Err(item);
+        }
+        // SAFETY: the slot is in the shared memory, aligned
+        unsafe { self.storage.slot(len).write_volatile(item) };
+        fence(Ordering::Release);
+        self.storage.set_word(VEC_LEN, len + 1);
+        Ok(())
+    }
+
+    /// Remove the last element, if any.
+    pub fn pop(&mut self) -> Option<T> {
+        let len = self.storage.word(VEC_LEN).min(self.storage.capacity);
+        let last = len.checked_sub(1)?;
+        self.storage.set_word(VEC_LEN, last);
+        // SAFETY: the slot is in the shared memory, initialized below the
+        // length
+        Some(unsafe { self.storage.slot(last).read_volatile() })
+    }
+
+    /// Append the elements of `items`, or none if they don't all fit.
+    ///
+    /// # Errors
+    /// Returns a `Status::Busy` error if the vector lacks room for them.
+    pub fn extend_from_slice(&mut self, items: &[T]) -> Result<(), Error> {
+        let len = self.len();
+        if items.len() > self.capacity() - len {
+            return Err(self.storage.shm.error(Status::Busy));
+        }
+        // SAFETY: the slots are in the shared memory, aligned, and not
+        // borrowed through `as_slice`, `self` being borrowed mutably
+        unsafe {
+            ptr::copy_nonoverlapping(
+                items.as_ptr(),
+                self.storage.data.add(len).as_ptr(),
+                items.len(),
+            );
+        }
+        fence(Ordering::Release);
+        // the new length is below the capacity
+        self.storage.set_word(
+            VEC_LEN,
+            u32::try_from(len + items.len()).unwrap_or(u32::MAX),
+        );
+        Ok(())
+    }
+
+    /// Shorten the vector to `len` elements, if longer.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            // below the length, itself a u32
+            self.storage
+                .set_word(VEC_LEN, u32::try_from(len).unwrap_or_default());
+        }
+    }
+
+    /// Remove all the elements.
+    pub fn clear(&mut self) {
+        self.storage.set_word(VEC_LEN, 0);
+    }
+
+    /// Return the elements.
+    #[must_use]
+    pub fn as_slice(&self) -> &[T] {
+        fence(Ordering::Acquire);
+        // SAFETY: the elements below the length are initialized, in the
+        // shared memory
+        unsafe { core::slice::from_raw_parts(self.storage.data.as_ptr(), self.len()) }
+    }
+
+    /// Return the elements, mutably.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        fence(Ordering::Acquire);
+        // SAFETY: see `as_slice`, `self` being borrowed mutably
+        unsafe { core::slice::from_raw_parts_mut(self.storage.data.as_ptr(), self.len()) }
+    }
+}
+
+impl<T: Copy> Deref for ShmVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: Copy> DerefMut for ShmVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+/// Single producer, single consumer ring in a shared memory, see the
+/// [module](self) documentation
+///
+/// The read and write counts run modulo twice the capacity, telling a full
+/// ring from an empty one, each one only being updated by one side.
+pub struct ShmRing<T> {
+    storage: Storage<T>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Copy> ShmRing<T> {
+    /// Lay out an empty ring in `shm`, before the peer opens it.
+    ///
+    /// # Errors
+    /// See [`ShmVec::new`].
+    pub fn new(shm: Shm<Mapped>) -> Result<Self, Error> {
+        let storage = Storage::new(shm, 2)?;
+        storage.set_word(RING_READ, 0);
+        storage.set_word(RING_WRITE, 0);
+        Ok(Self {
+            storage,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Return the ring laid out in `shm` by a peer with [`ShmRing::new`].
+    ///
+    /// # Safety
+    /// The elements enqueued by the peer must be valid `T` values.
+    ///
+    /// # Errors
+    /// See [`ShmVec::new`].
+    pub unsafe fn open(shm: Shm<Mapped>) -> Result<Self, Error> {
+        Ok(Self {
+            storage: Storage::new(shm, 2)?,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Release the shared memory, the queued elements staying in it.
+    #[must_use]
+    pub fn release(self) -> Shm<Mapped> {
+        self.storage.shm
+    }
+
+    /// Return the maximum number of queued elements.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.storage.capacity as usize
+    }
+
+    /// Return the number of queued elements, bounded by the capacity should
+    /// the peer have corrupted the counts.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.queued() as usize
+    }
+
+    /// Check whether the ring is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.queued() == 0
+    }
+
+    /// Check whether the ring is full.
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.queued() == self.storage.capacity
+    }
+
+    /// Queue `item`, returning it back if the ring is full.
+    ///
+    /// # Errors
+    /// Returns `item` if the ring is full.
+    pub fn enqueue(&mut self, item: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(item);
+        }
+        let write = self.storage.word(RING_WRITE);
+        // SAFETY: the slot is in the shared memory, aligned, and free
+        unsafe {
+            self.storage
+                .slot(write % self.storage.capacity)
+                .write_volatile(item);
+        }
+        fence(Ordering::Release);
+        self.storage.set_word(RING_WRITE, self.advance(write));
+        Ok(())
+    }
+
+    /// Remove the oldest element, if any.
+    pub fn dequeue(&mut self) -> Option<T> {
+        let item = self.peek()?;
+        let read = self.storage.word(RING_READ);
+        fence(Ordering::Release);
+        self.storage.set_word(RING_READ, self.advance(read));
+        Some(item)
+    }
+
+    /// Return the oldest element, if any, leaving it queued.
+    #[must_use]
+    pub fn peek(&self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        fence(Ordering::Acquire);
+        let read = self.storage.word(RING_READ);
+        // SAFETY: the slot is in the shared memory, written by the producer
+        Some(unsafe {
+            self.storage
+                .slot(read % self.storage.capacity)
+                .read_volatile()
+        })
+    }
+
+    fn queued(&self) -> u32 {
+        let span = 2 * u64::from(self.storage.capacity);
+        let read = u64::from(self.storage.word(RING_READ)) % span;
+        let write = u64::from(self.storage.word(RING_WRITE)) % span;
+        // below the span, itself a u32
+        u32::try_from((write + span - read) % span)
+            .unwrap_or_default()
+            .min(self.storage.capacity)
+    }
+
+    /// Return the count following `count`.
+    fn advance(&self, count: u32) -> u32 {
+        (count % (2 * self.storage.capacity) + 1) % (2 * self.storage.capacity)
+    }
+}
@@ -0,0 +1,144 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Mapped shared memory copies
+//!
+//! [`Shm::copy_into_shm`] and [`Shm::copy_from_shm`] copy with the CPU. Their
+//! `_dma` variants offload copies of at least [`DMA_COPY_THRESHOLD`] bytes to
+//! a memory-to-memory DMA stream, the task waiting for the stream completion
+//! event meanwhile. Sentry streams being statically configured, the stream is
+//! only used if its configuration is exactly the requested copy (source,
+//! destination and length), i.e. for copies between fixed buffers: the CPU
+//! copies otherwise. The `_dma` variants require the `dma` and `async`
+//! features.
+
+use core::ptr;
+use uapi::systypes::Status;
+#[cfg(all(feature = "dma", feature = "async"))]
+use uapi::systypes::dma::GpdmaTransferType;
+
+use super::{Mapped, Shm};
+#[cfg(all(feature = "dma", feature = "async"))]
+use crate::dma::DmaStream;
+use crate::error::Error;
+
+/// Length, in bytes, from which the `_dma` copies use the DMA stream
+#[cfg(all(feature = "dma", feature = "async"))]
+pub const DMA_COPY_THRESHOLD: usize = 1024;
+
+/// Check whether `stream` is configured for the `len` bytes copy from
+/// `source` to `dest`.
+#[cfg(all(feature = "dma", feature = "async"))]
+fn stream_copies(
+    stream: &DmaStream,
+    source: usize,
+    dest: usize,
+    len: usize,
+) -> Result<bool, Error> {
+    if len < DMA_COPY_THRESHOLD {
+        return Ok(false);
+    }
+    let config = stream.info()?;
+    Ok(
+        config.transfer_type == GpdmaTransferType::MemoryToMemory as u16
+            && config.source == source
+            && config.dest == dest
+            && config.transfer_len == len,
+    )
+}
+
+impl Shm<Mapped> {
+    /// Return the address of the `len` bytes at `offset`, if the shared
+    /// memory allows the `granted` access to them.
+    fn range(&mut self, offset: usize, len: usize, granted: bool) -> Result<*mut u8, Error> {
+        if !granted {
+            return Err(self.error(Status::Denied));
+        }
+        let shm_len = self.length()?;
+        if offset.checked_add(len).is_none_or(|end| end > shm_len) {
+            return Err(self.error(Status::Invalid));
+        }
+        Ok(ptr::with_exposed_provenance_mut(
+            self.base_address()? + offset,
+        ))
+    }
+
+    /// Copy `data` into the shared memory, at `offset`.
+    ///
+    /// # Errors
+    /// Returns `Status::Denied` if the shared memory is not writable,
+    /// `Status::Invalid` if `data` overflows it, and propagates kernel errors
+    /// if information retrieval fails.
+    pub fn copy_into_shm(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        let writable = self.is_writable();
+        let dest = self.range(offset, data.len(), writable)?;
+        // SAFETY: the range is in the mapped shared memory, which is not
+        // referenced by Rust code while the object is uniquely borrowed
+        unsafe { ptr::copy_nonoverlapping(data.as_ptr(), dest, data.len()) };
+        Ok(())
+    }
+
+    /// Copy the shared memory content at `offset` into `buf`.
+    ///
+    /// # Errors
+    /// Returns `Status::Denied` if the shared memory is not readable,
+    /// `Status::Invalid` if `buf` overflows it, and propagates kernel errors if
+    /// information retrieval fails.
+    pub fn copy_from_shm(&mut self, offset: usize, buf: &mut [u8]) -> Result<(), Error> {
+        let readable = self.is_readable();
+        let source = self.range(offset, buf.len(), readable)?;
+        // SAFETY: see copy_into_shm
+        unsafe { ptr::copy_nonoverlapping(source, buf.as_mut_ptr(), buf.len()) };
+        Ok(())
+    }
+
+    /// Copy `data` into the shared memory, at `offset`, with `stream` if it
+    /// is configured for this copy (see the [module](self) documentation).
+    ///
+    /// # Errors
+    /// See [`Shm::copy_into_shm`], DMA errors being propagated as well.
+    #[cfg(all(feature = "dma", feature = "async"))]
+    pub async fn copy_into_shm_dma(
+        &mut self,
+        stream: &mut DmaStream,
+        offset: usize,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let writable = self.is_writable();
+        let dest = self.range(offset, data.len(), writable)?;
+        if !stream_copies(stream, data.as_ptr().addr(), dest.addr(), data.len())? {
+            return self.copy_into_shm(offset, data);
+        }
+        stream
+            .transfer(data)
+            .await
+            .map(|_| ())
+            .map_err(|err| err.error)
+    }
+
+    /// Copy the shared memory content at `offset` into `buf`, with `stream`
+    /// if it is configured for this copy (see the [module](self)
+    /// documentation).
+    ///
+    /// # Errors
+    /// See [`Shm::copy_from_shm`], DMA errors being propagated as well.
+    #[cfg(all(feature = "dma", feature = "async"))]
+    pub async fn copy_from_shm_dma(
+        &mut self,
+        stream: &mut DmaStream,
+        offset: usize,
+        buf: &mut [u8],
+    ) -> Result<(), Error> {
+        let readable = self.is_readable();
+        let source = self.range(offset, buf.len(), readable)?;
+        if !stream_copies(stream, source.addr(), buf.as_ptr().addr(), buf.len())? {
+            return self.copy_from_shm(offset, buf);
+        }
+        stream
+            .transfer(buf)
+            .await
+            .map(|_| ())
+            .map_err(|err| err.error)
+    }
+}
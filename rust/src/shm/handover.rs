@@ -0,0 +1,214 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Shared memory handover
+//!
+//! A producer fills a shared memory mapped by both tasks, then signals the
+//! consumer with [`HANDOVER_SIGNAL`]. It must not touch the buffer again
+//! before the consumer acknowledges it, with the same signal, once done with
+//! the content.
+//!
+//! The shared memory starts with a header holding the payload length,
+//! followed by the payload.
+//!
+//! ```ignore
+//! // producer
+//! let mut tx = Producer::new(shm, consumer)?;
+//! let len = fill(tx.buffer());
+//! tx.send(len).await?;
+//!
+//! // consumer
+//! let mut rx = Consumer::new(shm, producer)?;
+//! let frame = rx.receive().await?;
+//! process(frame.data());
+//! frame.ack()?;
+//! ```
+
+use core::mem::size_of;
+use core::ptr::NonNull;
+use uapi::systypes::{Signal, Status, TaskHandle};
+
+use super::{Mapped, Shm};
+use crate::error::{Error, Subsystem};
+use crate::executor;
+
+/// Signal used for both the handover and its acknowledgement
+pub const HANDOVER_SIGNAL: Signal = Signal::Usr2;
+
+/// Length of the header preceding the payload
+const HEADER_LEN: usize = size_of::<u32>();
+
+/// Shared memory handover region
+struct Region {
+    shm: Shm<Mapped>,
+    base: NonNull<u8>,
+    len: usize,
+    peer: TaskHandle,
+}
+
+impl Region {
+    fn new(mut shm: Shm<Mapped>, peer: TaskHandle, writable: bool) -> Result<Self, Error> {
+        let invalid = shm.error(Status::Invalid);
+        let len = shm.length()?;
+        let base = shm.base_address()?;
+        if len < HEADER_LEN || base % align_of::<u32>() != 0 || (writable && !shm.is_writable()) {
+            return Err(invalid);
+        }
+        let base = NonNull::new(core::ptr::with_exposed_provenance_mut(base)).ok_or(invalid)?;
+        Ok(Self {
+            shm,
+            base,
+            len: len - HEADER_LEN,
+            peer,
+        })
+    }
+
+    fn payload(&self) -> *mut u8 {
+        // SAFETY: the region is larger than the header
+        unsafe { self.base.as_ptr().add(HEADER_LEN) }
+    }
+
+    fn header(&self) -> *mut u32 {
+        self.base.cast().as_ptr()
+    }
+
+    fn signal_peer(&self) -> Result<(), Error> {
+        match crate::sys::syscall::send_signal(self.peer, HANDOVER_SIGNAL) {
+            Status::Ok => Ok(()),
+            status => Err(Error::new(Subsystem::Shm, status).with_handle(self.peer)),
+        }
+    }
+
+    async fn wait_peer(&self) {
+        executor::wait_signal_from(HANDOVER_SIGNAL, self.peer).await;
+    }
+}
+
+/// Producer side of a shared memory handover
+pub struct Producer {
+    region: Region,
+}
+
+impl Producer {
+    /// Create the producer of the given shared memory, consumed by `consumer`.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the shared memory is too small,
+    /// misaligned or not writable, or propagates kernel errors if information
+    /// retrieval fails.
+    pub fn new(shm: Shm<Mapped>, consumer: TaskHandle) -> Result<Self, Error> {
+        Region::new(shm, consumer, true).map(|region| Self { region })
+    }
+
+    /// Payload buffer, to be filled before calling [`Producer::send`].
+    pub fn buffer(&mut self) -> &mut [u8] {
+        // SAFETY: the consumer does not access the region until the next send
+        unsafe { core::slice::from_raw_parts_mut(self.region.payload(), self.region.len) }
+    }
+
+    /// Hand the first `len` bytes of the buffer over to the consumer, and wait
+    /// for its acknowledgement.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if `len` exceeds the buffer length, or
+    /// propagates kernel errors if the consumer can't be signaled.
+    pub async fn send(&mut self, len: usize) -> Result<(), Error> {
+        let header = u32::try_from(len)
+            .ok()
+            .filter(|_| len <= self.region.len)
+            .ok_or(self.region.shm.error(Status::Invalid))?;
+        // SAFETY: the header is aligned, and owned by the producer until sent
+        unsafe { self.region.header().write_volatile(header) };
+        self.region.signal_peer()?;
+        self.region.wait_peer().await;
+        Ok(())
+    }
+
+    /// Release the shared memory.
+    #[must_use]
+    pub fn release(self) -> Shm<Mapped> {
+        self.region.shm
+    }
+}
+
+/// Consumer side of a shared memory handover
+pub struct Consumer {
+    region: Region,
+}
+
+impl Consumer {
+    /// Create the consumer of the given shared memory, filled by `producer`.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the shared memory is too small or
+    /// misaligned, or propagates kernel errors if information retrieval fails.
+    pub fn new(shm: Shm<Mapped>, producer: TaskHandle) -> Result<Self, Error> {
+        Region::new(shm, producer, false).map(|region| Self { region })
+    }
+
+    /// Wait for the producer to hand the buffer over.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the producer sent an invalid
+    /// payload length. The handover is acknowledged anyway.
+    pub async fn receive(&mut self) -> Result<Received<'_>, Error> {
+        self.region.wait_peer().await;
+        // SAFETY: the header is aligned, and owned by the consumer until acked
+        let len = unsafe { self.region.header().read_volatile() };
+        match usize::try_from(len) {
+            Ok(len) if len <= self.region.len => Ok(Received {
+                region: &self.region,
+                len,
+                acked: false,
+            }),
+            _ => {
+                self.region.signal_peer()?;
+                Err(self.region.shm.error(Status::Invalid))
+            }
+        }
+    }
+
+    /// Release the shared memory.
+    #[must_use]
+    pub fn release(self) -> Shm<Mapped> {
+        self.region.shm
+    }
+}
+
+/// Buffer handed over to the consumer
+///
+/// The handover is acknowledged when dropped, if not explicitly with
+/// [`Received::ack`].
+pub struct Received<'a> {
+    region: &'a Region,
+    len: usize,
+    acked: bool,
+}
+
+impl Received<'_> {
+    /// Payload sent by the producer.
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        // SAFETY: the producer does not access the region until acked
+        unsafe { core::slice::from_raw_parts(self.region.payload(), self.len) }
+    }
+
+    /// Acknowledge the handover, the producer being allowed to reuse the
+    /// buffer.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the producer can't be signaled.
+    pub fn ack(mut self) -> Result<(), Error> {
+        self.acked = true;
+        self.region.signal_peer()
+    }
+}
+
+impl Drop for Received<'_> {
+    fn drop(&mut self) {
+        if !self.acked {
+            let _ = self.region.signal_peer();
+        }
+    }
+}
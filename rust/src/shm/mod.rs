@@ -0,0 +1,360 @@
+// SPDX-FileCopyrightText: 2025-2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::pedantic)]
+
+use core::marker::PhantomData;
+use sentry_uapi::systypes::SHMPermission;
+use uapi::systypes::shm::ShmInfo;
+use uapi::systypes::{ShmHandle, ShmLabel, Status};
+
+use crate::error::{Error, Subsystem};
+use crate::sys::copy_from_kernel;
+
+mod collections;
+mod copy;
+#[cfg(feature = "async")]
+mod handover;
+mod registry;
+#[cfg(all(feature = "sync", target_has_atomic = "32"))]
+mod rwlock;
+mod secure;
+
+pub use collections::{ShmRing, ShmVec};
+#[cfg(all(feature = "dma", feature = "async"))]
+pub use copy::DMA_COPY_THRESHOLD;
+#[cfg(feature = "async")]
+pub use handover::{Consumer, HANDOVER_SIGNAL, Producer, Received};
+#[cfg(feature = "mock")]
+pub(crate) use registry::clear as clear_info_cache;
+pub use registry::{MAX_CACHED_SHM, invalidate_info};
+pub use secure::SecureShm;
+
+#[cfg(all(feature = "sync", target_has_atomic = "32"))]
+pub use rwlock::{SharedRwLock, SharedRwLockReadGuard, SharedRwLockWriteGuard};
+
+/// Shared memory information fetch policy, see [`Shm::new_with`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FetchPolicy {
+    /// Fetch the information on its first access
+    #[default]
+    Lazy,
+    /// Fetch the information at construction time
+    Eager,
+}
+
+/// Marker type representing an **unmapped** shared memory.
+pub struct Unmapped;
+
+/// Marker type representing a **mapped** shared memory.
+pub struct Mapped;
+
+/// Shared Memory abstraction using the *typestate* pattern.
+///
+/// The state of the shared memory (mapped or unmapped) is encoded in the type
+/// system, preventing invalid operations at compile time.
+///
+/// # Typestate
+/// - [`Shm<Unmapped>`]: shared memory exists but is not mapped
+/// - [`Shm<Mapped>`]: shared memory is mapped
+///
+/// # Invariants
+/// - A `Shm` always owns a valid kernel handle
+/// - Mapping / unmapping transitions are type-safe
+pub struct Shm<State> {
+    handle: ShmHandle,
+    label: ShmLabel,
+    info_cache: Option<registry::Held>,
+    _state: PhantomData<State>,
+}
+
+impl<State> Shm<State> {
+    /// Retrieve a shared memory handle from a label.
+    ///
+    /// This performs a syscall followed by a copy from kernel space.
+    /// # Errors
+    /// Propagates kernel errors if handle retrieval fails.
+    #[cfg_attr(feature = "audit-syscalls", track_caller)]
+    pub fn fetch_handle(label: ShmLabel) -> Result<ShmHandle, Error> {
+        match crate::sys::syscall::get_shm_handle(label) {
+            Status::Ok => {}
+            status => return Err(Error::new(Subsystem::Shm, status)),
+        }
+
+        let mut handle = 0;
+        match copy_from_kernel(&mut handle) {
+            Ok(Status::Ok) => Ok(handle),
+            Ok(status) | Err(status) => Err(Error::new(Subsystem::Shm, status)),
+        }
+    }
+
+    /// Rebuild a shared memory object from its handle, the caller being
+    /// responsible for the mapping state matching `State`.
+    #[cfg(feature = "ffi")]
+    pub(crate) fn from_raw(handle: ShmHandle, label: ShmLabel) -> Self {
+        Self {
+            handle,
+            label,
+            info_cache: None,
+            _state: PhantomData,
+        }
+    }
+
+    /// Refresh cached shared memory information from the kernel.
+    ///
+    /// The information is cached for all the objects of this shared memory.
+    /// # Errors
+    /// Propagates kernel errors if information refresh fails.
+    pub fn refresh_info(&mut self) -> Result<&ShmInfo, Error> {
+        let mut info = ShmInfo {
+            label: 0,
+            handle: 0,
+            base: 0,
+            len: 0,
+            perms: 0,
+        };
+
+        crate::sys::syscall::shm_get_infos(self.handle);
+        match copy_from_kernel(&mut info) {
+            Ok(Status::Ok) => {
+                registry::insert(info);
+                Ok(&self.info_cache.insert(registry::hold(info)).info)
+            }
+            Ok(status) | Err(status) => Err(self.error(status)),
+        }
+    }
+
+    /// Build an error involving this shared memory.
+    pub(crate) fn error(&self, status: Status) -> Error {
+        Error::new(Subsystem::Shm, status).with_handle(self.handle)
+    }
+
+    /// Return cached information or refresh it if needed.
+    ///
+    /// The information held by this object is used first, then the cache
+    /// shared by all the objects of this shared memory, see
+    /// [`invalidate_info`].
+    /// # Errors
+    /// Propagates kernel errors if information retrieval fails.
+    pub fn info(&mut self) -> Result<&ShmInfo, Error> {
+        if let Some(held) = self.info_cache.filter(registry::is_current) {
+            return Ok(&self.info_cache.insert(held).info);
+        }
+        match registry::cached(self.handle) {
+            Some(info) => Ok(&self.info_cache.insert(registry::hold(info)).info),
+            None => self.refresh_info(),
+        }
+    }
+
+    pub fn has_permission(&mut self, perm: SHMPermission) -> bool {
+        self.info().is_ok_and(|info| info.perms & perm as u32 != 0)
+    }
+
+    /// Return the permission mask of the shared memory.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if information retrieval fails.
+    pub fn permissions(&mut self) -> Result<u32, Error> {
+        Ok(self.info()?.perms)
+    }
+
+    /// Return the base virtual address of the shared memory.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if information retrieval fails.
+    pub fn base_address(&mut self) -> Result<usize, Error> {
+        Ok(self.info()?.base)
+    }
+
+    /// Return the length (in bytes) of the shared memory.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if information retrieval fails.
+    pub fn length(&mut self) -> Result<usize, Error> {
+        Ok(self.info()?.len)
+    }
+
+    /// Check whether the shared memory is readable.
+    pub fn is_readable(&mut self) -> bool {
+        self.has_permission(SHMPermission::Read)
+    }
+
+    /// Check whether the shared memory is writable.
+    pub fn is_writable(&mut self) -> bool {
+        self.has_permission(SHMPermission::Write)
+    }
+
+    /// Check whether the shared memory can be transferred to another task.
+    pub fn is_transferable(&mut self) -> bool {
+        self.has_permission(SHMPermission::Transfer)
+    }
+
+    /// Check whether the shared memory can be mapped.
+    pub fn is_mappable(&mut self) -> bool {
+        self.has_permission(SHMPermission::Map)
+    }
+}
+
+/* ------------------------------------------------------------------------- */
+/* Unmapped state                                                             */
+/* ------------------------------------------------------------------------- */
+
+impl Shm<Unmapped> {
+    /// Create a new shared memory object in the **unmapped** state.
+    ///
+    /// This does **not** map the memory; it only retrieves a handle.
+    ///
+    /// # Arguments
+    /// * `label` - Kernel shared memory label
+    ///
+    /// # Errors
+    /// Returns any kernel error encountered during handle retrieval.
+    #[cfg_attr(feature = "audit-syscalls", track_caller)]
+    pub fn new(label: ShmLabel) -> Result<Self, Error> {
+        Self::new_with(label, FetchPolicy::Lazy)
+    }
+
+    /// Create a new shared memory object in the **unmapped** state, fetching
+    /// its information as required by `policy`.
+    ///
+    /// With [`FetchPolicy::Eager`], the information is fetched right away, so
+    /// that [`Shm::info`] and its accessors then succeed with no syscall, as
+    /// long as no information is invalidated (see [`invalidate_info`]), the
+    /// object holding it even once evicted from the cache (see
+    /// [`MAX_CACHED_SHM`]).
+    ///
+    /// # Errors
+    /// Returns any kernel error encountered during handle or, with
+    /// [`FetchPolicy::Eager`], information retrieval.
+    #[cfg_attr(feature = "audit-syscalls", track_caller)]
+    pub fn new_with(label: ShmLabel, policy: FetchPolicy) -> Result<Self, Error> {
+        let handle = Self::fetch_handle(label)?;
+
+        let mut shm = Self {
+            handle,
+            label,
+            info_cache: None,
+            _state: PhantomData,
+        };
+        if policy == FetchPolicy::Eager {
+            shm.refresh_info()?;
+        }
+        Ok(shm)
+    }
+
+    /// Create the shared memory objects of `labels` in the **unmapped** state,
+    /// their information being prefetched as with [`FetchPolicy::Eager`].
+    ///
+    /// The Sentry ABI offers no batched syscall: this is a shorthand for
+    /// creating the objects one by one with [`Shm::new_with`], i.e. two
+    /// syscalls and two exchange area copies per label. The returned objects
+    /// then serve [`Shm::info`] and its accessors with no further syscall
+    /// until the next invalidation (see [`invalidate_info`]), even beyond
+    /// [`MAX_CACHED_SHM`] objects.
+    ///
+    /// # Errors
+    /// Returns the first kernel error encountered, no object being returned
+    /// then.
+    pub fn new_prefetched<const N: usize>(labels: [ShmLabel; N]) -> Result<[Self; N], Error> {
+        let mut infos = [ShmInfo {
+            label: 0,
+            handle: 0,
+            base: 0,
+            len: 0,
+            perms: 0,
+        }; N];
+        for (info, &label) in infos.iter_mut().zip(&labels) {
+            let mut shm = Self::new(label)?;
+            *info = *shm.refresh_info()?;
+        }
+
+        Ok(core::array::from_fn(|index| Self {
+            handle: infos[index].handle,
+            label: labels[index],
+            info_cache: Some(registry::hold(infos[index])),
+            _state: PhantomData,
+        }))
+    }
+
+    /// Map the shared memory into the current address space.
+    ///
+    /// On success, this consumes `self` and returns a [`Shm<Mapped>`].
+    ///
+    /// # Arguments
+    /// * `to_task` - Target task identifier
+    ///
+    /// # Errors
+    /// Returns kernel errors such as:
+    /// - `Status::Denied`
+    /// - `Status::Busy`
+    /// - `Status::Invalid`
+    #[cfg_attr(feature = "audit-syscalls", track_caller)]
+    pub fn map(self, _to_task: u32) -> Result<Shm<Mapped>, Error> {
+        match crate::sys::syscall::map_shm(self.handle) {
+            Status::Ok => {
+                invalidate_info(self.handle);
+                Ok(Shm {
+                    handle: self.handle,
+                    label: self.label,
+                    info_cache: None,
+                    _state: PhantomData,
+                })
+            }
+            status => Err(self.error(status)),
+        }
+    }
+
+    /// Set access permissions for another task.
+    ///
+    /// This operation is only valid while the memory is **unmapped**.
+    ///
+    /// # Arguments
+    /// * `to_task` - Target task identifier
+    /// * `perms` - Permission bitmask
+    ///
+    /// # Errors
+    /// Returns kernel errors if permission update fails.
+    #[cfg_attr(feature = "audit-syscalls", track_caller)]
+    pub fn set_credentials(&mut self, to_task: u32, perms: u32) -> Result<(), Error> {
+        match crate::sys::syscall::shm_set_credential(self.handle, to_task, perms) {
+            Status::Ok => {
+                invalidate_info(self.handle);
+                self.info_cache = None;
+                Ok(())
+            }
+            status => Err(self.error(status)),
+        }
+    }
+}
+
+/* ------------------------------------------------------------------------- */
+/* Mapped state                                                               */
+/* ------------------------------------------------------------------------- */
+
+impl Shm<Mapped> {
+    /// Unmap the shared memory.
+    ///
+    /// This consumes `self` and returns a [`Shm<Unmapped>`].
+    ///
+    /// # Errors
+    /// Returns kernel errors if unmapping fails.
+    #[cfg_attr(feature = "audit-syscalls", track_caller)]
+    pub fn unmap(self) -> Result<Shm<Unmapped>, Error> {
+        match crate::sys::syscall::unmap_shm(self.handle) {
+            Status::Ok => {
+                invalidate_info(self.handle);
+                Ok(Shm {
+                    handle: self.handle,
+                    label: self.label,
+                    info_cache: None,
+                    _state: PhantomData,
+                })
+            }
+            status => Err(self.error(status)),
+        }
+    }
+}
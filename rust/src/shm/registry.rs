@@ -0,0 +1,140 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Task wide shared memory information cache
+//!
+//! Shared memory information is cached per handle, so that all the [`Shm`]
+//! objects of a given shared memory share a single kernel query. Entries are
+//! invalidated on the operations changing the information (credentials
+//! update, mapping), or explicitly with [`invalidate_info`], e.g. once a peer
+//! reported an ownership transfer.
+//!
+//! Each object also keeps the information it got, along with the
+//! invalidation generation it got it at, so that an entry evicted from the
+//! registry is still served by the objects holding it, until the next
+//! invalidation.
+//!
+//! [`Shm`]: super::Shm
+
+use core::cell::UnsafeCell;
+use uapi::systypes::ShmHandle;
+use uapi::systypes::shm::ShmInfo;
+
+/// Maximum number of cached shared memories, the oldest entry being evicted
+/// when the cache is full
+pub const MAX_CACHED_SHM: usize = 8;
+
+struct Registry {
+    entries: [Option<ShmInfo>; MAX_CACHED_SHM],
+    /// Next entry to evict
+    next: usize,
+    /// Number of invalidations, wrapping
+    generation: u32,
+}
+
+/// Information held by a [`Shm`](super::Shm) object
+#[derive(Clone, Copy)]
+pub(super) struct Held {
+    pub(super) info: ShmInfo,
+    generation: u32,
+}
+
+impl Registry {
+    fn get(&self, handle: ShmHandle) -> Option<ShmInfo> {
+        self.entries
+            .iter()
+            .flatten()
+            .find(|info| info.handle == handle)
+            .copied()
+    }
+
+    fn insert(&mut self, info: ShmInfo) {
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .flatten()
+            .find(|entry| entry.handle == info.handle)
+        {
+            *entry = info;
+            return;
+        }
+
+        let slot = if let Some(free) = self.entries.iter().position(Option::is_none) {
+            free
+        } else {
+            let oldest = self.next;
+            self.next = (oldest + 1) % MAX_CACHED_SHM;
+            oldest
+        };
+        self.entries[slot] = Some(info);
+    }
+
+    fn invalidate(&mut self, handle: ShmHandle) {
+        self.generation = self.generation.wrapping_add(1);
+        for entry in &mut self.entries {
+            if entry.is_some_and(|info| info.handle == handle) {
+                *entry = None;
+            }
+        }
+    }
+}
+
+struct RegistryCell(UnsafeCell<Registry>);
+
+// SAFETY: a Sentry task is single-threaded, and the registry is never
+// borrowed across calls of `with_registry`
+unsafe impl Sync for RegistryCell {}
+
+static REGISTRY: RegistryCell = RegistryCell(UnsafeCell::new(Registry {
+    entries: [None; MAX_CACHED_SHM],
+    next: 0,
+    generation: 0,
+}));
+
+/// Execute `f` with an exclusive access to the registry
+fn with_registry<R>(f: impl FnOnce(&mut Registry) -> R) -> R {
+    // SAFETY: see RegistryCell, `f` never reaches `with_registry`
+    f(unsafe { &mut *REGISTRY.0.get() })
+}
+
+/// Return the cached information of `handle`.
+pub(super) fn cached(handle: ShmHandle) -> Option<ShmInfo> {
+    with_registry(|registry| registry.get(handle))
+}
+
+/// Cache the information of `info.handle`.
+pub(super) fn insert(info: ShmInfo) {
+    with_registry(|registry| registry.insert(info));
+}
+
+/// Return `info` as held by an object from now on.
+pub(super) fn hold(info: ShmInfo) -> Held {
+    Held {
+        info,
+        generation: with_registry(|registry| registry.generation),
+    }
+}
+
+/// Check whether `held` has not been invalidated since it was got.
+pub(super) fn is_current(held: &Held) -> bool {
+    with_registry(|registry| registry.generation == held.generation)
+}
+
+/// Drop the cached information of the shared memory `handle`, the next
+/// information access of any of its [`Shm`](super::Shm) objects querying the
+/// kernel again.
+pub fn invalidate_info(handle: ShmHandle) {
+    with_registry(|registry| registry.invalidate(handle));
+}
+
+/// Drop all the cached information, for a fresh fake kernel session.
+#[cfg(feature = "mock")]
+pub(crate) fn clear() {
+    with_registry(|registry| {
+        registry.entries = [None; MAX_CACHED_SHM];
+        registry.next = 0;
+        // the objects of the previous session are stale
+        registry.generation = registry.generation.wrapping_add(1);
+    });
+}
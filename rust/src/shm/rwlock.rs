@@ -0,0 +1,208 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+use core::cell::UnsafeCell;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicU32, Ordering};
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+
+use super::{Mapped, Shm};
+use crate::sync::WaitQueue;
+
+/// Lock state bit set while the writer holds the lock, lower bits holding
+/// the number of readers.
+const WRITER: u32 = 1 << 31;
+
+/// Readers-writer lock living in a shared memory.
+///
+/// Many reader tasks or a single writer task can hold the lock at a time.
+/// The lock is writer-preferring: as soon as a writer is waiting, new readers
+/// are blocked until the writer has released the lock, so that a continuous
+/// flow of readers can't starve it.
+///
+/// Blocked tasks are parked in the kernel and woken up by the releasing task,
+/// see [`crate::sync::wake`].
+///
+/// `T` should not hold any pointer, as the shared memory is not mapped at the
+/// same address in each task.
+#[repr(C)]
+pub struct SharedRwLock<T> {
+    state: AtomicU32,
+    writers_waiting: AtomicU32,
+    queue: WaitQueue,
+    data: UnsafeCell<T>,
+}
+
+// SAFETY: access to data is serialized by the lock state
+unsafe impl<T: Send> Send for SharedRwLock<T> {}
+// SAFETY: access to data is serialized by the lock state
+unsafe impl<T: Send + Sync> Sync for SharedRwLock<T> {}
+
+impl<T> SharedRwLock<T> {
+    /// Create a new, unlocked, readers-writer lock.
+    pub const fn new(value: T) -> Self {
+        Self {
+            state: AtomicU32::new(0),
+            writers_waiting: AtomicU32::new(0),
+            queue: WaitQueue::new(),
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Initialize a lock holding `value` at the base of a mapped shared memory.
+    ///
+    /// # Safety
+    /// No other task may access the lock while it is initialized; this is
+    /// typically done by the shared memory owner before granting access to
+    /// its peers.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the shared memory is too small or
+    /// misaligned, or propagates kernel errors if information retrieval fails.
+    pub unsafe fn init_in(shm: &mut Shm<Mapped>, value: T) -> Result<&Self, Error> {
+        let lock = Self::locate(shm)?;
+        // SAFETY: location checked, exclusivity guaranteed by the caller
+        unsafe { lock.write(Self::new(value)) };
+        // SAFETY: just initialized
+        Ok(unsafe { &*lock })
+    }
+
+    /// Return the lock living at the base of a mapped shared memory.
+    ///
+    /// # Safety
+    /// The lock must have been initialized with [`SharedRwLock::init_in`], by
+    /// this task or by a peer.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the shared memory is too small or
+    /// misaligned, or propagates kernel errors if information retrieval fails.
+    pub unsafe fn from_shm(shm: &mut Shm<Mapped>) -> Result<&Self, Error> {
+        let lock = Self::locate(shm)?;
+        // SAFETY: location checked, initialization guaranteed by the caller
+        Ok(unsafe { &*lock })
+    }
+
+    fn locate(shm: &mut Shm<Mapped>) -> Result<*mut Self, Error> {
+        let base = shm.base_address()?;
+        if shm.length()? < size_of::<Self>() || base % align_of::<Self>() != 0 {
+            return Err(Error::new(Subsystem::Shm, Status::Invalid));
+        }
+        Ok(core::ptr::with_exposed_provenance_mut(base))
+    }
+
+    /// Acquire the lock for reading, blocking while a writer holds or waits
+    /// for it.
+    pub fn read(&self) -> SharedRwLockReadGuard<'_, T> {
+        loop {
+            if let Some(guard) = self.try_read() {
+                return guard;
+            }
+            self.queue.wait_while(|| {
+                self.state.load(Ordering::Acquire) & WRITER != 0
+                    || self.writers_waiting.load(Ordering::Acquire) != 0
+            });
+        }
+    }
+
+    /// Try to acquire the lock for reading without blocking.
+    pub fn try_read(&self) -> Option<SharedRwLockReadGuard<'_, T>> {
+        if self.writers_waiting.load(Ordering::Acquire) != 0 {
+            return None;
+        }
+        let state = self.state.load(Ordering::Acquire);
+        if state & WRITER != 0 {
+            return None;
+        }
+        self.state
+            .compare_exchange(state, state + 1, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| SharedRwLockReadGuard { lock: self })
+    }
+
+    /// Acquire the lock for writing, blocking while readers or another writer
+    /// hold it.
+    pub fn write(&self) -> SharedRwLockWriteGuard<'_, T> {
+        self.writers_waiting.fetch_add(1, Ordering::AcqRel);
+        loop {
+            if self
+                .state
+                .compare_exchange(0, WRITER, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                self.writers_waiting.fetch_sub(1, Ordering::AcqRel);
+                return SharedRwLockWriteGuard { lock: self };
+            }
+            self.queue
+                .wait_while(|| self.state.load(Ordering::Acquire) != 0);
+        }
+    }
+
+    /// Try to acquire the lock for writing without blocking.
+    pub fn try_write(&self) -> Option<SharedRwLockWriteGuard<'_, T>> {
+        self.state
+            .compare_exchange(0, WRITER, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| SharedRwLockWriteGuard { lock: self })
+    }
+
+    fn read_unlock(&self) {
+        if self.state.fetch_sub(1, Ordering::Release) == 1 {
+            self.queue.notify_all();
+        }
+    }
+
+    fn write_unlock(&self) {
+        self.state.store(0, Ordering::Release);
+        self.queue.notify_all();
+    }
+}
+
+/// Shared access to the data protected by a [`SharedRwLock`].
+pub struct SharedRwLockReadGuard<'a, T> {
+    lock: &'a SharedRwLock<T>,
+}
+
+impl<T> Deref for SharedRwLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: readers only share the data
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T> Drop for SharedRwLockReadGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.read_unlock();
+    }
+}
+
+/// Exclusive access to the data protected by a [`SharedRwLock`].
+pub struct SharedRwLockWriteGuard<'a, T> {
+    lock: &'a SharedRwLock<T>,
+}
+
+impl<T> Deref for SharedRwLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the writer owns the lock
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T> DerefMut for SharedRwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: the writer owns the lock
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<T> Drop for SharedRwLockWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.write_unlock();
+    }
+}
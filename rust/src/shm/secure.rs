@@ -0,0 +1,98 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Shared memories wiped before being unmapped
+//!
+//! A [`SecureShm`] holds sensitive data (key material, plaintext): when the
+//! task holds the write permission, the whole region is zeroed before
+//! [`SecureShm::unmap`] unmaps it, and when the wrapper is dropped. The
+//! credentials of another task being only set while unmapped, the region is
+//! wiped before it can be handed over. A shared memory the task can't write
+//! holds nothing the task put in it, and is left untouched.
+
+use core::sync::atomic::{Ordering, compiler_fence};
+
+use super::{Mapped, Shm, Unmapped};
+use crate::error::Error;
+
+/// Mapped shared memory zeroed before being unmapped, see the
+/// [module](self) documentation
+pub struct SecureShm {
+    // taken when unmapped
+    shm: Option<Shm<Mapped>>,
+    base: usize,
+    /// Length to wipe, 0 if not writable
+    wipe_len: usize,
+}
+
+impl SecureShm {
+    /// Wrap `shm`, fetching its layout so that it can be wiped with no
+    /// syscall.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if information retrieval fails.
+    pub fn new(mut shm: Shm<Mapped>) -> Result<Self, Error> {
+        let base = shm.base_address()?;
+        let wipe_len = if shm.is_writable() { shm.length()? } else { 0 };
+        Ok(Self {
+            shm: Some(shm),
+            base,
+            wipe_len,
+        })
+    }
+
+    /// Return the wrapped shared memory.
+    pub fn shm(&mut self) -> &mut Shm<Mapped> {
+        match self.shm.as_mut() {
+            Some(shm) => shm,
+            // only taken by unmap(), consuming self
+            None => unreachable!(),
+        }
+    }
+
+    /// Return the shared memory content, if writable.
+    pub fn as_mut_slice(&mut self) -> Option<&mut [u8]> {
+        if self.wipe_len == 0 {
+            return None;
+        }
+        // SAFETY: the region is mapped and writable for the lifetime of the
+        // wrapper, exclusively borrowed
+        Some(unsafe {
+            core::slice::from_raw_parts_mut(
+                core::ptr::with_exposed_provenance_mut(self.base),
+                self.wipe_len,
+            )
+        })
+    }
+
+    /// Zero the shared memory, if writable.
+    pub fn wipe(&mut self) {
+        let dest = core::ptr::with_exposed_provenance_mut::<u8>(self.base);
+        for offset in 0..self.wipe_len {
+            // SAFETY: the region is mapped and writable, volatile writes not
+            // being elided even though the memory is about to be unmapped
+            unsafe { dest.add(offset).write_volatile(0) };
+        }
+        compiler_fence(Ordering::SeqCst);
+    }
+
+    /// Zero the shared memory, if writable, then unmap it.
+    ///
+    /// # Errors
+    /// Returns `Shm::unmap` errors, the region being wiped nonetheless.
+    pub fn unmap(mut self) -> Result<Shm<Unmapped>, Error> {
+        self.wipe();
+        self.wipe_len = 0;
+        match self.shm.take() {
+            Some(shm) => shm.unmap(),
+            None => unreachable!(),
+        }
+    }
+}
+
+impl Drop for SecureShm {
+    fn drop(&mut self) {
+        self.wipe();
+    }
+}
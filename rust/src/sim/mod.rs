@@ -0,0 +1,211 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Linux host simulator
+//!
+//! With the `sim` feature, Shield syscalls are served by the host operating
+//! system instead of the Sentry kernel, so that a multi-task Shield
+//! application can be run and debugged on a development machine, each task
+//! being a Linux process:
+//! - shared memories are files of the simulator runtime directory, mapped with
+//!   `mmap()` in the tasks mapping them,
+//! - IPCs and signals are datagrams sent over the tasks Unix sockets,
+//! - the uptime is the host monotonic clock, and alarms are host timers,
+//! - log records are written on the standard output.
+//!
+//! Each task process is configured through the environment:
+//! - `SHIELD_SIM_TASK`: task label, in hexadecimal (`0x` prefix optional),
+//! - `SHIELD_SIM_DIR`: runtime directory shared by the tasks, defaulting to
+//!   [`DEFAULT_DIR`].
+//!
+//! Task handles are the task labels, and the shared memories are created
+//! beforehand by the launcher with [`create_shm`]. Devices, IRQs and DMA
+//! streams are not simulated, and shared memory permissions are not enforced.
+//!
+//! The Shield task entrypoint is not used: the task application is a regular
+//! host binary, its `main()` function calling the task main function.
+
+extern crate std;
+
+pub mod syscall;
+
+use std::collections::VecDeque;
+use std::env;
+use std::fs::OpenOptions;
+use std::io;
+use std::os::unix::net::UnixDatagram;
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+use uapi::systypes::{ShmHandle, ShmLabel, TaskLabel};
+
+/// Runtime directory used when `SHIELD_SIM_DIR` is not set
+pub const DEFAULT_DIR: &str = "/tmp/shield-sim";
+
+/// Return the simulator runtime directory.
+#[must_use]
+pub fn runtime_dir() -> PathBuf {
+    env::var_os("SHIELD_SIM_DIR").map_or_else(|| PathBuf::from(DEFAULT_DIR), PathBuf::from)
+}
+
+/// Create (or resize) the file backing the shared memory `label`.
+///
+/// Launchers call this function for each shared memory of the application
+/// before starting the tasks.
+///
+/// # Errors
+///
+/// Will return the host I/O error if the runtime directory or the file can't
+/// be created.
+pub fn create_shm(label: ShmLabel, len: usize) -> io::Result<()> {
+    std::fs::create_dir_all(runtime_dir())?;
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(shm_path(label))?;
+    file.set_len(len as u64)
+}
+
+fn shm_path(label: ShmLabel) -> PathBuf {
+    runtime_dir().join(std::format!("shm-{label:08x}"))
+}
+
+fn socket_path(label: TaskLabel) -> PathBuf {
+    runtime_dir().join(std::format!("task-{label:08x}.sock"))
+}
+
+/// Label of the simulated task, from `SHIELD_SIM_TASK`
+fn task_label() -> TaskLabel {
+    env::var("SHIELD_SIM_TASK")
+        .ok()
+        .and_then(|label| {
+            let digits = label.trim_start_matches("0x");
+            u32::from_str_radix(digits, 16).ok()
+        })
+        .unwrap_or_default()
+}
+
+/// Datagram exchanged between simulated tasks: the event kind, a padding, the
+/// sender label as a native endian `u32`, and the event data.
+const DATAGRAM_HEADER_LEN: usize = 8;
+
+/// Event received from a peer, or raised by the local alarm
+struct SimEvent {
+    kind: u8,
+    peer: u32,
+    data: Vec<u8>,
+}
+
+impl SimEvent {
+    fn encode(&self) -> Vec<u8> {
+        let mut datagram = Vec::with_capacity(DATAGRAM_HEADER_LEN + self.data.len());
+        datagram.extend_from_slice(&[self.kind, 0, 0, 0]);
+        datagram.extend_from_slice(&self.peer.to_ne_bytes());
+        datagram.extend_from_slice(&self.data);
+        datagram
+    }
+
+    fn decode(datagram: &[u8]) -> Option<Self> {
+        let (header, data) = datagram.split_at_checked(DATAGRAM_HEADER_LEN)?;
+        let peer = u32::from_ne_bytes(header[4..8].try_into().ok()?);
+        Some(Self {
+            kind: header[0],
+            peer,
+            data: data.to_vec(),
+        })
+    }
+}
+
+/// Shared memory mapped by the simulated task
+struct SimShm {
+    label: ShmLabel,
+    base: usize,
+    len: usize,
+}
+
+/// Task alarm, as a host timer deadline
+struct Alarm {
+    deadline: Instant,
+    period: Option<Duration>,
+}
+
+/// Simulated task state
+pub(crate) struct Sim {
+    label: TaskLabel,
+    socket: Option<UnixDatagram>,
+    pending: VecDeque<SimEvent>,
+    shms: Vec<SimShm>,
+    alarm: Option<Alarm>,
+}
+
+impl Sim {
+    const fn new() -> Self {
+        Self {
+            label: 0,
+            socket: None,
+            pending: VecDeque::new(),
+            shms: Vec::new(),
+            alarm: None,
+        }
+    }
+
+    /// Return the task socket, binding it on first use.
+    fn socket(&mut self) -> io::Result<&UnixDatagram> {
+        if self.socket.is_none() {
+            self.label = task_label();
+            std::fs::create_dir_all(runtime_dir())?;
+            let path = socket_path(self.label);
+            // a previous run of the task may have left its socket behind
+            let _ = std::fs::remove_file(&path);
+            self.socket = Some(UnixDatagram::bind(path)?);
+        }
+        self.socket
+            .as_ref()
+            .ok_or(io::ErrorKind::NotConnected.into())
+    }
+
+    fn shm(&self, handle: ShmHandle) -> Option<&SimShm> {
+        self.shms.iter().find(|shm| shm.label == handle)
+    }
+
+    /// Queue the alarm signal if the alarm deadline is reached.
+    fn raise_alarm(&mut self) {
+        let now = Instant::now();
+        let Some(alarm) = self.alarm.as_mut() else {
+            return;
+        };
+        if alarm.deadline > now {
+            return;
+        }
+        match alarm.period {
+            Some(period) => alarm.deadline += period,
+            None => self.alarm = None,
+        }
+        self.pending.push_back(SimEvent {
+            kind: uapi::systypes::EventType::Signal as u8,
+            peer: self.label,
+            data: (uapi::systypes::Signal::Alarm as u32)
+                .to_ne_bytes()
+                .to_vec(),
+        });
+    }
+
+    fn pop_event(&mut self, mask: u8) -> Option<SimEvent> {
+        let index = self
+            .pending
+            .iter()
+            .position(|event| event.kind & mask != 0)?;
+        self.pending.remove(index)
+    }
+}
+
+static SIM: Mutex<Sim> = Mutex::new(Sim::new());
+
+/// Execute `f` with an exclusive access to the simulated task state
+fn with_sim<R>(f: impl FnOnce(&mut Sim) -> R) -> R {
+    f(&mut SIM.lock().unwrap_or_else(PoisonError::into_inner))
+}
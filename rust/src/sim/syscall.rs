@@ -0,0 +1,428 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Simulated syscalls, with the `sentry_uapi::syscall` signatures
+
+#![allow(clippy::needless_pass_by_value)]
+
+extern crate std;
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::os::fd::AsRawFd;
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+use uapi::systypes::shm::ShmInfo;
+use uapi::systypes::{
+    AlarmFlag, CPUSleep, DeviceHandle, EventType, Precision, SHMPermission, ShmHandle, ShmLabel,
+    Signal, SleepDuration, SleepMode, Status, StreamHandle, StreamLabel, TaskHandle, TaskLabel,
+};
+
+use super::{
+    Alarm, DATAGRAM_HEADER_LEN, Sim, SimEvent, SimShm, shm_path, socket_path, task_label, with_sim,
+};
+use crate::sys::exchange::{
+    EXCHANGE_LEN, deliver_event, deliver_shm_info, deliver_u32, deliver_u64, read_exchange,
+};
+
+/// CPU frequency used to convert the uptime to cycles
+const CYCLES_PER_US: u64 = 64;
+
+/// Shared memories permissions, which are not enforced by the simulator
+const SHM_PERMS: u32 = SHMPermission::Map as u32
+    | SHMPermission::Read as u32
+    | SHMPermission::Write as u32
+    | SHMPermission::Transfer as u32;
+
+pub fn exit(status: i32) -> Status {
+    std::process::exit(status)
+}
+
+/// Task handles being the task labels, any task is known.
+pub fn get_process_handle(process: TaskLabel) -> Status {
+    deliver_u32(process);
+    Status::Ok
+}
+
+pub fn get_shm_handle(shm: ShmLabel) -> Status {
+    if shm_path(shm).exists() {
+        deliver_u32(shm);
+        Status::Ok
+    } else {
+        Status::Invalid
+    }
+}
+
+pub fn get_dma_stream_handle(_stream: StreamLabel) -> Status {
+    Status::Invalid
+}
+
+pub fn sched_yield() -> Status {
+    std::thread::yield_now();
+    Status::Ok
+}
+
+pub fn sleep(duration_ms: SleepDuration, _mode: SleepMode) -> Status {
+    std::thread::sleep(Duration::from_millis(u64::from(u32::from(duration_ms))));
+    Status::Ok
+}
+
+/// Tasks being started by the launcher, starting a task has no effect.
+pub fn start(_process: TaskLabel) -> Status {
+    Status::Ok
+}
+
+pub fn map_dev(_dev: DeviceHandle) -> Status {
+    Status::Invalid
+}
+
+pub fn map_shm(shm: ShmHandle) -> Status {
+    with_sim(|sim| {
+        if sim.shm(shm).is_some() {
+            return Status::AlreadyMapped;
+        }
+        match map_file(shm) {
+            Ok(mapping) => {
+                sim.shms.push(mapping);
+                Status::Ok
+            }
+            Err(_) => Status::Invalid,
+        }
+    })
+}
+
+fn map_file(label: ShmLabel) -> io::Result<SimShm> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(shm_path(label))?;
+    let len = usize::try_from(file.metadata()?.len()).map_err(|_| io::ErrorKind::InvalidData)?;
+    // SAFETY: a new shared mapping of the file is created, no existing memory
+    // is affected. The mapping outlives the file descriptor.
+    let base = unsafe {
+        libc::mmap(
+            core::ptr::null_mut(),
+            len,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED,
+            file.as_raw_fd(),
+            0,
+        )
+    };
+    if base == libc::MAP_FAILED {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(SimShm {
+        label,
+        base: base.expose_provenance(),
+        len,
+    })
+}
+
+pub fn unmap_dev(_dev: DeviceHandle) -> Status {
+    Status::Invalid
+}
+
+pub fn unmap_shm(shm: ShmHandle) -> Status {
+    with_sim(|sim| {
+        let Some(index) = sim.shms.iter().position(|mapping| mapping.label == shm) else {
+            return Status::Invalid;
+        };
+        let mapping = sim.shms.swap_remove(index);
+        // SAFETY: the mapping was created by `map_file()`, Shield no longer
+        // accessing it once unmapped
+        unsafe {
+            libc::munmap(
+                core::ptr::with_exposed_provenance_mut(mapping.base),
+                mapping.len,
+            );
+        }
+        Status::Ok
+    })
+}
+
+pub fn shm_set_credential(shm: ShmHandle, _id: TaskHandle, _shm_perm: u32) -> Status {
+    if shm_path(shm).exists() {
+        Status::Ok
+    } else {
+        Status::Invalid
+    }
+}
+
+pub fn send_ipc(target: TaskHandle, length: u8) -> Status {
+    let data = read_exchange(usize::from(length));
+    send(target, EventType::Ipc, data)
+}
+
+pub fn send_signal(resource: u32, signal_type: Signal) -> Status {
+    let data = (signal_type as u32).to_ne_bytes().to_vec();
+    send(resource, EventType::Signal, data)
+}
+
+fn send(target: TaskHandle, kind: EventType, data: Vec<u8>) -> Status {
+    with_sim(|sim| {
+        let Ok(socket) = sim.socket() else {
+            return Status::Critical;
+        };
+        let datagram = SimEvent {
+            kind: kind as u8,
+            peer: task_label(),
+            data,
+        }
+        .encode();
+        match socket.send_to(&datagram, socket_path(target)) {
+            Ok(_) => Status::Ok,
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => Status::Busy,
+            // the target task is not running
+            Err(_) => Status::Invalid,
+        }
+    })
+}
+
+pub fn gpio_get(_resource: u32, _io: u8) -> Status {
+    Status::Invalid
+}
+
+pub fn gpio_set(_resource: u32, _io: u8, _val: bool) -> Status {
+    Status::Invalid
+}
+
+pub fn gpio_reset(_resource: u32, _io: u8) -> Status {
+    Status::Invalid
+}
+
+pub fn gpio_toggle(_resource: u32, _io: u8) -> Status {
+    Status::Invalid
+}
+
+pub fn gpio_configure(_resource: u32, _io: u8) -> Status {
+    Status::Invalid
+}
+
+pub fn get_device_handle(_devlabel: u8) -> Status {
+    Status::Invalid
+}
+
+pub fn irq_acknowledge(_irq: u16) -> Status {
+    Status::Invalid
+}
+
+pub fn irq_enable(_irq: u16) -> Status {
+    Status::Invalid
+}
+
+pub fn irq_disable(_irq: u16) -> Status {
+    Status::Invalid
+}
+
+/// Deliver the first received event matching `mask`, waiting for it on the
+/// task socket.
+pub fn wait_for_event(mask: u8, timeout: i32) -> Status {
+    let now = Instant::now();
+    let limit = match timeout {
+        0 => None,
+        timeout => Some(now + Duration::from_millis(u64::from(timeout.max(0).unsigned_abs()))),
+    };
+    with_sim(|sim| wait(sim, mask, limit)).map_or(Status::Critical, |wait| wait.status(timeout))
+}
+
+/// Wait result, before distinguishing a non-blocking wait
+enum Wait {
+    Event(Status),
+    Expired,
+}
+
+impl Wait {
+    fn status(self, timeout: i32) -> Status {
+        match self {
+            Self::Event(status) => status,
+            Self::Expired if timeout < 0 => Status::Again,
+            Self::Expired => Status::Timeout,
+        }
+    }
+}
+
+fn wait(sim: &mut Sim, mask: u8, limit: Option<Instant>) -> io::Result<Wait> {
+    loop {
+        // collect the events already received
+        while sim.receive(Some(Duration::ZERO))? {}
+        sim.raise_alarm();
+        if let Some(event) = sim.pop_event(mask) {
+            return Ok(Wait::Event(deliver_event(
+                event.kind,
+                event.peer,
+                &event.data,
+            )));
+        }
+
+        let now = Instant::now();
+        if limit.is_some_and(|limit| limit <= now) {
+            return Ok(Wait::Expired);
+        }
+        let alarm = sim.alarm.as_ref().map(|alarm| alarm.deadline);
+        let deadline = match (limit, alarm) {
+            (Some(limit), Some(alarm)) => Some(limit.min(alarm)),
+            (limit, alarm) => limit.or(alarm),
+        };
+        // a zero read timeout being rejected, wait at least a microsecond
+        let timeout = deadline.map(|deadline| {
+            deadline
+                .saturating_duration_since(now)
+                .max(Duration::from_micros(1))
+        });
+        sim.receive(timeout)?;
+    }
+}
+
+impl Sim {
+    /// Receive a datagram from a peer, waiting up to `timeout`, a zero timeout
+    /// being non-blocking. Returns whether an event has been received.
+    fn receive(&mut self, timeout: Option<Duration>) -> io::Result<bool> {
+        let socket = self.socket()?;
+        match timeout {
+            Some(timeout) if timeout.is_zero() => socket.set_nonblocking(true)?,
+            timeout => {
+                socket.set_nonblocking(false)?;
+                socket.set_read_timeout(timeout)?;
+            }
+        }
+        let mut datagram = [0; DATAGRAM_HEADER_LEN + EXCHANGE_LEN];
+        match socket.recv(&mut datagram) {
+            Ok(len) => {
+                if let Some(event) = SimEvent::decode(&datagram[..len]) {
+                    self.pending.push_back(event);
+                }
+                Ok(true)
+            }
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                Ok(false)
+            }
+            Err(error) => Err(error),
+        }
+    }
+}
+
+pub fn pm_manage(_mode: CPUSleep) -> Status {
+    Status::Ok
+}
+
+pub fn alarm(timeout_ms: u32, flag: AlarmFlag) -> Status {
+    let period = Duration::from_millis(u64::from(timeout_ms));
+    with_sim(|sim| {
+        sim.alarm = match flag {
+            AlarmFlag::AlarmStart => Some(Alarm {
+                deadline: Instant::now() + period,
+                period: None,
+            }),
+            AlarmFlag::AlarmStartPeriodic => Some(Alarm {
+                deadline: Instant::now() + period,
+                period: Some(period),
+            }),
+            AlarmFlag::AlarmStop => None,
+        };
+    });
+    Status::Ok
+}
+
+pub fn log(length: usize) -> Status {
+    if length > uapi::length() {
+        return Status::Invalid;
+    }
+    let mut stdout = io::stdout().lock();
+    match stdout
+        .write_all(&read_exchange(length))
+        .and_then(|()| stdout.flush())
+    {
+        Ok(()) => Status::Ok,
+        Err(_) => Status::Critical,
+    }
+}
+
+pub fn get_random() -> Status {
+    let mut random = [0_u8; 4];
+    // SAFETY: the buffer is valid for its length
+    let len = unsafe { libc::getrandom(random.as_mut_ptr().cast(), random.len(), 0) };
+    if len.unsigned_abs() != random.len() {
+        return Status::Critical;
+    }
+    deliver_u32(u32::from_ne_bytes(random));
+    Status::Ok
+}
+
+/// The uptime is the host monotonic clock, shared by all the tasks.
+pub fn get_cycle(precision: Precision) -> Status {
+    let mut now = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `now` is a valid timespec
+    if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) } != 0 {
+        return Status::Critical;
+    }
+    let uptime_ns = now.tv_sec.unsigned_abs() * 1_000_000_000 + now.tv_nsec.unsigned_abs();
+    deliver_u64(match precision {
+        Precision::Cycle => uptime_ns * CYCLES_PER_US / 1000,
+        Precision::Nanoseconds => uptime_ns,
+        Precision::Microseconds => uptime_ns / 1000,
+        Precision::Milliseconds => uptime_ns / 1_000_000,
+    });
+    Status::Ok
+}
+
+pub fn pm_set_clock(_clk_reg: u32, _clkmsk: u32, _val: u32) -> Status {
+    Status::Ok
+}
+
+pub fn dma_start_stream(_dmah: StreamHandle) -> Status {
+    Status::Invalid
+}
+
+pub fn dma_suspend_stream(_dmah: StreamHandle) -> Status {
+    Status::Invalid
+}
+
+pub fn dma_get_stream_status(_dmah: StreamHandle) -> Status {
+    Status::Invalid
+}
+
+pub fn shm_get_infos(shm: ShmHandle) -> Status {
+    let Ok(metadata) = shm_path(shm).metadata() else {
+        return Status::Invalid;
+    };
+    let (base, len) = with_sim(|sim| {
+        sim.shm(shm).map_or_else(
+            || (0, usize::try_from(metadata.len()).unwrap_or_default()),
+            |mapping| (mapping.base, mapping.len),
+        )
+    });
+    deliver_shm_info(&ShmInfo {
+        handle: shm,
+        label: shm,
+        base,
+        len,
+        perms: SHM_PERMS,
+    });
+    Status::Ok
+}
+
+pub fn dma_assign_stream(_dmah: StreamHandle) -> Status {
+    Status::Invalid
+}
+
+pub fn dma_unassign_stream(_dmah: StreamHandle) -> Status {
+    Status::Invalid
+}
+
+pub fn dma_get_stream_info(_dmah: StreamHandle) -> Status {
+    Status::Invalid
+}
+
+pub fn dma_resume_stream(_dmah: StreamHandle) -> Status {
+    Status::Invalid
+}
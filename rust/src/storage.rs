@@ -0,0 +1,391 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Flash storage
+//!
+//! [`Flash`] exposes the flash region granted to the task as a contiguous
+//! NOR flash, over the [`FlashDevice`] driver of its controller:
+//!
+//! - reads and programs may be unaligned, partial program units being padded
+//!   with erased bytes. As most flash controllers (ECC protected ones at
+//!   least) can't program a unit twice, a partially programmed unit must be
+//!   erased.
+//! - erases are sector aligned.
+//! - the sectors reported bad by the device are skipped, the capacity being
+//!   reduced accordingly.
+//!
+//! No wear leveling is done: the good sectors are mapped in order, their
+//! erase counts being left to the layers above, such as the key-value store
+//! writing its log across its two banks.
+//!
+//! Its `read`, `write`, `erase` and `capacity` methods follow the
+//! `embedded-storage` `NorFlash` semantics. As the traits take the erase and
+//! program sizes as constants, a [`SizedFlash`] implements
+//! [`ReadNorFlash`](embedded_storage::nor_flash::ReadNorFlash) and
+//! [`NorFlash`](embedded_storage::nor_flash::NorFlash) for a region of the
+//! sector size and program unit it is built with. With the `kvstore` feature,
+//! a [`Flash`] is a [`crate::kvstore::Backing`].
+
+use embedded_storage::nor_flash::{self, NorFlashErrorKind};
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+
+/// Maximum number of sectors of a [`FlashDevice`]
+pub const MAX_SECTORS: usize = 1024;
+
+/// Maximum program unit of a [`FlashDevice`], in bytes
+pub const MAX_WRITE_SIZE: usize = 64;
+
+/// Value of the erased bytes
+const ERASED: u8 = 0xff;
+
+/// Flash device geometry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Geometry {
+    /// Number of sectors, up to [`MAX_SECTORS`]
+    pub sectors: usize,
+    /// Sector (erase unit) size, in bytes
+    pub sector_size: usize,
+    /// Program unit, in bytes, a power of two up to [`MAX_WRITE_SIZE`]
+    pub write_size: usize,
+}
+
+/// Flash controller driver
+///
+/// Offsets are relative to the start of the region granted to the task.
+pub trait FlashDevice {
+    /// Return the device geometry.
+    fn geometry(&self) -> Geometry;
+
+    /// Read `buf.len()` bytes at `offset`.
+    ///
+    /// # Errors
+    /// Returns the controller errors.
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), Error>;
+
+    /// Program `data` at `offset`, both aligned on the program unit.
+    ///
+    /// # Errors
+    /// Returns the controller errors.
+    fn program(&mut self, offset: usize, data: &[u8]) -> Result<(), Error>;
+
+    /// Erase the sector `sector`.
+    ///
+    /// # Errors
+    /// Returns the controller errors.
+    fn erase_sector(&mut self, sector: usize) -> Result<(), Error>;
+
+    /// Check whether the sector `sector` is bad, which no sector is by
+    /// default.
+    ///
+    /// # Errors
+    /// Returns the controller errors.
+    fn is_bad(&self, _sector: usize) -> Result<bool, Error> {
+        Ok(false)
+    }
+}
+
+fn error(status: Status) -> Error {
+    Error::new(Subsystem::Storage, status)
+}
+
+/// Flash region, see the [module](self) documentation
+pub struct Flash<D: FlashDevice> {
+    device: D,
+    geometry: Geometry,
+    /// Bad sectors bitmap
+    bad: [u32; MAX_SECTORS / 32],
+    /// Number of good sectors
+    sectors: usize,
+}
+
+impl<D: FlashDevice> Flash<D> {
+    /// Build the flash region of `device`, looking up its bad sectors.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if the device geometry is not supported, or
+    /// propagates the device errors.
+    pub fn new(device: D) -> Result<Self, Error> {
+        let geometry = device.geometry();
+        if geometry.sectors > MAX_SECTORS
+            || geometry.sector_size == 0
+            || !geometry.write_size.is_power_of_two()
+            || geometry.write_size > MAX_WRITE_SIZE
+            || !geometry.sector_size.is_multiple_of(geometry.write_size)
+        {
+            return Err(error(Status::Invalid));
+        }
+        let mut flash = Self {
+            device,
+            geometry,
+            bad: [0; MAX_SECTORS / 32],
+            sectors: 0,
+        };
+        for sector in 0..geometry.sectors {
+            if flash.device.is_bad(sector)? {
+                flash.bad[sector / 32] |= 1 << (sector % 32);
+            } else {
+                flash.sectors += 1;
+            }
+        }
+        Ok(flash)
+    }
+
+    /// Give the device back.
+    pub fn release(self) -> D {
+        self.device
+    }
+
+    /// Return the usable capacity, in bytes, the bad sectors excluded.
+    pub fn capacity(&self) -> usize {
+        self.sectors * self.geometry.sector_size
+    }
+
+    /// Return the sector size, in bytes.
+    pub fn sector_size(&self) -> usize {
+        self.geometry.sector_size
+    }
+
+    /// Return the program unit, in bytes.
+    pub fn write_size(&self) -> usize {
+        self.geometry.write_size
+    }
+
+    /// Check whether the physical sector `sector` is bad, the sectors beyond
+    /// the device being reported bad.
+    pub fn is_bad(&self, sector: usize) -> bool {
+        sector >= self.geometry.sectors || self.bad[sector / 32] & (1 << (sector % 32)) != 0
+    }
+
+    /// Return the physical offset of the usable byte `offset`.
+    fn physical(&self, offset: usize) -> usize {
+        let mut logical = offset / self.geometry.sector_size;
+        let mut sector = 0;
+        loop {
+            if !self.is_bad(sector) {
+                if logical == 0 {
+                    break;
+                }
+                logical -= 1;
+            }
+            sector += 1;
+        }
+        sector * self.geometry.sector_size + offset % self.geometry.sector_size
+    }
+
+    fn check_range(&self, offset: usize, len: usize) -> Result<(), Error> {
+        match offset.checked_add(len) {
+            Some(end) if end <= self.capacity() => Ok(()),
+            _ => Err(error(Status::Invalid)),
+        }
+    }
+
+    /// Read `buf.len()` bytes at `offset`.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if the range exceeds the capacity, or
+    /// propagates the device errors.
+    pub fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), Error> {
+        self.check_range(offset, buf.len())?;
+        let mut done = 0;
+        while done < buf.len() {
+            let at = offset + done;
+            let len = (buf.len() - done).min(self.sector_size() - at % self.sector_size());
+            self.device
+                .read(self.physical(at), &mut buf[done..done + len])?;
+            done += len;
+        }
+        Ok(())
+    }
+
+    /// Program `data` at `offset`.
+    ///
+    /// The range needs not be aligned, the partially programmed units being
+    /// padded with erased bytes.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if the range exceeds the capacity,
+    /// `Status::Busy` if a partially programmed unit is not erased, or
+    /// propagates the device errors.
+    pub fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        self.check_range(offset, data.len())?;
+        let unit = self.write_size();
+        let mut done = 0;
+        while done < data.len() {
+            let at = offset + done;
+            let head = at % unit;
+            let sector_left = self.sector_size() - at % self.sector_size();
+            let len = (data.len() - done).min(sector_left);
+            let physical = self.physical(at);
+            if head == 0 && len >= unit {
+                // whole units, up to the end of the sector
+                let whole = len - len % unit;
+                self.device.program(physical, &data[done..done + whole])?;
+                done += whole;
+                continue;
+            }
+
+            let part = len.min(unit - head);
+            let mut raw = [0; MAX_WRITE_SIZE];
+            let raw = &mut raw[..unit];
+            self.device.read(physical - head, raw)?;
+            if raw.iter().any(|&byte| byte != ERASED) {
+                return Err(error(Status::Busy));
+            }
+            raw[head..head + part].copy_from_slice(&data[done..done + part]);
+            self.device.program(physical - head, raw)?;
+            done += part;
+        }
+        Ok(())
+    }
+
+    /// Erase the sectors holding the `from..to` range.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if the range is not sector aligned or
+    /// exceeds the capacity, or propagates the device errors.
+    pub fn erase(&mut self, from: usize, to: usize) -> Result<(), Error> {
+        let size = self.sector_size();
+        if from > to || !from.is_multiple_of(size) || !to.is_multiple_of(size) {
+            return Err(error(Status::Invalid));
+        }
+        self.check_range(from, to - from)?;
+        for offset in (from..to).step_by(size) {
+            self.device.erase_sector(self.physical(offset) / size)?;
+        }
+        Ok(())
+    }
+}
+
+/// [`Flash`] region of sectors of `SECTOR_SIZE` bytes and program units of
+/// `WRITE_SIZE` bytes, implementing the `embedded-storage` NOR flash traits
+///
+/// Reads may be unaligned, `READ_SIZE` being one byte, while writes are
+/// aligned on the program unit, each unit being programmed once per erase.
+pub struct SizedFlash<D: FlashDevice, const SECTOR_SIZE: usize, const WRITE_SIZE: usize>(Flash<D>);
+
+/// `embedded-storage` error of a [`SizedFlash`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NorFlashError {
+    /// The erase range is not sector aligned
+    NotAligned,
+    /// The range exceeds the capacity
+    OutOfBounds,
+    /// The region or device error
+    Flash(Error),
+}
+
+impl From<Error> for NorFlashError {
+    fn from(err: Error) -> Self {
+        Self::Flash(err)
+    }
+}
+
+impl From<NorFlashErrorKind> for NorFlashError {
+    fn from(kind: NorFlashErrorKind) -> Self {
+        match kind {
+            NorFlashErrorKind::NotAligned => Self::NotAligned,
+            NorFlashErrorKind::OutOfBounds => Self::OutOfBounds,
+            _ => Self::Flash(error(Status::Invalid)),
+        }
+    }
+}
+
+impl nor_flash::NorFlashError for NorFlashError {
+    fn kind(&self) -> NorFlashErrorKind {
+        match self {
+            Self::NotAligned => NorFlashErrorKind::NotAligned,
+            Self::OutOfBounds => NorFlashErrorKind::OutOfBounds,
+            Self::Flash(_) => NorFlashErrorKind::Other,
+        }
+    }
+}
+
+impl<D: FlashDevice, const SECTOR_SIZE: usize, const WRITE_SIZE: usize>
+    SizedFlash<D, SECTOR_SIZE, WRITE_SIZE>
+{
+    /// Build the region of `flash`.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if the sector size of `flash` is not
+    /// `SECTOR_SIZE`, or its program unit not `WRITE_SIZE`.
+    pub fn new(flash: Flash<D>) -> Result<Self, Error> {
+        match flash.sector_size() == SECTOR_SIZE && flash.write_size() == WRITE_SIZE {
+            true => Ok(Self(flash)),
+            false => Err(error(Status::Invalid)),
+        }
+    }
+
+    /// Give the flash region back.
+    pub fn release(self) -> Flash<D> {
+        self.0
+    }
+}
+
+impl<D: FlashDevice, const SECTOR_SIZE: usize, const WRITE_SIZE: usize> nor_flash::ErrorType
+    for SizedFlash<D, SECTOR_SIZE, WRITE_SIZE>
+{
+    type Error = NorFlashError;
+}
+
+impl<D: FlashDevice, const SECTOR_SIZE: usize, const WRITE_SIZE: usize> nor_flash::ReadNorFlash
+    for SizedFlash<D, SECTOR_SIZE, WRITE_SIZE>
+{
+    const READ_SIZE: usize = 1;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), NorFlashError> {
+        nor_flash::check_read(self, offset, bytes.len())?;
+        Ok(self.0.read(offset as usize, bytes)?)
+    }
+
+    fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+}
+
+impl<D: FlashDevice, const SECTOR_SIZE: usize, const WRITE_SIZE: usize> nor_flash::NorFlash
+    for SizedFlash<D, SECTOR_SIZE, WRITE_SIZE>
+{
+    const WRITE_SIZE: usize = WRITE_SIZE;
+
+    const ERASE_SIZE: usize = SECTOR_SIZE;
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), NorFlashError> {
+        nor_flash::check_erase(self, from, to)?;
+        Ok(self.0.erase(from as usize, to as usize)?)
+    }
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), NorFlashError> {
+        nor_flash::check_write(self, offset, bytes.len())?;
+        Ok(self.0.write(offset as usize, bytes)?)
+    }
+}
+
+#[cfg(feature = "kvstore")]
+impl<D: FlashDevice> crate::kvstore::Backing for Flash<D> {
+    fn size(&self) -> usize {
+        self.capacity()
+    }
+
+    fn erase_size(&self) -> usize {
+        self.sector_size()
+    }
+
+    fn write_size(&self) -> usize {
+        Flash::write_size(self)
+    }
+
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), Error> {
+        Flash::read(self, offset, buf)
+    }
+
+    fn program(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        self.write(offset, data)
+    }
+
+    fn erase(&mut self, offset: usize, len: usize) -> Result<(), Error> {
+        Flash::erase(self, offset, offset + len)
+    }
+}
@@ -0,0 +1,550 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Child task supervision
+//!
+//! A manager task, holding the task start capability, [`add`]s its children
+//! to a [`Supervisor`] along with their [`RestartPolicy`], starts them, then
+//! loops on [`Supervisor::wait`], which restarts the exited children as their
+//! policy requires and returns each exit, along with the child exit status
+//! and crash report.
+//!
+//! The kernel does not notify task exits: a child reports its own with
+//! [`notify_exit`] before exiting, an IPC carrying its exit status. A child
+//! aborting with [`crashlog::abort`](crate::crashlog::abort) is found out
+//! from the crash report written in its crash log shared memory (see
+//! [`crashlog::bind_shm`](crate::crashlog::bind_shm)), when bound with
+//! [`Supervisor::bind_crashlog`]: the supervisor checks them every
+//! [`CRASH_POLL_MS`], and clears the reports it has read.
+//!
+//! > **NOTE**: a child which panics, or which the kernel terminates on a
+//! > fault, writes no crash report (see the note of [`crate::crashlog`]), nor
+//! > reports its exit: it is never found exited, thus never restarted.
+//!
+//! ```ignore
+//! let mut supervisor = Supervisor::new();
+//! supervisor.add(NET_TASK, RestartPolicy::Backoff { initial_ms: 100, max_ms: 10_000 })?;
+//! supervisor.bind_crashlog(NET_TASK, Shm::new(NET_CRASHLOG_SHM)?.map(0)?)?;
+//! supervisor.start_all()?;
+//! loop {
+//!     let exit = supervisor.wait()?;
+//!     println!("task {:#x} exited: {:?}", exit.label, exit.status);
+//! }
+//! ```
+//!
+//! With the `selftest` feature, a child reports the outcome of its power-on
+//! self-tests with [`notify_selftest`], which the supervisor waits for with
+//! [`Supervisor::wait_selftest`], e.g. to hold back the tasks depending on a
+//! failed child.
+//!
+//! [`add`]: Supervisor::add
+
+use uapi::systypes::{EventType, ExchangeHeader, Status, TaskHandle, TaskLabel};
+
+use crate::crashlog::CrashReport;
+use crate::error::{Error, Subsystem};
+use crate::shm::{Mapped, Shm};
+
+/// Maximum number of supervised children
+pub const MAX_CHILDREN: usize = 8;
+
+/// Period of the crash log checks, in milliseconds
+pub const CRASH_POLL_MS: u32 = 100;
+
+/// Exit notification marker ("EXIT")
+const EXIT_MAGIC: u32 = 0x4558_4954;
+
+/// Exit notification length: the marker and the exit status
+const EXIT_LEN: usize = 8;
+
+/// Self-test notification marker ("POST")
+#[cfg(feature = "selftest")]
+const SELFTEST_MAGIC: u32 = 0x504f_5354;
+
+/// Self-test notification length: the marker, and the passed and failed
+/// check counts
+#[cfg(feature = "selftest")]
+const SELFTEST_LEN: usize = 6;
+
+/// `wait_for_event()` timeout value for an infinite wait
+const WFE_WAIT_FOREVER: i32 = 0;
+
+/// Maximum length of the data of an event
+const EVENT_DATA_LEN: usize = uapi::length() - size_of::<ExchangeHeader>();
+
+/// Restart policy of a child
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// Restart the child as soon as it exits
+    Always,
+    /// Restart the child after a delay, starting at `initial_ms` and doubling
+    /// on each exit up to `max_ms`, back to `initial_ms` once the child ran
+    /// for `max_ms`
+    Backoff {
+        /// First restart delay, in milliseconds
+        initial_ms: u32,
+        /// Longest restart delay, in milliseconds
+        max_ms: u32,
+    },
+    /// Leave the child stopped
+    Never,
+}
+
+/// How a child exited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// The child reported its exit, with this status
+    Exited(i32),
+    /// The child crashed, as the report written in its crash log tells
+    Crashed,
+}
+
+/// Child exit
+#[derive(Clone, Copy)]
+pub struct Exit {
+    /// Child task label
+    pub label: TaskLabel,
+    /// Exit status
+    pub status: ExitStatus,
+    /// Crash report found in the crash log of the child, if any
+    pub crash: Option<CrashReport>,
+    /// Delay before the child restart, in milliseconds, `None` if not
+    /// restarted
+    pub restart_in_ms: Option<u32>,
+}
+
+struct Child {
+    label: TaskLabel,
+    handle: TaskHandle,
+    policy: RestartPolicy,
+    crashlog: Option<Shm<Mapped>>,
+    last_exit: Option<Exit>,
+    restarts: u32,
+    /// Next restart delay, for the backoff policy
+    backoff_ms: u32,
+    started_ms: u64,
+    restart_at_ms: Option<u64>,
+    /// Self-test summary reported since the child started
+    #[cfg(feature = "selftest")]
+    selftest: Option<crate::selftest::Summary>,
+    /// Exit received while waiting for a self-test summary, not yet returned
+    /// by [`Supervisor::wait`]
+    #[cfg(feature = "selftest")]
+    unreported: bool,
+}
+
+/// Notification from a child
+enum Notification {
+    Exit(Exit),
+    /// Self-test summary, recorded in the child
+    #[cfg(feature = "selftest")]
+    Selftest,
+}
+
+impl Child {
+    /// Record the exit of the child at `now_ms`, scheduling its restart.
+    fn exited(&mut self, status: ExitStatus, crash: Option<CrashReport>, now_ms: u64) -> Exit {
+        let restart_in_ms = match self.policy {
+            RestartPolicy::Always => Some(0),
+            RestartPolicy::Backoff { initial_ms, max_ms } => {
+                if now_ms.saturating_sub(self.started_ms) >= u64::from(max_ms) {
+                    self.backoff_ms = initial_ms;
+                }
+                let delay_ms = self.backoff_ms.min(max_ms);
+                self.backoff_ms = delay_ms.saturating_mul(2).clamp(initial_ms, max_ms);
+                Some(delay_ms)
+            }
+            RestartPolicy::Never => None,
+        };
+        self.restart_at_ms = restart_in_ms.map(|delay_ms| now_ms + u64::from(delay_ms));
+        let exit = Exit {
+            label: self.label,
+            status,
+            crash,
+            restart_in_ms,
+        };
+        self.last_exit = Some(exit);
+        exit
+    }
+
+    /// Read, then clear, the crash report of the child, if any.
+    fn take_crash(&mut self) -> Option<CrashReport> {
+        let shm = self.crashlog.as_mut()?;
+        let report = CrashReport::read_from(shm).ok().flatten()?;
+        if shm.is_writable()
+            && let Ok(base) = shm.base_address()
+        {
+            // SAFETY: the shared memory is mapped, writable and larger than
+            // a report, starting with its marker
+            unsafe { core::ptr::with_exposed_provenance_mut::<u32>(base).write_volatile(0) };
+        }
+        Some(report)
+    }
+}
+
+/// Child task supervisor, see the [module](self) documentation
+pub struct Supervisor {
+    children: [Option<Child>; MAX_CHILDREN],
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Supervisor {
+    /// Create a supervisor without children.
+    pub const fn new() -> Self {
+        Self {
+            children: [const { None }; MAX_CHILDREN],
+        }
+    }
+
+    /// Supervise the task `label`, as `policy` requires.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the task is already supervised,
+    /// a `Status::Busy` error if [`MAX_CHILDREN`] tasks are, or propagates
+    /// kernel errors if its handle can't be retrieved.
+    pub fn add(&mut self, label: TaskLabel, policy: RestartPolicy) -> Result<(), Error> {
+        if self.child(label).is_ok() {
+            return Err(Error::new(Subsystem::Supervisor, Status::Invalid).with_handle(label));
+        }
+        let Some(slot) = self.children.iter_mut().find(|slot| slot.is_none()) else {
+            return Err(Error::new(Subsystem::Supervisor, Status::Busy));
+        };
+        let handle = crate::process::get_process_handle(label)?;
+        let backoff_ms = match policy {
+            RestartPolicy::Backoff { initial_ms, .. } => initial_ms,
+            _ => 0,
+        };
+        *slot = Some(Child {
+            label,
+            handle,
+            policy,
+            crashlog: None,
+            last_exit: None,
+            restarts: 0,
+            backoff_ms,
+            started_ms: 0,
+            restart_at_ms: None,
+            #[cfg(feature = "selftest")]
+            selftest: None,
+            #[cfg(feature = "selftest")]
+            unreported: false,
+        });
+        Ok(())
+    }
+
+    /// Check the crash reports written by the child `label` in `shm`, which
+    /// should be writable for the reports to be cleared once read.
+    ///
+    /// # Errors
+    /// Returns a `Status::NoEntity` error if the task is not supervised.
+    pub fn bind_crashlog(&mut self, label: TaskLabel, shm: Shm<Mapped>) -> Result<(), Error> {
+        self.child(label)?.crashlog = Some(shm);
+        Ok(())
+    }
+
+    /// Start the child `label`, a child already running being left as is.
+    ///
+    /// # Errors
+    /// Returns a `Status::NoEntity` error if the task is not supervised, or
+    /// propagates kernel errors.
+    pub fn start(&mut self, label: TaskLabel) -> Result<(), Error> {
+        let now_ms = crate::time::uptime_ms()?;
+        let child = self.child(label)?;
+        let status = crate::sys::syscall::start(label);
+        match status {
+            // already started
+            Status::Ok | Status::Invalid => {}
+            status => {
+                return Err(Error::new(Subsystem::Supervisor, status).with_handle(label));
+            }
+        }
+        // a restarted child runs its self-tests again
+        #[cfg(feature = "selftest")]
+        if status == Status::Ok {
+            child.selftest = None;
+        }
+        child.started_ms = now_ms;
+        child.restart_at_ms = None;
+        // the handle changes on each restart
+        if let Ok(handle) = crate::process::get_process_handle(label) {
+            child.handle = handle;
+        }
+        Ok(())
+    }
+
+    /// Start all the children.
+    ///
+    /// # Errors
+    /// See [`Supervisor::start`].
+    pub fn start_all(&mut self) -> Result<(), Error> {
+        let labels = self
+            .children
+            .each_ref()
+            .map(|child| child.as_ref().map(|c| c.label));
+        labels
+            .into_iter()
+            .flatten()
+            .try_for_each(|label| self.start(label))
+    }
+
+    /// Return the last exit of the child `label`, if any.
+    pub fn last_exit(&self, label: TaskLabel) -> Option<&Exit> {
+        self.find(label)?.last_exit.as_ref()
+    }
+
+    /// Return the number of restarts of the child `label`.
+    pub fn restarts(&self, label: TaskLabel) -> Option<u32> {
+        self.find(label).map(|child| child.restarts)
+    }
+
+    /// Wait for a child to exit, restarting the exited children when their
+    /// policy requires meanwhile.
+    ///
+    /// IPC from other tasks are dropped.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if a child can't be restarted or the task
+    /// can't wait for events.
+    pub fn wait(&mut self) -> Result<Exit, Error> {
+        loop {
+            let now_ms = crate::time::uptime_ms()?;
+            self.restart_due(now_ms)?;
+            if let Some(exit) = self.check_crashlogs(now_ms) {
+                return Ok(exit);
+            }
+            #[cfg(feature = "selftest")]
+            if let Some(child) = self
+                .children
+                .iter_mut()
+                .flatten()
+                .find(|child| child.unreported)
+                && let Some(exit) = child.last_exit
+            {
+                child.unreported = false;
+                return Ok(exit);
+            }
+
+            let polling = self
+                .children
+                .iter()
+                .flatten()
+                .any(|child| child.crashlog.is_some());
+            let timeout_ms = self
+                .children
+                .iter()
+                .flatten()
+                .filter_map(|child| child.restart_at_ms)
+                .map(|at_ms| u32::try_from(at_ms - now_ms).unwrap_or(u32::MAX).max(1))
+                .chain(polling.then_some(CRASH_POLL_MS))
+                .min();
+            let timeout =
+                timeout_ms.map_or(WFE_WAIT_FOREVER, |ms| i32::try_from(ms).unwrap_or(i32::MAX));
+            match crate::sys::syscall::wait_for_event(EventType::Ipc.into(), timeout) {
+                Status::Ok => {
+                    if let Some(Notification::Exit(exit)) = self.receive() {
+                        return Ok(exit);
+                    }
+                }
+                Status::Timeout => {}
+                status => return Err(Error::new(Subsystem::Supervisor, status)),
+            }
+        }
+    }
+
+    /// Return the self-test summary reported by the child `label` since it
+    /// started, if any.
+    #[cfg(feature = "selftest")]
+    pub fn selftest(&self, label: TaskLabel) -> Option<crate::selftest::Summary> {
+        self.find(label)?.selftest
+    }
+
+    /// Wait for the child `label` to report its self-test summary since it
+    /// started, for at most `timeout_ms` milliseconds.
+    ///
+    /// The exits of the children received meanwhile are returned by the next
+    /// calls of [`Supervisor::wait`], the children not being restarted until
+    /// then.
+    ///
+    /// # Errors
+    /// Returns a `Status::NoEntity` error if the task is not supervised, a
+    /// `Status::Intr` error if it exited without reporting, a
+    /// `Status::Timeout` error if it hasn't reported in time, or propagates
+    /// kernel errors if the task can't wait for events.
+    #[cfg(feature = "selftest")]
+    pub fn wait_selftest(
+        &mut self,
+        label: TaskLabel,
+        timeout_ms: u32,
+    ) -> Result<crate::selftest::Summary, Error> {
+        let error = |status| Error::new(Subsystem::Supervisor, status).with_handle(label);
+        let deadline_ms = crate::time::uptime_ms()? + u64::from(timeout_ms);
+        loop {
+            let child = self.child(label)?;
+            if let Some(summary) = child.selftest {
+                return Ok(summary);
+            }
+            if child.unreported {
+                return Err(error(Status::Intr));
+            }
+            let remaining_ms = deadline_ms.saturating_sub(crate::time::uptime_ms()?);
+            if remaining_ms == 0 {
+                return Err(error(Status::Timeout));
+            }
+            let timeout = i32::try_from(remaining_ms).unwrap_or(i32::MAX);
+            match crate::sys::syscall::wait_for_event(EventType::Ipc.into(), timeout) {
+                Status::Ok => {
+                    if let Some(Notification::Exit(exit)) = self.receive() {
+                        self.child(exit.label)?.unreported = true;
+                    }
+                }
+                Status::Timeout => {}
+                status => return Err(Error::new(Subsystem::Supervisor, status)),
+            }
+        }
+    }
+
+    /// Handle the notification delivered by the last `wait_for_event()`, if
+    /// sent by a child.
+    fn receive(&mut self) -> Option<Notification> {
+        let mut data = [0; EVENT_DATA_LEN];
+        let mut event = uapi::systypes::Event {
+            header: ExchangeHeader {
+                event: 0,
+                length: 0,
+                magic: 0,
+                peer: 0,
+            },
+            data: &mut data,
+        };
+        crate::sys::copy_from_kernel(&mut event).ok()?;
+        let (peer, len) = (event.header.peer, usize::from(event.header.length));
+        let message = data.get(..len)?;
+        let word = |index: usize| {
+            let mut word = [0; 4];
+            word.copy_from_slice(message.get(index * 4..index * 4 + 4)?);
+            Some(word)
+        };
+        let magic = u32::from_le_bytes(word(0)?);
+        let child = self
+            .children
+            .iter_mut()
+            .flatten()
+            .find(|child| child.handle == peer)?;
+        #[cfg(feature = "selftest")]
+        if magic == SELFTEST_MAGIC && len == SELFTEST_LEN {
+            child.selftest = Some(crate::selftest::Summary {
+                passed: message[4],
+                failed: message[5],
+            });
+            return Some(Notification::Selftest);
+        }
+        if magic != EXIT_MAGIC || len != EXIT_LEN {
+            return None;
+        }
+        let status = i32::from_le_bytes(word(1)?);
+        let now_ms = crate::time::uptime_ms().unwrap_or_default();
+        let crash = child.take_crash();
+        Some(Notification::Exit(child.exited(
+            ExitStatus::Exited(status),
+            crash,
+            now_ms,
+        )))
+    }
+
+    /// Return the exit of a child found crashed from its crash log, if any.
+    fn check_crashlogs(&mut self, now_ms: u64) -> Option<Exit> {
+        self.children
+            .iter_mut()
+            .flatten()
+            // a crashed child being restarted has already been reported
+            .filter(|child| child.restart_at_ms.is_none())
+            .find_map(|child| {
+                let crash = child.take_crash()?;
+                Some(child.exited(ExitStatus::Crashed, Some(crash), now_ms))
+            })
+    }
+
+    fn restart_due(&mut self, now_ms: u64) -> Result<(), Error> {
+        let due = self.children.each_ref().map(|child| {
+            child
+                .as_ref()
+                .filter(|child| child.restart_at_ms.is_some_and(|at_ms| at_ms <= now_ms))
+                .map(|child| child.label)
+        });
+        for label in due.into_iter().flatten() {
+            self.start(label)?;
+            self.child(label)?.restarts += 1;
+        }
+        Ok(())
+    }
+
+    fn find(&self, label: TaskLabel) -> Option<&Child> {
+        self.children
+            .iter()
+            .flatten()
+            .find(|child| child.label == label)
+    }
+
+    fn child(&mut self, label: TaskLabel) -> Result<&mut Child, Error> {
+        self.children
+            .iter_mut()
+            .flatten()
+            .find(|child| child.label == label)
+            .ok_or(Error::new(Subsystem::Supervisor, Status::NoEntity).with_handle(label))
+    }
+}
+
+/// Notify the supervisor task `supervisor` of the exit of the current task,
+/// with `status`, before exiting.
+///
+/// The notification is read once the supervisor waits for exits.
+///
+/// # Errors
+/// Propagates kernel errors if the supervisor can't be found or notified.
+pub fn notify_exit(supervisor: TaskLabel, status: i32) -> Result<(), Error> {
+    let handle = crate::process::get_process_handle(supervisor)?;
+    let mut message = [0; EXIT_LEN];
+    message[..4].copy_from_slice(&EXIT_MAGIC.to_le_bytes());
+    message[4..].copy_from_slice(&status.to_le_bytes());
+    notify(handle, &message)
+}
+
+/// Notify the supervisor task `supervisor` of the self-test `summary` of
+/// the current task.
+///
+/// The notification is read once the supervisor waits for exits or self-test
+/// summaries.
+///
+/// # Errors
+/// Propagates kernel errors if the supervisor can't be found or notified.
+#[cfg(feature = "selftest")]
+pub fn notify_selftest(
+    supervisor: TaskLabel,
+    summary: crate::selftest::Summary,
+) -> Result<(), Error> {
+    let handle = crate::process::get_process_handle(supervisor)?;
+    let mut message = [0; SELFTEST_LEN];
+    message[..4].copy_from_slice(&SELFTEST_MAGIC.to_le_bytes());
+    message[4] = summary.passed;
+    message[5] = summary.failed;
+    notify(handle, &message)
+}
+
+/// Send the notification `message` to the supervisor `handle`.
+fn notify(handle: TaskHandle, message: &[u8]) -> Result<(), Error> {
+    let status = match crate::sys::copy_to_kernel(&message) {
+        // the notification length is below the exchange area length
+        Ok(Status::Ok) => crate::sys::syscall::send_ipc(handle, message.len() as u8),
+        Ok(status) | Err(status) => status,
+    };
+    match status {
+        Status::Ok => Ok(()),
+        status => Err(Error::new(Subsystem::Supervisor, status).with_handle(handle)),
+    }
+}
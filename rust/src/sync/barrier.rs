@@ -0,0 +1,170 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::error::{Error, Subsystem};
+use uapi::systypes::Status;
+
+use super::wait_queue::WaitQueue;
+
+/// Maximum number of tasks of a [`Barrier`]
+pub const MAX_BARRIER_TASKS: u32 = 0xffff;
+
+/// Shift of the generation counter in the barrier state word
+const GENERATION_SHIFT: u32 = 16;
+
+/// Rendezvous point of a fixed group of tasks sharing a memory.
+///
+/// Each task of the group calls [`Barrier::wait`], blocking until all the
+/// tasks of the group have done so, e.g. to start the main loops once all the
+/// drivers are initialized. The barrier is then reset, and can be used for the
+/// next rendezvous.
+///
+/// The label identifies the barrier in the errors returned by timed waits.
+pub struct Barrier {
+    n_tasks: u32,
+    label: u32,
+    /// Generation counter in the high half-word, arrived tasks in the low one
+    state: AtomicU32,
+    queue: WaitQueue,
+}
+
+/// Result of a [`Barrier`] wait
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarrierWaitResult(bool);
+
+impl BarrierWaitResult {
+    /// Return whether the current task is the last one to have reached the
+    /// barrier.
+    ///
+    /// A single task of the group is the leader of a given rendezvous.
+    pub fn is_leader(self) -> bool {
+        self.0
+    }
+}
+
+impl Barrier {
+    /// Create a new barrier for a group of `n_tasks` tasks, identified by
+    /// `label`.
+    ///
+    /// A barrier for no task behaves as a barrier for a single task, and the
+    /// group size is capped at [`MAX_BARRIER_TASKS`].
+    pub const fn new(n_tasks: u32, label: u32) -> Self {
+        let n_tasks = match n_tasks {
+            0 => 1,
+            n if n > MAX_BARRIER_TASKS => MAX_BARRIER_TASKS,
+            n => n,
+        };
+        Self {
+            n_tasks,
+            label,
+            state: AtomicU32::new(0),
+            queue: WaitQueue::new(),
+        }
+    }
+
+    /// Return the number of tasks of the group.
+    pub fn n_tasks(&self) -> u32 {
+        self.n_tasks
+    }
+
+    /// Return the barrier label.
+    pub fn label(&self) -> u32 {
+        self.label
+    }
+
+    /// Return the number of tasks currently blocked on the barrier.
+    pub fn waiting(&self) -> u32 {
+        self.state.load(Ordering::Acquire) & MAX_BARRIER_TASKS
+    }
+
+    /// Block until all the tasks of the group have reached the barrier.
+    pub fn wait(&self) -> BarrierWaitResult {
+        match self.arrive() {
+            None => BarrierWaitResult(true),
+            Some(generation) => {
+                self.queue.wait_while(|| self.generation() == generation);
+                BarrierWaitResult(false)
+            }
+        }
+    }
+
+    /// Same as [`Barrier::wait`], for at most `timeout_ms` milliseconds.
+    ///
+    /// On timeout, the current task is withdrawn from the rendezvous, so that
+    /// it can wait again later.
+    ///
+    /// # Errors
+    /// Returns a `Status::Timeout` error, with the barrier label as handle, if
+    /// the other tasks have not all reached the barrier in time.
+    pub fn wait_timeout(&self, timeout_ms: u32) -> Result<BarrierWaitResult, Error> {
+        let Some(generation) = self.arrive() else {
+            return Ok(BarrierWaitResult(true));
+        };
+        let result = self
+            .queue
+            .wait_while_timeout(|| self.generation() == generation, timeout_ms);
+        if result.timed_out() && self.withdraw(generation) {
+            return Err(Error::new(Subsystem::Sync, Status::Timeout).with_handle(self.label));
+        }
+        Ok(BarrierWaitResult(false))
+    }
+
+    fn generation(&self) -> u32 {
+        self.state.load(Ordering::Acquire) >> GENERATION_SHIFT
+    }
+
+    /// Register the arrival of the current task.
+    ///
+    /// Returns the generation to wait the end of, or `None` if the current
+    /// task is the last one, the waiting ones having been released.
+    fn arrive(&self) -> Option<u32> {
+        let mut current = self.state.load(Ordering::Acquire);
+        loop {
+            let generation = current >> GENERATION_SHIFT;
+            let arrived = (current & MAX_BARRIER_TASKS) + 1;
+            let last = arrived == self.n_tasks;
+            let next = if last {
+                generation.wrapping_add(1) << GENERATION_SHIFT
+            } else {
+                (generation << GENERATION_SHIFT) | arrived
+            };
+            match self.state.compare_exchange_weak(
+                current,
+                next,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) if last => {
+                    self.queue.notify_all();
+                    return None;
+                }
+                Ok(_) => return Some(generation),
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Withdraw the arrival of the current task in `generation`.
+    ///
+    /// Returns `false` if the rendezvous has completed in the meantime.
+    fn withdraw(&self, generation: u32) -> bool {
+        let mut current = self.state.load(Ordering::Acquire);
+        loop {
+            if current >> GENERATION_SHIFT != generation {
+                return false;
+            }
+            match self.state.compare_exchange_weak(
+                current,
+                current - 1,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return true,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+}
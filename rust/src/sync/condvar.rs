@@ -0,0 +1,83 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+use super::mutex::MutexGuard;
+use super::wait_queue::{Deadline, WaitQueue, WaitTimeoutResult};
+
+/// Condition variable, paired with a [`super::Mutex`].
+///
+/// Waiting tasks are parked in the kernel through a [`WaitQueue`], so that
+/// producer/consumer patterns don't need to poll.
+///
+/// As for any condition variable, spurious wake-ups may happen: the awaited
+/// condition must always be checked again, which [`Condvar::wait_while`] does.
+pub struct Condvar {
+    queue: WaitQueue,
+}
+
+impl Condvar {
+    /// Create a new condition variable with an empty wait queue.
+    pub const fn new() -> Self {
+        Self {
+            queue: WaitQueue::new(),
+        }
+    }
+
+    /// Release the guard lock and block until notified, then lock again.
+    pub fn wait<'a, T: ?Sized>(&self, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+        let mutex = guard.mutex();
+        let waiter = self.queue.waiter();
+        drop(guard);
+        waiter.block_until(None);
+        drop(waiter);
+        mutex.lock()
+    }
+
+    /// Block until notified and `condition` returns `false`.
+    pub fn wait_while<'a, T: ?Sized, F>(
+        &self,
+        mut guard: MutexGuard<'a, T>,
+        mut condition: F,
+    ) -> MutexGuard<'a, T>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        while condition(&mut *guard) {
+            guard = self.wait(guard);
+        }
+        guard
+    }
+
+    /// Same as [`Condvar::wait`], returning after at most `timeout_ms`
+    /// milliseconds if not notified.
+    pub fn wait_timeout<'a, T: ?Sized>(
+        &self,
+        guard: MutexGuard<'a, T>,
+        timeout_ms: u32,
+    ) -> (MutexGuard<'a, T>, WaitTimeoutResult) {
+        let mutex = guard.mutex();
+        let mut deadline = Deadline::after(timeout_ms);
+        let waiter = self.queue.waiter();
+        drop(guard);
+        let notified = waiter.block_until(Some(&mut deadline));
+        drop(waiter);
+        (mutex.lock(), WaitTimeoutResult(!notified))
+    }
+
+    /// Wake up one waiting task.
+    pub fn notify_one(&self) {
+        self.queue.notify_one();
+    }
+
+    /// Wake up all waiting tasks.
+    pub fn notify_all(&self) {
+        self.queue.notify_all();
+    }
+}
+
+impl Default for Condvar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,137 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::error::{Error, Subsystem};
+use uapi::systypes::Status;
+
+use super::wait_queue::WaitQueue;
+
+/// Group of 32 event flags, as provided by most RTOS event groups.
+///
+/// Flags are set from event handlers (typically IRQ event callbacks) or from
+/// peer tasks, and waited on from the main loop. Setting or clearing flags
+/// never blocks.
+///
+/// Waiting calls consume the flags they return, so that a given event is
+/// handled once.
+pub struct EventFlags {
+    flags: AtomicU32,
+    queue: WaitQueue,
+}
+
+impl EventFlags {
+    /// Create a new event flags group with all flags cleared.
+    pub const fn new() -> Self {
+        Self {
+            flags: AtomicU32::new(0),
+            queue: WaitQueue::new(),
+        }
+    }
+
+    /// Set the flags of `mask`, waking up waiters. Returns the previous flags.
+    pub fn set(&self, mask: u32) -> u32 {
+        let previous = self.flags.fetch_or(mask, Ordering::AcqRel);
+        self.queue.notify_all();
+        previous
+    }
+
+    /// Clear the flags of `mask`. Returns the previous flags.
+    pub fn clear(&self, mask: u32) -> u32 {
+        self.flags.fetch_and(!mask, Ordering::AcqRel)
+    }
+
+    /// Return the current flags, without consuming them.
+    pub fn get(&self) -> u32 {
+        self.flags.load(Ordering::Acquire)
+    }
+
+    /// Consume any flag of `mask` that is set, without blocking.
+    ///
+    /// Returns `None` if none of them is set.
+    pub fn try_wait_any(&self, mask: u32) -> Option<u32> {
+        self.take(mask, false)
+    }
+
+    /// Consume all flags of `mask` if they are all set, without blocking.
+    ///
+    /// Returns `None` if at least one of them is not set.
+    pub fn try_wait_all(&self, mask: u32) -> Option<u32> {
+        self.take(mask, true)
+    }
+
+    /// Block until any flag of `mask` is set, and consume the set ones.
+    pub fn wait_any(&self, mask: u32) -> u32 {
+        self.wait(mask, false)
+    }
+
+    /// Block until all flags of `mask` are set, and consume them.
+    pub fn wait_all(&self, mask: u32) -> u32 {
+        self.wait(mask, true)
+    }
+
+    /// Same as [`EventFlags::wait_any`], for at most `timeout_ms` milliseconds.
+    ///
+    /// # Errors
+    /// Returns a `Status::Timeout` error if none of the flags have been set in time.
+    pub fn wait_any_timeout(&self, mask: u32, timeout_ms: u32) -> Result<u32, Error> {
+        self.wait_timeout(mask, false, timeout_ms)
+    }
+
+    /// Same as [`EventFlags::wait_all`], for at most `timeout_ms` milliseconds.
+    ///
+    /// # Errors
+    /// Returns a `Status::Timeout` error if the flags have not all been set in time.
+    pub fn wait_all_timeout(&self, mask: u32, timeout_ms: u32) -> Result<u32, Error> {
+        self.wait_timeout(mask, true, timeout_ms)
+    }
+
+    fn wait(&self, mask: u32, all: bool) -> u32 {
+        let mut taken = None;
+        self.queue.wait_while(|| {
+            taken = self.take(mask, all);
+            taken.is_none()
+        });
+        taken.unwrap_or_default()
+    }
+
+    fn wait_timeout(&self, mask: u32, all: bool, timeout_ms: u32) -> Result<u32, Error> {
+        let mut taken = None;
+        let _ = self.queue.wait_while_timeout(
+            || {
+                taken = self.take(mask, all);
+                taken.is_none()
+            },
+            timeout_ms,
+        );
+        taken.ok_or(Error::new(Subsystem::Sync, Status::Timeout))
+    }
+
+    /// Atomically consume the flags of `mask`, if the wait condition is met.
+    fn take(&self, mask: u32, all: bool) -> Option<u32> {
+        let mut current = self.flags.load(Ordering::Acquire);
+        loop {
+            let matched = current & mask;
+            if matched == 0 || (all && matched != mask) {
+                return None;
+            }
+            match self.flags.compare_exchange_weak(
+                current,
+                current & !matched,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return Some(matched),
+                Err(actual) => current = actual,
+            }
+        }
+    }
+}
+
+impl Default for EventFlags {
+    fn default() -> Self {
+        Self::new()
+    }
+}
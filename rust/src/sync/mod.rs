@@ -0,0 +1,49 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Synchronization primitives for Shield tasks
+//!
+//! A Sentry task is single-threaded: concurrent accesses to a given object only
+//! happen when this object lives in a shared memory mapped by several tasks.
+//! The primitives of this module are designed to be placed in such a shared
+//! memory, blocking callers through the kernel (see [`wake`]) instead of
+//! polling with `sleep`.
+//!
+//! [`Once`] and [`LazyLock`] are the exception: they are task-private
+//! one-time initialization helpers, usable in `static` items.
+//!
+//! The primitives shared between tasks require native compare-and-swap
+//! support, and are not provided on targets lacking it, such as ARMv6-M: a
+//! compare-and-swap emulation masking the interrupts would only be atomic in
+//! the task flow, the kernel still running a peer task sharing the memory in
+//! the middle of an operation.
+
+#[cfg(target_has_atomic = "32")]
+mod barrier;
+#[cfg(target_has_atomic = "32")]
+mod condvar;
+#[cfg(target_has_atomic = "32")]
+mod event_flags;
+#[cfg(target_has_atomic = "32")]
+mod mutex;
+mod once;
+#[cfg(target_has_atomic = "32")]
+mod wait_queue;
+pub mod wake;
+#[cfg(target_has_atomic = "32")]
+mod watch;
+
+#[cfg(target_has_atomic = "32")]
+pub use barrier::{Barrier, BarrierWaitResult, MAX_BARRIER_TASKS};
+#[cfg(target_has_atomic = "32")]
+pub use condvar::Condvar;
+#[cfg(target_has_atomic = "32")]
+pub use event_flags::EventFlags;
+#[cfg(target_has_atomic = "32")]
+pub use mutex::{Mutex, MutexGuard};
+pub use once::{LazyLock, Once};
+#[cfg(target_has_atomic = "32")]
+pub use wait_queue::{MAX_WAITERS, WaitQueue, WaitTimeoutResult};
+#[cfg(target_has_atomic = "32")]
+pub use watch::{Watch, WatchReceiver};
@@ -0,0 +1,136 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+use core::cell::{Cell, UnsafeCell};
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+const INCOMPLETE: u8 = 0;
+const RUNNING: u8 = 1;
+const COMPLETE: u8 = 2;
+
+/// One-time initialization primitive.
+///
+/// A Sentry task being single-threaded, a task-private `Once` can't be raced:
+/// only atomic loads and stores are used, making it sound on targets without
+/// compare-and-swap support such as Cortex-M0.
+///
+/// `Once` is **not** meant to be shared between tasks through a shared memory.
+pub struct Once {
+    state: AtomicU8,
+}
+
+impl Once {
+    /// Create a new, not yet completed, `Once` instance.
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(INCOMPLETE),
+        }
+    }
+
+    /// Execute `f` if this is the first call on this instance.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if called from `f` itself, or if a previous call of `f`
+    /// has panicked.
+    pub fn call_once<F: FnOnce()>(&self, f: F) {
+        match self.state.load(Ordering::Acquire) {
+            COMPLETE => return,
+            RUNNING => panic!("Once instance recursively initialized or poisoned"),
+            _ => {}
+        }
+
+        self.state.store(RUNNING, Ordering::Relaxed);
+        f();
+        self.state.store(COMPLETE, Ordering::Release);
+    }
+
+    /// Return whether the initialization has been completed.
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+}
+
+impl Default for Once {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Value lazily initialized on first access.
+///
+/// Replaces the `static mut` and flag pairs used for expensive one-time
+/// initialization (clock calibration, crypto contexts...).
+///
+/// # Example
+/// ```ignore
+/// static CALIBRATION: LazyLock<u32> = LazyLock::new(|| calibrate_clock());
+///
+/// let ticks = *CALIBRATION;
+/// ```
+pub struct LazyLock<T, F = fn() -> T> {
+    once: Once,
+    init: Cell<Option<F>>,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// SAFETY: a Sentry task is single-threaded, initialization can't be raced
+unsafe impl<T: Sync + Send, F: Send> Sync for LazyLock<T, F> {}
+
+impl<T, F: FnOnce() -> T> LazyLock<T, F> {
+    /// Create a new lazy value with the given initialization function.
+    pub const fn new(f: F) -> Self {
+        Self {
+            once: Once::new(),
+            init: Cell::new(Some(f)),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Force the evaluation of the lazy value, returning a reference to it.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the initialization function has previously panicked.
+    pub fn force(this: &Self) -> &T {
+        this.once.call_once(|| {
+            let Some(f) = this.init.take() else {
+                panic!("LazyLock instance poisoned");
+            };
+            // SAFETY: written once, before any read
+            unsafe { (*this.value.get()).write(f()) };
+        });
+        // SAFETY: initialized by the call above
+        unsafe { (*this.value.get()).assume_init_ref() }
+    }
+
+    /// Return the value if already initialized, without forcing it.
+    pub fn get(this: &Self) -> Option<&T> {
+        if this.once.is_completed() {
+            // SAFETY: completion means the value has been written
+            Some(unsafe { (*this.value.get()).assume_init_ref() })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T, F: FnOnce() -> T> Deref for LazyLock<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        Self::force(self)
+    }
+}
+
+impl<T, F> Drop for LazyLock<T, F> {
+    fn drop(&mut self) {
+        if self.once.is_completed() {
+            // SAFETY: completion means the value has been written
+            unsafe { self.value.get_mut().assume_init_drop() };
+        }
+    }
+}
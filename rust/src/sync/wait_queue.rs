@@ -0,0 +1,221 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use uapi::systypes::TaskHandle;
+
+use super::wake;
+use crate::{process, time};
+
+/// Maximum number of tasks that can be parked on a given wait queue
+pub const MAX_WAITERS: usize = 8;
+
+/// Empty waiter slot marker
+const NO_WAITER: TaskHandle = 0;
+
+/// Result of a wait with timeout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaitTimeoutResult(pub(crate) bool);
+
+impl WaitTimeoutResult {
+    /// Return whether the wait has ended because of the timeout.
+    pub fn timed_out(self) -> bool {
+        self.0
+    }
+}
+
+/// Queue of tasks waiting for a shared object to change.
+///
+/// This is the building block of the blocking primitives of this module:
+/// waiting tasks register their handle in the queue and park in the kernel,
+/// while notifying tasks wake them up through the [`wake`] mechanism.
+///
+/// The current task handle must have been registered using
+/// [`process::register_current`] for the waiter to be parked. Otherwise, or
+/// if the queue is full, the waiter falls back to yielding the CPU until
+/// notified.
+pub struct WaitQueue {
+    seq: AtomicU32,
+    waiters: [AtomicU32; MAX_WAITERS],
+}
+
+impl WaitQueue {
+    /// Create a new, empty, wait queue.
+    pub const fn new() -> Self {
+        Self {
+            seq: AtomicU32::new(0),
+            waiters: [const { AtomicU32::new(NO_WAITER) }; MAX_WAITERS],
+        }
+    }
+
+    /// Block while `condition` returns `true`.
+    ///
+    /// `condition` is evaluated again each time the queue is notified.
+    pub fn wait_while<F: FnMut() -> bool>(&self, mut condition: F) {
+        loop {
+            let waiter = self.waiter();
+            if !condition() {
+                return;
+            }
+            waiter.block_until(None);
+        }
+    }
+
+    /// Block while `condition` returns `true`, for at most `timeout_ms`
+    /// milliseconds.
+    pub fn wait_while_timeout<F: FnMut() -> bool>(
+        &self,
+        mut condition: F,
+        timeout_ms: u32,
+    ) -> WaitTimeoutResult {
+        let mut deadline = Deadline::after(timeout_ms);
+        loop {
+            let waiter = self.waiter();
+            if !condition() {
+                return WaitTimeoutResult(false);
+            }
+            if !waiter.block_until(Some(&mut deadline)) {
+                return WaitTimeoutResult(condition());
+            }
+        }
+    }
+
+    /// Wake up one waiting task.
+    pub fn notify_one(&self) {
+        self.seq.fetch_add(1, Ordering::Release);
+        for waiter in &self.waiters {
+            let task = waiter.load(Ordering::Acquire);
+            if task != NO_WAITER && wake::unpark(task).is_ok() {
+                break;
+            }
+        }
+    }
+
+    /// Wake up all waiting tasks.
+    pub fn notify_all(&self) {
+        self.seq.fetch_add(1, Ordering::Release);
+        for waiter in &self.waiters {
+            let task = waiter.load(Ordering::Acquire);
+            if task != NO_WAITER {
+                let _ = wake::unpark(task);
+            }
+        }
+    }
+
+    /// Register the current task in the queue.
+    ///
+    /// Any notification emitted after this call is seen by the returned
+    /// waiter, so that the awaited condition can be checked afterward
+    /// without losing wake-ups.
+    pub(crate) fn waiter(&self) -> Waiter<'_> {
+        let seq = self.seq.load(Ordering::Acquire);
+        let slot = process::current_handle().ok().and_then(|task| {
+            self.waiters.iter().position(|waiter| {
+                waiter
+                    .compare_exchange(NO_WAITER, task, Ordering::AcqRel, Ordering::Relaxed)
+                    .is_ok()
+            })
+        });
+        Waiter {
+            queue: self,
+            seq,
+            slot,
+        }
+    }
+}
+
+impl Default for WaitQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Registration of the current task in a [`WaitQueue`], removed on drop.
+pub(crate) struct Waiter<'a> {
+    queue: &'a WaitQueue,
+    seq: u32,
+    slot: Option<usize>,
+}
+
+impl Waiter<'_> {
+    /// Return whether the queue has been notified since registration.
+    pub(crate) fn notified(&self) -> bool {
+        self.queue.seq.load(Ordering::Acquire) != self.seq
+    }
+
+    /// Block until notified or until the deadline is reached.
+    ///
+    /// Returns `false` if the deadline has been reached first.
+    pub(crate) fn block_until(&self, mut deadline: Option<&mut Deadline>) -> bool {
+        while !self.notified() {
+            let timeout_ms = match deadline.as_deref_mut() {
+                Some(deadline) => match deadline.remaining() {
+                    Some(remaining) => Some(remaining),
+                    None => return false,
+                },
+                None => None,
+            };
+            match (self.slot, timeout_ms) {
+                (Some(_), None) => {
+                    let _ = wake::park();
+                }
+                (Some(_), Some(timeout_ms)) => {
+                    let _ = wake::park_timeout(timeout_ms);
+                }
+                (None, _) => {
+                    crate::sys::syscall::sched_yield();
+                }
+            }
+        }
+        true
+    }
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        if let Some(waiter) = self.slot.and_then(|slot| self.queue.waiters.get(slot)) {
+            waiter.store(NO_WAITER, Ordering::Release);
+        }
+    }
+}
+
+/// Wait deadline, based on the kernel uptime clock.
+///
+/// When the clock is not available, a single wait of the initial timeout is
+/// made.
+pub(crate) struct Deadline {
+    end: Option<u64>,
+    timeout_ms: u32,
+    expired: bool,
+}
+
+impl Deadline {
+    pub(crate) fn after(timeout_ms: u32) -> Self {
+        Self {
+            end: time::uptime_ms()
+                .ok()
+                .map(|now| now + u64::from(timeout_ms)),
+            timeout_ms,
+            expired: false,
+        }
+    }
+
+    /// Return the remaining time before the deadline, if any.
+    pub(crate) fn remaining(&mut self) -> Option<u32> {
+        if self.expired {
+            return None;
+        }
+        match self.end.map(|end| (end, time::uptime_ms())) {
+            Some((end, Ok(now))) if now >= end => {
+                self.expired = true;
+                None
+            }
+            Some((end, Ok(now))) => Some(u32::try_from(end - now).unwrap_or(u32::MAX)),
+            _ => {
+                self.expired = true;
+                Some(self.timeout_ms)
+            }
+        }
+    }
+}
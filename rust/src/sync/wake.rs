@@ -0,0 +1,72 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Cross-task wake-up mechanism
+//!
+//! A task waiting for a shared object to change parks itself in the kernel
+//! through `wait_for_event()`, waiting for signal events only. The task that
+//! modifies the object then unparks the waiters by sending them the
+//! [`WAKE_SIGNAL`] signal.
+//!
+//! Any other signal received by the parked task also wakes it up, meaning that
+//! callers must always check the condition they are waiting for again when
+//! returning from [`park`] or [`park_timeout`].
+
+use crate::error::{Error, Subsystem};
+use uapi::systypes::{EventType, Signal, Status, TaskHandle};
+
+/// Signal emitted by Shield to wake up a parked task
+pub const WAKE_SIGNAL: Signal = Signal::Usr1;
+
+/// `wait_for_event()` timeout value for a non-blocking wait
+const WFE_WAIT_NO: i32 = -1;
+
+/// `wait_for_event()` timeout value for an infinite wait
+const WFE_WAIT_FOREVER: i32 = 0;
+
+/// Park the current task until a signal is received.
+///
+/// # Errors
+/// Propagates kernel errors if the task can't wait for signals.
+#[inline]
+pub fn park() -> Result<(), Error> {
+    match crate::sys::syscall::wait_for_event(EventType::Signal.into(), WFE_WAIT_FOREVER) {
+        Status::Ok => Ok(()),
+        status => Err(Error::new(Subsystem::Sync, status)),
+    }
+}
+
+/// Park the current task until a signal is received or `timeout_ms`
+/// milliseconds have elapsed.
+///
+/// A null timeout only checks for an already pending signal.
+///
+/// # Errors
+/// Returns `Status::Timeout` if no signal has been received in time, or
+/// propagates kernel errors if the task can't wait for signals.
+pub fn park_timeout(timeout_ms: u32) -> Result<(), Error> {
+    let timeout = match timeout_ms {
+        0 => WFE_WAIT_NO,
+        ms => i32::try_from(ms).unwrap_or(i32::MAX),
+    };
+    match crate::sys::syscall::wait_for_event(EventType::Signal.into(), timeout) {
+        Status::Ok => Ok(()),
+        status => Err(Error::new(Subsystem::Sync, status)),
+    }
+}
+
+/// Wake up the given parked task.
+///
+/// A wake-up signal already pending for the target is not an error: the
+/// target will wake up anyway.
+///
+/// # Errors
+/// Propagates kernel errors if the signal can't be delivered.
+#[inline]
+pub fn unpark(task: TaskHandle) -> Result<(), Error> {
+    match crate::sys::syscall::send_signal(task, WAKE_SIGNAL) {
+        Status::Ok | Status::Busy => Ok(()),
+        status => Err(Error::new(Subsystem::Sync, status).with_handle(task)),
+    }
+}
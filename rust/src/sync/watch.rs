@@ -0,0 +1,158 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::error::{Error, Subsystem};
+use uapi::systypes::Status;
+
+use super::wait_queue::WaitQueue;
+
+/// Latest value cell, written by a task and watched by others.
+///
+/// The writer replaces the value with [`Watch::send`], e.g. with each sensor
+/// reading or configuration revision, and readers always observe the latest
+/// one: intermediate values are skipped by late readers instead of being
+/// queued. Each reader tracks the values it has seen with a
+/// [`WatchReceiver`], blocking until the next change.
+///
+/// The value is copied in and out under a sequence counter, so that readers
+/// never block the writer. Concurrent writers are serialized, the value
+/// being the last one sent.
+pub struct Watch<T> {
+    /// Sequence counter, odd while the value is written
+    sequence: AtomicU32,
+    value: UnsafeCell<T>,
+    queue: WaitQueue,
+}
+
+// SAFETY: the value is copied in and out under the sequence counter
+unsafe impl<T: Copy + Send> Send for Watch<T> {}
+// SAFETY: the value is copied in and out under the sequence counter
+unsafe impl<T: Copy + Send> Sync for Watch<T> {}
+
+impl<T: Copy> Watch<T> {
+    /// Create a new watch cell holding `value`.
+    pub const fn new(value: T) -> Self {
+        Self {
+            sequence: AtomicU32::new(0),
+            value: UnsafeCell::new(value),
+            queue: WaitQueue::new(),
+        }
+    }
+
+    /// Replace the value, waking up the waiting readers.
+    pub fn send(&self, value: T) {
+        let mut current = self.sequence.load(Ordering::Relaxed);
+        loop {
+            if current & 1 != 0 {
+                // another writer is preempted while writing
+                crate::sys::syscall::sched_yield();
+                current = self.sequence.load(Ordering::Relaxed);
+                continue;
+            }
+            match self.sequence.compare_exchange_weak(
+                current,
+                current.wrapping_add(1),
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(actual) => current = actual,
+            }
+        }
+        core::sync::atomic::fence(Ordering::Release);
+        // SAFETY: the odd sequence counter grants exclusive write access,
+        // readers discarding the values read meanwhile
+        unsafe { self.value.get().write_volatile(value) };
+        self.sequence
+            .store(current.wrapping_add(2), Ordering::Release);
+        self.queue.notify_all();
+    }
+
+    /// Return the latest value.
+    pub fn get(&self) -> T {
+        self.read().0
+    }
+
+    /// Return the number of values sent so far, wrapping around.
+    pub fn version(&self) -> u32 {
+        self.sequence.load(Ordering::Acquire) >> 1
+    }
+
+    /// Create a receiver which has seen the current value.
+    pub fn receiver(&self) -> WatchReceiver<'_, T> {
+        WatchReceiver {
+            watch: self,
+            seen: self.read().1,
+        }
+    }
+
+    /// Return the latest value along with its sequence counter.
+    fn read(&self) -> (T, u32) {
+        loop {
+            let before = self.sequence.load(Ordering::Acquire);
+            if before & 1 != 0 {
+                crate::sys::syscall::sched_yield();
+                continue;
+            }
+            // SAFETY: a value torn by a concurrent write is discarded below,
+            // `T` being `Copy`
+            let value = unsafe { self.value.get().read_volatile() };
+            core::sync::atomic::fence(Ordering::Acquire);
+            if self.sequence.load(Ordering::Relaxed) == before {
+                return (value, before);
+            }
+        }
+    }
+
+    /// Return the sequence counter, once the value is not being written.
+    fn stable_sequence(&self) -> u32 {
+        self.sequence.load(Ordering::Acquire) & !1
+    }
+}
+
+/// Reader of a [`Watch`], tracking the last value it has seen
+pub struct WatchReceiver<'a, T> {
+    watch: &'a Watch<T>,
+    seen: u32,
+}
+
+impl<T: Copy> WatchReceiver<'_, T> {
+    /// Return whether a value was sent since the last one seen.
+    pub fn has_changed(&self) -> bool {
+        self.watch.stable_sequence() != self.seen
+    }
+
+    /// Return the latest value, marking it as seen.
+    pub fn get(&mut self) -> T {
+        let (value, sequence) = self.watch.read();
+        self.seen = sequence;
+        value
+    }
+
+    /// Block until a value is sent, unless one was sent since the last one
+    /// seen, and return the latest value.
+    pub fn changed(&mut self) -> T {
+        self.watch.queue.wait_while(|| !self.has_changed());
+        self.get()
+    }
+
+    /// Same as [`WatchReceiver::changed`], for at most `timeout_ms`
+    /// milliseconds.
+    ///
+    /// # Errors
+    /// Returns a `Status::Timeout` error if no value has been sent in time.
+    pub fn changed_timeout(&mut self, timeout_ms: u32) -> Result<T, Error> {
+        let result = self
+            .watch
+            .queue
+            .wait_while_timeout(|| !self.has_changed(), timeout_ms);
+        match result.timed_out() {
+            true => Err(Error::new(Subsystem::Sync, Status::Timeout)),
+            false => Ok(self.get()),
+        }
+    }
+}
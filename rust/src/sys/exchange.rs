@@ -0,0 +1,163 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Exchange area accessors of the host backends
+//!
+//! The host backends serve syscalls in the calling process: results are
+//! delivered by writing the exchange area the way the Sentry kernel would, so
+//! that Shield reads them unchanged through `copy_from_kernel()`.
+
+extern crate std;
+
+use core::mem::offset_of;
+use std::vec::Vec;
+#[cfg(feature = "mock")]
+use uapi::systypes::dma::GpdmaStreamConfig;
+use uapi::systypes::shm::ShmInfo;
+use uapi::systypes::{ExchangeHeader, Status};
+
+/// Exchange area length
+pub(crate) const EXCHANGE_LEN: usize = uapi::length();
+
+/// Write raw bytes at the start of the exchange area.
+pub(crate) fn write_exchange(bytes: &[u8]) {
+    let _ = uapi::copy_to_kernel(&bytes);
+}
+
+/// Read raw bytes from the start of the exchange area.
+pub(crate) fn read_exchange(len: usize) -> Vec<u8> {
+    let mut area = [0; EXCHANGE_LEN];
+    let _ = uapi::copy_from_kernel(&mut &mut area[..]);
+    area[..len.min(EXCHANGE_LEN)].to_vec()
+}
+
+/// Offset, in the exchange area, at which `copy_from_kernel()` reads a `T`
+/// integer. The exchange area alignment being unknown on the host, it is
+/// probed by filling the area with the byte offsets.
+fn exchange_offset<T: Default + uapi::SentryExchangeable>(first_byte: fn(&T) -> u8) -> usize {
+    let probe: Vec<u8> = (0..EXCHANGE_LEN).map(|offset| offset as u8).collect();
+    write_exchange(&probe);
+    let mut value = T::default();
+    let _ = uapi::copy_from_kernel(&mut value);
+    usize::from(first_byte(&value))
+}
+
+/// Deliver a `u32` value, as read by `copy_from_kernel()`.
+pub(crate) fn deliver_u32(value: u32) {
+    let offset = exchange_offset(|value: &u32| value.to_ne_bytes()[0]);
+    let mut area = [0; EXCHANGE_LEN];
+    area[offset..offset + 4].copy_from_slice(&value.to_ne_bytes());
+    write_exchange(&area);
+}
+
+/// Deliver a `u64` value, as read by `copy_from_kernel()`.
+pub(crate) fn deliver_u64(value: u64) {
+    let offset = exchange_offset(|value: &u64| value.to_ne_bytes()[0]);
+    let mut area = [0; EXCHANGE_LEN];
+    area[offset..offset + 8].copy_from_slice(&value.to_ne_bytes());
+    write_exchange(&area);
+}
+
+/// Deliver shared memory information.
+pub(crate) fn deliver_shm_info(info: &ShmInfo) {
+    let mut area = [0; EXCHANGE_LEN];
+    let mut put = |offset: usize, bytes: &[u8]| {
+        area[offset..offset + bytes.len()].copy_from_slice(bytes);
+    };
+    put(offset_of!(ShmInfo, handle), &info.handle.to_ne_bytes());
+    put(offset_of!(ShmInfo, label), &info.label.to_ne_bytes());
+    put(offset_of!(ShmInfo, base), &info.base.to_ne_bytes());
+    put(offset_of!(ShmInfo, len), &info.len.to_ne_bytes());
+    put(offset_of!(ShmInfo, perms), &info.perms.to_ne_bytes());
+    write_exchange(&area);
+}
+
+/// Deliver a DMA stream configuration.
+#[cfg(feature = "mock")]
+pub(crate) fn deliver_stream_config(config: &GpdmaStreamConfig) {
+    let mut area = [0; EXCHANGE_LEN];
+    let mut put = |offset: usize, bytes: &[u8]| {
+        area[offset..offset + bytes.len()].copy_from_slice(bytes);
+    };
+    put(
+        offset_of!(GpdmaStreamConfig, channel),
+        &config.channel.to_ne_bytes(),
+    );
+    put(
+        offset_of!(GpdmaStreamConfig, stream),
+        &config.stream.to_ne_bytes(),
+    );
+    put(
+        offset_of!(GpdmaStreamConfig, controller),
+        &config.controller.to_ne_bytes(),
+    );
+    put(
+        offset_of!(GpdmaStreamConfig, transfer_type),
+        &config.transfer_type.to_ne_bytes(),
+    );
+    put(
+        offset_of!(GpdmaStreamConfig, source),
+        &config.source.to_ne_bytes(),
+    );
+    put(
+        offset_of!(GpdmaStreamConfig, dest),
+        &config.dest.to_ne_bytes(),
+    );
+    put(
+        offset_of!(GpdmaStreamConfig, transfer_len),
+        &config.transfer_len.to_ne_bytes(),
+    );
+    put(
+        offset_of!(GpdmaStreamConfig, circular_source),
+        &[u8::from(config.circular_source)],
+    );
+    put(
+        offset_of!(GpdmaStreamConfig, circular_dest),
+        &[u8::from(config.circular_dest)],
+    );
+    put(
+        offset_of!(GpdmaStreamConfig, interrupts),
+        &[config.interrupts],
+    );
+    put(
+        offset_of!(GpdmaStreamConfig, is_triggered),
+        &[u8::from(config.is_triggered)],
+    );
+    put(offset_of!(GpdmaStreamConfig, trigger), &[config.trigger]);
+    put(offset_of!(GpdmaStreamConfig, priority), &[config.priority]);
+    put(
+        offset_of!(GpdmaStreamConfig, transfer_mode),
+        &[config.transfer_mode],
+    );
+    put(
+        offset_of!(GpdmaStreamConfig, src_beat_len),
+        &[config.src_beat_len],
+    );
+    put(
+        offset_of!(GpdmaStreamConfig, dest_beat_len),
+        &[config.dest_beat_len],
+    );
+    write_exchange(&area);
+}
+
+/// Deliver an event, returning `Status::Invalid` if its data is too long.
+pub(crate) fn deliver_event(kind: u8, peer: u32, data: &[u8]) -> Status {
+    let header_len = size_of::<ExchangeHeader>();
+    let Ok(length) = u8::try_from(data.len()) else {
+        return Status::Invalid;
+    };
+    if header_len + data.len() > EXCHANGE_LEN {
+        return Status::Invalid;
+    }
+    let mut area = [0; EXCHANGE_LEN];
+    area[offset_of!(ExchangeHeader, event)] = kind;
+    area[offset_of!(ExchangeHeader, length)] = length;
+    let magic = offset_of!(ExchangeHeader, magic);
+    area[magic..magic + 2].copy_from_slice(&0x4242_u16.to_ne_bytes());
+    let offset = offset_of!(ExchangeHeader, peer);
+    area[offset..offset + 4].copy_from_slice(&peer.to_ne_bytes());
+    area[header_len..header_len + data.len()].copy_from_slice(data);
+    write_exchange(&area);
+    Status::Ok
+}
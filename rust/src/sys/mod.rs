@@ -0,0 +1,56 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Kernel interface backend
+//!
+//! Shield issues all its syscalls and exchange area copies through this
+//! module: the Sentry kernel syscalls, the in-process fake kernel of
+//! [`crate::mock`] when the `mock` feature is enabled, or the Linux host
+//! simulator of [`crate::sim`] when the `sim` feature is enabled.
+//!
+//! With the `trace-syscalls` feature, the syscalls of the selected backend are
+//! recorded in the [`crate::syscall_trace`] ring buffer, and with the
+//! `audit-syscalls` feature, the refused ones in the [`crate::syscall_audit`]
+//! log. With the `task-stats` feature, the time spent in the blocking ones is
+//! accounted for the [`crate::task::stats`] CPU usage statistics.
+
+#[cfg(all(feature = "mock", feature = "sim"))]
+compile_error!("the `mock` and `sim` features are mutually exclusive");
+
+#[cfg(any(feature = "mock", feature = "sim"))]
+pub(crate) mod exchange;
+#[cfg(any(
+    feature = "trace-syscalls",
+    feature = "audit-syscalls",
+    feature = "task-stats"
+))]
+pub(crate) mod trace;
+
+// copying to the kernel is only needed by some of the optional subsystems
+#[cfg(feature = "mock")]
+#[allow(unused_imports)]
+pub(crate) use crate::mock::{copy_from_kernel, copy_to_kernel};
+#[cfg(not(feature = "mock"))]
+#[allow(unused_imports)]
+pub(crate) use uapi::{copy_from_kernel, copy_to_kernel};
+
+#[cfg(feature = "mock")]
+pub(crate) use crate::mock::syscall as backend;
+#[cfg(all(feature = "sim", not(feature = "mock")))]
+pub(crate) use crate::sim::syscall as backend;
+#[cfg(not(any(feature = "mock", feature = "sim")))]
+pub(crate) use uapi::syscall as backend;
+
+#[cfg(not(any(
+    feature = "trace-syscalls",
+    feature = "audit-syscalls",
+    feature = "task-stats"
+)))]
+pub(crate) use backend as syscall;
+#[cfg(any(
+    feature = "trace-syscalls",
+    feature = "audit-syscalls",
+    feature = "task-stats"
+))]
+pub(crate) use trace::syscall;
@@ -0,0 +1,303 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Traced and audited syscalls
+//!
+//! Each syscall of the backend is wrapped so that, once it returns, its
+//! identifier, a digest of its arguments, its status and a timestamp are
+//! recorded with the `trace-syscalls` feature, and refused calls along with
+//! their caller location with the `audit-syscalls` feature. With the
+//! `task-stats` feature, the blocking syscalls are timestamped on entry and
+//! return, for the task CPU usage accounting.
+
+// the argument digests and syscall names parsing only serve the trace
+#![cfg_attr(not(feature = "trace-syscalls"), allow(dead_code))]
+
+use core::hash::Hasher;
+#[cfg(any(feature = "trace-syscalls", feature = "task-stats"))]
+use uapi::systypes::Status;
+use uapi::systypes::{AlarmFlag, CPUSleep, Precision, Signal, SleepDuration, SleepMode, Syscall};
+
+use super::backend;
+#[cfg(feature = "trace-syscalls")]
+use crate::syscall_trace;
+
+/// Exchange area length
+const EXCHANGE_LEN: usize = uapi::length();
+
+/// 32 bits FNV-1a hasher, digesting the syscall arguments
+pub(crate) struct Digest(u32);
+
+impl Digest {
+    const fn new() -> Self {
+        Self(0x811c_9dc5)
+    }
+}
+
+impl Hasher for Digest {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u32::from(byte)).wrapping_mul(0x0100_0193);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        u64::from(self.0)
+    }
+}
+
+/// Syscall argument, digested in the trace records
+///
+/// Arguments are digested in their register encoding, for a trace captured on
+/// target to be replayed on the host.
+trait Arg {
+    fn digest(&self, digest: &mut Digest);
+
+    /// Register value, truncated to 32 bits, the first argument being the
+    /// handle reported by the audit records
+    fn value(&self) -> u32;
+}
+
+macro_rules! integer_args {
+    ($($ty:ty),*) => {
+        $(impl Arg for $ty {
+            fn digest(&self, digest: &mut Digest) {
+                digest.write(&self.to_le_bytes());
+            }
+
+            fn value(&self) -> u32 {
+                *self as u32
+            }
+        })*
+    };
+}
+
+integer_args!(u8, u16, u32, i32);
+
+impl Arg for usize {
+    fn digest(&self, digest: &mut Digest) {
+        (*self as u64).digest(digest);
+    }
+
+    fn value(&self) -> u32 {
+        *self as u32
+    }
+}
+
+impl Arg for u64 {
+    fn digest(&self, digest: &mut Digest) {
+        digest.write(&self.to_le_bytes());
+    }
+
+    fn value(&self) -> u32 {
+        *self as u32
+    }
+}
+
+impl Arg for bool {
+    fn digest(&self, digest: &mut Digest) {
+        digest.write_u8(u8::from(*self));
+    }
+
+    fn value(&self) -> u32 {
+        u32::from(*self)
+    }
+}
+
+impl Arg for Signal {
+    fn digest(&self, digest: &mut Digest) {
+        (*self as u32).digest(digest);
+    }
+
+    fn value(&self) -> u32 {
+        *self as u32
+    }
+}
+
+macro_rules! enum_args {
+    ($($ty:ident { $($variant:pat => $value:expr),* $(,)? })*) => {
+        $(impl Arg for $ty {
+            fn digest(&self, digest: &mut Digest) {
+                self.value().digest(digest);
+            }
+
+            fn value(&self) -> u32 {
+                match *self {
+                    $($variant => $value),*
+                }
+            }
+        })*
+    };
+}
+
+enum_args! {
+    SleepDuration {
+        SleepDuration::D1ms => 1,
+        SleepDuration::D2ms => 2,
+        SleepDuration::D5ms => 5,
+        SleepDuration::D10ms => 10,
+        SleepDuration::D20ms => 20,
+        SleepDuration::D50ms => 50,
+        SleepDuration::ArbitraryMs(ms) => ms,
+    }
+    SleepMode {
+        SleepMode::Shallow => 0,
+        SleepMode::Deep => 1,
+    }
+    AlarmFlag {
+        AlarmFlag::AlarmStart => 0,
+        AlarmFlag::AlarmStartPeriodic => 1,
+        AlarmFlag::AlarmStop => 2,
+    }
+    Precision {
+        Precision::Cycle => 0,
+        Precision::Nanoseconds => 1,
+        Precision::Microseconds => 2,
+        Precision::Milliseconds => 3,
+    }
+    CPUSleep {
+        CPUSleep::WaitForInterrupt => 0,
+        CPUSleep::WaitForEvent => 1,
+        CPUSleep::ForbidSleep => 2,
+        CPUSleep::AllowSleep => 3,
+    }
+}
+
+/// Return the current uptime, in microseconds, preserving the exchange area
+/// content that the caller may not have read yet.
+#[cfg(any(feature = "trace-syscalls", feature = "task-stats"))]
+fn timestamp_us() -> u64 {
+    let mut saved = [0_u8; EXCHANGE_LEN];
+    let _ = uapi::copy_from_kernel(&mut &mut saved[..]);
+    let mut now = 0_u64;
+    if backend::get_cycle(Precision::Microseconds) != Status::Ok
+        || uapi::copy_from_kernel(&mut now).is_err()
+    {
+        now = 0;
+    }
+    let _ = uapi::copy_to_kernel(&&saved[..]);
+    now
+}
+
+macro_rules! traced {
+    ($($name:ident($($arg:ident: $ty:ty),*) => $syscall:ident;)*) => {
+        /// Traced syscalls, with the `sentry_uapi::syscall` signatures
+        pub(crate) mod syscall {
+            // the whole syscall set is mirrored, used by Shield or not
+            #![allow(dead_code, clippy::needless_pass_by_value)]
+
+            #[allow(unused_imports)]
+            use uapi::systypes::*;
+
+            #[allow(unused_imports)]
+            use super::{Arg, Digest, backend};
+            #[cfg(feature = "trace-syscalls")]
+            use super::syscall_trace;
+            #[cfg(any(feature = "trace-syscalls", feature = "task-stats"))]
+            use super::timestamp_us;
+
+            $(
+                #[track_caller]
+                pub fn $name($($arg: $ty),*) -> Status {
+                    #[cfg(feature = "trace-syscalls")]
+                    let digest = {
+                        #[allow(unused_mut)]
+                        let mut digest = Digest::new();
+                        $($arg.digest(&mut digest);)*
+                        digest
+                    };
+                    let id = Syscall::$syscall as u8;
+                    #[cfg(all(feature = "mock", feature = "trace-syscalls"))]
+                    let forced = crate::mock::replay_enter(id, digest.0);
+                    #[cfg(not(all(feature = "mock", feature = "trace-syscalls")))]
+                    let forced = None;
+                    #[cfg(feature = "audit-syscalls")]
+                    let handle: u32 = [$($arg.value()),*].first().copied().unwrap_or_default();
+                    #[cfg(feature = "task-stats")]
+                    let entered_us = crate::task::is_blocking(id).then(timestamp_us);
+                    let status = forced.unwrap_or_else(|| backend::$name($($arg),*));
+                    #[cfg(feature = "task-stats")]
+                    if let Some(entered_us) = entered_us {
+                        crate::task::account(entered_us, timestamp_us());
+                    }
+                    #[cfg(all(feature = "mock", feature = "trace-syscalls"))]
+                    crate::mock::replay_exit(status);
+                    #[cfg(feature = "trace-syscalls")]
+                    syscall_trace::record(id, digest.0, status, timestamp_us());
+                    #[cfg(feature = "audit-syscalls")]
+                    if matches!(status, Status::Denied | Status::Invalid) {
+                        crate::syscall_audit::record(
+                            id,
+                            handle,
+                            status,
+                            core::panic::Location::caller(),
+                        );
+                    }
+                    status
+                }
+            )*
+        }
+
+        /// Return the name of the syscall `id`.
+        pub(crate) fn syscall_name(id: u8) -> &'static str {
+            $(
+                if id == Syscall::$syscall as u8 {
+                    return stringify!($name);
+                }
+            )*
+            "unknown"
+        }
+
+        /// Return the identifier of the syscall `name`.
+        pub(crate) fn syscall_id(name: &str) -> Option<u8> {
+            $(
+                if name == stringify!($name) {
+                    return Some(Syscall::$syscall as u8);
+                }
+            )*
+            None
+        }
+    };
+}
+
+traced! {
+    exit(status: i32) => Exit;
+    get_process_handle(process: TaskLabel) => GetProcessHandle;
+    get_shm_handle(shm: ShmLabel) => GetShmHandle;
+    get_dma_stream_handle(stream: StreamLabel) => GetDmaStreamHandle;
+    sched_yield() => Yield;
+    sleep(duration_ms: SleepDuration, mode: SleepMode) => Sleep;
+    start(process: TaskLabel) => Start;
+    map_dev(dev: DeviceHandle) => MapDev;
+    map_shm(shm: ShmHandle) => MapShm;
+    unmap_dev(dev: DeviceHandle) => UnmapDev;
+    unmap_shm(shm: ShmHandle) => UnmapShm;
+    shm_set_credential(shm: ShmHandle, id: TaskHandle, shm_perm: u32) => SHMSetCredential;
+    send_ipc(target: TaskHandle, length: u8) => SendIPC;
+    send_signal(resource: u32, signal_type: Signal) => SendSignal;
+    gpio_get(resource: u32, io: u8) => GpioGet;
+    gpio_set(resource: u32, io: u8, val: bool) => GpioSet;
+    gpio_reset(resource: u32, io: u8) => GpioReset;
+    gpio_toggle(resource: u32, io: u8) => GpioToggle;
+    gpio_configure(resource: u32, io: u8) => GpioConfigure;
+    get_device_handle(devlabel: u8) => GetDeviceHandle;
+    irq_acknowledge(irq: u16) => IrqAcknowledge;
+    irq_enable(irq: u16) => IrqEnable;
+    irq_disable(irq: u16) => IrqDisable;
+    wait_for_event(mask: u8, timeout: i32) => WaitForEvent;
+    pm_manage(mode: CPUSleep) => PmManage;
+    alarm(timeout_ms: u32, flag: AlarmFlag) => Alarm;
+    log(length: usize) => Log;
+    get_random() => GetRandom;
+    get_cycle(precision: Precision) => GetCycle;
+    pm_set_clock(clk_reg: u32, clkmsk: u32, val: u32) => PmSetClock;
+    dma_start_stream(dmah: StreamHandle) => DmaStartStream;
+    dma_suspend_stream(dmah: StreamHandle) => DmaSuspendStream;
+    dma_get_stream_status(dmah: StreamHandle) => DmaGetStreamStatus;
+    shm_get_infos(shm: ShmHandle) => ShmGetInfos;
+    dma_assign_stream(dmah: StreamHandle) => DmaAssignStream;
+    dma_unassign_stream(dmah: StreamHandle) => DmaUnassignStream;
+    dma_get_stream_info(dmah: StreamHandle) => DmaGetStreamInfo;
+    dma_resume_stream(dmah: StreamHandle) => DmaResumeStream;
+}
@@ -0,0 +1,174 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Refused syscalls audit log
+//!
+//! With the `audit-syscalls` feature, every syscall returning
+//! `Status::Denied` or `Status::Invalid` is recorded in a ring buffer holding
+//! the [`AUDIT_LEN`] most recent ones: the syscall, its first argument
+//! (typically the handle or label it was refused for), its status and the
+//! source location it was issued from.
+//!
+//! The location is resolved with `#[track_caller]`: the Shield primitives
+//! issuing resource syscalls ([`crate::shm::Shm`] mapping and credentials,
+//! [`crate::dma::DmaStream`] assignment, [`crate::process`] handles and
+//! signals, [`crate::random`]) report their caller, other syscalls the
+//! Shield function issuing them.
+//!
+//! ```ignore
+//! if let Err(err) = Shm::new(KEY_SHM)?.map(0) {
+//!     shield::syscall_audit::dump();
+//!     // map_shm(0x00000f00) -> Denied at src/main.rs:42:38
+//! }
+//! ```
+//!
+//! Unlike [`crate::syscall_trace`], successful syscalls cost nothing more
+//! than the comparison of their status.
+
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::panic::Location;
+use uapi::systypes::Status;
+
+use crate::error::status_name;
+
+/// Number of records held by the audit ring buffer
+pub const AUDIT_LEN: usize = 16;
+
+/// Refused syscall
+#[derive(Clone, Copy, PartialEq)]
+pub struct AuditRecord {
+    /// Syscall identifier, as `Syscall as u8`
+    pub syscall: u8,
+    /// First syscall argument, truncated to 32 bits, 0 if none
+    pub handle: u32,
+    /// Status returned by the syscall
+    pub status: Status,
+    /// Location the syscall was issued from
+    pub location: &'static Location<'static>,
+}
+
+impl AuditRecord {
+    /// Return the syscall name.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        crate::sys::trace::syscall_name(self.syscall)
+    }
+}
+
+impl fmt::Display for AuditRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}({:#010x}) -> {} at {}",
+            self.name(),
+            self.handle,
+            status_name(self.status),
+            self.location
+        )
+    }
+}
+
+impl fmt::Debug for AuditRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuditRecord")
+            .field("syscall", &self.name())
+            .field("handle", &format_args!("{:#010x}", self.handle))
+            .field("status", &status_name(self.status))
+            .field("location", &self.location)
+            .finish()
+    }
+}
+
+struct Ring {
+    records: [Option<AuditRecord>; AUDIT_LEN],
+    next: usize,
+    total: u32,
+}
+
+struct RingCell(UnsafeCell<Ring>);
+
+// SAFETY: a Sentry task is single-threaded, and the ring is never borrowed
+// across calls of `with_ring`
+unsafe impl Sync for RingCell {}
+
+static RING: RingCell = RingCell(UnsafeCell::new(Ring {
+    records: [None; AUDIT_LEN],
+    next: 0,
+    total: 0,
+}));
+
+/// Execute `f` with an exclusive access to the audit ring buffer
+fn with_ring<R>(f: impl FnOnce(&mut Ring) -> R) -> R {
+    // SAFETY: see RingCell, `f` never issues syscalls
+    f(unsafe { &mut *RING.0.get() })
+}
+
+/// Record a refused syscall, dropping the oldest record if the ring is full.
+pub(crate) fn record(
+    syscall: u8,
+    handle: u32,
+    status: Status,
+    location: &'static Location<'static>,
+) {
+    with_ring(|ring| {
+        ring.records[ring.next] = Some(AuditRecord {
+            syscall,
+            handle,
+            status,
+            location,
+        });
+        ring.next = (ring.next + 1) % AUDIT_LEN;
+        ring.total = ring.total.wrapping_add(1);
+    });
+}
+
+/// Copy the most recent records to `out`, oldest first, returning the number
+/// of records copied.
+pub fn records(out: &mut [Option<AuditRecord>]) -> usize {
+    with_ring(|ring| {
+        let held = usize::try_from(ring.total).map_or(AUDIT_LEN, |total| total.min(AUDIT_LEN));
+        let count = held.min(out.len());
+        let first = (ring.next + AUDIT_LEN - count) % AUDIT_LEN;
+        for (index, slot) in out[..count].iter_mut().enumerate() {
+            *slot = ring.records[(first + index) % AUDIT_LEN];
+        }
+        count
+    })
+}
+
+/// Return the most recent record, if any.
+#[must_use]
+pub fn last() -> Option<AuditRecord> {
+    with_ring(|ring| ring.records[(ring.next + AUDIT_LEN - 1) % AUDIT_LEN])
+}
+
+/// Return the number of refused syscalls since boot (or the last [`clear`]),
+/// including the ones dropped from the ring.
+#[must_use]
+pub fn total() -> u32 {
+    with_ring(|ring| ring.total)
+}
+
+/// Drop all records.
+pub fn clear() {
+    with_ring(|ring| {
+        ring.records = [None; AUDIT_LEN];
+        ring.next = 0;
+        ring.total = 0;
+    });
+}
+
+/// Write the audit log through the kernel log channel, oldest record first.
+///
+/// Requires the `print` feature.
+#[cfg(feature = "print")]
+pub fn dump() {
+    let mut audit = [None; AUDIT_LEN];
+    let count = records(&mut audit);
+    crate::println!("syscall audit: {count} of {} refused syscalls", total());
+    for record in audit[..count].iter().flatten() {
+        crate::println!("{record}");
+    }
+}
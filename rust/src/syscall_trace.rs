@@ -0,0 +1,204 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Syscall tracing
+//!
+//! With the `trace-syscalls` feature, every syscall issued by Shield is
+//! recorded in a ring buffer holding the [`TRACE_LEN`] most recent ones: the
+//! syscall identifier, a digest of its arguments, its returned status and the
+//! uptime at which it returned.
+//!
+//! The trace is read back with [`records`], or written through the kernel log
+//! channel with [`dump`], typically when an unexpected `Status::Denied` is
+//! returned in the field:
+//!
+//! ```ignore
+//! if let Err(err) = shm.map(0) {
+//!     println!("map failed: {err}");
+//!     shield::syscall_trace::dump();
+//! }
+//! ```
+//!
+//! A dumped trace can be parsed back, line by line, with
+//! [`SyscallRecord::from_str`], typically to replay a trace captured on target
+//! against the fake kernel (see `mock::Session::replay`).
+//!
+//! Recording the timestamp of a syscall costs an additional `get_cycle()`
+//! syscall, which is not recorded, and two exchange area copies.
+
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::str::FromStr;
+use uapi::systypes::Status;
+
+use crate::error::status_name;
+
+/// Number of records held by the trace ring buffer
+pub const TRACE_LEN: usize = 64;
+
+/// Traced syscall
+#[derive(Clone, Copy, PartialEq)]
+pub struct SyscallRecord {
+    /// Syscall identifier, as `Syscall as u8`
+    pub syscall: u8,
+    /// FNV-1a digest of the syscall arguments
+    pub args: u32,
+    /// Status returned by the syscall
+    pub status: Status,
+    /// Uptime at which the syscall returned, in microseconds
+    pub timestamp_us: u64,
+}
+
+impl SyscallRecord {
+    const EMPTY: Self = Self {
+        syscall: 0,
+        args: 0,
+        status: Status::Ok,
+        timestamp_us: 0,
+    };
+
+    /// Return the syscall name.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        crate::sys::trace::syscall_name(self.syscall)
+    }
+}
+
+impl fmt::Display for SyscallRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>12}us {}(args {:#010x}) -> {}",
+            self.timestamp_us,
+            self.name(),
+            self.args,
+            status_name(self.status)
+        )
+    }
+}
+
+impl fmt::Debug for SyscallRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyscallRecord")
+            .field("syscall", &self.name())
+            .field("args", &format_args!("{:#010x}", self.args))
+            .field("status", &status_name(self.status))
+            .field("timestamp_us", &self.timestamp_us)
+            .finish()
+    }
+}
+
+/// Error returned when parsing a malformed [`SyscallRecord`] dump line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseRecordError;
+
+impl fmt::Display for ParseRecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("malformed syscall record")
+    }
+}
+
+impl core::error::Error for ParseRecordError {}
+
+impl FromStr for SyscallRecord {
+    type Err = ParseRecordError;
+
+    /// Parse a record dumped by [`dump`], in its `Display` format.
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (timestamp, line) = line.trim().split_once("us ").ok_or(ParseRecordError)?;
+        let (name, line) = line.split_once("(args ").ok_or(ParseRecordError)?;
+        let (args, status) = line.split_once(") -> ").ok_or(ParseRecordError)?;
+        let args = args.strip_prefix("0x").ok_or(ParseRecordError)?;
+        Ok(Self {
+            syscall: crate::sys::trace::syscall_id(name).ok_or(ParseRecordError)?,
+            args: u32::from_str_radix(args, 16).map_err(|_| ParseRecordError)?,
+            status: (0..=10)
+                .map(Status::from)
+                .find(|&candidate| status_name(candidate) == status)
+                .ok_or(ParseRecordError)?,
+            timestamp_us: timestamp.trim().parse().map_err(|_| ParseRecordError)?,
+        })
+    }
+}
+
+struct Ring {
+    records: [SyscallRecord; TRACE_LEN],
+    next: usize,
+    total: u32,
+}
+
+struct RingCell(UnsafeCell<Ring>);
+
+// SAFETY: a Sentry task is single-threaded, and the ring is never borrowed
+// across calls of `with_ring`
+unsafe impl Sync for RingCell {}
+
+static RING: RingCell = RingCell(UnsafeCell::new(Ring {
+    records: [SyscallRecord::EMPTY; TRACE_LEN],
+    next: 0,
+    total: 0,
+}));
+
+/// Execute `f` with an exclusive access to the trace ring buffer
+fn with_ring<R>(f: impl FnOnce(&mut Ring) -> R) -> R {
+    // SAFETY: see RingCell, `f` never issues syscalls
+    f(unsafe { &mut *RING.0.get() })
+}
+
+/// Record a syscall, dropping the oldest record if the ring is full.
+pub(crate) fn record(syscall: u8, args: u32, status: Status, timestamp_us: u64) {
+    with_ring(|ring| {
+        ring.records[ring.next] = SyscallRecord {
+            syscall,
+            args,
+            status,
+            timestamp_us,
+        };
+        ring.next = (ring.next + 1) % TRACE_LEN;
+        ring.total = ring.total.wrapping_add(1);
+    });
+}
+
+/// Copy the most recent records to `out`, oldest first, returning the number
+/// of records copied.
+pub fn records(out: &mut [SyscallRecord]) -> usize {
+    with_ring(|ring| {
+        let held = usize::try_from(ring.total).map_or(TRACE_LEN, |total| total.min(TRACE_LEN));
+        let count = held.min(out.len());
+        let first = (ring.next + TRACE_LEN - count) % TRACE_LEN;
+        for (index, slot) in out[..count].iter_mut().enumerate() {
+            *slot = ring.records[(first + index) % TRACE_LEN];
+        }
+        count
+    })
+}
+
+/// Return the number of syscalls recorded since boot (or the last [`clear`]),
+/// including the ones dropped from the ring.
+#[must_use]
+pub fn total() -> u32 {
+    with_ring(|ring| ring.total)
+}
+
+/// Drop all records.
+pub fn clear() {
+    with_ring(|ring| {
+        ring.next = 0;
+        ring.total = 0;
+    });
+}
+
+/// Write the trace through the kernel log channel, oldest record first.
+///
+/// The log syscalls issued while dumping are traced as well, but only after
+/// the trace has been copied. Requires the `print` feature.
+#[cfg(feature = "print")]
+pub fn dump() {
+    let mut trace = [SyscallRecord::EMPTY; TRACE_LEN];
+    let count = records(&mut trace);
+    crate::println!("syscall trace: {count} of {} records", total());
+    for record in &trace[..count] {
+        crate::println!("{record}");
+    }
+}
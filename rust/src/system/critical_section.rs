@@ -0,0 +1,31 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! `critical-section` crate implementation
+//!
+//! Sentry tasks run unprivileged, and as such are not able to mask interrupts.
+//! This is not required either: a task is single-threaded and interrupts are
+//! delivered by the kernel as events, handled in the task's own flow. Nothing
+//! can preempt the task in its own address space, the critical section only
+//! needs to prevent the compiler from reordering accesses across its
+//! boundaries.
+//!
+//! > **NOTE**: this does not protect data living in a shared memory against
+//! > peer tasks. Use the [`crate::sync`] primitives instead.
+
+use core::sync::atomic::{Ordering, compiler_fence};
+
+struct ShieldCriticalSection;
+::critical_section::set_impl!(ShieldCriticalSection);
+
+// SAFETY: a Sentry task is single-threaded and can't be interrupted in its own context
+unsafe impl ::critical_section::Impl for ShieldCriticalSection {
+    unsafe fn acquire() -> ::critical_section::RawRestoreState {
+        compiler_fence(Ordering::SeqCst);
+    }
+
+    unsafe fn release(_token: ::critical_section::RawRestoreState) {
+        compiler_fence(Ordering::SeqCst);
+    }
+}
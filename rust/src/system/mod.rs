@@ -5,4 +5,8 @@
 // #[cfg(panic = "abort")]
 // pub mod panic;
 
+#[cfg(feature = "critical-section")]
+mod critical_section;
+// with the host backends, the host process provides its own entrypoint
+#[cfg(not(any(feature = "mock", feature = "sim")))]
 pub mod startup;
@@ -30,7 +30,9 @@ pub extern "C" fn _start(_thread_id: u32, seed: u32) -> ! {
         __stack_chk_guard = seed;
     }
 
-    // TODO init seed for rand_r ?
+    #[cfg(feature = "ffi")]
+    crate::ffi::rand::init(seed);
+
     // rustlang initialisation ? heap for custom allocator ?
 
     // XXX: as main is extern, call is unsafe by construction.
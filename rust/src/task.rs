@@ -0,0 +1,539 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Current task capabilities
+//!
+//! The kernel grants the task capabilities and resources statically, from
+//! its build-time metadata, and has no syscall to list them: [`capabilities`]
+//! probes them instead, with side-effect free syscalls, so that libraries can
+//! degrade gracefully or report precise errors instead of failing with
+//! `Status::Denied` halfway through an operation.
+//!
+//! ```ignore
+//! let caps = task::capabilities();
+//! let seed = if caps.has(SyscallClass::Random) {
+//!     random::random_u32()?
+//! } else {
+//!     fallback_seed()
+//! };
+//! caps.require_device(SPI1)?;
+//! ```
+//!
+//! The system capabilities (starting tasks, power management) can't be
+//! probed without acting, and are not reported.
+//!
+//! The scheduling [`priority`] is reported as well, from the task metadata,
+//! the kernel denying [`set_priority`] changes.
+//!
+//! A CPU-bound computation gives the CPU back with [`yield_now`], or with a
+//! [`Budget`] yielding once it has run for a given time slice, so that the
+//! other tasks, and the task's own event handling, are not starved:
+//!
+//! ```ignore
+//! let mut budget = Budget::new(2.ms());
+//! for block in image.chunks(BLOCK_LEN) {
+//!     digest.update(block);
+//!     budget.tick();
+//! }
+//! ```
+//!
+//! With the `task-stats` feature, [`stats`] reports the CPU usage of the
+//! current task, e.g. for a watchdog task to detect starvation, while
+//! [`publish_stats`] exports it in a shared memory for a supervisor allowed to
+//! read it with [`peer_stats`].
+
+use uapi::systypes::{Precision, ShmLabel, Status, StreamLabel, TaskLabel};
+
+use crate::error::{Error, Subsystem};
+use crate::units::Microseconds;
+
+/// Access to a resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// The task owns the resource, or is allowed to use it
+    Allowed,
+    /// The resource exists, but the task is not allowed to use it
+    Denied,
+    /// The kernel knows no such resource
+    Unknown,
+}
+
+impl Access {
+    fn from_status(status: Status) -> Self {
+        match status {
+            Status::Ok => Self::Allowed,
+            Status::Denied => Self::Denied,
+            _ => Self::Unknown,
+        }
+    }
+
+    fn require(self, subsystem: Subsystem, label: u32) -> Result<(), Error> {
+        let status = match self {
+            Self::Allowed => return Ok(()),
+            Self::Denied => Status::Denied,
+            Self::Unknown => Status::Invalid,
+        };
+        Err(Error::new(subsystem, status).with_handle(label))
+    }
+}
+
+/// Syscall classes requiring a capability
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyscallClass {
+    /// Kernel TRNG ([`crate::random`]), requiring `CAP_CRY_KRNG`
+    Random,
+    /// Cycle and nanosecond precision clock, requiring `CAP_TIM_HP_CHRONO`
+    PreciseClock,
+}
+
+/// Capabilities of the current task, returned by [`capabilities`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    random: bool,
+    precise_clock: bool,
+}
+
+/// Probe the capabilities of the current task.
+///
+/// The syscall classes are probed once, the resources on each query.
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        // consumes 32 bits of entropy
+        random: crate::sys::syscall::get_random() == Status::Ok,
+        precise_clock: crate::sys::syscall::get_cycle(Precision::Nanoseconds) == Status::Ok,
+    }
+}
+
+impl Capabilities {
+    /// Check whether the task is allowed to use the syscalls of `class`.
+    pub fn has(&self, class: SyscallClass) -> bool {
+        match class {
+            SyscallClass::Random => self.random,
+            SyscallClass::PreciseClock => self.precise_clock,
+        }
+    }
+
+    /// Return the access to the device `label`.
+    pub fn device(&self, label: u8) -> Access {
+        Access::from_status(crate::sys::syscall::get_device_handle(label))
+    }
+
+    /// Return the access to the shared memory `label`, owned or shared with
+    /// the task.
+    pub fn shm(&self, label: ShmLabel) -> Access {
+        Access::from_status(crate::sys::syscall::get_shm_handle(label))
+    }
+
+    /// Return the access to the DMA stream `label`.
+    pub fn dma_stream(&self, label: StreamLabel) -> Access {
+        Access::from_status(crate::sys::syscall::get_dma_stream_handle(label))
+    }
+
+    /// Return whether the task may exchange IPC and signals with the task
+    /// `label`.
+    pub fn peer(&self, label: TaskLabel) -> Access {
+        Access::from_status(crate::sys::syscall::get_process_handle(label))
+    }
+
+    /// Check that the task is allowed to use the syscalls of `class`.
+    ///
+    /// # Errors
+    /// Returns a `Status::Denied` error of the corresponding subsystem if
+    /// not.
+    pub fn require(&self, class: SyscallClass) -> Result<(), Error> {
+        if self.has(class) {
+            return Ok(());
+        }
+        let subsystem = match class {
+            SyscallClass::Random => Subsystem::Random,
+            SyscallClass::PreciseClock => Subsystem::Time,
+        };
+        Err(Error::new(subsystem, Status::Denied))
+    }
+
+    /// Check that the task is allowed to use the device `label`.
+    ///
+    /// # Errors
+    /// Returns a `Status::Denied` error if the device is not allowed, or a
+    /// `Status::Invalid` error if unknown, along with the label.
+    pub fn require_device(&self, label: u8) -> Result<(), Error> {
+        self.device(label)
+            .require(Subsystem::Process, u32::from(label))
+    }
+
+    /// Check that the task is allowed to map the shared memory `label`.
+    ///
+    /// # Errors
+    /// See [`Capabilities::require_device`].
+    pub fn require_shm(&self, label: ShmLabel) -> Result<(), Error> {
+        self.shm(label).require(Subsystem::Shm, label)
+    }
+
+    /// Check that the task is allowed to use the DMA stream `label`.
+    ///
+    /// # Errors
+    /// See [`Capabilities::require_device`].
+    pub fn require_dma_stream(&self, label: StreamLabel) -> Result<(), Error> {
+        self.dma_stream(label).require(Subsystem::Dma, label)
+    }
+
+    /// Check that the task may communicate with the task `label`.
+    ///
+    /// # Errors
+    /// See [`Capabilities::require_device`].
+    pub fn require_peer(&self, label: TaskLabel) -> Result<(), Error> {
+        self.peer(label).require(Subsystem::Ipc, label)
+    }
+}
+
+/// Scheduling priority of a task, higher values being scheduled first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Priority(u8);
+
+impl Priority {
+    /// Lowest priority
+    pub const MIN: Self = Self(0);
+    /// Highest priority
+    pub const MAX: Self = Self(u8::MAX);
+
+    /// Create a priority from its task metadata value.
+    pub const fn new(value: u8) -> Self {
+        Self(value)
+    }
+
+    /// Return the task metadata value of the priority.
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<u8> for Priority {
+    fn from(value: u8) -> Self {
+        Self(value)
+    }
+}
+
+impl core::fmt::Display for Priority {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Return the scheduling priority of the current task.
+///
+/// The kernel schedules tasks according to the priority of their build-time
+/// metadata, which it doesn't report: the task build passes it to Shield in
+/// the `SHIELD_TASK_PRIORITY` environment variable.
+///
+/// # Errors
+/// Returns a `Status::NoEntity` error if the priority has not been given at
+/// build time, or a `Status::Invalid` error if it is not a valid priority.
+pub fn priority() -> Result<Priority, Error> {
+    let value = option_env!("SHIELD_TASK_PRIORITY")
+        .ok_or(Error::new(Subsystem::Process, Status::NoEntity))?;
+    value
+        .trim()
+        .parse()
+        .map(Priority)
+        .map_err(|_| Error::new(Subsystem::Process, Status::Invalid))
+}
+
+/// Set the scheduling priority of the current task.
+///
+/// The current kernel doesn't allow a task to change its priority at run
+/// time: setting the current priority is a no-op, anything else is denied,
+/// so that a task boosting itself around a critical section learns that the
+/// section runs at its usual priority.
+///
+/// # Errors
+/// Returns a `Status::Denied` error, along with the requested priority, if
+/// the priority differs from the current one, or propagates the
+/// [`priority`] errors.
+pub fn set_priority(priority: Priority) -> Result<(), Error> {
+    if self::priority()? == priority {
+        return Ok(());
+    }
+    Err(Error::new(Subsystem::Process, Status::Denied).with_handle(u32::from(priority.0)))
+}
+
+/// Give the CPU back to the scheduler.
+///
+/// The task is scheduled again once the ready tasks of the same or a higher
+/// priority have run. Its pending events are left pending: futures driven by
+/// the executor yield with `executor::yield_now` instead, which dispatches
+/// them.
+pub fn yield_now() {
+    // the kernel always accepts a yield
+    let _ = crate::sys::syscall::sched_yield();
+}
+
+/// Time slice of a long-running computation
+///
+/// The computation calls [`Budget::tick`] regularly, which yields and starts
+/// a new slice once the current one is exhausted. If the uptime can't be
+/// read, the slice is considered exhausted, so that the computation yields
+/// at each tick instead of never.
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    slice_us: u64,
+    start_us: u64,
+}
+
+impl Budget {
+    /// Create a budget of `slice` per time slice, the first one starting
+    /// now.
+    pub fn new(slice: impl Into<Microseconds>) -> Self {
+        Self {
+            slice_us: slice.into().get(),
+            start_us: crate::time::uptime_us().unwrap_or(0),
+        }
+    }
+
+    /// Return the duration of a time slice.
+    pub fn slice(&self) -> Microseconds {
+        Microseconds(self.slice_us)
+    }
+
+    /// Return the time elapsed in the current slice, or `None` if the uptime
+    /// can't be read.
+    pub fn elapsed(&self) -> Option<Microseconds> {
+        let now_us = crate::time::uptime_us().ok()?;
+        Some(Microseconds(now_us.saturating_sub(self.start_us)))
+    }
+
+    /// Return whether the current slice is exhausted.
+    pub fn is_exhausted(&self) -> bool {
+        self.elapsed()
+            .is_none_or(|elapsed| elapsed.get() >= self.slice_us)
+    }
+
+    /// Start a new time slice.
+    pub fn restart(&mut self) {
+        self.start_us = crate::time::uptime_us().unwrap_or(self.start_us);
+    }
+
+    /// Yield if the current slice is exhausted, starting a new one, and
+    /// return whether the task yielded.
+    pub fn tick(&mut self) -> bool {
+        self.tick_with(yield_now)
+    }
+
+    /// Same as [`Budget::tick`], calling `f` instead of yielding, e.g. to
+    /// handle the pending events.
+    pub fn tick_with(&mut self, f: impl FnOnce()) -> bool {
+        if !self.is_exhausted() {
+            return false;
+        }
+        f();
+        self.restart();
+        true
+    }
+
+    /// Same as [`Budget::tick`], for a future driven by the executor,
+    /// yielding with `executor::yield_now`.
+    #[cfg(feature = "async")]
+    pub async fn tick_async(&mut self) -> bool {
+        if !self.is_exhausted() {
+            return false;
+        }
+        crate::executor::yield_now().await;
+        self.restart();
+        true
+    }
+}
+
+/// CPU usage statistics of a task, returned by [`stats`] and [`peer_stats`]
+///
+/// The kernel doesn't report its scheduling decisions: the task accounts for
+/// the time it spends blocked in its yields, sleeps and event waits. The run
+/// time thus includes the time the task has been preempted by higher priority
+/// tasks, and the preemption count only covers the task giving the CPU back.
+#[cfg(feature = "task-stats")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaskStats {
+    /// Uptime at which the accounting started, in microseconds
+    pub since_us: u64,
+    /// Time spent out of the blocking syscalls, in microseconds
+    pub run_time_us: u64,
+    /// Number of times the task has been descheduled
+    pub preemptions: u32,
+    /// Uptime at which the task was last scheduled back, in microseconds
+    pub last_scheduled_us: u64,
+}
+
+#[cfg(feature = "task-stats")]
+#[derive(Clone, Copy)]
+struct Accounting {
+    since_us: Option<u64>,
+    blocked_us: u64,
+    preemptions: u32,
+    last_scheduled_us: u64,
+}
+
+#[cfg(feature = "task-stats")]
+struct AccountingCell(core::cell::UnsafeCell<Accounting>);
+
+// SAFETY: a Sentry task is single-threaded, and the accounting is never
+// borrowed across calls of `with_accounting`
+#[cfg(feature = "task-stats")]
+unsafe impl Sync for AccountingCell {}
+
+#[cfg(feature = "task-stats")]
+static ACCOUNTING: AccountingCell = AccountingCell(core::cell::UnsafeCell::new(Accounting {
+    since_us: None,
+    blocked_us: 0,
+    preemptions: 0,
+    last_scheduled_us: 0,
+}));
+
+#[cfg(feature = "task-stats")]
+fn with_accounting<R>(f: impl FnOnce(&mut Accounting) -> R) -> R {
+    // SAFETY: see AccountingCell, `f` never issues syscalls
+    f(unsafe { &mut *ACCOUNTING.0.get() })
+}
+
+/// Return whether the syscall `id` gives the CPU back to the scheduler.
+#[cfg(feature = "task-stats")]
+pub(crate) fn is_blocking(id: u8) -> bool {
+    use uapi::systypes::Syscall;
+    id == Syscall::Yield as u8 || id == Syscall::Sleep as u8 || id == Syscall::WaitForEvent as u8
+}
+
+/// Account for a blocking syscall entered at `entered_us` and returned at
+/// `returned_us`.
+#[cfg(feature = "task-stats")]
+pub(crate) fn account(entered_us: u64, returned_us: u64) {
+    with_accounting(|accounting| {
+        accounting.since_us.get_or_insert(entered_us);
+        accounting.blocked_us += returned_us.saturating_sub(entered_us);
+        accounting.preemptions = accounting.preemptions.wrapping_add(1);
+        accounting.last_scheduled_us = returned_us;
+    });
+}
+
+/// Return the CPU usage statistics of the current task.
+///
+/// The accounting starts at the first blocking syscall or call to this
+/// function.
+///
+/// # Errors
+/// Propagates kernel errors if the uptime can't be read.
+#[cfg(feature = "task-stats")]
+pub fn stats() -> Result<TaskStats, Error> {
+    let now_us = crate::time::uptime_us()?;
+    Ok(with_accounting(|accounting| {
+        let since_us = *accounting.since_us.get_or_insert(now_us);
+        TaskStats {
+            since_us,
+            run_time_us: now_us
+                .saturating_sub(since_us)
+                .saturating_sub(accounting.blocked_us),
+            preemptions: accounting.preemptions,
+            last_scheduled_us: accounting.last_scheduled_us.max(since_us),
+        }
+    }))
+}
+
+/// Published statistics magic, "TSTA" in little endian
+#[cfg(all(feature = "task-stats", feature = "shm"))]
+const STATS_MAGIC: u32 = 0x4154_5354;
+
+/// Published statistics length, in 32 bits words: magic, sequence, then the
+/// statistics
+#[cfg(all(feature = "task-stats", feature = "shm"))]
+const STATS_WORDS: usize = 9;
+
+/// Number of attempts at reading statistics being published
+#[cfg(all(feature = "task-stats", feature = "shm"))]
+const STATS_READ_ATTEMPTS: usize = 4;
+
+/// Check the shared memory holding published statistics, returning its
+/// words.
+#[cfg(all(feature = "task-stats", feature = "shm"))]
+fn stats_words(
+    shm: &mut crate::shm::Shm<crate::shm::Mapped>,
+    writable: bool,
+) -> Result<*mut u32, Error> {
+    let invalid = Error::new(Subsystem::Process, Status::Invalid);
+    let base = shm.base_address()?;
+    if shm.length()? < STATS_WORDS * 4
+        || base % align_of::<u32>() != 0
+        || (writable && !shm.is_writable())
+    {
+        return Err(invalid);
+    }
+    Ok(core::ptr::with_exposed_provenance_mut(base))
+}
+
+/// Publish the CPU usage statistics of the current task in `shm`, for a
+/// peer task granted the shared memory to read them with [`peer_stats`].
+///
+/// # Errors
+/// Returns a `Status::Invalid` error if the shared memory is too small,
+/// misaligned or not writable, or propagates kernel errors.
+#[cfg(all(feature = "task-stats", feature = "shm"))]
+pub fn publish_stats(shm: &mut crate::shm::Shm<crate::shm::Mapped>) -> Result<(), Error> {
+    let stats = stats()?;
+    let words = stats_words(shm, true)?;
+    // SAFETY: the shared memory holds the statistics words
+    unsafe {
+        let seq = words.add(1);
+        let next = u32::from_le(seq.read_volatile()).wrapping_add(1) | 1;
+        seq.write_volatile(next.to_le());
+        core::sync::atomic::fence(core::sync::atomic::Ordering::Release);
+        let values = [
+            stats.since_us as u32,
+            (stats.since_us >> 32) as u32,
+            stats.run_time_us as u32,
+            (stats.run_time_us >> 32) as u32,
+            stats.last_scheduled_us as u32,
+            (stats.last_scheduled_us >> 32) as u32,
+            stats.preemptions,
+        ];
+        for (index, value) in values.into_iter().enumerate() {
+            words.add(2 + index).write_volatile(value.to_le());
+        }
+        core::sync::atomic::fence(core::sync::atomic::Ordering::Release);
+        seq.write_volatile(next.wrapping_add(1).to_le());
+        words.write_volatile(STATS_MAGIC.to_le());
+    }
+    Ok(())
+}
+
+/// Read the CPU usage statistics published by a peer task in `shm`, see
+/// [`publish_stats`].
+///
+/// # Errors
+/// Returns a `Status::NoEntity` error if none have been published yet, a
+/// `Status::Busy` error if they are being published, a `Status::Invalid`
+/// error if the shared memory is too small or misaligned, or propagates
+/// kernel errors.
+#[cfg(all(feature = "task-stats", feature = "shm"))]
+pub fn peer_stats(shm: &mut crate::shm::Shm<crate::shm::Mapped>) -> Result<TaskStats, Error> {
+    let words = stats_words(shm, false)?;
+    // SAFETY: the shared memory holds the statistics words
+    let read = |index: usize| u32::from_le(unsafe { words.add(index).read_volatile() });
+    if read(0) != STATS_MAGIC {
+        return Err(Error::new(Subsystem::Process, Status::NoEntity));
+    }
+    let wide = |index: usize| u64::from(read(index)) | (u64::from(read(index + 1)) << 32);
+    for _ in 0..STATS_READ_ATTEMPTS {
+        let seq = read(1);
+        if seq % 2 == 1 {
+            continue;
+        }
+        core::sync::atomic::fence(core::sync::atomic::Ordering::Acquire);
+        let stats = TaskStats {
+            since_us: wide(2),
+            run_time_us: wide(4),
+            last_scheduled_us: wide(6),
+            preemptions: read(8),
+        };
+        core::sync::atomic::fence(core::sync::atomic::Ordering::Acquire);
+        if read(1) == seq {
+            return Ok(stats);
+        }
+    }
+    Err(Error::new(Subsystem::Process, Status::Busy))
+}
@@ -0,0 +1,111 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Kernel clock access
+//!
+//! Besides the uptime, a realtime clock is kept as an offset from the uptime.
+//! As on a host lacking a RTC, it runs from the Unix epoch at startup until
+//! set with [`set_realtime_us`], typically by a RTC or network time driver.
+
+use core::cell::UnsafeCell;
+
+use crate::error::{Error, Subsystem};
+use crate::units::Milliseconds;
+use uapi::systypes::{Precision, SleepDuration, SleepMode, Status};
+
+struct OffsetCell(UnsafeCell<i64>);
+
+// SAFETY: a Sentry task is single-threaded, and the offset is only copied in
+// and out
+unsafe impl Sync for OffsetCell {}
+
+/// Realtime clock offset from the uptime, in microseconds
+static REALTIME_OFFSET: OffsetCell = OffsetCell(UnsafeCell::new(0));
+
+/// Return the elapsed time since startup, in the given unit.
+///
+/// # Errors
+/// Returns `Status::Denied` if the task is not allowed to use the requested
+/// precision (cycles and nanoseconds require the high precision chronometer
+/// capability), or propagates kernel errors.
+#[inline]
+pub fn uptime(precision: Precision) -> Result<u64, Error> {
+    match crate::sys::syscall::get_cycle(precision) {
+        Status::Ok => {}
+        status => return Err(Error::new(Subsystem::Time, status)),
+    }
+
+    let mut now = 0_u64;
+    match crate::sys::copy_from_kernel(&mut now) {
+        Ok(Status::Ok) => Ok(now),
+        Ok(status) | Err(status) => Err(Error::new(Subsystem::Time, status)),
+    }
+}
+
+/// Return the elapsed time since startup, in milliseconds.
+///
+/// # Errors
+/// Propagates kernel errors.
+#[inline]
+pub fn uptime_ms() -> Result<u64, Error> {
+    uptime(Precision::Milliseconds)
+}
+
+/// Return the elapsed time since startup, in microseconds.
+///
+/// # Errors
+/// Propagates kernel errors.
+#[inline]
+pub fn uptime_us() -> Result<u64, Error> {
+    uptime(Precision::Microseconds)
+}
+
+/// Put the current task to sleep for `duration_ms` milliseconds.
+///
+/// The task is woken up sooner if an event (IRQ, IPC, signal) is received.
+///
+/// # Errors
+/// Returns a `Status::Intr` error if the sleep has been interrupted by an
+/// event, or propagates kernel errors.
+#[inline]
+pub fn sleep_ms(duration_ms: u32) -> Result<(), Error> {
+    match crate::sys::syscall::sleep(SleepDuration::ArbitraryMs(duration_ms), SleepMode::Shallow) {
+        Status::Ok => Ok(()),
+        status => Err(Error::new(Subsystem::Time, status)),
+    }
+}
+
+/// Put the current task to sleep for `duration`, see [`sleep_ms`].
+///
+/// # Errors
+/// Same as [`sleep_ms`].
+#[inline]
+pub fn sleep(duration: impl Into<Milliseconds>) -> Result<(), Error> {
+    sleep_ms(duration.into().get())
+}
+
+/// Return the realtime clock offset from the uptime, in microseconds.
+pub(crate) fn realtime_offset_us() -> i64 {
+    // SAFETY: see OffsetCell
+    unsafe { *REALTIME_OFFSET.0.get() }
+}
+
+/// Return the realtime clock, in microseconds since the Unix epoch.
+///
+/// # Errors
+/// Propagates kernel errors.
+pub fn realtime_us() -> Result<u64, Error> {
+    Ok(uptime_us()?.saturating_add_signed(realtime_offset_us()))
+}
+
+/// Set the realtime clock to `now_us` microseconds since the Unix epoch.
+///
+/// # Errors
+/// Propagates kernel errors.
+pub fn set_realtime_us(now_us: u64) -> Result<(), Error> {
+    let offset = now_us.wrapping_sub(uptime_us()?).cast_signed();
+    // SAFETY: see OffsetCell
+    unsafe { *REALTIME_OFFSET.0.get() = offset };
+    Ok(())
+}
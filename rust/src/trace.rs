@@ -0,0 +1,350 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Event tracing into a shared memory ring
+//!
+//! Once a shared memory is bound with [`bind_shm`], each [`event!`] writes a
+//! record (event identifier, argument and kernel uptime) into a ring laid out
+//! in it, overwriting the oldest records once full. A supervisor task, or a
+//! debugger, copies the shared memory and decodes it with [`decode`]. The
+//! records of several tasks are merged on their timestamps, the uptime being
+//! the same for all of them.
+//!
+//! ```ignore
+//! use shield::trace::{self, event};
+//!
+//! trace::bind_shm(Shm::new(TRACE_SHM)?.map(0)?)?;
+//! event!(IRQ_ENTER, irq);
+//! handle(irq);
+//! event!(IRQ_EXIT);
+//! ```
+//!
+//! The shared memory holds a [`HEADER_LEN`] bytes header, made of
+//! little-endian `u32` words (magic `TRCE`, [`VERSION`], capacity in records,
+//! number of records written, wrapping, and the task label), followed by the
+//! records. Each record is a CTF event, its timestamp (`u64`, microseconds)
+//! and identifier (`u32`) making the event header and its argument (`u32`)
+//! the payload, described by [`CTF_METADATA`]: host tools such as babeltrace
+//! read the records returned by [`Trace::events`], encoded with
+//! [`Event::to_bytes`], as a CTF stream.
+//!
+//! Recording the timestamp of an event costs a `get_cycle()` syscall and an
+//! exchange area copy. Nothing is recorded until a shared memory is bound.
+
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+
+/// Layout version
+pub const VERSION: u32 = 1;
+
+/// Shared memory header length, in bytes
+pub const HEADER_LEN: usize = 32;
+
+/// Record length, in bytes
+pub const RECORD_LEN: usize = 16;
+
+/// Layout marker ("TRCE")
+const MAGIC: u32 = 0x5452_4345;
+
+/// Predefined event identifiers, applications numbering theirs from
+/// [`USER`](ids::USER)
+pub mod ids {
+    /// IRQ handling start, the argument being the IRQ number
+    pub const IRQ_ENTER: u32 = 1;
+    /// IRQ handling end
+    pub const IRQ_EXIT: u32 = 2;
+    /// The task waits for an event
+    pub const WAIT: u32 = 3;
+    /// The task resumes after a wait
+    pub const WAKE: u32 = 4;
+    /// First application event identifier
+    pub const USER: u32 = 0x100;
+}
+
+/// CTF 1.8 metadata of the records, declaring the predefined events
+///
+/// Applications append an `event` block per identifier of their own, in the
+/// same form.
+pub const CTF_METADATA: &str = r#"/* CTF 1.8 */
+typealias integer { size = 32; align = 8; signed = false; byte_order = le; } := uint32_t;
+trace {
+    major = 1;
+    minor = 8;
+    byte_order = le;
+};
+clock {
+    name = uptime;
+    freq = 1000000;
+};
+typealias integer {
+    size = 64; align = 8; signed = false; byte_order = le; map = clock.uptime.value;
+} := uptime_t;
+stream {
+    event.header := struct {
+        uptime_t timestamp;
+        uint32_t id;
+    };
+};
+event { name = "irq_enter"; id = 1; fields := struct { uint32_t arg; }; };
+event { name = "irq_exit"; id = 2; fields := struct { uint32_t arg; }; };
+event { name = "wait"; id = 3; fields := struct { uint32_t arg; }; };
+event { name = "wake"; id = 4; fields := struct { uint32_t arg; }; };
+"#;
+
+/// Traced event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    /// Uptime at which the event was recorded, in microseconds
+    pub timestamp_us: u64,
+    /// Event identifier
+    pub id: u32,
+    /// Event argument, 0 if none
+    pub arg: u32,
+}
+
+impl Event {
+    /// Encode the event as a record, the CTF event layout.
+    pub fn to_bytes(&self) -> [u8; RECORD_LEN] {
+        let mut record = [0; RECORD_LEN];
+        record[..8].copy_from_slice(&self.timestamp_us.to_le_bytes());
+        record[8..12].copy_from_slice(&self.id.to_le_bytes());
+        record[12..].copy_from_slice(&self.arg.to_le_bytes());
+        record
+    }
+
+    /// Decode a record written by [`Event::to_bytes`].
+    pub fn from_bytes(record: &[u8; RECORD_LEN]) -> Self {
+        let word = |offset: usize| {
+            u32::from_le_bytes([
+                record[offset],
+                record[offset + 1],
+                record[offset + 2],
+                record[offset + 3],
+            ])
+        };
+        Self {
+            timestamp_us: u64::from(word(0)) | (u64::from(word(4)) << 32),
+            id: word(8),
+            arg: word(12),
+        }
+    }
+}
+
+/// Trace ring, as copied from the shared memory
+pub struct Trace<'a> {
+    label: u32,
+    total: u32,
+    records: &'a [u8],
+}
+
+impl Trace<'_> {
+    /// Return the label of the traced task.
+    pub fn label(&self) -> u32 {
+        self.label
+    }
+
+    /// Return the number of events recorded, including the overwritten
+    /// ones, wrapping.
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// Iterate over the held events, oldest first.
+    pub fn events(&self) -> impl Iterator<Item = Event> + '_ {
+        let capacity = self.records.len() / RECORD_LEN;
+        let held = (self.total as usize).min(capacity);
+        let first = self.total as usize - held;
+        (first..first + held).map(move |index| {
+            let offset = (index % capacity) * RECORD_LEN;
+            let mut record = [0; RECORD_LEN];
+            record.copy_from_slice(&self.records[offset..offset + RECORD_LEN]);
+            Event::from_bytes(&record)
+        })
+    }
+}
+
+/// Decode a copy of a trace shared memory.
+///
+/// A copy taken while the task records events may hold an overwritten oldest
+/// record.
+///
+/// # Errors
+/// Returns a `Status::Invalid` error if `shm` does not hold a trace ring of
+/// this layout version, or is too small for its capacity.
+pub fn decode(shm: &[u8]) -> Result<Trace<'_>, Error> {
+    let invalid = Error::new(Subsystem::Trace, Status::Invalid);
+    let word = |index: usize| {
+        shm.get(index * 4..index * 4 + 4)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u32::from_le_bytes)
+            .ok_or(invalid)
+    };
+    if word(0)? != MAGIC || word(1)? != VERSION {
+        return Err(invalid);
+    }
+    let capacity = word(2)? as usize;
+    let records = capacity
+        .checked_mul(RECORD_LEN)
+        .and_then(|len| shm.get(HEADER_LEN..HEADER_LEN + len))
+        .filter(|records| !records.is_empty())
+        .ok_or(invalid)?;
+    Ok(Trace {
+        label: word(4)?,
+        total: word(3)?,
+        records,
+    })
+}
+
+#[cfg(not(feature = "host-std"))]
+pub use ring::{bind_shm, record};
+
+#[cfg(not(feature = "host-std"))]
+mod ring {
+    use core::cell::UnsafeCell;
+    use core::sync::atomic::{Ordering, fence};
+    use uapi::systypes::Status;
+
+    use super::{HEADER_LEN, MAGIC, RECORD_LEN, VERSION};
+    use crate::error::{Error, Subsystem};
+    use crate::shm::{Mapped, Shm};
+
+    /// Bound ring, `base` being 0 if none
+    struct Sink {
+        base: usize,
+        capacity: u32,
+        total: u32,
+    }
+
+    struct SinkCell(UnsafeCell<Sink>);
+
+    // SAFETY: a Sentry task is single-threaded, and the sink is never
+    // borrowed across calls of `with_sink`
+    unsafe impl Sync for SinkCell {}
+
+    static SINK: SinkCell = SinkCell(UnsafeCell::new(Sink {
+        base: 0,
+        capacity: 0,
+        total: 0,
+    }));
+
+    /// Execute `f` with an exclusive access to the sink
+    fn with_sink<R>(f: impl FnOnce(&mut Sink) -> R) -> R {
+        // SAFETY: see SinkCell, `f` never reaches `with_sink`
+        f(unsafe { &mut *SINK.0.get() })
+    }
+
+    /// Write the header word `index`.
+    ///
+    /// # Safety
+    /// `base` must be the base address of the bound shared memory.
+    unsafe fn write_header(base: usize, index: usize, value: u32) {
+        // SAFETY: see the function contract, the header being smaller than
+        // the shared memory
+        unsafe {
+            core::ptr::with_exposed_provenance_mut::<u32>(base)
+                .add(index)
+                .write_volatile(value.to_le());
+        }
+    }
+
+    /// Record the traced events into the given shared memory, replacing the
+    /// previously bound one, if any.
+    ///
+    /// The shared memory is consumed, and can't be unmapped anymore, so that
+    /// events can be recorded at any time.
+    ///
+    /// # Errors
+    /// Returns a `Status::Invalid` error if the shared memory is too small
+    /// for a record, misaligned or not writable, or propagates kernel errors
+    /// if information retrieval fails.
+    pub fn bind_shm(mut shm: Shm<Mapped>) -> Result<(), Error> {
+        let invalid = Error::new(Subsystem::Trace, Status::Invalid);
+        let base = shm.base_address()?;
+        let capacity = shm.length()?.saturating_sub(HEADER_LEN) / RECORD_LEN;
+        if capacity == 0 || base % align_of::<u32>() != 0 || !shm.is_writable() {
+            return Err(invalid);
+        }
+        let capacity = u32::try_from(capacity).map_err(|_| invalid)?;
+        // the label only names the stream, a trace without it being usable
+        let label = crate::process::current_label().unwrap_or_default();
+
+        with_sink(|sink| {
+            // SAFETY: the header is in the shared memory
+            unsafe {
+                write_header(base, 2, capacity);
+                write_header(base, 3, 0);
+                write_header(base, 4, label);
+                for index in 5..HEADER_LEN / 4 {
+                    write_header(base, index, 0);
+                }
+                write_header(base, 1, VERSION);
+                write_header(base, 0, MAGIC);
+            }
+            *sink = Sink {
+                base,
+                capacity,
+                total: 0,
+            };
+        });
+        Ok(())
+    }
+
+    /// Record the event `id`, along with its argument. Use [`event!`] instead.
+    ///
+    /// [`event!`]: super::event
+    pub fn record(id: u32, arg: u32) {
+        if with_sink(|sink| sink.base == 0) {
+            return;
+        }
+        let timestamp_us = crate::time::uptime_us().unwrap_or_default();
+        let record = super::Event {
+            timestamp_us,
+            id,
+            arg,
+        }
+        .to_bytes();
+
+        with_sink(|sink| {
+            let offset = HEADER_LEN + (sink.total % sink.capacity) as usize * RECORD_LEN;
+            let slot = core::ptr::with_exposed_provenance_mut::<u8>(sink.base + offset);
+            // SAFETY: the record is below the capacity, in the shared memory
+            // bound forever
+            unsafe {
+                for (index, &byte) in record.iter().enumerate() {
+                    slot.add(index).write_volatile(byte);
+                }
+            }
+            fence(Ordering::Release);
+            sink.total = sink.total.wrapping_add(1);
+            // SAFETY: see above
+            unsafe { write_header(sink.base, 3, sink.total) };
+        });
+    }
+}
+
+/// Record a trace event, given its identifier, [predefined](ids) or from the
+/// caller scope, and an optional `u32` argument.
+///
+/// ```ignore
+/// trace::event!(IRQ_ENTER, irq);
+/// trace::event!(FRAME_RECEIVED, len as u32);
+/// ```
+#[cfg(not(feature = "host-std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __trace_event {
+    ($id:expr) => {
+        $crate::trace::event!($id, 0)
+    };
+    ($id:expr, $arg:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::trace::ids::*;
+        $crate::trace::record($id, $arg)
+    }};
+}
+
+#[cfg(not(feature = "host-std"))]
+#[doc(inline)]
+pub use crate::__trace_event as event;
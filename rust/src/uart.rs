@@ -0,0 +1,145 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Async UART driver
+//!
+//! The driver implements [`embedded_io_async::Read`] and
+//! [`embedded_io_async::Write`] on top of the UART interrupt, delivered by the
+//! kernel as an IRQ event and awaited through the [`crate::executor`].
+//!
+//! UART registers are SoC specific: the board support code gives access to
+//! the mapped device through the [`UartRegisters`] trait.
+//!
+//! ```ignore
+//! let mut uart = Uart::new(Usart1::mapped()?, USART1_IRQ)?;
+//! uart.write_all(b"AT\r\n").await?;
+//! let len = uart.read(&mut response).await?;
+//! ```
+
+use embedded_io_async::{ErrorType, Read, Write};
+use uapi::systypes::{EventType, Status};
+
+use crate::error::{Error, Subsystem};
+use crate::executor;
+
+/// Access to the registers of a mapped UART device
+pub trait UartRegisters {
+    /// Read a received byte, if any.
+    fn try_read(&mut self) -> Option<u8>;
+
+    /// Write a byte if the transmit register is empty, returning whether it
+    /// has been written.
+    fn try_write(&mut self, byte: u8) -> bool;
+
+    /// Check whether all the written bytes have been transmitted.
+    fn is_flushed(&self) -> bool;
+
+    /// Enable the receive interrupt (RX not empty).
+    fn enable_rx_interrupt(&mut self, enabled: bool);
+
+    /// Enable the transmit interrupts (TX empty and transmission complete).
+    fn enable_tx_interrupt(&mut self, enabled: bool);
+}
+
+/// Async UART driver
+pub struct Uart<R> {
+    regs: R,
+    irq: u16,
+}
+
+impl<R: UartRegisters> Uart<R> {
+    /// Create the driver, unmasking the UART interrupt.
+    ///
+    /// # Errors
+    /// Propagates kernel errors if the interrupt can't be enabled.
+    pub fn new(mut regs: R, irq: u16) -> Result<Self, Error> {
+        regs.enable_rx_interrupt(false);
+        regs.enable_tx_interrupt(false);
+        let uart = Self { regs, irq };
+        uart.check(crate::sys::syscall::irq_enable(irq))?;
+        Ok(uart)
+    }
+
+    /// Release the UART registers, masking the UART interrupt.
+    pub fn release(self) -> R {
+        let _ = crate::sys::syscall::irq_disable(self.irq);
+        self.regs
+    }
+
+    /// Wait for the UART interrupt.
+    async fn wait_irq(&mut self) -> Result<(), Error> {
+        executor::wait_event_from(EventType::Irq, u32::from(self.irq)).await;
+        // the interrupt is masked by the kernel until acknowledged
+        self.check(crate::sys::syscall::irq_acknowledge(self.irq))
+    }
+
+    fn check(&self, status: Status) -> Result<(), Error> {
+        match status {
+            Status::Ok => Ok(()),
+            status => Err(Error::new(Subsystem::Uart, status).with_handle(u32::from(self.irq))),
+        }
+    }
+}
+
+impl<R> ErrorType for Uart<R> {
+    type Error = Error;
+}
+
+impl<R: UartRegisters> Read for Uart<R> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let mut len = 0;
+            while let Some(slot) = buf.get_mut(len) {
+                let Some(byte) = self.regs.try_read() else {
+                    break;
+                };
+                *slot = byte;
+                len += 1;
+            }
+            if len > 0 {
+                return Ok(len);
+            }
+
+            self.regs.enable_rx_interrupt(true);
+            let waited = self.wait_irq().await;
+            self.regs.enable_rx_interrupt(false);
+            waited?;
+        }
+    }
+}
+
+impl<R: UartRegisters> Write for Uart<R> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let len = buf
+                .iter()
+                .take_while(|&&byte| self.regs.try_write(byte))
+                .count();
+            if len > 0 {
+                return Ok(len);
+            }
+
+            self.regs.enable_tx_interrupt(true);
+            let waited = self.wait_irq().await;
+            self.regs.enable_tx_interrupt(false);
+            waited?;
+        }
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        while !self.regs.is_flushed() {
+            self.regs.enable_tx_interrupt(true);
+            let waited = self.wait_irq().await;
+            self.regs.enable_tx_interrupt(false);
+            waited?;
+        }
+        Ok(())
+    }
+}
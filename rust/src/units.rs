@@ -0,0 +1,378 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Frequency, duration and fixed-point types
+//!
+//! Frequencies and durations are carried in a type per unit, so that a
+//! frequency in kHz can't be passed where Hz are expected: the conversions
+//! to the finer units are implicit through [`From`], the lossy ones to the
+//! coarser units are explicit. [`UnitsExt`] builds them from integers.
+//!
+//! ```ignore
+//! use shield::units::UnitsExt;
+//!
+//! sdmmc_regs.set_clock(400.khz().into());
+//! time::sleep(2.secs())?;
+//! ```
+//!
+//! [`Fixed`] is a signed 16.16 fixed-point number, for ratios such as PWM
+//! duty cycles and clock dividers.
+
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+macro_rules! unit {
+    ($(#[$doc:meta] $name:ident($repr:ty), $suffix:literal;)+) => {
+        $(
+            #[$doc]
+            #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name(pub $repr);
+
+            impl $name {
+                /// Create a value from its count of units.
+                pub const fn new(value: $repr) -> Self {
+                    Self(value)
+                }
+
+                /// Return the count of units.
+                pub const fn get(self) -> $repr {
+                    self.0
+                }
+
+                /// Return the sum, or `None` on overflow.
+                pub const fn checked_add(self, other: Self) -> Option<Self> {
+                    match self.0.checked_add(other.0) {
+                        Some(value) => Some(Self(value)),
+                        None => None,
+                    }
+                }
+
+                /// Return the difference, or `None` on underflow.
+                pub const fn checked_sub(self, other: Self) -> Option<Self> {
+                    match self.0.checked_sub(other.0) {
+                        Some(value) => Some(Self(value)),
+                        None => None,
+                    }
+                }
+            }
+
+            impl Add for $name {
+                type Output = Self;
+
+                fn add(self, other: Self) -> Self {
+                    Self(self.0 + other.0)
+                }
+            }
+
+            impl Sub for $name {
+                type Output = Self;
+
+                fn sub(self, other: Self) -> Self {
+                    Self(self.0 - other.0)
+                }
+            }
+
+            impl Mul<$repr> for $name {
+                type Output = Self;
+
+                fn mul(self, factor: $repr) -> Self {
+                    Self(self.0 * factor)
+                }
+            }
+
+            impl Div<$repr> for $name {
+                type Output = Self;
+
+                fn div(self, divisor: $repr) -> Self {
+                    Self(self.0 / divisor)
+                }
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    write!(f, "{} {}", self.0, $suffix)
+                }
+            }
+        )+
+    };
+}
+
+unit! {
+    /// Frequency, in Hz
+    Hertz(u32), "Hz";
+    /// Frequency, in kHz
+    KiloHertz(u32), "kHz";
+    /// Frequency, in MHz
+    MegaHertz(u32), "MHz";
+    /// Duration, in microseconds
+    Microseconds(u64), "us";
+    /// Duration, in milliseconds
+    Milliseconds(u32), "ms";
+    /// Duration, in seconds
+    Seconds(u32), "s";
+}
+
+/// Implement the conversion of `$from` to the finer `$to` unit, `$factor`
+/// times smaller, saturating.
+macro_rules! finer {
+    ($($from:ident => $to:ident($repr:ty), $factor:expr;)+) => {
+        $(
+            impl From<$from> for $to {
+                fn from(value: $from) -> Self {
+                    Self(<$repr>::from(value.0).saturating_mul($factor))
+                }
+            }
+        )+
+    };
+}
+
+finer! {
+    KiloHertz => Hertz(u32), 1_000;
+    MegaHertz => Hertz(u32), 1_000_000;
+    MegaHertz => KiloHertz(u32), 1_000;
+    Seconds => Milliseconds(u32), 1_000;
+    Seconds => Microseconds(u64), 1_000_000;
+    Milliseconds => Microseconds(u64), 1_000;
+}
+
+impl Hertz {
+    /// Return the period of the frequency, rounded down, or `None` for a
+    /// null frequency.
+    pub const fn period(self) -> Option<Microseconds> {
+        match self.0 {
+            0 => None,
+            hz => Some(Microseconds(1_000_000 / hz as u64)),
+        }
+    }
+
+    /// Return the frequency in kHz, rounded down.
+    pub const fn to_khz(self) -> KiloHertz {
+        KiloHertz(self.0 / 1_000)
+    }
+
+    /// Return the frequency in MHz, rounded down.
+    pub const fn to_mhz(self) -> MegaHertz {
+        MegaHertz(self.0 / 1_000_000)
+    }
+}
+
+impl Microseconds {
+    /// Return the duration in milliseconds, rounded down and saturating.
+    pub const fn to_millis(self) -> Milliseconds {
+        let ms = self.0 / 1_000;
+        Milliseconds(if ms > u32::MAX as u64 {
+            u32::MAX
+        } else {
+            ms as u32
+        })
+    }
+
+    /// Return the duration in milliseconds, rounded up and saturating, e.g.
+    /// for timeouts.
+    pub const fn to_millis_ceil(self) -> Milliseconds {
+        Microseconds(self.0.saturating_add(999)).to_millis()
+    }
+}
+
+impl Milliseconds {
+    /// Return the duration in seconds, rounded down.
+    pub const fn to_secs(self) -> Seconds {
+        Seconds(self.0 / 1_000)
+    }
+}
+
+/// Construction of unit values from integers
+///
+/// ```ignore
+/// assert_eq!(Hertz::from(8.mhz()), 8_000_000.hz());
+/// ```
+pub trait UnitsExt {
+    /// Return the value in Hz.
+    fn hz(self) -> Hertz;
+    /// Return the value in kHz.
+    fn khz(self) -> KiloHertz;
+    /// Return the value in MHz.
+    fn mhz(self) -> MegaHertz;
+    /// Return the value in microseconds.
+    fn us(self) -> Microseconds;
+    /// Return the value in milliseconds.
+    fn ms(self) -> Milliseconds;
+    /// Return the value in seconds.
+    fn secs(self) -> Seconds;
+}
+
+impl UnitsExt for u32 {
+    fn hz(self) -> Hertz {
+        Hertz(self)
+    }
+
+    fn khz(self) -> KiloHertz {
+        KiloHertz(self)
+    }
+
+    fn mhz(self) -> MegaHertz {
+        MegaHertz(self)
+    }
+
+    fn us(self) -> Microseconds {
+        Microseconds(u64::from(self))
+    }
+
+    fn ms(self) -> Milliseconds {
+        Milliseconds(self)
+    }
+
+    fn secs(self) -> Seconds {
+        Seconds(self)
+    }
+}
+
+/// Signed 16.16 fixed-point number
+///
+/// Arithmetic saturates at [`Fixed::MIN`] and [`Fixed::MAX`], and the
+/// products and quotients are rounded towards zero.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed(i32);
+
+impl Fixed {
+    /// Number of fractional bits
+    pub const FRAC_BITS: u32 = 16;
+    /// Zero
+    pub const ZERO: Self = Self(0);
+    /// One
+    pub const ONE: Self = Self(1 << Self::FRAC_BITS);
+    /// Smallest value, -32768
+    pub const MIN: Self = Self(i32::MIN);
+    /// Largest value, 32768 - 2^-16
+    pub const MAX: Self = Self(i32::MAX);
+
+    /// Create a value from its raw 16.16 representation.
+    pub const fn from_bits(bits: i32) -> Self {
+        Self(bits)
+    }
+
+    /// Return the raw 16.16 representation.
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    /// Create a value from an integer.
+    pub const fn from_int(value: i16) -> Self {
+        Self((value as i32) << Self::FRAC_BITS)
+    }
+
+    /// Return `numerator / denominator`, or `None` if the denominator is
+    /// null or the quotient doesn't fit.
+    pub const fn from_ratio(numerator: i32, denominator: i32) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let quotient = ((numerator as i64) << Self::FRAC_BITS) / denominator as i64;
+        if quotient > i32::MAX as i64 || quotient < i32::MIN as i64 {
+            return None;
+        }
+        Some(Self(quotient as i32))
+    }
+
+    /// Return the integer part, rounded towards negative infinity.
+    pub const fn floor(self) -> i32 {
+        self.0 >> Self::FRAC_BITS
+    }
+
+    /// Return the nearest integer, halves rounded up.
+    pub const fn round(self) -> i32 {
+        ((self.0 as i64 + (1 << (Self::FRAC_BITS - 1))) >> Self::FRAC_BITS) as i32
+    }
+
+    /// Return `value` scaled by the number, rounded towards zero and
+    /// saturating, e.g. a timer period by a duty cycle.
+    pub const fn scale(self, value: u32) -> u32 {
+        let product = (self.0 as i64 * value as i64) >> Self::FRAC_BITS;
+        if product < 0 {
+            0
+        } else if product > u32::MAX as i64 {
+            u32::MAX
+        } else {
+            product as u32
+        }
+    }
+
+    const fn saturate(value: i64) -> Self {
+        if value > i32::MAX as i64 {
+            Self::MAX
+        } else if value < i32::MIN as i64 {
+            Self::MIN
+        } else {
+            Self(value as i32)
+        }
+    }
+}
+
+impl From<i16> for Fixed {
+    fn from(value: i16) -> Self {
+        Self::from_int(value)
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0.saturating_add(other.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0))
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.saturating_neg())
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::saturate(i64::from(self.0) * i64::from(other.0) / i64::from(Self::ONE.0))
+    }
+}
+
+impl Div for Fixed {
+    type Output = Self;
+
+    /// # Panics
+    /// Panics if `other` is zero.
+    fn div(self, other: Self) -> Self {
+        Self::saturate((i64::from(self.0) << Self::FRAC_BITS) / i64::from(other.0))
+    }
+}
+
+impl fmt::Display for Fixed {
+    /// Display the number in decimal, with 4 fractional digits unless a
+    /// precision of up to 9 digits is given.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(4).min(9);
+        let scale = 10u64.pow(precision as u32);
+        let bits = i64::from(self.0).unsigned_abs();
+        let mut int = bits >> Self::FRAC_BITS;
+        let mut frac = ((bits & 0xffff) * scale + (1 << (Self::FRAC_BITS - 1))) >> Self::FRAC_BITS;
+        if frac == scale {
+            (int, frac) = (int + 1, 0);
+        }
+        let sign = if self.0 < 0 { "-" } else { "" };
+        match precision {
+            0 => write!(f, "{sign}{int}"),
+            _ => write!(f, "{sign}{int}.{frac:0precision$}"),
+        }
+    }
+}
@@ -0,0 +1,347 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! A/B firmware updates
+//!
+//! [`Slots`] holds the active slot, the running image, and the inactive one,
+//! which receives the update. [`Slots::begin`] erases the inactive slot and
+//! returns an [`ImageWriter`], fed with the image chunks as they are received
+//! (from an SHM with [`ImageWriter::write_from_shm`]). [`ImageWriter::finish`]
+//! hashes the image back from the flash and checks the digest and signature
+//! with an [`ImageVerifier`], before sealing the slot.
+//!
+//! Each slot ends with a trailer, shared with the bootloader:
+//!
+//! - the image record: magic, image length and SHA-256 digest,
+//! - the `pending` flag, set by [`Slots::request_swap`], asking the
+//!   bootloader to boot the slot on trial at the next reset,
+//! - the `confirmed` flag, set by the trial image with [`Slots::confirm`],
+//! - the `reverted` flag, set by the bootloader when it swaps an unconfirmed
+//!   trial image back out.
+//!
+//! The flags are write-once program units, the bootloader swapping the
+//! trailers along with the images. Sentry providing no reset syscall, the
+//! swap happens at the next reset, triggered by the caller the platform way,
+//! [`Slots::boot_state`] reporting the outcome after the first boot.
+//!
+//! ```ignore
+//! let mut slots = Slots::new(active, inactive);
+//! let mut writer = slots.begin(image_len)?;
+//! while let Some(len) = next_chunk(&mut shm)? {
+//!     writer.write_from_shm(&mut shm, 0, len)?;
+//! }
+//! writer.finish(&signature, &verifier)?;
+//! slots.request_swap()?;
+//! ```
+
+use uapi::systypes::Status;
+
+use crate::error::{Error, Subsystem};
+use crate::sha256::{DIGEST_LEN, Sha256};
+#[cfg(all(feature = "shm", not(feature = "host-std")))]
+use crate::shm::{Mapped, Shm};
+use crate::storage::{Flash, FlashDevice, MAX_WRITE_SIZE};
+
+const IMAGE_MAGIC: u32 = 0x4742_4153;
+const RECORD_LEN: usize = 8 + DIGEST_LEN;
+const FLAG: u8 = 0xa5;
+const ERASED: u8 = 0xff;
+
+/// Image signature check
+pub trait ImageVerifier {
+    /// Check `signature` against the image `digest`.
+    fn verify(&self, digest: &[u8; DIGEST_LEN], signature: &[u8]) -> bool;
+}
+
+/// Verifier accepting the images of a known digest, the signature being
+/// ignored
+pub struct ExpectedDigest(pub [u8; DIGEST_LEN]);
+
+impl ImageVerifier for ExpectedDigest {
+    fn verify(&self, digest: &[u8; DIGEST_LEN], _signature: &[u8]) -> bool {
+        *digest == self.0
+    }
+}
+
+/// Running image state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootState {
+    /// Permanent image
+    Confirmed,
+    /// Updated image booted on trial, to be confirmed with
+    /// [`Slots::confirm`], the bootloader reverting it at the next reset
+    /// otherwise
+    Trial,
+    /// The previous update was not confirmed and has been reverted
+    RolledBack,
+}
+
+fn error(status: Status) -> Error {
+    Error::new(Subsystem::Update, status)
+}
+
+/// Slot trailer layout
+struct Trailer {
+    /// Trailer offset in the slot
+    offset: usize,
+    /// Flag unit length
+    unit: usize,
+}
+
+impl Trailer {
+    fn of<D: FlashDevice>(slot: &Flash<D>) -> Self {
+        let unit = slot.write_size().max(8);
+        let len = RECORD_LEN.next_multiple_of(unit) + 3 * unit;
+        Self {
+            offset: slot.capacity().saturating_sub(len),
+            unit,
+        }
+    }
+
+    fn pending(&self) -> usize {
+        self.offset + RECORD_LEN.next_multiple_of(self.unit)
+    }
+
+    fn confirmed(&self) -> usize {
+        self.pending() + self.unit
+    }
+
+    fn reverted(&self) -> usize {
+        self.confirmed() + self.unit
+    }
+}
+
+/// Slot content, from its trailer
+struct SlotState {
+    /// Image length, if the slot holds a sealed image
+    image: Option<usize>,
+    pending: bool,
+    confirmed: bool,
+    reverted: bool,
+}
+
+fn flag<D: FlashDevice>(slot: &Flash<D>, offset: usize) -> Result<bool, Error> {
+    let mut byte = [0];
+    slot.read(offset, &mut byte)?;
+    Ok(byte[0] != ERASED)
+}
+
+fn slot_state<D: FlashDevice>(slot: &Flash<D>) -> Result<SlotState, Error> {
+    let trailer = Trailer::of(slot);
+    let mut record = [0; 8];
+    slot.read(trailer.offset, &mut record)?;
+    let magic = u32::from_le_bytes([record[0], record[1], record[2], record[3]]);
+    let len = u32::from_le_bytes([record[4], record[5], record[6], record[7]]) as usize;
+    Ok(SlotState {
+        image: (magic == IMAGE_MAGIC).then_some(len),
+        pending: flag(slot, trailer.pending())?,
+        confirmed: flag(slot, trailer.confirmed())?,
+        reverted: flag(slot, trailer.reverted())?,
+    })
+}
+
+/// Active and inactive slots, see the [module](self) documentation
+pub struct Slots<D: FlashDevice> {
+    active: Flash<D>,
+    inactive: Flash<D>,
+}
+
+impl<D: FlashDevice> Slots<D> {
+    /// Build the slots from the running image region, `active`, and the
+    /// update region, `inactive`.
+    pub fn new(active: Flash<D>, inactive: Flash<D>) -> Self {
+        Self { active, inactive }
+    }
+
+    /// Give the slot regions back, active first.
+    pub fn release(self) -> (Flash<D>, Flash<D>) {
+        (self.active, self.inactive)
+    }
+
+    /// Return the largest image length.
+    pub fn max_image_len(&self) -> usize {
+        Trailer::of(&self.inactive).offset
+    }
+
+    /// Return the running image state.
+    ///
+    /// An active slot without trailer (a factory image) is confirmed.
+    ///
+    /// # Errors
+    /// Propagates the flash errors.
+    pub fn boot_state(&self) -> Result<BootState, Error> {
+        let active = slot_state(&self.active)?;
+        if active.image.is_some() && active.pending && !active.confirmed {
+            return Ok(BootState::Trial);
+        }
+        let inactive = slot_state(&self.inactive)?;
+        if inactive.image.is_some() && inactive.pending && inactive.reverted {
+            return Ok(BootState::RolledBack);
+        }
+        Ok(BootState::Confirmed)
+    }
+
+    /// Make the running trial image permanent, which is a no-op if it is
+    /// confirmed already.
+    ///
+    /// # Errors
+    /// Propagates the flash errors.
+    pub fn confirm(&mut self) -> Result<(), Error> {
+        if self.boot_state()? != BootState::Trial {
+            return Ok(());
+        }
+        let offset = Trailer::of(&self.active).confirmed();
+        self.active.write(offset, &[FLAG])
+    }
+
+    /// Erase the inactive slot and start writing an image of `len` bytes.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if the image doesn't fit in the slot, or
+    /// propagates the flash errors.
+    pub fn begin(&mut self, len: usize) -> Result<ImageWriter<'_, D>, Error> {
+        if len > self.max_image_len() {
+            return Err(error(Status::Invalid));
+        }
+        self.inactive.erase(0, self.inactive.capacity())?;
+        Ok(ImageWriter {
+            slot: &mut self.inactive,
+            len,
+            written: 0,
+            unit: [0; MAX_WRITE_SIZE],
+            buffered: 0,
+        })
+    }
+
+    /// Ask the bootloader to boot the inactive slot image on trial at the
+    /// next reset.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if the inactive slot holds no sealed image,
+    /// or propagates the flash errors.
+    pub fn request_swap(&mut self) -> Result<(), Error> {
+        let state = slot_state(&self.inactive)?;
+        if state.image.is_none() {
+            return Err(error(Status::Invalid));
+        }
+        if state.pending {
+            return Ok(());
+        }
+        let offset = Trailer::of(&self.inactive).pending();
+        self.inactive.write(offset, &[FLAG])
+    }
+}
+
+/// Image being written to the inactive slot, see [`Slots::begin`]
+pub struct ImageWriter<'a, D: FlashDevice> {
+    slot: &'a mut Flash<D>,
+    len: usize,
+    written: usize,
+    /// Program unit being filled, the chunks being unaligned
+    unit: [u8; MAX_WRITE_SIZE],
+    buffered: usize,
+}
+
+impl<D: FlashDevice> ImageWriter<'_, D> {
+    /// Return the number of bytes written so far.
+    pub fn written(&self) -> usize {
+        self.written + self.buffered
+    }
+
+    /// Append `chunk` to the image.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if the chunk overflows the announced image
+    /// length, or propagates the flash errors.
+    pub fn write(&mut self, mut chunk: &[u8]) -> Result<(), Error> {
+        if self.written() + chunk.len() > self.len {
+            return Err(error(Status::Invalid));
+        }
+        let unit = self.slot.write_size();
+        if self.buffered > 0 {
+            let len = chunk.len().min(unit - self.buffered);
+            self.unit[self.buffered..self.buffered + len].copy_from_slice(&chunk[..len]);
+            self.buffered += len;
+            chunk = &chunk[len..];
+            if self.buffered < unit {
+                return Ok(());
+            }
+            self.slot.write(self.written, &self.unit[..unit])?;
+            self.written += unit;
+            self.buffered = 0;
+        }
+        let whole = chunk.len() - chunk.len() % unit;
+        self.slot.write(self.written, &chunk[..whole])?;
+        self.written += whole;
+        let tail = &chunk[whole..];
+        self.unit[..tail.len()].copy_from_slice(tail);
+        self.buffered = tail.len();
+        Ok(())
+    }
+
+    /// Append the `len` bytes at `offset` of `shm` to the image.
+    ///
+    /// # Errors
+    /// See [`ImageWriter::write`], the shared memory errors being propagated
+    /// as well.
+    #[cfg(all(feature = "shm", not(feature = "host-std")))]
+    pub fn write_from_shm(
+        &mut self,
+        shm: &mut Shm<Mapped>,
+        offset: usize,
+        len: usize,
+    ) -> Result<(), Error> {
+        let mut bounce = [0; 256];
+        let mut done = 0;
+        while done < len {
+            let part = (len - done).min(bounce.len());
+            shm.copy_from_shm(offset + done, &mut bounce[..part])?;
+            self.write(&bounce[..part])?;
+            done += part;
+        }
+        Ok(())
+    }
+
+    /// Check the written image and seal the slot, returning the image
+    /// digest.
+    ///
+    /// # Errors
+    /// Returns `Status::Invalid` if the image is incomplete,
+    /// `Status::Denied` if `verifier` rejects it, or propagates the flash
+    /// errors.
+    pub fn finish(
+        self,
+        signature: &[u8],
+        verifier: &impl ImageVerifier,
+    ) -> Result<[u8; DIGEST_LEN], Error> {
+        if self.written() != self.len {
+            return Err(error(Status::Invalid));
+        }
+        if self.buffered > 0 {
+            self.slot.write(self.written, &self.unit[..self.buffered])?;
+        }
+
+        // hash what the flash holds, catching program failures
+        let mut hasher = Sha256::new();
+        let mut chunk = [0; 256];
+        let mut done = 0;
+        while done < self.len {
+            let part = (self.len - done).min(chunk.len());
+            self.slot.read(done, &mut chunk[..part])?;
+            hasher.update(&chunk[..part]);
+            done += part;
+        }
+        let digest = hasher.finalize();
+        if !verifier.verify(&digest, signature) {
+            return Err(error(Status::Denied));
+        }
+
+        let mut record = [0; RECORD_LEN];
+        record[..4].copy_from_slice(&IMAGE_MAGIC.to_le_bytes());
+        record[4..8].copy_from_slice(&(self.len as u32).to_le_bytes());
+        record[8..].copy_from_slice(&digest);
+        self.slot.write(Trailer::of(self.slot).offset, &record)?;
+        Ok(digest)
+    }
+}
@@ -0,0 +1,96 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Attestation report tests
+
+#![cfg(feature = "attest")]
+
+use sentry_uapi::systypes::Status;
+use shield::attest::{Kind, MAX_MEASUREMENTS, Report, ReportSigner};
+use shield::error::Error;
+use shield::sha256::Sha256;
+
+const NONCE: [u8; 32] = [0x4e; 32];
+
+/// Test signer, the signature being the digest reversed
+struct Reverse;
+
+impl ReportSigner for Reverse {
+    fn signature_len(&self) -> usize {
+        32
+    }
+
+    fn sign(&self, digest: &[u8; 32], signature: &mut [u8]) -> Result<(), Error> {
+        signature.copy_from_slice(digest);
+        signature.reverse();
+        Ok(())
+    }
+}
+
+#[test]
+fn hash_chain() {
+    let mut report = Report::new(0xbabe);
+    report.measure(Kind::Image, b"image").unwrap();
+    report.extend(Kind::Custom(7), &[0x11; 32]).unwrap();
+
+    let mut first = Vec::from([0; 32]);
+    first.extend_from_slice(&2_u32.to_le_bytes());
+    first.extend_from_slice(&Sha256::digest(b"image"));
+    let mut second = Sha256::digest(&first).to_vec();
+    second.extend_from_slice(&0x1_0007_u32.to_le_bytes());
+    second.extend_from_slice(&[0x11; 32]);
+    assert_eq!(report.chain(), Sha256::digest(&second));
+
+    for _ in 2..MAX_MEASUREMENTS {
+        report.measure(Kind::Config, b"").unwrap();
+    }
+    let err = report.measure(Kind::Config, b"").unwrap_err();
+    assert!(err.status() == Status::Busy);
+}
+
+#[test]
+fn encode_decode() {
+    let mut report = Report::new(0xbabe);
+    report.measure(Kind::Config, b"baudrate=115200").unwrap();
+
+    let mut blob = [0; 256];
+    let len = report.encode(&NONCE, None, &mut blob).unwrap();
+    assert_eq!(len, report.encoded_len(0));
+    let decoded = Report::decode(&blob[..len]).unwrap();
+    assert_eq!(decoded.report.label(), 0xbabe);
+    assert_eq!(decoded.report.chain(), report.chain());
+    assert_eq!(decoded.nonce, NONCE);
+    assert!(decoded.signature.is_empty());
+
+    let len = report.encode(&NONCE, Some(&Reverse), &mut blob).unwrap();
+    let decoded = Report::decode(&blob[..len]).unwrap();
+    let mut signature = decoded.signed_digest;
+    signature.reverse();
+    assert_eq!(decoded.signature, signature);
+
+    // a forged measurement breaks the chain
+    blob[48] ^= 1;
+    assert!(Report::decode(&blob[..len]).is_err());
+
+    let err = report.encode(&NONCE, None, &mut blob[..64]).unwrap_err();
+    assert!(err.status() == Status::Invalid);
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn collect() {
+    let kernel = shield::mock::session();
+    kernel.add_task(0xbabe, 0x1000_babe);
+    assert!(Report::collect().is_err());
+
+    shield::process::register_current(0xbabe).unwrap();
+    let report = Report::collect().unwrap();
+    assert_eq!(report.label(), 0xbabe);
+    let (kind, digest) = report.measurements().next().unwrap();
+    assert_eq!(*kind, Kind::ShieldVersion);
+    assert_eq!(
+        *digest,
+        Sha256::digest(env!("CARGO_PKG_VERSION").as_bytes())
+    );
+}
@@ -0,0 +1,146 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! I2S audio streaming tests against the fake kernel
+
+#![cfg(all(feature = "audio", feature = "mock"))]
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use sentry_uapi::systypes::dma::{GpdmaChanInt, GpdmaStreamConfig, GpdmaTransferType};
+use sentry_uapi::systypes::{EventType, SHMPermission, Status, Syscall};
+use shield::audio::{
+    AudioStream, Config, Direction, Half, I2sRegisters, SampleFormat, from_f32, to_f32,
+};
+use shield::dma::DmaStream;
+use shield::shm::Shm;
+use shield::units::Hertz;
+use shield::{executor, mock};
+
+const STREAM: u32 = 0x120;
+const LEN: usize = 16;
+
+#[derive(Clone, Default)]
+struct FakeI2s(Rc<Cell<bool>>);
+
+impl I2sRegisters for FakeI2s {
+    fn configure(&mut self, config: &Config, direction: Direction) {
+        assert_eq!(config.sample_rate, Hertz(16_000));
+        assert_eq!(direction, Direction::Capture);
+    }
+
+    fn start(&mut self) {
+        self.0.set(true);
+    }
+
+    fn stop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+const CONFIG: Config = Config {
+    sample_rate: Hertz(16_000),
+    format: SampleFormat::S16,
+    channels: 2,
+};
+
+fn stream(kernel: &mock::Session, base: usize, interrupts: u8) -> DmaStream {
+    kernel.add_dma_stream(0x20, STREAM);
+    kernel.set_dma_stream_config(
+        STREAM,
+        GpdmaStreamConfig {
+            channel: 0,
+            stream: 0,
+            controller: 0,
+            transfer_type: GpdmaTransferType::DeviceToMemory as u16,
+            source: 0x4000_3800,
+            dest: base,
+            transfer_len: LEN,
+            circular_source: false,
+            circular_dest: true,
+            interrupts,
+            is_triggered: false,
+            trigger: 0,
+            priority: 0,
+            transfer_mode: 0,
+            src_beat_len: 0,
+            dest_beat_len: 0,
+        },
+    );
+    DmaStream::new(0x20).unwrap()
+}
+
+fn dma_event(kernel: &mock::Session, flags: GpdmaChanInt) {
+    let mut data = STREAM.to_ne_bytes().to_vec();
+    data.push(flags as u8);
+    kernel.push_event(EventType::Dma, 0, &data);
+}
+
+#[test]
+fn capture() {
+    let kernel = mock::session();
+    let perms = SHMPermission::Map as u32 | SHMPermission::Read as u32;
+    let base = kernel.add_shm(0x10, 0x110, LEN, perms);
+    let stream = stream(&kernel, base, GpdmaChanInt::HalfTransfer as u8);
+    let i2s = FakeI2s::default();
+    let shm = Shm::new(0x10).unwrap().map(0).unwrap();
+    let mut mic = AudioStream::new(i2s.clone(), CONFIG, shm, stream).unwrap();
+    assert_eq!(mic.direction(), Direction::Capture);
+    mic.start().unwrap();
+    assert!(i2s.0.get());
+    assert_eq!(kernel.call_count(Syscall::DmaStartStream), 1);
+
+    let samples = std::ptr::with_exposed_provenance_mut::<i16>(base);
+    unsafe {
+        samples.write(-2);
+        samples.add(4).write(0x100);
+    }
+    dma_event(&kernel, GpdmaChanInt::HalfTransfer);
+    let half = executor::run(mic.next()).unwrap();
+    assert_eq!(half, Half::First);
+    let decoded: Vec<i32> = CONFIG.format.decode(mic.half(half)).collect();
+    assert_eq!(decoded, [-2 << 16, 0, 0, 0]);
+
+    dma_event(&kernel, GpdmaChanInt::TransferComplete);
+    let half = executor::run(mic.next()).unwrap();
+    assert_eq!(half, Half::Second);
+    assert_eq!(mic.half(half)[..2], 0x100_i16.to_le_bytes());
+
+    dma_event(&kernel, GpdmaChanInt::DmaError);
+    let error = executor::run(mic.next()).unwrap_err();
+    assert_eq!(error.status(), Status::Critical);
+    assert!(!i2s.0.get());
+    assert_eq!(kernel.call_count(Syscall::DmaSuspendStream), 1);
+}
+
+#[test]
+fn not_circular() {
+    let kernel = mock::session();
+    let perms = SHMPermission::Map as u32 | SHMPermission::Read as u32;
+    let base = kernel.add_shm(0x10, 0x110, LEN, perms);
+    // no half transfer interrupt
+    let stream = stream(&kernel, base, GpdmaChanInt::TransferComplete as u8);
+    let shm = Shm::new(0x10).unwrap().map(0).unwrap();
+    let error = AudioStream::new(FakeI2s::default(), CONFIG, shm, stream)
+        .err()
+        .unwrap();
+    assert_eq!(error.status(), Status::Invalid);
+}
+
+#[test]
+fn conversions() {
+    let mut bytes = [0; 8];
+    let samples = [i32::MIN, -1 << 8, 0x1234_5600, i32::MAX];
+    assert_eq!(SampleFormat::S24.encode(samples, &mut bytes), 2);
+    let decoded: Vec<i32> = SampleFormat::S24.decode(&bytes).collect();
+    assert_eq!(decoded, [i32::MIN, -1 << 8]);
+    assert_eq!(SampleFormat::S16.encode(samples, &mut bytes), 4);
+    let decoded: Vec<i32> = SampleFormat::S16.decode(&bytes).collect();
+    assert_eq!(decoded, [i32::MIN, -1 << 16, 0x1234_0000, 0x7fff_0000]);
+
+    assert_eq!(to_f32(i32::MIN), -1.0);
+    assert_eq!(from_f32(0.5), 1 << 30);
+    assert_eq!(from_f32(2.0), i32::MAX);
+}
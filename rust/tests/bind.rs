@@ -0,0 +1,35 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Typed resource labels tests against the fake kernel
+
+#![cfg(all(feature = "shm", feature = "mock"))]
+
+use sentry_uapi::systypes::{SHMPermission, Status};
+use shield::mock;
+
+shield::bind!("tests/bindings.toml");
+
+#[test]
+fn constants() {
+    assert_eq!(devices::UART0.label(), 1);
+    assert_eq!(shms::FRAME_BUFFER.label(), 0x10);
+    assert_eq!(dma_streams::AUDIO_TX.label(), 0x20);
+    assert_eq!(peers::CRYPTO_TASK.label(), 0xbabe);
+    assert_eq!(peers::DISPLAY_TASK.label(), 0xbeef);
+}
+
+#[test]
+fn resources() {
+    let kernel = mock::session();
+    kernel.add_task(0xbabe, 0x1000_babe);
+    kernel.add_shm(0x10, 0x110, 64, SHMPermission::Map as u32);
+    assert_eq!(peers::CRYPTO_TASK.handle().unwrap(), 0x1000_babe);
+    assert!(peers::DISPLAY_TASK.handle().is_err());
+    assert!(shms::FRAME_BUFFER.shm().unwrap().map(0).is_ok());
+
+    let error = devices::UART0.device().err().unwrap();
+    assert_eq!(error.status(), Status::Invalid);
+    assert_eq!(error.handle(), Some(1));
+}
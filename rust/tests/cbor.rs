@@ -0,0 +1,214 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! CBOR encoding tests
+
+#![cfg(feature = "cbor")]
+
+use sentry_uapi::systypes::Status;
+use shield::Subsystem;
+use shield::collections::{FixedString, FixedVec};
+use shield::encoding::cbor::{self, Decode, Decoder, Encode, Encoder, Type};
+use shield::error::Error;
+
+fn encoded(f: impl FnOnce(&mut Encoder<'_>) -> Result<(), Error>) -> Vec<u8> {
+    let mut buf = [0; 64];
+    let mut encoder = Encoder::new(&mut buf);
+    f(&mut encoder).unwrap();
+    encoder.as_slice().to_vec()
+}
+
+#[test]
+fn encode() {
+    // RFC 8949 appendix A
+    let cases: [(Vec<u8>, &[u8]); 14] = [
+        (encoded(|e| e.u8(0).map(drop)), &[0x00]),
+        (encoded(|e| e.u8(23).map(drop)), &[0x17]),
+        (encoded(|e| e.u8(24).map(drop)), &[0x18, 0x18]),
+        (
+            encoded(|e| e.u32(1_000_000).map(drop)),
+            &[0x1a, 0x00, 0x0f, 0x42, 0x40],
+        ),
+        (
+            encoded(|e| e.u64(1_000_000_000_000).map(drop)),
+            &[0x1b, 0x00, 0x00, 0x00, 0xe8, 0xd4, 0xa5, 0x10, 0x00],
+        ),
+        (encoded(|e| e.i8(-1).map(drop)), &[0x20]),
+        (encoded(|e| e.i16(-1000).map(drop)), &[0x39, 0x03, 0xe7]),
+        (
+            encoded(|e| e.f32(100_000.0).map(drop)),
+            &[0xfa, 0x47, 0xc3, 0x50, 0x00],
+        ),
+        (
+            encoded(|e| e.bool(false)?.bool(true)?.null().map(drop)),
+            &[0xf4, 0xf5, 0xf6],
+        ),
+        (encoded(|e| e.str("IETF").map(drop)), b"\x64IETF"),
+        (
+            encoded(|e| e.bytes(&[1, 2, 3, 4]).map(drop)),
+            &[0x44, 1, 2, 3, 4],
+        ),
+        (
+            encoded(|e| e.array(3)?.u8(1)?.u8(2)?.u8(3).map(drop)),
+            &[0x83, 1, 2, 3],
+        ),
+        (
+            encoded(|e| {
+                e.map(2)?
+                    .str("a")?
+                    .u8(1)?
+                    .str("b")?
+                    .array(2)?
+                    .u8(2)?
+                    .u8(3)
+                    .map(drop)
+            }),
+            &[0xa2, 0x61, b'a', 0x01, 0x61, b'b', 0x82, 0x02, 0x03],
+        ),
+        (
+            encoded(|e| {
+                e.begin_array()?
+                    .u8(1)?
+                    .array(2)?
+                    .u8(2)?
+                    .u8(3)?
+                    .end()
+                    .map(drop)
+            }),
+            &[0x9f, 0x01, 0x82, 0x02, 0x03, 0xff],
+        ),
+    ];
+    for (encoded, expected) in cases {
+        assert_eq!(encoded, expected);
+    }
+    assert_eq!(encoded(|e| e.i64(i64::MIN).map(drop))[0], 0x3b);
+    assert_eq!(encoded(|e| e.tag(1)?.u32(1_363_896_240).map(drop))[0], 0xc1);
+
+    let mut buf = [0; 4];
+    let err = Encoder::new(&mut buf).str("IETF").map(drop).unwrap_err();
+    assert_eq!(
+        (err.subsystem(), err.status()),
+        (Subsystem::Encoding, Status::Busy)
+    );
+}
+
+#[test]
+fn decode() {
+    let data = [
+        0x1a, 0x00, 0x0f, 0x42, 0x40, 0x39, 0x03, 0xe7, 0x3b, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0x64, b'I', b'E', b'T', b'F', 0x42, 1, 2, 0xf5, 0xf6, 0xfa, 0x47, 0xc3, 0x50,
+        0x00, 0xc1, 0x01,
+    ];
+    let mut d = Decoder::new(&data);
+    assert_eq!(d.datatype().unwrap(), Type::Unsigned);
+    assert_eq!(d.u32().unwrap(), 1_000_000);
+    assert_eq!(d.datatype().unwrap(), Type::Negative);
+    assert_eq!(d.i16().unwrap(), -1000);
+    assert_eq!(d.i64().unwrap(), i64::MIN);
+    assert_eq!(d.str().unwrap(), "IETF");
+    assert_eq!(d.bytes().unwrap(), [1, 2]);
+    assert!(d.bool().unwrap());
+    d.null().unwrap();
+    assert_eq!(d.datatype().unwrap(), Type::Float);
+    assert_eq!(d.f64().unwrap(), 100_000.0);
+    assert_eq!(d.tag().unwrap(), 1);
+    assert_eq!(d.u8().unwrap(), 1);
+    assert_eq!(d.position(), data.len());
+    assert_eq!(d.datatype().unwrap_err().status(), Status::Invalid);
+
+    // out of range, mismatched and truncated items
+    for data in [
+        &[0x19, 0x01, 0x00][..],
+        &[0x20],
+        &[0x1a, 0x00],
+        &[0x62, b'a'],
+        &[0x1c],
+    ] {
+        assert_eq!(
+            Decoder::new(data).u8().unwrap_err().status(),
+            Status::Invalid
+        );
+    }
+    assert!(Decoder::new(&[0x62, 0xff, 0xfe]).str().is_err());
+    assert!(cbor::from_slice::<u8>(&[0x01, 0x02]).is_err());
+}
+
+#[test]
+fn skip() {
+    let data = [
+        0xa2, 0x61, b'a', 0x01, 0x61, b'b', 0x9f, 0x02, 0xc1, 0x03, 0x42, 0, 0, 0xfb, 0, 0, 0, 0,
+        0, 0, 0, 0, 0xff, 0x07,
+    ];
+    let mut d = Decoder::new(&data);
+    d.skip().unwrap();
+    assert_eq!(d.u8().unwrap(), 7);
+
+    let nested = [0x81; 17];
+    assert!(Decoder::new(&nested).skip().is_err());
+    assert!(Decoder::new(&[0x9f, 0x01]).skip().is_err());
+    assert!(Decoder::new(&[0xff]).skip().is_err());
+}
+
+#[derive(Debug, PartialEq)]
+struct Reading<'a> {
+    sensor: &'a str,
+    values: FixedVec<i32, 4>,
+    unit: Option<FixedString<8>>,
+}
+
+impl Encode for Reading<'_> {
+    fn encode(&self, e: &mut Encoder<'_>) -> Result<(), Error> {
+        e.array(3)?
+            .str(self.sensor)?
+            .encode(&self.values)?
+            .encode(&self.unit)?;
+        Ok(())
+    }
+}
+
+impl<'b> Decode<'b> for Reading<'b> {
+    fn decode(d: &mut Decoder<'b>) -> Result<Self, Error> {
+        if d.array()? != Some(3) {
+            return Err(Error::new(Subsystem::Encoding, Status::Invalid));
+        }
+        Ok(Self {
+            sensor: d.decode()?,
+            values: d.decode()?,
+            unit: d.decode()?,
+        })
+    }
+}
+
+#[test]
+fn values() {
+    let reading = Reading {
+        sensor: "temp",
+        values: FixedVec::try_from(&[-40, 0, 125][..]).unwrap(),
+        unit: Some(FixedString::try_from("mdegC").unwrap()),
+    };
+    let mut buf = [0; 32];
+    let len = cbor::to_slice(&reading, &mut buf).unwrap();
+    assert_eq!(
+        cbor::from_slice::<Reading<'_>>(&buf[..len]).unwrap(),
+        reading
+    );
+
+    let reading = Reading {
+        unit: None,
+        ..reading
+    };
+    let len = cbor::to_slice(&reading, &mut buf).unwrap();
+    assert_eq!(buf[len - 1], 0xf6);
+    assert_eq!(
+        cbor::from_slice::<Reading<'_>>(&buf[..len]).unwrap(),
+        reading
+    );
+
+    // indefinite length arrays, and capacity
+    let values: FixedVec<u8, 2> = cbor::from_slice(&[0x9f, 0x01, 0x02, 0xff]).unwrap();
+    assert_eq!(values.as_slice(), [1, 2]);
+    assert!(cbor::from_slice::<FixedVec<u8, 2>>(&[0x83, 1, 2, 3]).is_err());
+    assert!(cbor::from_slice::<FixedString<2>>(b"\x63abc").is_err());
+    assert_eq!(cbor::from_slice::<&[u8]>(&[0x41, 0x2a]).unwrap(), [0x2a]);
+}
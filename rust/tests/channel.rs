@@ -0,0 +1,73 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Bounded channel tests
+
+use std::pin::pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use shield::channel::Bounded;
+
+#[test]
+fn fifo() {
+    let mut channel = Bounded::<u32, 3>::new();
+    assert_eq!(channel.capacity(), 3);
+    let (tx, mut rx) = channel.split();
+    let other = tx;
+    assert!(rx.try_recv().is_none());
+
+    for round in 0..4 {
+        tx.try_send(round * 10).unwrap();
+        other.try_send(round * 10 + 1).unwrap();
+        tx.try_send(round * 10 + 2).unwrap();
+        assert!(tx.is_full());
+        assert_eq!(other.try_send(99), Err(99));
+        assert_eq!(rx.try_recv(), Some(round * 10));
+        assert_eq!(rx.try_recv(), Some(round * 10 + 1));
+        assert_eq!(rx.len(), 1);
+        assert_eq!(rx.try_recv(), Some(round * 10 + 2));
+        assert!(rx.is_empty());
+    }
+}
+
+#[test]
+fn drop_items() {
+    let item = Rc::new(());
+    {
+        let channel = Bounded::<_, 4>::new();
+        channel.try_send(item.clone()).unwrap();
+        channel.try_send(item.clone()).unwrap();
+        drop(channel.try_recv());
+        assert_eq!(Rc::strong_count(&item), 2);
+    }
+    assert_eq!(Rc::strong_count(&item), 1);
+}
+
+#[test]
+fn static_channel() {
+    static EVENTS: Bounded<u8, 2> = Bounded::new();
+    EVENTS.try_send(1).unwrap();
+    assert_eq!(EVENTS.try_recv(), Some(1));
+}
+
+#[test]
+fn wait() {
+    let channel = Bounded::<u8, 1>::new();
+    let mut cx = Context::from_waker(Waker::noop());
+
+    {
+        let mut recv = pin!(channel.recv());
+        assert!(recv.as_mut().poll(&mut cx).is_pending());
+        channel.try_send(7).unwrap();
+        assert_eq!(recv.as_mut().poll(&mut cx), Poll::Ready(7));
+    }
+
+    channel.try_send(1).unwrap();
+    let mut send = pin!(channel.send(2));
+    assert!(send.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(channel.try_recv(), Some(1));
+    assert!(send.as_mut().poll(&mut cx).is_ready());
+    assert_eq!(channel.try_recv(), Some(2));
+}
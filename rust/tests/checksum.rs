@@ -0,0 +1,47 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! CRC checksums tests
+
+use shield::checksum::*;
+
+const CHECK: &[u8] = b"123456789";
+
+#[test]
+fn catalogue() {
+    assert_eq!(Crc::checksum(CRC_8_SMBUS, CHECK), 0xf4);
+    assert_eq!(Crc::checksum(CRC_8_MAXIM_DOW, CHECK), 0xa1);
+    assert_eq!(Crc::checksum(CRC_16_IBM_3740, CHECK), 0x29b1);
+    assert_eq!(Crc::checksum(CRC_16_XMODEM, CHECK), 0x31c3);
+    assert_eq!(Crc::checksum(CRC_16_KERMIT, CHECK), 0x2189);
+    assert_eq!(Crc::checksum(CRC_16_MODBUS, CHECK), 0x4b37);
+    assert_eq!(Crc::checksum(CRC_32_ISO_HDLC, CHECK), 0xcbf4_3926);
+    assert_eq!(Crc::checksum(CRC_32_ISCSI, CHECK), 0xe306_9283);
+    assert_eq!(Crc::checksum(CRC_32_MPEG_2, CHECK), 0x0376_e6e7);
+    assert_eq!(Crc::checksum(CRC_32_ISO_HDLC, b""), 0);
+}
+
+#[test]
+fn streaming() {
+    let mut crc = Crc::new(CRC_16_MODBUS);
+    crc.update(&CHECK[..4]);
+    assert_eq!(crc.value(), Crc::checksum(CRC_16_MODBUS, &CHECK[..4]));
+    crc.update(&CHECK[4..]);
+    assert_eq!(crc.algorithm(), CRC_16_MODBUS);
+    assert_eq!(crc.finalize(), 0x4b37);
+
+    // non symmetric initial value of a reflected algorithm
+    let algorithm = Algorithm {
+        init: 0x00ff,
+        ..CRC_16_KERMIT
+    };
+    let reflected = Algorithm {
+        init: 0xff00,
+        ..CRC_16_KERMIT
+    };
+    assert_ne!(
+        Crc::checksum(algorithm, CHECK),
+        Crc::checksum(reflected, CHECK)
+    );
+}
@@ -0,0 +1,112 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! COBS framing tests
+
+use sentry_uapi::systypes::Status;
+use shield::Subsystem;
+use shield::encoding::cobs::{self, Decoder, Encoder, max_encoded_len};
+
+fn encoded(packet: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0; max_encoded_len(packet.len())];
+    let len = cobs::encode(packet, &mut frame).unwrap();
+    frame.truncate(len);
+    frame
+}
+
+#[test]
+fn encode() {
+    assert_eq!(encoded(&[]), [0x01, 0x00]);
+    assert_eq!(encoded(&[0x00]), [0x01, 0x01, 0x00]);
+    assert_eq!(encoded(&[0x00, 0x00]), [0x01, 0x01, 0x01, 0x00]);
+    assert_eq!(
+        encoded(&[0x11, 0x22, 0x00, 0x33]),
+        [0x03, 0x11, 0x22, 0x02, 0x33, 0x00]
+    );
+    assert_eq!(encoded(&[0x11, 0x00]), [0x02, 0x11, 0x01, 0x00]);
+
+    // full blocks
+    let packet: Vec<u8> = (1..=254).collect();
+    let frame = encoded(&packet);
+    assert_eq!((frame[0], frame.len()), (0xff, 256));
+    let packet: Vec<u8> = (1..=255).collect();
+    let frame = encoded(&packet);
+    assert_eq!(&frame[254..], [0xfe, 0x02, 0xff, 0x00]);
+    assert_eq!(frame.len(), max_encoded_len(255));
+
+    // pieces
+    let mut frame = [0; 8];
+    let mut encoder = Encoder::new(&mut frame);
+    encoder.push(&[0x11]).unwrap();
+    encoder.push(&[0x22, 0x00]).unwrap();
+    encoder.push(&[0x33]).unwrap();
+    let len = encoder.finish().unwrap();
+    assert_eq!(frame[..len], [0x03, 0x11, 0x22, 0x02, 0x33, 0x00]);
+
+    let err = cobs::encode(&[1, 2, 3], &mut [0; 4]).unwrap_err();
+    assert_eq!(err.status(), Status::Busy);
+    assert_eq!(err.subsystem(), Subsystem::Encoding);
+}
+
+#[test]
+fn decode() {
+    let mut packets: Vec<Vec<u8>> = vec![vec![], vec![0], vec![0x11, 0x00, 0x00, 0x22]];
+    packets.push((0..=255).cycle().take(600).collect());
+    packets.push((1..=254).collect());
+    for packet in &packets {
+        let frame = encoded(packet);
+        let mut out = vec![0; packet.len()];
+        assert_eq!(cobs::decode(&frame, &mut out).unwrap(), packet.len());
+        assert_eq!(&out, packet);
+
+        // without delimiter
+        let len = cobs::decode(&frame[..frame.len() - 1], &mut out).unwrap();
+        assert_eq!(&out[..len], packet);
+
+        let mut buffer = frame.clone();
+        let len = cobs::decode_in_place(&mut buffer).unwrap();
+        assert_eq!(&buffer[..len], packet);
+    }
+
+    let mut out = [0; 8];
+    for frame in [&[][..], &[0x00], &[0x03, 0x11, 0x00], &[0x05, 0x11]] {
+        let err = cobs::decode(frame, &mut out).unwrap_err();
+        assert_eq!(err.status(), Status::Invalid);
+    }
+    let err = cobs::decode(&[0x05, 1, 2, 3, 4, 0], &mut out[..3]).unwrap_err();
+    assert_eq!(err.status(), Status::Busy);
+}
+
+#[test]
+fn stream() {
+    let mut stream = vec![0x00, 0x00, 0x42, 0x42, 0x00];
+    stream.extend(encoded(&[0x11, 0x00, 0x22]));
+    stream.extend(encoded(&[0x33; 9]));
+    stream.extend(encoded(&[]));
+    stream.extend(encoded(&[0x44; 3]));
+
+    let mut decoder = Decoder::<8>::new();
+    let mut packets = Vec::new();
+    for chunk in stream.chunks(3) {
+        decoder.feed(chunk, |packet| {
+            packets.push(packet.map(<[u8]>::to_vec).map_err(|err| err.status()))
+        });
+    }
+    assert_eq!(
+        packets,
+        [
+            Err(Status::Invalid),
+            Ok(vec![0x11, 0x00, 0x22]),
+            Err(Status::Busy),
+            Ok(vec![]),
+            Ok(vec![0x44; 3]),
+        ]
+    );
+
+    decoder.push(0x03).unwrap();
+    decoder.reset();
+    assert_eq!(decoder.push(0x02).unwrap(), None);
+    assert_eq!(decoder.push(0x55).unwrap(), None);
+    assert_eq!(decoder.push(0x00).unwrap(), Some(&[0x55][..]));
+}
@@ -0,0 +1,106 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Bounded collections tests
+
+use std::fmt::Write;
+use std::rc::Rc;
+
+use sentry_uapi::systypes::Status;
+use shield::Subsystem;
+use shield::collections::{FixedMap, FixedString, FixedVec};
+
+#[test]
+fn vec() {
+    let mut vec = FixedVec::<u32, 4>::new();
+    assert_eq!(vec.capacity(), 4);
+    vec.try_push(1).unwrap();
+    vec.try_push(3).unwrap();
+    vec.try_insert(1, 2).unwrap();
+    assert_eq!(vec.try_insert(4, 9), Err(9));
+    vec.try_extend_from_slice(&[4]).unwrap();
+    assert_eq!(*vec, [1, 2, 3, 4]);
+    assert_eq!(vec.try_push(5), Err(5));
+    let err = vec.try_extend_from_slice(&[5]).unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::Collections);
+    assert_eq!(err.status(), Status::Busy);
+
+    assert_eq!(vec.remove(0), Some(1));
+    assert_eq!(vec.swap_remove(0), Some(2));
+    assert_eq!(*vec, [4, 3]);
+    vec.retain(|&value| value != 4);
+    assert_eq!(vec.pop(), Some(3));
+    assert_eq!(vec.pop(), None);
+    assert!(FixedVec::<u8, 2>::try_from(&[1, 2, 3][..]).is_err());
+}
+
+#[test]
+fn vec_drops() {
+    let item = Rc::new(());
+    {
+        let mut vec = FixedVec::<Rc<()>, 3>::new();
+        for _ in 0..3 {
+            vec.try_push(item.clone()).unwrap();
+        }
+        assert!(vec.try_push(item.clone()).is_err());
+        let clone = vec.clone();
+        assert_eq!(Rc::strong_count(&item), 7);
+        drop(clone);
+        vec.truncate(1);
+        assert_eq!(Rc::strong_count(&item), 2);
+    }
+    assert_eq!(Rc::strong_count(&item), 1);
+}
+
+#[test]
+fn string() {
+    let mut s = FixedString::<8>::try_from("caf").unwrap();
+    s.try_push('é').unwrap();
+    assert_eq!(s.len(), 5);
+    // left unchanged
+    assert!(s.try_push_str("1234").is_err());
+    assert_eq!(s.as_str(), "café");
+    write!(s, "{}", 42).unwrap();
+    assert!(write!(s, "{}", 123).is_err());
+    assert_eq!(s.as_str(), "café42");
+
+    s.truncate(4);
+    assert_eq!(&*s, "caf");
+    assert_eq!(s.pop(), Some('f'));
+    assert_eq!(format!("{s}"), "ca");
+}
+
+#[test]
+fn map() {
+    let mut map = FixedMap::<u32, &str, 2>::new();
+    assert_eq!(map.try_insert(1, "one"), Ok(None));
+    assert_eq!(map.try_insert(2, "two"), Ok(None));
+    assert_eq!(map.try_insert(1, "uno"), Ok(Some("one")));
+    assert_eq!(map.try_insert(3, "three"), Err((3, "three")));
+    assert_eq!(map.get(&1), Some(&"uno"));
+    assert!(map.contains_key(&2));
+    *map.get_mut(&2).unwrap() = "dos";
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(map.values().copied().collect::<Vec<_>>(), ["uno", "dos"]);
+
+    assert_eq!(map.remove(&1), Some("uno"));
+    assert_eq!(map.remove(&1), None);
+    assert_eq!(map.len(), 1);
+    assert_eq!(format!("{map:?}"), "{2: \"dos\"}");
+}
+
+#[cfg(feature = "heap")]
+#[test]
+fn spill() {
+    use shield::collections::SpillVec;
+
+    let mut vec = SpillVec::<u32, 2>::new();
+    vec.try_push(1).unwrap();
+    vec.try_push(2).unwrap();
+    assert!(!vec.spilled());
+    vec.try_push(3).unwrap();
+    assert!(vec.spilled());
+    assert_eq!(*vec, [1, 2, 3]);
+    assert_eq!(vec.pop(), Some(3));
+}
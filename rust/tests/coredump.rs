@@ -0,0 +1,88 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Core dump tests against the fake kernel
+
+#![cfg(all(feature = "coredump", feature = "mock"))]
+
+use sentry_uapi::systypes::{SHMPermission, Status};
+use shield::coredump::{self, COREDUMP_LEN, CoreDump, Region};
+use shield::crashlog::{self, CrashKind};
+use shield::mock;
+use shield::shm::Shm;
+
+const PERMS: u32 =
+    SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+
+#[test]
+fn dump() {
+    let kernel = mock::session();
+    kernel.set_uptime_us(1234);
+    let shm = kernel.add_shm(0x10, 0x110, COREDUMP_LEN, PERMS);
+    coredump::bind_shm(Shm::new(0x10).unwrap().map(0).unwrap()).unwrap();
+
+    // without layout, the stack bounds are unknown
+    crashlog::record(CrashKind::Abort, format_args!("first"));
+    let mut buf = [0; COREDUMP_LEN];
+    let dump = coredump::last(&mut buf).unwrap();
+    assert_eq!(dump.kind().unwrap(), CrashKind::Abort);
+    assert_eq!(dump.uptime_us(), 1234);
+    assert!(dump.stack().is_empty());
+
+    // a region around the current stack frame, the capture frames being
+    // below it
+    let marker = 0_u8;
+    let frame = (&raw const marker).expose_provenance();
+    let layout = Box::leak(Box::new([
+        Region {
+            name: "stack",
+            start: frame - 0x4000,
+            len: 0x4100,
+        },
+        Region {
+            name: "a_long_name",
+            start: 0x2000_0000,
+            len: 0x800,
+        },
+    ]));
+    coredump::set_layout(layout);
+    crashlog::record(CrashKind::Panic, format_args!("boom"));
+
+    let dump = coredump::last(&mut buf).unwrap();
+    assert_eq!(dump.kind().unwrap(), CrashKind::Panic);
+    let regions: Vec<_> = dump.regions().collect();
+    assert_eq!(regions.len(), 2);
+    assert_eq!(regions[0].name(), "stack");
+    assert_eq!(regions[1].name(), "a_long_n");
+    assert_eq!((regions[1].start, regions[1].len), (0x2000_0000, 0x800));
+    let sp = dump.registers().sp as usize;
+    assert!(sp < frame && sp > frame - 0x4000);
+    assert_eq!(dump.as_bytes().len(), COREDUMP_LEN);
+    assert!(!dump.stack().is_empty());
+    assert_eq!(crashlog::last().unwrap().message(), "boom");
+
+    // the supervisor reads the same dump from the shared memory
+    let forwarded = unsafe {
+        std::slice::from_raw_parts(std::ptr::with_exposed_provenance::<u8>(shm), COREDUMP_LEN)
+    };
+    assert_eq!(
+        CoreDump::decode(forwarded).unwrap().as_bytes(),
+        dump.as_bytes()
+    );
+
+    coredump::clear();
+    assert!(coredump::last(&mut buf).is_none());
+}
+
+#[test]
+fn corrupted() {
+    assert!(CoreDump::decode(&[0; COREDUMP_LEN]).is_none());
+
+    let kernel = mock::session();
+    kernel.add_shm(0x10, 0x110, COREDUMP_LEN / 2, PERMS);
+    let err = coredump::bind_shm(Shm::new(0x10).unwrap().map(0).unwrap())
+        .err()
+        .unwrap();
+    assert!(err.status() == Status::Invalid);
+}
@@ -0,0 +1,82 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Mapped device and peripheral access tests against the fake kernel
+
+#![cfg(feature = "mock")]
+
+use core::cell::Cell;
+use sentry_uapi::systypes::{Status, Syscall};
+use shield::device::{Device, StealablePeripheral};
+use shield::error::Subsystem;
+use shield::mock;
+
+/// Register block of the fake peripheral
+#[repr(C)]
+struct RegisterBlock {
+    cr: Cell<u32>,
+}
+
+struct Registers(RegisterBlock);
+
+// SAFETY: single-threaded tests
+unsafe impl Sync for Registers {}
+
+static REGISTERS: Registers = Registers(RegisterBlock { cr: Cell::new(0) });
+
+/// svd2rust style peripheral
+struct Usart1 {
+    _marker: core::marker::PhantomData<*const ()>,
+}
+
+impl Usart1 {
+    const PTR: *const RegisterBlock = &REGISTERS.0;
+
+    unsafe fn steal() -> Self {
+        Self {
+            _marker: core::marker::PhantomData,
+        }
+    }
+}
+
+impl core::ops::Deref for Usart1 {
+    type Target = RegisterBlock;
+
+    fn deref(&self) -> &RegisterBlock {
+        unsafe { &*Self::PTR }
+    }
+}
+
+shield::stealable!(Usart1);
+
+#[test]
+fn peripheral() {
+    let kernel = mock::session();
+    kernel.add_device(0x04, 0x104);
+    let usart = Device::new(0x04).unwrap().map().unwrap();
+    assert_eq!(usart.handle(), 0x104);
+    {
+        let regs = usart.peripheral::<Usart1>();
+        assert_eq!(regs.base_address(), Usart1::REGISTERS.addr());
+        regs.cr.set(1);
+    }
+    assert_eq!(REGISTERS.0.cr.get(), 1);
+    let usart = usart.unmap().unwrap();
+    assert_eq!(usart.label(), 0x04);
+    assert_eq!(kernel.call_count(Syscall::UnmapDev), 1);
+}
+
+#[test]
+fn denied() {
+    let kernel = mock::session();
+    let err = Device::new(0x04).err().unwrap();
+    assert_eq!(err.subsystem(), Subsystem::Device);
+    assert_eq!(err.handle(), Some(0x04));
+
+    kernel.add_device(0x04, 0x104);
+    kernel.set_status(Syscall::MapDev, Status::Busy);
+    let err = Device::new(0x04).unwrap().map().err().unwrap();
+    assert_eq!(err.status(), Status::Busy);
+    assert_eq!(err.handle(), Some(0x104));
+}
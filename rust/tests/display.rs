@@ -0,0 +1,91 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Double buffered framebuffer tests against the fake kernel
+
+#![cfg(all(feature = "display", feature = "mock"))]
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use embedded_graphics_core::Pixel;
+use embedded_graphics_core::geometry::Dimensions;
+use embedded_graphics_core::pixelcolor::{IntoStorage, Rgb565, RgbColor};
+use embedded_graphics_core::prelude::{DrawTarget, Point, Size};
+use embedded_graphics_core::primitives::Rectangle;
+use sentry_uapi::systypes::{EventType, SHMPermission, Status};
+use shield::display::{Display, Panel};
+use shield::shm::Shm;
+use shield::{executor, mock};
+
+const IRQ: u16 = 88;
+
+#[derive(Clone, Default)]
+struct FakePanel {
+    front: Rc<Cell<usize>>,
+    vsyncs: Rc<Cell<usize>>,
+}
+
+impl Panel for FakePanel {
+    fn set_front(&mut self, address: usize) {
+        self.front.set(address);
+    }
+
+    fn clear_vsync(&mut self) {
+        self.vsyncs.set(self.vsyncs.get() + 1);
+    }
+}
+
+fn pixel(address: usize, index: usize) -> u16 {
+    unsafe {
+        std::ptr::with_exposed_provenance::<u16>(address)
+            .add(index)
+            .read()
+    }
+}
+
+#[test]
+fn swap() {
+    let kernel = mock::session();
+    let perms =
+        SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+    let base = kernel.add_shm(0x10, 0x110, 2 * 4 * 3 * 2, perms);
+    let panel = FakePanel::default();
+    let shm = Shm::new(0x10).unwrap().map(0).unwrap();
+    let mut display = Display::new(panel.clone(), shm, 4, 3, IRQ).unwrap();
+    assert_eq!(panel.front.get(), base);
+
+    assert_eq!(display.bounding_box().size, Size::new(4, 3));
+    let red = Rgb565::RED.into_storage();
+    display.clear(Rgb565::BLACK).unwrap();
+    let area = Rectangle::new(Point::new(-1, 1), Size::new(3, 5));
+    display.fill_solid(&area, Rgb565::RED).unwrap();
+    let white = [(3, 0), (4, 0)].map(|(x, y)| Pixel(Point::new(x, y), Rgb565::WHITE));
+    display.draw_iter(white).unwrap();
+    kernel.push_event(EventType::Irq, 0, &u32::from(IRQ).to_ne_bytes());
+    executor::run(display.swap()).unwrap();
+    let back = base + 4 * 3 * 2;
+    assert_eq!(panel.front.get(), back);
+    assert_eq!(panel.vsyncs.get(), 1);
+    let pixels: Vec<u16> = (0..12).map(|index| pixel(back, index)).collect();
+    assert_eq!(pixels, [0, 0, 0, 0xffff, red, red, 0, 0, red, red, 0, 0]);
+
+    // the first buffer is drawn into next
+    display.clear(Rgb565::WHITE).unwrap();
+    assert_eq!(pixel(base, 0), 0xffff);
+    assert_eq!(pixel(back, 0), 0);
+}
+
+#[test]
+fn small_shm() {
+    let kernel = mock::session();
+    let perms =
+        SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+    kernel.add_shm(0x10, 0x110, 4 * 3 * 2, perms);
+    let shm = Shm::new(0x10).unwrap().map(0).unwrap();
+    let error = Display::new(FakePanel::default(), shm, 4, 3, IRQ)
+        .err()
+        .unwrap();
+    assert_eq!(error.status(), Status::Invalid);
+}
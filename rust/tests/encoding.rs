@@ -0,0 +1,70 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Hexadecimal and base64 encoding tests
+
+use sentry_uapi::systypes::Status;
+use shield::encoding::{base64, decode_base64, decode_hex, encode_base64_to, encode_hex_to, hex};
+
+#[test]
+fn hex() {
+    let mut text = [0; hex::encoded_len(4)];
+    assert_eq!(
+        encode_hex_to(&[0xde, 0xad, 0x00, 0x1f], &mut text).unwrap(),
+        "dead001f"
+    );
+    assert_eq!(encode_hex_to(&[], &mut []).unwrap(), "");
+    let err = encode_hex_to(&[1, 2], &mut [0; 3]).unwrap_err();
+    assert_eq!(err.status(), Status::Busy);
+
+    let mut out = [0; 4];
+    assert_eq!(decode_hex("DEad001f", &mut out).unwrap(), 4);
+    assert_eq!(out, [0xde, 0xad, 0x00, 0x1f]);
+    for text in ["abc", "0g", "-1"] {
+        let err = decode_hex(text, &mut out).unwrap_err();
+        assert_eq!(err.status(), Status::Invalid);
+    }
+    let err = decode_hex("0102", &mut out[..1]).unwrap_err();
+    assert_eq!(err.status(), Status::Busy);
+}
+
+#[test]
+fn base64() {
+    // RFC 4648 test vectors
+    let vectors = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+    for (data, encoded) in vectors {
+        let mut text = [0; base64::encoded_len(6)];
+        assert_eq!(
+            encode_base64_to(data.as_bytes(), &mut text).unwrap(),
+            encoded
+        );
+
+        let mut out = [0; 6];
+        let len = decode_base64(encoded, &mut out).unwrap();
+        assert_eq!(&out[..len], data.as_bytes());
+        let len = decode_base64(encoded.trim_end_matches('='), &mut out).unwrap();
+        assert_eq!(&out[..len], data.as_bytes());
+        assert!(len <= base64::max_decoded_len(encoded.len()));
+    }
+
+    let mut out = [0; 8];
+    assert_eq!(decode_base64("/+8=", &mut out).unwrap(), 2);
+    assert_eq!(out[..2], [0xff, 0xef]);
+    for text in ["Z", "Zg=a", "Z===", "Zm9v!A==", "=Zm9"] {
+        let err = decode_base64(text, &mut out).unwrap_err();
+        assert_eq!(err.status(), Status::Invalid);
+    }
+    let err = decode_base64("Zm9vYmFy", &mut out[..5]).unwrap_err();
+    assert_eq!(err.status(), Status::Busy);
+    let err = encode_base64_to(b"foo", &mut [0; 3]).unwrap_err();
+    assert_eq!(err.status(), Status::Busy);
+}
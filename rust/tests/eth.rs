@@ -0,0 +1,158 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Ethernet MAC driver tests against the fake kernel, the test playing the
+//! MAC DMA
+
+#![cfg(all(feature = "eth", feature = "mock"))]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use sentry_uapi::systypes::{EventType, SHMPermission, Status};
+use shield::eth::{BUFFER_LEN, DESCRIPTOR_LEN, Mac, MacRegisters};
+use shield::mock;
+use shield::net::Notify;
+use shield::shm::Shm;
+use smoltcp::phy::{Device, RxToken, TxToken};
+use smoltcp::time::Instant;
+
+const OWN: u32 = 1 << 31;
+const FD: u32 = 1 << 29;
+const LD: u32 = 1 << 28;
+const RX_ES: u32 = 1 << 15;
+
+#[derive(Default)]
+struct State {
+    started: Option<(usize, usize, usize)>,
+    rx_tail: usize,
+    tx_tail: usize,
+    cleared: usize,
+}
+
+#[derive(Clone, Default)]
+struct FakeMac(Rc<RefCell<State>>);
+
+impl MacRegisters for FakeMac {
+    fn start(&mut self, rx_ring: usize, tx_ring: usize, len: usize) {
+        self.0.borrow_mut().started = Some((rx_ring, tx_ring, len));
+    }
+
+    fn stop(&mut self) {
+        self.0.borrow_mut().started = None;
+    }
+
+    fn set_rx_tail(&mut self, descriptor: usize) {
+        self.0.borrow_mut().rx_tail = descriptor;
+    }
+
+    fn set_tx_tail(&mut self, descriptor: usize) {
+        self.0.borrow_mut().tx_tail = descriptor;
+    }
+
+    fn clear_interrupts(&mut self) {
+        self.0.borrow_mut().cleared += 1;
+    }
+}
+
+/// Shared memory view, for descriptor rings of two descriptors
+struct Dma(usize);
+
+impl Dma {
+    fn word(&self, descriptor: usize, index: usize) -> u32 {
+        let address = self.0 + descriptor * DESCRIPTOR_LEN + index * 4;
+        unsafe { std::ptr::with_exposed_provenance::<u32>(address).read() }
+    }
+
+    fn set_word(&self, descriptor: usize, index: usize, value: u32) {
+        let address = self.0 + descriptor * DESCRIPTOR_LEN + index * 4;
+        unsafe { std::ptr::with_exposed_provenance_mut::<u32>(address).write(value) }
+    }
+
+    fn buffer(&self, index: usize) -> *mut u8 {
+        std::ptr::with_exposed_provenance_mut(self.0 + 4 * DESCRIPTOR_LEN + index * BUFFER_LEN)
+    }
+
+    /// Receive `frame` into the receive descriptor `index`
+    fn receive(&self, index: usize, frame: &[u8], status: u32) {
+        assert_ne!(self.word(index, 3) & OWN, 0);
+        unsafe { std::ptr::copy_nonoverlapping(frame.as_ptr(), self.buffer(index), frame.len()) };
+        self.set_word(index, 3, status | frame.len() as u32);
+    }
+}
+
+fn mac(kernel: &mock::Session) -> (Mac<FakeMac>, FakeMac, Dma) {
+    let perms =
+        SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+    let base = kernel.add_shm(0x10, 0x110, 4 * (DESCRIPTOR_LEN + BUFFER_LEN), perms);
+    let regs = FakeMac::default();
+    let mac = Mac::new(regs.clone(), Shm::new(0x10).unwrap().map(0).unwrap(), 42).unwrap();
+    (mac, regs, Dma(base))
+}
+
+#[test]
+fn frames() {
+    let kernel = mock::session();
+    let (mut mac, regs, dma) = mac(&kernel);
+    assert_eq!(
+        regs.0.borrow().started,
+        Some((dma.0, dma.0 + 2 * DESCRIPTOR_LEN, 2))
+    );
+    assert_eq!(regs.0.borrow().rx_tail, dma.0 + 2 * DESCRIPTOR_LEN);
+    assert_eq!(mac.capabilities().max_burst_size, Some(2));
+    assert!(mac.receive(Instant::ZERO).is_none());
+
+    dma.receive(0, b"ping", FD | LD);
+    let (rx, tx) = mac.receive(Instant::ZERO).unwrap();
+    assert_eq!(rx.consume(<[u8]>::to_vec), b"ping");
+    tx.consume(4, |frame| frame.copy_from_slice(b"pong"));
+    // the receive descriptor is armed again
+    assert_ne!(dma.word(0, 3) & OWN, 0);
+    assert_eq!(regs.0.borrow().rx_tail, dma.0 + DESCRIPTOR_LEN);
+
+    assert_eq!(dma.word(2, 3), OWN | FD | LD | 4);
+    assert_eq!(dma.word(2, 2) & 0x7fff, 4);
+    assert_eq!(regs.0.borrow().tx_tail, dma.0 + 3 * DESCRIPTOR_LEN);
+    let sent = unsafe { std::slice::from_raw_parts(dma.buffer(2), 4) };
+    assert_eq!(sent, b"pong");
+
+    // transmitting another frame, the first one is still owned by the DMA
+    mac.transmit(Instant::ZERO)
+        .unwrap()
+        .consume(2, |frame| frame.copy_from_slice(b"hi"));
+    assert!(mac.transmit(Instant::ZERO).is_none());
+    dma.set_word(2, 3, 0);
+    assert!(mac.transmit(Instant::ZERO).is_some());
+
+    // the MAC interrupt notifies the frames
+    assert_eq!(mac.event(), (EventType::Irq, 42));
+    mac.acknowledge().unwrap();
+    assert_eq!(regs.0.borrow().cleared, 1);
+
+    let (regs, _shm) = mac.release();
+    assert!(regs.0.borrow().started.is_none());
+}
+
+#[test]
+fn errored_frames() {
+    let kernel = mock::session();
+    let (mut mac, _regs, dma) = mac(&kernel);
+    dma.receive(0, b"bad", FD | LD | RX_ES);
+    dma.receive(1, b"good", FD | LD);
+    let (rx, _tx) = mac.receive(Instant::ZERO).unwrap();
+    assert_eq!(rx.consume(<[u8]>::to_vec), b"good");
+    assert_ne!(dma.word(0, 3) & OWN, 0);
+    assert_ne!(dma.word(1, 3) & OWN, 0);
+}
+
+#[test]
+fn small_shm() {
+    let kernel = mock::session();
+    let perms =
+        SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+    kernel.add_shm(0x10, 0x110, BUFFER_LEN, perms);
+    let shm = Shm::new(0x10).unwrap().map(0).unwrap();
+    let error = Mac::new(FakeMac::default(), shm, 42).err().unwrap();
+    assert_eq!(error.status(), Status::Invalid);
+}
@@ -0,0 +1,533 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! C ABI entry points tests against the fake kernel
+
+#![cfg(all(feature = "mock", feature = "ffi"))]
+
+use core::ffi::{CStr, c_char, c_int, c_ulong};
+use core::ptr;
+use sentry_uapi::systypes::{SHMPermission, Status, Syscall};
+use shield::errno::{self, __errno_location, Errno};
+use shield::ffi::fmt::{Arg, format_into};
+use shield::ffi::rand::{RAND_MAX, rand, rand_r, srand};
+use shield::ffi::shm::{
+    ShieldShm, ShieldShmInfo, shield_shm_get, shield_shm_get_info, shield_shm_map,
+    shield_shm_set_creds, shield_shm_unmap,
+};
+use shield::mock;
+
+const SHM_LABEL: u32 = 0xf00;
+const SHM_HANDLE: u32 = 0x1f00;
+
+const OK: u32 = Status::Ok as u32;
+
+#[test]
+fn shm_checked_transitions() {
+    let kernel = mock::session();
+    let perms = SHMPermission::Map as u32 | SHMPermission::Read as u32;
+    let base = kernel.add_shm(SHM_LABEL, SHM_HANDLE, 128, perms);
+
+    let mut shm = ShieldShm {
+        handle: 0,
+        label: 0,
+        mapped: false,
+    };
+    unsafe {
+        assert_eq!(shield_shm_get(SHM_LABEL, &mut shm), OK);
+        assert_eq!(shm.handle, SHM_HANDLE);
+        assert_eq!(shield_shm_unmap(&mut shm), Status::Invalid as u32);
+        assert_eq!(shield_shm_set_creds(&mut shm, 0x42, perms), OK);
+
+        assert_eq!(shield_shm_map(&mut shm), OK);
+        assert!(shm.mapped && kernel.is_mapped(SHM_HANDLE));
+        assert_eq!(shield_shm_map(&mut shm), Status::AlreadyMapped as u32);
+        assert_eq!(
+            shield_shm_set_creds(&mut shm, 0x42, 0),
+            Status::AlreadyMapped as u32
+        );
+        assert_eq!(kernel.call_count(Syscall::MapShm), 1);
+        assert_eq!(kernel.call_count(Syscall::SHMSetCredential), 1);
+
+        let mut info = ShieldShmInfo {
+            label: 0,
+            handle: 0,
+            base: 0,
+            len: 0,
+            perms: 0,
+        };
+        assert_eq!(shield_shm_get_info(&mut shm, &mut info), OK);
+        assert_eq!((info.base, info.len, info.perms), (base, 128, perms));
+
+        assert_eq!(shield_shm_unmap(&mut shm), OK);
+        assert!(!shm.mapped && !kernel.is_mapped(SHM_HANDLE));
+    }
+}
+
+#[test]
+fn shm_errors() {
+    let kernel = mock::session();
+    kernel.add_shm(SHM_LABEL, SHM_HANDLE, 64, 0);
+
+    let mut shm = ShieldShm {
+        handle: 0,
+        label: 0,
+        mapped: false,
+    };
+    unsafe {
+        assert_eq!(
+            shield_shm_get(SHM_LABEL, ptr::null_mut()),
+            Status::Invalid as u32
+        );
+        assert_ne!(shield_shm_get(SHM_LABEL + 1, &mut shm), OK);
+        assert_eq!(shield_shm_map(ptr::null_mut()), Status::Invalid as u32);
+
+        assert_eq!(shield_shm_get(SHM_LABEL, &mut shm), OK);
+        assert_eq!(shield_shm_map(&mut shm), Status::Denied as u32);
+        assert!(!shm.mapped);
+    }
+}
+
+#[test]
+fn errno_reporting() {
+    let kernel = mock::session();
+    kernel.add_shm(SHM_LABEL, SHM_HANDLE, 64, 0);
+    errno::set(Errno::NONE);
+
+    let mut shm = ShieldShm {
+        handle: 0,
+        label: 0,
+        mapped: false,
+    };
+    unsafe {
+        assert_eq!(shield_shm_get(SHM_LABEL, &mut shm), OK);
+        assert_eq!(errno::get(), Errno::NONE);
+        assert_ne!(shield_shm_map(&mut shm), OK);
+        assert_eq!(errno::get(), Errno::EPERM);
+        assert_ne!(shield_shm_unmap(ptr::null_mut()), OK);
+        assert_eq!(errno::get(), Errno::EINVAL);
+
+        // the C library view of errno
+        assert_eq!(*__errno_location() as u32, Errno::EINVAL.0);
+        *__errno_location() = 0;
+        assert_eq!(errno::get(), Errno::NONE);
+    }
+
+    #[cfg(feature = "heap")]
+    {
+        assert!(shield::ffi::malloc::malloc(usize::MAX).is_null());
+        assert_eq!(errno::get(), Errno::ENOMEM);
+    }
+}
+
+#[test]
+fn clocks() {
+    use shield::ffi::time::{
+        CLOCK_BOOTTIME, CLOCK_MONOTONIC, CLOCK_REALTIME, Timespec, clock_gettime, clock_settime,
+        time,
+    };
+
+    let kernel = mock::session();
+    kernel.set_uptime_us(3_000_500);
+    let mut tp = Timespec::default();
+    unsafe {
+        assert_eq!(clock_gettime(CLOCK_MONOTONIC, &mut tp), 0);
+        assert_eq!((tp.tv_sec, tp.tv_nsec), (3, 500_000));
+
+        // microsecond resolution fallback
+        kernel.fail_nth(Syscall::GetCycle, 1, Status::Denied);
+        assert_eq!(clock_gettime(CLOCK_BOOTTIME, &mut tp), 0);
+        assert_eq!((tp.tv_sec, tp.tv_nsec), (3, 500_000));
+
+        let now = Timespec {
+            tv_sec: 1_700_000_000,
+            tv_nsec: 250_000_000,
+        };
+        assert_eq!(clock_settime(CLOCK_REALTIME, &now), 0);
+        kernel.set_uptime_us(5_000_500);
+        assert_eq!(clock_gettime(CLOCK_REALTIME, &mut tp), 0);
+        assert_eq!((tp.tv_sec, tp.tv_nsec), (1_700_000_002, 250_000_000));
+        let mut tloc = 0;
+        assert_eq!(time(&mut tloc), 1_700_000_002);
+        assert_eq!(tloc, 1_700_000_002);
+
+        errno::set(Errno::NONE);
+        assert_eq!(clock_settime(CLOCK_MONOTONIC, &now), -1);
+        assert_eq!(errno::get(), Errno::EINVAL);
+        assert_eq!(clock_gettime(42, &mut tp), -1);
+        assert_eq!(clock_gettime(CLOCK_MONOTONIC, ptr::null_mut()), -1);
+        kernel.set_status(Syscall::GetCycle, Status::Critical);
+        assert_eq!(time(ptr::null_mut()), c_ulong::MAX);
+        assert_eq!(errno::get(), Errno::EIO);
+    }
+}
+
+#[test]
+fn getrandom_chunks_and_retries() {
+    use shield::ffi::random::{GRND_INSECURE, GRND_NONBLOCK, GRND_RANDOM, getrandom as raw};
+
+    let kernel = mock::session();
+    let mut buf = [0_u8; 11];
+    let getrandom =
+        |buf: &mut [u8], flags| unsafe { raw(buf.as_mut_ptr().cast(), buf.len(), flags) };
+
+    // unaligned length and buffer, the TRNG not being ready once
+    kernel.fail_nth(Syscall::GetRandom, 2, Status::Again);
+    assert_eq!(getrandom(&mut buf[1..], 0), 10);
+    assert_eq!(kernel.call_count(Syscall::GetRandom), 4);
+    assert_ne!(buf[1..], [0; 10]);
+    assert_eq!(getrandom(&mut [], 0), 0);
+
+    errno::set(Errno::NONE);
+    kernel.fail_nth(Syscall::GetRandom, 1, Status::Again);
+    assert_eq!(getrandom(&mut buf, GRND_NONBLOCK), -1);
+    assert_eq!(errno::get(), Errno::EAGAIN);
+
+    // partial fill on a later failure
+    kernel.fail_nth(Syscall::GetRandom, 2, Status::Critical);
+    assert_eq!(getrandom(&mut buf, GRND_RANDOM), 4);
+
+    kernel.set_status(Syscall::GetRandom, Status::Denied);
+    assert_eq!(getrandom(&mut buf, 0), -1);
+    assert_eq!(errno::get(), Errno::EPERM);
+    assert_eq!(getrandom(&mut buf, GRND_INSECURE), 11);
+
+    assert_eq!(getrandom(&mut buf, GRND_RANDOM | GRND_INSECURE), -1);
+    assert_eq!(getrandom(&mut buf, 0x10), -1);
+    assert_eq!(errno::get(), Errno::EINVAL);
+    assert_eq!(unsafe { raw(ptr::null_mut(), 4, 0) }, -1);
+    assert_eq!(errno::get(), Errno::EFAULT);
+}
+
+#[test]
+fn rand_sequence() {
+    srand(42);
+    let values = [rand(), rand(), rand()];
+    assert!(values.iter().all(|value| (0..=RAND_MAX).contains(value)));
+
+    let mut seed = 42;
+    let reentrant = unsafe { [rand_r(&mut seed), rand_r(&mut seed), rand_r(&mut seed)] };
+    assert_eq!(values, reentrant);
+    assert_eq!(unsafe { rand_r(ptr::null_mut()) }, 0);
+}
+
+unsafe extern "C" {
+    /// Host C library reference implementation
+    fn snprintf(dest: *mut c_char, len: usize, fmt: *const c_char, ...) -> c_int;
+}
+
+fn format(fmt: &CStr, args: &[Arg]) -> (Option<usize>, String) {
+    let mut buf = [0_u8; 128];
+    let len = unsafe { format_into(&mut buf, fmt.to_bytes(), &mut args.iter()) };
+    let text = CStr::from_bytes_until_nul(&buf).unwrap();
+    (len, text.to_str().unwrap().to_owned())
+}
+
+fn reference(len: c_int, buf: &[u8]) -> (Option<usize>, String) {
+    let text = CStr::from_bytes_until_nul(buf).unwrap();
+    (usize::try_from(len).ok(), text.to_str().unwrap().to_owned())
+}
+
+macro_rules! check_int {
+    ($fmt:literal, $value:expr) => {{
+        let mut buf = [0_u8; 128];
+        let len = unsafe { snprintf(buf.as_mut_ptr().cast(), buf.len(), $fmt.as_ptr(), $value) };
+        #[allow(clippy::cast_sign_loss)]
+        let arg = Arg::Int($value as u64);
+        assert_eq!(format($fmt, &[arg]), reference(len, &buf), "{:?}", $fmt);
+    }};
+}
+
+macro_rules! check_float {
+    ($fmt:literal, $value:expr) => {{
+        let mut buf = [0_u8; 128];
+        let len = unsafe { snprintf(buf.as_mut_ptr().cast(), buf.len(), $fmt.as_ptr(), $value) };
+        assert_eq!(
+            format($fmt, &[Arg::Float($value)]),
+            reference(len, &buf),
+            "{:?}",
+            $fmt
+        );
+    }};
+}
+
+#[test]
+fn printf_integers() {
+    check_int!(c"%d|", 42);
+    check_int!(c"[%5d]", 42);
+    check_int!(c"[%-5d]", 42);
+    check_int!(c"[%+05d]", 42);
+    check_int!(c"[% d]", -7_i32);
+    check_int!(c"[%.3d]", 7);
+    check_int!(c"[%.0d]", 0);
+    check_int!(c"[%08.3i]", -12_i32);
+    check_int!(c"[%u]", 4_000_000_000_u32);
+    check_int!(c"[%#x]", 0xbeef);
+    check_int!(c"[%X]", 0xbeef);
+    check_int!(c"[%#o]", 0xbeef);
+    check_int!(c"[%#o]", 0);
+    check_int!(c"[%hhd]", 0x1ff);
+    check_int!(c"[%hu]", 0x12345);
+    check_int!(c"[%lld]", i64::MIN);
+    check_int!(c"[%zu]", usize::MAX);
+    check_int!(c"[%-#10lx]", 0xcafe_u64);
+    check_int!(c"[%c]", c_int::from(b'a'));
+    check_int!(c"[%-3c]", c_int::from(b'a'));
+}
+
+#[test]
+fn printf_floats() {
+    check_float!(c"[%f]", core::f64::consts::PI);
+    check_float!(c"[%.2f]", -2.5);
+    check_float!(c"[%10.3f]", -2.5);
+    check_float!(c"[%-10.1f]", -2.5);
+    check_float!(c"[%010.2f]", -2.5);
+    check_float!(c"[%+.0f]", 0.5);
+    check_float!(c"[%#.0f]", 2.0);
+    check_float!(c"[%e]", 123_456.789);
+    check_float!(c"[%.3E]", 0.000_123_4);
+    check_float!(c"[%e]", 0.0);
+    check_float!(c"[%.2e]", 1e-300);
+    check_float!(c"[%g]", 100_000.0);
+    check_float!(c"[%g]", 1_000_000.0);
+    check_float!(c"[%g]", 0.000_1);
+    check_float!(c"[%g]", 0.000_012_345);
+    check_float!(c"[%.3g]", 9.876_54);
+    check_float!(c"[%#g]", 1.5);
+    check_float!(c"[%G]", 1e-10);
+    check_float!(c"[%8.2g]", 1234.0);
+    check_float!(c"[%f]", f64::INFINITY);
+    check_float!(c"[%E]", f64::NAN);
+    check_float!(c"[%5f]", -f64::INFINITY);
+    check_float!(c"[%f]", -0.0);
+}
+
+#[test]
+fn printf_strings() {
+    let name = c"shield";
+    let args = [Arg::Int(name.as_ptr() as u64)];
+    assert_eq!(format(c"[%s]", &args).1, "[shield]");
+    assert_eq!(format(c"[%8.3s]", &args).1, "[     shi]");
+    assert_eq!(format(c"[%-8s]", &args).1, "[shield  ]");
+    assert_eq!(format(c"[%s]", &[Arg::Int(0)]).1, "[(null)]");
+
+    let args = [Arg::Int(3), Arg::Int(5), Arg::Int(42)];
+    assert_eq!(format(c"[%*.*d] 100%%", &args).1, "[00042] 100%");
+    assert_eq!(format(c"%d %n", &[Arg::Int(1), Arg::Int(0)]).0, None);
+
+    let mut buf = [0xff_u8; 4];
+    let len = unsafe {
+        format_into(
+            &mut buf,
+            b"%s",
+            &mut [Arg::Int(name.as_ptr() as u64)].iter(),
+        )
+    };
+    assert_eq!(len, Some(6));
+    assert_eq!(&buf, b"shi\0");
+}
+
+#[test]
+fn printf_log() {
+    use shield::ffi::fmt::print;
+
+    let kernel = mock::session();
+    let len = unsafe { print(b"%d apples\n", &mut [Arg::Int(3)].iter()) };
+    assert_eq!(len, Some(9));
+    assert_eq!(kernel.log_output(), b"3 apples\n");
+
+    // written up to the invalid conversion
+    let fmt = [&[b'x'; 300][..], b" %d %y %d"].concat();
+    let len = unsafe { print(&fmt, &mut [Arg::Int(1), Arg::Int(2)].iter()) };
+    assert_eq!(len, None);
+    let log = kernel.log_output();
+    assert_eq!(&log[9..], [&fmt[..300], b" 1 "].concat());
+}
+
+#[cfg(feature = "heap")]
+#[test]
+fn malloc_family() {
+    use shield::ffi::malloc::{calloc, free, malloc, realloc};
+
+    // errno is shared with the errno_reporting test
+    let _kernel = mock::session();
+
+    let block = malloc(10).cast::<u8>();
+    assert!(!block.is_null());
+    assert_eq!(block.addr() % (2 * size_of::<usize>()), 0);
+    unsafe {
+        ptr::copy_nonoverlapping(c"shield".as_ptr().cast(), block, 7);
+        let block = realloc(block.cast(), 4096).cast::<u8>();
+        assert_eq!(CStr::from_ptr(block.cast()), c"shield");
+        free(block.cast());
+        free(ptr::null_mut());
+    }
+
+    let zeroed = calloc(16, 4).cast::<u8>();
+    assert!(
+        unsafe { core::slice::from_raw_parts(zeroed, 64) }
+            .iter()
+            .all(|&byte| byte == 0)
+    );
+    unsafe { free(zeroed.cast()) };
+    assert!(calloc(usize::MAX, 2).is_null());
+    assert!(malloc(usize::MAX).is_null());
+}
+
+/// Every source and destination alignment, and lengths crossing the word and
+/// unrolled group sizes
+fn mem_cases() -> impl Iterator<Item = (usize, usize, usize)> {
+    (0..8).flat_map(|dst| (0..8).flat_map(move |src| (0..80).map(move |len| (dst, src, len))))
+}
+
+#[test]
+fn mem_functions() {
+    use shield::ffi::string::{memcmp, memcpy, memmove, memset};
+
+    let pattern: Vec<u8> = (0..128).map(|byte: u8| byte.wrapping_mul(37)).collect();
+    for (dst, src, len) in mem_cases() {
+        let mut buf = [0_u8; 128];
+        unsafe {
+            memcpy(
+                buf[dst..].as_mut_ptr().cast(),
+                pattern[src..].as_ptr().cast(),
+                len,
+            )
+        };
+        assert_eq!(buf[dst..dst + len], pattern[src..src + len]);
+        assert!(
+            buf[..dst]
+                .iter()
+                .chain(&buf[dst + len..])
+                .all(|&byte| byte == 0)
+        );
+
+        let mut buf = [0_u8; 128];
+        let c = c_int::from(src as u8) - 300;
+        unsafe { memset(buf[dst..].as_mut_ptr().cast(), c, len) };
+        assert!(buf[dst..dst + len].iter().all(|&byte| byte == c as u8));
+        assert!(
+            buf[..dst]
+                .iter()
+                .chain(&buf[dst + len..])
+                .all(|&byte| byte == 0)
+        );
+
+        // overlapping moves, in both directions
+        let (from, to) = (8 + src, 8 + src + dst);
+        for (from, to) in [(from, to), (to, from)] {
+            let mut buf = pattern.clone();
+            let mut expected = pattern.clone();
+            expected.copy_within(from..from + len, to);
+            unsafe {
+                memmove(
+                    buf[to..].as_mut_ptr().cast(),
+                    buf[from..].as_ptr().cast(),
+                    len,
+                )
+            };
+            assert_eq!(buf, expected, "{from} -> {to}, {len}");
+        }
+
+        let mut other = pattern.clone();
+        if len != 0 {
+            other[src + len - 1] ^= 0x80;
+        }
+        let sign = unsafe {
+            memcmp(
+                pattern[src..].as_ptr().cast(),
+                other[src..].as_ptr().cast(),
+                len,
+            )
+        };
+        assert_eq!(
+            sign.signum(),
+            pattern[src..src + len].cmp(&other[src..src + len]) as c_int
+        );
+    }
+}
+
+#[test]
+fn str_functions() {
+    use shield::ffi::string::{strcmp, strcpy, strlen, strncmp, strnlen};
+
+    for (dst, src, len) in mem_cases() {
+        let mut string = [0_u8; 128];
+        string[src..src + len].fill(b'x');
+        let s = string[src..].as_ptr().cast::<c_char>();
+        unsafe {
+            assert_eq!(strlen(s), len);
+            assert_eq!(strnlen(s, dst * 4), len.min(dst * 4));
+
+            let mut copy = [0xff_u8; 128];
+            let d = copy[dst..].as_mut_ptr().cast::<c_char>();
+            assert_eq!(strcpy(d, s), d);
+            assert_eq!(copy[dst..=dst + len], string[src..=src + len]);
+            assert_eq!(copy[dst + len + 1], 0xff);
+
+            assert_eq!(strcmp(d, s), 0);
+            if len != 0 {
+                d.add(len - 1).write(b'y' as c_char);
+                assert!(strcmp(d, s) > 0);
+                assert_eq!(strncmp(d, s, len - 1), 0);
+                assert!(strncmp(s, d, len) < 0);
+            }
+        }
+    }
+    assert_eq!(
+        unsafe { strcmp(c"ab".as_ptr(), c"abc".as_ptr()) },
+        -c_int::from(b'c')
+    );
+    assert!(unsafe { strcmp(c"\u{e9}".as_ptr(), c"e".as_ptr()) } > 0);
+}
+
+#[cfg(feature = "newlib")]
+#[test]
+fn newlib_stubs() {
+    use shield::ffi::newlib::{
+        _close, _fstat, _isatty, _lseek, _read, _write, EBADF, EIO, ESPIPE, S_IFCHR, StatHead,
+        errno as newlib_errno,
+    };
+
+    let kernel = mock::session();
+    let text = b"hello newlib\n";
+    assert_eq!(unsafe { _write(1, text.as_ptr().cast(), text.len()) }, 13);
+    assert_eq!(kernel.log_output(), text);
+
+    // the newlib errno, with the newlib values, is set
+    errno::set(Errno::NONE);
+    assert_eq!(unsafe { _write(3, text.as_ptr().cast(), text.len()) }, -1);
+    assert_eq!(newlib_errno(), EBADF);
+    assert_eq!(errno::get(), Errno::NONE);
+    assert_eq!(_read(0, ptr::null_mut(), 4), 0);
+    assert_eq!(_close(2), 0);
+    assert_eq!(_isatty(1), 1);
+    assert_eq!(_lseek(1, 0, 0), -1);
+    assert_eq!(newlib_errno(), ESPIPE);
+    kernel.fail_copy_to_kernel(1, Status::Critical);
+    assert_eq!(unsafe { _write(2, text.as_ptr().cast(), text.len()) }, -1);
+    assert_eq!(newlib_errno(), EIO);
+
+    let mut st = StatHead::default();
+    assert_eq!(unsafe { _fstat(1, &mut st) }, 0);
+    assert_eq!(st.st_mode, S_IFCHR);
+}
+
+#[cfg(all(feature = "newlib", not(feature = "heap")))]
+#[test]
+fn newlib_sbrk() {
+    use shield::ffi::newlib::{_sbrk, ENOMEM, errno as newlib_errno, set_sbrk_region};
+
+    let _kernel = mock::session();
+    let mut region = [0_u8; 64];
+    let start = region.as_mut_ptr();
+    unsafe { set_sbrk_region(start, region.len()) };
+    assert_eq!(_sbrk(16).cast::<u8>(), start);
+    assert_eq!(_sbrk(48).cast::<u8>(), start.wrapping_add(16));
+    assert_eq!(_sbrk(1).addr(), usize::MAX);
+    assert_eq!(newlib_errno(), ENOMEM);
+    assert_eq!(_sbrk(-32).cast::<u8>(), start.wrapping_add(64));
+    assert_eq!(_sbrk(0).cast::<u8>(), start.wrapping_add(32));
+    assert_eq!(_sbrk(-64).addr(), usize::MAX);
+}
@@ -0,0 +1,411 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Async SPI, I2C and GPIO driver tests against the fake kernel
+
+#![cfg(all(feature = "hal", feature = "mock"))]
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::pin::pin;
+use std::rc::Rc;
+
+use sentry_uapi::systypes::{EventType, Status, Syscall};
+use shield::error::Subsystem;
+use shield::hal::Delay;
+use shield::hal::gpio::{InputPin, OutputPin};
+use shield::hal::i2c::{I2c, I2cEvent, I2cRegisters, Operation};
+use shield::hal::spi::{ExclusiveDevice, Spi, SpiRegisters};
+use shield::time;
+use shield::{executor, mock};
+
+const IRQ: u16 = 35;
+
+fn irqs(kernel: &mock::Session, count: usize) {
+    for _ in 0..count {
+        kernel.push_event(EventType::Irq, 0, &u32::from(IRQ).to_ne_bytes());
+    }
+}
+
+/// Poll `future` once the pending events are handled, returning its output
+/// if ready
+fn step<F: Future>(future: std::pin::Pin<&mut F>) -> Option<F::Output> {
+    let mut yielded = false;
+    let yield_once = std::future::poll_fn(|cx| {
+        if std::mem::replace(&mut yielded, true) {
+            return std::task::Poll::Ready(());
+        }
+        cx.waker().wake_by_ref();
+        std::task::Poll::Pending
+    });
+    match executor::run(executor::select2(future, yield_once)) {
+        executor::Either::First(output) => Some(output),
+        executor::Either::Second(()) => None,
+    }
+}
+
+/// SPI device answering each byte with its successor, the bytes being
+/// shifted once the interrupts are enabled
+#[derive(Default)]
+struct SpiModel {
+    shifting: VecDeque<u8>,
+    received: VecDeque<u8>,
+    sent: Vec<u8>,
+    max_in_flight: usize,
+}
+
+#[derive(Clone, Default)]
+struct SpiRegs(Rc<RefCell<SpiModel>>);
+
+impl SpiRegisters for SpiRegs {
+    fn try_read(&mut self) -> Option<u8> {
+        self.0.borrow_mut().received.pop_front()
+    }
+
+    fn try_write(&mut self, byte: u8) -> bool {
+        let mut model = self.0.borrow_mut();
+        if model.shifting.len() == 2 {
+            return false;
+        }
+        model.shifting.push_back(byte);
+        model.sent.push(byte);
+        let in_flight = model.shifting.len() + model.received.len();
+        model.max_in_flight = model.max_in_flight.max(in_flight);
+        true
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.borrow().shifting.is_empty()
+    }
+
+    fn enable_interrupts(&mut self, enabled: bool) {
+        let mut model = self.0.borrow_mut();
+        if enabled {
+            while let Some(byte) = model.shifting.pop_front() {
+                model.received.push_back(byte.wrapping_add(1));
+            }
+        }
+    }
+
+    fn fifo_depth(&self) -> usize {
+        4
+    }
+}
+
+#[test]
+fn spi_transfers() {
+    let kernel = mock::session();
+    irqs(&kernel, 64);
+    let regs = SpiRegs::default();
+    let mut spi = Spi::new(regs.clone(), IRQ).unwrap();
+
+    let mut words = [1, 2, 3, 4, 5, 6, 7];
+    executor::run(spi.transfer_in_place(&mut words)).unwrap();
+    assert_eq!(words, [2, 3, 4, 5, 6, 7, 8]);
+
+    let mut read = [0; 3];
+    executor::run(spi.transfer(&mut read, &[0x10, 0x20, 0x30, 0x40, 0x50])).unwrap();
+    assert_eq!(read, [0x11, 0x21, 0x31]);
+
+    executor::run(spi.write(&[0xaa])).unwrap();
+    let mut read = [0xff; 2];
+    executor::run(spi.read(&mut read)).unwrap();
+    assert_eq!(read, [1, 1]);
+    executor::run(spi.flush()).unwrap();
+
+    let model = regs.0.borrow();
+    assert_eq!(model.sent[7..12], [0x10, 0x20, 0x30, 0x40, 0x50]);
+    assert_eq!(model.sent[12..], [0xaa, 0, 0]);
+    assert!(model.max_in_flight <= 4);
+    assert!(kernel.call_count(Syscall::IrqAcknowledge) > 0);
+}
+
+#[test]
+fn spi_irq_failure() {
+    let kernel = mock::session();
+    kernel.set_status(Syscall::IrqEnable, Status::Denied);
+    let err = Spi::new(SpiRegs::default(), IRQ).err().unwrap();
+    assert_eq!(err.subsystem(), Subsystem::Spi);
+    assert_eq!(err.handle(), Some(u32::from(IRQ)));
+}
+
+#[test]
+fn spi_device() {
+    use embedded_hal::spi::Operation;
+    use embedded_hal_async::spi::SpiDevice;
+
+    let kernel = mock::session();
+    irqs(&kernel, 64);
+    kernel.set_gpio(0x42, 5, false);
+    let regs = SpiRegs::default();
+    let cs = OutputPin::new(0x42, 5).unwrap();
+    let mut device = ExclusiveDevice::new(Spi::new(regs.clone(), IRQ).unwrap(), cs).unwrap();
+    assert!(kernel.gpio(0x42, 5));
+
+    let mut read = [0; 2];
+    let before = time::uptime_us().unwrap();
+    executor::run(device.transaction(&mut [
+        Operation::Write(&[0x03]),
+        Operation::DelayNs(1500),
+        Operation::Read(&mut read),
+    ]))
+    .unwrap();
+    assert_eq!(read, [1, 1]);
+    assert!(time::uptime_us().unwrap() - before >= 2);
+    // deselected once done
+    assert!(kernel.gpio(0x42, 5));
+    assert_eq!(kernel.call_count(Syscall::GpioSet), 3);
+
+    // deselected on failure as well
+    kernel.set_status(Syscall::IrqAcknowledge, Status::Denied);
+    let err = executor::run(SpiDevice::write(&mut device, &[0; 8])).unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::Spi);
+    assert!(kernel.gpio(0x42, 5));
+    let (_spi, _cs) = device.release();
+}
+
+/// EEPROM at 0x50, the first written byte of a transfer setting the
+/// address, each byte being ready once the interrupts are enabled
+#[derive(Default)]
+struct I2cModel {
+    memory: [u8; 16],
+    pointer: usize,
+    transfer: Option<(u8, bool, usize)>,
+    first: bool,
+    ready: bool,
+    starts: Vec<(u8, bool, usize, bool)>,
+    stops: usize,
+}
+
+#[derive(Clone, Default)]
+struct I2cRegs(Rc<RefCell<I2cModel>>);
+
+impl I2cRegisters for I2cRegs {
+    fn start(&mut self, address: u8, read: bool, len: usize, stop: bool) {
+        let mut model = self.0.borrow_mut();
+        model.starts.push((address, read, len, stop));
+        model.transfer = Some((address, read, len));
+        model.first = true;
+    }
+
+    fn poll(&mut self) -> I2cEvent {
+        let model = self.0.borrow();
+        match model.transfer {
+            _ if !model.ready => I2cEvent::None,
+            Some((address, ..)) if address != 0x50 => I2cEvent::Nack,
+            Some((_, _, 0)) => I2cEvent::Complete,
+            Some((_, true, _)) => I2cEvent::RxReady,
+            Some((_, false, _)) => I2cEvent::TxReady,
+            None => I2cEvent::BusError,
+        }
+    }
+
+    fn read_byte(&mut self) -> u8 {
+        let mut model = self.0.borrow_mut();
+        let byte = model.memory[model.pointer];
+        model.pointer += 1;
+        if let Some((_, _, len)) = &mut model.transfer {
+            *len -= 1;
+        }
+        model.ready = false;
+        byte
+    }
+
+    fn write_byte(&mut self, byte: u8) {
+        let mut model = self.0.borrow_mut();
+        if std::mem::replace(&mut model.first, false) {
+            model.pointer = usize::from(byte);
+        } else {
+            let pointer = model.pointer;
+            model.memory[pointer] = byte;
+            model.pointer += 1;
+        }
+        if let Some((_, _, len)) = &mut model.transfer {
+            *len -= 1;
+        }
+        model.ready = false;
+    }
+
+    fn stop(&mut self) {
+        let mut model = self.0.borrow_mut();
+        model.stops += 1;
+        model.transfer = None;
+    }
+
+    fn enable_interrupts(&mut self, enabled: bool) {
+        if enabled {
+            self.0.borrow_mut().ready = true;
+        }
+    }
+}
+
+#[test]
+fn i2c_transactions() {
+    let kernel = mock::session();
+    irqs(&kernel, 64);
+    let regs = I2cRegs::default();
+    let mut i2c = I2c::new(regs.clone(), IRQ).unwrap();
+
+    executor::run(i2c.write(0x50, &[4, 0xde, 0xad])).unwrap();
+    let mut read = [0; 2];
+    executor::run(i2c.write_read(0x50, &[4], &mut read)).unwrap();
+    assert_eq!(read, [0xde, 0xad]);
+
+    // adjacent operations of the same kind are merged
+    let (mut first, mut second) = ([0; 1], [0; 2]);
+    executor::run(i2c.transaction(
+        0x50,
+        &mut [
+            Operation::Write(&[3]),
+            Operation::Read(&mut first),
+            Operation::Read(&mut second),
+        ],
+    ))
+    .unwrap();
+    assert_eq!((first, second), ([0], [0xde, 0xad]));
+
+    let model = regs.0.borrow();
+    assert_eq!(
+        model.starts,
+        [
+            (0x50, false, 3, true),
+            (0x50, false, 1, false),
+            (0x50, true, 2, true),
+            (0x50, false, 1, false),
+            (0x50, true, 3, true),
+        ]
+    );
+    assert_eq!(model.stops, 0);
+}
+
+#[test]
+fn i2c_trait() {
+    use embedded_hal::i2c::{Error as _, ErrorKind, NoAcknowledgeSource};
+
+    /// Driver generic over the `embedded-hal-async` bus
+    async fn read_register<B: embedded_hal_async::i2c::I2c>(
+        bus: &mut B,
+        address: u8,
+        register: u8,
+    ) -> Result<u8, B::Error> {
+        let mut value = [0];
+        bus.write_read(address, &[register], &mut value).await?;
+        Ok(value[0])
+    }
+
+    let kernel = mock::session();
+    irqs(&kernel, 64);
+    let regs = I2cRegs::default();
+    regs.0.borrow_mut().memory[7] = 0x5a;
+    let mut i2c = I2c::new(regs, IRQ).unwrap();
+    assert_eq!(
+        executor::run(read_register(&mut i2c, 0x50, 7)).unwrap(),
+        0x5a
+    );
+    let err = executor::run(read_register(&mut i2c, 0x51, 7)).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown)
+    );
+}
+
+#[test]
+fn i2c_nack() {
+    let kernel = mock::session();
+    irqs(&kernel, 8);
+    let regs = I2cRegs::default();
+    let mut i2c = I2c::new(regs.clone(), IRQ).unwrap();
+    let err = executor::run(i2c.write(0x51, &[0])).unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::I2c);
+    assert_eq!(err.status(), Status::NoEntity);
+    assert_eq!(regs.0.borrow().stops, 1);
+
+    let err = executor::run(i2c.write(0x80, &[0])).unwrap_err();
+    assert_eq!(err.status(), Status::Invalid);
+}
+
+#[test]
+fn gpio_levels() {
+    let kernel = mock::session();
+    let mut pin = InputPin::new(0x42, 3, IRQ).unwrap();
+    assert!(pin.is_low().unwrap());
+    kernel.set_gpio(0x42, 3, true);
+    assert!(pin.is_high().unwrap());
+
+    // already high
+    executor::run(pin.wait_for_high()).unwrap();
+    assert_eq!(kernel.call_count(Syscall::IrqAcknowledge), 0);
+
+    let mut low = pin!(pin.wait_for_low());
+    assert!(step(low.as_mut()).is_none());
+    irqs(&kernel, 1);
+    assert!(step(low.as_mut()).is_none());
+    kernel.set_gpio(0x42, 3, false);
+    irqs(&kernel, 1);
+    assert!(step(low.as_mut()).unwrap().is_ok());
+    assert_eq!(kernel.call_count(Syscall::IrqAcknowledge), 2);
+}
+
+#[test]
+fn gpio_traits() {
+    use embedded_hal::digital::OutputPin as _;
+    use embedded_hal_async::digital::Wait;
+
+    let kernel = mock::session();
+    let mut output = OutputPin::new(0x42, 4).unwrap();
+    output.set_high().unwrap();
+    assert!(kernel.gpio(0x42, 4));
+    output.toggle().unwrap();
+    assert!(!kernel.gpio(0x42, 4));
+
+    let mut input = InputPin::new(0x42, 4, IRQ).unwrap();
+    assert!(embedded_hal::digital::InputPin::is_low(&mut input).unwrap());
+    let mut high = pin!(Wait::wait_for_high(&mut input));
+    assert!(step(high.as_mut()).is_none());
+    output.set_high().unwrap();
+    irqs(&kernel, 1);
+    assert!(step(high.as_mut()).unwrap().is_ok());
+}
+
+#[test]
+fn delay() {
+    use embedded_hal_async::delay::DelayNs;
+
+    let _kernel = mock::session();
+    let before = time::uptime_us().unwrap();
+    executor::run(Delay.delay_ms(3));
+    assert!(time::uptime_us().unwrap() - before >= 3000);
+}
+
+#[test]
+fn gpio_edges() {
+    let kernel = mock::session();
+    let mut pin = InputPin::new(0x42, 3, IRQ).unwrap();
+    kernel.set_gpio(0x42, 3, true);
+
+    // high, then a falling and a rising edge
+    {
+        let mut rising = pin!(pin.wait_for_rising_edge());
+        assert!(step(rising.as_mut()).is_none());
+        kernel.set_gpio(0x42, 3, false);
+        irqs(&kernel, 1);
+        assert!(step(rising.as_mut()).is_none());
+        kernel.set_gpio(0x42, 3, true);
+        irqs(&kernel, 1);
+        assert!(step(rising.as_mut()).unwrap().is_ok());
+    }
+
+    {
+        let mut any = pin!(pin.wait_for_any_edge());
+        assert!(step(any.as_mut()).is_none());
+        irqs(&kernel, 1);
+        assert!(step(any.as_mut()).unwrap().is_ok());
+    }
+    assert_eq!(kernel.call_count(Syscall::IrqAcknowledge), 3);
+
+    kernel.set_status(Syscall::GpioGet, Status::Denied);
+    let err = pin.is_high().unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::Gpio);
+    assert_eq!(err.handle(), Some(0x42));
+}
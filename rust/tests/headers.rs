@@ -0,0 +1,25 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Checks of the C headers generated from the Rust sources
+
+use std::path::Path;
+
+#[test]
+fn sys_types() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let config = cbindgen::Config::from_file(root.join("cbindgen/sys_types.toml")).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(root.join("src/ffi/types.rs"))
+        .generate()
+        .unwrap()
+        .write(&mut generated);
+    let header = std::fs::read(root.join("../include/shield/sys/types.h")).unwrap();
+    assert!(
+        generated == header,
+        "include/shield/sys/types.h is out of date, regenerate it as documented in cbindgen/sys_types.toml"
+    );
+}
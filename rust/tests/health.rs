@@ -0,0 +1,64 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Heartbeat tests against the fake kernel
+
+#![cfg(all(feature = "health", feature = "mock"))]
+
+use sentry_uapi::systypes::Status;
+use shield::{health, mock};
+
+#[derive(Default)]
+struct Iwdg {
+    feeds: u32,
+}
+
+impl health::Watchdog for Iwdg {
+    fn feed(&mut self) {
+        self.feeds += 1;
+    }
+}
+
+#[test]
+fn feeding() {
+    let kernel = mock::session();
+    let mut iwdg = Iwdg::default();
+    health::register("uart", 100).unwrap();
+    health::register("net", 500).unwrap();
+    assert!(health::feed(&mut iwdg).unwrap());
+
+    kernel.set_uptime_us(90_000);
+    health::beat("uart");
+    kernel.set_uptime_us(150_000);
+    assert!(health::feed(&mut iwdg).unwrap());
+
+    // the UART activity is stuck
+    health::beat("net");
+    kernel.set_uptime_us(200_000);
+    assert_eq!(health::stale().unwrap(), Some("uart"));
+    assert!(!health::feed(&mut iwdg).unwrap());
+    assert!(!health::feed(&mut iwdg).unwrap());
+    assert_eq!(iwdg.feeds, 2);
+    let log = String::from_utf8(kernel.log_output()).unwrap();
+    assert_eq!(log.matches("health: uart is stale").count(), 1);
+
+    health::beat("uart");
+    assert!(health::feed(&mut iwdg).unwrap());
+    health::unregister("net");
+    kernel.set_uptime_us(280_000);
+    assert!(health::feed(&mut iwdg).unwrap());
+}
+
+#[test]
+fn registration() {
+    let _kernel = mock::session();
+    for index in 0..health::MAX_BEATS {
+        let name: &'static str = format!("task{index}").leak();
+        health::register(name, 10).unwrap();
+    }
+    let err = health::register("task0", 10).unwrap_err();
+    assert!(err.status() == Status::Invalid);
+    let err = health::register("extra", 10).unwrap_err();
+    assert!(err.status() == Status::Busy);
+}
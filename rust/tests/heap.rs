@@ -0,0 +1,91 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Task heap allocator tests over a static region
+
+#![cfg(feature = "heap")]
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::ptr;
+use shield::heap::{Heap, HeapStats};
+
+const HEAP_LEN: usize = 1024;
+
+#[repr(align(64))]
+struct Region([u8; HEAP_LEN]);
+
+fn heap(region: &mut Region) -> Heap {
+    let heap = Heap::new();
+    unsafe { heap.init(region.0.as_mut_ptr(), HEAP_LEN) };
+    heap
+}
+
+#[test]
+fn alloc_and_merge() {
+    let mut region = Region([0; HEAP_LEN]);
+    let heap = heap(&mut region);
+    let layout = Layout::from_size_align(100, 8).unwrap();
+
+    let blocks: Vec<*mut u8> = (0..4).map(|_| unsafe { heap.alloc(layout) }).collect();
+    assert!(blocks.iter().all(|block| !block.is_null()));
+    for block in &blocks {
+        unsafe { ptr::write_bytes(*block, 0xa5, layout.size()) };
+    }
+    let stats = heap.stats();
+    assert_eq!(stats.allocations, 4);
+    assert!(stats.used >= 400);
+
+    // release out of order, the free blocks being merged back
+    for index in [1, 3, 0, 2] {
+        unsafe { heap.dealloc(blocks[index], layout) };
+    }
+    assert_eq!(
+        heap.stats(),
+        HeapStats {
+            size: HEAP_LEN,
+            used: 0,
+            peak: stats.used,
+            allocations: 0,
+            failures: 0,
+        }
+    );
+
+    // the whole region is available again
+    let all = Layout::from_size_align(HEAP_LEN, 8).unwrap();
+    let block = unsafe { heap.alloc(all) };
+    assert!(!block.is_null());
+    unsafe { heap.dealloc(block, all) };
+}
+
+#[test]
+fn alignment_and_exhaustion() {
+    let mut region = Region([0; HEAP_LEN]);
+    let heap = heap(&mut region);
+
+    let small = Layout::from_size_align(1, 1).unwrap();
+    let aligned = Layout::from_size_align(32, 256).unwrap();
+    let first = unsafe { heap.alloc(small) };
+    let second = unsafe { heap.alloc(aligned) };
+    assert_eq!(second.addr() % 256, 0);
+    assert!(second.addr() > first.addr());
+
+    let too_big = Layout::from_size_align(HEAP_LEN, 8).unwrap();
+    assert!(unsafe { heap.alloc(too_big) }.is_null());
+    assert_eq!(heap.stats().failures, 1);
+
+    unsafe {
+        heap.dealloc(second, aligned);
+        heap.dealloc(first, small);
+    }
+    assert_eq!(heap.stats().used, 0);
+    assert!(!unsafe { heap.alloc(too_big) }.is_null());
+}
+
+#[test]
+fn uninitialized() {
+    let heap = Heap::new();
+    let layout = Layout::from_size_align(8, 8).unwrap();
+    assert!(unsafe { heap.alloc(layout) }.is_null());
+    assert_eq!(heap.stats().failures, 1);
+}
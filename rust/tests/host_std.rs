@@ -0,0 +1,69 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Kernel-free modules tests, runnable under Miri:
+//! `cargo +nightly miri test --features host-std --test host_std`
+
+#![cfg(feature = "host-std")]
+
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+use log::Level;
+use sentry_uapi::systypes::Status;
+use shield::errno::Errno;
+use shield::executor::{Either, join2, select2};
+use shield::log::record::{HEADER_LEN, RecordDecoder, RecordHeader};
+use shield::{Context as _, Error, Subsystem};
+
+#[test]
+fn record_codec() {
+    let header = RecordHeader {
+        level: Level::Warn,
+        seq: 7,
+        task: 0xbabe,
+        timestamp_us: 1_234_567,
+        len: 5,
+    };
+    let mut stream = b"noise\n".to_vec();
+    let mut raw = [0; HEADER_LEN];
+    header.encode(&mut raw);
+    stream.extend_from_slice(&raw);
+    stream.extend_from_slice(b"hello");
+    // truncated record
+    stream.extend_from_slice(&raw[..HEADER_LEN - 1]);
+
+    assert_eq!(RecordHeader::decode(&raw).unwrap(), header);
+    let records: Vec<_> = RecordDecoder::new(&stream).collect();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].header, header);
+    assert_eq!(records[0].payload, "hello");
+}
+
+#[test]
+fn error_contexts() {
+    let result: Result<(), Error> =
+        Err(Error::new(Subsystem::Shm, Status::Denied).with_handle(0x42));
+    let err = result.context("mapping").context("startup").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "startup: mapping: shm: denied (handle 0x42)"
+    );
+    assert_eq!(Errno::from(err), Errno::EPERM);
+    assert!(Status::from(Errno::EPERM) == Status::Denied);
+}
+
+#[test]
+fn combinators() {
+    let mut cx = Context::from_waker(Waker::noop());
+
+    let mut join = pin!(join2(async { 1 }, async { "two" }));
+    assert_eq!(join.as_mut().poll(&mut cx), Poll::Ready((1, "two")));
+
+    let mut select = pin!(select2(std::future::pending::<()>(), async { 2 }));
+    assert_eq!(
+        select.as_mut().poll(&mut cx),
+        Poll::Ready(Either::Second(2))
+    );
+}
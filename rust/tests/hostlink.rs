@@ -0,0 +1,178 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! RTT host channel tests against the fake kernel, acting as the debugger
+
+#![cfg(all(feature = "hostlink", feature = "mock"))]
+
+use sentry_uapi::systypes::{SHMPermission, Status};
+use shield::error::Subsystem;
+use shield::hostlink::{HostLink, ID, TERMINAL};
+use shield::mock;
+use shield::shm::Shm;
+
+const PERMS: u32 =
+    SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+
+const ADDRESS: usize = size_of::<usize>();
+const DESCRIPTOR_LEN: usize = 2 * ADDRESS + 16;
+
+/// Debugger view of a control block
+struct Host {
+    base: usize,
+}
+
+impl Host {
+    fn ptr(&self, offset: usize) -> *mut u8 {
+        std::ptr::with_exposed_provenance_mut(self.base + offset)
+    }
+
+    fn word(&self, offset: usize) -> u32 {
+        unsafe { self.ptr(offset).cast::<u32>().read_volatile() }
+    }
+
+    fn set_word(&self, offset: usize, value: u32) {
+        unsafe { self.ptr(offset).cast::<u32>().write_volatile(value) }
+    }
+
+    fn address(&self, offset: usize) -> usize {
+        unsafe { self.ptr(offset).cast::<usize>().read_volatile() }
+    }
+
+    /// Return the descriptor offset of the channel `index`, up channels first
+    fn descriptor(&self, index: usize) -> usize {
+        24 + index * DESCRIPTOR_LEN
+    }
+
+    /// Return the buffer address, size, write and read offsets of a channel
+    fn channel(&self, index: usize) -> (usize, u32, u32, u32) {
+        let descriptor = self.descriptor(index);
+        (
+            self.address(descriptor + ADDRESS),
+            self.word(descriptor + 2 * ADDRESS),
+            self.word(descriptor + 2 * ADDRESS + 4),
+            self.word(descriptor + 2 * ADDRESS + 8),
+        )
+    }
+
+    /// Read the bytes of the up channel `index`, as a debugger does
+    fn read_up(&self, index: usize) -> Vec<u8> {
+        let (buffer, size, write, mut read) = self.channel(index);
+        let mut bytes = Vec::new();
+        while read != write {
+            bytes.push(unsafe {
+                std::ptr::with_exposed_provenance::<u8>(buffer + read as usize).read()
+            });
+            read = (read + 1) % size;
+        }
+        self.set_word(self.descriptor(index) + 2 * ADDRESS + 8, read);
+        bytes
+    }
+
+    /// Write bytes into the down channel `index`, as a debugger does
+    fn write_down(&self, index: usize, bytes: &[u8]) {
+        let (buffer, size, mut write, _) = self.channel(index);
+        for &byte in bytes {
+            unsafe {
+                std::ptr::with_exposed_provenance_mut::<u8>(buffer + write as usize).write(byte);
+            }
+            write = (write + 1) % size;
+        }
+        self.set_word(self.descriptor(index) + 2 * ADDRESS + 4, write);
+    }
+}
+
+fn link(kernel: &mock::Session, len: usize, channels: usize) -> (HostLink, Host) {
+    let base = kernel.add_shm(0x20, 0x120, len, PERMS);
+    let link = HostLink::new(Shm::new(0x20).unwrap().map(0).unwrap(), channels).unwrap();
+    (link, Host { base })
+}
+
+#[test]
+fn control_block() {
+    let kernel = mock::session();
+    let (link, host) = link(&kernel, 512, 2);
+    let id = unsafe { std::slice::from_raw_parts(host.ptr(0), 16) };
+    assert_eq!(&id[..10], ID);
+    assert_eq!(id[10..], [0; 6]);
+    assert_eq!((host.word(16), host.word(20)), (2, 2));
+
+    // the first up and down channels are named, the others unnamed
+    for index in 0..4 {
+        let name = host.address(host.descriptor(index));
+        if index % 2 == 0 {
+            let name = unsafe { std::ffi::CStr::from_ptr(std::ptr::with_exposed_provenance(name)) };
+            assert_eq!(name.to_str(), Ok(TERMINAL));
+        } else {
+            assert_eq!(name, 0);
+        }
+        let (buffer, size, write, read) = host.channel(index);
+        assert!(
+            buffer >= host.base + host.descriptor(4) && buffer + size as usize <= host.base + 512
+        );
+        assert_eq!(size as usize, link.capacity() + 1);
+        assert_eq!((write, read), (0, 0));
+    }
+    assert_eq!(host.word(host.descriptor(0) + 2 * ADDRESS + 12), 1);
+    assert_eq!(host.word(host.descriptor(2) + 2 * ADDRESS + 12), 0);
+}
+
+#[test]
+fn up_channel_trims() {
+    let kernel = mock::session();
+    let (mut link, host) = link(&kernel, 256, 1);
+    let capacity = link.capacity();
+
+    assert_eq!(link.write(0, b"booted\n").unwrap(), 7);
+    assert_eq!(host.read_up(0), b"booted\n");
+
+    // wrapping, then trimmed once full
+    let data: Vec<u8> = (0..=255).collect();
+    assert_eq!(link.write(0, &data).unwrap(), capacity);
+    assert_eq!(link.write(0, b"lost").unwrap(), 0);
+    assert_eq!(host.read_up(0), data[..capacity]);
+    assert_eq!(link.write(0, b"again").unwrap(), 5);
+    assert_eq!(host.read_up(0), b"again");
+}
+
+#[test]
+fn down_channel() {
+    let kernel = mock::session();
+    let (mut link, host) = link(&kernel, 512, 2);
+    let mut buf = [0; 8];
+    assert_eq!(link.read(0, &mut buf).unwrap(), 0);
+
+    host.write_down(3, b"reset\n");
+    assert_eq!(link.available(0), 0);
+    assert_eq!(link.available(1), 6);
+    assert_eq!(link.read(1, &mut buf[..4]).unwrap(), 4);
+    assert_eq!(&buf[..4], b"rese");
+    assert_eq!(link.read(1, &mut buf).unwrap(), 2);
+    assert_eq!(&buf[..2], b"t\n");
+    assert_eq!(link.available(1), 0);
+}
+
+#[test]
+fn invalid() {
+    let kernel = mock::session();
+    let (mut link, host) = link(&kernel, 256, 1);
+    let err = link.write(1, b"x").unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::HostLink);
+    assert_eq!(err.status(), Status::Invalid);
+    assert!(link.read(1, &mut [0; 4]).is_err());
+
+    // corrupted by the host
+    host.set_word(host.descriptor(1) + 2 * ADDRESS + 4, 0xffff);
+    assert_eq!(
+        link.read(0, &mut [0; 4]).unwrap_err().status(),
+        Status::Invalid
+    );
+
+    kernel.add_shm(0x21, 0x121, 64, PERMS);
+    let shm = Shm::new(0x21).unwrap().map(0).unwrap();
+    assert!(HostLink::new(shm, 1).is_err());
+    kernel.add_shm(0x22, 0x122, 4096, PERMS);
+    let shm = Shm::new(0x22).unwrap().map(0).unwrap();
+    assert!(HostLink::new(shm, 5).is_err());
+}
@@ -0,0 +1,155 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Input events tests against the fake kernel
+
+#![cfg(all(feature = "input", feature = "mock"))]
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use sentry_uapi::systypes::{EventType, Status, Syscall};
+use shield::input::{Input, InputEvent, InputKind, InputSource, Sample};
+use shield::{executor, mock};
+
+/// Source whose next samples are set by the test
+struct Fake {
+    irq: u16,
+    sample: Rc<Cell<Sample>>,
+}
+
+impl InputSource for Fake {
+    fn irq(&self) -> u16 {
+        self.irq
+    }
+
+    fn sample(&mut self) -> Sample {
+        let sample = self.sample.get();
+        if let Sample::Encoder(_) = sample {
+            self.sample.set(Sample::Encoder(0));
+        }
+        sample
+    }
+}
+
+fn irq(kernel: &mock::Session, uptime_ms: u64, irq: u16) {
+    kernel.set_uptime_us(uptime_ms * 1000);
+    kernel.push_event(EventType::Irq, 0, &u32::from(irq).to_ne_bytes());
+}
+
+fn event(uptime_ms: u64, source: u8, kind: InputKind) -> InputEvent {
+    InputEvent {
+        timestamp_us: uptime_ms * 1000,
+        source,
+        kind,
+    }
+}
+
+/// Handle the pending IRQ, if any, returning the first event queued
+fn poll(input: &mut Input<'_, 2>) -> Option<InputEvent> {
+    let mut yielded = false;
+    let yield_once = std::future::poll_fn(|cx| {
+        if std::mem::replace(&mut yielded, true) {
+            return std::task::Poll::Ready(());
+        }
+        cx.waker().wake_by_ref();
+        std::task::Poll::Pending
+    });
+    match executor::run(executor::select2(input.next(), yield_once)) {
+        executor::Either::First(event) => Some(event.unwrap()),
+        executor::Either::Second(()) => None,
+    }
+}
+
+#[test]
+fn button() {
+    let kernel = mock::session();
+    let level = Rc::new(Cell::new(Sample::Button(false)));
+    let mut button = Fake {
+        irq: 10,
+        sample: level.clone(),
+    };
+    let mut input: Input<'_, 2> = Input::new(20);
+    let id = input.add(&mut button).unwrap();
+
+    level.set(Sample::Button(true));
+    irq(&kernel, 100, 10);
+    assert_eq!(poll(&mut input), Some(event(100, id, InputKind::Press)));
+
+    // bounces
+    level.set(Sample::Button(false));
+    irq(&kernel, 105, 10);
+    assert_eq!(poll(&mut input), None);
+    level.set(Sample::Button(true));
+    irq(&kernel, 110, 10);
+    assert_eq!(poll(&mut input), None);
+
+    level.set(Sample::Button(false));
+    irq(&kernel, 200, 10);
+    assert_eq!(poll(&mut input), Some(event(200, id, InputKind::Release)));
+    assert_eq!(kernel.call_count(Syscall::IrqAcknowledge), 4);
+}
+
+#[test]
+fn touch_and_encoder() {
+    let kernel = mock::session();
+    let contact = Rc::new(Cell::new(Sample::Touch(None)));
+    let steps = Rc::new(Cell::new(Sample::Encoder(0)));
+    let mut touch = Fake {
+        irq: 11,
+        sample: contact.clone(),
+    };
+    let mut encoder = Fake {
+        irq: 12,
+        sample: steps.clone(),
+    };
+    let mut input: Input<'_, 2> = Input::new(20);
+    let touch_id = input.add(&mut touch).unwrap();
+    let encoder_id = input.add(&mut encoder).unwrap();
+
+    contact.set(Sample::Touch(Some((3, 4))));
+    irq(&kernel, 1, 11);
+    let mut events = Vec::new();
+    for _ in 0..2 {
+        events.push(executor::run(input.next()).unwrap());
+    }
+    contact.set(Sample::Touch(Some((5, 4))));
+    irq(&kernel, 2, 11);
+    events.push(executor::run(input.next()).unwrap());
+    contact.set(Sample::Touch(None));
+    irq(&kernel, 3, 11);
+    events.push(executor::run(input.next()).unwrap());
+    steps.set(Sample::Encoder(-2));
+    irq(&kernel, 4, 12);
+    events.push(executor::run(input.next()).unwrap());
+    assert_eq!(
+        events,
+        [
+            event(1, touch_id, InputKind::Move { x: 3, y: 4 }),
+            event(1, touch_id, InputKind::Press),
+            event(2, touch_id, InputKind::Move { x: 5, y: 4 }),
+            event(3, touch_id, InputKind::Release),
+            event(4, encoder_id, InputKind::Move { x: -2, y: 0 }),
+        ]
+    );
+}
+
+#[test]
+fn no_source() {
+    let _kernel = mock::session();
+    let mut input: Input<'_, 1> = Input::new(20);
+    let error = executor::run(input.next()).unwrap_err();
+    assert_eq!(error.status(), Status::Invalid);
+    let mut button = Fake {
+        irq: 10,
+        sample: Rc::new(Cell::new(Sample::Button(false))),
+    };
+    let mut other = Fake {
+        irq: 10,
+        sample: Rc::new(Cell::new(Sample::Button(false))),
+    };
+    input.add(&mut button).unwrap();
+    let error = input.add(&mut other).unwrap_err();
+    assert_eq!(error.status(), Status::Busy);
+}
@@ -0,0 +1,113 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! IPC byte stream tests against the fake kernel
+
+#![cfg(all(feature = "ipc", feature = "embedded-io-async", feature = "mock"))]
+
+use embedded_io_async::{Read, Write};
+use sentry_uapi::systypes::{EventType, Signal, Status, Syscall};
+use shield::error::Subsystem;
+use shield::ipc::{IpcStream, MAILBOX_READY_SIGNAL, MAX_MESSAGE_LEN};
+use shield::{executor, mock};
+
+const PEER: u32 = 0x1234;
+
+#[test]
+fn read_grants_a_message() {
+    let kernel = mock::session();
+    kernel.push_event(EventType::Ipc, PEER, b"hello");
+    let mut stream = IpcStream::new(PEER);
+
+    let mut buf = [0; 3];
+    assert_eq!(executor::run(stream.read(&mut buf)).unwrap(), 3);
+    assert_eq!(&buf, b"hel");
+    assert_eq!(kernel.signals(), [(PEER, MAILBOX_READY_SIGNAL)]);
+
+    // the rest of the message is buffered, without a new grant
+    assert_eq!(stream.available(), 2);
+    assert_eq!(executor::run(stream.read(&mut buf)).unwrap(), 2);
+    assert_eq!(&buf[..2], b"lo");
+    assert_eq!(kernel.signals().len(), 1);
+}
+
+#[test]
+fn read_ignores_other_peers() {
+    let kernel = mock::session();
+    kernel.push_event(EventType::Ipc, PEER + 1, b"other");
+    kernel.push_event(EventType::Ipc, PEER, b"mine");
+    let mut stream = IpcStream::new(PEER);
+
+    let mut buf = [0; 8];
+    assert_eq!(executor::run(stream.read(&mut buf)).unwrap(), 4);
+    assert_eq!(&buf[..4], b"mine");
+    assert_eq!(executor::run(stream.read(&mut [])).unwrap(), 0);
+}
+
+#[test]
+fn read_exact_across_messages() {
+    let kernel = mock::session();
+    kernel.push_event(EventType::Ipc, PEER, b"ab");
+    kernel.push_event(EventType::Ipc, PEER, b"cd");
+    let mut stream = IpcStream::new(PEER);
+
+    let mut buf = [0; 4];
+    executor::run(stream.read_exact(&mut buf)).unwrap();
+    assert_eq!(&buf, b"abcd");
+    assert_eq!(kernel.signals().len(), 2);
+}
+
+#[test]
+fn write_waits_for_the_reader() {
+    let kernel = mock::session();
+    let message = [0x5a; MAX_MESSAGE_LEN + 3];
+    kernel.push_signal(MAILBOX_READY_SIGNAL, PEER + 1);
+    kernel.push_signal(Signal::Usr1, PEER);
+    kernel.push_signal(MAILBOX_READY_SIGNAL, PEER);
+    kernel.push_signal(MAILBOX_READY_SIGNAL, PEER);
+    let mut stream = IpcStream::new(PEER);
+
+    executor::run(stream.write_all(&message)).unwrap();
+    executor::run(stream.flush()).unwrap();
+    let sent = kernel.sent_ipc();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(sent[0], (PEER, message[..MAX_MESSAGE_LEN].to_vec()));
+    assert_eq!(sent[1], (PEER, message[..3].to_vec()));
+    assert_eq!(executor::run(stream.write(&[])).unwrap(), 0);
+}
+
+#[test]
+fn peer_failures() {
+    let kernel = mock::session();
+    kernel.push_signal(MAILBOX_READY_SIGNAL, PEER);
+    kernel.set_status(Syscall::SendIPC, Status::Intr);
+    let mut stream = IpcStream::new(PEER);
+    let err = executor::run(stream.write(b"ping")).unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::Ipc);
+    assert_eq!(err.status(), Status::Intr);
+    assert_eq!(err.handle(), Some(PEER));
+
+    kernel.set_status(Syscall::SendSignal, Status::Invalid);
+    let err = executor::run(stream.read(&mut [0; 4])).unwrap_err();
+    assert_eq!(err.status(), Status::Invalid);
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn cbor_messages() {
+    let kernel = mock::session();
+    kernel.push_signal(MAILBOX_READY_SIGNAL, PEER);
+    kernel.push_event(EventType::Ipc, PEER, &[0x82, 0x01, 0x61, b'a']);
+    let mut stream = IpcStream::new(PEER);
+
+    executor::run(stream.send_cbor(&-1000i16)).unwrap();
+    assert_eq!(kernel.sent_ipc(), [(PEER, vec![0x39, 0x03, 0xe7])]);
+
+    let mut buf = [0; MAX_MESSAGE_LEN];
+    let err = executor::run(stream.receive_cbor::<u32>(&mut buf)).unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::Encoding);
+
+    let err = executor::run(stream.send_cbor(&[0u8; MAX_MESSAGE_LEN][..])).unwrap_err();
+    assert_eq!(err.status(), Status::Busy);
+}
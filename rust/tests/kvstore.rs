@@ -0,0 +1,272 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Key-value store tests over a simulated NOR flash
+
+#![cfg(feature = "kvstore")]
+
+use sentry_uapi::systypes::Status;
+use shield::error::{Error, Subsystem};
+use shield::kvstore::{Backing, KvStore};
+
+const ERASE_SIZE: usize = 256;
+const WRITE_SIZE: usize = 8;
+
+/// NOR flash, programs failing halfway once `budget` programs are done
+struct Flash {
+    memory: Vec<u8>,
+    write_size: usize,
+    budget: Option<usize>,
+}
+
+impl Flash {
+    fn new(sectors: usize) -> Self {
+        Self::with_write_size(sectors, WRITE_SIZE)
+    }
+
+    fn with_write_size(sectors: usize, write_size: usize) -> Self {
+        Self {
+            memory: vec![0xff; sectors * ERASE_SIZE],
+            write_size,
+            budget: None,
+        }
+    }
+
+    /// Power cycle, the flash content being kept
+    fn reboot(self) -> Self {
+        Self {
+            memory: self.memory,
+            write_size: self.write_size,
+            budget: None,
+        }
+    }
+}
+
+impl Backing for Flash {
+    fn size(&self) -> usize {
+        self.memory.len()
+    }
+
+    fn erase_size(&self) -> usize {
+        ERASE_SIZE
+    }
+
+    fn write_size(&self) -> usize {
+        self.write_size
+    }
+
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), Error> {
+        buf.copy_from_slice(&self.memory[offset..offset + buf.len()]);
+        Ok(())
+    }
+
+    fn program(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        assert!(
+            offset.is_multiple_of(self.write_size) && data.len().is_multiple_of(self.write_size)
+        );
+        let target = &mut self.memory[offset..offset + data.len()];
+        assert!(
+            target.iter().all(|&byte| byte == 0xff),
+            "programming {offset:#x} twice"
+        );
+        match &mut self.budget {
+            Some(0) => {
+                let half = data.len() / 2;
+                target[..half].copy_from_slice(&data[..half]);
+                Err(Error::new(Subsystem::Storage, Status::Critical))
+            }
+            budget => {
+                if let Some(budget) = budget {
+                    *budget -= 1;
+                }
+                target.copy_from_slice(data);
+                Ok(())
+            }
+        }
+    }
+
+    fn erase(&mut self, offset: usize, len: usize) -> Result<(), Error> {
+        assert!(offset.is_multiple_of(ERASE_SIZE) && len.is_multiple_of(ERASE_SIZE));
+        self.memory[offset..offset + len].fill(0xff);
+        Ok(())
+    }
+}
+
+fn get(store: &KvStore<Flash>, key: &[u8]) -> Option<Vec<u8>> {
+    let mut buf = [0; 64];
+    let len = store.get(key, &mut buf).unwrap()?;
+    Some(buf[..len].to_vec())
+}
+
+#[test]
+fn set_get_delete() {
+    let mut store = KvStore::open(Flash::new(4)).unwrap();
+    assert_eq!(get(&store, b"gain"), None);
+
+    store.set(b"gain", &[1, 2, 3]).unwrap();
+    store.set(b"offset", b"-12").unwrap();
+    store.set(b"gain", &[4, 5]).unwrap();
+    assert_eq!(get(&store, b"gain").unwrap(), [4, 5]);
+    assert_eq!(get(&store, b"offset").unwrap(), b"-12");
+
+    // rewriting the current value doesn't program the flash
+    let free = store.free();
+    store.set(b"gain", &[4, 5]).unwrap();
+    assert_eq!(store.free(), free);
+
+    assert!(store.delete(b"offset").unwrap());
+    assert!(!store.delete(b"offset").unwrap());
+    assert_eq!(get(&store, b"offset"), None);
+
+    let mut small = [0; 1];
+    let err = store.get(b"gain", &mut small).unwrap_err();
+    assert!(err.status() == Status::Invalid);
+    let err = store.set(&[b'k'; 33], b"").unwrap_err();
+    assert!(err.status() == Status::Invalid);
+
+    let store = KvStore::open(store.release().reboot()).unwrap();
+    assert_eq!(get(&store, b"gain").unwrap(), [4, 5]);
+    assert_eq!(get(&store, b"offset"), None);
+}
+
+#[test]
+fn iterate() {
+    let mut store = KvStore::open(Flash::new(4)).unwrap();
+    store.set(b"a", b"1").unwrap();
+    store.set(b"b", b"22").unwrap();
+    store.set(b"c", b"333").unwrap();
+    store.set(b"a", b"4444").unwrap();
+    store.delete(b"b").unwrap();
+
+    let mut pairs = Vec::new();
+    for entry in store.iter() {
+        let entry = entry.unwrap();
+        let mut value = vec![0; entry.value_len()];
+        store.read_value(&entry, &mut value).unwrap();
+        pairs.push((entry.key().to_vec(), value));
+    }
+    assert_eq!(
+        pairs,
+        [
+            (b"c".to_vec(), b"333".to_vec()),
+            (b"a".to_vec(), b"4444".to_vec())
+        ]
+    );
+}
+
+#[test]
+fn compaction() {
+    let mut store = KvStore::open(Flash::new(4)).unwrap();
+    store.set(b"serial", b"SN-0042").unwrap();
+    for count in 0_u32..500 {
+        store.set(b"boots", &count.to_le_bytes()).unwrap();
+    }
+    assert_eq!(get(&store, b"boots").unwrap(), 499_u32.to_le_bytes());
+    assert_eq!(get(&store, b"serial").unwrap(), b"SN-0042");
+
+    let store = KvStore::open(store.release().reboot()).unwrap();
+    assert_eq!(get(&store, b"boots").unwrap(), 499_u32.to_le_bytes());
+    assert_eq!(get(&store, b"serial").unwrap(), b"SN-0042");
+    assert_eq!(store.iter().count(), 2);
+}
+
+#[test]
+fn compaction_byte_writes() {
+    for write_size in [1, 2] {
+        let mut store = KvStore::open(Flash::with_write_size(4, write_size)).unwrap();
+        store.set(b"serial", b"SN-0042").unwrap();
+        store.set(b"mode", b"a").unwrap();
+        for count in 0_u32..300 {
+            store.set(b"boots", &count.to_le_bytes()).unwrap();
+        }
+        store.compact().unwrap();
+        assert_eq!(get(&store, b"serial").unwrap(), b"SN-0042");
+        assert_eq!(get(&store, b"mode").unwrap(), b"a");
+
+        let store = KvStore::open(store.release().reboot()).unwrap();
+        assert_eq!(get(&store, b"boots").unwrap(), 299_u32.to_le_bytes());
+        assert_eq!(get(&store, b"serial").unwrap(), b"SN-0042");
+        assert_eq!(get(&store, b"mode").unwrap(), b"a");
+        assert_eq!(store.iter().count(), 3);
+    }
+}
+
+#[test]
+fn full() {
+    let mut store = KvStore::open(Flash::new(2)).unwrap();
+    let value = [0x42; 60];
+    let err = (0..10_u8)
+        .try_for_each(|key| store.set(&[b'k', key], &value))
+        .unwrap_err();
+    assert!(err.status() == Status::Busy);
+    assert_eq!(get(&store, b"k\0").unwrap(), value);
+}
+
+#[test]
+fn torn_record() {
+    let mut store = KvStore::open(Flash::new(4)).unwrap();
+    store.set(b"mode", b"normal").unwrap();
+
+    let mut flash = store.release();
+    flash.budget = Some(0);
+    let mut store = KvStore::open(flash).unwrap();
+    assert!(store.set(b"mode", b"calibration pending").is_err());
+
+    let mut store = KvStore::open(store.release().reboot()).unwrap();
+    assert_eq!(get(&store, b"mode").unwrap(), b"normal");
+    store.set(b"mode", b"safe").unwrap();
+    assert_eq!(get(&store, b"mode").unwrap(), b"safe");
+
+    let store = KvStore::open(store.release().reboot()).unwrap();
+    assert_eq!(get(&store, b"mode").unwrap(), b"safe");
+}
+
+#[test]
+fn interrupted_compaction() {
+    let mut store = KvStore::open(Flash::new(4)).unwrap();
+    store.set(b"a", b"first").unwrap();
+    store.set(b"b", b"second").unwrap();
+
+    let mut flash = store.release();
+    flash.budget = Some(1);
+    let mut store = KvStore::open(flash).unwrap();
+    assert!(store.compact().is_err());
+
+    let mut store = KvStore::open(store.release().reboot()).unwrap();
+    assert_eq!(get(&store, b"a").unwrap(), b"first");
+    assert_eq!(get(&store, b"b").unwrap(), b"second");
+    store.compact().unwrap();
+    let store = KvStore::open(store.release().reboot()).unwrap();
+    assert_eq!(get(&store, b"a").unwrap(), b"first");
+    assert_eq!(get(&store, b"b").unwrap(), b"second");
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn cbor_values() {
+    let mut store = KvStore::open(Flash::new(4)).unwrap();
+    let mut buf = [0; 16];
+    store.set_cbor(b"gain", &-12i32, &mut buf).unwrap();
+    store.set_cbor(b"name", "sensor", &mut buf).unwrap();
+    assert_eq!(get(&store, b"gain").unwrap(), [0x2b]);
+    assert_eq!(store.get_cbor::<i32>(b"gain", &mut buf).unwrap(), Some(-12));
+    assert_eq!(
+        store.get_cbor::<&str>(b"name", &mut buf).unwrap(),
+        Some("sensor")
+    );
+    assert_eq!(store.get_cbor::<i32>(b"none", &mut buf).unwrap(), None);
+
+    let err = store.get_cbor::<u8>(b"gain", &mut buf).unwrap_err();
+    assert_eq!(
+        (err.subsystem(), err.status()),
+        (Subsystem::Encoding, Status::Invalid)
+    );
+    let err = store
+        .set_cbor(b"name", "a name much too long", &mut buf)
+        .unwrap_err();
+    assert_eq!(
+        (err.subsystem(), err.status()),
+        (Subsystem::Encoding, Status::Busy)
+    );
+}
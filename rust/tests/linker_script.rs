@@ -0,0 +1,17 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Generated linker script tests
+
+#![cfg(feature = "linker-script")]
+
+const SCRIPT: &str = include_str!(concat!(env!("OUT_DIR"), "/shield.ld"));
+
+#[test]
+fn template_filled() {
+    assert!(!SCRIPT.contains('@'));
+    assert!(SCRIPT.contains("_sheap = .;"));
+    assert!(SCRIPT.contains("KEEP(*(.noinit*))"));
+    assert!(SCRIPT.contains("APP_RAM (rw): ORIGIN = 0x"));
+}
@@ -0,0 +1,34 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Log backend tests against the fake kernel
+
+#![cfg(all(feature = "log", feature = "mock"))]
+
+use shield::log::{self, Level, LevelFilter};
+use shield::mock;
+
+#[test]
+fn hexdump() {
+    let kernel = mock::session();
+    log::init(LevelFilter::Info).unwrap();
+
+    let frame = *b"Hello, secure world";
+    log::hexdump!(frame);
+    assert!(kernel.log_output().is_empty());
+
+    log::hexdump!(Level::Warn, &frame[..]);
+    log::hexdump!(Level::Info, [0u8; 0]);
+    let output = String::from_utf8(kernel.log_output()).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "[WARN ] log: &frame[..]: 19 bytes",
+            "[WARN ] log: 0000: 48656c6c6f2c2073656375726520776f Hello, secure wo",
+            "[WARN ] log: 0010: 726c64                           rld",
+            "[INFO ] log: [0u8; 0]: 0 bytes",
+        ]
+    );
+}
@@ -0,0 +1,93 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Metrics export tests against the fake kernel
+
+#![cfg(all(feature = "metrics", feature = "mock"))]
+
+use sentry_uapi::systypes::{SHMPermission, Status};
+use shield::metrics::{ENTRY_LEN, Registry, Scraper, Value};
+use shield::mock;
+use shield::shm::Shm;
+
+const PERMS: u32 =
+    SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+
+#[test]
+fn scrape() {
+    let kernel = mock::session();
+    let base = kernel.add_shm(0x10, 0x110, 16 + 3 * ENTRY_LEN, PERMS);
+    let registry = Registry::new(Shm::new(0x10).unwrap().map(0).unwrap()).unwrap();
+    assert_eq!(registry.capacity(), 3);
+
+    let errors = registry.counter("rx_errors").unwrap();
+    let depth = registry.gauge("queue_depth").unwrap();
+    let latency = registry
+        .histogram("loop_us", &[10, 20, 50, 100, 200, 500, 1000])
+        .unwrap();
+    let full = registry.counter("dropped").err().unwrap();
+    assert!(full.status() == Status::Busy);
+
+    errors.increment();
+    errors.add(2);
+    depth.set(5);
+    depth.set(-1);
+    for sample in [0, 10, 11, 700, 5000, 5001] {
+        latency.record(sample);
+    }
+    assert_eq!(errors.get(), 3);
+    assert_eq!((depth.get(), depth.max()), (-1, 5));
+
+    // header and first name, as laid out for the supervisor
+    let header =
+        unsafe { std::slice::from_raw_parts(std::ptr::with_exposed_provenance::<u8>(base), 32) };
+    assert_eq!(&header[..16], b"CRTM\x01\0\0\0\x03\0\0\0\x03\0\0\0");
+    assert_eq!(&header[16..26], b"rx_errors\0");
+
+    let scraper = Scraper::new(registry.release()).unwrap();
+    let metrics: Vec<_> = scraper.iter().collect();
+    assert_eq!(metrics.len(), 3);
+    assert_eq!(metrics[0].name(), "rx_errors");
+    assert_eq!(metrics[0].value, Value::Counter(3));
+    assert_eq!(metrics[1].name(), "queue_depth");
+    assert_eq!(metrics[1].value, Value::Gauge { value: -1, max: 5 });
+    assert_eq!(metrics[2].name(), "loop_us");
+    assert_eq!(
+        metrics[2].value,
+        Value::Histogram {
+            bounds: [10, 20, 50, 100, 200, 500, 1000],
+            buckets: [2, 1, 0, 0, 0, 0, 1, 2],
+        }
+    );
+}
+
+#[test]
+fn invalid() {
+    let kernel = mock::session();
+    kernel.add_shm(0x10, 0x110, 16 + ENTRY_LEN, PERMS);
+    kernel.add_shm(0x11, 0x111, 16, PERMS);
+    let registry = Registry::new(Shm::new(0x10).unwrap().map(0).unwrap()).unwrap();
+    for name in ["", "a_much_too_long_name"] {
+        let err = registry.counter(name).err().unwrap();
+        assert!(err.status() == Status::Invalid);
+    }
+    let err = registry
+        .histogram("h", &[1, 2, 3, 3, 4, 5, 6])
+        .err()
+        .unwrap();
+    assert!(err.status() == Status::Invalid);
+    assert!(registry.is_empty());
+
+    let err = Registry::new(Shm::new(0x11).unwrap().map(0).unwrap())
+        .err()
+        .unwrap();
+    assert!(err.status() == Status::Invalid);
+
+    // a zeroed shared memory holds no metrics table
+    kernel.add_shm(0x12, 0x112, 16 + ENTRY_LEN, PERMS);
+    let err = Scraper::new(Shm::new(0x12).unwrap().map(0).unwrap())
+        .err()
+        .unwrap();
+    assert!(err.status() == Status::Invalid);
+}
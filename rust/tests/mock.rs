@@ -0,0 +1,346 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Shield logic tests against the fake kernel
+
+#![cfg(feature = "mock")]
+
+use sentry_uapi::systypes::dma::{GpdmaStreamConfig, GpdmaTransferType};
+use sentry_uapi::systypes::{EventType, SHMPermission, Signal, Status, Syscall};
+use shield::dma::DmaStream;
+use shield::shm::{self, DMA_COPY_THRESHOLD, FetchPolicy, Shm};
+use shield::{executor, mock, process, random, time};
+
+const SHM_LABEL: u32 = 0xf00;
+const SHM_HANDLE: u32 = 0x1f00;
+
+#[test]
+fn shm_lifecycle() {
+    let kernel = mock::session();
+    let perms = SHMPermission::Map as u32 | SHMPermission::Write as u32;
+    let base = kernel.add_shm(SHM_LABEL, SHM_HANDLE, 256, perms);
+
+    let mut shm = Shm::new(SHM_LABEL).unwrap();
+    shm.set_credentials(0x42, SHMPermission::Map as u32)
+        .unwrap();
+    assert_eq!(
+        kernel.credentials(SHM_HANDLE),
+        [(0x42, SHMPermission::Map as u32)]
+    );
+
+    let mut shm = shm.map(0).unwrap();
+    assert!(kernel.is_mapped(SHM_HANDLE));
+    assert_eq!(shm.base_address().unwrap(), base);
+    assert_eq!(shm.length().unwrap(), 256);
+    assert!(shm.is_writable());
+    assert!(!shm.is_transferable());
+
+    shm.unmap().unwrap();
+    assert!(!kernel.is_mapped(SHM_HANDLE));
+}
+
+#[test]
+fn shm_prefetched() {
+    let kernel = mock::session();
+    let perms = SHMPermission::Map as u32 | SHMPermission::Read as u32;
+    let first = kernel.add_shm(SHM_LABEL, SHM_HANDLE, 64, perms);
+    let second = kernel.add_shm(SHM_LABEL + 1, SHM_HANDLE + 1, 128, 0);
+
+    let [mut a, mut b] = Shm::new_prefetched([SHM_LABEL, SHM_LABEL + 1]).unwrap();
+    assert_eq!(kernel.call_count(Syscall::GetShmHandle), 2);
+    assert_eq!(kernel.call_count(Syscall::ShmGetInfos), 2);
+    assert_eq!(
+        (a.base_address().unwrap(), a.length().unwrap()),
+        (first, 64)
+    );
+    assert_eq!(
+        (b.base_address().unwrap(), b.length().unwrap()),
+        (second, 128)
+    );
+    assert!(a.is_readable() && !b.is_mappable());
+    assert_eq!(kernel.call_count(Syscall::ShmGetInfos), 2);
+
+    assert!(Shm::new_prefetched([SHM_LABEL, SHM_LABEL + 2]).is_err());
+
+    // more objects than cached entries
+    const COUNT: usize = shm::MAX_CACHED_SHM + 2;
+    for index in 2..COUNT as u32 {
+        kernel.add_shm(SHM_LABEL + index, SHM_HANDLE + index, 32, 0);
+    }
+    let mut shms = Shm::new_prefetched(core::array::from_fn::<_, COUNT, _>(|index| {
+        SHM_LABEL + index as u32
+    }))
+    .unwrap();
+    let calls = kernel.call_count(Syscall::ShmGetInfos);
+    assert_eq!(shms[0].length().unwrap(), 64);
+    assert!(
+        shms.iter_mut()
+            .skip(2)
+            .all(|shm| shm.length().unwrap() == 32)
+    );
+    assert_eq!(kernel.call_count(Syscall::ShmGetInfos), calls);
+}
+
+#[test]
+fn shm_evicted_info() {
+    let kernel = mock::session();
+    kernel.add_shm(SHM_LABEL, SHM_HANDLE, 64, SHMPermission::Read as u32);
+    let mut eager = Shm::new_with(SHM_LABEL, FetchPolicy::Eager).unwrap();
+
+    // evicted from the cache by the other shared memories
+    for index in 1..=shm::MAX_CACHED_SHM as u32 {
+        kernel.add_shm(SHM_LABEL + index, SHM_HANDLE + index, 32, 0);
+        Shm::new_with(SHM_LABEL + index, FetchPolicy::Eager).unwrap();
+    }
+    let calls = kernel.call_count(Syscall::ShmGetInfos);
+    assert_eq!(eager.length().unwrap(), 64);
+    assert!(eager.is_readable());
+    assert_eq!(kernel.call_count(Syscall::ShmGetInfos), calls);
+
+    // held until the next invalidation, of any shared memory
+    shm::invalidate_info(SHM_HANDLE + 1);
+    assert_eq!(eager.length().unwrap(), 64);
+    assert_eq!(kernel.call_count(Syscall::ShmGetInfos), calls + 1);
+}
+
+#[test]
+fn shm_shared_info() {
+    let kernel = mock::session();
+    kernel.add_shm(SHM_LABEL, SHM_HANDLE, 64, SHMPermission::Map as u32);
+
+    let mut a = Shm::new(SHM_LABEL).unwrap();
+    let mut b = Shm::new(SHM_LABEL).unwrap();
+    assert_eq!(a.length().unwrap(), 64);
+    assert!(b.is_mappable());
+    assert_eq!(kernel.call_count(Syscall::ShmGetInfos), 1);
+
+    a.set_credentials(0x42, 0).unwrap();
+    assert!(b.is_mappable());
+    assert_eq!(kernel.call_count(Syscall::ShmGetInfos), 2);
+
+    shm::invalidate_info(SHM_HANDLE);
+    assert_eq!(a.length().unwrap(), 64);
+    assert_eq!(kernel.call_count(Syscall::ShmGetInfos), 3);
+}
+
+#[test]
+fn shm_fetch_policy() {
+    let kernel = mock::session();
+    kernel.add_shm(SHM_LABEL, SHM_HANDLE, 64, SHMPermission::Read as u32);
+
+    Shm::new_with(SHM_LABEL, FetchPolicy::default()).unwrap();
+    assert_eq!(kernel.call_count(Syscall::ShmGetInfos), 0);
+
+    let mut shm = Shm::new_with(SHM_LABEL, FetchPolicy::Eager).unwrap();
+    assert_eq!(kernel.call_count(Syscall::ShmGetInfos), 1);
+    assert!(shm.is_readable());
+    assert_eq!(shm.length().unwrap(), 64);
+    assert_eq!(kernel.call_count(Syscall::ShmGetInfos), 1);
+}
+
+#[test]
+fn shm_errors() {
+    let kernel = mock::session();
+    kernel.add_shm(SHM_LABEL, SHM_HANDLE, 64, 0);
+
+    assert!(Shm::new(SHM_LABEL + 1).is_err());
+
+    let err = Shm::new(SHM_LABEL).unwrap().map(0).err().unwrap();
+    assert!(err.status() == Status::Denied);
+    assert_eq!(err.handle(), Some(SHM_HANDLE));
+
+    kernel.set_status(Syscall::SHMSetCredential, Status::Busy);
+    let mut shm = Shm::new(SHM_LABEL).unwrap();
+    let err = shm.set_credentials(0x42, 0).unwrap_err();
+    assert!(err.status() == Status::Busy);
+
+    kernel.clear_status(Syscall::SHMSetCredential);
+    shm.set_credentials(0x42, 0).unwrap();
+    assert_eq!(kernel.call_count(Syscall::SHMSetCredential), 2);
+}
+
+#[test]
+fn shm_copies() {
+    let kernel = mock::session();
+    let perms =
+        SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+    let base = kernel.add_shm(SHM_LABEL, SHM_HANDLE, 4096, perms);
+    kernel.add_dma_stream(0x10, 0x110);
+    let mut shm = Shm::new(SHM_LABEL).unwrap().map(0).unwrap();
+    let mut stream = DmaStream::new(0x10).unwrap();
+
+    shm.copy_into_shm(4, b"frame").unwrap();
+    let mut buf = [0; 5];
+    shm.copy_from_shm(4, &mut buf).unwrap();
+    assert_eq!(&buf, b"frame");
+    let err = shm.copy_into_shm(4092, b"frame").unwrap_err();
+    assert!(err.status() == Status::Invalid);
+
+    // unconfigured stream, copied by the CPU
+    let frame = [0x5a; DMA_COPY_THRESHOLD];
+    executor::run(shm.copy_into_shm_dma(&mut stream, 0, &frame)).unwrap();
+    assert_eq!(kernel.call_count(Syscall::DmaStartStream), 0);
+
+    let mut frame = [0; DMA_COPY_THRESHOLD];
+    kernel.set_dma_stream_config(
+        0x110,
+        GpdmaStreamConfig {
+            channel: 0,
+            stream: 0,
+            controller: 0,
+            transfer_type: GpdmaTransferType::MemoryToMemory as u16,
+            source: base,
+            dest: frame.as_ptr().addr(),
+            transfer_len: DMA_COPY_THRESHOLD,
+            circular_source: false,
+            circular_dest: false,
+            interrupts: 0,
+            is_triggered: false,
+            trigger: 0,
+            priority: 0,
+            transfer_mode: 0,
+            src_beat_len: 0,
+            dest_beat_len: 0,
+        },
+    );
+    executor::run(shm.copy_from_shm_dma(&mut stream, 0, &mut frame)).unwrap();
+    assert_eq!(kernel.call_count(Syscall::DmaStartStream), 1);
+    assert!(frame.iter().all(|&byte| byte == 0x5a));
+
+    // another destination, copied by the CPU
+    let mut other = [0; DMA_COPY_THRESHOLD];
+    executor::run(shm.copy_from_shm_dma(&mut stream, 0, &mut other)).unwrap();
+    assert_eq!(kernel.call_count(Syscall::DmaStartStream), 1);
+    assert_eq!(other, frame);
+}
+
+#[test]
+fn current_task() {
+    let kernel = mock::session();
+    kernel.add_task(0xbabe, 0x1000_babe);
+
+    assert_eq!(process::register_current(0xbabe).unwrap(), 0x1000_babe);
+    assert_eq!(process::current_handle().unwrap(), 0x1000_babe);
+    assert_eq!(process::current_label().unwrap(), 0xbabe);
+    assert!(process::get_process_handle(0xdead).is_err());
+
+    process::send_signal(0x1000_babe, Signal::Usr1).unwrap();
+    assert_eq!(kernel.signals(), [(0x1000_babe, Signal::Usr1)]);
+    kernel.set_status(Syscall::SendSignal, Status::Busy);
+    let err = process::send_signal(0x1000_babe, Signal::Usr1).unwrap_err();
+    assert!(err.status() == Status::Busy);
+    assert_eq!(err.handle(), Some(0x1000_babe));
+}
+
+#[test]
+fn random_fill() {
+    let kernel = mock::session();
+
+    let mut buf = [0_u8; 10];
+    random::fill(&mut buf).unwrap();
+    assert_ne!(buf, [0; 10]);
+    assert_eq!(kernel.call_count(Syscall::GetRandom), 3);
+
+    kernel.set_status(Syscall::GetRandom, Status::Denied);
+    let err = random::random_u32().unwrap_err();
+    assert!(err.status() == Status::Denied);
+}
+
+#[test]
+fn uptime() {
+    let kernel = mock::session();
+    kernel.set_uptime_us(12_345_678);
+
+    assert_eq!(time::uptime_us().unwrap(), 12_345_678);
+    assert_eq!(time::uptime_ms().unwrap(), 12_345);
+    time::sleep_ms(5).unwrap();
+    assert_eq!(time::uptime_ms().unwrap(), 12_350);
+}
+
+#[test]
+fn executor_event_dispatch() {
+    let kernel = mock::session();
+    kernel.push_event(EventType::Irq, 0, &42_u32.to_ne_bytes());
+    kernel.push_signal(Signal::Usr2, 0x77);
+
+    let (irq, signal) = executor::run(executor::join2(
+        executor::wait_event_from(EventType::Irq, 42),
+        executor::wait_signal_from(Signal::Usr2, 0x77),
+    ));
+    assert_eq!(irq.source(), 42);
+    assert_eq!(signal.peer(), 0x77);
+}
+
+#[test]
+fn executor_yield() {
+    let kernel = mock::session();
+    kernel.push_signal(Signal::Usr2, 0x77);
+
+    // the pending signal is dispatched while the computation yields
+    let (signal, steps) = executor::run(executor::join2(
+        executor::wait_signal_from(Signal::Usr2, 0x77),
+        async {
+            for _ in 0..3 {
+                executor::yield_now().await;
+            }
+            3
+        },
+    ));
+    assert_eq!((signal.peer(), steps), (0x77, 3));
+    assert_eq!(kernel.call_count(Syscall::Yield), 0);
+}
+
+#[test]
+fn print_output() {
+    let kernel = mock::session();
+    shield::println!("hello {}", 42);
+    assert_eq!(kernel.log_output(), b"hello 42\n");
+}
+
+#[test]
+fn print_chunks() {
+    use shield::print::PRINT_BUFFER_LEN;
+
+    let kernel = mock::session();
+    let long = "0123456789".repeat(PRINT_BUFFER_LEN / 10 + 5);
+    shield::eprintln!("{long}");
+    let mut expected = long.into_bytes();
+    expected.push(b'\n');
+    assert_eq!(kernel.log_output(), expected);
+    // one log syscall per full buffer, then the rest
+    assert_eq!(
+        kernel.call_count(Syscall::Log),
+        expected.len().div_ceil(PRINT_BUFFER_LEN)
+    );
+
+    // output refused by the kernel is dropped, the task going on
+    kernel.set_status(Syscall::Log, Status::Invalid);
+    shield::println!("dropped");
+    assert_eq!(kernel.log_output().len(), expected.len());
+}
+
+#[test]
+fn scripted_faults() {
+    let kernel = mock::session();
+    kernel.add_shm(SHM_LABEL, SHM_HANDLE, 64, SHMPermission::Map as u32);
+
+    kernel.fail_nth(Syscall::MapShm, 3, Status::Busy);
+    for _ in 0..2 {
+        Shm::new(SHM_LABEL)
+            .unwrap()
+            .map(0)
+            .unwrap()
+            .unmap()
+            .unwrap();
+    }
+    let err = Shm::new(SHM_LABEL).unwrap().map(0).err().unwrap();
+    assert!(err.status() == Status::Busy);
+    assert!(!kernel.is_mapped(SHM_HANDLE));
+    Shm::new(SHM_LABEL).unwrap().map(0).unwrap();
+
+    kernel.fail_copy_from_kernel(1, Status::Invalid);
+    let err = Shm::new(SHM_LABEL).err().unwrap();
+    assert!(err.status() == Status::Invalid);
+    Shm::new(SHM_LABEL).unwrap();
+}
@@ -0,0 +1,165 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Shared memory frame rings tests against the fake kernel
+
+#![cfg(all(feature = "net", feature = "mock"))]
+
+use sentry_uapi::systypes::{SHMPermission, Status};
+use shield::mock;
+use shield::net::{MTU, SLOT_LEN, ShmDevice};
+use shield::shm::Shm;
+use smoltcp::phy::{Device, Medium, RxToken, TxToken};
+use smoltcp::time::Instant;
+
+const DRIVER: u32 = 0x1000_0e7e;
+
+/// Driver side view of a ring
+struct Ring(*mut u8);
+
+impl Ring {
+    fn counter(&self, index: usize) -> u32 {
+        unsafe { self.0.cast::<u32>().add(index).read() }
+    }
+
+    fn set_counter(&self, index: usize, value: u32) {
+        unsafe { self.0.cast::<u32>().add(index).write(value) }
+    }
+
+    fn slot(&self, index: usize) -> *mut u8 {
+        unsafe { self.0.add(8 + index * SLOT_LEN) }
+    }
+
+    fn bytes(&self, index: usize, len: usize) -> Vec<u8> {
+        unsafe { std::slice::from_raw_parts(self.slot(index), len) }.to_vec()
+    }
+
+    /// Queue `frame`, as the driver does
+    fn push(&self, frame: &[u8]) {
+        let head = self.counter(0);
+        let slot = self.slot(head as usize % 2);
+        unsafe {
+            slot.cast::<u16>().write(frame.len() as u16);
+            std::ptr::copy_nonoverlapping(frame.as_ptr(), slot.add(4), frame.len());
+        }
+        self.set_counter(0, head + 1);
+    }
+}
+
+fn device(kernel: &mock::Session) -> (ShmDevice, Ring, Ring) {
+    let perms =
+        SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+    let len = 8 + 2 * SLOT_LEN;
+    let rx = kernel.add_shm(0x10, 0x110, len, perms);
+    let tx = kernel.add_shm(0x11, 0x111, len, perms);
+    let device = ShmDevice::new(
+        Shm::new(0x10).unwrap().map(0).unwrap(),
+        Shm::new(0x11).unwrap().map(0).unwrap(),
+        DRIVER,
+    )
+    .unwrap();
+    (
+        device,
+        Ring(std::ptr::with_exposed_provenance_mut(rx)),
+        Ring(std::ptr::with_exposed_provenance_mut(tx)),
+    )
+}
+
+#[test]
+fn frames() {
+    let kernel = mock::session();
+    let (mut device, rx, tx) = device(&kernel);
+    let caps = device.capabilities();
+    assert_eq!(caps.medium, Medium::Ethernet);
+    assert_eq!(caps.max_transmission_unit, MTU);
+    assert_eq!(caps.max_burst_size, Some(2));
+    assert!(device.receive(Instant::ZERO).is_none());
+
+    rx.push(b"ping");
+    let (token, reply) = device.receive(Instant::ZERO).unwrap();
+    let frame = token.consume(<[u8]>::to_vec);
+    assert_eq!(frame, b"ping");
+    reply.consume(4, |buf| buf.copy_from_slice(b"pong"));
+    assert_eq!(rx.counter(1), 1);
+    assert!(device.receive(Instant::ZERO).is_none());
+
+    assert_eq!(tx.counter(0), 1);
+    assert_eq!(tx.bytes(0, 8), b"\x04\x00\x00\x00pong");
+    assert_eq!(kernel.sent_ipc(), [(DRIVER, vec![0xdb])]);
+
+    // the tx ring is full until the driver consumes it
+    device.transmit(Instant::ZERO).unwrap().consume(2, |_| ());
+    assert!(device.transmit(Instant::ZERO).is_none());
+    tx.set_counter(1, 2);
+    assert!(device.transmit(Instant::ZERO).is_some());
+}
+
+#[test]
+fn interface() {
+    use smoltcp::iface::{Config, Interface, SocketSet, SocketStorage};
+    use smoltcp::wire::{EthernetAddress, IpCidr, Ipv4Address};
+
+    let kernel = mock::session();
+    let (mut device, rx, tx) = device(&kernel);
+    let mac = EthernetAddress([0x02, 0, 0, 0, 0, 0x02]);
+    let mut iface = Interface::new(Config::new(mac.into()), &mut device, Instant::ZERO);
+    iface.update_ip_addrs(|addrs| {
+        addrs
+            .push(IpCidr::new(Ipv4Address::new(192, 168, 1, 2).into(), 24))
+            .unwrap();
+    });
+    let mut storage = [SocketStorage::EMPTY; 1];
+    let mut sockets = SocketSet::new(&mut storage[..]);
+
+    // ARP request for 192.168.1.2 from 192.168.1.1
+    let mut request = Vec::new();
+    request.extend([0xff; 6]);
+    request.extend([0x02, 0, 0, 0, 0, 0x01, 0x08, 0x06]);
+    request.extend([0, 1, 0x08, 0, 6, 4, 0, 1]);
+    request.extend([0x02, 0, 0, 0, 0, 0x01, 192, 168, 1, 1]);
+    request.extend([0; 6]);
+    request.extend([192, 168, 1, 2]);
+    rx.push(&request);
+    iface.poll(Instant::ZERO, &mut device, &mut sockets);
+
+    assert_eq!(rx.counter(1), 1);
+    assert_eq!(tx.counter(0), 1);
+    let reply = tx.bytes(0, 4 + 42);
+    assert_eq!(reply[..2], [42, 0]);
+    assert_eq!(reply[4..10], [0x02, 0, 0, 0, 0, 0x01]);
+    // ARP reply, from the interface address
+    assert_eq!(reply[4 + 20..4 + 22], [0, 2]);
+    assert_eq!(reply[4 + 22..4 + 28], mac.0);
+    assert_eq!(reply[4 + 28..4 + 32], [192, 168, 1, 2]);
+}
+
+#[test]
+fn doorbell_errors() {
+    let kernel = mock::session();
+    let (mut device, _, tx) = device(&kernel);
+
+    kernel.set_status(sentry_uapi::systypes::Syscall::SendIPC, Status::Busy);
+    device
+        .transmit(Instant::ZERO)
+        .unwrap()
+        .consume(60, |buf| buf.fill(0x42));
+    assert_eq!(device.doorbell_errors(), 1);
+    assert_eq!(tx.counter(0), 1);
+}
+
+#[test]
+fn undersized_ring() {
+    let kernel = mock::session();
+    let perms = SHMPermission::Map as u32 | SHMPermission::Write as u32;
+    kernel.add_shm(0x10, 0x110, SLOT_LEN, perms);
+    kernel.add_shm(0x11, 0x111, 8 + SLOT_LEN, perms);
+    let err = ShmDevice::new(
+        Shm::new(0x10).unwrap().map(0).unwrap(),
+        Shm::new(0x11).unwrap().map(0).unwrap(),
+        DRIVER,
+    )
+    .err()
+    .unwrap();
+    assert!(err.status() == Status::Invalid);
+}
@@ -0,0 +1,196 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! TCP/IP stack sockets tests against the fake kernel, over a loopback
+//! device
+
+#![cfg(all(feature = "net", feature = "mock"))]
+
+use std::collections::VecDeque;
+
+use sentry_uapi::systypes::{EventType, Status};
+use shield::mock;
+use shield::net::{MTU, Notify, Stack, TcpStream, UdpSocket};
+use smoltcp::iface::{Config, SocketStorage};
+use smoltcp::phy::{Device, DeviceCapabilities, Medium, RxToken, TxToken};
+use smoltcp::socket::udp::{PacketBuffer, PacketMetadata};
+use smoltcp::time::Instant;
+use smoltcp::wire::{EthernetAddress, IpCidr, Ipv4Address};
+
+const ADDRESS: Ipv4Address = Ipv4Address::new(192, 168, 1, 2);
+
+const DRIVER: u32 = 0x1000_0e7e;
+
+/// Device receiving the frames it transmits, notifying them as a driver task
+/// doorbell
+struct Loopback<'k> {
+    kernel: &'k mock::Session,
+    frames: VecDeque<Vec<u8>>,
+}
+
+struct Rx(Vec<u8>);
+
+struct Tx<'a, 'k>(&'a mut Loopback<'k>);
+
+impl<'k> Device for Loopback<'k> {
+    type RxToken<'a>
+        = Rx
+    where
+        Self: 'a;
+    type TxToken<'a>
+        = Tx<'a, 'k>
+    where
+        Self: 'a;
+
+    fn receive(&mut self, _timestamp: Instant) -> Option<(Rx, Tx<'_, 'k>)> {
+        let frame = self.frames.pop_front()?;
+        Some((Rx(frame), Tx(self)))
+    }
+
+    fn transmit(&mut self, _timestamp: Instant) -> Option<Tx<'_, 'k>> {
+        Some(Tx(self))
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        let mut caps = DeviceCapabilities::default();
+        caps.medium = Medium::Ethernet;
+        caps.max_transmission_unit = MTU;
+        caps
+    }
+}
+
+impl RxToken for Rx {
+    fn consume<R, F: FnOnce(&[u8]) -> R>(self, f: F) -> R {
+        f(&self.0)
+    }
+}
+
+impl TxToken for Tx<'_, '_> {
+    fn consume<R, F: FnOnce(&mut [u8]) -> R>(self, len: usize, f: F) -> R {
+        let mut frame = vec![0; len];
+        let output = f(&mut frame);
+        self.0.frames.push_back(frame);
+        self.0.kernel.push_event(EventType::Ipc, DRIVER, &[0xdb]);
+        output
+    }
+}
+
+impl Notify for Loopback<'_> {
+    fn event(&self) -> (EventType, u32) {
+        (EventType::Ipc, DRIVER)
+    }
+}
+
+fn stack<'a, 'k>(
+    kernel: &'k mock::Session,
+    storage: &'a mut [SocketStorage<'a>],
+) -> Stack<'a, Loopback<'k>> {
+    let device = Loopback {
+        kernel,
+        frames: VecDeque::new(),
+    };
+    let mac = EthernetAddress([0x02, 0, 0, 0, 0, 0x02]);
+    let stack = Stack::new(device, Config::new(mac.into()), storage);
+    stack.with_interface(|iface| {
+        iface.update_ip_addrs(|addrs| addrs.push(IpCidr::new(ADDRESS.into(), 24)).unwrap());
+    });
+    stack
+}
+
+#[test]
+fn udp() {
+    let kernel = mock::session();
+    let (mut rx_meta, mut rx_payload) = ([PacketMetadata::EMPTY; 2], [0; 64]);
+    let (mut tx_meta, mut tx_payload) = ([PacketMetadata::EMPTY; 2], [0; 64]);
+    let (mut meta, mut payload) = ([PacketMetadata::EMPTY; 1], [0; 8]);
+    let mut storage = [SocketStorage::EMPTY; 1];
+    let stack = stack(&kernel, &mut storage);
+    let socket = UdpSocket::bind(
+        &stack,
+        5683,
+        PacketBuffer::new(&mut rx_meta[..], &mut rx_payload[..]),
+        PacketBuffer::new(&mut tx_meta[..], &mut tx_payload[..]),
+    )
+    .unwrap();
+    assert_eq!(socket.local_endpoint().port, 5683);
+
+    socket.send_to(b"ping", (ADDRESS, 5683)).unwrap();
+    let mut buf = [0; 2];
+    let (len, peer) = socket.recv_from(&mut buf).unwrap();
+    // truncated to the buffer
+    assert_eq!((len, &buf), (2, b"pi"));
+    assert_eq!((peer.addr, peer.port), (ADDRESS.into(), 5683));
+
+    let err = socket.send_to(&[0; 65], (ADDRESS, 5683)).unwrap_err();
+    assert_eq!(err.status(), Status::Invalid);
+    // nothing received, and no deadline: waiting would never end
+    let err = socket.recv_from(&mut buf).unwrap_err();
+    assert_eq!(err.status(), Status::Deadlk);
+
+    // a single socket fits in the storage
+    let buffer = PacketBuffer::new(&mut meta[..], &mut payload[..]);
+    let err = UdpSocket::bind(
+        &stack,
+        5684,
+        buffer,
+        PacketBuffer::new(&mut [][..], &mut [][..]),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(err.status(), Status::Busy);
+}
+
+#[test]
+fn tcp_refused() {
+    let kernel = mock::session();
+    let (mut rx, mut tx) = ([0; 64], [0; 64]);
+    let mut storage = [SocketStorage::EMPTY; 1];
+    let stack = stack(&kernel, &mut storage);
+    let err = TcpStream::connect(&stack, (ADDRESS, 80), 49152, &mut rx, &mut tx)
+        .err()
+        .unwrap();
+    assert_eq!(err.status(), Status::Intr);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn tcp() {
+    use embedded_io::{Read, Write};
+    use shield::executor;
+
+    let kernel = mock::session();
+    let (mut server_rx, mut server_tx) = ([0; 256], [0; 256]);
+    let (mut client_rx, mut client_tx) = ([0; 256], [0; 256]);
+    let mut storage = [SocketStorage::EMPTY; 2];
+    let stack = stack(&kernel, &mut storage);
+
+    let (server, client) = executor::run(executor::join2(
+        TcpStream::accept_async(&stack, 80, &mut server_rx, &mut server_tx),
+        TcpStream::connect_async(&stack, (ADDRESS, 80), 49152, &mut client_rx, &mut client_tx),
+    ));
+    let (mut server, mut client) = (server.unwrap(), client.unwrap());
+    assert_eq!(server.remote_endpoint().unwrap().port, 49152);
+    assert_eq!(client.local_endpoint().unwrap().port, 49152);
+
+    // blocking embedded-io calls, the stack serving both ends
+    client.write_all(b"hello").unwrap();
+    client.flush().unwrap();
+    let mut buf = [0; 16];
+    assert_eq!(Read::read(&mut server, &mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"hello");
+
+    executor::run(async {
+        assert_eq!(server.write_async(b"world").await.unwrap(), 5);
+        assert_eq!(client.read_async(&mut buf).await.unwrap(), 5);
+    });
+    assert_eq!(&buf[..5], b"world");
+
+    // a graceful close, seen as the end of the stream by the peer
+    client.close().unwrap();
+    assert_eq!(server.read(&mut buf).unwrap(), 0);
+    server.close().unwrap();
+    drop(client);
+    let err = server.write(b"late").unwrap_err();
+    assert_eq!(err.status(), Status::Intr);
+}
@@ -0,0 +1,184 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Anti-rollback counter tests over a simulated flash and secure element
+
+#![cfg(all(feature = "rollback", feature = "kvstore", feature = "secure-element"))]
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use sentry_uapi::systypes::Status;
+use shield::error::{Error, Subsystem};
+use shield::kvstore::{Backing, KvStore};
+use shield::rollback::{KvCounter, MonotonicCounter, SecureElementCounter};
+use shield::secure_element::{KeySlot, SecureElement, Transport};
+
+const ERASE_SIZE: usize = 256;
+
+/// NOR flash, counting its programs
+struct Flash {
+    memory: Vec<u8>,
+    programs: usize,
+}
+
+impl Backing for Flash {
+    fn size(&self) -> usize {
+        self.memory.len()
+    }
+
+    fn erase_size(&self) -> usize {
+        ERASE_SIZE
+    }
+
+    fn write_size(&self) -> usize {
+        8
+    }
+
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), Error> {
+        buf.copy_from_slice(&self.memory[offset..offset + buf.len()]);
+        Ok(())
+    }
+
+    fn program(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        self.programs += 1;
+        self.memory[offset..offset + data.len()].copy_from_slice(data);
+        Ok(())
+    }
+
+    fn erase(&mut self, offset: usize, len: usize) -> Result<(), Error> {
+        self.memory[offset..offset + len].fill(0xff);
+        Ok(())
+    }
+}
+
+fn store() -> RefCell<KvStore<Flash>> {
+    let flash = Flash {
+        memory: vec![0xff; 4 * ERASE_SIZE],
+        programs: 0,
+    };
+    RefCell::new(KvStore::open(flash).unwrap())
+}
+
+/// Secure element counters, saturating at `limit`
+struct Device {
+    counters: HashMap<u8, u32>,
+    limit: u32,
+}
+
+impl Transport for Device {
+    fn exchange(&mut self, command: &[u8], response: &mut [u8]) -> Result<usize, Error> {
+        let counter = self.counters.entry(command[1]).or_default();
+        let status = match command[0] {
+            0x08 => 0,
+            0x09 if *counter < self.limit => {
+                *counter += 1;
+                0
+            }
+            0x09 => 0x02,
+            _ => 0x01,
+        };
+        response[0] = status;
+        if status != 0 {
+            response[1..3].fill(0);
+            return Ok(3);
+        }
+        response[1..3].copy_from_slice(&4u16.to_le_bytes());
+        response[3..7].copy_from_slice(&counter.to_le_bytes());
+        Ok(7)
+    }
+}
+
+#[test]
+fn kv_counter() {
+    let store = store();
+    let mut counter = KvCounter::new(&store, b"fw-version");
+    assert_eq!(counter.read().unwrap(), 0);
+    assert_eq!(counter.increment().unwrap(), 1);
+    assert_eq!(counter.increment().unwrap(), 2);
+    assert_eq!(counter.read().unwrap(), 2);
+
+    // other counters and users share the store
+    let mut other = KvCounter::new(&store, b"config-version");
+    assert_eq!(other.read().unwrap(), 0);
+    store.borrow_mut().set(b"name", b"shield").unwrap();
+    assert_eq!(counter.read().unwrap(), 2);
+}
+
+#[test]
+fn kv_advance_to() {
+    let opened = store().into_inner().release().programs;
+    let store = store();
+    let mut counter = KvCounter::new(&store, b"fw-version");
+    assert_eq!(counter.advance_to(1000).unwrap(), 1000);
+
+    // never decreases
+    assert_eq!(counter.advance_to(10).unwrap(), 1000);
+    assert_eq!(counter.read().unwrap(), 1000);
+
+    // a single record written
+    let programs = store.into_inner().release().programs - opened;
+    assert!(programs <= 2, "{programs} programs");
+}
+
+#[test]
+fn check() {
+    let store = store();
+    let mut counter = KvCounter::new(&store, b"fw-version");
+    counter.advance_to(3).unwrap();
+    assert!(counter.check(3).is_ok());
+    assert!(counter.check(4).is_ok());
+    let err = counter.check(2).unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::Rollback);
+    assert_eq!(err.status(), Status::Denied);
+    assert_eq!(err.handle(), Some(2));
+}
+
+#[test]
+fn kv_corrupted() {
+    let store = store();
+    store.borrow_mut().set(b"fw-version", b"v3").unwrap();
+    let err = KvCounter::new(&store, b"fw-version").read().unwrap_err();
+    assert_eq!(err.status(), Status::Critical);
+
+    store
+        .borrow_mut()
+        .set(b"fw-version", &u32::MAX.to_le_bytes())
+        .unwrap();
+    let mut counter = KvCounter::new(&store, b"fw-version");
+    assert_eq!(counter.increment().unwrap_err().status(), Status::Denied);
+}
+
+#[test]
+fn kv_store_borrowed() {
+    let store = store();
+    let mut counter = KvCounter::new(&store, b"fw-version");
+    let _guard = store.borrow();
+    assert_eq!(counter.read().unwrap_err().status(), Status::Busy);
+}
+
+#[test]
+fn secure_element_counter() {
+    let element = RefCell::new(SecureElement::new(Device {
+        counters: HashMap::new(),
+        limit: 5,
+    }));
+    let mut counter = SecureElementCounter::new(&element, KeySlot(2));
+    assert_eq!(counter.read().unwrap(), 0);
+    assert_eq!(counter.increment().unwrap(), 1);
+    assert_eq!(counter.advance_to(4).unwrap(), 4);
+    assert_eq!(counter.advance_to(2).unwrap(), 4);
+    assert_eq!(
+        SecureElementCounter::new(&element, KeySlot(3))
+            .read()
+            .unwrap(),
+        0
+    );
+
+    // the element enforces its limit
+    let err = counter.advance_to(8).unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::SecureElement);
+    assert_eq!(err.status(), Status::Denied);
+    assert_eq!(counter.read().unwrap(), 5);
+}
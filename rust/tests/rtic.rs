@@ -0,0 +1,63 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! RTIC monotonic tests against the fake kernel
+
+#![cfg(all(feature = "rtic", feature = "mock"))]
+
+use sentry_uapi::systypes::{EventType, Syscall};
+use shield::rtic::{Duration, ExtU64, Instant, Monotonic, ShieldMono, TimeoutError};
+use shield::{executor, mock};
+
+#[test]
+fn delays() {
+    let kernel = mock::session();
+    kernel.set_uptime_us(1_000);
+    assert_eq!(ShieldMono::now(), Instant::from_ticks(1_000));
+
+    executor::run(ShieldMono::delay(20.millis()));
+    let now = ShieldMono::now();
+    assert!(now >= Instant::from_ticks(21_000));
+    assert!(kernel.call_count(Syscall::Alarm) > 0);
+
+    // already reached
+    executor::run(ShieldMono::delay_until(now - Duration::micros(1)));
+    assert_eq!(ShieldMono::now(), now);
+}
+
+#[test]
+fn timeouts() {
+    let kernel = mock::session();
+    kernel.push_event(EventType::Irq, 0, &7_u32.to_ne_bytes());
+    let irq = executor::run(ShieldMono::timeout_after(
+        Duration::secs(1),
+        executor::wait_event_from(EventType::Irq, 7),
+    ));
+    assert_eq!(irq.ok().map(|event| event.source()), Some(7));
+
+    let start = ShieldMono::now();
+    let irq = executor::run(ShieldMono::timeout_after(
+        Duration::millis(5),
+        executor::wait_event_from(EventType::Irq, 7),
+    ));
+    assert!(matches!(irq, Err(TimeoutError)));
+    assert!(ShieldMono::now() - start >= Duration::millis(5));
+}
+
+#[test]
+fn arithmetic() {
+    let instant = Instant::from_ticks(1_500);
+    assert_eq!(instant + Duration::millis(1), Instant::from_ticks(2_500));
+    assert_eq!(instant - Duration::micros(500), Instant::from_ticks(1_000));
+    assert_eq!(
+        Instant::from_ticks(4_000) - instant,
+        Duration::micros(2_500)
+    );
+    assert_eq!(
+        instant.checked_duration_since(Instant::from_ticks(2_000)),
+        None
+    );
+    assert_eq!(Duration::micros(2_999).to_millis(), 2);
+    assert_eq!(ShieldMono::TICK_PERIOD, Duration::from_ticks(1));
+}
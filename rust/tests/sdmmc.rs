@@ -0,0 +1,229 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! SD card driver tests against the fake kernel and a fake card
+
+#![cfg(all(feature = "sdmmc", feature = "mock"))]
+
+use sentry_uapi::systypes::dma::{GpdmaStreamConfig, GpdmaTransferType};
+use sentry_uapi::systypes::{SHMPermission, Status};
+use shield::dma::DmaStream;
+use shield::sdmmc::{
+    BLOCK_LEN, Block, BlockingSdmmc, BusWidth, Direction, Response, Sdmmc, SdmmcRegisters,
+    TransferError,
+};
+use shield::shm::Shm;
+use shield::units::{Hertz, UnitsExt};
+use shield::{executor, mock};
+
+/// Fake card behind its controller, the DMA streams moving blocks through
+/// `fifo`
+struct Card {
+    high_capacity: bool,
+    storage: Vec<Block>,
+    fifo: Box<Block>,
+    app: bool,
+    op_cond_polls: u32,
+    pending_write: Option<usize>,
+    width: BusWidth,
+    clock: Hertz,
+}
+
+impl Card {
+    fn new(high_capacity: bool) -> Self {
+        let blocks = if high_capacity { 2048 } else { 16 };
+        Self {
+            high_capacity,
+            storage: vec![[0; BLOCK_LEN]; blocks],
+            fifo: Box::new([0; BLOCK_LEN]),
+            app: false,
+            op_cond_polls: 0,
+            pending_write: None,
+            width: BusWidth::One,
+            clock: Hertz(0),
+        }
+    }
+
+    fn index(&self, address: u32) -> usize {
+        if self.high_capacity {
+            address as usize
+        } else {
+            address as usize / BLOCK_LEN
+        }
+    }
+
+    fn csd(&self) -> [u32; 4] {
+        if self.high_capacity {
+            // CSD version 2, C_SIZE = 1: 2 * 512 KiB
+            [0x4000_0000, 0, 1 << 16, 0]
+        } else {
+            // CSD version 1, C_SIZE = 3, C_SIZE_MULT = 0, READ_BL_LEN = 9
+            [0, 9 << 16, 3 << 30, 0]
+        }
+    }
+}
+
+impl SdmmcRegisters for Card {
+    fn set_clock(&mut self, frequency: Hertz) {
+        self.clock = frequency;
+    }
+
+    fn set_bus_width(&mut self, width: BusWidth) {
+        self.width = width;
+    }
+
+    fn command(&mut self, index: u8, arg: u32, _: Response) -> Result<[u32; 4], TransferError> {
+        let app = std::mem::take(&mut self.app);
+        let status = match (app, index) {
+            (_, 0 | 2 | 7 | 16) => 0x900,
+            (_, 8) if self.high_capacity => arg,
+            (_, 8) => return Err(TransferError::Timeout),
+            (_, 55) => {
+                self.app = true;
+                0x920
+            }
+            (true, 41) => {
+                self.op_cond_polls += 1;
+                let ready = if self.op_cond_polls > 2 { 1 << 31 } else { 0 };
+                let capacity = if self.high_capacity && arg & (1 << 30) != 0 {
+                    1 << 30
+                } else {
+                    0
+                };
+                0x00ff_8000 | ready | capacity
+            }
+            (true, 6) => {
+                assert_eq!(arg, 2);
+                0x920
+            }
+            (_, 3) => 0x1234_0500,
+            (_, 9) => return Ok(self.csd()),
+            (_, 13) => 0x900,
+            (_, 17) => {
+                *self.fifo = self.storage[self.index(arg)];
+                0x900
+            }
+            (_, 24) => {
+                self.pending_write = Some(self.index(arg));
+                0x900
+            }
+            _ => panic!("unexpected command {index}"),
+        };
+        Ok([status, 0, 0, 0])
+    }
+
+    fn start_data(&mut self, _: Direction, len: usize) {
+        assert_eq!(len, BLOCK_LEN);
+    }
+
+    fn finish_data(&mut self) -> Result<(), TransferError> {
+        if let Some(index) = self.pending_write.take() {
+            self.storage[index] = *self.fifo;
+        }
+        Ok(())
+    }
+}
+
+fn stream_config(source: usize, dest: usize) -> GpdmaStreamConfig {
+    GpdmaStreamConfig {
+        channel: 0,
+        stream: 0,
+        controller: 0,
+        transfer_type: GpdmaTransferType::MemoryToMemory as u16,
+        source,
+        dest,
+        transfer_len: BLOCK_LEN,
+        circular_source: false,
+        circular_dest: false,
+        interrupts: 0,
+        is_triggered: false,
+        trigger: 0,
+        priority: 0,
+        transfer_mode: 0,
+        src_beat_len: 0,
+        dest_beat_len: 0,
+    }
+}
+
+fn driver(kernel: &mock::Session, card: Card) -> Sdmmc<Card> {
+    let perms =
+        SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+    let base = kernel.add_shm(0x10, 0x110, BLOCK_LEN, perms);
+    let fifo = card.fifo.as_ptr().expose_provenance();
+    kernel.add_dma_stream(0x20, 0x120);
+    kernel.add_dma_stream(0x21, 0x121);
+    kernel.set_dma_stream_config(0x120, stream_config(fifo, base));
+    kernel.set_dma_stream_config(0x121, stream_config(base, fifo));
+    Sdmmc::new(
+        card,
+        Shm::new(0x10).unwrap().map(0).unwrap(),
+        DmaStream::new(0x20).unwrap(),
+        DmaStream::new(0x21).unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn high_capacity() {
+    let kernel = mock::session();
+    let mut sd = driver(&kernel, Card::new(true));
+    let err = sd.num_blocks().unwrap_err();
+    assert!(err.status() == Status::NoEntity);
+
+    sd.init().unwrap();
+    assert_eq!(sd.num_blocks().unwrap(), 2048);
+
+    let blocks = [[0xa5; BLOCK_LEN], [0x5a; BLOCK_LEN]];
+    executor::run(sd.write(2046, &blocks)).unwrap();
+    let mut read = [[0; BLOCK_LEN]; 2];
+    executor::run(sd.read(2046, &mut read)).unwrap();
+    assert_eq!(read, blocks);
+
+    let err = executor::run(sd.read(2047, &mut read)).unwrap_err();
+    assert!(err.status() == Status::Invalid);
+
+    let (card, ..) = sd.release();
+    assert_eq!(card.storage[2047], [0x5a; BLOCK_LEN]);
+    assert_eq!((card.width, card.clock), (BusWidth::Four, 25_000_000.hz()));
+}
+
+#[test]
+fn standard_capacity() {
+    let kernel = mock::session();
+    let mut card = Card::new(false);
+    card.storage[3] = [0x42; BLOCK_LEN];
+    let mut sd = driver(&kernel, card);
+    sd.init().unwrap();
+    assert_eq!(sd.num_blocks().unwrap(), 16);
+
+    let mut read = [[0; BLOCK_LEN]];
+    executor::run(sd.read(3, &mut read)).unwrap();
+    assert_eq!(read[0], [0x42; BLOCK_LEN]);
+}
+
+#[test]
+fn block_device() {
+    use embedded_sdmmc::{BlockCount, BlockDevice, BlockIdx};
+
+    let kernel = mock::session();
+    let mut sd = driver(&kernel, Card::new(false));
+    sd.init().unwrap();
+    let device = BlockingSdmmc::new(sd);
+    assert_eq!(device.num_blocks().unwrap(), BlockCount(16));
+
+    let mut blocks = [embedded_sdmmc::Block::new(), embedded_sdmmc::Block::new()];
+    blocks[0].contents = [0x11; BLOCK_LEN];
+    blocks[1].contents = [0x22; BLOCK_LEN];
+    device.write(&blocks, BlockIdx(14)).unwrap();
+    let mut read = [embedded_sdmmc::Block::new()];
+    device.read(&mut read, BlockIdx(15)).unwrap();
+    assert_eq!(read[0].contents, [0x22; BLOCK_LEN]);
+
+    // nothing is written past the end of the card
+    let err = device.write(&blocks, BlockIdx(15)).unwrap_err();
+    assert!(err.status() == Status::Invalid);
+    let (card, ..) = device.into_inner().release();
+    assert_eq!(card.storage[14], [0x11; BLOCK_LEN]);
+    assert_eq!(card.storage[15], [0x22; BLOCK_LEN]);
+}
@@ -0,0 +1,195 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Secure element tests against a simulated device
+
+#![cfg(feature = "secure-element")]
+
+use std::collections::HashMap;
+
+use sentry_uapi::systypes::Status;
+use shield::error::Error;
+use shield::secure_element::{KeySlot, SecureElement, Transport};
+
+/// Secure element model, a key pair being its slot number repeated, and a
+/// signature the digest twice, xored with the key
+#[derive(Default)]
+struct Device {
+    keys: HashMap<u8, u8>,
+    data: HashMap<u8, Vec<u8>>,
+    commands: Vec<Vec<u8>>,
+}
+
+impl Device {
+    fn signature(key: u8, digest: &[u8]) -> Vec<u8> {
+        digest.iter().chain(digest).map(|byte| byte ^ key).collect()
+    }
+
+    /// Tag of a ciphertext, the position weighted sum of its authenticated
+    /// bytes, xored with the key
+    fn tag(key: u8, nonce_aad: &[u8], data: &[u8]) -> Vec<u8> {
+        let sum = nonce_aad
+            .iter()
+            .chain(data)
+            .enumerate()
+            .fold(0u8, |sum, (index, &byte)| {
+                sum.wrapping_add(byte.wrapping_mul(index as u8 + 1))
+            });
+        (0..16).map(|index| sum ^ key ^ index).collect()
+    }
+
+    /// Split a seal or open payload into the nonce and the AAD, and the data
+    fn aead(payload: &[u8]) -> (&[u8], &[u8]) {
+        payload.split_at(13 + usize::from(payload[12]))
+    }
+}
+
+impl Transport for Device {
+    fn exchange(&mut self, command: &[u8], response: &mut [u8]) -> Result<usize, Error> {
+        self.commands.push(command.to_vec());
+        let (code, slot, payload) = (command[0], command[1], &command[4..]);
+        assert_eq!(
+            usize::from(command[2]) + 256 * usize::from(command[3]),
+            payload.len()
+        );
+        let (status, out) = match (code, self.keys.get(&slot)) {
+            (0x01, _) => {
+                self.keys.insert(slot, slot);
+                (0, vec![slot; 64])
+            }
+            (0x02, Some(&key)) => (0, vec![key; 64]),
+            (0x03, Some(&key)) => (0, Self::signature(key, payload)),
+            (0x04, Some(&key)) if Self::signature(key, &payload[..32]) == payload[32..] => {
+                (0, vec![])
+            }
+            (0x04, Some(_)) => (0x10, vec![]),
+            (0x05, _) => {
+                self.data.insert(slot, payload.to_vec());
+                (0, vec![])
+            }
+            (0x06, _) => match self.data.get(&slot) {
+                Some(data) => (0, data.clone()),
+                None => (0x03, vec![]),
+            },
+            (0x0a, Some(&key)) => {
+                let (nonce_aad, data) = Self::aead(payload);
+                let mut out: Vec<u8> = data.iter().map(|byte| byte ^ key).collect();
+                out.extend(Self::tag(key, nonce_aad, data));
+                (0, out)
+            }
+            (0x0b, Some(&key)) => {
+                let (nonce_aad, data) = Self::aead(payload);
+                let (data, tag) = data.split_at(data.len() - 16);
+                let plain: Vec<u8> = data.iter().map(|byte| byte ^ key).collect();
+                if Self::tag(key, nonce_aad, &plain) == tag {
+                    (0, plain)
+                } else {
+                    (0x10, vec![])
+                }
+            }
+            (0x07, _) => {
+                self.keys.remove(&slot);
+                self.data.remove(&slot);
+                (0, vec![])
+            }
+            _ => (0x03, vec![]),
+        };
+        response[0] = status;
+        response[1..3].copy_from_slice(&(out.len() as u16).to_le_bytes());
+        response[3..3 + out.len()].copy_from_slice(&out);
+        Ok(3 + out.len())
+    }
+}
+
+#[test]
+fn keys() {
+    let mut element = SecureElement::new(Device::default());
+    let slot = KeySlot(3);
+    let err = element.public_key(slot).unwrap_err();
+    assert!(err.status() == Status::NoEntity);
+
+    assert_eq!(element.generate_key(slot).unwrap(), [3; 64]);
+    assert_eq!(element.public_key(slot).unwrap(), [3; 64]);
+    let signature = element.sign(slot, &[0x42; 32]).unwrap();
+    assert!(element.verify(slot, &[0x42; 32], &signature).unwrap());
+    assert!(!element.verify(slot, &[0x43; 32], &signature).unwrap());
+
+    element.erase(slot).unwrap();
+    assert!(element.sign(slot, &[0x42; 32]).is_err());
+    let device = element.release();
+    assert_eq!(device.commands[0], [0x02, 3, 0, 0]);
+    assert_eq!(device.commands[4][..4], [0x04, 3, 96, 0]);
+}
+
+#[test]
+fn data_slots() {
+    let mut element = SecureElement::new(Device::default());
+    element.write_data(KeySlot(8), b"serial: 0042").unwrap();
+    let mut buf = [0; 32];
+    let len = element.read_data(KeySlot(8), &mut buf).unwrap();
+    assert_eq!(&buf[..len], b"serial: 0042");
+
+    let mut small = [0; 4];
+    let err = element.read_data(KeySlot(8), &mut small).unwrap_err();
+    assert!(err.status() == Status::Invalid);
+    let err = element.write_data(KeySlot(8), &[0; 257]).unwrap_err();
+    assert!(err.status() == Status::Invalid);
+    let err = element.read_data(KeySlot(9), &mut buf).unwrap_err();
+    assert!(err.status() == Status::NoEntity);
+}
+
+#[test]
+fn sealing() {
+    let mut element = SecureElement::new(Device::default());
+    let slot = KeySlot(5);
+    element.generate_key(slot).unwrap();
+    let nonce = [7; 12];
+    let mut data = *b"api token";
+    let tag = element.seal(slot, &nonce, b"token", &mut data).unwrap();
+    assert_ne!(&data, b"api token");
+
+    let mut tampered = data;
+    tampered[0] ^= 1;
+    let err = element
+        .open(slot, &nonce, b"token", &mut tampered, &tag)
+        .unwrap_err();
+    assert!(err.status() == Status::Denied);
+    let err = element
+        .open(slot, &nonce, b"other", &mut data.clone(), &tag)
+        .unwrap_err();
+    assert!(err.status() == Status::Denied);
+
+    element
+        .open(slot, &nonce, b"token", &mut data, &tag)
+        .unwrap();
+    assert_eq!(&data, b"api token");
+    let device = element.release();
+    assert_eq!(device.commands[1][..4], [0x0a, 5, 27, 0]);
+    assert_eq!(
+        device.commands[1][16..22],
+        [5, b't', b'o', b'k', b'e', b'n']
+    );
+}
+
+#[cfg(feature = "attest")]
+#[test]
+fn report_signing() {
+    use core::cell::RefCell;
+    use shield::attest::{Kind, Report};
+    use shield::secure_element::SigningKey;
+
+    let element = RefCell::new(SecureElement::new(Device::default()));
+    element.borrow_mut().generate_key(KeySlot(1)).unwrap();
+    let key = SigningKey::new(&element, KeySlot(1));
+
+    let mut report = Report::new(0xbabe);
+    report.measure(Kind::Image, b"image").unwrap();
+    let mut blob = [0; 256];
+    let len = report.encode(&[0; 32], Some(&key), &mut blob).unwrap();
+    let decoded = Report::decode(&blob[..len]).unwrap();
+    assert_eq!(
+        decoded.signature,
+        Device::signature(1, &decoded.signed_digest)
+    );
+}
@@ -0,0 +1,63 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Wiped shared memories tests against the fake kernel
+
+#![cfg(all(feature = "shm", feature = "mock"))]
+
+use sentry_uapi::systypes::{SHMPermission, Status, Syscall};
+use shield::mock;
+use shield::shm::{SecureShm, Shm};
+
+const PERMS: u32 =
+    SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+
+fn content(base: usize, len: usize) -> Vec<u8> {
+    unsafe { std::slice::from_raw_parts(std::ptr::with_exposed_provenance::<u8>(base), len) }
+        .to_vec()
+}
+
+#[test]
+fn unmap() {
+    let kernel = mock::session();
+    let base = kernel.add_shm(0x10, 0x110, 32, PERMS);
+    let mut key = SecureShm::new(Shm::new(0x10).unwrap().map(0).unwrap()).unwrap();
+    key.as_mut_slice().unwrap().fill(0xa5);
+    assert_eq!(content(base, 32), [0xa5; 32]);
+
+    let mut shm = key.unmap().unwrap();
+    assert_eq!(content(base, 32), [0; 32]);
+    assert!(!kernel.is_mapped(0x110));
+    shm.set_credentials(0x1000_babe, PERMS).unwrap();
+}
+
+#[test]
+fn drop_and_failed_unmap() {
+    let kernel = mock::session();
+    let base = kernel.add_shm(0x10, 0x110, 16, PERMS);
+    let mut key = SecureShm::new(Shm::new(0x10).unwrap().map(0).unwrap()).unwrap();
+    key.as_mut_slice().unwrap().fill(0xa5);
+    drop(key);
+    assert_eq!(content(base, 16), [0; 16]);
+
+    let base = kernel.add_shm(0x11, 0x111, 16, PERMS);
+    let mut key = SecureShm::new(Shm::new(0x11).unwrap().map(0).unwrap()).unwrap();
+    key.as_mut_slice().unwrap().fill(0xa5);
+    kernel.set_status(Syscall::UnmapShm, Status::Busy);
+    assert!(key.unmap().is_err());
+    assert_eq!(content(base, 16), [0; 16]);
+}
+
+#[test]
+fn read_only() {
+    let kernel = mock::session();
+    let perms = SHMPermission::Map as u32 | SHMPermission::Read as u32;
+    let base = kernel.add_shm(0x10, 0x110, 16, perms);
+    unsafe { std::ptr::with_exposed_provenance_mut::<u8>(base).write(0x42) };
+    let mut shm = SecureShm::new(Shm::new(0x10).unwrap().map(0).unwrap()).unwrap();
+    assert!(shm.as_mut_slice().is_none());
+    assert!(!shm.shm().is_writable());
+    shm.unmap().unwrap();
+    assert_eq!(content(base, 1), [0x42]);
+}
@@ -0,0 +1,295 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Sealed key-value store tests over a simulated flash and sealer
+
+#![cfg(all(feature = "mock", feature = "securestore"))]
+
+use std::collections::HashMap;
+
+use sentry_uapi::systypes::{Status, Syscall};
+use shield::error::{Error, Subsystem};
+use shield::kvstore::{Backing, KvStore};
+use shield::mock;
+use shield::securestore::{MAX_SECRET_LEN, NONCE_LEN, Sealer, SecureStore, TAG_LEN};
+
+const ERASE_SIZE: usize = 256;
+
+/// NOR flash
+struct Flash(Vec<u8>);
+
+impl Backing for Flash {
+    fn size(&self) -> usize {
+        self.0.len()
+    }
+
+    fn erase_size(&self) -> usize {
+        ERASE_SIZE
+    }
+
+    fn write_size(&self) -> usize {
+        8
+    }
+
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), Error> {
+        buf.copy_from_slice(&self.0[offset..offset + buf.len()]);
+        Ok(())
+    }
+
+    fn program(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        self.0[offset..offset + data.len()].copy_from_slice(data);
+        Ok(())
+    }
+
+    fn erase(&mut self, offset: usize, len: usize) -> Result<(), Error> {
+        self.0[offset..offset + len].fill(0xff);
+        Ok(())
+    }
+}
+
+/// Sealer model, encrypting by xoring the nonce and the slot key, the tag
+/// being the position weighted sum of the authenticated bytes
+#[derive(Default)]
+struct Keys {
+    keys: HashMap<u8, u8>,
+    /// Fail the sealing after `budget` seals
+    budget: Option<usize>,
+}
+
+impl Keys {
+    fn with(slots: &[u8]) -> Self {
+        Self {
+            keys: slots.iter().map(|&slot| (slot, slot ^ 0x5c)).collect(),
+            budget: None,
+        }
+    }
+
+    fn key(&self, slot: u8) -> Result<u8, Error> {
+        self.keys
+            .get(&slot)
+            .copied()
+            .ok_or(Error::new(Subsystem::SecureElement, Status::NoEntity))
+    }
+
+    fn tag(key: u8, nonce: &[u8], aad: &[u8], plain: &[u8]) -> [u8; TAG_LEN] {
+        let sum = nonce
+            .iter()
+            .chain(aad)
+            .chain(plain)
+            .enumerate()
+            .fold(0u8, |sum, (index, &byte)| {
+                sum.wrapping_add(byte.wrapping_mul(index as u8 + 1))
+            });
+        core::array::from_fn(|index| sum ^ key ^ index as u8)
+    }
+
+    fn apply(key: u8, nonce: &[u8; NONCE_LEN], data: &mut [u8]) {
+        for (index, byte) in data.iter_mut().enumerate() {
+            *byte ^= key ^ nonce[index % NONCE_LEN];
+        }
+    }
+}
+
+impl Sealer for Keys {
+    fn seal(
+        &mut self,
+        slot: u8,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        data: &mut [u8],
+    ) -> Result<[u8; TAG_LEN], Error> {
+        match &mut self.budget {
+            Some(0) => return Err(Error::new(Subsystem::SecureElement, Status::Busy)),
+            Some(budget) => *budget -= 1,
+            None => {}
+        }
+        let key = self.key(slot)?;
+        let tag = Self::tag(key, nonce, aad, data);
+        Self::apply(key, nonce, data);
+        Ok(tag)
+    }
+
+    fn open(
+        &mut self,
+        slot: u8,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        data: &mut [u8],
+        tag: &[u8; TAG_LEN],
+    ) -> Result<(), Error> {
+        let key = self.key(slot)?;
+        let mut plain = data.to_vec();
+        Self::apply(key, nonce, &mut plain);
+        if Self::tag(key, nonce, aad, &plain) != *tag {
+            return Err(Error::new(Subsystem::SecureElement, Status::Denied));
+        }
+        data.copy_from_slice(&plain);
+        Ok(())
+    }
+
+    fn erase(&mut self, slot: u8) -> Result<(), Error> {
+        self.keys
+            .remove(&slot)
+            .map(|_| ())
+            .ok_or(Error::new(Subsystem::SecureElement, Status::NoEntity))
+    }
+}
+
+fn store(keys: Keys, slot: u8) -> SecureStore<Flash, Keys> {
+    let flash = Flash(vec![0xff; 8 * ERASE_SIZE]);
+    SecureStore::new(KvStore::open(flash).unwrap(), keys, slot)
+}
+
+/// Raw flash content of the store
+fn content(store: SecureStore<Flash, Keys>) -> (Vec<u8>, Keys) {
+    let (kv, keys) = store.release();
+    (kv.release().0, keys)
+}
+
+#[test]
+fn sealed_values() {
+    let _kernel = mock::session();
+    let mut store = store(Keys::with(&[1]), 1);
+    store.set(b"wifi-psk", b"correct horse battery").unwrap();
+    store.set(b"token", b"").unwrap();
+
+    let mut buf = [0; 64];
+    let len = store.get(b"wifi-psk", &mut buf).unwrap().unwrap();
+    assert_eq!(&buf[..len], b"correct horse battery");
+    assert_eq!(store.get(b"token", &mut buf).unwrap(), Some(0));
+    assert_eq!(store.get(b"missing", &mut buf).unwrap(), None);
+
+    let err = store.get(b"wifi-psk", &mut [0; 4]).unwrap_err();
+    assert_eq!(err.status(), Status::Invalid);
+    let err = store.set(b"big", &[0; MAX_SECRET_LEN + 1]).unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::SecureStore);
+    assert_eq!(err.status(), Status::Invalid);
+
+    assert!(store.delete(b"token").unwrap());
+    assert_eq!(store.get(b"token", &mut buf).unwrap(), None);
+
+    // only the ciphertext reaches the flash
+    let (flash, _) = content(store);
+    assert!(
+        !flash
+            .windows(b"correct horse".len())
+            .any(|window| window == b"correct horse")
+    );
+}
+
+#[test]
+fn fresh_nonces() {
+    let _kernel = mock::session();
+    let mut kv = KvStore::open(Flash(vec![0xff; 8 * ERASE_SIZE])).unwrap();
+    let mut first = [0; 64];
+    let mut second = [0; 64];
+    for raw in [&mut first, &mut second] {
+        let mut store = SecureStore::new(kv, Keys::with(&[1]), 1);
+        store.set(b"pin", b"1234").unwrap();
+        kv = store.release().0;
+        kv.get(b"pin", raw).unwrap();
+    }
+    assert_eq!(first[0], 1);
+    assert_ne!(first[1..1 + NONCE_LEN], second[1..1 + NONCE_LEN]);
+}
+
+#[test]
+fn tampering() {
+    let _kernel = mock::session();
+    let mut store = store(Keys::with(&[1]), 1);
+    store.set(b"a", b"secret a").unwrap();
+    store.set(b"b", b"secret b").unwrap();
+    let (mut kv, keys) = store.release();
+
+    // a value moved to another key fails to open
+    let mut raw = [0; 64];
+    let len = kv.get(b"a", &mut raw).unwrap().unwrap();
+    kv.set(b"b", &raw[..len]).unwrap();
+    kv.set(b"c", b"short").unwrap();
+    let mut store = SecureStore::new(kv, keys, 1);
+    let mut buf = [0; 64];
+    let err = store.get(b"b", &mut buf).unwrap_err();
+    assert_eq!(err.status(), Status::Denied);
+    let err = store.get(b"c", &mut buf).unwrap_err();
+    assert_eq!(err.status(), Status::Critical);
+    assert!(store.get(b"a", &mut buf).unwrap().is_some());
+}
+
+#[test]
+fn random_denied() {
+    let kernel = mock::session();
+    let mut store = store(Keys::with(&[1]), 1);
+    kernel.set_status(Syscall::GetRandom, Status::Denied);
+    let err = store.set(b"pin", b"1234").unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::Random);
+    assert_eq!(store.get(b"pin", &mut [0; 8]).unwrap(), None);
+}
+
+#[test]
+fn key_rotation() {
+    let _kernel = mock::session();
+    let mut store = store(Keys::with(&[1, 2]), 1);
+    for (key, value) in [
+        (&b"a"[..], &b"alpha"[..]),
+        (b"b", b"beta"),
+        (b"c", b"gamma"),
+    ] {
+        store.set(key, value).unwrap();
+    }
+    assert_eq!(store.rotate(2).unwrap(), 3);
+    assert_eq!(store.slot(), 2);
+    assert_eq!(store.rotate(2).unwrap(), 0);
+
+    // the previous key is erased, no longer needed
+    let (kv, keys) = store.release();
+    assert!(!keys.keys.contains_key(&1) && keys.keys.contains_key(&2));
+    let mut store = SecureStore::new(kv, keys, 2);
+    let mut buf = [0; 16];
+    let len = store.get(b"b", &mut buf).unwrap().unwrap();
+    assert_eq!(&buf[..len], b"beta");
+    store.set(b"d", b"delta").unwrap();
+    let (kv, _) = store.release();
+    let mut raw = [0; 64];
+    kv.get(b"d", &mut raw).unwrap();
+    assert_eq!(raw[0], 2);
+}
+
+#[test]
+fn interrupted_rotation() {
+    let _kernel = mock::session();
+    let mut store = store(Keys::with(&[1, 2]), 1);
+    for (key, value) in [
+        (&b"a"[..], &b"alpha"[..]),
+        (b"b", b"beta"),
+        (b"c", b"gamma"),
+    ] {
+        store.set(key, value).unwrap();
+    }
+    let (kv, mut keys) = store.release();
+    keys.budget = Some(1);
+    let mut store = SecureStore::new(kv, keys, 1);
+    let err = store.rotate(2).unwrap_err();
+    assert_eq!(err.status(), Status::Busy);
+    assert_eq!(store.slot(), 1);
+
+    // mixed keys, still readable
+    let mut buf = [0; 16];
+    for (key, value) in [
+        (&b"a"[..], &b"alpha"[..]),
+        (b"b", b"beta"),
+        (b"c", b"gamma"),
+    ] {
+        let len = store.get(key, &mut buf).unwrap().unwrap();
+        assert_eq!(&buf[..len], value);
+    }
+
+    // the previous key kept until the rotation completes
+    let (kv, mut keys) = store.release();
+    assert!(keys.keys.contains_key(&1));
+    keys.budget = None;
+    let mut store = SecureStore::new(kv, keys, 1);
+    assert_eq!(store.rotate(2).unwrap(), 2);
+    let (_, keys) = store.release();
+    assert!(!keys.keys.contains_key(&1));
+}
@@ -0,0 +1,121 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Power-on self-test tests against the fake kernel
+
+#![cfg(all(feature = "selftest", feature = "mock"))]
+
+use sentry_uapi::systypes::{SHMPermission, Status, Syscall};
+use shield::error::{Error, Subsystem};
+use shield::mock;
+use shield::selftest::{self, MAX_CHECKS, Summary};
+use shield::shm::Shm;
+use shield::units::UnitsExt;
+
+const PERMS: u32 =
+    SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+
+fn passing() -> Result<(), Error> {
+    Ok(())
+}
+
+fn missing_imu() -> Result<(), Error> {
+    selftest::expect_id(0x00, 0x6c)
+}
+
+#[test]
+fn run() {
+    let kernel = mock::session();
+    assert_eq!(selftest::summary(), None);
+    selftest::register("sram", passing).unwrap();
+    selftest::register("imu", missing_imu).unwrap();
+    selftest::register("clock", || selftest::check_clock(10.ms())).unwrap();
+    let err = selftest::register("imu", passing).unwrap_err();
+    assert_eq!(err.status(), Status::Invalid);
+    assert_eq!(selftest::result("sram"), None);
+
+    let summary = selftest::run();
+    assert_eq!(
+        summary,
+        Summary {
+            passed: 2,
+            failed: 1
+        }
+    );
+    assert!(!summary.is_pass());
+    assert_eq!(selftest::summary(), Some(summary));
+    assert_eq!(selftest::result("clock"), Some(Ok(())));
+    let err = selftest::result("imu").unwrap().unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::Selftest);
+    assert_eq!((err.status(), err.handle()), (Status::NoEntity, Some(0)));
+    assert_eq!(selftest::result("uart"), None);
+
+    let log = String::from_utf8(kernel.log_output()).unwrap();
+    assert!(log.contains("selftest: imu failed"));
+    assert!(log.contains("selftest: 2 passed, 1 failed"));
+
+    let names = [
+        "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m",
+    ];
+    assert_eq!(names.len(), MAX_CHECKS - 3);
+    for name in names {
+        selftest::register(name, passing).unwrap();
+    }
+    let err = selftest::register("full", passing).unwrap_err();
+    assert_eq!(err.status(), Status::Busy);
+}
+
+#[test]
+fn generic_checks() {
+    let kernel = mock::session();
+    let base = kernel.add_shm(0x10, 0x110, 64, PERMS);
+    // SAFETY: the fake shared memory is 64 bytes long
+    unsafe { core::ptr::with_exposed_provenance_mut::<u32>(base).write(0xdead_beef) };
+    let mut shm = Shm::new(0x10).unwrap().map(0).unwrap();
+    selftest::ram_pattern(&mut shm).unwrap();
+    // SAFETY: see above
+    let words =
+        unsafe { core::slice::from_raw_parts(core::ptr::with_exposed_provenance::<u32>(base), 16) };
+    assert!(words.iter().all(|&word| word == 0));
+
+    kernel.add_shm(0x11, 0x111, 64, SHMPermission::Map as u32);
+    let mut shm = Shm::new(0x11).unwrap().map(0).unwrap();
+    let err = selftest::ram_pattern(&mut shm).unwrap_err();
+    assert_eq!(err.status(), Status::Denied);
+
+    selftest::check_clock(5.ms()).unwrap();
+    kernel.set_status(Syscall::Sleep, Status::Intr);
+    let err = selftest::check_clock(5.ms()).unwrap_err();
+    assert_eq!(err.status(), Status::Intr);
+
+    assert!(selftest::expect_id(0x6c, 0x6c).is_ok());
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn metrics() {
+    use shield::metrics::{ENTRY_LEN, Registry, Scraper, Value};
+
+    let kernel = mock::session();
+    kernel.add_shm(0x10, 0x110, 16 + 2 * ENTRY_LEN, PERMS);
+    let registry = Registry::new(Shm::new(0x10).unwrap().map(0).unwrap()).unwrap();
+    let summary = Summary {
+        passed: 3,
+        failed: 1,
+    };
+    summary.export(&registry).unwrap();
+
+    let scraper = Scraper::new(registry.release()).unwrap();
+    let metrics: Vec<_> = scraper
+        .iter()
+        .map(|metric| (metric.name().to_owned(), metric.value))
+        .collect();
+    assert_eq!(
+        metrics,
+        [
+            ("selftest_passed".into(), Value::Gauge { value: 3, max: 3 }),
+            ("selftest_failed".into(), Value::Gauge { value: 1, max: 1 }),
+        ]
+    );
+}
@@ -0,0 +1,125 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Debug shell tests over a scripted stream
+
+#![cfg(feature = "shell")]
+
+use core::fmt::Write as _;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+use embedded_io_async::{ErrorType, Read, Write};
+use sentry_uapi::systypes::Status;
+use shield::error::{Error, Subsystem};
+use shield::shell::{Command, Output, Shell};
+
+/// Stream replaying `input`, recording the output
+struct Script {
+    input: Vec<u8>,
+    output: Vec<u8>,
+}
+
+impl ErrorType for Script {
+    type Error = core::convert::Infallible;
+}
+
+impl Read for Script {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let len = buf.len().min(self.input.len()).min(3);
+        buf[..len].copy_from_slice(&self.input[..len]);
+        self.input.drain(..len);
+        Ok(len)
+    }
+}
+
+impl Write for Script {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.output.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+}
+
+#[derive(Default)]
+struct Board {
+    led: bool,
+}
+
+fn led(board: &mut Board, args: &[&str], _: &mut Output) -> Result<(), Error> {
+    board.led = match args {
+        ["on"] => true,
+        ["off"] => false,
+        _ => return Err(Error::new(Subsystem::Process, Status::Invalid)),
+    };
+    Ok(())
+}
+
+fn echo(_: &mut Board, args: &[&str], output: &mut Output) -> Result<(), Error> {
+    write!(output, "{}\r\n", args.join("|")).unwrap();
+    Ok(())
+}
+
+const COMMANDS: &[Command<Board>] = &[
+    Command {
+        name: "led",
+        help: "led <on|off>: drive the LED",
+        handler: led,
+    },
+    Command {
+        name: "echo",
+        help: "echo <args>: print the arguments",
+        handler: echo,
+    },
+];
+
+/// Run the shell over `input`, returning the output and the board state
+fn run(input: &[u8]) -> (String, Board) {
+    let mut script = Script {
+        input: input.to_vec(),
+        output: Vec::new(),
+    };
+    let mut board = Board::default();
+    let mut shell = Shell::new(COMMANDS, "> ");
+    {
+        let mut future = pin!(shell.run(&mut script, &mut board));
+        let poll = future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()));
+        assert!(
+            matches!(poll, Poll::Ready(Ok(()))),
+            "the shell didn't end with the stream"
+        );
+    }
+    (String::from_utf8(script.output).unwrap(), board)
+}
+
+#[test]
+fn dispatch() {
+    let (output, board) = run(b"led on\r");
+    assert_eq!(output, "> led on\r\n> ");
+    assert!(board.led);
+
+    let (output, _) = run(b"echo a  \"b c\" d\rhelp\rfoo\rled blink\r");
+    let lines: Vec<_> = output.split("\r\n").collect();
+    assert_eq!(lines[1], "a|b c|d");
+    assert_eq!(lines[3..5], [COMMANDS[0].help, COMMANDS[1].help]);
+    assert_eq!(lines[6], "foo: unknown command, try help");
+    assert_eq!(lines[8], "error: process: invalid parameter");
+}
+
+#[test]
+fn line_editing() {
+    // backspace, arrow key and line erase
+    let (output, board) = run(b"lex\x7fd o\x1b[An\r");
+    assert!(board.led);
+    assert!(output.starts_with("> lex\x08 \x08d on\r\n"));
+
+    let (output, board) = run(b"led on\x15led off\r");
+    assert!(!board.led);
+    assert!(output.contains(&"\x08 \x08".repeat(6)));
+
+    let (output, board) = run(b"led on\x03\r");
+    assert!(!board.led);
+    assert_eq!(output, "> led on^C\r\n> \r\n> ");
+}
@@ -0,0 +1,99 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Shared memory vector and ring tests against the fake kernel
+
+#![cfg(all(feature = "shm", feature = "mock"))]
+
+use sentry_uapi::systypes::{SHMPermission, Status};
+use shield::mock;
+use shield::shm::{Shm, ShmRing, ShmVec};
+
+const PERMS: u32 =
+    SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+
+fn shm(kernel: &mock::Session, len: usize, perms: u32) -> (Shm<shield::shm::Mapped>, usize) {
+    let base = kernel.add_shm(0x10, 0x110, len, perms);
+    (Shm::new(0x10).unwrap().map(0).unwrap(), base)
+}
+
+#[test]
+fn vec_in_place() {
+    let kernel = mock::session();
+    let (shm, base) = shm(&kernel, 4 + 5 * 2, PERMS);
+    let mut samples = ShmVec::<u16>::new(shm).unwrap();
+    assert_eq!(samples.capacity(), 5);
+    assert!(samples.is_empty());
+
+    samples.push(0x1234).unwrap();
+    samples.extend_from_slice(&[1, 2, 3]).unwrap();
+    assert_eq!(
+        samples.extend_from_slice(&[4, 5]).unwrap_err().status(),
+        Status::Busy
+    );
+    samples.push(4).unwrap();
+    assert_eq!(samples.push(5), Err(5));
+    assert!(samples.is_full());
+    samples[1] = 7;
+    assert_eq!(*samples, [0x1234, 7, 2, 3, 4]);
+
+    // written in place, after the length
+    let words =
+        unsafe { std::slice::from_raw_parts(std::ptr::with_exposed_provenance::<u16>(base), 7) };
+    assert_eq!(words, [5, 0, 0x1234, 7, 2, 3, 4]);
+
+    // seen by the peer
+    let peer = unsafe { ShmVec::<u16>::open(samples.release()) }.unwrap();
+    assert_eq!(peer.as_slice(), [0x1234, 7, 2, 3, 4]);
+    let mut samples = peer;
+    assert_eq!(samples.pop(), Some(4));
+    samples.truncate(1);
+    assert_eq!(samples.len(), 1);
+    samples.clear();
+    assert_eq!(samples.pop(), None);
+}
+
+#[test]
+fn ring() {
+    let kernel = mock::session();
+    let (shm, _) = shm(&kernel, 8 + 3 * 4, PERMS);
+    let mut producer = ShmRing::<u32>::new(shm).unwrap();
+    assert_eq!(producer.capacity(), 3);
+    assert_eq!(producer.peek(), None);
+
+    // wrapping several times, full rings being told apart from empty ones
+    let mut next = 0;
+    let mut expected = 0;
+    for _ in 0..5 {
+        while producer.enqueue(next).is_ok() {
+            next += 1;
+        }
+        assert!(producer.is_full());
+        assert_eq!(producer.len(), 3);
+        assert_eq!(producer.peek(), Some(expected));
+        for _ in 0..2 {
+            assert_eq!(producer.dequeue(), Some(expected));
+            expected += 1;
+        }
+    }
+
+    let mut consumer = unsafe { ShmRing::<u32>::open(producer.release()) }.unwrap();
+    assert_eq!(consumer.len(), 1);
+    assert_eq!(consumer.dequeue(), Some(expected));
+    assert!(consumer.is_empty());
+    assert_eq!(consumer.dequeue(), None);
+}
+
+#[test]
+fn invalid_regions() {
+    let kernel = mock::session();
+    let (shm, _) = shm(&kernel, 4 + 3, PERMS);
+    let err = ShmVec::<u32>::new(shm).err().unwrap();
+    assert_eq!(err.status(), Status::Invalid);
+    assert_eq!(err.handle(), Some(0x110));
+
+    kernel.add_shm(0x11, 0x111, 64, PERMS & !(SHMPermission::Write as u32));
+    let shm = Shm::new(0x11).unwrap().map(0).unwrap();
+    assert!(ShmRing::<u8>::new(shm).is_err());
+}
@@ -0,0 +1,134 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Shared memory typestate model checking against the fake kernel
+//!
+//! Random sequences of operations are applied both to the typestate wrapper
+//! and to a model of the kernel shared memory state. Operations which are not
+//! available in the current typestate (such as mapping a mapped shared memory)
+//! can't be expressed, and are skipped. Every operation applied must be
+//! accepted by the kernel, and the kernel state must follow the model.
+
+#![cfg(feature = "mock")]
+
+use proptest::prelude::*;
+use sentry_uapi::systypes::{SHMPermission, Syscall};
+use shield::mock;
+use shield::shm::{Mapped, Shm, Unmapped};
+
+const SHM_LABEL: u32 = 0xf00;
+const SHM_HANDLE: u32 = 0x1f00;
+const SHM_LEN: usize = 128;
+
+/// Operation on the shared memory
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Map,
+    Unmap,
+    SetCredentials {
+        task: u32,
+        perms: u32,
+    },
+    /// Hand the shared memory over to another task
+    Transfer {
+        task: u32,
+    },
+    RefreshInfo,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        Just(Op::Map),
+        Just(Op::Unmap),
+        (0..4_u32, 0..16_u32).prop_map(|(task, perms)| Op::SetCredentials { task, perms }),
+        (0..4_u32).prop_map(|task| Op::Transfer { task }),
+        Just(Op::RefreshInfo),
+    ]
+}
+
+/// Typestate wrapper, in either state
+enum Wrapper {
+    Unmapped(Shm<Unmapped>),
+    Mapped(Shm<Mapped>),
+}
+
+/// Kernel shared memory state model
+#[derive(Default)]
+struct Model {
+    mapped: bool,
+    credentials: Vec<(u32, u32)>,
+}
+
+impl Model {
+    fn grant(&mut self, task: u32, perms: u32) {
+        self.credentials.retain(|&(granted, _)| granted != task);
+        self.credentials.push((task, perms));
+    }
+}
+
+/// Apply `op`, returning the next wrapper state, or an error describing the
+/// rejected operation.
+fn apply(wrapper: Wrapper, op: Op, model: &mut Model) -> Result<Wrapper, String> {
+    let rejected = |error: shield::Error| format!("{op:?} rejected: {error}");
+    Ok(match (wrapper, op) {
+        (Wrapper::Unmapped(shm), Op::Map) => {
+            model.mapped = true;
+            Wrapper::Mapped(shm.map(0).map_err(rejected)?)
+        }
+        (Wrapper::Mapped(shm), Op::Unmap) => {
+            model.mapped = false;
+            Wrapper::Unmapped(shm.unmap().map_err(rejected)?)
+        }
+        (Wrapper::Unmapped(mut shm), Op::SetCredentials { task, perms }) => {
+            shm.set_credentials(task, perms).map_err(rejected)?;
+            model.grant(task, perms);
+            Wrapper::Unmapped(shm)
+        }
+        (Wrapper::Unmapped(mut shm), Op::Transfer { task }) => {
+            let perms = SHMPermission::Map as u32 | SHMPermission::Transfer as u32;
+            shm.set_credentials(task, perms).map_err(rejected)?;
+            model.grant(task, perms);
+            Wrapper::Unmapped(shm)
+        }
+        (Wrapper::Unmapped(mut shm), Op::RefreshInfo) => {
+            check_info(shm.refresh_info().map_err(rejected)?.len)?;
+            Wrapper::Unmapped(shm)
+        }
+        (Wrapper::Mapped(mut shm), Op::RefreshInfo) => {
+            check_info(shm.refresh_info().map_err(rejected)?.len)?;
+            Wrapper::Mapped(shm)
+        }
+        // not expressible in the current typestate
+        (wrapper, _) => wrapper,
+    })
+}
+
+fn check_info(len: usize) -> Result<(), String> {
+    if len == SHM_LEN {
+        Ok(())
+    } else {
+        Err(format!("unexpected length {len}"))
+    }
+}
+
+proptest! {
+    #[test]
+    fn typestate_sequences(ops in prop::collection::vec(op(), 0..32)) {
+        let kernel = mock::session();
+        let perms = SHMPermission::Map as u32 | SHMPermission::Write as u32;
+        kernel.add_shm(SHM_LABEL, SHM_HANDLE, SHM_LEN, perms);
+
+        let mut model = Model::default();
+        let mut wrapper = Wrapper::Unmapped(Shm::new(SHM_LABEL).unwrap());
+        for op in ops {
+            wrapper = apply(wrapper, op, &mut model).map_err(TestCaseError::fail)?;
+            prop_assert_eq!(kernel.is_mapped(SHM_HANDLE), model.mapped);
+            prop_assert_eq!(kernel.credentials(SHM_HANDLE), model.credentials.clone());
+        }
+        // the wrapper never maps an already mapped shared memory
+        prop_assert!(
+            kernel.call_count(Syscall::MapShm) <= kernel.call_count(Syscall::UnmapShm) + 1
+        );
+    }
+}
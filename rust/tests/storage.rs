@@ -0,0 +1,242 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Flash region tests over a simulated flash controller
+
+#![cfg(feature = "storage")]
+
+use sentry_uapi::systypes::Status;
+use shield::error::Error;
+use shield::storage::{Flash, FlashDevice, Geometry};
+
+const SECTOR_SIZE: usize = 128;
+const WRITE_SIZE: usize = 8;
+
+/// Flash controller, each unit being programmable once per erase
+struct Device {
+    memory: Vec<u8>,
+    bad: Vec<usize>,
+}
+
+impl Device {
+    fn new(sectors: usize, bad: &[usize]) -> Self {
+        Self {
+            memory: vec![0xff; sectors * SECTOR_SIZE],
+            bad: bad.to_vec(),
+        }
+    }
+}
+
+impl FlashDevice for Device {
+    fn geometry(&self) -> Geometry {
+        Geometry {
+            sectors: self.memory.len() / SECTOR_SIZE,
+            sector_size: SECTOR_SIZE,
+            write_size: WRITE_SIZE,
+        }
+    }
+
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), Error> {
+        buf.copy_from_slice(&self.memory[offset..offset + buf.len()]);
+        Ok(())
+    }
+
+    fn program(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        assert!(offset.is_multiple_of(WRITE_SIZE) && data.len().is_multiple_of(WRITE_SIZE));
+        assert!(!self.bad.contains(&(offset / SECTOR_SIZE)));
+        let target = &mut self.memory[offset..offset + data.len()];
+        assert!(
+            target.iter().all(|&byte| byte == 0xff),
+            "programming {offset:#x} twice"
+        );
+        target.copy_from_slice(data);
+        Ok(())
+    }
+
+    fn erase_sector(&mut self, sector: usize) -> Result<(), Error> {
+        assert!(!self.bad.contains(&sector));
+        self.memory[sector * SECTOR_SIZE..(sector + 1) * SECTOR_SIZE].fill(0xff);
+        Ok(())
+    }
+
+    fn is_bad(&self, sector: usize) -> Result<bool, Error> {
+        Ok(self.bad.contains(&sector))
+    }
+}
+
+#[test]
+fn unaligned_write() {
+    let mut flash = Flash::new(Device::new(4, &[])).unwrap();
+    assert_eq!(flash.capacity(), 4 * SECTOR_SIZE);
+
+    // head, whole units and tail, across a sector boundary
+    let data: Vec<u8> = (0..200).collect();
+    flash.write(SECTOR_SIZE - 61, &data).unwrap();
+    let mut buf = vec![0; 200];
+    flash.read(SECTOR_SIZE - 61, &mut buf).unwrap();
+    assert_eq!(buf, data);
+
+    let device = flash.release();
+    assert_eq!(device.memory[SECTOR_SIZE - 64..SECTOR_SIZE - 61], [0xff; 3]);
+    assert_eq!(device.memory[SECTOR_SIZE + 139], 0xff);
+
+    // the partial units are programmed already
+    let mut flash = Flash::new(device).unwrap();
+    let err = flash.write(SECTOR_SIZE - 64, &[0]).unwrap_err();
+    assert!(err.status() == Status::Busy);
+    let err = flash.write(4 * SECTOR_SIZE - 1, &[0, 0]).unwrap_err();
+    assert!(err.status() == Status::Invalid);
+}
+
+#[test]
+fn erase() {
+    let mut flash = Flash::new(Device::new(4, &[])).unwrap();
+    flash.write(0, &[0; 2 * SECTOR_SIZE]).unwrap();
+
+    let err = flash.erase(0, SECTOR_SIZE / 2).unwrap_err();
+    assert!(err.status() == Status::Invalid);
+    let err = flash.erase(3 * SECTOR_SIZE, 5 * SECTOR_SIZE).unwrap_err();
+    assert!(err.status() == Status::Invalid);
+
+    flash.erase(SECTOR_SIZE, 2 * SECTOR_SIZE).unwrap();
+    let mut buf = [0; 2 * SECTOR_SIZE];
+    flash.read(0, &mut buf).unwrap();
+    assert_eq!(buf[..SECTOR_SIZE], [0; SECTOR_SIZE]);
+    assert_eq!(buf[SECTOR_SIZE..], [0xff; SECTOR_SIZE]);
+}
+
+#[test]
+fn bad_sectors() {
+    let mut flash = Flash::new(Device::new(5, &[0, 2])).unwrap();
+    assert_eq!(flash.capacity(), 3 * SECTOR_SIZE);
+    assert!(flash.is_bad(2) && !flash.is_bad(3));
+    assert!(flash.is_bad(5) && flash.is_bad(usize::MAX));
+
+    let data = [0x42; 2 * SECTOR_SIZE];
+    flash.write(SECTOR_SIZE / 2, &data).unwrap();
+    flash.erase(2 * SECTOR_SIZE, 3 * SECTOR_SIZE).unwrap();
+    let mut buf = [0; 2 * SECTOR_SIZE];
+    flash.read(0, &mut buf).unwrap();
+    assert_eq!(buf[..SECTOR_SIZE / 2], [0xff; SECTOR_SIZE / 2]);
+    assert_eq!(buf[SECTOR_SIZE / 2..], [0x42; 3 * SECTOR_SIZE / 2]);
+
+    // logical sectors 0, 1 and 2 are the physical sectors 1, 3 and 4
+    let device = flash.release();
+    assert_eq!(device.memory[..SECTOR_SIZE], [0xff; SECTOR_SIZE]);
+    assert_eq!(device.memory[3 * SECTOR_SIZE], 0x42);
+    assert_eq!(device.memory[4 * SECTOR_SIZE..], [0xff; SECTOR_SIZE]);
+}
+
+#[test]
+fn invalid_geometry() {
+    /// Device of the given geometry, never accessed
+    struct Geometric(Geometry);
+
+    impl FlashDevice for Geometric {
+        fn geometry(&self) -> Geometry {
+            self.0
+        }
+
+        fn read(&self, _offset: usize, _buf: &mut [u8]) -> Result<(), Error> {
+            unreachable!()
+        }
+
+        fn program(&mut self, _offset: usize, _data: &[u8]) -> Result<(), Error> {
+            unreachable!()
+        }
+
+        fn erase_sector(&mut self, _sector: usize) -> Result<(), Error> {
+            unreachable!()
+        }
+    }
+
+    let valid = Geometry {
+        sectors: 4,
+        sector_size: SECTOR_SIZE,
+        write_size: WRITE_SIZE,
+    };
+    for geometry in [
+        Geometry {
+            sector_size: 0,
+            ..valid
+        },
+        Geometry {
+            write_size: 3,
+            ..valid
+        },
+        Geometry {
+            sector_size: 100,
+            ..valid
+        },
+        Geometry {
+            sectors: 1025,
+            ..valid
+        },
+    ] {
+        let err = Flash::new(Geometric(geometry)).err().unwrap();
+        assert!(err.status() == Status::Invalid);
+    }
+}
+
+#[test]
+fn nor_flash() {
+    use embedded_storage::nor_flash::{
+        NorFlash, NorFlashError as _, NorFlashErrorKind, ReadNorFlash,
+    };
+    use shield::storage::{NorFlashError, SizedFlash};
+
+    assert!(
+        SizedFlash::<_, 256, WRITE_SIZE>::new(Flash::new(Device::new(3, &[])).unwrap()).is_err()
+    );
+    assert!(
+        SizedFlash::<_, SECTOR_SIZE, 1>::new(Flash::new(Device::new(3, &[])).unwrap()).is_err()
+    );
+    let flash = Flash::new(Device::new(3, &[1])).unwrap();
+    let mut nor = SizedFlash::<_, SECTOR_SIZE, WRITE_SIZE>::new(flash).unwrap();
+    assert_eq!(nor.capacity(), 2 * SECTOR_SIZE);
+    assert_eq!(
+        SizedFlash::<Device, SECTOR_SIZE, WRITE_SIZE>::WRITE_SIZE,
+        WRITE_SIZE
+    );
+
+    // write over the bad sector, and unaligned read
+    let data: Vec<u8> = (0..96).collect();
+    nor.write(SECTOR_SIZE as u32 - 48, &data).unwrap();
+    let mut buf = [0; 95];
+    nor.read(SECTOR_SIZE as u32 - 47, &mut buf).unwrap();
+    assert_eq!(buf[..], data[1..]);
+
+    let err = nor.erase(0, SECTOR_SIZE as u32 / 2).unwrap_err();
+    assert_eq!(err.kind(), NorFlashErrorKind::NotAligned);
+    let err = nor.read(2 * SECTOR_SIZE as u32 - 1, &mut buf).unwrap_err();
+    assert_eq!(err.kind(), NorFlashErrorKind::OutOfBounds);
+    let err = nor
+        .write(SECTOR_SIZE as u32 - 50, &[0; WRITE_SIZE])
+        .unwrap_err();
+    assert_eq!(err.kind(), NorFlashErrorKind::NotAligned);
+    let err = nor
+        .write(2 * SECTOR_SIZE as u32, &[0; WRITE_SIZE])
+        .unwrap_err();
+    assert!(matches!(err, NorFlashError::OutOfBounds));
+
+    nor.erase(SECTOR_SIZE as u32, 2 * SECTOR_SIZE as u32)
+        .unwrap();
+    nor.read(SECTOR_SIZE as u32, &mut buf).unwrap();
+    assert_eq!(buf, [0xff; 95]);
+    let device = nor.release().release();
+    assert_eq!(device.memory[SECTOR_SIZE - 48..SECTOR_SIZE], data[..48]);
+}
+
+#[cfg(feature = "kvstore")]
+#[test]
+fn kvstore_backing() {
+    use shield::kvstore::KvStore;
+
+    let mut store = KvStore::open(Flash::new(Device::new(6, &[1])).unwrap()).unwrap();
+    store.set(b"boots", &7_u32.to_le_bytes()).unwrap();
+    let store = KvStore::open(store.release()).unwrap();
+    let mut buf = [0; 4];
+    assert_eq!(store.get(b"boots", &mut buf).unwrap(), Some(4));
+    assert_eq!(buf, 7_u32.to_le_bytes());
+}
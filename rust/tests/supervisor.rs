@@ -0,0 +1,234 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Child task supervision tests against the fake kernel
+
+#![cfg(all(feature = "supervisor", feature = "mock"))]
+
+use sentry_uapi::systypes::{EventType, SHMPermission, Status, Syscall};
+use shield::crashlog::{self, CrashKind};
+use shield::error::Subsystem;
+use shield::shm::Shm;
+use shield::supervisor::{ExitStatus, RestartPolicy, Supervisor, notify_exit};
+use shield::{mock, time};
+
+const CHILD: u32 = 0x20;
+const CHILD_HANDLE: u32 = 0x2000;
+
+const PERMS: u32 =
+    SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+
+fn exit_message(status: i32) -> Vec<u8> {
+    let mut message = b"TIXE".to_vec();
+    message.extend_from_slice(&status.to_le_bytes());
+    message
+}
+
+fn supervisor(kernel: &mock::Session, policy: RestartPolicy) -> Supervisor {
+    kernel.add_task(CHILD, CHILD_HANDLE);
+    let mut supervisor = Supervisor::new();
+    supervisor.add(CHILD, policy).unwrap();
+    supervisor.start_all().unwrap();
+    supervisor
+}
+
+#[test]
+fn backoff_restarts() {
+    let kernel = mock::session();
+    let mut supervisor = supervisor(
+        &kernel,
+        RestartPolicy::Backoff {
+            initial_ms: 100,
+            max_ms: 400,
+        },
+    );
+    assert_eq!(kernel.call_count(Syscall::Start), 1);
+
+    let mut delays = Vec::new();
+    for status in 1..=4 {
+        kernel.push_event(EventType::Ipc, CHILD_HANDLE, &exit_message(status));
+        let exit = supervisor.wait().unwrap();
+        assert_eq!(exit.label, CHILD);
+        assert_eq!(exit.status, ExitStatus::Exited(status));
+        assert!(exit.crash.is_none());
+        delays.push(exit.restart_in_ms.unwrap());
+
+        // restarted once the delay elapsed, then nothing left to wait for
+        let before_ms = time::uptime_ms().unwrap();
+        let err = supervisor.wait().err().unwrap();
+        assert_eq!(err.status(), Status::Deadlk);
+        assert!(time::uptime_ms().unwrap() - before_ms >= u64::from(delays[delays.len() - 1]));
+    }
+    assert_eq!(delays, [100, 200, 400, 400]);
+    assert_eq!(supervisor.restarts(CHILD), Some(4));
+    assert_eq!(kernel.call_count(Syscall::Start), 5);
+
+    // back to the initial delay once the child ran long enough
+    kernel.set_uptime_us((time::uptime_ms().unwrap() + 400) * 1000);
+    kernel.push_event(EventType::Ipc, CHILD_HANDLE, &exit_message(0));
+    assert_eq!(supervisor.wait().unwrap().restart_in_ms, Some(100));
+}
+
+#[test]
+fn never_restarted() {
+    let kernel = mock::session();
+    let mut supervisor = supervisor(&kernel, RestartPolicy::Never);
+    assert!(supervisor.last_exit(CHILD).is_none());
+
+    // dropped: not a child, not a notification
+    kernel.push_event(EventType::Ipc, 0x3000, &exit_message(1));
+    kernel.push_event(EventType::Ipc, CHILD_HANDLE, b"hello");
+    kernel.push_event(EventType::Ipc, CHILD_HANDLE, &exit_message(-2));
+    let exit = supervisor.wait().unwrap();
+    assert_eq!(exit.status, ExitStatus::Exited(-2));
+    assert_eq!(exit.restart_in_ms, None);
+    assert_eq!(supervisor.wait().err().unwrap().status(), Status::Deadlk);
+    assert_eq!(
+        supervisor.last_exit(CHILD).map(|exit| exit.status),
+        Some(ExitStatus::Exited(-2))
+    );
+    assert_eq!(supervisor.restarts(CHILD), Some(0));
+    assert_eq!(kernel.call_count(Syscall::Start), 1);
+}
+
+#[test]
+fn crash_found_in_crashlog() {
+    let kernel = mock::session();
+    let base = kernel.add_shm(0x10, 0x110, 256, PERMS);
+    let mut supervisor = supervisor(&kernel, RestartPolicy::Always);
+    supervisor
+        .bind_crashlog(CHILD, Shm::new(0x10).unwrap().map(0).unwrap())
+        .unwrap();
+
+    // written by the child fault handler
+    crashlog::record(CrashKind::Fault, format_args!("bus fault"));
+    let report = crashlog::last().unwrap();
+    crashlog::clear();
+    let bytes = report.as_bytes();
+    unsafe {
+        std::ptr::copy_nonoverlapping(
+            bytes.as_ptr(),
+            std::ptr::with_exposed_provenance_mut(base),
+            bytes.len(),
+        );
+    }
+
+    let exit = supervisor.wait().unwrap();
+    assert_eq!(exit.status, ExitStatus::Crashed);
+    assert_eq!(exit.crash.unwrap().message(), "bus fault");
+    assert_eq!(exit.restart_in_ms, Some(0));
+
+    // restarted, the report being cleared, then polled
+    kernel.set_status(Syscall::Start, Status::Invalid);
+    kernel.push_event(EventType::Ipc, CHILD_HANDLE, &exit_message(0));
+    let exit = supervisor.wait().unwrap();
+    assert_eq!(exit.status, ExitStatus::Exited(0));
+    assert!(exit.crash.is_none());
+    assert_eq!(supervisor.restarts(CHILD), Some(1));
+}
+
+#[test]
+fn abort_found_in_crashlog() {
+    let kernel = mock::session();
+    let base = kernel.add_shm(0x10, 0x110, 256, PERMS);
+    let mut supervisor = supervisor(&kernel, RestartPolicy::Never);
+    supervisor
+        .bind_crashlog(CHILD, Shm::new(0x10).unwrap().map(0).unwrap())
+        .unwrap();
+
+    // the child aborts, its report being written before its exit
+    let exited = std::panic::catch_unwind(|| crashlog::abort(format_args!("no route")));
+    let message = *exited.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(
+        message,
+        format!("task exited with status {}", crashlog::ABORT_STATUS)
+    );
+    assert_eq!(kernel.call_count(Syscall::Exit), 1);
+    let report = crashlog::last().unwrap();
+    crashlog::clear();
+    assert_eq!(report.kind().unwrap(), CrashKind::Abort);
+    let bytes = report.as_bytes();
+    unsafe {
+        std::ptr::copy_nonoverlapping(
+            bytes.as_ptr(),
+            std::ptr::with_exposed_provenance_mut(base),
+            bytes.len(),
+        );
+    }
+
+    let exit = supervisor.wait().unwrap();
+    assert_eq!(exit.status, ExitStatus::Crashed);
+    assert_eq!(exit.crash.unwrap().message(), "no route");
+    assert_eq!(exit.restart_in_ms, None);
+}
+
+#[test]
+fn notification() {
+    let kernel = mock::session();
+    kernel.add_task(0x01, 0x100);
+    notify_exit(0x01, 42).unwrap();
+    assert_eq!(kernel.sent_ipc(), [(0x100, exit_message(42))]);
+
+    kernel.set_status(Syscall::SendIPC, Status::Intr);
+    let err = notify_exit(0x01, 42).unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::Supervisor);
+    assert_eq!(err.status(), Status::Intr);
+}
+
+#[test]
+fn children() {
+    let kernel = mock::session();
+    let mut supervisor = supervisor(&kernel, RestartPolicy::Always);
+    let err = supervisor.add(CHILD, RestartPolicy::Never).unwrap_err();
+    assert_eq!(err.status(), Status::Invalid);
+    assert!(supervisor.add(0x21, RestartPolicy::Never).is_err());
+    assert_eq!(
+        supervisor.start(0x21).unwrap_err().status(),
+        Status::NoEntity
+    );
+
+    kernel.set_status(Syscall::Start, Status::Denied);
+    assert_eq!(
+        supervisor.start(CHILD).unwrap_err().status(),
+        Status::Denied
+    );
+}
+
+#[cfg(feature = "selftest")]
+#[test]
+fn selftest_summaries() {
+    use shield::selftest::Summary;
+    use shield::supervisor::notify_selftest;
+
+    let kernel = mock::session();
+    kernel.add_task(0x01, 0x100);
+    let summary = Summary {
+        passed: 4,
+        failed: 1,
+    };
+    notify_selftest(0x01, summary).unwrap();
+    assert_eq!(kernel.sent_ipc(), [(0x100, b"TSOP\x04\x01".to_vec())]);
+
+    let mut supervisor = supervisor(&kernel, RestartPolicy::Always);
+    assert_eq!(supervisor.selftest(CHILD), None);
+    kernel.push_event(EventType::Ipc, CHILD_HANDLE, b"TSOP\x04\x01");
+    assert_eq!(supervisor.wait_selftest(CHILD, 100).unwrap(), summary);
+    assert_eq!(supervisor.selftest(CHILD), Some(summary));
+
+    // reset on restart, the exit being returned by the next wait
+    supervisor.start(CHILD).unwrap();
+    assert_eq!(supervisor.selftest(CHILD), None);
+    let before = time::uptime_ms().unwrap();
+    let err = supervisor.wait_selftest(CHILD, 100).unwrap_err();
+    assert_eq!((err.status(), err.handle()), (Status::Timeout, Some(CHILD)));
+    assert!(time::uptime_ms().unwrap() - before >= 100);
+
+    kernel.push_event(EventType::Ipc, CHILD_HANDLE, &exit_message(3));
+    let err = supervisor.wait_selftest(CHILD, 100).unwrap_err();
+    assert_eq!(err.status(), Status::Intr);
+    assert_eq!(supervisor.restarts(CHILD), Some(0));
+    let exit = supervisor.wait().unwrap();
+    assert_eq!(exit.status, ExitStatus::Exited(3));
+    assert_eq!(supervisor.restarts(CHILD), Some(1));
+}
@@ -0,0 +1,38 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Inter-task barrier tests against the fake kernel
+
+#![cfg(all(feature = "sync", feature = "mock"))]
+
+use sentry_uapi::systypes::Status;
+use shield::error::Subsystem;
+use shield::sync::Barrier;
+use shield::{mock, process};
+
+#[test]
+fn single_task_leads() {
+    let _kernel = mock::session();
+    let barrier = Barrier::new(0, 0x42);
+    assert_eq!(barrier.n_tasks(), 1);
+    for _ in 0..3 {
+        assert!(barrier.wait().is_leader());
+        assert!(barrier.wait_timeout(10).unwrap().is_leader());
+    }
+    assert_eq!(barrier.waiting(), 0);
+}
+
+#[test]
+fn timeout_withdraws() {
+    let kernel = mock::session();
+    // parked in the kernel, the timeout elapsing
+    kernel.add_task(0x01, 0x100);
+    process::register_current(0x01).unwrap();
+    let barrier = Barrier::new(2, 0x42);
+    let err = barrier.wait_timeout(20).unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::Sync);
+    assert_eq!(err.status(), Status::Timeout);
+    assert_eq!(err.handle(), Some(0x42));
+    assert_eq!(barrier.waiting(), 0);
+}
@@ -0,0 +1,73 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Watch cell tests against the fake kernel
+
+#![cfg(all(feature = "sync", feature = "mock"))]
+
+use sentry_uapi::systypes::Status;
+use shield::error::Subsystem;
+use shield::sync::Watch;
+use shield::{mock, process};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Reading {
+    sensor: u8,
+    value: i32,
+}
+
+#[test]
+fn latest_value() {
+    let _kernel = mock::session();
+    let watch = Watch::new(Reading {
+        sensor: 1,
+        value: 0,
+    });
+    let mut early = watch.receiver();
+    assert!(!early.has_changed());
+    assert_eq!(watch.version(), 0);
+
+    watch.send(Reading {
+        sensor: 1,
+        value: 10,
+    });
+    watch.send(Reading {
+        sensor: 1,
+        value: 20,
+    });
+    assert_eq!(watch.version(), 2);
+    assert_eq!(watch.get().value, 20);
+
+    // intermediate values are skipped
+    let mut late = watch.receiver();
+    assert!(early.has_changed() && !late.has_changed());
+    assert_eq!(early.changed().value, 20);
+    assert!(!early.has_changed());
+    assert_eq!(late.get().value, 20);
+
+    watch.send(Reading {
+        sensor: 2,
+        value: -5,
+    });
+    assert_eq!(
+        late.changed_timeout(10).unwrap(),
+        Reading {
+            sensor: 2,
+            value: -5
+        }
+    );
+}
+
+#[test]
+fn changed_timeout() {
+    let kernel = mock::session();
+    // parked in the kernel, the timeout elapsing
+    kernel.add_task(0x01, 0x100);
+    process::register_current(0x01).unwrap();
+    let watch = Watch::new(0u32);
+    let mut receiver = watch.receiver();
+    let err = receiver.changed_timeout(20).unwrap_err();
+    assert_eq!(err.subsystem(), Subsystem::Sync);
+    assert_eq!(err.status(), Status::Timeout);
+}
@@ -0,0 +1,68 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Refused syscalls audit tests against the fake kernel
+
+#![cfg(all(feature = "audit-syscalls", feature = "mock"))]
+
+use sentry_uapi::systypes::{SHMPermission, Status, Syscall};
+use shield::shm::Shm;
+use shield::syscall_audit::{self, AUDIT_LEN};
+use shield::{mock, random};
+
+#[test]
+fn refused() {
+    let kernel = mock::session();
+    kernel.add_shm(0x10, 0x110, 64, SHMPermission::Map as u32);
+    let shm = Shm::new(0x10).unwrap();
+    assert_eq!(syscall_audit::total(), 0);
+
+    kernel.set_status(Syscall::MapShm, Status::Denied);
+    let line = line!() + 1;
+    assert!(shm.map(0).is_err());
+    let record = syscall_audit::last().unwrap();
+    assert_eq!(record.name(), "map_shm");
+    assert_eq!(record.handle, 0x110);
+    assert_eq!(record.status, Status::Denied);
+    assert_eq!(record.location.file(), file!());
+    assert_eq!(record.location.line(), line);
+    assert_eq!(
+        record.to_string(),
+        format!("map_shm(0x00000110) -> denied at {}", record.location)
+    );
+
+    // unknown label
+    assert!(Shm::new(0x11).is_err());
+    let mut records = [None; AUDIT_LEN];
+    assert_eq!(syscall_audit::records(&mut records), 2);
+    let record = records[1].unwrap();
+    assert_eq!(record.name(), "get_shm_handle");
+    assert_eq!((record.handle, record.status), (0x11, Status::Invalid));
+    assert_eq!(record.location.file(), file!());
+
+    syscall_audit::clear();
+    assert!(syscall_audit::last().is_none());
+}
+
+#[test]
+fn ring() {
+    let kernel = mock::session();
+    random::random_u32().unwrap();
+    assert_eq!(syscall_audit::total(), 0);
+
+    // other errors are not audited
+    kernel.set_status(Syscall::GetRandom, Status::Busy);
+    assert!(random::random_u32().is_err());
+    assert_eq!(syscall_audit::total(), 0);
+
+    kernel.set_status(Syscall::GetRandom, Status::Denied);
+    for _ in 0..AUDIT_LEN + 2 {
+        assert!(random::random_u32().is_err());
+    }
+    assert_eq!(syscall_audit::total(), AUDIT_LEN as u32 + 2);
+    let mut records = [None; 4];
+    assert_eq!(syscall_audit::records(&mut records), 4);
+    let record = records[3].unwrap();
+    assert_eq!((record.name(), record.handle), ("get_random", 0));
+}
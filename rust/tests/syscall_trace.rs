@@ -0,0 +1,93 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Syscall tracing tests against the fake kernel
+
+#![cfg(all(feature = "mock", feature = "trace-syscalls"))]
+
+use sentry_uapi::systypes::{SHMPermission, Status, Syscall};
+use shield::shm::Shm;
+use shield::syscall_trace::{self, SyscallRecord, TRACE_LEN};
+use shield::{mock, process};
+
+#[test]
+fn records_syscalls() {
+    let kernel = mock::session();
+    kernel.add_task(0xbabe, 0x1000_babe);
+    kernel.set_uptime_us(1_000);
+    syscall_trace::clear();
+
+    // the traced results are still delivered through the exchange area
+    assert_eq!(process::get_process_handle(0xbabe).unwrap(), 0x1000_babe);
+    assert!(Shm::new(0xf00).is_err());
+
+    let mut trace = [SyscallRecord {
+        syscall: 0,
+        args: 0,
+        status: Status::Ok,
+        timestamp_us: 0,
+    }; TRACE_LEN];
+    assert_eq!(syscall_trace::records(&mut trace), 2);
+    assert_eq!(trace[0].name(), "get_process_handle");
+    assert!(trace[0].status == Status::Ok);
+    assert_eq!(trace[0].timestamp_us, 1_000);
+    assert_eq!(trace[1].syscall, Syscall::GetShmHandle as u8);
+    assert!(trace[1].status == Status::Invalid);
+    assert_ne!(trace[0].args, trace[1].args);
+
+    for _ in 0..TRACE_LEN {
+        let _ = process::get_process_handle(0xbabe);
+    }
+    assert_eq!(syscall_trace::total(), 2 + TRACE_LEN as u32);
+    assert_eq!(syscall_trace::records(&mut trace), TRACE_LEN);
+    assert!(
+        trace
+            .iter()
+            .all(|record| record.name() == "get_process_handle")
+    );
+}
+
+const SHM_LABEL: u32 = 0xf00;
+const SHM_HANDLE: u32 = 0x1f00;
+
+fn map_frame_shm() -> Result<(), shield::Error> {
+    let shm = Shm::new(SHM_LABEL)?.map(0)?;
+    shm.unmap()?;
+    Ok(())
+}
+
+#[test]
+fn replay_dump() {
+    // capture a trace in which the mapping is denied
+    let dump = {
+        let kernel = mock::session();
+        kernel.add_shm(SHM_LABEL, SHM_HANDLE, 64, 0);
+        syscall_trace::clear();
+        assert!(map_frame_shm().is_err());
+        syscall_trace::dump();
+        String::from_utf8(kernel.log_output()).unwrap()
+    };
+
+    // the denial is reproduced although the fake kernel allows the mapping
+    let kernel = mock::session();
+    kernel.add_shm(SHM_LABEL, SHM_HANDLE, 64, SHMPermission::Map as u32);
+    assert_eq!(kernel.replay_dump(&dump), 2);
+    let err = map_frame_shm().unwrap_err();
+    assert!(err.status() == Status::Denied);
+    kernel.replay_result().unwrap();
+
+    // a diverging syscall sequence is reported
+    kernel.replay_dump(&dump);
+    let _ = Shm::new(SHM_LABEL + 1);
+    let divergence = kernel.replay_result().unwrap_err();
+    assert_eq!(divergence.index, 0);
+    assert_eq!(divergence.expected.unwrap().name(), "get_shm_handle");
+
+    // as well as a trace not fully reproduced
+    kernel.replay_dump(&dump);
+    Shm::new(SHM_LABEL).unwrap();
+    let missing = kernel.replay_result().unwrap_err();
+    assert_eq!(missing.index, 1);
+    assert!(missing.actual.is_none());
+}
@@ -0,0 +1,155 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Capability introspection tests against the fake kernel
+
+#![cfg(feature = "mock")]
+
+use sentry_uapi::systypes::{SHMPermission, Status, Syscall};
+use shield::mock;
+use shield::task::{self, Access, Priority, SyscallClass};
+
+#[test]
+fn syscall_classes() {
+    let kernel = mock::session();
+    let caps = task::capabilities();
+    assert!(caps.has(SyscallClass::Random));
+    assert!(caps.require(SyscallClass::PreciseClock).is_ok());
+
+    kernel.set_status(Syscall::GetRandom, Status::Denied);
+    let caps = task::capabilities();
+    assert!(!caps.has(SyscallClass::Random));
+    assert!(caps.has(SyscallClass::PreciseClock));
+    let error = caps.require(SyscallClass::Random).unwrap_err();
+    assert_eq!(error.status(), Status::Denied);
+}
+
+#[test]
+fn resources() {
+    let kernel = mock::session();
+    kernel.add_shm(0x10, 0x110, 64, SHMPermission::Map as u32);
+    kernel.add_task(0xbabe, 0x1000_babe);
+    kernel.add_dma_stream(0x20, 0x120);
+    let caps = task::capabilities();
+    assert_eq!(caps.shm(0x10), Access::Allowed);
+    assert_eq!(caps.shm(0x11), Access::Unknown);
+    assert_eq!(caps.peer(0xbabe), Access::Allowed);
+    assert_eq!(caps.dma_stream(0x20), Access::Allowed);
+    assert!(caps.require_shm(0x10).is_ok());
+
+    kernel.set_status(Syscall::GetProcessHandle, Status::Denied);
+    assert_eq!(caps.peer(0xbabe), Access::Denied);
+    let error = caps.require_peer(0xbabe).unwrap_err();
+    assert_eq!(error.status(), Status::Denied);
+    let error = caps.require_device(3).unwrap_err();
+    assert_eq!(error.status(), Status::Invalid);
+}
+
+#[cfg(feature = "task-stats")]
+#[test]
+fn cpu_usage() {
+    use shield::shm::Shm;
+    use shield::time;
+
+    let kernel = mock::session();
+    kernel.set_uptime_us(1_000_000);
+    let before = task::stats().unwrap();
+
+    // running for 3ms, then sleeping 5ms
+    kernel.set_uptime_us(1_003_000);
+    time::sleep_ms(5).unwrap();
+    let stats = task::stats().unwrap();
+    assert_eq!(stats.since_us, before.since_us);
+    assert_eq!(stats.preemptions, before.preemptions + 1);
+    assert_eq!(stats.run_time_us - before.run_time_us, 3000);
+    assert_eq!(stats.last_scheduled_us, 1_008_000);
+
+    // published for a peer granted the shared memory
+    kernel.add_shm(
+        0x10,
+        0x110,
+        64,
+        SHMPermission::Map as u32 | SHMPermission::Write as u32,
+    );
+    let mut shm = Shm::new(0x10).unwrap().map(0).unwrap();
+    assert_eq!(
+        task::peer_stats(&mut shm).unwrap_err().status(),
+        Status::NoEntity
+    );
+    task::publish_stats(&mut shm).unwrap();
+    assert_eq!(task::peer_stats(&mut shm).unwrap(), stats);
+
+    kernel.add_shm(
+        0x11,
+        0x111,
+        16,
+        SHMPermission::Map as u32 | SHMPermission::Write as u32,
+    );
+    let mut shm = Shm::new(0x11).unwrap().map(0).unwrap();
+    let error = task::publish_stats(&mut shm).unwrap_err();
+    assert_eq!(error.status(), Status::Invalid);
+}
+
+#[test]
+fn priority() {
+    let _kernel = mock::session();
+    assert!(Priority::MIN < Priority::new(3));
+    assert_eq!(Priority::from(3).get(), 3);
+
+    // given by the task build, if any
+    match task::priority() {
+        Ok(priority) => {
+            assert!(task::set_priority(priority).is_ok());
+            let other = Priority::new(priority.get().wrapping_add(1));
+            let error = task::set_priority(other).unwrap_err();
+            assert_eq!(error.status(), Status::Denied);
+            assert_eq!(error.handle(), Some(u32::from(other.get())));
+        }
+        Err(error) => {
+            assert_eq!(error.status(), Status::NoEntity);
+            assert!(task::set_priority(Priority::MAX).is_err());
+        }
+    }
+}
+
+#[test]
+fn budget() {
+    use shield::task::Budget;
+    use shield::units::UnitsExt;
+
+    // before the uptimes of the CPU usage test, the yields being accounted for
+    let kernel = mock::session();
+    kernel.set_uptime_us(10_000);
+    let mut budget = Budget::new(2.ms());
+    assert_eq!(budget.slice().get(), 2_000);
+    assert!(!budget.tick());
+
+    kernel.set_uptime_us(11_500);
+    assert_eq!(budget.elapsed().unwrap().get(), 1_500);
+    assert!(!budget.is_exhausted());
+    assert!(!budget.tick());
+    assert_eq!(kernel.call_count(Syscall::Yield), 0);
+
+    // a new slice starts at the yield
+    kernel.set_uptime_us(12_000);
+    assert!(budget.tick());
+    assert_eq!(kernel.call_count(Syscall::Yield), 1);
+    kernel.set_uptime_us(13_000);
+    assert!(!budget.tick());
+
+    let mut handled = 0;
+    kernel.set_uptime_us(15_000);
+    assert!(budget.tick_with(|| handled += 1));
+    assert_eq!((handled, kernel.call_count(Syscall::Yield)), (1, 1));
+
+    // without clock, yielding at each tick
+    kernel.set_status(Syscall::GetCycle, Status::Denied);
+    assert!(budget.elapsed().is_none());
+    assert!(budget.tick());
+    assert!(budget.tick());
+    assert_eq!(kernel.call_count(Syscall::Yield), 3);
+
+    task::yield_now();
+    assert_eq!(kernel.call_count(Syscall::Yield), 4);
+}
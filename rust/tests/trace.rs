@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Event trace ring tests against the fake kernel
+
+#![cfg(all(feature = "trace", feature = "mock"))]
+
+use sentry_uapi::systypes::{SHMPermission, Status};
+use shield::mock;
+use shield::shm::Shm;
+use shield::trace::{self, Event, HEADER_LEN, RECORD_LEN, event, ids};
+
+const PERMS: u32 =
+    SHMPermission::Map as u32 | SHMPermission::Read as u32 | SHMPermission::Write as u32;
+
+const FRAME_RECEIVED: u32 = ids::USER;
+
+fn snapshot(base: usize, len: usize) -> Vec<u8> {
+    unsafe { std::slice::from_raw_parts(std::ptr::with_exposed_provenance::<u8>(base), len) }
+        .to_vec()
+}
+
+#[test]
+fn ring() {
+    let kernel = mock::session();
+    let len = HEADER_LEN + 3 * RECORD_LEN;
+    let base = kernel.add_shm(0x10, 0x110, len, PERMS);
+
+    // nothing is recorded until bound
+    event!(IRQ_ENTER, 7);
+    trace::bind_shm(Shm::new(0x10).unwrap().map(0).unwrap()).unwrap();
+    assert_eq!(
+        trace::decode(&snapshot(base, len))
+            .unwrap()
+            .events()
+            .count(),
+        0
+    );
+
+    for (uptime, irq) in [(100, 7), (250, 8)] {
+        kernel.set_uptime_us(uptime);
+        event!(IRQ_ENTER, irq);
+        event!(IRQ_EXIT);
+    }
+    event!(FRAME_RECEIVED, 60);
+
+    let shm = snapshot(base, len);
+    assert_eq!(&shm[..4], b"ECRT");
+    let trace = trace::decode(&shm).unwrap();
+    assert_eq!(trace.total(), 5);
+    let events: Vec<_> = trace.events().collect();
+    assert_eq!(
+        events,
+        [
+            Event {
+                timestamp_us: 250,
+                id: ids::IRQ_ENTER,
+                arg: 8
+            },
+            Event {
+                timestamp_us: 250,
+                id: ids::IRQ_EXIT,
+                arg: 0
+            },
+            Event {
+                timestamp_us: 250,
+                id: FRAME_RECEIVED,
+                arg: 60
+            },
+        ]
+    );
+    assert_eq!(Event::from_bytes(&events[0].to_bytes()), events[0]);
+}
+
+#[test]
+fn invalid() {
+    let kernel = mock::session();
+    kernel.add_shm(0x10, 0x110, HEADER_LEN, PERMS);
+    let err = trace::bind_shm(Shm::new(0x10).unwrap().map(0).unwrap())
+        .err()
+        .unwrap();
+    assert!(err.status() == Status::Invalid);
+
+    assert!(trace::decode(&[0; HEADER_LEN + RECORD_LEN]).is_err());
+    let mut shm = [0; HEADER_LEN];
+    shm[..12].copy_from_slice(b"ECRT\x01\0\0\0\x04\0\0\0");
+    assert!(trace::decode(&shm).is_err());
+}
@@ -0,0 +1,68 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! Unit types tests
+
+use shield::units::*;
+
+#[test]
+fn conversions() {
+    assert_eq!(Hertz::from(400.khz()), 400_000.hz());
+    assert_eq!(Hertz::from(25.mhz()), Hertz(25_000_000));
+    assert_eq!(Hertz::from(5_000.mhz()), Hertz(u32::MAX));
+    assert_eq!(Hertz(1_999).to_khz(), KiloHertz(1));
+    assert_eq!(Hertz(48_000_000).to_mhz(), 48.mhz());
+    assert_eq!(Hertz(1_000).period(), Some(1_000.us()));
+    assert_eq!(Hertz(0).period(), None);
+
+    assert_eq!(Microseconds::from(2.secs()), Microseconds(2_000_000));
+    assert_eq!(Milliseconds::from(5_000_000.secs()), Milliseconds(u32::MAX));
+    assert_eq!(Microseconds::from(3.ms()), 3_000.us());
+    assert_eq!(1_500.us().to_millis(), 1.ms());
+    assert_eq!(1_500.us().to_millis_ceil(), 2.ms());
+    assert_eq!(Microseconds(u64::MAX).to_millis(), Milliseconds(u32::MAX));
+    assert_eq!(2_500.ms().to_secs(), 2.secs());
+
+    assert_eq!(2.ms() + 3.ms(), 5.ms());
+    assert_eq!(Hertz(300) * 2 - Hertz(100), 500.hz());
+    assert_eq!(10.secs() / 4, 2.secs());
+    assert_eq!(Milliseconds(u32::MAX).checked_add(1.ms()), None);
+    assert_eq!(1.ms().checked_sub(2.ms()), None);
+    assert_eq!(format!("{} {}", 16.khz(), 250.us()), "16 kHz 250 us");
+}
+
+#[test]
+fn fixed() {
+    let half = Fixed::from_ratio(1, 2).unwrap();
+    assert_eq!(half.to_bits(), 0x8000);
+    assert_eq!(half + half, Fixed::ONE);
+    assert_eq!(Fixed::from(3) * half, Fixed::from_ratio(3, 2).unwrap());
+    assert_eq!(
+        Fixed::ONE / Fixed::from(4),
+        Fixed::from_ratio(1, 4).unwrap()
+    );
+    assert_eq!(Fixed::from_ratio(1, 0), None);
+    assert_eq!(Fixed::from_ratio(1 << 20, 1), None);
+
+    assert_eq!(Fixed::MAX + Fixed::ONE, Fixed::MAX);
+    assert_eq!(Fixed::from(-20_000) * Fixed::from(2), Fixed::MIN);
+    assert_eq!(-Fixed::MIN, Fixed::MAX);
+
+    let value = Fixed::from_ratio(-7, 4).unwrap();
+    assert_eq!((value.floor(), value.round()), (-2, -2));
+    assert_eq!(Fixed::from_ratio(5, 2).unwrap().round(), 3);
+
+    // 25% duty cycle of a 1000 ticks period
+    assert_eq!(Fixed::from_ratio(1, 4).unwrap().scale(1_000), 250);
+    assert_eq!((-half).scale(1_000), 0);
+    assert_eq!(Fixed::MAX.scale(u32::MAX), u32::MAX);
+
+    assert_eq!(value.to_string(), "-1.7500");
+    assert_eq!(format!("{:.2}", Fixed::from_ratio(2, 3).unwrap()), "0.67");
+    assert_eq!(format!("{:.0}", Fixed::from_ratio(7, 2).unwrap()), "4");
+    assert_eq!(
+        format!("{:.1}", Fixed::from_ratio(199, 100).unwrap()),
+        "2.0"
+    );
+}
@@ -0,0 +1,163 @@
+// SPDX-FileCopyrightText: 2026 Stephane N (ANSSI)
+//
+// SPDX-License-Identifier: Apache-2.0 OR BSD-3-Clause
+
+//! A/B update tests over simulated flash slots
+
+#![cfg(feature = "update")]
+
+use sentry_uapi::systypes::Status;
+use shield::error::Error;
+use shield::sha256::Sha256;
+use shield::storage::{Flash, FlashDevice, Geometry};
+use shield::update::{BootState, ExpectedDigest, Slots};
+
+const SECTOR_SIZE: usize = 256;
+const WRITE_SIZE: usize = 16;
+
+struct Device {
+    memory: Vec<u8>,
+}
+
+impl FlashDevice for Device {
+    fn geometry(&self) -> Geometry {
+        Geometry {
+            sectors: self.memory.len() / SECTOR_SIZE,
+            sector_size: SECTOR_SIZE,
+            write_size: WRITE_SIZE,
+        }
+    }
+
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), Error> {
+        buf.copy_from_slice(&self.memory[offset..offset + buf.len()]);
+        Ok(())
+    }
+
+    fn program(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        let target = &mut self.memory[offset..offset + data.len()];
+        assert!(target.iter().all(|&byte| byte == 0xff));
+        target.copy_from_slice(data);
+        Ok(())
+    }
+
+    fn erase_sector(&mut self, sector: usize) -> Result<(), Error> {
+        self.memory[sector * SECTOR_SIZE..(sector + 1) * SECTOR_SIZE].fill(0xff);
+        Ok(())
+    }
+}
+
+fn slot() -> Flash<Device> {
+    Flash::new(Device {
+        memory: vec![0xff; 8 * SECTOR_SIZE],
+    })
+    .unwrap()
+}
+
+/// Swap the slots the way the bootloader does, on reset
+fn reset(slots: Slots<Device>) -> Slots<Device> {
+    let (active, inactive) = slots.release();
+    Slots::new(inactive, active)
+}
+
+fn image() -> Vec<u8> {
+    (0..1000_u32).map(|i| (i * 7) as u8).collect()
+}
+
+fn write_image(slots: &mut Slots<Device>, image: &[u8]) -> Result<[u8; 32], Error> {
+    let mut writer = slots.begin(image.len())?;
+    for chunk in image.chunks(37) {
+        writer.write(chunk)?;
+    }
+    writer.finish(b"", &ExpectedDigest(Sha256::digest(image)))
+}
+
+#[test]
+fn sha256_vectors() {
+    let hex = |digest: [u8; 32]| {
+        digest
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>()
+    };
+    assert_eq!(
+        hex(Sha256::digest(b"")),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        hex(Sha256::digest(b"abc")),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    let mut hasher = Sha256::new();
+    hasher.update(b"abcdbcdecdefdefgefghfghighijhij");
+    hasher.update(b"kijkljklmklmnlmnomnopnopq");
+    assert_eq!(
+        hex(hasher.finalize()),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+}
+
+#[test]
+fn update_and_confirm() {
+    let mut slots = Slots::new(slot(), slot());
+    assert_eq!(slots.boot_state().unwrap(), BootState::Confirmed);
+
+    let image = image();
+    let digest = write_image(&mut slots, &image).unwrap();
+    assert_eq!(digest, Sha256::digest(&image));
+    slots.request_swap().unwrap();
+
+    let mut slots = reset(slots);
+    assert_eq!(slots.boot_state().unwrap(), BootState::Trial);
+    slots.confirm().unwrap();
+    assert_eq!(slots.boot_state().unwrap(), BootState::Confirmed);
+
+    let (active, _) = slots.release();
+    let mut booted = vec![0; image.len()];
+    active.read(0, &mut booted).unwrap();
+    assert_eq!(booted, image);
+}
+
+#[test]
+fn rollback() {
+    let mut slots = Slots::new(slot(), slot());
+    write_image(&mut slots, &image()).unwrap();
+    slots.request_swap().unwrap();
+
+    // the trial image is not confirmed, the bootloader reverting it
+    let slots = reset(slots);
+    assert_eq!(slots.boot_state().unwrap(), BootState::Trial);
+    let (trial, previous) = slots.release();
+    let mut trial = trial.release();
+    let reverted = 8 * SECTOR_SIZE - WRITE_SIZE;
+    trial.memory[reverted] = 0;
+    let slots = Slots::new(previous, Flash::new(trial).unwrap());
+    assert_eq!(slots.boot_state().unwrap(), BootState::RolledBack);
+}
+
+#[test]
+fn rejected_image() {
+    let mut slots = Slots::new(slot(), slot());
+    let image = image();
+
+    let mut writer = slots.begin(image.len()).unwrap();
+    writer.write(&image).unwrap();
+    let err = writer
+        .finish(b"", &ExpectedDigest(Sha256::digest(b"rogue")))
+        .unwrap_err();
+    assert!(err.status() == Status::Denied);
+    let err = slots.request_swap().unwrap_err();
+    assert!(err.status() == Status::Invalid);
+
+    let mut writer = slots.begin(image.len()).unwrap();
+    let err = writer
+        .write(&[image.as_slice(), b"!"].concat())
+        .unwrap_err();
+    assert!(err.status() == Status::Invalid);
+    writer.write(&image[..10]).unwrap();
+    let err = writer
+        .finish(b"", &ExpectedDigest(Sha256::digest(&image)))
+        .unwrap_err();
+    assert!(err.status() == Status::Invalid);
+
+    assert!(slots.begin(slots.max_image_len() + 1).is_err());
+}
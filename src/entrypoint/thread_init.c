@@ -59,11 +59,17 @@ void __attribute__((no_stack_protector, noreturn, used))
 _start(uint32_t const thread_id, uint32_t const seed)
 {
     int task_ret;
+    uint32_t entropy;
     /* here, the kernel alreay have copied data and zeroified bss section */
     /* set the current SSP to kernel-given seed (stack-passed) */
     __stack_chk_guard = seed;
     __libc_init(); /* initiate libc-relative ontext, if needed (globlals, etc.) */
     __shield_rand_set_seed(seed);
+    /* reseed from the kernel TRNG, if the task is allowed to use it */
+    if ((__sys_get_random() == STATUS_OK) &&
+        (copy_from_kernel((uint8_t*)&entropy, sizeof(uint32_t)) == STATUS_OK)) {
+        __shield_rand_set_seed(entropy);
+    }
     /* calling thread entrypoint. the main function being implemented out of this file, SSP is active */
     task_ret = main();
     /* End of thread, store exit value in kernel thread information */
@@ -22,6 +22,23 @@
  * Like in POSIX systems, the current thread errno_v vector is initiated to zero by the
  * runtime .init funtion. 0 means 'errno has never been set'.
  */
+#if CONFIG_WITH_RUST_ERRNO
+
+/*
+ * errno is stored by the shield crate, so that C and Rust code of the task
+ * share the same value. Sentry tasks being single-threaded, there is a single
+ * instance.
+ */
+int __shield_errno_location(void) {
+    return *__errno_location();
+}
+
+void __shield_set_errno(int val) {
+    *__errno_location() = val;
+}
+
+#else
+
 static atomic_int shield_errno_v[MAX_THREAD_PER_TASK];
 
 #if (MAX_THREAD_PER_TASK == 1)
@@ -51,3 +68,5 @@ int __shield_errno_location(void) {
 void __shield_set_errno(int val) {
     atomic_store(&(shield_errno_v[pthread_self()]), val);
 }
+
+#endif/*!CONFIG_WITH_RUST_ERRNO*/
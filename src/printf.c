@@ -36,6 +36,35 @@ err:
  * libstream exported API implementation: POSIX compilant API
  ************************************************************/
 
+#if CONFIG_WITH_RUST_PRINTF
+
+/*
+ * formatting is done by the shield crate vprintf() and vsnprintf()
+ */
+__attribute__ ((format (printf, 1, 2))) int shield_printf(const char *fmt, ...)
+{
+    va_list args;
+    int res;
+
+    va_start(args, fmt);
+    res = vprintf(fmt, args);
+    va_end(args);
+    return res;
+}
+
+__attribute__ ((format (printf, 3, 4))) int shield_snprintf(char*dest, size_t dlen, const char *fmt, ...)
+{
+    va_list args;
+    int res;
+
+    va_start(args, fmt);
+    res = vsnprintf(dest, dlen, fmt, args);
+    va_end(args);
+    return res;
+}
+
+#else
+
 /*
  * Linux-like printk() API (no kernel tagging by now)
  */
@@ -101,6 +130,8 @@ err:
     return res;
 }
 
+#endif/*!CONFIG_WITH_RUST_PRINTF*/
+
 #ifndef TEST_MODE
 __attribute__ ((format (printf, 1, 2))) int printf(const char *fmt, ...) __attribute__((alias("shield_printf")));
 __attribute__ ((format (printf, 3, 4))) int snprintf(char*dest, size_t dlen, const char *fmt, ...) __attribute__((alias("shield_snprintf")));
@@ -57,6 +57,8 @@ int shield_rand_r(unsigned int *seedp)
         return (int)((unsigned int)((*seedp)/65536) % (RAND_MAX + 1));
 }
 
+#if !CONFIG_WITH_RUST_GETRANDOM
+/* otherwise exported by the shield crate */
 ssize_t shield_getrandom(void *buf, size_t buflen, unsigned int flags)
 {
     ssize_t copied = -1;
@@ -71,11 +73,17 @@ ssize_t shield_getrandom(void *buf, size_t buflen, unsigned int flags)
 end:
     return copied;
 }
+#endif/*!CONFIG_WITH_RUST_GETRANDOM*/
 
 #ifndef TEST_MODE
 /* if not in the test suite case, aliasing to POSIX symbols */
+#if !CONFIG_WITH_RUST_RAND
+/* otherwise exported by the shield crate */
 int rand(void) __attribute__((alias("shield_rand")));
 void srand(unsigned int seedp) __attribute__((alias("shield_srand")));
 int rand_r(unsigned int *seedp) __attribute__((alias("shield_rand_r")));
+#endif
+#if !CONFIG_WITH_RUST_GETRANDOM
 ssize_t getrandom(void *buf, size_t buflen, unsigned int flags) __attribute__((alias("shield_getrandom")));
 #endif
+#endif
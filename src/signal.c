@@ -126,7 +126,7 @@ int kill(pid_t pid, int sig)
 {
     int res = -1;
 
-    if (unlikely(__sys_send_signal(pid, sig) != STATUS_OK)) {
+    if (unlikely(__sys_send_signal((uint32_t)pid, sig) != STATUS_OK)) {
         /* do we differenciate ESRCH ? (invalid target) ? */
         __shield_set_errno(EINVAL);
         goto end;
@@ -12,6 +12,9 @@
  * prefix, in order to help in the unit testing part.
  * in nominal build, prefixed symbols are local to this file and only aliases are
  * exported
+ *
+ * With CONFIG_WITH_RUST_STRING, the mem*() functions and the most common str*()
+ * ones are the word-wise implementations exported by the shield crate.
  */
 
 #include <stdbool.h>
@@ -25,6 +28,8 @@
 #include <shield/private/coreutils.h>
 #include <limits.h>
 
+#if !CONFIG_WITH_RUST_STRING
+
 /**
  * \brief standard (and thus unsecure) strlen implementation
  *
@@ -153,6 +158,8 @@ err:
     return result;
 }
 
+#endif/*!CONFIG_WITH_RUST_STRING*/
+
 /**
  * TODO: way to allow concat
  * Here we only concat on place, whish is an UB by default.
@@ -168,6 +175,8 @@ char *shield_strcat(char *dest, const char* src)
     return dest;
 }
 
+#if !CONFIG_WITH_RUST_STRING
+
 static inline void *_aligned_memcpy(void*dest, const void*src, size_t n)
 {
     union memarea {
@@ -257,6 +266,8 @@ end:
     return result;
 }
 
+#endif/*!CONFIG_WITH_RUST_STRING*/
+
 #define IS_SPACE(c) (c == ' ' || c == '\t' || )
 
 unsigned long shield_strtoul(const char *__restrict __n, char **__restrict __end_PTR, int __base)
@@ -438,12 +449,14 @@ end:
 
 #ifndef TEST_MODE
 /* if not in the test suite case, aliasing to POSIX symbols */
+#if !CONFIG_WITH_RUST_STRING
 size_t strlen(const char *s) __attribute__((alias("shield_strlen")));
 size_t strnlen(const char *s, size_t len) __attribute__((alias("shield_strnlen")));
 char *strcpy(char *dest, const char *src) __attribute__((alias("shield_strcpy")));
-char *strcat(char *dest, const char *src) __attribute__((alias("shield_strcat")));
 int strcmp(const char *str1, const char *str2) __attribute__((alias("shield_strcmp")));
 void *memcpy(void* dest, const void* src, size_t n) __attribute__((alias("shield_memcpy")));
+#endif
+char *strcat(char *dest, const char *src) __attribute__((alias("shield_strcat")));
 long strtol(const char *__restrict __n, char **__restrict __end_PTR, int __base) __attribute__((alias("shield_strtol")));
 unsigned long strtoul(const char *__restrict __n, char **__restrict __end_PTR, int __base) __attribute__((alias("shield_strtoul")));
 #endif
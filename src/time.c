@@ -672,6 +672,8 @@ err:
  * Exported functions part 1; clock
  */
 
+#if !CONFIG_WITH_RUST_TIME
+/* otherwise exported by the shield crate, with time() and clock_settime() */
 int shield_clock_gettime(clockid_t clockid, struct timespec *tp)
 {
     int errcode = 0;
@@ -702,6 +704,7 @@ err:
 end:
     return errcode;
 }
+#endif/*!CONFIG_WITH_RUST_TIME*/
 
 int shield_nanosleep(const struct timespec *req, struct timespec *rem)
 {
@@ -749,7 +752,9 @@ err:
 }
 
 #ifndef TEST_MODE
+#if !CONFIG_WITH_RUST_TIME
 int clock_gettime(clockid_t clockid, struct timespec *tp) __attribute__((alias("shield_clock_gettime")));
+#endif
 int timer_gettime(timer_t timerid, struct itimerspec *curr_value) __attribute__((alias("shield_timer_gettime")));
 int timer_settime(timer_t timerid, int flags, const struct itimerspec *new_value, struct itimerspec *old_value) __attribute__((alias("shield_timer_settime")));
 int timer_create(clockid_t clockid, struct sigevent *sevp, timer_t *timerid) __attribute__((alias("shield_timer_create")));